@@ -2,9 +2,11 @@
 //!
 //! This server implements the Model Context Protocol (MCP) over stdio,
 //! providing the `maestro_status` tool that reports agent status to
-//! the Maestro application via HTTP POST.
+//! the Maestro application via HTTP POST, plus message bus tools for
+//! coordinating with other Maestro sessions.
 
 mod mcp_protocol;
+mod message_client;
 mod status_reporter;
 
 use mcp_protocol::McpServer;
@@ -1,13 +1,16 @@
 //! MCP protocol implementation over stdio.
 //!
 //! Implements the Model Context Protocol (MCP) JSON-RPC over stdio,
-//! providing the `maestro_status` tool for reporting agent state.
+//! providing the `maestro_status` tool for reporting agent state and the
+//! `maestro_subscribe` / `maestro_publish` / `maestro_send` /
+//! `maestro_receive` tools for exchanging messages with other sessions.
 
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use std::io::{self, BufRead, Write};
 use thiserror::Error;
 
+use crate::message_client::{MessageClient, MessageError};
 use crate::status_reporter::StatusReporter;
 
 #[derive(Debug, Error)]
@@ -51,6 +54,7 @@ struct JsonRpcError {
 /// MCP server implementation.
 pub struct McpServer {
     status_reporter: StatusReporter,
+    message_client: MessageClient,
 }
 
 impl McpServer {
@@ -60,6 +64,7 @@ impl McpServer {
         instance_id: Option<String>,
     ) -> Self {
         Self {
+            message_client: MessageClient::new(status_url.clone(), session_id, instance_id.clone()),
             status_reporter: StatusReporter::new(status_url, session_id, instance_id),
         }
     }
@@ -192,6 +197,77 @@ impl McpServer {
                         },
                         "required": ["state", "message"]
                     }
+                },
+                {
+                    "name": "maestro_subscribe",
+                    "description": "Subscribe to a message topic shared with other Maestro sessions. If another session already published on the topic, its latest message is delivered to your mailbox immediately.",
+                    "inputSchema": {
+                        "type": "object",
+                        "properties": {
+                            "topic": {
+                                "type": "string",
+                                "description": "Topic name, e.g. 'api-contract' (no whitespace)"
+                            }
+                        },
+                        "required": ["topic"]
+                    }
+                },
+                {
+                    "name": "maestro_publish",
+                    "description": "Publish a message to every Maestro session subscribed to a topic. The latest message per topic is retained for sessions that subscribe later.",
+                    "inputSchema": {
+                        "type": "object",
+                        "properties": {
+                            "topic": {
+                                "type": "string",
+                                "description": "Topic name, e.g. 'api-contract' (no whitespace)"
+                            },
+                            "body": {
+                                "type": "string",
+                                "description": "Message content"
+                            }
+                        },
+                        "required": ["topic", "body"]
+                    }
+                },
+                {
+                    "name": "maestro_send",
+                    "description": "Send a message directly to another Maestro session by its session ID.",
+                    "inputSchema": {
+                        "type": "object",
+                        "properties": {
+                            "toSession": {
+                                "type": "integer",
+                                "description": "Recipient Maestro session ID"
+                            },
+                            "topic": {
+                                "type": "string",
+                                "description": "Topic name used to group the message (no whitespace)"
+                            },
+                            "body": {
+                                "type": "string",
+                                "description": "Message content"
+                            }
+                        },
+                        "required": ["toSession", "topic", "body"]
+                    }
+                },
+                {
+                    "name": "maestro_receive",
+                    "description": "Read and remove pending messages from your Maestro mailbox, oldest first.",
+                    "inputSchema": {
+                        "type": "object",
+                        "properties": {
+                            "topic": {
+                                "type": "string",
+                                "description": "Only receive messages for this topic"
+                            },
+                            "max": {
+                                "type": "integer",
+                                "description": "Maximum number of messages to return (default 20)"
+                            }
+                        }
+                    }
                 }
             ]
         })
@@ -237,6 +313,28 @@ impl McpServer {
                     ]
                 }))
             }
+            "maestro_subscribe" | "maestro_publish" | "maestro_send" | "maestro_receive" => {
+                let arguments = params.get("arguments").cloned().unwrap_or(json!({}));
+                Ok(match self.call_message_tool(name, &arguments).await {
+                    Ok(value) => json!({
+                        "content": [
+                            {
+                                "type": "text",
+                                "text": value.to_string()
+                            }
+                        ]
+                    }),
+                    Err(e) => json!({
+                        "content": [
+                            {
+                                "type": "text",
+                                "text": e.to_string()
+                            }
+                        ],
+                        "isError": true
+                    }),
+                })
+            }
             _ => Ok(json!({
                 "content": [
                     {
@@ -248,6 +346,38 @@ impl McpServer {
             })),
         }
     }
+
+    /// Dispatch one of the message bus tools to the Maestro app.
+    ///
+    /// Failures are reported back to the agent as tool errors rather than
+    /// JSON-RPC errors so it can react (e.g. fix an invalid topic name).
+    async fn call_message_tool(&self, name: &str, arguments: &Value) -> Result<Value, MessageError> {
+        let str_arg = |key: &str| arguments.get(key).and_then(|v| v.as_str()).unwrap_or("");
+
+        match name {
+            "maestro_subscribe" => self.message_client.subscribe(str_arg("topic")).await,
+            "maestro_publish" => {
+                self.message_client
+                    .publish(str_arg("topic"), str_arg("body"))
+                    .await
+            }
+            "maestro_send" => {
+                let to_session = arguments
+                    .get("toSession")
+                    .and_then(|v| v.as_u64())
+                    .and_then(|v| u32::try_from(v).ok())
+                    .ok_or_else(|| MessageError::Rejected(400, "toSession must be a session ID".to_string()))?;
+                self.message_client
+                    .send(to_session, str_arg("topic"), str_arg("body"))
+                    .await
+            }
+            _ => {
+                let topic = arguments.get("topic").and_then(|v| v.as_str());
+                let max = arguments.get("max").and_then(|v| v.as_u64());
+                self.message_client.receive(topic, max).await
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -302,8 +432,28 @@ mod tests {
         let response = server.handle_request(&request).await.expect("should return response");
         let result = response.result.expect("should have result");
         let tools = result["tools"].as_array().expect("tools should be array");
-        assert_eq!(tools.len(), 1);
+        assert_eq!(tools.len(), 5);
         assert_eq!(tools[0]["name"], "maestro_status");
+        let names: Vec<&str> = tools.iter().filter_map(|t| t["name"].as_str()).collect();
+        for expected in ["maestro_subscribe", "maestro_publish", "maestro_send", "maestro_receive"] {
+            assert!(names.contains(&expected), "missing tool {}", expected);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_message_tool_without_config_returns_tool_error() {
+        let server = test_server();
+        let request = make_request(json!({
+            "jsonrpc": "2.0",
+            "id": 5,
+            "method": "tools/call",
+            "params": { "name": "maestro_receive", "arguments": {} }
+        }));
+        let response = server.handle_request(&request).await.expect("should return response");
+        assert!(response.error.is_none());
+        let result = response.result.expect("should have result");
+        assert_eq!(result["isError"], true);
+        assert!(result["content"][0]["text"].as_str().unwrap().contains("not configured"));
     }
 
     #[tokio::test]
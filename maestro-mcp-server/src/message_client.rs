@@ -0,0 +1,185 @@
+//! HTTP client for Maestro's cross-session message bus.
+//!
+//! Talks to the `/messages/*` routes served alongside the status endpoint.
+//! The base URL is derived from `MAESTRO_STATUS_URL` by dropping the
+//! trailing `/status` path segment.
+
+use serde_json::{json, Value};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum MessageError {
+    #[error("Maestro message bus is not configured (MAESTRO_STATUS_URL / MAESTRO_SESSION_ID missing)")]
+    NotConfigured,
+    #[error("HTTP request failed: {0}")]
+    HttpError(#[from] reqwest::Error),
+    #[error("Maestro rejected the request ({0}): {1}")]
+    Rejected(u16, String),
+}
+
+/// Sends and receives mailbox messages on behalf of this session.
+pub struct MessageClient {
+    client: reqwest::Client,
+    base_url: Option<String>,
+    session_id: Option<u32>,
+    instance_id: Option<String>,
+}
+
+impl MessageClient {
+    pub fn new(
+        status_url: Option<String>,
+        session_id: Option<u32>,
+        instance_id: Option<String>,
+    ) -> Self {
+        let base_url = status_url.map(|url| {
+            url.trim_end_matches('/')
+                .trim_end_matches("/status")
+                .to_string()
+        });
+        Self {
+            client: reqwest::Client::new(),
+            base_url,
+            session_id,
+            instance_id,
+        }
+    }
+
+    /// Subscribe this session to a topic.
+    pub async fn subscribe(&self, topic: &str) -> Result<Value, MessageError> {
+        self.post("subscribe", json!({ "topic": topic })).await
+    }
+
+    /// Publish a message to every subscriber of a topic.
+    pub async fn publish(&self, topic: &str, body: &str) -> Result<Value, MessageError> {
+        self.post("publish", json!({ "topic": topic, "body": body }))
+            .await
+    }
+
+    /// Send a message directly to another session.
+    pub async fn send(&self, to_session: u32, topic: &str, body: &str) -> Result<Value, MessageError> {
+        self.post(
+            "send",
+            json!({ "to_session": to_session, "topic": topic, "body": body }),
+        )
+        .await
+    }
+
+    /// Drain up to `max` queued messages, optionally for a single topic.
+    pub async fn receive(&self, topic: Option<&str>, max: Option<u64>) -> Result<Value, MessageError> {
+        self.post("receive", json!({ "topic": topic, "max": max }))
+            .await
+    }
+
+    /// POST to `/messages/<route>`, merging in this session's identity.
+    async fn post(&self, route: &str, mut body: Value) -> Result<Value, MessageError> {
+        let (Some(base_url), Some(session_id)) = (&self.base_url, self.session_id) else {
+            return Err(MessageError::NotConfigured);
+        };
+
+        body["session_id"] = json!(session_id);
+        body["instance_id"] = json!(self.instance_id.as_deref().unwrap_or("unknown"));
+
+        let url = format!("{}/messages/{}", base_url, route);
+        eprintln!("[maestro-mcp-server] POST {}", url);
+
+        let response = self
+            .client
+            .post(&url)
+            .json(&body)
+            .timeout(std::time::Duration::from_secs(5))
+            .send()
+            .await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let text = response.text().await.unwrap_or_default();
+            return Err(MessageError::Rejected(status.as_u16(), text));
+        }
+        Ok(response.json().await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    #[tokio::test]
+    async fn test_not_configured_returns_error() {
+        let client = MessageClient::new(None, Some(1), Some("test".to_string()));
+        let result = client.receive(None, None).await;
+        assert!(matches!(result, Err(MessageError::NotConfigured)));
+    }
+
+    #[test]
+    fn test_base_url_strips_status_path() {
+        let client = MessageClient::new(
+            Some("http://127.0.0.1:9900/status".to_string()),
+            Some(1),
+            None,
+        );
+        assert_eq!(client.base_url.as_deref(), Some("http://127.0.0.1:9900"));
+    }
+
+    #[tokio::test]
+    async fn test_post_includes_session_identity() {
+        let received: Arc<Mutex<Option<Value>>> = Arc::new(Mutex::new(None));
+        let received_clone = received.clone();
+
+        let app = axum::Router::new().route(
+            "/messages/publish",
+            axum::routing::post(move |axum::Json(body): axum::Json<Value>| {
+                let received = received_clone.clone();
+                async move {
+                    *received.lock().unwrap() = Some(body);
+                    axum::Json(json!({ "delivered_to": [2] }))
+                }
+            }),
+        );
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+
+        let client = MessageClient::new(
+            Some(format!("http://{}/status", addr)),
+            Some(7),
+            Some("inst-1".to_string()),
+        );
+        let result = client.publish("api-contract", "GET /users").await.unwrap();
+        assert_eq!(result["delivered_to"], json!([2]));
+
+        let body = received.lock().unwrap().clone().unwrap();
+        assert_eq!(body["session_id"], 7);
+        assert_eq!(body["instance_id"], "inst-1");
+        assert_eq!(body["topic"], "api-contract");
+        assert_eq!(body["body"], "GET /users");
+    }
+
+    #[tokio::test]
+    async fn test_rejection_surfaces_status_and_text() {
+        let app = axum::Router::new().route(
+            "/messages/send",
+            axum::routing::post(|| async {
+                (axum::http::StatusCode::BAD_REQUEST, "invalid topic name: ''")
+            }),
+        );
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+
+        let client = MessageClient::new(Some(format!("http://{}/status", addr)), Some(1), None);
+        match client.send(2, "", "x").await {
+            Err(MessageError::Rejected(code, text)) => {
+                assert_eq!(code, 400);
+                assert!(text.contains("invalid topic"));
+            }
+            other => panic!("expected Rejected, got {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,159 @@
+//! IPC commands for per-project worktree bootstrap specs.
+//!
+//! Specs are stored in the project's `maestro-<hash>.json` store alongside
+//! its other per-project settings.
+
+use std::path::Path;
+
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_store::StoreExt;
+
+use crate::commands::worktree::WorktreePreparationResult;
+use crate::core::fast_clone::{measure_clone_dirs, CloneDirSize};
+use crate::core::worktree_bootstrap::{
+    run_bootstrap, BootstrapOutput, BootstrapReport, BootstrapSpec,
+};
+
+/// Store key holding the project's [`BootstrapSpec`].
+const BOOTSTRAP_STORE_KEY: &str = "worktree_bootstrap";
+
+/// Generates a stable hash for a project path to use as a store key.
+fn hash_project_path(path: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(path.as_bytes());
+    let result = hasher.finalize();
+    // Take first 12 hex characters for a reasonably short but unique filename
+    format!("{:x}", &result)[..12].to_string()
+}
+
+fn project_store_name(project_path: &str) -> Result<String, String> {
+    let canonical = std::fs::canonicalize(project_path)
+        .map_err(|e| format!("Invalid project path '{}': {}", project_path, e))?
+        .to_string_lossy()
+        .into_owned();
+    Ok(format!("maestro-{}.json", hash_project_path(&canonical)))
+}
+
+/// Loads the project's bootstrap spec (empty if none was saved).
+pub(crate) fn load_bootstrap_spec(
+    app: &AppHandle,
+    project_path: &str,
+) -> Result<BootstrapSpec, String> {
+    let store = app
+        .store(project_store_name(project_path)?)
+        .map_err(|e| e.to_string())?;
+    Ok(store
+        .get(BOOTSTRAP_STORE_KEY)
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default())
+}
+
+/// Runs the project's bootstrap spec in a worktree, emitting command output
+/// as `worktree-bootstrap-output` events.
+pub(crate) async fn bootstrap_worktree(
+    app: &AppHandle,
+    spec: &BootstrapSpec,
+    project_path: &str,
+    worktree_path: &str,
+) -> BootstrapReport {
+    log::info!("Bootstrapping worktree {}", worktree_path);
+    let report = run_bootstrap(
+        spec,
+        Path::new(project_path),
+        Path::new(worktree_path),
+        |output: BootstrapOutput| {
+            if let Err(e) = app.emit("worktree-bootstrap-output", &output) {
+                log::error!("Failed to emit worktree-bootstrap-output: {}", e);
+            }
+        },
+    )
+    .await;
+    if let Some(summary) = report.failure_summary() {
+        log::warn!("{} ({})", summary, worktree_path);
+    }
+    report
+}
+
+/// Bootstraps a worktree that `prepare_worktree_inner` just created, so
+/// the agent starts with env files and dependencies in place. Reused
+/// worktrees and projects without a spec are left alone. Failures are
+/// reported in `result.bootstrap` and `result.warning`.
+pub(crate) async fn bootstrap_prepared_worktree(
+    app: &AppHandle,
+    project_path: &str,
+    result: &mut WorktreePreparationResult,
+) {
+    let Some(worktree_path) = result.worktree_path.clone().filter(|_| result.created) else {
+        return;
+    };
+    let spec = match load_bootstrap_spec(app, project_path) {
+        Ok(spec) if !spec.is_empty() => spec,
+        Ok(_) => return,
+        Err(e) => {
+            log::warn!("Failed to load bootstrap spec for {}: {}", project_path, e);
+            return;
+        }
+    };
+
+    let report = bootstrap_worktree(app, &spec, project_path, &worktree_path).await;
+    if let Some(summary) = report.failure_summary() {
+        result.warning = Some(match result.warning.take() {
+            Some(existing) => format!("{existing}; {summary}"),
+            None => summary,
+        });
+    }
+    result.bootstrap = Some(report);
+}
+
+/// Returns the project's worktree bootstrap spec.
+#[tauri::command]
+pub async fn get_worktree_bootstrap(
+    app: AppHandle,
+    project_path: String,
+) -> Result<BootstrapSpec, String> {
+    load_bootstrap_spec(&app, &project_path)
+}
+
+/// Saves the project's worktree bootstrap spec, applied to every worktree
+/// created for it from now on.
+#[tauri::command]
+pub async fn save_worktree_bootstrap(
+    app: AppHandle,
+    project_path: String,
+    spec: BootstrapSpec,
+) -> Result<(), String> {
+    let store = app
+        .store(project_store_name(&project_path)?)
+        .map_err(|e| e.to_string())?;
+    store.set(
+        BOOTSTRAP_STORE_KEY,
+        serde_json::to_value(&spec).map_err(|e| e.to_string())?,
+    );
+    store.save().map_err(|e| e.to_string())
+}
+
+/// Runs the project's bootstrap spec in an existing worktree (e.g. to
+/// retry after fixing a failed setup command).
+#[tauri::command]
+pub async fn run_worktree_bootstrap(
+    app: AppHandle,
+    project_path: String,
+    worktree_path: String,
+) -> Result<BootstrapReport, String> {
+    let spec = load_bootstrap_spec(&app, &project_path)?;
+    Ok(bootstrap_worktree(&app, &spec, &project_path, &worktree_path).await)
+}
+
+/// Reports how large each of the spec's clone directories currently is in
+/// the main checkout, so users can see what cloning saves.
+#[tauri::command]
+pub async fn measure_worktree_clone_dirs(
+    app: AppHandle,
+    project_path: String,
+) -> Result<Vec<CloneDirSize>, String> {
+    let spec = load_bootstrap_spec(&app, &project_path)?;
+    tokio::task::spawn_blocking(move || measure_clone_dirs(Path::new(&project_path), &spec.clone))
+        .await
+        .map_err(|e| e.to_string())
+}
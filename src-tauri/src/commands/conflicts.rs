@@ -0,0 +1,34 @@
+//! IPC commands for cross-session conflict detection.
+
+use std::sync::Arc;
+
+use tauri::State;
+
+use crate::core::conflict_detector::{ConflictDetector, ConflictRisk};
+use crate::core::session_manager::SessionManager;
+use crate::core::worktree_manager::WorktreeManager;
+
+/// Returns the conflict risks found by the last analysis. With a
+/// `session_id`, only risks involving that session are returned.
+#[tauri::command]
+pub async fn conflict_risks_list(
+    detector: State<'_, Arc<ConflictDetector>>,
+    session_id: Option<u32>,
+) -> Result<Vec<ConflictRisk>, String> {
+    Ok(match session_id {
+        Some(id) => detector.risks_for_session(id),
+        None => detector.risks(),
+    })
+}
+
+/// Compares all session worktrees immediately instead of waiting for the
+/// next analysis interval.
+#[tauri::command]
+pub async fn conflict_risks_refresh(
+    detector: State<'_, Arc<ConflictDetector>>,
+    sessions: State<'_, SessionManager>,
+    worktree_manager: State<'_, WorktreeManager>,
+) -> Result<Vec<ConflictRisk>, String> {
+    let sessions = sessions.all_sessions();
+    Ok(detector.run(&sessions, &worktree_manager).await)
+}
@@ -0,0 +1,171 @@
+use std::path::Path;
+
+use crate::forge::{self, token, Forge, ForgeError, ForgeRemote};
+use crate::github::{
+    AuthStatus, CreatePullRequestOptions, IssueDetail, IssueFilter, IssueInfo, MergeMethod,
+    PullRequestDetail, PullRequestFilter, PullRequestInfo,
+};
+
+/// Resolves which forge (GitHub, GitLab or Gitea) hosts the repository.
+#[tauri::command]
+pub async fn forge_detect(repo_path: String) -> Result<ForgeRemote, ForgeError> {
+    forge::detect_forge(Path::new(&repo_path)).await
+}
+
+/// Stores the API token used for a forge host. For GitHub it is only used
+/// when the gh CLI is not installed.
+#[tauri::command]
+pub async fn forge_set_token(host: String, token: String) -> Result<(), ForgeError> {
+    token::set_token(&host, &token).await
+}
+
+/// Removes the stored API token for a host.
+#[tauri::command]
+pub async fn forge_delete_token(host: String) -> Result<(), ForgeError> {
+    token::delete_token(&host).await
+}
+
+/// Checks if the user is authenticated with the repository's forge.
+#[tauri::command]
+pub async fn forge_auth_status(repo_path: String) -> Result<AuthStatus, ForgeError> {
+    let forge = forge::open_forge(Path::new(&repo_path)).await?;
+    forge.auth_status().await
+}
+
+/// Lists pull (merge) requests with optional filtering.
+#[tauri::command]
+pub async fn forge_list_prs(
+    repo_path: String,
+    state: Option<String>,
+    limit: Option<u32>,
+    search: Option<String>,
+) -> Result<Vec<PullRequestInfo>, ForgeError> {
+    let forge = forge::open_forge(Path::new(&repo_path)).await?;
+    let filter = PullRequestFilter {
+        state,
+        limit,
+        search,
+    };
+    forge.list_pull_requests(filter).await
+}
+
+/// Gets detailed information about a specific pull (merge) request.
+#[tauri::command]
+pub async fn forge_get_pr(repo_path: String, number: u64) -> Result<PullRequestDetail, ForgeError> {
+    let forge = forge::open_forge(Path::new(&repo_path)).await?;
+    forge.get_pull_request(number).await
+}
+
+/// Creates a new pull (merge) request with optional labels, assignees, reviewers,
+/// milestone and projects.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn forge_create_pr(
+    repo_path: String,
+    title: String,
+    body: String,
+    base: String,
+    head: String,
+    draft: bool,
+    labels: Option<Vec<String>>,
+    assignees: Option<Vec<String>>,
+    reviewers: Option<Vec<String>>,
+    milestone: Option<String>,
+    projects: Option<Vec<String>>,
+) -> Result<PullRequestInfo, ForgeError> {
+    let forge = forge::open_forge(Path::new(&repo_path)).await?;
+    let options = CreatePullRequestOptions {
+        title,
+        body,
+        base,
+        head,
+        draft,
+        labels: labels.unwrap_or_default(),
+        assignees: assignees.unwrap_or_default(),
+        reviewers: reviewers.unwrap_or_default(),
+        milestone,
+        projects: projects.unwrap_or_default(),
+    };
+    forge.create_pull_request(options).await
+}
+
+/// Merges a pull (merge) request.
+#[tauri::command]
+pub async fn forge_merge_pr(
+    repo_path: String,
+    number: u64,
+    method: MergeMethod,
+    delete_branch: bool,
+) -> Result<(), ForgeError> {
+    let forge = forge::open_forge(Path::new(&repo_path)).await?;
+    forge
+        .merge_pull_request(number, method, delete_branch)
+        .await
+}
+
+/// Closes a pull (merge) request without merging.
+#[tauri::command]
+pub async fn forge_close_pr(repo_path: String, number: u64) -> Result<(), ForgeError> {
+    let forge = forge::open_forge(Path::new(&repo_path)).await?;
+    forge.close_pull_request(number).await
+}
+
+/// Adds a comment to a pull (merge) request.
+#[tauri::command]
+pub async fn forge_comment_pr(
+    repo_path: String,
+    number: u64,
+    body: String,
+) -> Result<(), ForgeError> {
+    let forge = forge::open_forge(Path::new(&repo_path)).await?;
+    forge.comment_pull_request(number, &body).await
+}
+
+/// Lists issues with optional filtering.
+#[tauri::command]
+pub async fn forge_list_issues(
+    repo_path: String,
+    state: Option<String>,
+    limit: Option<u32>,
+    search: Option<String>,
+) -> Result<Vec<IssueInfo>, ForgeError> {
+    let forge = forge::open_forge(Path::new(&repo_path)).await?;
+    let filter = IssueFilter {
+        state,
+        limit,
+        search,
+    };
+    forge.list_issues(filter).await
+}
+
+/// Gets detailed information about a specific issue.
+#[tauri::command]
+pub async fn forge_get_issue(repo_path: String, number: u64) -> Result<IssueDetail, ForgeError> {
+    let forge = forge::open_forge(Path::new(&repo_path)).await?;
+    forge.get_issue(number).await
+}
+
+/// Adds a comment to an issue.
+#[tauri::command]
+pub async fn forge_comment_issue(
+    repo_path: String,
+    number: u64,
+    body: String,
+) -> Result<(), ForgeError> {
+    let forge = forge::open_forge(Path::new(&repo_path)).await?;
+    forge.comment_issue(number, &body).await
+}
+
+/// Closes an issue.
+#[tauri::command]
+pub async fn forge_close_issue(repo_path: String, number: u64) -> Result<(), ForgeError> {
+    let forge = forge::open_forge(Path::new(&repo_path)).await?;
+    forge.close_issue(number).await
+}
+
+/// Reopens a closed issue.
+#[tauri::command]
+pub async fn forge_reopen_issue(repo_path: String, number: u64) -> Result<(), ForgeError> {
+    let forge = forge::open_forge(Path::new(&repo_path)).await?;
+    forge.reopen_issue(number).await
+}
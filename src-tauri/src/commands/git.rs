@@ -1,6 +1,18 @@
 use std::path::PathBuf;
+use std::sync::Arc;
 
-use crate::git::{BranchInfo, CommitInfo, FileChange, Git, GitError, GitUserConfig, RemoteInfo, WorktreeInfo};
+use tauri::{AppHandle, Emitter, State};
+
+use crate::core::git_operations::GitOperations;
+use crate::core::repo_watcher::{RepoChanged, RepoWatcher};
+use crate::core::session_manager::SessionManager;
+use crate::git::history::mark_session_branches;
+use crate::git::{
+    worktree_snapshot_name, AutostashOutcome, BlameOptions, BlameRange, BranchInfo, CommitInfo,
+    DiffOptions, DiffTarget, FileChange, FileDiff, FileLogEntry, Git, GitError, GitOperation,
+    GitProgress, GitUserConfig, GraphPage, GraphQuery, PushResult, RemoteInfo, Snapshot,
+    StashEntry, StatusEntry, WorktreeInfo,
+};
 
 /// Information about a detected git repository within a workspace.
 #[derive(Debug, Clone, serde::Serialize)]
@@ -110,13 +122,99 @@ pub async fn git_commit_log(
     git.commit_log(max_count, all_branches).await
 }
 
+/// Blames a file (relative to the repository root). Ranges last changed on
+/// the branch of one of this project's Maestro worktree sessions carry that
+/// branch in `session_branch`.
+#[tauri::command]
+pub async fn git_blame(
+    sessions: State<'_, SessionManager>,
+    repo_path: String,
+    path: String,
+    options: Option<BlameOptions>,
+) -> Result<Vec<BlameRange>, GitError> {
+    validate_repo_path(&repo_path)?;
+    let git = Git::new(&repo_path);
+    let mut ranges = git.blame(&path, &options.unwrap_or_default()).await?;
+
+    let branches = session_branches(&git, &sessions).await?;
+    if !branches.is_empty() {
+        let branch_commits = git.branch_commits(&branches).await?;
+        mark_session_branches(&mut ranges, &branch_commits);
+    }
+    Ok(ranges)
+}
+
+/// Returns up to `max_count` commits that changed a file, newest first,
+/// following renames. `skip` pages further back.
+#[tauri::command]
+pub async fn git_file_log(
+    repo_path: String,
+    path: String,
+    rev: Option<String>,
+    max_count: usize,
+    skip: Option<usize>,
+) -> Result<Vec<FileLogEntry>, GitError> {
+    validate_repo_path(&repo_path)?;
+    let git = Git::new(&repo_path);
+    git.file_log(&path, rev.as_deref(), max_count, skip.unwrap_or(0))
+        .await
+}
+
+/// Branches of Maestro-created worktree sessions in the same project as
+/// `git`'s repository (which may itself be one of those worktrees).
+async fn session_branches(git: &Git, sessions: &SessionManager) -> Result<Vec<String>, GitError> {
+    let worktrees = git.worktree_list().await?;
+    let Some(main) = worktrees.iter().find(|w| w.is_main_worktree) else {
+        return Ok(Vec::new());
+    };
+    let main = std::fs::canonicalize(&main.path).unwrap_or_else(|_| PathBuf::from(&main.path));
+
+    let mut branches: Vec<String> = sessions
+        .all_sessions()
+        .into_iter()
+        .filter(|s| s.worktree_path.is_some())
+        .filter(|s| {
+            std::fs::canonicalize(&s.project_path).is_ok_and(|project| project == main)
+        })
+        .filter_map(|s| s.branch)
+        .collect();
+    branches.sort();
+    branches.dedup();
+    Ok(branches)
+}
+
+/// Exposes `Git::commit_graph` to the frontend: one page of commits with
+/// lanes, parent edges and ref decorations. Pass the returned `next` cursor
+/// back in `query.cursor` for the following page.
+#[tauri::command]
+pub async fn git_commit_graph(
+    repo_path: String,
+    query: Option<GraphQuery>,
+) -> Result<GraphPage, GitError> {
+    validate_repo_path(&repo_path)?;
+    let git = Git::new(&repo_path);
+    git.commit_graph(&query.unwrap_or_default()).await
+}
+
 /// Checks out a branch by name.
 /// Handles both local and remote branches.
+///
+/// With `autostash`, uncommitted changes are stashed before switching and
+/// the target branch's earlier auto-stash is re-applied (see
+/// `Git::checkout_with_autostash`).
 #[tauri::command]
-pub async fn git_checkout_branch(repo_path: String, branch_name: String) -> Result<(), GitError> {
+pub async fn git_checkout_branch(
+    repo_path: String,
+    branch_name: String,
+    autostash: Option<bool>,
+) -> Result<AutostashOutcome, GitError> {
     validate_repo_path(&repo_path)?;
     let git = Git::new(&repo_path);
-    git.checkout_branch(&branch_name).await
+    if autostash.unwrap_or(false) {
+        return git.checkout_with_autostash(&branch_name).await;
+    }
+    git.checkout_branch(&branch_name).await?;
+    Ok(AutostashOutcome::default())
 }
 
 /// Creates a new branch, optionally from a specific starting point.
@@ -205,21 +303,82 @@ pub async fn git_refs_for_commit(
 
 /// Fetches refs and objects from a specific remote.
 /// Uses --prune to clean up stale remote-tracking branches.
+///
+/// Progress is emitted as `git-progress` events tagged with `operation_id`
+/// (generated if omitted), which can be passed to `cancel_git_operation`.
 #[tauri::command]
-pub async fn git_fetch(repo_path: String, remote_name: String) -> Result<(), GitError> {
+pub async fn git_fetch(
+    app: AppHandle,
+    operations: State<'_, GitOperations>,
+    repo_path: String,
+    remote_name: String,
+    operation_id: Option<String>,
+) -> Result<(), GitError> {
     validate_repo_path(&repo_path)?;
-    let git = Git::new(&repo_path);
+    let operation = progress_operation(&app, operation_id);
+    let _running = operations.register(&operation);
+    let git = Git::new(&repo_path).with_operation(operation);
     git.fetch(&remote_name).await
 }
 
-/// Fetches refs and objects from all configured remotes.
+/// Fetches refs and objects from all configured remotes, reporting progress
+/// like `git_fetch`.
 #[tauri::command]
-pub async fn git_fetch_all(repo_path: String) -> Result<(), GitError> {
+pub async fn git_fetch_all(
+    app: AppHandle,
+    operations: State<'_, GitOperations>,
+    repo_path: String,
+    operation_id: Option<String>,
+) -> Result<(), GitError> {
     validate_repo_path(&repo_path)?;
-    let git = Git::new(&repo_path);
+    let operation = progress_operation(&app, operation_id);
+    let _running = operations.register(&operation);
+    let git = Git::new(&repo_path).with_operation(operation);
     git.fetch_all().await
 }
 
+/// Cancels a running git operation, killing its git process. Returns false
+/// if no operation with that ID is running.
+#[tauri::command]
+pub async fn cancel_git_operation(
+    operations: State<'_, GitOperations>,
+    operation_id: String,
+) -> Result<bool, GitError> {
+    Ok(operations.cancel(&operation_id))
+}
+
+/// Starts watching a repository or worktree and returns its current status.
+/// Later changes are emitted as `repo-changed` events until a matching
+/// `git_unwatch_repository` call.
+#[tauri::command]
+pub async fn git_watch_repository(
+    watcher: State<'_, Arc<RepoWatcher>>,
+    repo_path: String,
+) -> Result<RepoChanged, GitError> {
+    validate_repo_path(&repo_path)?;
+    watcher.watch(&repo_path).await
+}
+
+/// Releases one `git_watch_repository` call. Returns whether the repository
+/// is still watched by another caller.
+#[tauri::command]
+pub async fn git_unwatch_repository(
+    watcher: State<'_, Arc<RepoWatcher>>,
+    repo_path: String,
+) -> Result<bool, GitError> {
+    Ok(watcher.unwatch(&repo_path))
+}
+
+/// Creates an operation whose progress is emitted as `git-progress` events.
+/// Callers register it with [`GitOperations`] while it runs.
+pub(crate) fn progress_operation(app: &AppHandle, operation_id: Option<String>) -> GitOperation {
+    let id = operation_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    let app = app.clone();
+    GitOperation::new(id).with_progress(Arc::new(move |progress: &GitProgress| {
+        let _ = app.emit("git-progress", progress);
+    }))
+}
+
 /// Tests connectivity to a remote.
 /// Returns true if reachable, false otherwise.
 #[tauri::command]
@@ -261,6 +420,224 @@ pub async fn git_set_default_branch(
     git.set_default_branch(&branch, global).await
 }
 
+/// Stages all changes (`git add -A`).
+#[tauri::command]
+pub async fn git_stage_all(repo_path: String) -> Result<(), GitError> {
+    validate_repo_path(&repo_path)?;
+    let git = Git::new(&repo_path);
+    git.stage_all().await
+}
+
+/// Returns the files currently staged for commit.
+#[tauri::command]
+pub async fn git_staged_files(repo_path: String) -> Result<Vec<FileChange>, GitError> {
+    validate_repo_path(&repo_path)?;
+    let git = Git::new(&repo_path);
+    git.staged_files().await
+}
+
+/// Commits the staged changes and returns the new commit.
+#[tauri::command]
+pub async fn git_commit(repo_path: String, message: String) -> Result<CommitInfo, GitError> {
+    validate_repo_path(&repo_path)?;
+    let git = Git::new(&repo_path);
+    git.commit(&message).await
+}
+
+/// Pushes a branch, emitting git's progress output as `git-push-progress` events.
+#[tauri::command]
+pub async fn git_push(
+    app: AppHandle,
+    repo_path: String,
+    remote_name: String,
+    branch: String,
+    set_upstream: bool,
+) -> Result<PushResult, GitError> {
+    validate_repo_path(&repo_path)?;
+    let git = Git::new(&repo_path);
+    git.push(&remote_name, &branch, set_upstream, |line| {
+        let _ = app.emit("git-push-progress", line);
+    })
+    .await
+}
+
+/// Returns per-file index and working-tree state, including untracked and
+/// conflicted files.
+#[tauri::command]
+pub async fn git_working_tree_status(repo_path: String) -> Result<Vec<StatusEntry>, GitError> {
+    validate_repo_path(&repo_path)?;
+    let git = Git::new(&repo_path);
+    git.status_entries().await
+}
+
+/// Returns a structured diff (files, hunks, numbered lines) of the working
+/// tree or index against `target`.
+#[tauri::command]
+pub async fn git_diff(
+    repo_path: String,
+    target: DiffTarget,
+    options: Option<DiffOptions>,
+) -> Result<Vec<FileDiff>, GitError> {
+    validate_repo_path(&repo_path)?;
+    let git = Git::new(&repo_path);
+    git.diff(&target, &options.unwrap_or_default()).await
+}
+
+/// Returns the branch session work is compared against by default
+/// (the remote default branch, falling back to `main` / `master`).
+#[tauri::command]
+pub async fn git_default_base_branch(repo_path: String) -> Result<Option<String>, GitError> {
+    validate_repo_path(&repo_path)?;
+    let git = Git::new(&repo_path);
+    git.default_base_branch().await
+}
+
+/// Diffs a session's worktree (or project directory, if it has none).
+///
+/// Without an explicit `target`, compares against the merge base with the
+/// repository's default branch so the result shows everything the session
+/// changed, committed or not. Falls back to `HEAD` when no base branch exists.
+#[tauri::command]
+pub async fn session_diff(
+    sessions: State<'_, SessionManager>,
+    session_id: u32,
+    target: Option<DiffTarget>,
+    options: Option<DiffOptions>,
+) -> Result<Vec<FileDiff>, String> {
+    let session = sessions
+        .get_session(session_id)
+        .ok_or_else(|| format!("Session {} not found", session_id))?;
+    let path = session.worktree_path.unwrap_or(session.project_path);
+    let git = Git::new(&path);
+
+    let target = match target {
+        Some(target) => target,
+        None => match git.default_base_branch().await.map_err(|e| e.to_string())? {
+            Some(branch) => DiffTarget::Base { branch },
+            None => DiffTarget::Head,
+        },
+    };
+    git.diff(&target, &options.unwrap_or_default())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Lists stashes, most recent first.
+#[tauri::command]
+pub async fn git_stash_list(repo_path: String) -> Result<Vec<StashEntry>, GitError> {
+    validate_repo_path(&repo_path)?;
+    let git = Git::new(&repo_path);
+    git.stash_list().await
+}
+
+/// Stashes local changes. Returns `None` if there was nothing to stash.
+#[tauri::command]
+pub async fn git_stash_push(
+    repo_path: String,
+    message: Option<String>,
+    include_untracked: Option<bool>,
+) -> Result<Option<StashEntry>, GitError> {
+    validate_repo_path(&repo_path)?;
+    let git = Git::new(&repo_path);
+    git.stash_push(message.as_deref(), include_untracked.unwrap_or(false))
+        .await
+}
+
+/// Applies the stash at `index`, keeping it in the list.
+#[tauri::command]
+pub async fn git_stash_apply(repo_path: String, index: usize) -> Result<(), GitError> {
+    validate_repo_path(&repo_path)?;
+    let git = Git::new(&repo_path);
+    git.stash_apply(index).await
+}
+
+/// Applies the stash at `index` and removes it from the list.
+#[tauri::command]
+pub async fn git_stash_pop(repo_path: String, index: usize) -> Result<(), GitError> {
+    validate_repo_path(&repo_path)?;
+    let git = Git::new(&repo_path);
+    git.stash_pop(index).await
+}
+
+/// Removes the stash at `index` without applying it.
+#[tauri::command]
+pub async fn git_stash_drop(repo_path: String, index: usize) -> Result<(), GitError> {
+    validate_repo_path(&repo_path)?;
+    let git = Git::new(&repo_path);
+    git.stash_drop(index).await
+}
+
+/// Diffs the stash at `index` against the commit it was created on.
+#[tauri::command]
+pub async fn git_stash_diff(
+    repo_path: String,
+    index: usize,
+    options: Option<DiffOptions>,
+) -> Result<Vec<FileDiff>, GitError> {
+    validate_repo_path(&repo_path)?;
+    let git = Git::new(&repo_path);
+    git.stash_diff(index, &options.unwrap_or_default()).await
+}
+
+/// Snapshots a worktree's current state onto its hidden snapshot ref.
+/// Returns `None` if nothing changed since the previous snapshot.
+#[tauri::command]
+pub async fn git_snapshot_create(
+    repo_path: String,
+    reason: Option<String>,
+    session_id: Option<u32>,
+) -> Result<Option<Snapshot>, GitError> {
+    validate_repo_path(&repo_path)?;
+    let git = Git::new(&repo_path);
+    let name = worktree_snapshot_name(git.repo_path());
+    git.create_snapshot(&name, reason.as_deref().unwrap_or("manual"), session_id)
+        .await
+}
+
+/// Lists a worktree's snapshots, newest first (50 by default).
+#[tauri::command]
+pub async fn git_snapshot_list(
+    repo_path: String,
+    limit: Option<usize>,
+) -> Result<Vec<Snapshot>, GitError> {
+    validate_repo_path(&repo_path)?;
+    let git = Git::new(&repo_path);
+    let name = worktree_snapshot_name(git.repo_path());
+    git.list_snapshots(&name, limit.unwrap_or(50)).await
+}
+
+/// Diffs a snapshot against the worktree, or against another snapshot or
+/// commit when `against` is given.
+#[tauri::command]
+pub async fn git_snapshot_diff(
+    repo_path: String,
+    snapshot: String,
+    against: Option<String>,
+    options: Option<DiffOptions>,
+) -> Result<Vec<FileDiff>, GitError> {
+    validate_repo_path(&repo_path)?;
+    let git = Git::new(&repo_path);
+    git.diff_snapshot(&snapshot, against.as_deref(), &options.unwrap_or_default())
+        .await
+}
+
+/// Restores files (all of them when `paths` is empty) from a snapshot into
+/// the worktree. The current state is snapshotted first and returned, so
+/// the restore can be undone.
+#[tauri::command]
+pub async fn git_snapshot_restore(
+    repo_path: String,
+    snapshot: String,
+    paths: Option<Vec<String>>,
+    session_id: Option<u32>,
+) -> Result<Option<Snapshot>, GitError> {
+    validate_repo_path(&repo_path)?;
+    let git = Git::new(&repo_path);
+    let name = worktree_snapshot_name(git.repo_path());
+    git.restore_snapshot(&name, &snapshot, &paths.unwrap_or_default(), session_id)
+        .await
+}
+
 /// Checks if a path is a git repository root.
 /// Returns true if the path contains a .git directory or file (could be a worktree).
 #[tauri::command]
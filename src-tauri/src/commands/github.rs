@@ -1,7 +1,15 @@
+use std::path::Path;
+
+use tauri::State;
+
+use crate::core::task_queue::prompt_input;
+use crate::core::{ProcessManager, PtyError};
 use crate::github::{
-    AuthStatus, CreatePullRequestOptions, DiscussionDetail, DiscussionInfo, GitHub, GitHubError,
-    IssueDetail, IssueFilter, IssueInfo, MergeMethod, PullRequestDetail, PullRequestFilter,
-    PullRequestInfo,
+    load_templates, AuthStatus, CreateIssueOptions, CreatePullRequestOptions, DiscussionDetail,
+    DiscussionInfo, DraftReviewComment, EditOptions, FailingStep, GitHub, GitHubError, IssueDetail,
+    IssueFilter, IssueInfo, JobLog, MergeMethod, PrCheck, PrReviewStatus, PullRequestDetail,
+    PullRequestFilter, PullRequestInfo, RepoTemplates, ReviewComment, ReviewEvent, ReviewThread,
+    WorkflowRun, WorkflowRunDetail,
 };
 
 /// Checks if the user is authenticated with GitHub CLI.
@@ -38,8 +46,10 @@ pub async fn github_get_pr(
     gh.get_pull_request(number).await
 }
 
-/// Creates a new pull request.
+/// Creates a new pull request with optional labels, assignees, reviewers,
+/// milestone and projects.
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn github_create_pr(
     repo_path: String,
     title: String,
@@ -47,6 +57,11 @@ pub async fn github_create_pr(
     base: String,
     head: String,
     draft: bool,
+    labels: Option<Vec<String>>,
+    assignees: Option<Vec<String>>,
+    reviewers: Option<Vec<String>>,
+    milestone: Option<String>,
+    projects: Option<Vec<String>>,
 ) -> Result<PullRequestInfo, GitHubError> {
     let gh = GitHub::new(&repo_path);
     let options = CreatePullRequestOptions {
@@ -55,10 +70,27 @@ pub async fn github_create_pr(
         base,
         head,
         draft,
+        labels: labels.unwrap_or_default(),
+        assignees: assignees.unwrap_or_default(),
+        reviewers: reviewers.unwrap_or_default(),
+        milestone,
+        projects: projects.unwrap_or_default(),
     };
     gh.create_pull_request(options).await
 }
 
+/// Edits a pull request's title, body, labels, assignees, reviewers,
+/// milestone or projects.
+#[tauri::command]
+pub async fn github_edit_pr(
+    repo_path: String,
+    number: u64,
+    options: EditOptions,
+) -> Result<(), GitHubError> {
+    let gh = GitHub::new(&repo_path);
+    gh.edit_pull_request(number, &options).await
+}
+
 /// Merges a pull request.
 #[tauri::command]
 pub async fn github_merge_pr(
@@ -89,6 +121,180 @@ pub async fn github_comment_pr(
     gh.comment_pull_request(number, &body).await
 }
 
+/// Lists the status checks on a pull request.
+#[tauri::command]
+pub async fn github_pr_checks(repo_path: String, number: u64) -> Result<Vec<PrCheck>, GitHubError> {
+    let gh = GitHub::new(&repo_path);
+    gh.pr_checks(number).await
+}
+
+/// Gets the review decision, requested reviewers and latest reviews of a pull request.
+#[tauri::command]
+pub async fn github_pr_reviews(
+    repo_path: String,
+    number: u64,
+) -> Result<PrReviewStatus, GitHubError> {
+    let gh = GitHub::new(&repo_path);
+    gh.pr_review_status(number).await
+}
+
+/// Lists the review threads on a pull request with their diff positions.
+#[tauri::command]
+pub async fn github_list_review_threads(
+    repo_path: String,
+    number: u64,
+) -> Result<Vec<ReviewThread>, GitHubError> {
+    let gh = GitHub::new(&repo_path);
+    gh.list_review_threads(number).await
+}
+
+/// Replies to a review thread.
+#[tauri::command]
+pub async fn github_reply_review_thread(
+    repo_path: String,
+    thread_id: String,
+    body: String,
+) -> Result<ReviewComment, GitHubError> {
+    let gh = GitHub::new(&repo_path);
+    gh.reply_to_review_thread(&thread_id, &body).await
+}
+
+/// Resolves or unresolves a review thread.
+#[tauri::command]
+pub async fn github_resolve_review_thread(
+    repo_path: String,
+    thread_id: String,
+    resolved: bool,
+) -> Result<(), GitHubError> {
+    let gh = GitHub::new(&repo_path);
+    gh.set_review_thread_resolved(&thread_id, resolved).await
+}
+
+/// Starts a pending review with line comments and returns its id.
+#[tauri::command]
+pub async fn github_start_review(
+    repo_path: String,
+    number: u64,
+    body: Option<String>,
+    comments: Vec<DraftReviewComment>,
+) -> Result<String, GitHubError> {
+    let gh = GitHub::new(&repo_path);
+    gh.start_review(number, body.as_deref(), &comments).await
+}
+
+/// Adds a line comment to a pending review and returns the new thread's id.
+#[tauri::command]
+pub async fn github_add_review_comment(
+    repo_path: String,
+    review_id: String,
+    comment: DraftReviewComment,
+) -> Result<String, GitHubError> {
+    let gh = GitHub::new(&repo_path);
+    gh.add_review_comment(&review_id, &comment).await
+}
+
+/// Submits a pending review as approve, request-changes or comment.
+#[tauri::command]
+pub async fn github_submit_review(
+    repo_path: String,
+    review_id: String,
+    event: ReviewEvent,
+    body: Option<String>,
+) -> Result<(), GitHubError> {
+    let gh = GitHub::new(&repo_path);
+    gh.submit_review(&review_id, event, body.as_deref()).await
+}
+
+/// Discards a pending review and its comments.
+#[tauri::command]
+pub async fn github_discard_review(repo_path: String, review_id: String) -> Result<(), GitHubError> {
+    let gh = GitHub::new(&repo_path);
+    gh.discard_review(&review_id).await
+}
+
+/// Lists recent workflow runs, optionally filtered by branch.
+#[tauri::command]
+pub async fn github_list_runs(
+    repo_path: String,
+    branch: Option<String>,
+    limit: Option<u32>,
+) -> Result<Vec<WorkflowRun>, GitHubError> {
+    let gh = GitHub::new(&repo_path);
+    gh.list_workflow_runs(branch.as_deref(), limit.unwrap_or(20))
+        .await
+}
+
+/// Gets a workflow run with its jobs.
+#[tauri::command]
+pub async fn github_get_run(
+    repo_path: String,
+    run_id: u64,
+) -> Result<WorkflowRunDetail, GitHubError> {
+    let gh = GitHub::new(&repo_path);
+    gh.get_workflow_run(run_id).await
+}
+
+/// Gets the log output of a workflow run's failed steps.
+#[tauri::command]
+pub async fn github_run_failed_log(repo_path: String, run_id: u64) -> Result<String, GitHubError> {
+    let gh = GitHub::new(&repo_path);
+    gh.workflow_run_failed_log(run_id).await
+}
+
+/// Gets a job's log split into steps, with ANSI escapes and timestamps removed.
+#[tauri::command]
+pub async fn github_job_log(
+    repo_path: String,
+    run_id: u64,
+    job_id: u64,
+) -> Result<JobLog, GitHubError> {
+    let gh = GitHub::new(&repo_path);
+    gh.workflow_job_log(run_id, job_id).await
+}
+
+/// Lists the failed steps of a workflow run with the tail of each step's log.
+#[tauri::command]
+pub async fn github_run_failing_steps(
+    repo_path: String,
+    run_id: u64,
+) -> Result<Vec<FailingStep>, GitHubError> {
+    let gh = GitHub::new(&repo_path);
+    gh.workflow_run_failing_steps(run_id).await
+}
+
+/// Types a failing step's prompt into an agent session. The prompt comes
+/// back from the frontend, so it is sanitized again by [`prompt_input`].
+#[tauri::command]
+pub async fn github_send_failing_step(
+    state: State<'_, ProcessManager>,
+    session_id: u32,
+    step: FailingStep,
+) -> Result<(), PtyError> {
+    let pm = state.inner().clone();
+    pm.write_stdin(session_id, &prompt_input(&step.prompt))
+}
+
+/// Re-runs the failed jobs of a workflow run.
+#[tauri::command]
+pub async fn github_rerun_failed_jobs(repo_path: String, run_id: u64) -> Result<(), GitHubError> {
+    let gh = GitHub::new(&repo_path);
+    gh.rerun_failed_jobs(run_id).await
+}
+
+/// Re-runs a single job of a workflow run.
+#[tauri::command]
+pub async fn github_rerun_job(repo_path: String, job_id: u64) -> Result<(), GitHubError> {
+    let gh = GitHub::new(&repo_path);
+    gh.rerun_workflow_job(job_id).await
+}
+
+/// Cancels a queued or in-progress workflow run.
+#[tauri::command]
+pub async fn github_cancel_run(repo_path: String, run_id: u64) -> Result<(), GitHubError> {
+    let gh = GitHub::new(&repo_path);
+    gh.cancel_workflow_run(run_id).await
+}
+
 /// Lists issues with optional filtering.
 #[tauri::command]
 pub async fn github_list_issues(
@@ -126,6 +332,33 @@ pub async fn github_get_issue(
     gh.get_issue(number).await
 }
 
+/// Creates an issue with optional labels, assignees, milestone and projects.
+#[tauri::command]
+pub async fn github_create_issue(
+    repo_path: String,
+    options: CreateIssueOptions,
+) -> Result<IssueInfo, GitHubError> {
+    let gh = GitHub::new(&repo_path);
+    gh.create_issue(&options).await
+}
+
+/// Edits an issue's title, body, labels, assignees, milestone or projects.
+#[tauri::command]
+pub async fn github_edit_issue(
+    repo_path: String,
+    number: u64,
+    options: EditOptions,
+) -> Result<(), GitHubError> {
+    let gh = GitHub::new(&repo_path);
+    gh.edit_issue(number, &options).await
+}
+
+/// Returns the repository's issue and pull request templates.
+#[tauri::command]
+pub async fn github_templates(repo_path: String) -> Result<RepoTemplates, GitHubError> {
+    Ok(load_templates(Path::new(&repo_path)))
+}
+
 /// Adds a comment to an issue.
 #[tauri::command]
 pub async fn github_comment_issue(
@@ -0,0 +1,105 @@
+//! IPC commands for merging and rebasing branches in scratch worktrees.
+
+use std::path::Path;
+
+use tauri::State;
+
+use crate::core::integration_manager::{
+    Integration, IntegrationError, IntegrationManager, IntegrationStrategy,
+};
+use crate::core::session_manager::SessionManager;
+use crate::core::worktree_manager::WorktreeManager;
+use crate::git::Git;
+
+/// Integrates `source_branch` into `target_branch`. Returns the integration
+/// as `completed`, or `conflicted` with the files to resolve in its scratch
+/// worktree.
+#[tauri::command]
+pub async fn integration_start(
+    integrations: State<'_, IntegrationManager>,
+    worktree_manager: State<'_, WorktreeManager>,
+    repo_path: String,
+    source_branch: String,
+    target_branch: String,
+    strategy: IntegrationStrategy,
+) -> Result<Integration, IntegrationError> {
+    integrations
+        .start(
+            &worktree_manager,
+            Path::new(&repo_path),
+            &source_branch,
+            &target_branch,
+            strategy,
+        )
+        .await
+}
+
+/// Integrates a session's branch into `target_branch`, or into the
+/// repository's default branch when none is given.
+#[tauri::command]
+pub async fn session_integrate(
+    sessions: State<'_, SessionManager>,
+    integrations: State<'_, IntegrationManager>,
+    worktree_manager: State<'_, WorktreeManager>,
+    session_id: u32,
+    target_branch: Option<String>,
+    strategy: IntegrationStrategy,
+) -> Result<Integration, String> {
+    let session = sessions
+        .get_session(session_id)
+        .ok_or_else(|| format!("Session {} not found", session_id))?;
+    let source_branch = session
+        .branch
+        .ok_or_else(|| format!("Session {} has no branch", session_id))?;
+
+    let repo_path = Path::new(&session.project_path);
+    let target_branch = match target_branch {
+        Some(branch) => branch,
+        None => Git::new(repo_path)
+            .default_base_branch()
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| "Could not determine the default branch".to_string())?,
+    };
+
+    integrations
+        .start(
+            &worktree_manager,
+            repo_path,
+            &source_branch,
+            &target_branch,
+            strategy,
+        )
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Continues an integration after its conflicts were resolved.
+#[tauri::command]
+pub async fn integration_continue(
+    integrations: State<'_, IntegrationManager>,
+    worktree_manager: State<'_, WorktreeManager>,
+    id: String,
+) -> Result<Integration, IntegrationError> {
+    integrations
+        .continue_integration(&worktree_manager, &id)
+        .await
+}
+
+/// Abandons an integration and removes its scratch worktree.
+#[tauri::command]
+pub async fn integration_abort(
+    integrations: State<'_, IntegrationManager>,
+    worktree_manager: State<'_, WorktreeManager>,
+    id: String,
+) -> Result<Integration, IntegrationError> {
+    integrations.abort(&worktree_manager, &id).await
+}
+
+/// Returns the integrations waiting for conflict resolution or a retry.
+#[tauri::command]
+pub async fn integration_list(
+    integrations: State<'_, IntegrationManager>,
+) -> Result<Vec<Integration>, String> {
+    Ok(integrations.list())
+}
@@ -5,6 +5,8 @@
 use tauri::{AppHandle, Emitter, State};
 use tauri_plugin_store::StoreExt;
 
+use crate::commands::git::progress_operation;
+use crate::core::git_operations::GitOperations;
 use crate::core::marketplace_manager::MarketplaceManager;
 use crate::core::marketplace_models::*;
 
@@ -162,16 +164,28 @@ pub async fn get_installed_plugins(
 }
 
 /// Installs a plugin from a marketplace.
+///
+/// Clone progress is emitted as `git-progress` events under `operation_id`
+/// (generated if omitted), which `cancel_git_operation` accepts.
 #[tauri::command]
 pub async fn install_marketplace_plugin(
     app: AppHandle,
     state: State<'_, MarketplaceManager>,
+    operations: State<'_, GitOperations>,
     marketplace_plugin_id: String,
     scope: InstallScope,
     project_path: Option<String>,
+    operation_id: Option<String>,
 ) -> Result<InstalledPlugin, String> {
+    let operation = progress_operation(&app, operation_id);
+    let _running = operations.register(&operation);
     let installed = state
-        .install_plugin(&marketplace_plugin_id, scope, project_path.as_deref())
+        .install_plugin(
+            &marketplace_plugin_id,
+            scope,
+            project_path.as_deref(),
+            Some(operation),
+        )
         .await
         .map_err(|e| e.to_string())?;
 
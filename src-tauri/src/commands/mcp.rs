@@ -6,11 +6,13 @@ use std::sync::Arc;
 
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
-use tauri::{AppHandle, State};
+use tauri::{AppHandle, Manager, State};
 use tauri_plugin_store::StoreExt;
 
 use crate::core::mcp_config_writer;
 use crate::core::mcp_manager::{McpManager, McpServerConfig};
+use crate::core::message_bus::MessageBus;
+use crate::core::session_manager::SessionManager;
 use crate::core::status_server::StatusServer;
 
 /// Store filename for custom MCP servers (global, user-level).
@@ -261,6 +263,26 @@ pub async fn get_status_server_info(
     })
 }
 
+/// Registers the session with the status server and attaches its mailbox to
+/// the project and branch, so messages queued for it survive a restart.
+async fn register_session(
+    app: &AppHandle,
+    status_server: &StatusServer,
+    session_id: u32,
+    canonical_project: &str,
+) {
+    status_server
+        .register_session(session_id, canonical_project)
+        .await;
+
+    let branch = app
+        .state::<SessionManager>()
+        .get_session(session_id)
+        .and_then(|s| s.branch);
+    app.state::<Arc<MessageBus>>()
+        .attach_session(session_id, canonical_project, branch.as_deref());
+}
+
 /// Writes a session-specific `.mcp.json` file to the working directory.
 ///
 /// This must be called BEFORE launching the Claude CLI so it can discover
@@ -288,10 +310,7 @@ pub async fn write_session_mcp_config(
         .to_string_lossy()
         .into_owned();
 
-    // Register this session with the status server
-    status_server
-        .register_session(session_id, &canonical)
-        .await;
+    register_session(&app, &status_server, session_id, &canonical).await;
 
     // Get the status URL and instance ID from the status server
     let status_url = status_server.status_url();
@@ -352,10 +371,7 @@ pub async fn write_opencode_mcp_config(
         .to_string_lossy()
         .into_owned();
 
-    // Register this session with the status server
-    status_server
-        .register_session(session_id, &canonical)
-        .await;
+    register_session(&app, &status_server, session_id, &canonical).await;
 
     // Get the status URL and instance ID from the status server
     let status_url = status_server.status_url();
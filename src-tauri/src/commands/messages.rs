@@ -0,0 +1,92 @@
+//! IPC commands for the cross-session message bus.
+//!
+//! Messages sent from the UI carry `from_session: None` so agents can tell
+//! human-authored instructions apart from messages sent by other sessions.
+
+use std::sync::Arc;
+
+use tauri::State;
+
+use crate::core::message_bus::{
+    BusMessage, MailboxSummary, MessageBus, MessageBusError, PublishReceipt, TopicInfo,
+};
+
+/// Publishes a message to every session subscribed to `topic`.
+#[tauri::command]
+pub async fn message_bus_publish(
+    bus: State<'_, Arc<MessageBus>>,
+    topic: String,
+    body: String,
+) -> Result<PublishReceipt, MessageBusError> {
+    bus.publish(None, &topic, &body)
+}
+
+/// Queues a message directly in one session's mailbox.
+#[tauri::command]
+pub async fn message_bus_send(
+    bus: State<'_, Arc<MessageBus>>,
+    to_session: u32,
+    topic: String,
+    body: String,
+) -> Result<BusMessage, MessageBusError> {
+    bus.send(None, to_session, &topic, &body)
+}
+
+/// Removes and returns up to `max` (default 20) queued messages for a session.
+#[tauri::command]
+pub async fn message_bus_receive(
+    bus: State<'_, Arc<MessageBus>>,
+    session_id: u32,
+    topic: Option<String>,
+    max: Option<usize>,
+) -> Result<Vec<BusMessage>, MessageBusError> {
+    Ok(bus.receive(session_id, topic.as_deref(), max.unwrap_or(20)))
+}
+
+/// Returns a session's queued messages without consuming them.
+#[tauri::command]
+pub async fn message_bus_peek(
+    bus: State<'_, Arc<MessageBus>>,
+    session_id: u32,
+    topic: Option<String>,
+) -> Result<Vec<BusMessage>, MessageBusError> {
+    Ok(bus.peek(session_id, topic.as_deref()))
+}
+
+/// Returns per-topic pending message counts for a session.
+#[tauri::command]
+pub async fn message_bus_pending(
+    bus: State<'_, Arc<MessageBus>>,
+    session_id: u32,
+) -> Result<Vec<MailboxSummary>, MessageBusError> {
+    Ok(bus.pending(session_id))
+}
+
+/// Subscribes a session to a topic on its behalf.
+/// Returns `true` if the session was not already subscribed.
+#[tauri::command]
+pub async fn message_bus_subscribe(
+    bus: State<'_, Arc<MessageBus>>,
+    session_id: u32,
+    topic: String,
+) -> Result<bool, MessageBusError> {
+    bus.subscribe(session_id, &topic)
+}
+
+/// Removes a session's subscription to a topic.
+#[tauri::command]
+pub async fn message_bus_unsubscribe(
+    bus: State<'_, Arc<MessageBus>>,
+    session_id: u32,
+    topic: String,
+) -> Result<bool, MessageBusError> {
+    Ok(bus.unsubscribe(session_id, &topic))
+}
+
+/// Lists all known topics with their subscribers and retained message.
+#[tauri::command]
+pub async fn message_bus_topics(
+    bus: State<'_, Arc<MessageBus>>,
+) -> Result<Vec<TopicInfo>, MessageBusError> {
+    Ok(bus.topics())
+}
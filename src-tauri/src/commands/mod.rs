@@ -1,12 +1,23 @@
+pub mod bootstrap;
 pub mod claudemd;
+pub mod conflicts;
 pub mod fonts;
+pub mod forge;
 pub mod git;
 pub mod github;
 pub mod hooks;
+pub mod integration;
+pub mod issue_session;
 pub mod marketplace;
 pub mod mcp;
+pub mod messages;
 pub mod plugin;
+pub mod pr_status;
+pub mod review_feedback;
 pub mod session;
+pub mod session_launch;
+pub mod ship;
+pub mod tasks;
 pub mod terminal;
 pub mod update;
 pub mod usage;
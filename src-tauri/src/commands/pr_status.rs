@@ -0,0 +1,45 @@
+//! IPC commands for the pull request status monitor.
+
+use std::sync::Arc;
+
+use tauri::State;
+
+use crate::core::pr_monitor::{PrMonitor, PrStatus};
+use crate::core::session_manager::SessionManager;
+
+/// Returns the latest known checks and review state for every session with
+/// a linked pull request.
+#[tauri::command]
+pub async fn pr_status_list(monitor: State<'_, Arc<PrMonitor>>) -> Result<Vec<PrStatus>, String> {
+    Ok(monitor.statuses())
+}
+
+/// Returns the latest known status of a session's pull request, or `None`
+/// if it has not been polled yet.
+#[tauri::command]
+pub async fn pr_status_get(
+    monitor: State<'_, Arc<PrMonitor>>,
+    session_id: u32,
+) -> Result<Option<PrStatus>, String> {
+    Ok(monitor.get(session_id))
+}
+
+/// Polls a session's pull request immediately instead of waiting for the
+/// next poll interval.
+#[tauri::command]
+pub async fn pr_status_refresh(
+    monitor: State<'_, Arc<PrMonitor>>,
+    sessions: State<'_, SessionManager>,
+    session_id: u32,
+) -> Result<PrStatus, String> {
+    let session = sessions
+        .get_session(session_id)
+        .ok_or_else(|| format!("Session {} not found", session_id))?;
+    let watched = PrMonitor::watched_from_sessions(&[session])
+        .pop()
+        .ok_or_else(|| format!("Session {} has no linked pull request", session_id))?;
+
+    let status = PrMonitor::fetch(&watched).await.map_err(|e| e.to_string())?;
+    monitor.apply(status.clone());
+    Ok(status)
+}
@@ -0,0 +1,57 @@
+//! IPC commands for feeding pull request review feedback into sessions.
+
+use std::sync::Arc;
+
+use tauri::State;
+
+use crate::core::review_feedback::{FeedbackBatch, FeedbackPolicy, ReviewFeedback};
+
+/// Returns the pending feedback of every session that has some.
+#[tauri::command]
+pub async fn review_feedback_list(
+    feedback: State<'_, Arc<ReviewFeedback>>,
+) -> Result<Vec<FeedbackBatch>, String> {
+    Ok(feedback.batches())
+}
+
+/// Sends a session's pending feedback now if it is idle, or once it next
+/// becomes idle. Returns `None` if nothing is pending.
+#[tauri::command]
+pub async fn review_feedback_send(
+    feedback: State<'_, Arc<ReviewFeedback>>,
+    session_id: u32,
+) -> Result<Option<FeedbackBatch>, String> {
+    Ok(feedback.send(session_id))
+}
+
+/// Discards a session's pending feedback.
+#[tauri::command]
+pub async fn review_feedback_dismiss(
+    feedback: State<'_, Arc<ReviewFeedback>>,
+    session_id: u32,
+) -> Result<(), String> {
+    feedback.dismiss(session_id);
+    Ok(())
+}
+
+/// Returns whether a session's feedback is ignored, held for confirmation
+/// or sent automatically.
+#[tauri::command]
+pub async fn review_feedback_get_policy(
+    feedback: State<'_, Arc<ReviewFeedback>>,
+    session_id: u32,
+) -> Result<FeedbackPolicy, String> {
+    Ok(feedback.policy(session_id))
+}
+
+/// Sets whether a session's feedback is ignored, held for confirmation or
+/// sent automatically.
+#[tauri::command]
+pub async fn review_feedback_set_policy(
+    feedback: State<'_, Arc<ReviewFeedback>>,
+    session_id: u32,
+    policy: FeedbackPolicy,
+) -> Result<(), String> {
+    feedback.set_policy(session_id, policy);
+    Ok(())
+}
@@ -5,10 +5,16 @@ use tauri::State;
 
 use crate::core::mcp_config_writer;
 use crate::core::mcp_manager::McpManager;
+use crate::core::message_bus::MessageBus;
 use crate::core::plugin_manager::PluginManager;
+use crate::core::pr_monitor::PrMonitor;
 use crate::core::process_manager::ProcessManager;
-use crate::core::session_manager::{AiMode, SessionConfig, SessionManager, SessionStatus};
+use crate::core::snapshot_manager::SnapshotManager;
+use crate::core::session_manager::{
+    AiMode, PullRequestLink, SessionConfig, SessionManager, SessionStatus,
+};
 use crate::core::status_server::StatusServer;
+use crate::core::task_queue::TaskQueue;
 
 /// Exposes `SessionManager::all_sessions` to the frontend.
 /// Returns a snapshot of all active sessions in arbitrary order.
@@ -18,11 +24,12 @@ pub async fn get_sessions(state: State<'_, SessionManager>) -> Result<Vec<Sessio
 }
 
 /// Exposes `SessionManager::create_session` to the frontend.
-/// Registers a new session with `Idle` status. Returns an error if the
-/// session ID already exists.
+/// Registers a new session with `Idle` status, recording the ports its shell
+/// was given. Returns an error if the session ID already exists.
 #[tauri::command]
 pub async fn create_session(
     state: State<'_, SessionManager>,
+    process_manager: State<'_, ProcessManager>,
     id: u32,
     mode: AiMode,
     project_path: String,
@@ -33,8 +40,12 @@ pub async fn create_session(
         .to_string_lossy()
         .into_owned();
 
-    state.create_session(id, mode, canonical)
-        .map_err(|existing| format!("Session {} already exists", existing.id))
+    let session = state.create_session(id, mode, canonical)
+        .map_err(|existing| format!("Session {} already exists", existing.id))?;
+    Ok(match process_manager.session_ports(id) {
+        Some(ports) => state.assign_ports(id, ports).unwrap_or(session),
+        None => session,
+    })
 }
 
 /// Exposes `SessionManager::update_status` to the frontend.
@@ -63,14 +74,42 @@ pub async fn assign_session_branch(
         .ok_or_else(|| format!("Session {} not found", session_id))
 }
 
+/// Exposes `SessionManager::link_pull_request` to the frontend.
+/// Records the PR opened for a session so its checks and reviews are
+/// monitored. Returns an error string if the session does not exist.
+#[tauri::command]
+pub async fn link_session_pull_request(
+    state: State<'_, SessionManager>,
+    session_id: u32,
+    number: u64,
+    url: String,
+) -> Result<SessionConfig, String> {
+    state
+        .link_pull_request(session_id, PullRequestLink { number, url })
+        .ok_or_else(|| format!("Session {} not found", session_id))
+}
+
 /// Exposes `SessionManager::remove_session` to the frontend.
 /// Returns the removed session config, or `None` if it was not found.
+/// Also drops the session's mailbox and topic subscriptions, releases it
+/// from the task queue worker pool, forgets its pull request status and
+/// trims its worktree's snapshots.
 #[tauri::command]
 pub async fn remove_session(
     state: State<'_, SessionManager>,
+    message_bus: State<'_, Arc<MessageBus>>,
+    task_queue: State<'_, Arc<TaskQueue>>,
+    pr_monitor: State<'_, Arc<PrMonitor>>,
     session_id: u32,
 ) -> Result<Option<SessionConfig>, String> {
-    Ok(state.remove_session(session_id))
+    message_bus.remove_session(session_id);
+    task_queue.release_session(session_id);
+    pr_monitor.remove_session(session_id);
+    let removed = state.remove_session(session_id);
+    if let Some(session) = removed.clone() {
+        tokio::spawn(async move { SnapshotManager::session_ended(&session).await });
+    }
+    Ok(removed)
 }
 
 /// Gets all sessions for a specific project.
@@ -90,12 +129,16 @@ pub async fn get_sessions_for_project(
 /// Removes all sessions for a project (used when closing a project tab).
 /// Also kills the associated PTY sessions and cleans up MCP/plugin state.
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn remove_sessions_for_project(
     state: State<'_, SessionManager>,
     process_manager: State<'_, ProcessManager>,
     mcp_manager: State<'_, McpManager>,
     status_server: State<'_, Arc<StatusServer>>,
     plugin_manager: State<'_, PluginManager>,
+    message_bus: State<'_, Arc<MessageBus>>,
+    task_queue: State<'_, Arc<TaskQueue>>,
+    pr_monitor: State<'_, Arc<PrMonitor>>,
     project_path: String,
 ) -> Result<Vec<SessionConfig>, String> {
     let canonical = std::fs::canonicalize(&project_path)
@@ -110,6 +153,9 @@ pub async fn remove_sessions_for_project(
         // Clean up in-memory MCP and plugin state
         mcp_manager.remove_session(&canonical, session.id);
         plugin_manager.remove_session(&canonical, session.id);
+        message_bus.remove_session(session.id);
+        task_queue.release_session(session.id);
+        pr_monitor.remove_session(session.id);
 
         // Unregister session from status server
         status_server.unregister_session(session.id).await;
@@ -133,6 +179,9 @@ pub async fn remove_sessions_for_project(
         if let Err(e) = process_manager.kill_session(session.id).await {
             log::warn!("Failed to kill PTY for session {}: {}", session.id, e);
         }
+
+        let session = session.clone();
+        tokio::spawn(async move { SnapshotManager::session_ended(&session).await });
     }
 
     log::debug!(
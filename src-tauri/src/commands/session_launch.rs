@@ -0,0 +1,246 @@
+//! Opens agent sessions from the backend.
+//!
+//! Issue sessions and task-queue spawns don't go through the terminal grid's
+//! launch flow, so this module does the equivalent work: prepare (and
+//! bootstrap) a worktree, spawn a shell there, register the session, write
+//! the Maestro MCP/hooks config and type the CLI command. The new session is
+//! announced with `session-launched` so the frontend can register it.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::commands::bootstrap::bootstrap_prepared_worktree;
+use crate::commands::worktree::{prepare_worktree_inner, WorktreePreparationResult};
+use crate::core::mcp_manager::McpManager;
+use crate::core::session_manager::{AiMode, SessionConfig, SessionManager};
+use crate::core::status_server::StatusServer;
+use crate::core::task_queue::{SpawnRequest, TaskQueue};
+use crate::core::worktree_manager::WorktreeManager;
+use crate::core::ProcessManager;
+
+/// Delay before typing the first prompt into CLIs that don't report status
+/// through the Maestro MCP server (Gemini, Codex).
+pub(crate) const UNREPORTED_CLI_STARTUP_DELAY: std::time::Duration =
+    std::time::Duration::from_secs(5);
+
+/// A session opened by [`launch_session`].
+#[derive(Debug, Clone, Serialize)]
+pub struct LaunchedSession {
+    /// The registered session, including its branch and ports.
+    pub session: SessionConfig,
+    /// Directory the shell was started in (worktree or project path).
+    pub working_directory: String,
+    /// Warning from worktree preparation, if it fell back to the project path.
+    pub warning: Option<String>,
+}
+
+/// CLI executable launched for each AI mode (`None` for a plain shell).
+pub(crate) fn cli_command(mode: &AiMode) -> Option<&'static str> {
+    match mode {
+        AiMode::Claude => Some("claude"),
+        AiMode::Gemini => Some("gemini"),
+        AiMode::Codex => Some("codex"),
+        AiMode::OpenCode => Some("opencode"),
+        AiMode::Plain => None,
+    }
+}
+
+/// Whether the mode's CLI reports `Idle`/`Working` through the Maestro MCP
+/// server, which the task queue relies on to dispatch and complete tasks.
+pub(crate) fn reports_status(mode: &AiMode) -> bool {
+    matches!(mode, AiMode::Claude | AiMode::OpenCode)
+}
+
+/// Opens a session for `branch` in the canonical `project_path` and starts
+/// the mode's CLI in it. `customize` runs on the registered session before
+/// the CLI starts and returns the config to report (e.g. after linking an
+/// issue).
+pub(crate) async fn launch_session(
+    app: &AppHandle,
+    project_path: &str,
+    branch: Option<String>,
+    mode: AiMode,
+    worktree_base_path: Option<String>,
+    customize: impl FnOnce(&SessionManager, SessionConfig) -> Option<SessionConfig>,
+) -> Result<LaunchedSession, String> {
+    let mut prepared = prepare_worktree_inner(
+        &app.state::<WorktreeManager>(),
+        project_path.to_string(),
+        branch,
+        worktree_base_path,
+        false,
+    )
+    .await?;
+    bootstrap_prepared_worktree(app, project_path, &mut prepared).await;
+    let WorktreePreparationResult {
+        working_directory,
+        worktree_path,
+        branch: prepared_branch,
+        warning,
+        ..
+    } = prepared;
+
+    // Spawn the shell (MAESTRO_SESSION_ID is injected by the process manager)
+    let process_manager = app.state::<ProcessManager>();
+    let env = HashMap::from([(
+        "MAESTRO_PROJECT_HASH".to_string(),
+        StatusServer::generate_project_hash(project_path),
+    )]);
+    let session_id = crate::commands::terminal::spawn_shell(
+        app.clone(),
+        process_manager.clone(),
+        Some(working_directory.clone()),
+        Some(env),
+    )
+    .await
+    .map_err(|e| e.to_string())?;
+
+    // From here on a failure must not leave an orphaned shell behind
+    let setup = async {
+        let session_manager = app.state::<SessionManager>();
+        session_manager
+            .create_session(session_id, mode.clone(), project_path.to_string())
+            .map_err(|_| format!("Session {} already exists", session_id))?;
+        if let Some(ports) = process_manager.session_ports(session_id) {
+            session_manager.assign_ports(session_id, ports);
+        }
+        if let Some(ref branch) = prepared_branch {
+            session_manager.assign_branch(session_id, branch.clone(), worktree_path.clone());
+        }
+        let session = session_manager
+            .get_session(session_id)
+            .and_then(|session| customize(&session_manager, session))
+            .ok_or_else(|| format!("Session {} disappeared during setup", session_id))?;
+
+        // Maestro MCP server (status reporting, message bus) and hooks
+        let mcp_manager = app.state::<McpManager>();
+        let status_server = app.state::<Arc<StatusServer>>();
+        let enabled_servers = mcp_manager.get_session_enabled(project_path, session_id);
+        let config_result = match mode {
+            AiMode::Claude => {
+                let result = crate::commands::mcp::write_session_mcp_config(
+                    app.clone(),
+                    mcp_manager.clone(),
+                    status_server.clone(),
+                    working_directory.clone(),
+                    session_id,
+                    project_path.to_string(),
+                    enabled_servers,
+                )
+                .await;
+                if let Err(e) = crate::commands::hooks::write_session_hooks_config(
+                    status_server.clone(),
+                    working_directory.clone(),
+                    session_id,
+                )
+                .await
+                {
+                    log::warn!("Failed to write hooks config for session {}: {}", session_id, e);
+                }
+                result
+            }
+            AiMode::OpenCode => {
+                crate::commands::mcp::write_opencode_mcp_config(
+                    app.clone(),
+                    mcp_manager.clone(),
+                    status_server.clone(),
+                    working_directory.clone(),
+                    session_id,
+                    project_path.to_string(),
+                    enabled_servers,
+                )
+                .await
+            }
+            _ => Ok(()),
+        };
+        if let Err(e) = config_result {
+            // Non-fatal: the agent still runs, it just can't report status
+            log::error!("Failed to write MCP config for session {}: {}", session_id, e);
+        }
+
+        if let Some(cli) = cli_command(&mode) {
+            process_manager
+                .write_stdin(session_id, &format!("{cli}\r"))
+                .map_err(|e| e.to_string())?;
+        }
+        Ok::<_, String>(session)
+    }
+    .await;
+    let session = match setup {
+        Ok(session) => session,
+        Err(e) => {
+            discard_session(app, session_id).await;
+            return Err(e);
+        }
+    };
+
+    log::info!("Launched session {} in {}", session_id, working_directory);
+
+    let launched = LaunchedSession {
+        session,
+        working_directory,
+        warning,
+    };
+    if let Err(e) = app.emit("session-launched", &launched) {
+        log::error!("Failed to emit session-launched: {}", e);
+    }
+    Ok(launched)
+}
+
+/// Tears down a shell whose session setup failed: kills the PTY and drops the
+/// session from the session manager and status server.
+async fn discard_session(app: &AppHandle, session_id: u32) {
+    let process_manager = app.state::<ProcessManager>().inner().clone();
+    if let Err(e) = process_manager.kill_session(session_id).await {
+        log::warn!("Failed to kill session {} after setup failure: {}", session_id, e);
+    }
+    app.state::<Arc<StatusServer>>()
+        .unregister_session(session_id)
+        .await;
+    app.state::<SessionManager>().remove_session(session_id);
+}
+
+/// Handles a task queue [`SpawnRequest`]: opens a session for the task and
+/// enrolls it, or fails the task if the session can't be opened.
+pub(crate) async fn spawn_task_session(app: AppHandle, request: SpawnRequest) {
+    let queue = app.state::<Arc<TaskQueue>>().inner().clone();
+    let mode = request.mode.clone().unwrap_or(AiMode::Claude);
+    if !reports_status(&mode) {
+        queue.fail_spawn(
+            request.task_id,
+            format!("{:?} sessions don't report status and can't run queued tasks", mode),
+        );
+        return;
+    }
+
+    // Enroll before the CLI starts so its first `Idle` report isn't missed.
+    let task_id = request.task_id;
+    let enroll_queue = queue.clone();
+    let enroll_mode = mode.clone();
+    let launched = launch_session(
+        &app,
+        &request.project_path,
+        request.branch.clone(),
+        mode,
+        None,
+        move |_, session| {
+            if let Err(e) = enroll_queue.enroll_session(
+                session.id,
+                session.project_path.clone(),
+                session.branch.clone(),
+                enroll_mode,
+                Some(task_id),
+            ) {
+                log::warn!("Session {} opened for task {} could not enroll: {}", session.id, task_id, e);
+            }
+            Some(session)
+        },
+    )
+    .await;
+    if let Err(e) = launched {
+        queue.fail_spawn(task_id, format!("Failed to open session: {e}"));
+    }
+}
@@ -0,0 +1,604 @@
+//! "Ship it": stage, commit, push and open a pull request for a session in
+//! one backend call.
+//!
+//! Each step is recorded as a [`ShipStep`] so the UI can show exactly where
+//! the workflow stopped. The workflow stops at the first failed step; later
+//! steps are not attempted and do not appear in the result. The pull
+//! request's base branch is checked before anything is staged, so a failed
+//! `PullRequest` step can be the only one recorded.
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, State};
+
+use crate::core::session_manager::{IssueLink, PullRequestLink, SessionManager};
+use crate::git::{CommitInfo, FileChange, FileChangeStatus, Git, PushResult};
+use crate::github::{CreatePullRequestOptions, GitHub, PullRequestInfo};
+
+/// Maximum number of files listed in a generated commit message body.
+const MAX_LISTED_FILES: usize = 20;
+
+/// A step of the ship workflow.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ShipStepKind {
+    Stage,
+    Commit,
+    Push,
+    PullRequest,
+}
+
+/// Outcome of a single step.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ShipStepStatus {
+    Done,
+    Skipped,
+    Failed,
+}
+
+/// Result of one step, with a human-readable detail (or the error message).
+#[derive(Debug, Clone, Serialize)]
+pub struct ShipStep {
+    pub step: ShipStepKind,
+    pub status: ShipStepStatus,
+    pub detail: String,
+}
+
+/// Caller overrides for the ship workflow. Everything is optional.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ShipOptions {
+    /// Commit message. Generated from the staged files (and linked issue) when absent.
+    pub message: Option<String>,
+    /// Pull request title. Defaults to the linked issue title, then the HEAD commit summary.
+    pub title: Option<String>,
+    /// Pull request body. `Closes #N` is appended for sessions linked to an issue.
+    pub body: Option<String>,
+    /// Base branch. Defaults to the repository's default branch.
+    pub base: Option<String>,
+    /// Remote to push to. Defaults to `origin`.
+    pub remote: Option<String>,
+    /// Open the pull request as a draft.
+    pub draft: bool,
+    /// Stop after pushing without opening a pull request.
+    pub skip_pr: bool,
+}
+
+/// Result of the whole workflow.
+#[derive(Debug, Clone, Serialize)]
+pub struct ShipResult {
+    /// Branch that was pushed (the PR head).
+    pub branch: String,
+    /// Base branch of the pull request, once resolved.
+    pub base: Option<String>,
+    pub steps: Vec<ShipStep>,
+    /// The commit created by this run, if anything was staged.
+    pub commit: Option<CommitInfo>,
+    pub push: Option<PushResult>,
+    pub pull_request: Option<PullRequestInfo>,
+    /// `true` when no step failed.
+    pub success: bool,
+}
+
+/// Payload of the `ship-progress` event.
+#[derive(Debug, Clone, Serialize)]
+struct ShipProgress {
+    session_id: u32,
+    step: ShipStepKind,
+    line: String,
+}
+
+impl ShipResult {
+    fn record(&mut self, step: ShipStepKind, status: ShipStepStatus, detail: impl Into<String>) {
+        if status == ShipStepStatus::Failed {
+            self.success = false;
+        }
+        self.steps.push(ShipStep {
+            step,
+            status,
+            detail: detail.into(),
+        });
+    }
+}
+
+/// Builds a commit message from the staged files.
+///
+/// Sessions linked to an issue use the issue title as the subject and
+/// reference the issue in the body; otherwise the subject summarizes the
+/// changed paths. The body lists the files (capped at [`MAX_LISTED_FILES`]).
+pub(crate) fn generate_commit_message(issue: Option<&IssueLink>, files: &[FileChange]) -> String {
+    let subject = match (issue, files) {
+        (Some(issue), _) => issue.title.trim().to_string(),
+        (None, [file]) => {
+            let verb = match file.status {
+                FileChangeStatus::Added => "Add",
+                FileChangeStatus::Deleted => "Remove",
+                FileChangeStatus::Renamed => "Rename",
+                _ => "Update",
+            };
+            format!("{verb} {}", file.path)
+        }
+        (None, files) => match common_dir(files) {
+            Some(dir) => format!("Update {} files in {dir}", files.len()),
+            None => format!("Update {} files", files.len()),
+        },
+    };
+
+    let mut message = subject;
+    if files.len() > 1 || issue.is_some() {
+        message.push_str("\n\n");
+        for file in files.iter().take(MAX_LISTED_FILES) {
+            let marker = match file.status {
+                FileChangeStatus::Added => 'A',
+                FileChangeStatus::Modified => 'M',
+                FileChangeStatus::Deleted => 'D',
+                FileChangeStatus::Renamed => 'R',
+                FileChangeStatus::Copied => 'C',
+                FileChangeStatus::Unknown => '?',
+            };
+            message.push_str(&format!("- {marker} {}\n", file.path));
+        }
+        if files.len() > MAX_LISTED_FILES {
+            message.push_str(&format!("- ... and {} more\n", files.len() - MAX_LISTED_FILES));
+        }
+        if let Some(issue) = issue {
+            message.push_str(&format!("\nRefs #{}\n", issue.number));
+        }
+    }
+    message.trim_end().to_string()
+}
+
+/// Longest directory prefix shared by every path, if any.
+fn common_dir(files: &[FileChange]) -> Option<String> {
+    let mut iter = files.iter().map(|f| f.path.split('/').collect::<Vec<_>>());
+    let first = iter.next()?;
+    // Exclude the file name itself
+    let mut common = first.len().saturating_sub(1);
+    for parts in iter {
+        let dirs = parts.len().saturating_sub(1);
+        common = common
+            .min(dirs)
+            .min(first.iter().zip(&parts).take_while(|(a, b)| a == b).count());
+    }
+    (common > 0).then(|| first[..common].join("/"))
+}
+
+/// Builds the pull request body, appending `Closes #N` for linked issues
+/// unless the body already references the issue.
+pub(crate) fn build_pr_body(body: Option<&str>, issue: Option<&IssueLink>) -> String {
+    let mut body = body.unwrap_or_default().trim().to_string();
+    if let Some(issue) = issue {
+        let reference = format!("#{}", issue.number);
+        if !body.contains(&reference) {
+            if !body.is_empty() {
+                body.push_str("\n\n");
+            }
+            body.push_str(&format!("Closes {reference}"));
+        }
+    }
+    body
+}
+
+/// Runs the workflow in `repo_dir` for `branch`. Progress lines from the
+/// push are passed to `on_progress`.
+pub(crate) async fn ship_inner(
+    repo_dir: &Path,
+    branch: &str,
+    issue: Option<&IssueLink>,
+    options: &ShipOptions,
+    mut on_progress: impl FnMut(ShipStepKind, &str),
+) -> ShipResult {
+    let git = Git::new(repo_dir);
+    let remote = options.remote.as_deref().unwrap_or("origin");
+    let mut result = ShipResult {
+        branch: branch.to_string(),
+        base: None,
+        steps: Vec::new(),
+        commit: None,
+        push: None,
+        pull_request: None,
+        success: true,
+    };
+
+    // The base branch is resolved before anything is staged, committed or
+    // pushed, so shipping the base branch itself fails without side effects.
+    let gh = GitHub::new(repo_dir);
+    let base = if options.skip_pr {
+        None
+    } else {
+        let base = match options.base.clone() {
+            Some(base) => Some(base),
+            None => match gh.default_branch().await {
+                Ok(base) => Some(base),
+                Err(e) => {
+                    log::warn!("ship: could not read default branch from GitHub: {}", e);
+                    git.remote_default_branch(remote).await.ok().flatten()
+                }
+            },
+        };
+        let Some(base) = base else {
+            result.record(
+                ShipStepKind::PullRequest,
+                ShipStepStatus::Failed,
+                "could not determine the base branch; pass one explicitly",
+            );
+            return result;
+        };
+        if base == branch {
+            result.record(
+                ShipStepKind::PullRequest,
+                ShipStepStatus::Failed,
+                format!("'{branch}' is the base branch; ship from a feature branch"),
+            );
+            return result;
+        }
+        result.base = Some(base.clone());
+        Some(base)
+    };
+
+    // 1. Stage
+    let staged = match git.stage_all().await {
+        Ok(()) => match git.staged_files().await {
+            Ok(files) => files,
+            Err(e) => {
+                result.record(ShipStepKind::Stage, ShipStepStatus::Failed, e.to_string());
+                return result;
+            }
+        },
+        Err(e) => {
+            result.record(ShipStepKind::Stage, ShipStepStatus::Failed, e.to_string());
+            return result;
+        }
+    };
+    if staged.is_empty() {
+        result.record(ShipStepKind::Stage, ShipStepStatus::Skipped, "working tree clean");
+    } else {
+        result.record(
+            ShipStepKind::Stage,
+            ShipStepStatus::Done,
+            format!("{} file(s) staged", staged.len()),
+        );
+    }
+
+    // 2. Commit
+    if staged.is_empty() {
+        result.record(ShipStepKind::Commit, ShipStepStatus::Skipped, "nothing to commit");
+    } else {
+        let message = match options.message.as_deref().map(str::trim) {
+            Some(message) if !message.is_empty() => message.to_string(),
+            _ => generate_commit_message(issue, &staged),
+        };
+        match git.commit(&message).await {
+            Ok(commit) => {
+                result.record(
+                    ShipStepKind::Commit,
+                    ShipStepStatus::Done,
+                    format!("{} {}", commit.short_hash, commit.summary),
+                );
+                result.commit = Some(commit);
+            }
+            Err(e) => {
+                result.record(ShipStepKind::Commit, ShipStepStatus::Failed, e.to_string());
+                return result;
+            }
+        }
+    }
+
+    // 3. Push
+    match git
+        .push(remote, branch, true, |line| on_progress(ShipStepKind::Push, line))
+        .await
+    {
+        Ok(push) => {
+            let detail = if push.up_to_date {
+                format!("{remote}/{branch} already up to date")
+            } else {
+                format!("pushed to {remote}/{branch}")
+            };
+            result.record(ShipStepKind::Push, ShipStepStatus::Done, detail);
+            result.push = Some(push);
+        }
+        Err(e) => {
+            result.record(ShipStepKind::Push, ShipStepStatus::Failed, e.to_string());
+            return result;
+        }
+    }
+
+    // 4. Pull request
+    let Some(base) = base else {
+        result.record(ShipStepKind::PullRequest, ShipStepStatus::Skipped, "skipped by request");
+        return result;
+    };
+
+    let title = match (&options.title, issue) {
+        (Some(title), _) => title.clone(),
+        (None, Some(issue)) => issue.title.clone(),
+        (None, None) => match git.commit_log(1, false).await {
+            Ok(commits) if !commits.is_empty() => commits[0].summary.clone(),
+            _ => branch.to_string(),
+        },
+    };
+    let pr_options = CreatePullRequestOptions {
+        title,
+        body: build_pr_body(options.body.as_deref(), issue),
+        base,
+        head: branch.to_string(),
+        draft: options.draft,
+        ..Default::default()
+    };
+    match gh.create_pull_request(pr_options).await {
+        Ok(pr) => {
+            result.record(
+                ShipStepKind::PullRequest,
+                ShipStepStatus::Done,
+                format!("#{} {}", pr.number, pr.url),
+            );
+            result.pull_request = Some(pr);
+        }
+        Err(e) => {
+            result.record(ShipStepKind::PullRequest, ShipStepStatus::Failed, e.to_string());
+        }
+    }
+
+    result
+}
+
+/// Stages, commits, pushes and opens a pull request for a session's branch.
+///
+/// Runs in the session's worktree (or project directory), using the session
+/// branch as the PR head. Push progress is emitted as `ship-progress` events.
+/// A created PR is linked to the session so its checks are monitored.
+/// Step failures are reported in the returned [`ShipResult`]; `Err` is only
+/// returned when the session cannot be resolved.
+#[tauri::command]
+pub async fn ship_session(
+    app: AppHandle,
+    session_manager: State<'_, SessionManager>,
+    session_id: u32,
+    options: Option<ShipOptions>,
+) -> Result<ShipResult, String> {
+    let session = session_manager
+        .get_session(session_id)
+        .ok_or_else(|| format!("Session {} not found", session_id))?;
+    let repo_dir = session
+        .worktree_path
+        .clone()
+        .unwrap_or_else(|| session.project_path.clone());
+
+    let branch = match session.branch.clone() {
+        Some(branch) => branch,
+        None => Git::new(&repo_dir)
+            .current_branch()
+            .await
+            .map_err(|e| e.to_string())?,
+    };
+
+    let options = options.unwrap_or_default();
+    let result = ship_inner(
+        Path::new(&repo_dir),
+        &branch,
+        session.issue.as_ref(),
+        &options,
+        |step, line| {
+            let payload = ShipProgress {
+                session_id,
+                step,
+                line: line.to_string(),
+            };
+            if let Err(e) = app.emit("ship-progress", &payload) {
+                log::warn!("Failed to emit ship-progress: {}", e);
+            }
+        },
+    )
+    .await;
+
+    if let Some(ref pr) = result.pull_request {
+        session_manager.link_pull_request(
+            session_id,
+            PullRequestLink {
+                number: pr.number,
+                url: pr.url.clone(),
+            },
+        );
+    }
+
+    log::info!(
+        "Ship for session {} on '{}' finished (success: {})",
+        session_id,
+        branch,
+        result.success
+    );
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn change(path: &str, status: FileChangeStatus) -> FileChange {
+        FileChange {
+            path: path.to_string(),
+            status,
+            old_path: None,
+        }
+    }
+
+    fn issue() -> IssueLink {
+        IssueLink {
+            number: 42,
+            title: "Fix login redirect".to_string(),
+            url: "https://github.com/acme/app/issues/42".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_generate_commit_message_single_file() {
+        let msg = generate_commit_message(None, &[change("src/a.rs", FileChangeStatus::Added)]);
+        assert_eq!(msg, "Add src/a.rs");
+    }
+
+    #[test]
+    fn test_generate_commit_message_common_dir() {
+        let files = [
+            change("src/auth/login.rs", FileChangeStatus::Modified),
+            change("src/auth/session.rs", FileChangeStatus::Deleted),
+        ];
+        let msg = generate_commit_message(None, &files);
+        assert!(msg.starts_with("Update 2 files in src/auth\n\n"));
+        assert!(msg.contains("- M src/auth/login.rs"));
+        assert!(msg.contains("- D src/auth/session.rs"));
+
+        let spread = [
+            change("README.md", FileChangeStatus::Modified),
+            change("src/lib.rs", FileChangeStatus::Modified),
+        ];
+        assert!(generate_commit_message(None, &spread).starts_with("Update 2 files\n"));
+    }
+
+    #[test]
+    fn test_generate_commit_message_with_issue() {
+        let msg = generate_commit_message(Some(&issue()), &[change("a.rs", FileChangeStatus::Modified)]);
+        assert!(msg.starts_with("Fix login redirect\n\n- M a.rs"));
+        assert!(msg.ends_with("Refs #42"));
+    }
+
+    #[test]
+    fn test_generate_commit_message_caps_file_list() {
+        let files: Vec<_> = (0..25)
+            .map(|i| change(&format!("f{i}.txt"), FileChangeStatus::Added))
+            .collect();
+        let msg = generate_commit_message(None, &files);
+        assert_eq!(msg.lines().filter(|l| l.starts_with("- A")).count(), MAX_LISTED_FILES);
+        assert!(msg.ends_with("- ... and 5 more"));
+    }
+
+    #[test]
+    fn test_build_pr_body_closes_issue() {
+        assert_eq!(build_pr_body(None, Some(&issue())), "Closes #42");
+        assert_eq!(
+            build_pr_body(Some("Reworks the redirect."), Some(&issue())),
+            "Reworks the redirect.\n\nCloses #42"
+        );
+        assert_eq!(build_pr_body(Some("Fixes #42"), Some(&issue())), "Fixes #42");
+        assert_eq!(build_pr_body(Some("Body"), None), "Body");
+    }
+
+    /// Creates a repo on `feature` with an initial commit and a bare `origin`.
+    async fn repo_with_remote() -> (tempfile::TempDir, tempfile::TempDir, Git) {
+        let dir = tempdir().unwrap();
+        let remote = tempdir().unwrap();
+        let git = Git::new(dir.path());
+        git.run(&["init"]).await.unwrap();
+        git.run(&["config", "user.email", "test@test.com"]).await.unwrap();
+        git.run(&["config", "user.name", "Test"]).await.unwrap();
+        tokio::fs::write(dir.path().join("README.md"), "# Test").await.unwrap();
+        git.run(&["add", "."]).await.unwrap();
+        git.run(&["commit", "-m", "initial"]).await.unwrap();
+        git.run(&["checkout", "-b", "feature"]).await.unwrap();
+
+        Git::new(remote.path()).run(&["init", "--bare"]).await.unwrap();
+        git.add_remote("origin", &remote.path().to_string_lossy())
+            .await
+            .unwrap();
+        (dir, remote, git)
+    }
+
+    #[tokio::test]
+    async fn test_ship_inner_commits_and_pushes() {
+        let (dir, _remote, git) = repo_with_remote().await;
+        tokio::fs::write(dir.path().join("login.rs"), "fn main() {}").await.unwrap();
+
+        let options = ShipOptions {
+            skip_pr: true,
+            ..Default::default()
+        };
+        let mut progress = Vec::new();
+        let result = ship_inner(dir.path(), "feature", Some(&issue()), &options, |step, line| {
+            progress.push((step, line.to_string()))
+        })
+        .await;
+
+        assert!(result.success, "steps: {:?}", result.steps);
+        let statuses: Vec<_> = result.steps.iter().map(|s| (s.step, s.status)).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                (ShipStepKind::Stage, ShipStepStatus::Done),
+                (ShipStepKind::Commit, ShipStepStatus::Done),
+                (ShipStepKind::Push, ShipStepStatus::Done),
+                (ShipStepKind::PullRequest, ShipStepStatus::Skipped),
+            ]
+        );
+        assert_eq!(result.commit.unwrap().summary, "Fix login redirect");
+        assert!(progress.iter().all(|(step, _)| *step == ShipStepKind::Push));
+
+        let upstream = git
+            .run(&["rev-parse", "--abbrev-ref", "feature@{upstream}"])
+            .await
+            .unwrap();
+        assert_eq!(upstream.trimmed(), "origin/feature");
+    }
+
+    #[tokio::test]
+    async fn test_ship_inner_clean_tree_skips_commit() {
+        let (dir, _remote, _git) = repo_with_remote().await;
+        let options = ShipOptions {
+            skip_pr: true,
+            ..Default::default()
+        };
+        let result = ship_inner(dir.path(), "feature", None, &options, |_, _| {}).await;
+
+        assert!(result.success);
+        assert_eq!(result.steps[0].status, ShipStepStatus::Skipped);
+        assert_eq!(result.steps[1].status, ShipStepStatus::Skipped);
+        assert_eq!(result.steps[2].status, ShipStepStatus::Done);
+        assert!(result.commit.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_ship_inner_stops_at_failed_push() {
+        let (dir, _remote, _git) = repo_with_remote().await;
+        tokio::fs::write(dir.path().join("x.txt"), "x").await.unwrap();
+        let options = ShipOptions {
+            remote: Some("missing".to_string()),
+            message: Some("Custom message".to_string()),
+            base: Some("main".to_string()),
+            ..Default::default()
+        };
+        let result = ship_inner(dir.path(), "feature", None, &options, |_, _| {}).await;
+
+        assert!(!result.success);
+        assert_eq!(result.commit.as_ref().unwrap().summary, "Custom message");
+        let last = result.steps.last().unwrap();
+        assert_eq!(last.step, ShipStepKind::Push);
+        assert_eq!(last.status, ShipStepStatus::Failed);
+        assert_eq!(result.steps.len(), 3, "PR step must not run after a failed push");
+    }
+
+    #[tokio::test]
+    async fn test_ship_inner_refuses_pr_onto_itself_before_committing() {
+        let (dir, _remote, git) = repo_with_remote().await;
+        tokio::fs::write(dir.path().join("x.txt"), "x").await.unwrap();
+        let options = ShipOptions {
+            base: Some("feature".to_string()),
+            ..Default::default()
+        };
+        let result = ship_inner(dir.path(), "feature", None, &options, |_, _| {}).await;
+
+        assert!(!result.success);
+        assert_eq!(result.steps.len(), 1, "steps: {:?}", result.steps);
+        assert_eq!(result.steps[0].step, ShipStepKind::PullRequest);
+        assert_eq!(result.steps[0].status, ShipStepStatus::Failed);
+        assert!(result.commit.is_none() && result.push.is_none());
+
+        assert!(git.staged_files().await.unwrap().is_empty());
+        assert_eq!(git.commit_log(10, false).await.unwrap().len(), 1);
+        assert!(git
+            .run(&["rev-parse", "--abbrev-ref", "feature@{upstream}"])
+            .await
+            .is_err());
+    }
+}
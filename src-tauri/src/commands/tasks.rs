@@ -0,0 +1,129 @@
+//! IPC commands for the session task queue.
+
+use std::sync::Arc;
+
+use tauri::State;
+
+use crate::core::session_manager::{AiMode, SessionManager};
+use crate::core::task_queue::{Task, TaskQueue, TaskQueueConfig, TaskQueueError, TaskSpec};
+
+/// Adds a prompt to the task queue.
+///
+/// The project path is canonicalized so it matches the paths stored on
+/// sessions. `branch` and `mode` restrict which sessions may run the task.
+#[tauri::command]
+pub async fn task_queue_enqueue(
+    queue: State<'_, Arc<TaskQueue>>,
+    project_path: String,
+    prompt: String,
+    branch: Option<String>,
+    mode: Option<AiMode>,
+    max_retries: Option<u32>,
+) -> Result<Task, String> {
+    let canonical = std::fs::canonicalize(&project_path)
+        .map_err(|e| format!("Invalid project path '{}': {}", project_path, e))?
+        .to_string_lossy()
+        .into_owned();
+
+    queue
+        .enqueue(TaskSpec {
+            project_path: canonical,
+            prompt,
+            branch,
+            mode,
+            max_retries,
+        })
+        .map_err(|e| e.to_string())
+}
+
+/// Returns all tasks in queue order.
+#[tauri::command]
+pub async fn task_queue_list(queue: State<'_, Arc<TaskQueue>>) -> Result<Vec<Task>, String> {
+    Ok(queue.list())
+}
+
+/// Returns a single task, or `None` if it does not exist.
+#[tauri::command]
+pub async fn task_queue_get(
+    queue: State<'_, Arc<TaskQueue>>,
+    task_id: u64,
+) -> Result<Option<Task>, String> {
+    Ok(queue.get(task_id))
+}
+
+/// Cancels a queued or in-flight task.
+#[tauri::command]
+pub async fn task_queue_cancel(
+    queue: State<'_, Arc<TaskQueue>>,
+    task_id: u64,
+) -> Result<Task, TaskQueueError> {
+    queue.cancel(task_id)
+}
+
+/// Requeues a failed or cancelled task.
+#[tauri::command]
+pub async fn task_queue_retry(
+    queue: State<'_, Arc<TaskQueue>>,
+    task_id: u64,
+) -> Result<Task, TaskQueueError> {
+    queue.retry(task_id)
+}
+
+/// Removes finished tasks. Returns how many were removed.
+#[tauri::command]
+pub async fn task_queue_clear_finished(queue: State<'_, Arc<TaskQueue>>) -> Result<usize, String> {
+    Ok(queue.clear_finished())
+}
+
+/// Enrolls an existing session as a task worker.
+///
+/// Pass `task_id` when the session was opened for a task in `Spawning` so
+/// the task is reserved for it.
+#[tauri::command]
+pub async fn task_queue_enroll_session(
+    queue: State<'_, Arc<TaskQueue>>,
+    sessions: State<'_, SessionManager>,
+    session_id: u32,
+    task_id: Option<u64>,
+) -> Result<(), String> {
+    let session = sessions
+        .get_session(session_id)
+        .ok_or_else(|| format!("Session {} not found", session_id))?;
+
+    queue
+        .enroll_session(
+            session_id,
+            session.project_path,
+            session.branch,
+            session.mode,
+            task_id,
+        )
+        .map_err(|e| e.to_string())
+}
+
+/// Removes a session from the worker pool, requeueing its in-flight task.
+#[tauri::command]
+pub async fn task_queue_release_session(
+    queue: State<'_, Arc<TaskQueue>>,
+    session_id: u32,
+) -> Result<(), String> {
+    queue.release_session(session_id);
+    Ok(())
+}
+
+/// Returns the scheduler settings.
+#[tauri::command]
+pub async fn task_queue_get_config(
+    queue: State<'_, Arc<TaskQueue>>,
+) -> Result<TaskQueueConfig, String> {
+    Ok(queue.config())
+}
+
+/// Updates the concurrency cap and auto-spawn setting.
+#[tauri::command]
+pub async fn task_queue_set_config(
+    queue: State<'_, Arc<TaskQueue>>,
+    config: TaskQueueConfig,
+) -> Result<TaskQueueConfig, String> {
+    Ok(queue.set_config(config))
+}
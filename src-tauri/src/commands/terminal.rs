@@ -4,6 +4,7 @@ use std::sync::Arc;
 use serde::Serialize;
 use tauri::{AppHandle, State};
 
+use crate::core::port_allocator::PortBlock;
 use crate::core::session_manager::SessionManager;
 use crate::core::status_server::StatusServer;
 use crate::core::windows_process::TokioCommandExt;
@@ -178,6 +179,36 @@ pub async fn kill_session(
     result
 }
 
+/// Returns the port block reserved for a session's shell, or `None` if the
+/// session has none (unknown session, or the port range was exhausted).
+#[tauri::command]
+pub async fn get_session_ports(
+    state: State<'_, ProcessManager>,
+    session_id: u32,
+) -> Result<Option<PortBlock>, PtyError> {
+    Ok(state.session_ports(session_id))
+}
+
+/// A session's port reservation, for listing all allocations.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionPorts {
+    pub session_id: u32,
+    pub ports: PortBlock,
+}
+
+/// Returns the port blocks reserved for all live sessions, ordered by port.
+#[tauri::command]
+pub async fn list_port_allocations(
+    state: State<'_, ProcessManager>,
+) -> Result<Vec<SessionPorts>, PtyError> {
+    Ok(state
+        .port_allocations()
+        .into_iter()
+        .map(|(session_id, ports)| SessionPorts { session_id, ports })
+        .collect())
+}
+
 /// Returns the process tree for a specific session.
 ///
 /// The tree includes the root shell process and all its descendants.
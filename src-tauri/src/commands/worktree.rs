@@ -1,10 +1,16 @@
 use std::path::{Path, PathBuf};
 
 use serde::Serialize;
-use tauri::State;
-
+use tauri::{AppHandle, State};
+
+use crate::commands::bootstrap::bootstrap_prepared_worktree;
+use crate::commands::git::progress_operation;
+use crate::core::git_operations::GitOperations;
+use crate::core::session_manager::SessionManager;
+use crate::core::worktree_bootstrap::BootstrapReport;
+use crate::core::worktree_gc::{GcPolicy, GcReport, WorktreeUsage};
 use crate::core::worktree_manager::{worktree_base_dir, WorktreeManager};
-use crate::git::{BranchInfo, Git};
+use crate::git::{BranchInfo, Git, GitError, GitOperation};
 
 /// Result of preparing a worktree for a session.
 #[derive(Debug, Clone, Serialize)]
@@ -20,6 +26,9 @@ pub struct WorktreePreparationResult {
     pub created: bool,
     /// Warning message if something unexpected happened but we recovered.
     pub warning: Option<String>,
+    /// Outcome of the project's bootstrap spec, when a new worktree was
+    /// created and the project has one.
+    pub bootstrap: Option<BootstrapReport>,
 }
 
 /// Prepares a worktree for a session, handling all edge cases gracefully.
@@ -34,15 +43,37 @@ pub struct WorktreePreparationResult {
 ///
 /// On any failure, falls back to the project path so sessions always launch.
 /// The caller is responsible for updating the session with the worktree path.
+///
+/// Newly created worktrees are then bootstrapped with the project's
+/// bootstrap spec (env files, dependencies) before the result is returned.
+///
+/// The checkout runs as a git operation (`operation_id`, generated if
+/// omitted) that can be cancelled through `cancel_git_operation`.
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn prepare_session_worktree(
+    app: AppHandle,
     worktree_manager: State<'_, WorktreeManager>,
+    operations: State<'_, GitOperations>,
     project_path: String,
     branch: Option<String>,
     worktree_base_path: Option<String>,
     force_new: Option<bool>,
+    operation_id: Option<String>,
 ) -> Result<WorktreePreparationResult, String> {
-    prepare_worktree_inner(&worktree_manager, project_path, branch, worktree_base_path, force_new.unwrap_or(false)).await
+    let operation = progress_operation(&app, operation_id);
+    let _running = operations.register(&operation);
+    let mut result = prepare_worktree_with_operation(
+        &worktree_manager,
+        project_path.clone(),
+        branch,
+        worktree_base_path,
+        force_new.unwrap_or(false),
+        Some(operation),
+    )
+    .await?;
+    bootstrap_prepared_worktree(&app, &project_path, &mut result).await;
+    Ok(result)
 }
 
 /// Inner implementation extracted from the Tauri command for testability.
@@ -55,6 +86,27 @@ pub(crate) async fn prepare_worktree_inner(
     branch: Option<String>,
     worktree_base_path: Option<String>,
     force_new: bool,
+) -> Result<WorktreePreparationResult, String> {
+    prepare_worktree_with_operation(
+        worktree_manager,
+        project_path,
+        branch,
+        worktree_base_path,
+        force_new,
+        None,
+    )
+    .await
+}
+
+/// [`prepare_worktree_inner`], with the worktree checkout run under
+/// `operation` when given so it can be cancelled.
+pub(crate) async fn prepare_worktree_with_operation(
+    worktree_manager: &WorktreeManager,
+    project_path: String,
+    branch: Option<String>,
+    worktree_base_path: Option<String>,
+    force_new: bool,
+    operation: Option<GitOperation>,
 ) -> Result<WorktreePreparationResult, String> {
     // Build git object first so we can call current_branch() for auto-detection
     let repo_path = PathBuf::from(&project_path);
@@ -96,6 +148,7 @@ pub(crate) async fn prepare_worktree_inner(
                                     branch: Some(wt_branch.clone()),
                                     created: false,
                                     warning: None,
+                                    bootstrap: None,
                                 });
                             }
                         }
@@ -114,6 +167,7 @@ pub(crate) async fn prepare_worktree_inner(
                         branch: None,
                         created: false,
                         warning: None,
+                        bootstrap: None,
                     });
                 }
             }
@@ -150,6 +204,7 @@ pub(crate) async fn prepare_worktree_inner(
                                 branch: Some(local_branch.clone()),
                                 created: false,
                                 warning: None,
+                                bootstrap: None,
                             });
                         }
                     }
@@ -162,11 +217,28 @@ pub(crate) async fn prepare_worktree_inner(
     }
 
     // Check if the branch is checked out in the main repo.
-    // If so, we use --force on worktree add instead of switching the main repo,
-    // which allows worktree creation even with uncommitted changes present.
+    // If so, switch the main repo to a fallback branch, auto-stashing its
+    // uncommitted changes. Without a fallback (or if the switch fails), we use
+    // --force on worktree add instead.
     let current_branch = git.current_branch().await.ok();
-    let branch_in_main = current_branch.as_ref() == Some(&local_branch);
-    let warning = None;
+    let mut branch_in_main = current_branch.as_ref() == Some(&local_branch);
+    let mut warning = None;
+    if branch_in_main {
+        if let Some(fallback) = get_fallback_branch(&git, &local_branch).await {
+            match switch_main_checkout(&git, &local_branch, &fallback).await {
+                Ok(message) => {
+                    branch_in_main = false;
+                    warning = message;
+                }
+                Err(e) => log::warn!(
+                    "Failed to switch main repo from {} to {}: {}",
+                    local_branch,
+                    fallback,
+                    e
+                ),
+            }
+        }
+    }
 
     // Ensure the branch exists locally, handling remote branches correctly
     if let Err(e) = ensure_local_branch(&git, &branch, &local_branch, &branches).await {
@@ -177,6 +249,7 @@ pub(crate) async fn prepare_worktree_inner(
             branch: None,
             created: false,
             warning: Some(format!("Failed to create branch {}: {}", local_branch, e)),
+            bootstrap: None,
         });
     }
 
@@ -184,10 +257,25 @@ pub(crate) async fn prepare_worktree_inner(
     // force_new: always create a fresh worktree with a unique path (ignores existing worktrees).
     // branch_in_main: the branch is currently checked out in the main repo, use --force.
     let base_override = worktree_base_path.as_deref().map(Path::new);
-    let create_result = if force_new {
-        worktree_manager.create_with_base_new(&local_branch, &repo_path, base_override).await
-    } else {
-        worktree_manager.create_with_base(&local_branch, &repo_path, base_override, branch_in_main).await
+    let create_result = match operation {
+        Some(operation) => {
+            worktree_manager
+                .create_with_operation(
+                    &local_branch,
+                    &repo_path,
+                    base_override,
+                    branch_in_main,
+                    force_new,
+                    operation,
+                )
+                .await
+        }
+        None if force_new => {
+            worktree_manager.create_with_base_new(&local_branch, &repo_path, base_override).await
+        }
+        None => {
+            worktree_manager.create_with_base(&local_branch, &repo_path, base_override, branch_in_main).await
+        }
     };
     match create_result {
         Ok(wt_path) => {
@@ -204,8 +292,11 @@ pub(crate) async fn prepare_worktree_inner(
                 branch: Some(local_branch.clone()),
                 created: true,
                 warning,
+                bootstrap: None,
             })
         }
+        // A cancelled checkout aborts the launch instead of falling back.
+        Err(e @ GitError::Cancelled { .. }) => Err(e.to_string()),
         Err(e) => {
             log::error!("Failed to create worktree for {}: {}", local_branch, e);
             Ok(WorktreePreparationResult {
@@ -214,6 +305,7 @@ pub(crate) async fn prepare_worktree_inner(
                 branch: None,
                 created: false,
                 warning: Some(format!("Failed to create worktree: {}", e)),
+                bootstrap: None,
             })
         }
     }
@@ -291,6 +383,43 @@ pub(crate) async fn get_fallback_branch(git: &Git, avoid_branch: &str) -> Option
     None
 }
 
+/// Switches the main checkout from `branch` to `fallback` so a worktree can
+/// check `branch` out. Uncommitted changes are auto-stashed and come back
+/// when the main checkout switches to `branch` again. Returns a message for
+/// the user when changes were stashed or an earlier stash of `fallback`
+/// could not be re-applied.
+async fn switch_main_checkout(
+    git: &Git,
+    branch: &str,
+    fallback: &str,
+) -> Result<Option<String>, GitError> {
+    let outcome = git.checkout_with_autostash(fallback).await?;
+    log::info!("Switched main repo from {} to {}", branch, fallback);
+
+    let mut notes = Vec::new();
+    if let Some(stashed) = outcome.stashed {
+        notes.push(format!(
+            "Uncommitted changes on {} were stashed ({}) and are restored when the main checkout switches back to it.",
+            branch, stashed.reference
+        ));
+    }
+    if let Some(error) = outcome.restore_error {
+        notes.push(format!(
+            "Stashed changes of {} could not be re-applied and are kept in the stash list: {}",
+            fallback, error
+        ));
+    }
+    if notes.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(format!(
+        "Switched the main checkout to {} to free {}. {}",
+        fallback,
+        branch,
+        notes.join(" ")
+    )))
+}
+
 /// Returns the default worktree base directory path.
 ///
 /// This allows the frontend to display the default path when no custom
@@ -300,6 +429,51 @@ pub async fn get_default_worktree_base_dir() -> Result<String, String> {
     Ok(worktree_base_dir().to_string_lossy().to_string())
 }
 
+/// Reports disk usage, last activity, dirty state and merged state for each
+/// of the project's managed worktrees.
+#[tauri::command]
+pub async fn get_worktree_usage(
+    worktree_manager: State<'_, WorktreeManager>,
+    project_path: String,
+    worktree_base_path: Option<String>,
+) -> Result<Vec<WorktreeUsage>, String> {
+    let base = worktree_base_path.map(PathBuf::from);
+    worktree_manager
+        .worktree_usage(Path::new(&project_path), base.as_deref())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Removes the project's managed worktrees selected by `policy` (the
+/// default policy if omitted). Worktrees used by any current session are
+/// always kept. With `dry_run` the report lists what would be removed.
+#[tauri::command]
+pub async fn collect_worktree_garbage(
+    worktree_manager: State<'_, WorktreeManager>,
+    session_manager: State<'_, SessionManager>,
+    project_path: String,
+    worktree_base_path: Option<String>,
+    policy: Option<GcPolicy>,
+    dry_run: bool,
+) -> Result<GcReport, String> {
+    let base = worktree_base_path.map(PathBuf::from);
+    let protected: Vec<String> = session_manager
+        .all_sessions()
+        .into_iter()
+        .filter_map(|s| s.worktree_path)
+        .collect();
+    worktree_manager
+        .collect_garbage(
+            Path::new(&project_path),
+            base.as_deref(),
+            &policy.unwrap_or_default(),
+            &protected,
+            dry_run,
+        )
+        .await
+        .map_err(|e| e.to_string())
+}
+
 /// Returns true if at least one Maestro-managed worktree exists for the given project.
 /// Used by the frontend to enable/disable the "Current Worktree" launch option.
 ///
@@ -624,18 +798,53 @@ mod tests {
             "Working directory should NOT be the main repo"
         );
 
-        // With --force worktree add, main repo stays on the same branch
+        // The main repo is switched to the fallback branch
         let new_current = git.current_branch().await.unwrap();
-        assert_eq!(
-            new_current, current,
-            "Main repo should stay on the same branch when --force is used"
-        );
+        assert_eq!(new_current, "fallback");
+        assert!(result.warning.is_none(), "Nothing was stashed");
 
         // Cleanup
         let wt_path = PathBuf::from(result.worktree_path.unwrap());
         let _ = wm.remove(&path, &wt_path).await;
     }
 
+    #[tokio::test]
+    async fn test_prepare_current_branch_stashes_main_checkout_changes() {
+        let (_dir, path) = create_test_repo().await;
+        let git = Git::new(&path);
+        create_branch(&git, "fallback").await;
+        let current = git.current_branch().await.unwrap();
+        tokio::fs::write(path.join("README.md"), "# Edited").await.unwrap();
+        tokio::fs::write(path.join("notes.txt"), "wip").await.unwrap();
+
+        let wm = WorktreeManager::new();
+        let result = prepare_worktree_inner(
+            &wm,
+            path.to_string_lossy().to_string(),
+            Some(current.clone()),
+            None,
+            false,
+        )
+        .await
+        .unwrap();
+
+        assert!(result.created);
+        assert_eq!(git.current_branch().await.unwrap(), "fallback");
+        assert!(git.status_entries().await.unwrap().is_empty());
+        assert!(result.warning.unwrap().contains("stash@{0}"));
+        let stashes = git.stash_list().await.unwrap();
+        assert_eq!(stashes.len(), 1);
+        assert!(stashes[0].includes_untracked);
+
+        // Once the worktree is gone, switching back restores the changes
+        let wt_path = PathBuf::from(result.worktree_path.unwrap());
+        wm.remove(&path, &wt_path).await.unwrap();
+        let back = git.checkout_with_autostash(&current).await.unwrap();
+        assert!(back.restored.is_some());
+        assert_eq!(std::fs::read_to_string(path.join("README.md")).unwrap(), "# Edited");
+        assert_eq!(std::fs::read_to_string(path.join("notes.txt")).unwrap(), "wip");
+    }
+
     #[tokio::test]
     async fn test_prepare_current_branch_single_branch_detaches() {
         let (_dir, path) = create_test_repo().await;
@@ -129,6 +129,44 @@ pub enum ClaudeEvent {
         cache_creation_tokens: u64,
         timestamp: String,
     },
+
+    // === Coordination (MessageBus-sourced) ===
+    /// A message was queued in this session's mailbox.
+    MessageReceived {
+        session_id: u32,
+        message_id: String,
+        topic: String,
+        from_session: Option<u32>,
+        body: String,
+        timestamp: String,
+    },
+
+    // === GitHub (PrMonitor-sourced) ===
+    /// Checks or reviews changed on the pull request linked to this session.
+    PullRequestStatusChanged {
+        session_id: u32,
+        number: u64,
+        url: String,
+        /// Overall check state: `passing`, `failing`, `pending` or `none`.
+        checks_state: String,
+        failing_checks: Vec<String>,
+        review_decision: Option<String>,
+        timestamp: String,
+    },
+
+    // === Worktrees (ConflictDetector-sourced) ===
+    /// This session's changes clash with another session or the base branch.
+    ConflictRiskDetected {
+        session_id: u32,
+        /// The other session involved; `None` when the risk is against the base branch.
+        other_session_id: Option<u32>,
+        /// Branch compared against (the other session's branch or the base branch).
+        against: String,
+        /// `conflict` (merge would stop) or `overlap` (same files, merges cleanly).
+        level: String,
+        files: Vec<String>,
+        timestamp: String,
+    },
 }
 
 impl ClaudeEvent {
@@ -146,7 +184,10 @@ impl ClaudeEvent {
             | ClaudeEvent::SubagentSpawned { session_id, .. }
             | ClaudeEvent::SubagentCompleted { session_id, .. }
             | ClaudeEvent::StatusUpdate { session_id, .. }
-            | ClaudeEvent::TokenUsageUpdate { session_id, .. } => *session_id,
+            | ClaudeEvent::TokenUsageUpdate { session_id, .. }
+            | ClaudeEvent::MessageReceived { session_id, .. }
+            | ClaudeEvent::PullRequestStatusChanged { session_id, .. }
+            | ClaudeEvent::ConflictRiskDetected { session_id, .. } => *session_id,
         }
     }
 
@@ -192,6 +233,31 @@ impl ClaudeEvent {
             ClaudeEvent::TokenUsageUpdate { session_id, input_tokens, output_tokens, .. } => {
                 format!("TokenUsageUpdate:{session_id}:{input_tokens}:{output_tokens}")
             }
+            ClaudeEvent::MessageReceived { session_id, message_id, .. } => {
+                format!("MessageReceived:{session_id}:{message_id}")
+            }
+            ClaudeEvent::PullRequestStatusChanged {
+                session_id,
+                number,
+                checks_state,
+                failing_checks,
+                review_decision,
+                ..
+            } => {
+                let failing = failing_checks.join(",");
+                let decision = review_decision.as_deref().unwrap_or("");
+                format!("PullRequestStatusChanged:{session_id}:{number}:{checks_state}:{failing}:{decision}")
+            }
+            ClaudeEvent::ConflictRiskDetected {
+                session_id,
+                against,
+                level,
+                files,
+                ..
+            } => {
+                let files = files.join(",");
+                format!("ConflictRiskDetected:{session_id}:{against}:{level}:{files}")
+            }
         }
     }
 }
@@ -251,6 +317,9 @@ mod tests {
             ClaudeEvent::SubagentCompleted { session_id: 10, agent_id: "s".into(), timestamp: "t".into() },
             ClaudeEvent::StatusUpdate { session_id: 11, state: "working".into(), message: "m".into(), needs_input_prompt: None, timestamp: "t".into() },
             ClaudeEvent::TokenUsageUpdate { session_id: 12, input_tokens: 100, output_tokens: 50, cache_read_tokens: 10, cache_creation_tokens: 5, timestamp: "t".into() },
+            ClaudeEvent::MessageReceived { session_id: 13, message_id: "m".into(), topic: "api".into(), from_session: Some(1), body: "b".into(), timestamp: "t".into() },
+            ClaudeEvent::PullRequestStatusChanged { session_id: 14, number: 7, url: "u".into(), checks_state: "failing".into(), failing_checks: vec!["build".into()], review_decision: None, timestamp: "t".into() },
+            ClaudeEvent::ConflictRiskDetected { session_id: 15, other_session_id: Some(3), against: "feat/a".into(), level: "conflict".into(), files: vec!["src/router.rs".into()], timestamp: "t".into() },
         ];
         for (i, event) in events.iter().enumerate() {
             assert_eq!(event.session_id(), (i as u32) + 1);
@@ -0,0 +1,630 @@
+//! Detects conflicting work between parallel session worktrees.
+//!
+//! Each session with a Maestro-managed worktree is snapshotted (uncommitted
+//! and untracked changes included, see [`Git::snapshot_commit`]) and dry-run
+//! merged against every other session of the same repository and against the
+//! default base branch with `git merge-tree`. Nothing is checked out or
+//! written to any ref.
+//!
+//! Two levels of risk are reported:
+//! - [`RiskLevel::Conflict`]: the merge would stop with conflicts.
+//! - [`RiskLevel::Overlap`]: both sides changed the same files but git can
+//!   still merge them automatically.
+//!
+//! Newly found risks are announced as [`ClaudeEvent::ConflictRiskDetected`]
+//! on the [`EventBus`] for every session involved, and the full risk list is
+//! passed to an optional callback (used to emit a Tauri event) when it changes.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde::Serialize;
+
+use super::claude_event::ClaudeEvent;
+use super::event_bus::EventBus;
+use super::session_manager::SessionConfig;
+use super::worktree_manager::WorktreeManager;
+use crate::git::{Git, GitError, MergeTreeResult};
+
+/// How often session worktrees are compared.
+pub const DEFAULT_ANALYZE_INTERVAL: Duration = Duration::from_secs(90);
+
+/// Callback invoked with the complete risk list whenever it changes.
+pub type ConflictRisksFn = Arc<dyn Fn(&[ConflictRisk]) + Send + Sync>;
+
+/// Severity of a conflict risk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RiskLevel {
+    /// Both sides changed the same files; git can still merge them.
+    Overlap,
+    /// Merging would produce conflicts.
+    Conflict,
+}
+
+impl RiskLevel {
+    fn as_str(&self) -> &'static str {
+        match self {
+            RiskLevel::Overlap => "overlap",
+            RiskLevel::Conflict => "conflict",
+        }
+    }
+}
+
+/// A risk that a session's work will clash with another session or with
+/// the base branch.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct ConflictRisk {
+    pub session_id: u32,
+    /// The other session involved, or `None` when the risk is against the
+    /// base branch.
+    pub other_session_id: Option<u32>,
+    /// What the session was compared against: the other session's branch or
+    /// the base branch.
+    pub against: String,
+    pub level: RiskLevel,
+    /// Affected paths, sorted.
+    pub files: Vec<String>,
+}
+
+/// A session worktree taking part in the analysis.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SessionWorktree {
+    pub session_id: u32,
+    /// Main repository the worktree belongs to.
+    pub repo_path: PathBuf,
+    pub worktree_path: PathBuf,
+    pub branch: Option<String>,
+}
+
+impl SessionWorktree {
+    fn label(&self) -> String {
+        self.branch
+            .clone()
+            .unwrap_or_else(|| format!("session {}", self.session_id))
+    }
+}
+
+/// A worktree snapshot ready for dry-run merges.
+struct Snapshot<'a> {
+    worktree: &'a SessionWorktree,
+    commit: String,
+}
+
+/// Keeps the latest conflict risks between session worktrees and announces
+/// new ones.
+pub struct ConflictDetector {
+    risks: Mutex<Vec<ConflictRisk>>,
+    event_bus: Option<Arc<EventBus>>,
+    on_change: Option<ConflictRisksFn>,
+}
+
+impl ConflictDetector {
+    /// Creates a detector that reports to `event_bus` and `on_change`.
+    pub fn new(event_bus: Option<Arc<EventBus>>, on_change: Option<ConflictRisksFn>) -> Self {
+        Self {
+            risks: Mutex::new(Vec::new()),
+            event_bus,
+            on_change,
+        }
+    }
+
+    /// Returns the sessions whose worktree is managed by `worktrees`.
+    ///
+    /// Sessions working directly in the project directory, or in a worktree
+    /// Maestro did not create, are skipped.
+    pub async fn session_worktrees(
+        sessions: &[SessionConfig],
+        worktrees: &WorktreeManager,
+    ) -> Vec<SessionWorktree> {
+        let mut by_repo: BTreeMap<&str, Vec<&SessionConfig>> = BTreeMap::new();
+        for session in sessions.iter().filter(|s| s.worktree_path.is_some()) {
+            by_repo
+                .entry(&session.project_path)
+                .or_default()
+                .push(session);
+        }
+
+        let mut result = Vec::new();
+        for (repo, sessions) in by_repo {
+            let repo_path = Path::new(repo);
+            let managed = match worktrees.list_managed(repo_path).await {
+                Ok(managed) => managed,
+                Err(e) => {
+                    log::warn!(
+                        "Conflict detector: failed to list worktrees of {}: {}",
+                        repo,
+                        e
+                    );
+                    continue;
+                }
+            };
+            let managed_paths: Vec<PathBuf> = managed
+                .iter()
+                .map(|wt| canonical(Path::new(&wt.path)))
+                .collect();
+
+            for session in sessions {
+                let Some(ref wt_path) = session.worktree_path else {
+                    continue;
+                };
+                let wt_path = PathBuf::from(wt_path);
+                if managed_paths.contains(&canonical(&wt_path)) {
+                    result.push(SessionWorktree {
+                        session_id: session.id,
+                        repo_path: repo_path.to_path_buf(),
+                        worktree_path: wt_path,
+                        branch: session.branch.clone(),
+                    });
+                }
+            }
+        }
+        result
+    }
+
+    /// Compares every pair of worktrees of the same repository, and each
+    /// worktree against the repository's default base branch.
+    ///
+    /// Worktrees that cannot be snapshotted (e.g. removed mid-analysis) are
+    /// logged and skipped.
+    pub async fn analyze(worktrees: &[SessionWorktree]) -> Vec<ConflictRisk> {
+        let mut by_repo: BTreeMap<&Path, Vec<&SessionWorktree>> = BTreeMap::new();
+        for wt in worktrees {
+            by_repo.entry(&wt.repo_path).or_default().push(wt);
+        }
+
+        let mut risks = Vec::new();
+        for (repo, worktrees) in by_repo {
+            match Self::analyze_repo(repo, &worktrees).await {
+                Ok(found) => risks.extend(found),
+                Err(e) => log::warn!(
+                    "Conflict detector: failed to analyze {}: {}",
+                    repo.display(),
+                    e
+                ),
+            }
+        }
+        risks.sort();
+        risks
+    }
+
+    async fn analyze_repo(
+        repo: &Path,
+        worktrees: &[&SessionWorktree],
+    ) -> Result<Vec<ConflictRisk>, GitError> {
+        let git = Git::new(repo);
+
+        let mut snapshots = Vec::new();
+        for wt in worktrees {
+            match Git::new(&wt.worktree_path).snapshot_commit().await {
+                Ok(commit) => snapshots.push(Snapshot {
+                    worktree: wt,
+                    commit,
+                }),
+                Err(e) => log::warn!(
+                    "Conflict detector: failed to snapshot session {} ({}): {}",
+                    wt.session_id,
+                    wt.worktree_path.display(),
+                    e
+                ),
+            }
+        }
+
+        let mut risks = Vec::new();
+        for (i, a) in snapshots.iter().enumerate() {
+            for b in &snapshots[i + 1..] {
+                if a.commit == b.commit {
+                    continue;
+                }
+                // One failing pair (e.g. unrelated histories) must not hide
+                // the risks between the others
+                match Self::analyze_pair(&git, a, b).await {
+                    Ok(found) => risks.extend(found),
+                    Err(e) => log::warn!(
+                        "Conflict detector: failed to compare sessions {} and {}: {}",
+                        a.worktree.session_id,
+                        b.worktree.session_id,
+                        e
+                    ),
+                }
+            }
+        }
+
+        if let Some(base_branch) = git.default_base_branch().await? {
+            for snapshot in &snapshots {
+                let merge = match git.merge_tree(&base_branch, &snapshot.commit).await {
+                    Ok(merge) => merge,
+                    Err(e) => {
+                        log::warn!(
+                            "Conflict detector: failed to compare session {} with {}: {}",
+                            snapshot.worktree.session_id,
+                            base_branch,
+                            e
+                        );
+                        continue;
+                    }
+                };
+                if !merge.is_clean() {
+                    risks.push(ConflictRisk {
+                        session_id: snapshot.worktree.session_id,
+                        other_session_id: None,
+                        against: base_branch.clone(),
+                        level: RiskLevel::Conflict,
+                        files: sorted(merge.conflicted_files),
+                    });
+                }
+            }
+        }
+        Ok(risks)
+    }
+
+    async fn analyze_pair(
+        git: &Git,
+        a: &Snapshot<'_>,
+        b: &Snapshot<'_>,
+    ) -> Result<Vec<ConflictRisk>, GitError> {
+        let merge = git.merge_tree(&a.commit, &b.commit).await?;
+        let base = git.merge_base(&a.commit, &b.commit).await?;
+        let changed_a = git.changed_paths(&base, &a.commit).await?;
+        let changed_b = git.changed_paths(&base, &b.commit).await?;
+        Ok(pair_risks(
+            a.worktree, b.worktree, &merge, &changed_a, &changed_b,
+        ))
+    }
+
+    /// Analyzes the sessions' worktrees and stores the result.
+    pub async fn run(
+        &self,
+        sessions: &[SessionConfig],
+        worktrees: &WorktreeManager,
+    ) -> Vec<ConflictRisk> {
+        let participants = Self::session_worktrees(sessions, worktrees).await;
+        let risks = Self::analyze(&participants).await;
+        self.apply(risks.clone());
+        risks
+    }
+
+    /// Replaces the stored risks. Risks not present in the previous result
+    /// are announced on the event bus (once per session involved). Returns
+    /// `true` (and calls `on_change`) if the list changed.
+    pub fn apply(&self, risks: Vec<ConflictRisk>) -> bool {
+        let new_risks: Vec<ConflictRisk> = {
+            let mut stored = self.risks.lock().unwrap();
+            if *stored == risks {
+                return false;
+            }
+            let new_risks = risks
+                .iter()
+                .filter(|r| !stored.contains(r))
+                .cloned()
+                .collect();
+            *stored = risks.clone();
+            new_risks
+        };
+
+        if let Some(ref bus) = self.event_bus {
+            let timestamp = chrono::Utc::now().to_rfc3339();
+            for risk in &new_risks {
+                bus.emit(risk_event(
+                    risk,
+                    risk.session_id,
+                    risk.other_session_id,
+                    &timestamp,
+                ));
+                if let Some(other) = risk.other_session_id {
+                    bus.emit(risk_event(risk, other, Some(risk.session_id), &timestamp));
+                }
+            }
+        }
+        if let Some(ref on_change) = self.on_change {
+            on_change(&risks);
+        }
+        true
+    }
+
+    /// Latest risks across all sessions.
+    pub fn risks(&self) -> Vec<ConflictRisk> {
+        self.risks.lock().unwrap().clone()
+    }
+
+    /// Latest risks involving one session.
+    pub fn risks_for_session(&self, session_id: u32) -> Vec<ConflictRisk> {
+        self.risks
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|r| r.session_id == session_id || r.other_session_id == Some(session_id))
+            .cloned()
+            .collect()
+    }
+}
+
+/// Builds the risks between two session snapshots from their dry-run merge
+/// and the files each changed since their merge base. Conflicting files are
+/// reported as a conflict; other files changed on both sides as an overlap.
+fn pair_risks(
+    a: &SessionWorktree,
+    b: &SessionWorktree,
+    merge: &MergeTreeResult,
+    changed_a: &[String],
+    changed_b: &[String],
+) -> Vec<ConflictRisk> {
+    let (first, second) = if a.session_id <= b.session_id {
+        (a, b)
+    } else {
+        (b, a)
+    };
+    let risk = |level, files| ConflictRisk {
+        session_id: first.session_id,
+        other_session_id: Some(second.session_id),
+        against: second.label(),
+        level,
+        files,
+    };
+
+    let conflicted: BTreeSet<&String> = merge.conflicted_files.iter().collect();
+    let changed_b: BTreeSet<&String> = changed_b.iter().collect();
+    let overlapping: Vec<String> = changed_a
+        .iter()
+        .filter(|path| changed_b.contains(path) && !conflicted.contains(path))
+        .cloned()
+        .collect();
+
+    let mut risks = Vec::new();
+    if !conflicted.is_empty() {
+        risks.push(risk(
+            RiskLevel::Conflict,
+            sorted(merge.conflicted_files.clone()),
+        ));
+    }
+    if !overlapping.is_empty() {
+        risks.push(risk(RiskLevel::Overlap, sorted(overlapping)));
+    }
+    risks
+}
+
+fn risk_event(
+    risk: &ConflictRisk,
+    session_id: u32,
+    other_session_id: Option<u32>,
+    timestamp: &str,
+) -> ClaudeEvent {
+    ClaudeEvent::ConflictRiskDetected {
+        session_id,
+        other_session_id,
+        against: risk.against.clone(),
+        level: risk.level.as_str().to_string(),
+        files: risk.files.clone(),
+        timestamp: timestamp.to_string(),
+    }
+}
+
+fn sorted(mut files: Vec<String>) -> Vec<String> {
+    files.sort();
+    files.dedup();
+    files
+}
+
+fn canonical(path: &Path) -> PathBuf {
+    std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn worktree(session_id: u32, branch: &str) -> SessionWorktree {
+        SessionWorktree {
+            session_id,
+            repo_path: PathBuf::from("/repo"),
+            worktree_path: PathBuf::from(format!("/wt/{branch}")),
+            branch: Some(branch.to_string()),
+        }
+    }
+
+    fn paths(paths: &[&str]) -> Vec<String> {
+        paths.iter().map(|p| p.to_string()).collect()
+    }
+
+    fn recording_detector() -> (ConflictDetector, Arc<Mutex<Vec<ClaudeEvent>>>) {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let sink = events.clone();
+        let bus = Arc::new(EventBus::new(Arc::new(move |e| {
+            sink.lock().unwrap().push(e)
+        })));
+        (ConflictDetector::new(Some(bus), None), events)
+    }
+
+    #[test]
+    fn test_pair_risks_split_conflicts_and_overlaps() {
+        let merge = MergeTreeResult {
+            tree: "t".to_string(),
+            conflicted_files: paths(&["src/router.rs"]),
+        };
+        let risks = pair_risks(
+            &worktree(5, "feat/b"),
+            &worktree(2, "feat/a"),
+            &merge,
+            &paths(&["src/router.rs", "README.md", "a.rs"]),
+            &paths(&["src/router.rs", "README.md", "b.rs"]),
+        );
+
+        assert_eq!(risks.len(), 2);
+        // Ordered by session id regardless of argument order
+        assert_eq!(
+            (risks[0].session_id, risks[0].other_session_id),
+            (2, Some(5))
+        );
+        assert_eq!(risks[0].against, "feat/b");
+        assert_eq!(risks[0].level, RiskLevel::Conflict);
+        assert_eq!(risks[0].files, paths(&["src/router.rs"]));
+        assert_eq!(risks[1].level, RiskLevel::Overlap);
+        assert_eq!(risks[1].files, paths(&["README.md"]));
+    }
+
+    #[test]
+    fn test_pair_risks_disjoint_changes() {
+        let merge = MergeTreeResult {
+            tree: "t".to_string(),
+            conflicted_files: vec![],
+        };
+        let risks = pair_risks(
+            &worktree(1, "a"),
+            &worktree(2, "b"),
+            &merge,
+            &paths(&["a.rs"]),
+            &paths(&["b.rs"]),
+        );
+        assert!(risks.is_empty());
+    }
+
+    #[test]
+    fn test_apply_announces_new_risks_to_both_sessions() {
+        let (detector, events) = recording_detector();
+        let risk = ConflictRisk {
+            session_id: 2,
+            other_session_id: Some(5),
+            against: "feat/b".to_string(),
+            level: RiskLevel::Conflict,
+            files: paths(&["src/router.rs"]),
+        };
+
+        assert!(detector.apply(vec![risk.clone()]));
+        assert!(!detector.apply(vec![risk.clone()]));
+        assert_eq!(detector.risks_for_session(5), vec![risk.clone()]);
+        assert!(detector.risks_for_session(3).is_empty());
+
+        let events = events.lock().unwrap();
+        assert_eq!(events.len(), 2);
+        let sessions: Vec<(u32, Option<u32>)> = events
+            .iter()
+            .map(|e| match e {
+                ClaudeEvent::ConflictRiskDetected {
+                    session_id,
+                    other_session_id,
+                    level,
+                    ..
+                } => {
+                    assert_eq!(level, "conflict");
+                    (*session_id, *other_session_id)
+                }
+                other => panic!("unexpected event {:?}", other),
+            })
+            .collect();
+        assert_eq!(sessions, vec![(2, Some(5)), (5, Some(2))]);
+    }
+
+    #[test]
+    fn test_apply_resolved_risks_notify_callback_only() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let sink = seen.clone();
+        let (events_detector, events) = recording_detector();
+        let detector = ConflictDetector::new(
+            events_detector.event_bus.clone(),
+            Some(Arc::new(move |risks: &[ConflictRisk]| {
+                sink.lock().unwrap().push(risks.len())
+            })),
+        );
+        let risk = ConflictRisk {
+            session_id: 1,
+            other_session_id: None,
+            against: "main".to_string(),
+            level: RiskLevel::Conflict,
+            files: paths(&["a.rs"]),
+        };
+
+        detector.apply(vec![risk]);
+        assert!(detector.apply(vec![]));
+        assert_eq!(*seen.lock().unwrap(), vec![1, 0]);
+        assert_eq!(events.lock().unwrap().len(), 1);
+    }
+
+    async fn git(dir: &Path, args: &[&str]) -> String {
+        Git::new(dir).run(args).await.unwrap().trimmed().to_string()
+    }
+
+    #[tokio::test]
+    async fn test_analyze_worktrees() {
+        let dir = tempdir().unwrap();
+        let repo = dir.path().join("repo");
+        std::fs::create_dir(&repo).unwrap();
+        git(&repo, &["init", "-b", "main"]).await;
+        git(&repo, &["config", "user.email", "test@test.com"]).await;
+        git(&repo, &["config", "user.name", "Test"]).await;
+        std::fs::write(repo.join("router.rs"), "fn route() {\n    a();\n}\n").unwrap();
+        std::fs::write(repo.join("lib.rs"), "one\n\n\n\n\n\n\ntwo\n").unwrap();
+        git(&repo, &["add", "."]).await;
+        git(&repo, &["commit", "-m", "initial"]).await;
+
+        let mut sessions = Vec::new();
+        for (id, branch) in [(2, "feat/a"), (5, "feat/b")] {
+            let path = dir.path().join(branch.replace('/', "-"));
+            git(
+                &repo,
+                &["worktree", "add", "-b", branch, path.to_str().unwrap()],
+            )
+            .await;
+            sessions.push(SessionWorktree {
+                session_id: id,
+                repo_path: repo.clone(),
+                worktree_path: path,
+                branch: Some(branch.to_string()),
+            });
+        }
+
+        // Session 2 commits its router change, session 5 leaves it uncommitted
+        let (a, b) = (&sessions[0].worktree_path, &sessions[1].worktree_path);
+        std::fs::write(a.join("router.rs"), "fn route() {\n    a2();\n}\n").unwrap();
+        std::fs::write(a.join("lib.rs"), "ONE\n\n\n\n\n\n\ntwo\n").unwrap();
+        git(a, &["commit", "-am", "a"]).await;
+        std::fs::write(b.join("router.rs"), "fn route() {\n    b();\n}\n").unwrap();
+        std::fs::write(b.join("lib.rs"), "one\n\n\n\n\n\n\nTWO\n").unwrap();
+
+        let risks = ConflictDetector::analyze(&sessions).await;
+        assert_eq!(risks.len(), 2, "{:?}", risks);
+        assert_eq!(risks[0].level, RiskLevel::Overlap);
+        assert_eq!(risks[0].files, paths(&["lib.rs"]));
+        assert_eq!(risks[1].level, RiskLevel::Conflict);
+        assert_eq!(
+            (risks[1].session_id, risks[1].other_session_id),
+            (2, Some(5))
+        );
+        assert_eq!(risks[1].files, paths(&["router.rs"]));
+
+        // Main moves on and now conflicts with session 2's committed change
+        std::fs::write(repo.join("router.rs"), "fn route() {\n    main();\n}\n").unwrap();
+        git(&repo, &["commit", "-am", "main"]).await;
+        let risks = ConflictDetector::analyze(&sessions).await;
+        let against_main: Vec<_> = risks
+            .iter()
+            .filter(|r| r.other_session_id.is_none())
+            .collect();
+        assert_eq!(against_main.len(), 2);
+        assert!(against_main
+            .iter()
+            .all(|r| r.against == "main" && r.files == paths(&["router.rs"])));
+
+        // A session on an unrelated history can't be compared with anything,
+        // but the other pairs are still analyzed
+        let orphan = dir.path().join("orphan");
+        git(&repo, &["worktree", "add", "--detach", orphan.to_str().unwrap()]).await;
+        git(&orphan, &["checkout", "--orphan", "orphan"]).await;
+        git(&orphan, &["commit", "-m", "unrelated"]).await;
+        sessions.push(SessionWorktree {
+            session_id: 7,
+            repo_path: repo.clone(),
+            worktree_path: orphan,
+            branch: Some("orphan".to_string()),
+        });
+        let risks = ConflictDetector::analyze(&sessions).await;
+        assert!(risks
+            .iter()
+            .any(|r| r.session_id == 2 && r.other_session_id == Some(5)));
+        assert!(risks
+            .iter()
+            .all(|r| r.session_id != 7 && r.other_session_id != Some(7)));
+    }
+}
@@ -0,0 +1,461 @@
+//! Fast cloning of large, ignored directories into new worktrees.
+//!
+//! Build outputs (`target/`) and installed dependencies (`node_modules/`)
+//! are rebuilt from scratch in every fresh worktree, which makes parallel
+//! sessions slow to start. [`clone_tree`] copies such a directory from the
+//! main checkout using the cheapest mechanism the filesystem supports:
+//!
+//! 1. reflinks (`FICLONE` on btrfs/XFS, `clonefile` on APFS), which share
+//!    blocks copy-on-write so the clone is near-instant and uses no space;
+//! 2. hard links, when the directory is marked as an immutable cache that
+//!    is only ever added to (never a build directory written in place);
+//! 3. a plain byte copy.
+//!
+//! Reflinked and copied files get fresh modification times (set explicitly
+//! where the platform would carry the source's over), so build tools see the
+//! cloned outputs as newer than the freshly checked-out sources. Hard links
+//! share the source's inode and therefore its modification time, which is
+//! fine for the caches they are limited to.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::SystemTime;
+
+use serde::{Deserialize, Serialize};
+
+/// A directory to clone into new worktrees, relative to the repository root.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CloneDir {
+    pub path: String,
+    /// Fall back to hard links instead of copying when reflinks are not
+    /// available. Only safe for caches whose files are never modified in
+    /// place, since both checkouts share the same inodes.
+    pub allow_hardlink: bool,
+}
+
+/// How a file was cloned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CloneMethod {
+    Reflink,
+    Hardlink,
+    Copy,
+}
+
+/// Number of files and total bytes in a directory tree.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TreeSize {
+    pub files: u64,
+    pub bytes: u64,
+}
+
+/// What [`clone_tree`] did.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CloneStats {
+    pub size: TreeSize,
+    pub reflinked: u64,
+    pub hardlinked: u64,
+    pub copied: u64,
+}
+
+impl CloneStats {
+    /// The slowest method used for any file, or `None` if the tree had no
+    /// regular files.
+    pub fn method(&self) -> Option<CloneMethod> {
+        if self.copied > 0 {
+            Some(CloneMethod::Copy)
+        } else if self.hardlinked > 0 {
+            Some(CloneMethod::Hardlink)
+        } else if self.reflinked > 0 {
+            Some(CloneMethod::Reflink)
+        } else {
+            None
+        }
+    }
+
+    fn record(&mut self, method: CloneMethod, bytes: u64) {
+        self.size.files += 1;
+        self.size.bytes += bytes;
+        match method {
+            CloneMethod::Reflink => self.reflinked += 1,
+            CloneMethod::Hardlink => self.hardlinked += 1,
+            CloneMethod::Copy => self.copied += 1,
+        }
+    }
+}
+
+/// Size of a configured clone directory in the main checkout.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CloneDirSize {
+    pub path: String,
+    /// `None` if the directory does not exist or could not be read.
+    pub size: Option<TreeSize>,
+}
+
+/// Clones the file or directory at `source` to `dest`, which must not exist.
+/// Symlinks are recreated with the same target so relative links (e.g.
+/// `node_modules/.bin`) keep pointing inside the clone.
+///
+/// Blocking; run it on a blocking thread.
+pub fn clone_tree(source: &Path, dest: &Path, allow_hardlink: bool) -> io::Result<CloneStats> {
+    let mut cloner = Cloner {
+        try_reflink: true,
+        try_hardlink: allow_hardlink,
+        stats: CloneStats::default(),
+    };
+    cloner.clone_entry(source, dest)?;
+    Ok(cloner.stats)
+}
+
+/// Counts the regular files under `path` and their total size, without
+/// following symlinks.
+pub fn tree_size(path: &Path) -> io::Result<TreeSize> {
+    let meta = fs::symlink_metadata(path)?;
+    let mut size = TreeSize::default();
+    if meta.is_dir() {
+        for entry in fs::read_dir(path)? {
+            let child = tree_size(&entry?.path())?;
+            size.files += child.files;
+            size.bytes += child.bytes;
+        }
+    } else if meta.is_file() {
+        size.files = 1;
+        size.bytes = meta.len();
+    }
+    Ok(size)
+}
+
+/// Measures each configured directory under `root`.
+pub fn measure_clone_dirs(root: &Path, dirs: &[CloneDir]) -> Vec<CloneDirSize> {
+    dirs.iter()
+        .map(|dir| CloneDirSize {
+            path: dir.path.clone(),
+            size: tree_size(&root.join(&dir.path)).ok(),
+        })
+        .collect()
+}
+
+struct Cloner {
+    /// Cleared after the first reflink the filesystem rejects, so an
+    /// unsupported filesystem costs one failed attempt rather than one per
+    /// file.
+    try_reflink: bool,
+    try_hardlink: bool,
+    stats: CloneStats,
+}
+
+impl Cloner {
+    fn clone_entry(&mut self, source: &Path, dest: &Path) -> io::Result<()> {
+        let meta = fs::symlink_metadata(source)?;
+        let file_type = meta.file_type();
+        if file_type.is_symlink() {
+            return symlink(&fs::read_link(source)?, dest);
+        }
+        if file_type.is_dir() {
+            fs::create_dir(dest)?;
+            for entry in fs::read_dir(source)? {
+                let entry = entry?;
+                self.clone_entry(&entry.path(), &dest.join(entry.file_name()))?;
+            }
+            return fs::set_permissions(dest, meta.permissions());
+        }
+        if !file_type.is_file() {
+            // Sockets, FIFOs and devices have no content worth cloning.
+            return Ok(());
+        }
+
+        let method = self.clone_file(source, dest)?;
+        self.stats.record(method, meta.len());
+        Ok(())
+    }
+
+    fn clone_file(&mut self, source: &Path, dest: &Path) -> io::Result<CloneMethod> {
+        let method = self.clone_file_contents(source, dest)?;
+        if method != CloneMethod::Hardlink
+            && cfg!(not(any(target_os = "linux", target_os = "android")))
+        {
+            // clonefile and the macOS/Windows fs::copy keep the source's times
+            if let Err(e) = touch(dest) {
+                log::debug!("Could not update mtime of {}: {}", dest.display(), e);
+            }
+        }
+        Ok(method)
+    }
+
+    fn clone_file_contents(&mut self, source: &Path, dest: &Path) -> io::Result<CloneMethod> {
+        if self.try_reflink {
+            match reflink(source, dest) {
+                Ok(()) => return Ok(CloneMethod::Reflink),
+                Err(e) if is_reflink_unsupported(&e) => {
+                    log::debug!("Reflinks unavailable ({}), falling back", e);
+                    self.try_reflink = false;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        if self.try_hardlink {
+            match fs::hard_link(source, dest) {
+                Ok(()) => return Ok(CloneMethod::Hardlink),
+                Err(e) => {
+                    log::debug!("Hard links unavailable ({}), falling back to copy", e);
+                    self.try_hardlink = false;
+                }
+            }
+        }
+        fs::copy(source, dest)?;
+        Ok(CloneMethod::Copy)
+    }
+}
+
+/// Whether a reflink error means the filesystem (or platform) cannot clone
+/// at all, as opposed to a failure specific to this file.
+fn is_reflink_unsupported(err: &io::Error) -> bool {
+    if err.kind() == io::ErrorKind::Unsupported {
+        return true;
+    }
+    #[cfg(unix)]
+    {
+        // EOPNOTSUPP and ENOTSUP share a value on Linux but not on macOS.
+        let unsupported = [
+            libc::EOPNOTSUPP,
+            libc::ENOTSUP,
+            libc::EXDEV,
+            libc::EINVAL,
+            libc::ENOTTY,
+            libc::ENOSYS,
+        ];
+        err.raw_os_error()
+            .is_some_and(|code| unsupported.contains(&code))
+    }
+    #[cfg(not(unix))]
+    {
+        false
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn reflink(source: &Path, dest: &Path) -> io::Result<()> {
+    use std::os::unix::io::AsRawFd;
+
+    let src = fs::File::open(source)?;
+    let permissions = src.metadata()?.permissions();
+    let dst = fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(dest)?;
+    // SAFETY: both descriptors are open for the duration of the call.
+    let result = unsafe { libc::ioctl(dst.as_raw_fd(), libc::FICLONE, src.as_raw_fd()) };
+    if result == -1 {
+        let err = io::Error::last_os_error();
+        drop(dst);
+        let _ = fs::remove_file(dest);
+        return Err(err);
+    }
+    dst.set_permissions(permissions)
+}
+
+#[cfg(target_os = "macos")]
+fn reflink(source: &Path, dest: &Path) -> io::Result<()> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let src = CString::new(source.as_os_str().as_bytes())?;
+    let dst = CString::new(dest.as_os_str().as_bytes())?;
+    // SAFETY: both pointers are valid NUL-terminated strings.
+    if unsafe { libc::clonefile(src.as_ptr(), dst.as_ptr(), 0) } == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android", target_os = "macos")))]
+fn reflink(_source: &Path, _dest: &Path) -> io::Result<()> {
+    Err(io::ErrorKind::Unsupported.into())
+}
+
+/// Sets a file's modification time to now. Needs no write access to the
+/// contents, so read-only files are updated too.
+fn touch(path: &Path) -> io::Result<()> {
+    #[cfg(windows)]
+    let file = {
+        use std::os::windows::fs::OpenOptionsExt;
+        // FILE_WRITE_ATTRIBUTES
+        fs::OpenOptions::new().access_mode(0x100).open(path)?
+    };
+    #[cfg(not(windows))]
+    let file = fs::File::open(path)?;
+    file.set_modified(SystemTime::now())
+}
+
+#[cfg(unix)]
+fn symlink(target: &Path, link: &Path) -> io::Result<()> {
+    std::os::unix::fs::symlink(target, link)
+}
+
+#[cfg(windows)]
+fn symlink(target: &Path, link: &Path) -> io::Result<()> {
+    let resolved = link.parent().map(|p| p.join(target));
+    if resolved.is_some_and(|p| p.is_dir()) {
+        std::os::windows::fs::symlink_dir(target, link)
+    } else {
+        std::os::windows::fs::symlink_file(target, link)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn sample_tree(root: &Path) {
+        fs::create_dir_all(root.join("debug/deps")).unwrap();
+        fs::write(root.join("debug/deps/libfoo.rlib"), vec![7u8; 4096]).unwrap();
+        fs::write(root.join("debug/app"), "binary").unwrap();
+        fs::write(root.join("CACHEDIR.TAG"), "tag").unwrap();
+        #[cfg(unix)]
+        std::os::unix::fs::symlink("deps/libfoo.rlib", root.join("debug/link")).unwrap();
+    }
+
+    #[test]
+    fn test_clone_tree_copies_contents_and_reports_size() {
+        let dir = tempdir().unwrap();
+        let source = dir.path().join("target");
+        let dest = dir.path().join("clone");
+        sample_tree(&source);
+
+        let stats = clone_tree(&source, &dest, false).unwrap();
+
+        assert_eq!(
+            stats.size,
+            TreeSize {
+                files: 3,
+                bytes: 4096 + 6 + 3
+            }
+        );
+        assert_eq!(stats.reflinked + stats.copied, 3);
+        assert_eq!(stats.hardlinked, 0);
+        assert!(stats.method().is_some());
+        assert_eq!(
+            fs::read(dest.join("debug/deps/libfoo.rlib")).unwrap(),
+            vec![7u8; 4096]
+        );
+        assert_eq!(
+            fs::read_to_string(dest.join("debug/app")).unwrap(),
+            "binary"
+        );
+        assert_eq!(tree_size(&dest).unwrap(), stats.size);
+
+        #[cfg(unix)]
+        {
+            assert_eq!(
+                fs::read_link(dest.join("debug/link")).unwrap(),
+                Path::new("deps/libfoo.rlib")
+            );
+            assert_eq!(fs::read(dest.join("debug/link")).unwrap(), vec![7u8; 4096]);
+        }
+    }
+
+    #[test]
+    fn test_clone_is_independent_unless_hardlinked() {
+        let dir = tempdir().unwrap();
+        let source = dir.path().join("node_modules");
+        sample_tree(&source);
+
+        let copy = dir.path().join("copy");
+        let stats = clone_tree(&source, &copy, false).unwrap();
+        assert_eq!(stats.hardlinked, 0);
+        fs::write(copy.join("debug/app"), "changed").unwrap();
+        assert_eq!(
+            fs::read_to_string(source.join("debug/app")).unwrap(),
+            "binary"
+        );
+
+        let linked = dir.path().join("linked");
+        let stats = clone_tree(&source, &linked, true).unwrap();
+        assert_eq!(stats.copied, 0);
+        if stats.method() == Some(CloneMethod::Hardlink) {
+            assert_eq!(stats.hardlinked, 3);
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::MetadataExt;
+                assert_eq!(
+                    fs::metadata(linked.join("debug/app")).unwrap().ino(),
+                    fs::metadata(source.join("debug/app")).unwrap().ino()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_copies_get_fresh_mtimes() {
+        let dir = tempdir().unwrap();
+        let source = dir.path().join("target");
+        sample_tree(&source);
+        let old = SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1_000_000_000);
+        fs::File::options()
+            .write(true)
+            .open(source.join("debug/app"))
+            .unwrap()
+            .set_modified(old)
+            .unwrap();
+
+        let before = SystemTime::now() - std::time::Duration::from_secs(60);
+        let dest = dir.path().join("clone");
+        clone_tree(&source, &dest, false).unwrap();
+        let modified = fs::metadata(dest.join("debug/app")).unwrap().modified().unwrap();
+        assert!(modified > before);
+    }
+
+    #[test]
+    fn test_clone_tree_refuses_existing_destination() {
+        let dir = tempdir().unwrap();
+        let source = dir.path().join("target");
+        sample_tree(&source);
+        let dest = dir.path().join("dest");
+        fs::create_dir(&dest).unwrap();
+        assert!(clone_tree(&source, &dest, false).is_err());
+    }
+
+    #[test]
+    fn test_measure_clone_dirs() {
+        let dir = tempdir().unwrap();
+        sample_tree(&dir.path().join("target"));
+        let sizes = measure_clone_dirs(
+            dir.path(),
+            &[
+                CloneDir {
+                    path: "target".into(),
+                    allow_hardlink: false,
+                },
+                CloneDir {
+                    path: "node_modules".into(),
+                    allow_hardlink: true,
+                },
+            ],
+        );
+        assert_eq!(sizes[0].size.unwrap().files, 3);
+        assert!(sizes[1].size.is_none());
+    }
+
+    #[test]
+    fn test_stats_method_reports_slowest() {
+        let mut stats = CloneStats::default();
+        assert_eq!(stats.method(), None);
+        stats.record(CloneMethod::Reflink, 10);
+        assert_eq!(stats.method(), Some(CloneMethod::Reflink));
+        stats.record(CloneMethod::Copy, 5);
+        assert_eq!(stats.method(), Some(CloneMethod::Copy));
+        assert_eq!(
+            stats.size,
+            TreeSize {
+                files: 2,
+                bytes: 15
+            }
+        );
+    }
+}
@@ -0,0 +1,81 @@
+//! Registry of in-flight cancellable git operations.
+//!
+//! Commands that run long git operations (fetch, clone, worktree creation)
+//! register their [`GitOperation`] here for as long as they run, so the
+//! frontend can cancel them by ID through `cancel_git_operation`.
+
+use dashmap::DashMap;
+
+use crate::git::{CancelToken, GitOperation};
+
+/// Tracks the cancel tokens of running git operations by operation ID.
+#[derive(Default)]
+pub struct GitOperations {
+    running: DashMap<String, CancelToken>,
+}
+
+impl GitOperations {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers `operation` until the returned guard is dropped.
+    pub fn register(&self, operation: &GitOperation) -> OperationGuard<'_> {
+        self.running
+            .insert(operation.id().to_string(), operation.cancel_token().clone());
+        OperationGuard {
+            operations: self,
+            id: operation.id().to_string(),
+        }
+    }
+
+    /// Cancels the running operation with this ID, killing its git process.
+    /// Returns false if no such operation is running.
+    pub fn cancel(&self, operation_id: &str) -> bool {
+        match self.running.get(operation_id) {
+            Some(token) => {
+                token.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Whether an operation with this ID is running.
+    pub fn is_running(&self, operation_id: &str) -> bool {
+        self.running.contains_key(operation_id)
+    }
+}
+
+/// Removes an operation from the registry when dropped.
+pub struct OperationGuard<'a> {
+    operations: &'a GitOperations,
+    id: String,
+}
+
+impl Drop for OperationGuard<'_> {
+    fn drop(&mut self) {
+        self.operations.running.remove(&self.id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cancel_reaches_registered_operation() {
+        let operations = GitOperations::new();
+        let operation = GitOperation::new("fetch-1");
+        assert!(!operations.cancel("fetch-1"));
+
+        let guard = operations.register(&operation);
+        assert!(operations.is_running("fetch-1"));
+        assert!(operations.cancel("fetch-1"));
+        assert!(operation.cancel_token().is_cancelled());
+
+        drop(guard);
+        assert!(!operations.is_running("fetch-1"));
+        assert!(!operations.cancel("fetch-1"));
+    }
+}
@@ -0,0 +1,636 @@
+//! Integrates finished session branches into a target branch.
+//!
+//! Every integration runs in its own scratch worktree (detached `HEAD`,
+//! created next to the session worktrees) so neither the user's checkout
+//! nor the session's worktree is touched while merging or rebasing:
+//!
+//! - **Merge**: merge commit of the source into the target.
+//! - **Squash**: the source's changes as a single commit on the target.
+//! - **Rebase**: the source's commits replayed onto the target, which is
+//!   then fast-forwarded (the source branch itself is left as is).
+//!
+//! When the operation stops on conflicts, the [`Integration`] stays in the
+//! manager with structured conflict details; the files are resolved in the
+//! scratch worktree and the integration is continued or aborted. On success
+//! the target branch is advanced only if it still points where it did when
+//! the integration started, and the scratch worktree is removed.
+
+use std::path::{Path, PathBuf};
+
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+
+use super::worktree_manager::WorktreeManager;
+use crate::git::{ConflictedFile, Git, GitError, MergeOutcome};
+
+/// How a source branch is folded into the target.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IntegrationStrategy {
+    Merge,
+    Squash,
+    Rebase,
+}
+
+/// Progress of an integration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IntegrationStatus {
+    /// Stopped on conflicts that must be resolved in the scratch worktree.
+    Conflicted,
+    /// The result is ready in the scratch worktree but the target branch
+    /// could not be updated yet (e.g. its checkout has conflicting local
+    /// changes). Continuing retries the update.
+    Resolved,
+    /// The target branch points at the result.
+    Completed,
+    /// The integration was abandoned and the target left untouched.
+    Aborted,
+}
+
+/// A merge, squash or rebase of one branch into another.
+#[derive(Debug, Clone, Serialize)]
+pub struct Integration {
+    pub id: String,
+    pub repo_path: String,
+    pub source_branch: String,
+    pub target_branch: String,
+    pub strategy: IntegrationStrategy,
+    /// Worktree the operation runs in. Removed once the integration
+    /// completes or is aborted.
+    pub scratch_path: String,
+    /// Commit the target pointed at when the integration started.
+    pub target_head: String,
+    pub status: IntegrationStatus,
+    /// Unresolved files while `Conflicted`.
+    pub conflicts: Vec<ConflictedFile>,
+    /// Commit the target branch is (or will be) advanced to.
+    pub result_commit: Option<String>,
+}
+
+/// Errors from integration operations, serialized as a string to the
+/// Tauri frontend.
+#[derive(Debug, thiserror::Error)]
+pub enum IntegrationError {
+    #[error(transparent)]
+    Git(#[from] GitError),
+
+    #[error("integration not found: {0}")]
+    NotFound(String),
+
+    #[error("branch not found: {0}")]
+    BranchNotFound(String),
+
+    /// The target branch moved since the integration started; updating it
+    /// would discard the new commits.
+    #[error("branch '{branch}' changed while integrating; abort and start again")]
+    TargetMoved { branch: String },
+}
+
+impl serde::Serialize for IntegrationError {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+/// Tracks in-progress integrations by id.
+pub struct IntegrationManager {
+    integrations: DashMap<String, Integration>,
+    /// Overrides where scratch worktrees are created (defaults to the
+    /// managed worktree directory).
+    scratch_base: Option<PathBuf>,
+}
+
+impl Default for IntegrationManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl IntegrationManager {
+    pub fn new() -> Self {
+        Self {
+            integrations: DashMap::new(),
+            scratch_base: None,
+        }
+    }
+
+    /// Creates a manager that puts scratch worktrees under `base`.
+    pub fn with_scratch_base(base: PathBuf) -> Self {
+        Self {
+            integrations: DashMap::new(),
+            scratch_base: Some(base),
+        }
+    }
+
+    /// Integrates `source_branch` into `target_branch` of the repository at
+    /// `repo_path`.
+    ///
+    /// Returns the integration as `Completed` if it went through cleanly,
+    /// or `Conflicted` (and keeps tracking it) if it needs resolution.
+    pub async fn start(
+        &self,
+        worktrees: &WorktreeManager,
+        repo_path: &Path,
+        source_branch: &str,
+        target_branch: &str,
+        strategy: IntegrationStrategy,
+    ) -> Result<Integration, IntegrationError> {
+        let git = Git::new(repo_path);
+        let target_head = resolve_branch(&git, target_branch).await?;
+        let source_head = resolve_branch(&git, source_branch).await?;
+
+        let scratch_path = worktrees
+            .worktree_path_new(
+                repo_path,
+                &format!("integrate-{source_branch}"),
+                self.scratch_base.as_deref(),
+            )
+            .await;
+        if let Some(parent) = scratch_path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(|e| GitError::SpawnError {
+                    source: e,
+                    command: format!("create_dir_all {:?}", parent),
+                })?;
+        }
+        let start_at = match strategy {
+            IntegrationStrategy::Rebase => &source_head,
+            IntegrationStrategy::Merge | IntegrationStrategy::Squash => &target_head,
+        };
+        git.worktree_add_detached(&scratch_path, start_at).await?;
+
+        let mut integration = Integration {
+            id: uuid::Uuid::new_v4().to_string(),
+            repo_path: repo_path.to_string_lossy().to_string(),
+            source_branch: source_branch.to_string(),
+            target_branch: target_branch.to_string(),
+            strategy,
+            scratch_path: scratch_path.to_string_lossy().to_string(),
+            target_head,
+            status: IntegrationStatus::Conflicted,
+            conflicts: Vec::new(),
+            result_commit: None,
+        };
+
+        let scratch = Git::new(&scratch_path);
+        let outcome = match strategy {
+            IntegrationStrategy::Merge => {
+                let message = format!("Merge branch '{source_branch}' into {target_branch}");
+                scratch.merge_branch(source_branch, &message, false).await
+            }
+            IntegrationStrategy::Squash => {
+                let message = format!("Squash merge branch '{source_branch}' into {target_branch}");
+                scratch.merge_branch(source_branch, &message, true).await
+            }
+            IntegrationStrategy::Rebase => scratch.rebase_onto(target_branch).await,
+        };
+
+        match outcome {
+            Ok(MergeOutcome::Completed) => self.finish(worktrees, integration).await,
+            Ok(MergeOutcome::Conflicted(conflicts)) => {
+                integration.conflicts = conflicts;
+                self.integrations
+                    .insert(integration.id.clone(), integration.clone());
+                Ok(integration)
+            }
+            Err(e) => {
+                remove_scratch(worktrees, &integration).await;
+                Err(e.into())
+            }
+        }
+    }
+
+    /// Continues an integration after its conflicts were resolved in the
+    /// scratch worktree. Returns it as `Conflicted` again if markers remain
+    /// or a later rebase step conflicts.
+    pub async fn continue_integration(
+        &self,
+        worktrees: &WorktreeManager,
+        id: &str,
+    ) -> Result<Integration, IntegrationError> {
+        let mut integration = self
+            .get(id)
+            .ok_or_else(|| IntegrationError::NotFound(id.to_string()))?;
+
+        if integration.status == IntegrationStatus::Conflicted {
+            let scratch = Git::new(&integration.scratch_path);
+            let outcome = match integration.strategy {
+                IntegrationStrategy::Rebase => scratch.rebase_continue().await?,
+                IntegrationStrategy::Merge | IntegrationStrategy::Squash => {
+                    scratch.merge_continue().await?
+                }
+            };
+            if let MergeOutcome::Conflicted(conflicts) = outcome {
+                integration.conflicts = conflicts;
+                self.integrations
+                    .insert(id.to_string(), integration.clone());
+                return Ok(integration);
+            }
+        }
+        self.finish(worktrees, integration).await
+    }
+
+    /// Abandons an integration, leaving the target branch untouched, and
+    /// removes its scratch worktree.
+    pub async fn abort(
+        &self,
+        worktrees: &WorktreeManager,
+        id: &str,
+    ) -> Result<Integration, IntegrationError> {
+        let (_, mut integration) = self
+            .integrations
+            .remove(id)
+            .ok_or_else(|| IntegrationError::NotFound(id.to_string()))?;
+
+        if integration.status == IntegrationStatus::Conflicted {
+            let scratch = Git::new(&integration.scratch_path);
+            let result = match integration.strategy {
+                IntegrationStrategy::Rebase => scratch.rebase_abort().await,
+                IntegrationStrategy::Merge | IntegrationStrategy::Squash => {
+                    scratch.merge_abort().await
+                }
+            };
+            if let Err(e) = result {
+                log::debug!("Integration {}: abort failed (removing anyway): {}", id, e);
+            }
+        }
+        remove_scratch(worktrees, &integration).await;
+
+        integration.status = IntegrationStatus::Aborted;
+        integration.conflicts.clear();
+        Ok(integration)
+    }
+
+    /// Returns an in-progress integration.
+    pub fn get(&self, id: &str) -> Option<Integration> {
+        self.integrations.get(id).map(|i| i.clone())
+    }
+
+    /// Returns all in-progress integrations.
+    pub fn list(&self) -> Vec<Integration> {
+        self.integrations
+            .iter()
+            .map(|i| i.value().clone())
+            .collect()
+    }
+
+    /// Advances the target to the scratch worktree's `HEAD` and cleans up.
+    /// If the target cannot be updated, the integration is kept as
+    /// `Resolved` so it can be retried or aborted.
+    async fn finish(
+        &self,
+        worktrees: &WorktreeManager,
+        mut integration: Integration,
+    ) -> Result<Integration, IntegrationError> {
+        let scratch = Git::new(&integration.scratch_path);
+        let result = scratch
+            .run(&["rev-parse", "HEAD"])
+            .await?
+            .trimmed()
+            .to_string();
+        integration.status = IntegrationStatus::Resolved;
+        integration.conflicts.clear();
+        integration.result_commit = Some(result.clone());
+
+        if let Err(e) = advance_target(&integration, &result).await {
+            self.integrations
+                .insert(integration.id.clone(), integration);
+            return Err(e);
+        }
+
+        self.integrations.remove(&integration.id);
+        remove_scratch(worktrees, &integration).await;
+        integration.status = IntegrationStatus::Completed;
+        Ok(integration)
+    }
+}
+
+/// Resolves a local branch to its commit id.
+async fn resolve_branch(git: &Git, branch: &str) -> Result<String, IntegrationError> {
+    let spec = format!("refs/heads/{branch}^{{commit}}");
+    match git.run(&["rev-parse", "--verify", "--quiet", &spec]).await {
+        Ok(output) => Ok(output.trimmed().to_string()),
+        Err(GitError::CommandFailed { .. }) => {
+            Err(IntegrationError::BranchNotFound(branch.to_string()))
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Moves the target branch from `integration.target_head` to `result`.
+///
+/// If the target is checked out in a worktree (typically the user's main
+/// checkout), that worktree is fast-forwarded so its files stay in sync
+/// with the branch; git refuses if local changes would be overwritten.
+/// Otherwise the ref is updated atomically, failing if it moved.
+async fn advance_target(integration: &Integration, result: &str) -> Result<(), IntegrationError> {
+    if result == integration.target_head {
+        return Ok(());
+    }
+    let git = Git::new(&integration.repo_path);
+    let branch = &integration.target_branch;
+    let moved = || IntegrationError::TargetMoved {
+        branch: branch.clone(),
+    };
+
+    let checked_out = git
+        .worktree_list()
+        .await?
+        .into_iter()
+        .find(|wt| wt.branch.as_deref() == Some(branch.as_str()));
+    match checked_out {
+        Some(wt) => {
+            if wt.head != integration.target_head {
+                return Err(moved());
+            }
+            Git::new(PathBuf::from(&wt.path))
+                .run(&["merge", "--ff-only", result])
+                .await?;
+        }
+        None => {
+            let reference = format!("refs/heads/{branch}");
+            let message = format!("maestro: integrate {}", integration.source_branch);
+            match git
+                .run(&[
+                    "update-ref",
+                    "-m",
+                    &message,
+                    &reference,
+                    result,
+                    &integration.target_head,
+                ])
+                .await
+            {
+                Ok(_) => {}
+                Err(GitError::CommandFailed { .. }) => return Err(moved()),
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+    Ok(())
+}
+
+async fn remove_scratch(worktrees: &WorktreeManager, integration: &Integration) {
+    let repo = Path::new(&integration.repo_path);
+    if let Err(e) = worktrees
+        .remove(repo, Path::new(&integration.scratch_path))
+        .await
+    {
+        log::warn!(
+            "Integration {}: failed to remove scratch worktree {}: {}",
+            integration.id,
+            integration.scratch_path,
+            e
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    async fn git(dir: &Path, args: &[&str]) -> String {
+        Git::new(dir).run(args).await.unwrap().trimmed().to_string()
+    }
+
+    /// Manager whose scratch worktrees live inside the test's temp dir.
+    fn manager(dir: &tempfile::TempDir) -> IntegrationManager {
+        IntegrationManager::with_scratch_base(dir.path().join("worktrees"))
+    }
+
+    /// Repo on `main` with a `feature` branch; both edit line 2 of f.txt
+    /// when `conflicting`, otherwise feature adds a separate file.
+    async fn create_repo(conflicting: bool) -> (tempfile::TempDir, PathBuf) {
+        let dir = tempdir().unwrap();
+        let repo = dir.path().join("repo");
+        std::fs::create_dir(&repo).unwrap();
+        git(&repo, &["init", "-b", "main"]).await;
+        git(&repo, &["config", "user.email", "test@test.com"]).await;
+        git(&repo, &["config", "user.name", "Test"]).await;
+        std::fs::write(repo.join("f.txt"), "a\nb\nc\n").unwrap();
+        git(&repo, &["add", "."]).await;
+        git(&repo, &["commit", "-m", "initial"]).await;
+
+        git(&repo, &["checkout", "-b", "feature"]).await;
+        if conflicting {
+            std::fs::write(repo.join("f.txt"), "a\nFEATURE\nc\n").unwrap();
+        } else {
+            std::fs::write(repo.join("g.txt"), "g\n").unwrap();
+        }
+        git(&repo, &["add", "."]).await;
+        git(&repo, &["commit", "-m", "feature work"]).await;
+
+        git(&repo, &["checkout", "main"]).await;
+        std::fs::write(repo.join("f.txt"), "a\nMAIN\nc\n").unwrap();
+        git(&repo, &["commit", "-am", "main work"]).await;
+        (dir, repo)
+    }
+
+    fn scratch_exists(integration: &Integration) -> bool {
+        Path::new(&integration.scratch_path).exists()
+    }
+
+    #[tokio::test]
+    async fn test_clean_merge_fast_forwards_checked_out_target() {
+        let (dir, repo) = create_repo(false).await;
+        let manager = manager(&dir);
+        let worktrees = WorktreeManager::new();
+
+        let result = manager
+            .start(
+                &worktrees,
+                &repo,
+                "feature",
+                "main",
+                IntegrationStrategy::Merge,
+            )
+            .await
+            .unwrap();
+        assert_eq!(result.status, IntegrationStatus::Completed);
+        assert!(!scratch_exists(&result));
+        assert!(manager.list().is_empty());
+
+        // main is checked out in the repo, so its files follow the branch
+        assert_eq!(
+            git(&repo, &["rev-parse", "HEAD"]).await,
+            result.result_commit.unwrap()
+        );
+        assert!(repo.join("g.txt").exists());
+        assert_eq!(
+            git(&repo, &["log", "-1", "--format=%s"]).await,
+            "Merge branch 'feature' into main"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rebase_updates_unchecked_out_target() {
+        let (dir, repo) = create_repo(false).await;
+        git(&repo, &["branch", "release", "main"]).await;
+        let manager = manager(&dir);
+        let worktrees = WorktreeManager::new();
+
+        let result = manager
+            .start(
+                &worktrees,
+                &repo,
+                "feature",
+                "release",
+                IntegrationStrategy::Rebase,
+            )
+            .await
+            .unwrap();
+        assert_eq!(result.status, IntegrationStatus::Completed);
+
+        // Linear history on release; feature itself is unchanged
+        assert_eq!(
+            git(&repo, &["log", "--format=%s", "main..release"]).await,
+            "feature work"
+        );
+        assert_eq!(
+            git(&repo, &["rev-list", "--count", "--merges", "release"]).await,
+            "0"
+        );
+        assert_ne!(
+            git(&repo, &["rev-parse", "feature"]).await,
+            git(&repo, &["rev-parse", "release"]).await
+        );
+    }
+
+    #[tokio::test]
+    async fn test_conflict_then_continue() {
+        let (dir, repo) = create_repo(true).await;
+        let manager = manager(&dir);
+        let worktrees = WorktreeManager::new();
+
+        let started = manager
+            .start(
+                &worktrees,
+                &repo,
+                "feature",
+                "main",
+                IntegrationStrategy::Squash,
+            )
+            .await
+            .unwrap();
+        assert_eq!(started.status, IntegrationStatus::Conflicted);
+        assert_eq!(started.conflicts.len(), 1);
+        assert_eq!(started.conflicts[0].path, "f.txt");
+        assert_eq!(started.conflicts[0].hunks[0].theirs, vec!["FEATURE"]);
+        // The user's checkout is untouched while conflicted
+        assert_eq!(
+            std::fs::read_to_string(repo.join("f.txt")).unwrap(),
+            "a\nMAIN\nc\n"
+        );
+
+        // Unresolved: still conflicted
+        let again = manager
+            .continue_integration(&worktrees, &started.id)
+            .await
+            .unwrap();
+        assert_eq!(again.status, IntegrationStatus::Conflicted);
+
+        std::fs::write(
+            Path::new(&started.scratch_path).join("f.txt"),
+            "a\nBOTH\nc\n",
+        )
+        .unwrap();
+        let done = manager
+            .continue_integration(&worktrees, &started.id)
+            .await
+            .unwrap();
+        assert_eq!(done.status, IntegrationStatus::Completed);
+        assert!(!scratch_exists(&done));
+        assert_eq!(
+            std::fs::read_to_string(repo.join("f.txt")).unwrap(),
+            "a\nBOTH\nc\n"
+        );
+        assert!(manager.get(&started.id).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_abort_leaves_target_untouched() {
+        let (dir, repo) = create_repo(true).await;
+        let head = git(&repo, &["rev-parse", "main"]).await;
+        let manager = manager(&dir);
+        let worktrees = WorktreeManager::new();
+
+        let started = manager
+            .start(
+                &worktrees,
+                &repo,
+                "feature",
+                "main",
+                IntegrationStrategy::Rebase,
+            )
+            .await
+            .unwrap();
+        assert_eq!(started.status, IntegrationStatus::Conflicted);
+
+        let aborted = manager.abort(&worktrees, &started.id).await.unwrap();
+        assert_eq!(aborted.status, IntegrationStatus::Aborted);
+        assert!(!scratch_exists(&aborted));
+        assert_eq!(git(&repo, &["rev-parse", "main"]).await, head);
+        assert!(matches!(
+            manager.abort(&worktrees, &started.id).await,
+            Err(IntegrationError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_target_moved_is_refused() {
+        let (dir, repo) = create_repo(true).await;
+        git(&repo, &["branch", "release", "main"]).await;
+        let manager = manager(&dir);
+        let worktrees = WorktreeManager::new();
+
+        let started = manager
+            .start(
+                &worktrees,
+                &repo,
+                "feature",
+                "release",
+                IntegrationStrategy::Merge,
+            )
+            .await
+            .unwrap();
+        // Someone else advances release meanwhile
+        git(&repo, &["branch", "-f", "release", "feature"]).await;
+
+        std::fs::write(
+            Path::new(&started.scratch_path).join("f.txt"),
+            "a\nBOTH\nc\n",
+        )
+        .unwrap();
+        let err = manager
+            .continue_integration(&worktrees, &started.id)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, IntegrationError::TargetMoved { .. }));
+        assert_eq!(
+            manager.get(&started.id).unwrap().status,
+            IntegrationStatus::Resolved
+        );
+
+        manager.abort(&worktrees, &started.id).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_unknown_branch() {
+        let (dir, repo) = create_repo(false).await;
+        let manager = manager(&dir);
+        let err = manager
+            .start(
+                &WorktreeManager::new(),
+                &repo,
+                "nope",
+                "main",
+                IntegrationStrategy::Merge,
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err, IntegrationError::BranchNotFound(b) if b == "nope"));
+    }
+}
@@ -11,17 +11,20 @@ use dashmap::DashMap;
 use directories::BaseDirs;
 use std::path::{Path, PathBuf};
 use std::sync::RwLock;
-use tokio::process::Command;
+use std::time::Duration;
 
 use super::marketplace_error::{MarketplaceError, MarketplaceResult};
 use super::marketplace_models::*;
-use super::windows_process::TokioCommandExt;
+use crate::git::{Git, GitError, GitOperation};
 
 /// Official Anthropic Claude Code marketplace.
 const OFFICIAL_MARKETPLACE_NAME: &str = "Claude Code Official";
 const OFFICIAL_MARKETPLACE_URL: &str = "https://github.com/anthropics/claude-code";
 const OFFICIAL_MARKETPLACE_ID: &str = "official-anthropic-claude-code";
 
+/// Upper bound on a plugin clone, including the sparse checkout's blob fetch.
+const CLONE_TIMEOUT: Duration = Duration::from_secs(600);
+
 /// Session key for per-session configuration: (project_path, session_id).
 type SessionKey = (String, u32);
 
@@ -306,11 +309,14 @@ impl MarketplaceManager {
     /// Clones a repository using git.
     ///
     /// If `source_path` is provided, uses sparse checkout to clone only the
-    /// specified subdirectory (for monorepo plugins).
+    /// specified subdirectory (for monorepo plugins). When `operation` is
+    /// given, clone progress is reported through it and the clone can be
+    /// cancelled.
     async fn clone_repository(
         repo_url: &str,
         target_dir: &Path,
         source_path: Option<&str>,
+        operation: Option<GitOperation>,
     ) -> MarketplaceResult<()> {
         // Ensure parent directory exists
         if let Some(parent) = target_dir.parent() {
@@ -319,36 +325,60 @@ impl MarketplaceManager {
 
         if let Some(subpath) = source_path {
             // Sparse checkout for subdirectory within a monorepo
-            Self::clone_sparse(repo_url, target_dir, subpath).await
+            Self::clone_sparse(repo_url, target_dir, subpath, operation).await
         } else {
             // Simple shallow clone for standalone repos
-            Self::clone_shallow(repo_url, target_dir).await
+            Self::clone_shallow(repo_url, target_dir, operation).await
         }
     }
 
-    /// Performs a shallow clone of the entire repository.
-    async fn clone_shallow(repo_url: &str, target_dir: &Path) -> MarketplaceResult<()> {
-        let output = Command::new("git")
-            .args(["clone", "--depth", "1", repo_url])
-            .arg(target_dir)
-            .hide_console_window()
-            .output()
-            .await
-            .map_err(|e| MarketplaceError::CloneError(format!("Failed to run git: {}", e)))?;
-
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            return Err(MarketplaceError::CloneError(stderr.to_string()));
+    /// Returns a git runner for `dir`, attached to `operation` if given.
+    fn git_in(dir: &Path, operation: &Option<GitOperation>) -> Git {
+        let git = Git::new(dir);
+        match operation {
+            Some(operation) => git.with_operation(operation.clone()),
+            None => git,
         }
+    }
 
-        Ok(())
+    /// Performs a shallow clone of the entire repository.
+    async fn clone_shallow(
+        repo_url: &str,
+        target_dir: &Path,
+        operation: Option<GitOperation>,
+    ) -> MarketplaceResult<()> {
+        let parent = target_dir.parent().unwrap_or(Path::new("."));
+        let target = target_dir.to_string_lossy();
+        let result = Self::git_in(parent, &operation)
+            .run_streaming(
+                &["clone", "--progress", "--depth", "1", repo_url, &target],
+                CLONE_TIMEOUT,
+                |_| {},
+            )
+            .await;
+
+        match result {
+            Ok(_) => Ok(()),
+            Err(e) => {
+                // A killed clone leaves a partial checkout behind.
+                if matches!(e, GitError::Cancelled { .. }) {
+                    let _ = tokio::fs::remove_dir_all(target_dir).await;
+                }
+                Err(MarketplaceError::CloneError(Self::git_error_message(e)))
+            }
+        }
     }
 
     /// Performs a sparse checkout to clone only a specific subdirectory.
     ///
     /// This is used for plugins that are subdirectories within a larger monorepo
     /// (e.g., anthropics/claude-code/plugins/frontend-design).
-    async fn clone_sparse(repo_url: &str, target_dir: &Path, subpath: &str) -> MarketplaceResult<()> {
+    async fn clone_sparse(
+        repo_url: &str,
+        target_dir: &Path,
+        subpath: &str,
+        operation: Option<GitOperation>,
+    ) -> MarketplaceResult<()> {
         // Create a temporary directory for the sparse checkout
         let temp_dir = target_dir.with_file_name(format!(
             ".{}-sparse-temp",
@@ -360,67 +390,10 @@ impl MarketplaceManager {
             tokio::fs::remove_dir_all(&temp_dir).await?;
         }
 
-        // Step 1: Clone with no checkout and blob filter for efficiency
-        let output = Command::new("git")
-            .args([
-                "clone",
-                "--filter=blob:none",
-                "--no-checkout",
-                "--depth",
-                "1",
-                repo_url,
-            ])
-            .arg(&temp_dir)
-            .hide_console_window()
-            .output()
-            .await
-            .map_err(|e| MarketplaceError::CloneError(format!("Failed to run git clone: {}", e)))?;
-
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            let _ = tokio::fs::remove_dir_all(&temp_dir).await;
-            return Err(MarketplaceError::CloneError(format!(
-                "git clone failed: {}",
-                stderr
-            )));
-        }
-
-        // Step 2: Set up sparse checkout for the specific subdirectory
-        let output = Command::new("git")
-            .args(["sparse-checkout", "set", "--no-cone", subpath])
-            .current_dir(&temp_dir)
-            .hide_console_window()
-            .output()
-            .await
-            .map_err(|e| {
-                MarketplaceError::CloneError(format!("Failed to run git sparse-checkout: {}", e))
-            })?;
-
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
+        let result = Self::sparse_checkout(repo_url, &temp_dir, subpath, &operation).await;
+        if let Err(e) = result {
             let _ = tokio::fs::remove_dir_all(&temp_dir).await;
-            return Err(MarketplaceError::CloneError(format!(
-                "git sparse-checkout failed: {}",
-                stderr
-            )));
-        }
-
-        // Step 3: Checkout the files
-        let output = Command::new("git")
-            .args(["checkout"])
-            .current_dir(&temp_dir)
-            .hide_console_window()
-            .output()
-            .await
-            .map_err(|e| MarketplaceError::CloneError(format!("Failed to run git checkout: {}", e)))?;
-
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            let _ = tokio::fs::remove_dir_all(&temp_dir).await;
-            return Err(MarketplaceError::CloneError(format!(
-                "git checkout failed: {}",
-                stderr
-            )));
+            return Err(e);
         }
 
         // Step 4: Move the subdirectory contents to the target directory
@@ -444,6 +417,73 @@ impl MarketplaceManager {
         Ok(())
     }
 
+    /// Clones `repo_url` into `temp_dir` with only `subpath` checked out.
+    async fn sparse_checkout(
+        repo_url: &str,
+        temp_dir: &Path,
+        subpath: &str,
+        operation: &Option<GitOperation>,
+    ) -> MarketplaceResult<()> {
+        let parent = temp_dir.parent().unwrap_or(Path::new("."));
+        let temp = temp_dir.to_string_lossy();
+
+        // Step 1: Clone with no checkout and blob filter for efficiency
+        Self::git_in(parent, operation)
+            .run_streaming(
+                &[
+                    "clone",
+                    "--progress",
+                    "--filter=blob:none",
+                    "--no-checkout",
+                    "--depth",
+                    "1",
+                    repo_url,
+                    &temp,
+                ],
+                CLONE_TIMEOUT,
+                |_| {},
+            )
+            .await
+            .map_err(|e| {
+                MarketplaceError::CloneError(format!(
+                    "git clone failed: {}",
+                    Self::git_error_message(e)
+                ))
+            })?;
+
+        let git = Self::git_in(temp_dir, operation);
+
+        // Step 2: Set up sparse checkout for the specific subdirectory
+        git.run(&["sparse-checkout", "set", "--no-cone", subpath])
+            .await
+            .map_err(|e| {
+                MarketplaceError::CloneError(format!(
+                    "git sparse-checkout failed: {}",
+                    Self::git_error_message(e)
+                ))
+            })?;
+
+        // Step 3: Checkout the files (fetches the filtered blobs)
+        git.run_streaming(&["checkout", "--progress"], CLONE_TIMEOUT, |_| {})
+            .await
+            .map_err(|e| {
+                MarketplaceError::CloneError(format!(
+                    "git checkout failed: {}",
+                    Self::git_error_message(e)
+                ))
+            })?;
+
+        Ok(())
+    }
+
+    /// Reports git's own error output where there is one.
+    fn git_error_message(error: GitError) -> String {
+        match error {
+            GitError::CommandFailed { stderr, .. } => stderr,
+            other => other.to_string(),
+        }
+    }
+
     /// Discovers plugin components from an installed directory.
     fn discover_plugin_components(plugin_dir: &Path) -> (Vec<String>, Vec<String>, Vec<String>, Vec<String>, Vec<String>) {
         let mut skills = Vec::new();
@@ -530,11 +570,15 @@ impl MarketplaceManager {
     }
 
     /// Installs a plugin from a marketplace.
+    ///
+    /// The clone runs under `operation` when given, so it reports progress
+    /// and can be cancelled.
     pub async fn install_plugin(
         &self,
         marketplace_plugin_id: &str,
         scope: InstallScope,
         project_path: Option<&str>,
+        operation: Option<GitOperation>,
     ) -> MarketplaceResult<InstalledPlugin> {
         // Find the plugin in available plugins
         let plugin = self.get_available_plugins()
@@ -567,7 +611,8 @@ impl MarketplaceManager {
         let plugin_dir = install_base.join(&plugin_dir_name);
 
         // Clone the repository (with sparse checkout for monorepo plugins)
-        Self::clone_repository(repo_url, &plugin_dir, plugin.source_path.as_deref()).await?;
+        Self::clone_repository(repo_url, &plugin_dir, plugin.source_path.as_deref(), operation)
+            .await?;
 
         // Create plugin manifest directory
         let manifest_dir = plugin_dir.join(".claude-plugin");
@@ -0,0 +1,821 @@
+//! Cross-session mailbox for agent-to-agent coordination.
+//!
+//! The [`MessageBus`] keeps a FIFO queue per `(session_id, topic)` pair.
+//! Messages are either sent directly to one session or published to a topic,
+//! in which case every subscribed session except the sender receives a copy.
+//! The last message published on each topic is retained and delivered to
+//! sessions that subscribe later, so a consumer that starts after the producer
+//! still sees the current value (e.g. an API contract).
+//!
+//! Every delivery is announced as a [`ClaudeEvent::MessageReceived`] on the
+//! [`EventBus`]. Session IDs restart at 1 on every launch, so queues and
+//! subscriptions are persisted under a mailbox address (project path plus
+//! branch) instead: [`MessageBus::attach_session`] binds a session to its
+//! address, and a session attaching to the address after a restart gets the
+//! undelivered messages and subscriptions back. Retained messages and the
+//! mailboxes of attached sessions are written to a JSON file whenever they
+//! change; sessions without an address keep their mailbox in memory only.
+
+use std::collections::{BTreeSet, HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+
+use super::claude_event::ClaudeEvent;
+use super::event_bus::EventBus;
+
+/// Maximum number of undelivered messages kept per `(session, topic)` queue.
+/// When exceeded, the oldest message is dropped.
+const MAX_QUEUE_LEN: usize = 500;
+
+/// Maximum accepted message body size in bytes.
+const MAX_BODY_BYTES: usize = 256 * 1024;
+
+/// Errors returned by mailbox operations, serialized as a plain string to the
+/// frontend and to MCP clients.
+#[derive(Debug, thiserror::Error)]
+pub enum MessageBusError {
+    /// Topic names must be non-empty and free of whitespace.
+    #[error("invalid topic name: '{0}'")]
+    InvalidTopic(String),
+
+    /// The message body exceeds [`MAX_BODY_BYTES`].
+    #[error("message body too large ({size} bytes, max {max})")]
+    BodyTooLarge { size: usize, max: usize },
+
+    /// A session tried to send a direct message to itself.
+    #[error("session {0} cannot send a message to itself")]
+    SelfAddressed(u32),
+}
+
+impl serde::Serialize for MessageBusError {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+/// A single message sitting in (or delivered from) a session's mailbox.
+///
+/// `from_session` is `None` when the message was sent by a human through the
+/// UI rather than by another agent session.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct BusMessage {
+    pub id: String,
+    pub topic: String,
+    pub from_session: Option<u32>,
+    pub body: String,
+    pub timestamp: String,
+}
+
+/// Result of a topic publish: the stored message and the sessions it was
+/// queued for.
+#[derive(Debug, Clone, Serialize)]
+pub struct PublishReceipt {
+    pub message: BusMessage,
+    pub delivered_to: Vec<u32>,
+}
+
+/// Number of pending messages for one topic in a session's mailbox.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct MailboxSummary {
+    pub topic: String,
+    pub pending: usize,
+}
+
+/// Subscribers and retained message for a single topic.
+#[derive(Debug, Clone, Serialize)]
+pub struct TopicInfo {
+    pub topic: String,
+    pub subscribers: Vec<u32>,
+    pub retained: Option<BusMessage>,
+}
+
+/// Subscriptions and undelivered messages of one mailbox address.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct StoredMailbox {
+    subscriptions: BTreeSet<String>,
+    /// topic -> queued messages (oldest first)
+    queues: HashMap<String, VecDeque<BusMessage>>,
+}
+
+/// What the store file holds.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct StoredBus {
+    /// topic -> last published message
+    retained: HashMap<String, BusMessage>,
+    /// mailbox address -> mailbox
+    mailboxes: HashMap<String, StoredMailbox>,
+}
+
+/// Mailbox state.
+#[derive(Debug, Default)]
+struct BusState {
+    /// session_id -> topic -> queued messages (oldest first)
+    queues: HashMap<u32, HashMap<String, VecDeque<BusMessage>>>,
+    /// topic -> subscribed session IDs
+    subscriptions: HashMap<String, BTreeSet<u32>>,
+    /// topic -> last published message
+    retained: HashMap<String, BusMessage>,
+    /// session_id -> mailbox address, for sessions whose mailbox persists
+    addresses: HashMap<u32, String>,
+    /// Persisted mailboxes no session has attached to since startup
+    parked: HashMap<String, StoredMailbox>,
+    /// Bumped on every snapshot so a stale snapshot never overwrites a newer one
+    generation: u64,
+}
+
+impl BusState {
+    /// Whether changes to the session's mailbox need to be persisted.
+    fn is_durable(&self, session_id: u32) -> bool {
+        self.addresses.contains_key(&session_id)
+    }
+
+    fn stored(&self) -> StoredBus {
+        let mut mailboxes = self.parked.clone();
+        for (&session_id, address) in &self.addresses {
+            let subscriptions = self
+                .subscriptions
+                .iter()
+                .filter(|(_, subs)| subs.contains(&session_id))
+                .map(|(topic, _)| topic.clone())
+                .collect();
+            let queues = self.queues.get(&session_id).cloned().unwrap_or_default();
+            mailboxes.insert(
+                address.clone(),
+                StoredMailbox {
+                    subscriptions,
+                    queues,
+                },
+            );
+        }
+        StoredBus {
+            retained: self.retained.clone(),
+            mailboxes,
+        }
+    }
+
+    /// Appends `message` to the recipient's queue, dropping the oldest entry
+    /// if the queue is full.
+    fn enqueue(&mut self, recipient: u32, message: BusMessage) {
+        let queue = self
+            .queues
+            .entry(recipient)
+            .or_default()
+            .entry(message.topic.clone())
+            .or_default();
+        if queue.len() >= MAX_QUEUE_LEN {
+            if let Some(dropped) = queue.pop_front() {
+                log::warn!(
+                    "MessageBus: queue for session {recipient} topic '{}' full, dropped message {}",
+                    dropped.topic,
+                    dropped.id
+                );
+            }
+        }
+        queue.push_back(message);
+    }
+}
+
+/// Durable mailbox shared by all sessions.
+///
+/// Thread-safe: all state lives behind a single `std::sync::Mutex`, which is
+/// never held across an await point or while calling into the [`EventBus`].
+pub struct MessageBus {
+    state: Mutex<BusState>,
+    store_path: Option<PathBuf>,
+    /// Generation of the last snapshot written; serializes file writes,
+    /// which happen outside the state lock.
+    written: Mutex<u64>,
+    event_bus: Option<Arc<EventBus>>,
+}
+
+/// Serialized state waiting to be written, see [`MessageBus::write`].
+type Snapshot = Option<(u64, String)>;
+
+impl MessageBus {
+    /// Creates a mailbox that persists to `store_path` (if given) and
+    /// announces deliveries on `event_bus` (if given). Retained messages and
+    /// mailboxes are loaded from `store_path`; an unreadable or corrupt file
+    /// is logged and ignored.
+    pub fn new(store_path: Option<PathBuf>, event_bus: Option<Arc<EventBus>>) -> Self {
+        let stored = store_path
+            .as_deref()
+            .and_then(load_state)
+            .unwrap_or_default();
+        Self {
+            state: Mutex::new(BusState {
+                retained: stored.retained,
+                parked: stored.mailboxes,
+                ..BusState::default()
+            }),
+            store_path,
+            written: Mutex::new(0),
+            event_bus,
+        }
+    }
+
+    /// Binds a session to the mailbox address of `project_path` and `branch`
+    /// so its mailbox survives a restart, and hands it the undelivered
+    /// messages and subscriptions stored there by a previous session.
+    ///
+    /// An address belongs to one live session at a time; a second session on
+    /// the same project and branch keeps its mailbox in memory only.
+    pub fn attach_session(&self, session_id: u32, project_path: &str, branch: Option<&str>) {
+        let address = mailbox_address(project_path, branch);
+        let snapshot = {
+            let mut state = self.lock();
+            if state.addresses.get(&session_id) == Some(&address) {
+                return;
+            }
+            if state.addresses.values().any(|a| *a == address) {
+                log::debug!(
+                    "MessageBus: mailbox {address} already attached, session {session_id} stays in memory"
+                );
+                return;
+            }
+
+            let mailbox = state.parked.remove(&address).unwrap_or_default();
+            for topic in mailbox.subscriptions {
+                state.subscriptions.entry(topic).or_default().insert(session_id);
+            }
+            for (topic, stored) in mailbox.queues {
+                let queue = state
+                    .queues
+                    .entry(session_id)
+                    .or_default()
+                    .entry(topic)
+                    .or_default();
+                // Stored messages predate anything queued since the session started
+                for message in stored.into_iter().rev() {
+                    queue.push_front(message);
+                }
+                while queue.len() > MAX_QUEUE_LEN {
+                    queue.pop_front();
+                }
+            }
+            state.addresses.insert(session_id, address);
+            self.snapshot(&mut state)
+        };
+        self.write(snapshot);
+    }
+
+    /// Default location of the persisted mailbox file inside the app data dir.
+    pub fn default_store_path() -> PathBuf {
+        directories::ProjectDirs::from("com", "maestro", "maestro")
+            .map(|p| p.data_dir().to_path_buf())
+            .unwrap_or_else(std::env::temp_dir)
+            .join("message_bus.json")
+    }
+
+    /// Subscribes a session to a topic. If the topic has a retained message it
+    /// is queued for the session immediately. Returns `true` if the session
+    /// was not already subscribed.
+    pub fn subscribe(&self, session_id: u32, topic: &str) -> Result<bool, MessageBusError> {
+        validate_topic(topic)?;
+        let (added, delivered, snapshot) = {
+            let mut state = self.lock();
+            let added = state
+                .subscriptions
+                .entry(topic.to_string())
+                .or_default()
+                .insert(session_id);
+            let retained = state
+                .retained
+                .get(topic)
+                .filter(|m| added && m.from_session != Some(session_id))
+                .cloned();
+            if let Some(ref message) = retained {
+                state.enqueue(session_id, message.clone());
+            }
+            let snapshot = if added && state.is_durable(session_id) {
+                self.snapshot(&mut state)
+            } else {
+                None
+            };
+            (added, retained, snapshot)
+        };
+        self.write(snapshot);
+        if let Some(message) = delivered {
+            self.announce(session_id, &message);
+        }
+        Ok(added)
+    }
+
+    /// Removes a session's subscription. Already-queued messages are kept.
+    pub fn unsubscribe(&self, session_id: u32, topic: &str) -> bool {
+        let mut state = self.lock();
+        let removed = state
+            .subscriptions
+            .get_mut(topic)
+            .map(|subs| subs.remove(&session_id))
+            .unwrap_or(false);
+        if state.subscriptions.get(topic).is_some_and(|s| s.is_empty()) {
+            state.subscriptions.remove(topic);
+        }
+        let snapshot = if removed && state.is_durable(session_id) {
+            self.snapshot(&mut state)
+        } else {
+            None
+        };
+        drop(state);
+        self.write(snapshot);
+        removed
+    }
+
+    /// Publishes a message to every subscriber of `topic` except the sender,
+    /// and retains it as the topic's latest value.
+    pub fn publish(
+        &self,
+        from_session: Option<u32>,
+        topic: &str,
+        body: &str,
+    ) -> Result<PublishReceipt, MessageBusError> {
+        validate_topic(topic)?;
+        validate_body(body)?;
+        let message = new_message(from_session, topic, body);
+
+        let (delivered_to, snapshot) = {
+            let mut state = self.lock();
+            let recipients: Vec<u32> = state
+                .subscriptions
+                .get(topic)
+                .map(|subs| {
+                    subs.iter()
+                        .copied()
+                        .filter(|id| Some(*id) != from_session)
+                        .collect()
+                })
+                .unwrap_or_default();
+            for &recipient in &recipients {
+                state.enqueue(recipient, message.clone());
+            }
+            state.retained.insert(topic.to_string(), message.clone());
+            (recipients, self.snapshot(&mut state))
+        };
+        self.write(snapshot);
+
+        for &recipient in &delivered_to {
+            self.announce(recipient, &message);
+        }
+
+        Ok(PublishReceipt {
+            message,
+            delivered_to,
+        })
+    }
+
+    /// Queues a message for a single session, regardless of subscriptions.
+    pub fn send(
+        &self,
+        from_session: Option<u32>,
+        to_session: u32,
+        topic: &str,
+        body: &str,
+    ) -> Result<BusMessage, MessageBusError> {
+        validate_topic(topic)?;
+        validate_body(body)?;
+        if from_session == Some(to_session) {
+            return Err(MessageBusError::SelfAddressed(to_session));
+        }
+        let message = new_message(from_session, topic, body);
+        let snapshot = {
+            let mut state = self.lock();
+            state.enqueue(to_session, message.clone());
+            if state.is_durable(to_session) {
+                self.snapshot(&mut state)
+            } else {
+                None
+            }
+        };
+        self.write(snapshot);
+        self.announce(to_session, &message);
+        Ok(message)
+    }
+
+    /// Removes and returns up to `max` of a session's oldest messages,
+    /// optionally restricted to one topic. Messages are ordered by timestamp
+    /// across topics.
+    pub fn receive(&self, session_id: u32, topic: Option<&str>, max: usize) -> Vec<BusMessage> {
+        let mut state = self.lock();
+        let Some(mailbox) = state.queues.get_mut(&session_id) else {
+            return Vec::new();
+        };
+
+        let mut taken = Vec::new();
+        while taken.len() < max {
+            // Pick the queue whose head is oldest among the eligible topics.
+            let next_topic = mailbox
+                .iter()
+                .filter(|(t, q)| !q.is_empty() && topic.is_none_or(|want| want == t.as_str()))
+                .min_by(|(_, a), (_, b)| a[0].timestamp.cmp(&b[0].timestamp))
+                .map(|(t, _)| t.clone());
+            let Some(next_topic) = next_topic else { break };
+            if let Some(message) = mailbox.get_mut(&next_topic).and_then(|q| q.pop_front()) {
+                taken.push(message);
+            }
+        }
+
+        mailbox.retain(|_, q| !q.is_empty());
+        if mailbox.is_empty() {
+            state.queues.remove(&session_id);
+        }
+        let snapshot = if !taken.is_empty() && state.is_durable(session_id) {
+            self.snapshot(&mut state)
+        } else {
+            None
+        };
+        drop(state);
+        self.write(snapshot);
+        taken
+    }
+
+    /// Returns a session's queued messages without consuming them.
+    pub fn peek(&self, session_id: u32, topic: Option<&str>) -> Vec<BusMessage> {
+        let state = self.lock();
+        let mut messages: Vec<BusMessage> = state
+            .queues
+            .get(&session_id)
+            .map(|mailbox| {
+                mailbox
+                    .iter()
+                    .filter(|(t, _)| topic.is_none_or(|want| want == t.as_str()))
+                    .flat_map(|(_, q)| q.iter().cloned())
+                    .collect()
+            })
+            .unwrap_or_default();
+        messages.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
+        messages
+    }
+
+    /// Returns per-topic pending counts for a session, sorted by topic name.
+    pub fn pending(&self, session_id: u32) -> Vec<MailboxSummary> {
+        let state = self.lock();
+        let mut summary: Vec<MailboxSummary> = state
+            .queues
+            .get(&session_id)
+            .map(|mailbox| {
+                mailbox
+                    .iter()
+                    .map(|(topic, q)| MailboxSummary {
+                        topic: topic.clone(),
+                        pending: q.len(),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        summary.sort_by(|a, b| a.topic.cmp(&b.topic));
+        summary
+    }
+
+    /// Lists every known topic (subscribed or retained), sorted by name.
+    pub fn topics(&self) -> Vec<TopicInfo> {
+        let state = self.lock();
+        let names: BTreeSet<&String> = state
+            .subscriptions
+            .keys()
+            .chain(state.retained.keys())
+            .collect();
+        names
+            .into_iter()
+            .map(|topic| TopicInfo {
+                topic: topic.clone(),
+                subscribers: state
+                    .subscriptions
+                    .get(topic)
+                    .map(|s| s.iter().copied().collect())
+                    .unwrap_or_default(),
+                retained: state.retained.get(topic).cloned(),
+            })
+            .collect()
+    }
+
+    /// Drops a session's mailbox and subscriptions, including the persisted
+    /// copy at its address. Retained topic messages published by the session
+    /// are kept so later consumers still see them.
+    pub fn remove_session(&self, session_id: u32) {
+        let mut state = self.lock();
+        state.queues.remove(&session_id);
+        for subs in state.subscriptions.values_mut() {
+            subs.remove(&session_id);
+        }
+        state.subscriptions.retain(|_, subs| !subs.is_empty());
+        let snapshot = if state.addresses.remove(&session_id).is_some() {
+            self.snapshot(&mut state)
+        } else {
+            None
+        };
+        drop(state);
+        self.write(snapshot);
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BusState> {
+        self.state.lock().expect("message bus lock poisoned")
+    }
+
+    /// Serializes the persisted parts of the state, under the state lock.
+    fn snapshot(&self, state: &mut BusState) -> Snapshot {
+        self.store_path.as_ref()?;
+        state.generation += 1;
+        match serde_json::to_string(&state.stored()) {
+            Ok(content) => Some((state.generation, content)),
+            Err(e) => {
+                log::warn!("MessageBus: failed to serialize state: {e}");
+                None
+            }
+        }
+    }
+
+    /// Writes a snapshot to disk unless a newer one was already written.
+    /// Called after releasing the state lock. Failures are logged, not
+    /// propagated: a delivery that could not be persisted is still valid in
+    /// memory.
+    fn write(&self, snapshot: Snapshot) {
+        let (Some((generation, content)), Some(path)) = (snapshot, self.store_path.as_ref()) else {
+            return;
+        };
+        let mut written = self.written.lock().expect("message bus store lock poisoned");
+        if *written >= generation {
+            return;
+        }
+        match save_state(path, &content) {
+            Ok(()) => *written = generation,
+            Err(e) => log::warn!("MessageBus: failed to persist to {}: {e}", path.display()),
+        }
+    }
+
+    fn announce(&self, recipient: u32, message: &BusMessage) {
+        if let Some(ref bus) = self.event_bus {
+            bus.emit(ClaudeEvent::MessageReceived {
+                session_id: recipient,
+                message_id: message.id.clone(),
+                topic: message.topic.clone(),
+                from_session: message.from_session,
+                body: message.body.clone(),
+                timestamp: message.timestamp.clone(),
+            });
+        }
+    }
+}
+
+/// Key under which a session's mailbox is persisted.
+fn mailbox_address(project_path: &str, branch: Option<&str>) -> String {
+    match branch {
+        Some(branch) => format!("{project_path}#{branch}"),
+        None => project_path.to_string(),
+    }
+}
+
+fn new_message(from_session: Option<u32>, topic: &str, body: &str) -> BusMessage {
+    BusMessage {
+        id: uuid::Uuid::new_v4().to_string(),
+        topic: topic.to_string(),
+        from_session,
+        body: body.to_string(),
+        timestamp: chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Micros, true),
+    }
+}
+
+fn validate_topic(topic: &str) -> Result<(), MessageBusError> {
+    if topic.is_empty() || topic.len() > 128 || topic.chars().any(char::is_whitespace) {
+        return Err(MessageBusError::InvalidTopic(topic.to_string()));
+    }
+    Ok(())
+}
+
+fn validate_body(body: &str) -> Result<(), MessageBusError> {
+    if body.len() > MAX_BODY_BYTES {
+        return Err(MessageBusError::BodyTooLarge {
+            size: body.len(),
+            max: MAX_BODY_BYTES,
+        });
+    }
+    Ok(())
+}
+
+fn load_state(path: &Path) -> Option<StoredBus> {
+    let content = match std::fs::read_to_string(path) {
+        Ok(c) => c,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return None,
+        Err(e) => {
+            log::warn!("MessageBus: failed to read {}: {e}", path.display());
+            return None;
+        }
+    };
+    match serde_json::from_str::<StoredBus>(&content) {
+        Ok(mut state) => {
+            // Sender IDs belong to the previous run; keeping them would stop
+            // a new session that reuses the ID from receiving the message.
+            let queued = state
+                .mailboxes
+                .values_mut()
+                .flat_map(|m| m.queues.values_mut())
+                .flatten();
+            for message in state.retained.values_mut().chain(queued) {
+                message.from_session = None;
+            }
+            Some(state)
+        }
+        Err(e) => {
+            log::warn!("MessageBus: ignoring corrupt store {}: {e}", path.display());
+            None
+        }
+    }
+}
+
+/// Writes state atomically: temp file in the same directory, then rename.
+fn save_state(path: &Path, content: &str) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let temp_path = path.with_extension(format!("json.tmp.{}", std::process::id()));
+    std::fs::write(&temp_path, content)?;
+    std::fs::rename(&temp_path, path).inspect_err(|_| {
+        let _ = std::fs::remove_file(&temp_path);
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    /// Helper: build a MessageBus wired to an EventBus that records events.
+    fn bus_with_events() -> (MessageBus, Arc<Mutex<Vec<ClaudeEvent>>>) {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let events_clone = events.clone();
+        let event_bus = Arc::new(EventBus::new(Arc::new(move |e: ClaudeEvent| {
+            events_clone.lock().unwrap().push(e);
+        })));
+        (MessageBus::new(None, Some(event_bus)), events)
+    }
+
+    #[test]
+    fn test_publish_fans_out_to_subscribers_except_sender() {
+        let (bus, events) = bus_with_events();
+        bus.subscribe(1, "api-contract").unwrap();
+        bus.subscribe(2, "api-contract").unwrap();
+        bus.subscribe(3, "api-contract").unwrap();
+
+        let receipt = bus.publish(Some(1), "api-contract", "GET /users").unwrap();
+        assert_eq!(receipt.delivered_to, vec![2, 3]);
+
+        assert!(bus.peek(1, None).is_empty());
+        assert_eq!(bus.peek(2, None)[0].body, "GET /users");
+        assert_eq!(bus.peek(3, Some("api-contract")).len(), 1);
+        assert_eq!(events.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_retained_message_delivered_on_late_subscribe() {
+        let (bus, _events) = bus_with_events();
+        bus.publish(Some(1), "schema", "v1").unwrap();
+        bus.publish(Some(1), "schema", "v2").unwrap();
+
+        assert!(bus.subscribe(2, "schema").unwrap());
+        let received = bus.receive(2, None, 10);
+        assert_eq!(received.len(), 1);
+        assert_eq!(received[0].body, "v2", "only the latest value is retained");
+
+        // Re-subscribing does not redeliver.
+        assert!(!bus.subscribe(2, "schema").unwrap());
+        assert!(bus.peek(2, None).is_empty());
+    }
+
+    #[test]
+    fn test_send_and_receive_consumes_in_order() {
+        let (bus, events) = bus_with_events();
+        bus.send(Some(1), 2, "notes", "first").unwrap();
+        bus.send(None, 2, "review", "second").unwrap();
+        bus.send(Some(3), 2, "notes", "third").unwrap();
+
+        assert_eq!(
+            bus.pending(2),
+            vec![
+                MailboxSummary { topic: "notes".into(), pending: 2 },
+                MailboxSummary { topic: "review".into(), pending: 1 },
+            ]
+        );
+
+        let first_two = bus.receive(2, None, 2);
+        assert_eq!(
+            first_two.iter().map(|m| m.body.as_str()).collect::<Vec<_>>(),
+            vec!["first", "second"]
+        );
+        let rest = bus.receive(2, Some("notes"), 10);
+        assert_eq!(rest.len(), 1);
+        assert_eq!(rest[0].body, "third");
+        assert!(bus.pending(2).is_empty());
+
+        let first = events.lock().unwrap()[0].clone();
+        match first {
+            ClaudeEvent::MessageReceived { session_id, from_session, topic, .. } => {
+                assert_eq!(session_id, 2);
+                assert_eq!(from_session, Some(1));
+                assert_eq!(topic, "notes");
+            }
+            other => panic!("Expected MessageReceived, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_validation_errors() {
+        let bus = MessageBus::new(None, None);
+        assert!(matches!(
+            bus.publish(None, "", "x"),
+            Err(MessageBusError::InvalidTopic(_))
+        ));
+        assert!(matches!(
+            bus.subscribe(1, "has space"),
+            Err(MessageBusError::InvalidTopic(_))
+        ));
+        assert!(matches!(
+            bus.send(Some(4), 4, "t", "x"),
+            Err(MessageBusError::SelfAddressed(4))
+        ));
+        let big = "x".repeat(MAX_BODY_BYTES + 1);
+        assert!(matches!(
+            bus.send(None, 1, "t", &big),
+            Err(MessageBusError::BodyTooLarge { .. })
+        ));
+    }
+
+    #[test]
+    fn test_only_retained_messages_persist_across_instances() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("bus.json");
+
+        let bus = MessageBus::new(Some(path.clone()), None);
+        bus.subscribe(2, "contract").unwrap();
+        bus.publish(Some(1), "contract", "POST /orders").unwrap();
+        bus.send(Some(1), 3, "notes", "hello").unwrap();
+        drop(bus);
+
+        // Session IDs restart after a relaunch, so queues and subscriptions
+        // must not be handed to whichever new session gets the same ID.
+        let reloaded = MessageBus::new(Some(path), None);
+        assert!(reloaded.peek(2, None).is_empty());
+        assert!(reloaded.peek(3, None).is_empty());
+        let topics = reloaded.topics();
+        assert_eq!(topics.len(), 1);
+        assert!(topics[0].subscribers.is_empty());
+        assert!(topics[0].retained.is_some());
+
+        // A new session reusing the old sender's ID still gets the value.
+        reloaded.subscribe(1, "contract").unwrap();
+        assert_eq!(reloaded.receive(1, None, 10)[0].body, "POST /orders");
+    }
+
+    #[test]
+    fn test_attached_mailboxes_survive_restart() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("bus.json");
+
+        let bus = MessageBus::new(Some(path.clone()), None);
+        bus.send(None, 3, "notes", "in memory only").unwrap();
+        assert!(!path.exists(), "unattached mailboxes are not written");
+
+        bus.attach_session(1, "/repo", Some("feat/api"));
+        bus.subscribe(1, "contract").unwrap();
+        bus.send(Some(2), 1, "review", "first").unwrap();
+        bus.send(Some(2), 1, "review", "second").unwrap();
+        assert_eq!(bus.receive(1, None, 1)[0].body, "first");
+        drop(bus);
+
+        let reloaded = MessageBus::new(Some(path), None);
+        assert!(reloaded.peek(1, None).is_empty());
+        reloaded.send(None, 9, "review", "queued before attach").unwrap();
+
+        // Another branch of the same project gets nothing
+        reloaded.attach_session(8, "/repo", Some("feat/ui"));
+        assert!(reloaded.peek(8, None).is_empty());
+
+        reloaded.attach_session(9, "/repo", Some("feat/api"));
+        let received = reloaded.receive(9, None, 10);
+        assert_eq!(
+            received.iter().map(|m| m.body.as_str()).collect::<Vec<_>>(),
+            vec!["second", "queued before attach"]
+        );
+        assert_eq!(received[0].from_session, None, "old sender IDs are dropped");
+        assert_eq!(reloaded.topics()[0].subscribers, vec![9]);
+
+        // The address now belongs to session 9
+        reloaded.attach_session(10, "/repo", Some("feat/api"));
+        reloaded.publish(None, "contract", "v1").unwrap();
+        assert!(reloaded.peek(10, None).is_empty());
+    }
+
+    #[test]
+    fn test_remove_session_clears_mailbox_and_subscriptions() {
+        let bus = MessageBus::new(None, None);
+        bus.subscribe(5, "a").unwrap();
+        bus.send(None, 5, "a", "hello").unwrap();
+        bus.publish(Some(5), "b", "retained").unwrap();
+
+        bus.remove_session(5);
+        assert!(bus.peek(5, None).is_empty());
+        let topics = bus.topics();
+        assert_eq!(topics.len(), 1, "retained topic 'b' survives");
+        assert_eq!(topics[0].topic, "b");
+    }
+}
@@ -1,23 +1,36 @@
 pub mod claude_event;
+pub mod conflict_detector;
 pub mod error;
 pub mod event_bus;
+pub mod fast_clone;
+pub mod git_operations;
 pub mod transcript_parser;
 pub mod transcript_watcher;
 pub mod font_detector;
 pub mod hook_config_writer;
+pub mod integration_manager;
 pub mod marketplace_error;
 pub mod marketplace_manager;
 pub mod marketplace_models;
 pub mod mcp_config_writer;
 pub mod mcp_manager;
+pub mod message_bus;
 pub mod plugin_config_writer;
 pub mod plugin_manager;
+pub mod port_allocator;
+pub mod pr_monitor;
 pub mod process_manager;
 pub mod process_tree;
+pub mod repo_watcher;
+pub mod review_feedback;
 pub mod session_manager;
+pub mod snapshot_manager;
 pub mod status_server;
+pub mod task_queue;
 pub mod terminal_backend;
 pub mod windows_process;
+pub mod worktree_bootstrap;
+pub mod worktree_gc;
 pub mod worktree_manager;
 pub mod xterm_backend;
 
@@ -25,15 +38,24 @@ pub mod xterm_backend;
 pub mod vte_backend;
 
 pub use claude_event::ClaudeEvent;
+pub use conflict_detector::ConflictDetector;
 pub use error::PtyError;
 pub use event_bus::EventBus;
 pub use font_detector::{detect_available_fonts, is_font_available, AvailableFont};
+pub use git_operations::GitOperations;
+pub use integration_manager::IntegrationManager;
 pub use marketplace_manager::MarketplaceManager;
 pub use mcp_manager::McpManager;
+pub use message_bus::MessageBus;
 pub use plugin_manager::PluginManager;
+pub use pr_monitor::PrMonitor;
 pub use process_manager::ProcessManager;
+pub use repo_watcher::{RepoChanged, RepoWatcher};
+pub use review_feedback::ReviewFeedback;
 pub use session_manager::SessionManager;
+pub use snapshot_manager::SnapshotManager;
 pub use status_server::StatusServer;
+pub use task_queue::TaskQueue;
 pub use terminal_backend::{
     BackendCapabilities, BackendType, SubscriptionHandle, TerminalBackend, TerminalConfig,
     TerminalError, TerminalState,
@@ -0,0 +1,267 @@
+//! Reserves a block of TCP ports for each session.
+//!
+//! Agents in parallel worktrees routinely start dev servers on the same
+//! default port. Each PTY session is given its own non-overlapping block of
+//! ports from a fixed range, skipping blocks where any port is already bound
+//! on this machine. The block is exported to the shell as `PORT` and
+//! `MAESTRO_PORT_*` variables (see [`PortBlock::env_vars`]) so dev servers
+//! that honour `PORT` pick a distinct port automatically.
+
+use std::collections::HashMap;
+use std::net::{Ipv4Addr, TcpListener};
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+
+/// First port of the default allocation range.
+pub const DEFAULT_RANGE_START: u16 = 10000;
+
+/// Ports reserved per session by default.
+pub const DEFAULT_BLOCK_SIZE: u16 = 10;
+
+/// Number of blocks in the default range (ports 10000-10999).
+pub const DEFAULT_BLOCK_COUNT: u16 = 100;
+
+/// Reports whether a port can currently be bound.
+pub type PortCheckFn = Arc<dyn Fn(u16) -> bool + Send + Sync>;
+
+/// Errors returned by the port allocator, serialized as a plain string.
+#[derive(Debug, thiserror::Error)]
+pub enum PortError {
+    #[error("no free block of {block_size} ports left in {start}-{end}")]
+    Exhausted {
+        block_size: u16,
+        start: u16,
+        end: u16,
+    },
+}
+
+impl serde::Serialize for PortError {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+/// A contiguous range of ports reserved for one session.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PortBlock {
+    pub base: u16,
+    pub count: u16,
+}
+
+impl PortBlock {
+    /// Last port in the block (inclusive).
+    pub fn end(&self) -> u16 {
+        self.base + self.count - 1
+    }
+
+    pub fn ports(&self) -> impl Iterator<Item = u16> {
+        self.base..=self.end()
+    }
+
+    /// Environment variables describing the block:
+    /// `PORT` (the first port, honoured by most dev servers),
+    /// `MAESTRO_PORT_BASE`, `MAESTRO_PORT_END` and `MAESTRO_PORT_COUNT`.
+    pub fn env_vars(&self) -> Vec<(String, String)> {
+        vec![
+            ("PORT".to_string(), self.base.to_string()),
+            ("MAESTRO_PORT_BASE".to_string(), self.base.to_string()),
+            ("MAESTRO_PORT_END".to_string(), self.end().to_string()),
+            ("MAESTRO_PORT_COUNT".to_string(), self.count.to_string()),
+        ]
+    }
+}
+
+/// Hands out non-overlapping [`PortBlock`]s keyed by session ID.
+pub struct PortAllocator {
+    range_start: u16,
+    block_size: u16,
+    block_count: u16,
+    is_free: PortCheckFn,
+    blocks: Mutex<HashMap<u32, PortBlock>>,
+}
+
+impl Default for PortAllocator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PortAllocator {
+    /// Allocates from the default range, probing ports by binding them.
+    pub fn new() -> Self {
+        Self::with_range(DEFAULT_RANGE_START, DEFAULT_BLOCK_SIZE, DEFAULT_BLOCK_COUNT)
+    }
+
+    /// Allocates `block_count` blocks of `block_size` ports starting at
+    /// `range_start`. The range is truncated if it would pass port 65535.
+    pub fn with_range(range_start: u16, block_size: u16, block_count: u16) -> Self {
+        let block_size = block_size.max(1);
+        let available = (u32::from(u16::MAX) + 1 - u32::from(range_start)) / u32::from(block_size);
+        Self {
+            range_start,
+            block_size,
+            block_count: block_count.min(u16::try_from(available).unwrap_or(u16::MAX)),
+            is_free: Arc::new(port_is_free),
+            blocks: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Replaces the port probe (used by tests to simulate busy ports).
+    pub fn with_port_check(mut self, is_free: PortCheckFn) -> Self {
+        self.is_free = is_free;
+        self
+    }
+
+    /// Reserves a block for the session, or returns its existing one.
+    ///
+    /// Blocks held by other sessions are skipped, as are blocks where any
+    /// port is already bound by another process.
+    pub fn allocate(&self, session_id: u32) -> Result<PortBlock, PortError> {
+        let mut blocks = self.blocks.lock().unwrap();
+        if let Some(block) = blocks.get(&session_id) {
+            return Ok(*block);
+        }
+
+        for index in 0..self.block_count {
+            let block = PortBlock {
+                base: self.range_start + index * self.block_size,
+                count: self.block_size,
+            };
+            if blocks.values().any(|b| b.base == block.base) {
+                continue;
+            }
+            if block.ports().all(|port| (self.is_free)(port)) {
+                blocks.insert(session_id, block);
+                return Ok(block);
+            }
+        }
+
+        Err(PortError::Exhausted {
+            block_size: self.block_size,
+            start: self.range_start,
+            end: self.range_start + self.block_count * self.block_size - 1,
+        })
+    }
+
+    /// Frees the session's block. Returns it, or `None` if it had none.
+    pub fn release(&self, session_id: u32) -> Option<PortBlock> {
+        self.blocks.lock().unwrap().remove(&session_id)
+    }
+
+    /// Returns the session's block, if one is reserved.
+    pub fn get(&self, session_id: u32) -> Option<PortBlock> {
+        self.blocks.lock().unwrap().get(&session_id).copied()
+    }
+
+    /// Returns all reservations as `(session_id, block)` pairs, ordered by port.
+    pub fn all(&self) -> Vec<(u32, PortBlock)> {
+        let mut all: Vec<_> = self
+            .blocks
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(id, block)| (*id, *block))
+            .collect();
+        all.sort_by_key(|(_, block)| block.base);
+        all
+    }
+}
+
+/// A port counts as free if it can be bound on both the wildcard and the
+/// loopback address, since dev servers listen on either.
+fn port_is_free(port: u16) -> bool {
+    [Ipv4Addr::UNSPECIFIED, Ipv4Addr::LOCALHOST]
+        .into_iter()
+        .all(|addr| TcpListener::bind((addr, port)).is_ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn allocator(busy: &'static [u16]) -> PortAllocator {
+        PortAllocator::with_range(5000, 10, 3)
+            .with_port_check(Arc::new(move |port| !busy.contains(&port)))
+    }
+
+    #[test]
+    fn test_blocks_do_not_overlap() {
+        let ports = allocator(&[]);
+        let a = ports.allocate(1).unwrap();
+        let b = ports.allocate(2).unwrap();
+        assert_eq!(
+            a,
+            PortBlock {
+                base: 5000,
+                count: 10
+            }
+        );
+        assert_eq!(
+            b,
+            PortBlock {
+                base: 5010,
+                count: 10
+            }
+        );
+        assert_eq!(ports.allocate(1).unwrap(), a);
+        assert_eq!(ports.all(), vec![(1, a), (2, b)]);
+    }
+
+    #[test]
+    fn test_busy_blocks_are_skipped() {
+        let ports = allocator(&[5003]);
+        assert_eq!(ports.allocate(1).unwrap().base, 5010);
+    }
+
+    #[test]
+    fn test_release_frees_block_for_reuse() {
+        let ports = allocator(&[]);
+        ports.allocate(1).unwrap();
+        ports.allocate(2).unwrap();
+        assert_eq!(ports.release(1).map(|b| b.base), Some(5000));
+        assert_eq!(ports.release(1), None);
+        assert_eq!(ports.get(1), None);
+        assert_eq!(ports.allocate(3).unwrap().base, 5000);
+    }
+
+    #[test]
+    fn test_exhaustion() {
+        let ports = allocator(&[5025]);
+        ports.allocate(1).unwrap();
+        ports.allocate(2).unwrap();
+        let err = ports.allocate(3).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "no free block of 10 ports left in 5000-5029"
+        );
+    }
+
+    #[test]
+    fn test_range_is_clamped_to_valid_ports() {
+        let ports = PortAllocator::with_range(65530, 4, 10).with_port_check(Arc::new(|_| true));
+        assert_eq!(ports.allocate(1).unwrap().end(), 65533);
+        assert!(ports.allocate(2).is_err());
+    }
+
+    #[test]
+    fn test_env_vars() {
+        let block = PortBlock {
+            base: 10020,
+            count: 10,
+        };
+        let env: HashMap<_, _> = block.env_vars().into_iter().collect();
+        assert_eq!(env["PORT"], "10020");
+        assert_eq!(env["MAESTRO_PORT_BASE"], "10020");
+        assert_eq!(env["MAESTRO_PORT_END"], "10029");
+        assert_eq!(env["MAESTRO_PORT_COUNT"], "10");
+    }
+
+    #[test]
+    fn test_bound_port_is_not_free() {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        let port = listener.local_addr().unwrap().port();
+        assert!(!port_is_free(port));
+    }
+}
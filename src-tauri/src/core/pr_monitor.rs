@@ -0,0 +1,436 @@
+//! Polls CI checks and reviews for pull requests linked to sessions.
+//!
+//! Sessions get a [`PullRequestLink`](super::session_manager::PullRequestLink)
+//! when their branch is shipped (or when the frontend links one). The
+//! [`PrMonitor`] periodically fetches `gh pr checks` and review data for each
+//! linked PR, keeps the latest [`PrStatus`] per session, and announces every
+//! meaningful change as a [`ClaudeEvent::PullRequestStatusChanged`] on the
+//! [`EventBus`] plus an optional callback (used to emit a Tauri event).
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde::Serialize;
+
+use super::claude_event::ClaudeEvent;
+use super::event_bus::EventBus;
+use super::session_manager::SessionConfig;
+use crate::github::{GitHub, GitHubError, PrCheck, PrReview};
+
+/// How often linked pull requests are polled.
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Callback invoked with the new status whenever a PR's status changes.
+pub type PrStatusFn = Arc<dyn Fn(&PrStatus) + Send + Sync>;
+
+/// Overall state of a PR's checks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChecksState {
+    /// Every check passed or was skipped.
+    Passing,
+    /// At least one check failed or was cancelled.
+    Failing,
+    /// No failures yet, but some checks are still running.
+    Pending,
+    /// The PR has no checks.
+    None,
+}
+
+impl ChecksState {
+    fn as_str(&self) -> &'static str {
+        match self {
+            ChecksState::Passing => "passing",
+            ChecksState::Failing => "failing",
+            ChecksState::Pending => "pending",
+            ChecksState::None => "none",
+        }
+    }
+}
+
+/// Check counts by gh bucket.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct CheckSummary {
+    pub passed: usize,
+    pub failed: usize,
+    pub pending: usize,
+    pub skipped: usize,
+}
+
+/// Latest known checks and review state of a session's pull request.
+#[derive(Debug, Clone, Serialize)]
+pub struct PrStatus {
+    pub session_id: u32,
+    pub number: u64,
+    pub url: String,
+    pub checks_state: ChecksState,
+    pub summary: CheckSummary,
+    pub checks: Vec<PrCheck>,
+    pub review_decision: Option<String>,
+    pub requested_reviewers: Vec<String>,
+    pub latest_reviews: Vec<PrReview>,
+    /// RFC 3339 time of the poll that produced this status.
+    pub updated_at: String,
+}
+
+impl PrStatus {
+    /// Builds a status from fetched checks and review data.
+    pub fn new(
+        session_id: u32,
+        number: u64,
+        url: String,
+        checks: Vec<PrCheck>,
+        review_decision: Option<String>,
+        requested_reviewers: Vec<String>,
+        latest_reviews: Vec<PrReview>,
+    ) -> Self {
+        let mut summary = CheckSummary::default();
+        for check in &checks {
+            match check.bucket.as_str() {
+                "pass" => summary.passed += 1,
+                "fail" | "cancel" => summary.failed += 1,
+                "skipping" => summary.skipped += 1,
+                _ => summary.pending += 1,
+            }
+        }
+        let checks_state = if checks.is_empty() {
+            ChecksState::None
+        } else if summary.failed > 0 {
+            ChecksState::Failing
+        } else if summary.pending > 0 {
+            ChecksState::Pending
+        } else {
+            ChecksState::Passing
+        };
+
+        Self {
+            session_id,
+            number,
+            url,
+            checks_state,
+            summary,
+            checks,
+            review_decision,
+            requested_reviewers,
+            latest_reviews,
+            updated_at: chrono::Utc::now().to_rfc3339(),
+        }
+    }
+
+    /// Names of failed or cancelled checks.
+    pub fn failing_checks(&self) -> Vec<String> {
+        self.checks
+            .iter()
+            .filter(|c| c.bucket == "fail" || c.bucket == "cancel")
+            .map(|c| c.name.clone())
+            .collect()
+    }
+
+    /// Whether anything a user would act on differs: check outcomes, the
+    /// review decision, requested reviewers or submitted reviews. Timestamps
+    /// and check descriptions are ignored.
+    fn differs_from(&self, other: &PrStatus) -> bool {
+        let buckets = |s: &PrStatus| -> BTreeMap<String, String> {
+            s.checks
+                .iter()
+                .map(|c| (c.name.clone(), c.bucket.clone()))
+                .collect()
+        };
+        let reviews = |s: &PrStatus| -> BTreeMap<String, String> {
+            s.latest_reviews
+                .iter()
+                .map(|r| (r.author.login.clone(), r.state.clone()))
+                .collect()
+        };
+
+        self.number != other.number
+            || buckets(self) != buckets(other)
+            || self.review_decision != other.review_decision
+            || self.requested_reviewers != other.requested_reviewers
+            || reviews(self) != reviews(other)
+    }
+}
+
+/// A pull request to poll, derived from a session.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WatchedPr {
+    pub session_id: u32,
+    /// Directory `gh` runs in (the session's worktree or project path).
+    pub repo_dir: PathBuf,
+    pub number: u64,
+    pub url: String,
+}
+
+/// Keeps the latest [`PrStatus`] per session and announces changes.
+pub struct PrMonitor {
+    statuses: Mutex<HashMap<u32, PrStatus>>,
+    event_bus: Option<Arc<EventBus>>,
+    on_change: Option<PrStatusFn>,
+}
+
+impl PrMonitor {
+    /// Creates a monitor that reports changes to `event_bus` and `on_change`.
+    pub fn new(event_bus: Option<Arc<EventBus>>, on_change: Option<PrStatusFn>) -> Self {
+        Self {
+            statuses: Mutex::new(HashMap::new()),
+            event_bus,
+            on_change,
+        }
+    }
+
+    /// Returns the pull requests to poll for the given sessions.
+    pub fn watched_from_sessions(sessions: &[SessionConfig]) -> Vec<WatchedPr> {
+        sessions
+            .iter()
+            .filter_map(|s| {
+                let pr = s.pull_request.as_ref()?;
+                Some(WatchedPr {
+                    session_id: s.id,
+                    repo_dir: PathBuf::from(s.worktree_path.as_deref().unwrap_or(&s.project_path)),
+                    number: pr.number,
+                    url: pr.url.clone(),
+                })
+            })
+            .collect()
+    }
+
+    /// Fetches the current checks and review state of one pull request.
+    pub async fn fetch(watched: &WatchedPr) -> Result<PrStatus, GitHubError> {
+        let gh = GitHub::new(&watched.repo_dir);
+        let checks = gh.pr_checks(watched.number).await?;
+        let reviews = gh.pr_review_status(watched.number).await?;
+        Ok(PrStatus::new(
+            watched.session_id,
+            watched.number,
+            watched.url.clone(),
+            checks,
+            reviews.review_decision,
+            reviews.requested_reviewers,
+            reviews.latest_reviews,
+        ))
+    }
+
+    /// Polls every watched pull request and forgets sessions that are no
+    /// longer watched. Fetch failures are logged and keep the last status.
+    pub async fn poll(&self, watched: &[WatchedPr]) {
+        let ids: HashSet<u32> = watched.iter().map(|w| w.session_id).collect();
+        self.statuses
+            .lock()
+            .unwrap()
+            .retain(|session_id, _| ids.contains(session_id));
+
+        for pr in watched {
+            match Self::fetch(pr).await {
+                Ok(status) => {
+                    self.apply(status);
+                }
+                Err(e) => log::warn!(
+                    "PR monitor: failed to poll PR #{} for session {}: {}",
+                    pr.number,
+                    pr.session_id,
+                    e
+                ),
+            }
+        }
+    }
+
+    /// Stores a freshly fetched status. Returns `true` (and announces the
+    /// status) if it is the first one for the session or differs from the
+    /// previous one.
+    pub fn apply(&self, status: PrStatus) -> bool {
+        let changed = {
+            let mut statuses = self.statuses.lock().unwrap();
+            let changed = statuses
+                .get(&status.session_id)
+                .is_none_or(|previous| status.differs_from(previous));
+            statuses.insert(status.session_id, status.clone());
+            changed
+        };
+
+        if changed {
+            if let Some(ref bus) = self.event_bus {
+                bus.emit(ClaudeEvent::PullRequestStatusChanged {
+                    session_id: status.session_id,
+                    number: status.number,
+                    url: status.url.clone(),
+                    checks_state: status.checks_state.as_str().to_string(),
+                    failing_checks: status.failing_checks(),
+                    review_decision: status.review_decision.clone(),
+                    timestamp: status.updated_at.clone(),
+                });
+            }
+            if let Some(ref on_change) = self.on_change {
+                on_change(&status);
+            }
+        }
+        changed
+    }
+
+    /// Latest status for every monitored session.
+    pub fn statuses(&self) -> Vec<PrStatus> {
+        let mut statuses: Vec<_> = self.statuses.lock().unwrap().values().cloned().collect();
+        statuses.sort_by_key(|s| s.session_id);
+        statuses
+    }
+
+    /// Latest status for one session.
+    pub fn get(&self, session_id: u32) -> Option<PrStatus> {
+        self.statuses.lock().unwrap().get(&session_id).cloned()
+    }
+
+    /// Stops tracking a session.
+    pub fn remove_session(&self, session_id: u32) {
+        self.statuses.lock().unwrap().remove(&session_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::session_manager::{AiMode, PullRequestLink, SessionStatus};
+    use crate::github::PrAuthor;
+
+    fn check(name: &str, bucket: &str) -> PrCheck {
+        PrCheck {
+            name: name.to_string(),
+            state: bucket.to_uppercase(),
+            bucket: bucket.to_string(),
+            link: String::new(),
+            workflow: "CI".to_string(),
+            description: String::new(),
+            started_at: None,
+            completed_at: None,
+        }
+    }
+
+    fn status(checks: Vec<PrCheck>, decision: Option<&str>) -> PrStatus {
+        PrStatus::new(
+            2,
+            17,
+            "https://github.com/o/r/pull/17".to_string(),
+            checks,
+            decision.map(str::to_string),
+            vec![],
+            vec![],
+        )
+    }
+
+    fn recording_monitor() -> (PrMonitor, Arc<Mutex<Vec<ClaudeEvent>>>) {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let sink = events.clone();
+        let bus = Arc::new(EventBus::new(Arc::new(move |e| sink.lock().unwrap().push(e))));
+        (PrMonitor::new(Some(bus), None), events)
+    }
+
+    #[test]
+    fn test_checks_state_summary() {
+        assert_eq!(status(vec![], None).checks_state, ChecksState::None);
+        assert_eq!(
+            status(vec![check("a", "pass"), check("b", "skipping")], None).checks_state,
+            ChecksState::Passing
+        );
+        assert_eq!(
+            status(vec![check("a", "pass"), check("b", "pending")], None).checks_state,
+            ChecksState::Pending
+        );
+
+        let failing = status(
+            vec![check("a", "pending"), check("b", "fail"), check("c", "cancel")],
+            None,
+        );
+        assert_eq!(failing.checks_state, ChecksState::Failing);
+        assert_eq!(
+            failing.summary,
+            CheckSummary { passed: 0, failed: 2, pending: 1, skipped: 0 }
+        );
+        assert_eq!(failing.failing_checks(), vec!["b", "c"]);
+    }
+
+    #[test]
+    fn test_apply_announces_only_changes() {
+        let (monitor, events) = recording_monitor();
+
+        assert!(monitor.apply(status(vec![check("build", "pending")], None)));
+        assert!(!monitor.apply(status(vec![check("build", "pending")], None)));
+        assert!(monitor.apply(status(vec![check("build", "fail")], None)));
+        assert!(monitor.apply(status(vec![check("build", "fail")], Some("CHANGES_REQUESTED"))));
+
+        let events = events.lock().unwrap();
+        assert_eq!(events.len(), 3);
+        match &events[1] {
+            ClaudeEvent::PullRequestStatusChanged {
+                session_id,
+                checks_state,
+                failing_checks,
+                ..
+            } => {
+                assert_eq!(*session_id, 2);
+                assert_eq!(checks_state, "failing");
+                assert_eq!(failing_checks, &vec!["build".to_string()]);
+            }
+            other => panic!("unexpected event {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_review_change_is_detected() {
+        let (monitor, _events) = recording_monitor();
+        monitor.apply(status(vec![], Some("REVIEW_REQUIRED")));
+
+        let mut reviewed = status(vec![], Some("REVIEW_REQUIRED"));
+        reviewed.latest_reviews.push(PrReview {
+            author: PrAuthor { login: "bob".to_string() },
+            state: "COMMENTED".to_string(),
+            body: "nit".to_string(),
+            submitted_at: None,
+        });
+        assert!(monitor.apply(reviewed));
+        assert_eq!(monitor.get(2).unwrap().latest_reviews.len(), 1);
+    }
+
+    #[test]
+    fn test_on_change_callback_and_remove() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let sink = seen.clone();
+        let monitor = PrMonitor::new(
+            None,
+            Some(Arc::new(move |s: &PrStatus| sink.lock().unwrap().push(s.number))),
+        );
+        monitor.apply(status(vec![check("a", "pass")], None));
+        assert_eq!(*seen.lock().unwrap(), vec![17]);
+        assert_eq!(monitor.statuses().len(), 1);
+
+        monitor.remove_session(2);
+        assert!(monitor.get(2).is_none());
+    }
+
+    #[test]
+    fn test_watched_from_sessions() {
+        let session = |id: u32, pr: Option<u64>, worktree: Option<&str>| SessionConfig {
+            id,
+            mode: AiMode::Claude,
+            branch: Some("feature".to_string()),
+            status: SessionStatus::Idle,
+            worktree_path: worktree.map(str::to_string),
+            project_path: "/repo".to_string(),
+            issue: None,
+            pull_request: pr.map(|number| PullRequestLink {
+                number,
+                url: format!("https://github.com/o/r/pull/{number}"),
+            }),
+            ports: None,
+        };
+
+        let watched = PrMonitor::watched_from_sessions(&[
+            session(1, None, None),
+            session(2, Some(5), Some("/wt/feature")),
+            session(3, Some(6), None),
+        ]);
+        assert_eq!(watched.len(), 2);
+        assert_eq!(watched[0].repo_dir, PathBuf::from("/wt/feature"));
+        assert_eq!(watched[1].repo_dir, PathBuf::from("/repo"));
+        assert_eq!(watched[1].number, 6);
+    }
+}
@@ -13,6 +13,7 @@ use tokio::sync::Notify;
 use libc;
 
 use super::error::PtyError;
+use super::port_allocator::{PortAllocator, PortBlock};
 
 /// Stateful UTF-8 decoder that handles split multi-byte sequences.
 ///
@@ -98,6 +99,8 @@ struct PtySession {
 struct Inner {
     sessions: DashMap<u32, PtySession>,
     next_id: AtomicU32,
+    /// Port blocks reserved for live sessions, released in `kill_session`.
+    ports: PortAllocator,
     /// Tracks last spawn time on Windows to prevent rapid consecutive spawns
     /// that may cause terminal spawning loops (Bug #76).
     #[cfg(windows)]
@@ -128,6 +131,7 @@ impl ProcessManager {
             inner: Arc::new(Inner {
                 sessions: DashMap::new(),
                 next_id: AtomicU32::new(1),
+                ports: PortAllocator::new(),
                 #[cfg(windows)]
                 last_spawn_time: Mutex::new(std::time::Instant::now()),
             }),
@@ -146,7 +150,11 @@ impl ProcessManager {
     ///
     /// # Environment Variables
     /// - `MAESTRO_SESSION_ID` is automatically set to the session ID
-    /// - Additional env vars can be passed via the `env` parameter (e.g., `MAESTRO_PROJECT_HASH`)
+    /// - `PORT` and `MAESTRO_PORT_*` describe a block of free ports reserved for
+    ///   the session (see `PortBlock::env_vars`). If no block is available the
+    ///   shell still starts, just without these variables.
+    /// - Additional env vars can be passed via the `env` parameter (e.g., `MAESTRO_PROJECT_HASH`);
+    ///   they override the automatic ones
     ///
     /// # Windows Debouncing
     /// On Windows, rapid consecutive spawn calls (within 500ms) are rejected to prevent
@@ -238,6 +246,19 @@ impl ProcessManager {
         // Inject MAESTRO_SESSION_ID automatically (used by MCP status server)
         cmd.env("MAESTRO_SESSION_ID", id.to_string());
 
+        // Reserve a port block so parallel dev servers don't collide
+        match self.inner.ports.allocate(id) {
+            Ok(block) => {
+                for (key, value) in block.env_vars() {
+                    cmd.env(key, value);
+                }
+            }
+            Err(e) => log::warn!("Session {id} started without reserved ports: {e}"),
+        }
+        let release_ports = || {
+            self.inner.ports.release(id);
+        };
+
         // Apply any additional environment variables from caller
         if let Some(envs) = env {
             for (key, value) in envs {
@@ -252,12 +273,18 @@ impl ProcessManager {
         let child = pair
             .slave
             .spawn_command(cmd)
-            .map_err(|e| PtyError::spawn_failed(format!("Failed to spawn shell: {e}")))?;
+            .map_err(|e| {
+                release_ports();
+                PtyError::spawn_failed(format!("Failed to spawn shell: {e}"))
+            })?;
 
         let child_pid = child
             .process_id()
             .map(|pid| pid as i32)
-            .ok_or_else(|| PtyError::spawn_failed("Could not obtain child PID"))?;
+            .ok_or_else(|| {
+                release_ports();
+                PtyError::spawn_failed("Could not obtain child PID")
+            })?;
 
         // Capture process group ID before moving master into Mutex (Unix only).
         // portable-pty calls setsid() on spawn, so PGID == child PID.
@@ -269,13 +296,19 @@ impl ProcessManager {
         let writer = pair
             .master
             .take_writer()
-            .map_err(|e| PtyError::spawn_failed(format!("Failed to take PTY writer: {e}")))?;
+            .map_err(|e| {
+                release_ports();
+                PtyError::spawn_failed(format!("Failed to take PTY writer: {e}"))
+            })?;
 
         // Get reader from master
         let mut reader = pair
             .master
             .try_clone_reader()
-            .map_err(|e| PtyError::spawn_failed(format!("Failed to clone PTY reader: {e}")))?;
+            .map_err(|e| {
+                release_ports();
+                PtyError::spawn_failed(format!("Failed to clone PTY reader: {e}"))
+            })?;
 
         let shutdown = Arc::new(Notify::new());
         let shutdown_clone = shutdown.clone();
@@ -323,7 +356,10 @@ impl ProcessManager {
                 }
                 log::debug!("PTY reader {id} exited");
             })
-            .map_err(|e| PtyError::spawn_failed(format!("Failed to spawn reader thread: {e}")))?;
+            .map_err(|e| {
+                release_ports();
+                PtyError::spawn_failed(format!("Failed to spawn reader thread: {e}"))
+            })?;
 
         // Tokio task: drain the channel and emit Tauri events with time-based batching.
         // Accumulates decoded text and flushes every 16ms (aligned with 60fps) or when
@@ -559,6 +595,7 @@ impl ProcessManager {
             .remove(&session_id)
             .ok_or_else(|| PtyError::session_not_found(session_id))?
             .1;
+        self.inner.ports.release(session_id);
 
         let pid = session.child_pid;
 
@@ -639,6 +676,16 @@ impl ProcessManager {
         Ok(())
     }
 
+    /// Returns the port block reserved for a session, if any.
+    pub fn session_ports(&self, session_id: u32) -> Option<PortBlock> {
+        self.inner.ports.get(session_id)
+    }
+
+    /// Returns all reserved port blocks as `(session_id, block)` pairs.
+    pub fn port_allocations(&self) -> Vec<(u32, PortBlock)> {
+        self.inner.ports.all()
+    }
+
     /// Returns the child PID for a specific session.
     ///
     /// Returns None if the session doesn't exist.
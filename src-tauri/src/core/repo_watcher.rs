@@ -0,0 +1,563 @@
+//! Pushes repository status changes instead of having the UI poll for them.
+//!
+//! Each watched repository (a project root or a session worktree) gets a
+//! [`notify`] watcher on its working tree and git directory, plus a tokio
+//! task that recomputes the branch, uncommitted-change count and worktree
+//! list once changes settle for [`REPO_CHANGE_DEBOUNCE`]. A [`RepoChanged`]
+//! is reported only when that status actually differs from the last one.
+//!
+//! Inside the git directory only `HEAD`, `index`, `packed-refs`, `refs/` and
+//! `worktrees/` matter; object writes, logs and lock files are ignored, as
+//! are dependency and build directories in the working tree.
+//!
+//! The working tree is not watched recursively: on Linux that registers an
+//! inotify watch for every directory, and `node_modules` or `target` alone
+//! can exhaust the per-user limit. Instead each directory outside
+//! [`IGNORED_DIRS`] is watched on its own, and directories created later are
+//! added as their creation is reported.
+
+use std::ffi::OsStr;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use dashmap::DashMap;
+use dashmap::mapref::entry::Entry;
+use notify::event::{AccessKind, AccessMode, ModifyKind, RenameMode};
+use notify::{Event as NotifyEvent, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::Serialize;
+use std::sync::Mutex;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+use crate::git::{Git, GitError, WorktreeInfo};
+
+/// Quiet period after a filesystem change before the status is recomputed,
+/// so a checkout or an agent's burst of edits produces one event.
+pub const REPO_CHANGE_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Working-tree directories whose churn never needs a status refresh.
+const IGNORED_DIRS: &[&str] = &[
+    "node_modules",
+    "target",
+    "dist",
+    "build",
+    ".next",
+    "__pycache__",
+    ".venv",
+];
+
+/// Callback invoked whenever a watched repository's status changes.
+pub type RepoChangedFn = Arc<dyn Fn(&RepoChanged) + Send + Sync>;
+
+/// Fresh status of a watched repository.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RepoChanged {
+    /// The path passed to [`RepoWatcher::watch`].
+    pub repo_path: String,
+    /// Current branch, or the short hash when HEAD is detached. `None` if it
+    /// could not be read.
+    pub branch: Option<String>,
+    /// Staged, unstaged and untracked changes, as `git status --porcelain`
+    /// counts them.
+    pub uncommitted_count: Option<usize>,
+    pub worktrees: Vec<WorktreeInfo>,
+}
+
+/// The directories a repository's status depends on.
+#[derive(Debug, Clone)]
+struct RepoDirs {
+    root: PathBuf,
+    git_dir: PathBuf,
+    /// Shared refs and worktree registry; differs from `git_dir` in linked
+    /// worktrees.
+    common_dir: PathBuf,
+}
+
+impl RepoDirs {
+    /// Whether a change at `path` can affect the repository's status.
+    fn is_relevant(&self, path: &Path) -> bool {
+        for dir in [&self.git_dir, &self.common_dir] {
+            if let Ok(rel) = path.strip_prefix(dir) {
+                return is_relevant_git_path(rel);
+            }
+        }
+        match path.strip_prefix(&self.root) {
+            Ok(rel) => !matches!(
+                rel.components().next(),
+                Some(Component::Normal(first)) if IGNORED_DIRS.iter().any(|d| OsStr::new(d) == first)
+            ),
+            Err(_) => false,
+        }
+    }
+}
+
+/// Whether `rel`, relative to a git directory, holds HEAD, the index, refs
+/// or the worktree registry.
+fn is_relevant_git_path(rel: &Path) -> bool {
+    let mut components = rel.components().map(Component::as_os_str);
+    let Some(first) = components.next() else {
+        return false;
+    };
+    let rest = components.count();
+    match first.to_str() {
+        Some("HEAD") | Some("index") | Some("packed-refs") => rest == 0,
+        Some("refs") => true,
+        // `worktrees/<id>` itself (added/removed) or its HEAD and index.
+        Some("worktrees") => {
+            rest <= 1
+                || (rest == 2
+                    && matches!(
+                        rel.file_name().and_then(OsStr::to_str),
+                        Some("HEAD") | Some("index")
+                    ))
+        }
+        _ => false,
+    }
+}
+
+/// Whether a notify event may have brought a new directory into place.
+fn may_add_dir(kind: &EventKind) -> bool {
+    matches!(
+        kind,
+        EventKind::Create(_)
+            | EventKind::Modify(ModifyKind::Name(RenameMode::To | RenameMode::Both | RenameMode::Any))
+    )
+}
+
+/// Whether a notify event reports a change rather than a read.
+fn is_change(kind: &EventKind) -> bool {
+    match kind {
+        EventKind::Access(AccessKind::Close(AccessMode::Write)) => true,
+        EventKind::Access(_) => false,
+        _ => true,
+    }
+}
+
+struct WatchedRepo {
+    task_handle: JoinHandle<()>,
+    /// Adds watches for new directories; owns the notify watcher.
+    dir_task_handle: JoinHandle<()>,
+    /// Number of `watch` calls not yet matched by `unwatch`.
+    watchers: usize,
+}
+
+/// Watches repositories and reports their status when it changes.
+pub struct RepoWatcher {
+    repos: DashMap<String, WatchedRepo>,
+    on_change: Option<RepoChangedFn>,
+    debounce: Duration,
+}
+
+impl RepoWatcher {
+    /// Creates a watcher that reports status changes to `on_change`.
+    pub fn new(on_change: Option<RepoChangedFn>) -> Self {
+        Self {
+            repos: DashMap::new(),
+            on_change,
+            debounce: REPO_CHANGE_DEBOUNCE,
+        }
+    }
+
+    /// Overrides [`REPO_CHANGE_DEBOUNCE`].
+    pub fn with_debounce(mut self, debounce: Duration) -> Self {
+        self.debounce = debounce;
+        self
+    }
+
+    /// Starts watching `repo_path` and returns its current status.
+    ///
+    /// Watching an already watched path only bumps its watcher count; it
+    /// stays watched until [`unwatch`](Self::unwatch) has been called as
+    /// many times.
+    pub async fn watch(&self, repo_path: &str) -> Result<RepoChanged, GitError> {
+        if let Some(mut repo) = self.repos.get_mut(repo_path) {
+            repo.watchers += 1;
+            drop(repo);
+            return Ok(Self::status(repo_path).await);
+        }
+
+        let dirs = resolve_dirs(repo_path).await?;
+        let status = Self::status(repo_path).await;
+        let (tx, rx) = mpsc::channel::<()>(16);
+        let (dir_tx, dir_rx) = mpsc::unbounded_channel::<PathBuf>();
+
+        let watcher = {
+            let dirs = dirs.clone();
+            notify::recommended_watcher(move |res: Result<NotifyEvent, notify::Error>| match res {
+                Ok(event) => {
+                    if may_add_dir(&event.kind) {
+                        for path in event.paths.iter().filter(|p| p.is_dir()) {
+                            let _ = dir_tx.send(path.clone());
+                        }
+                    }
+                    if is_change(&event.kind) && event.paths.iter().any(|p| dirs.is_relevant(p)) {
+                        // A full channel already has a refresh pending.
+                        let _ = tx.try_send(());
+                    }
+                }
+                Err(e) => log::error!("RepoWatcher: notify error: {e}"),
+            })
+            .map_err(|e| watch_error(repo_path, e))?
+        };
+        let watcher = Arc::new(Mutex::new(watcher));
+        {
+            let (watcher, dirs) = (watcher.clone(), dirs.clone());
+            tokio::task::spawn_blocking(move || {
+                let mut watcher = watcher.lock().unwrap();
+                watch_dir(&mut *watcher, &dirs, &dirs.root)?;
+                if !dirs.git_dir.starts_with(&dirs.root) {
+                    // Linked worktree: HEAD and index live in the main repo.
+                    watch_dir(&mut *watcher, &dirs, &dirs.git_dir)?;
+                }
+                if dirs.common_dir != dirs.git_dir {
+                    watch_dir(&mut *watcher, &dirs, &dirs.common_dir)?;
+                }
+                Ok::<_, notify::Error>(())
+            })
+            .await
+            .map_err(|e| watch_error(repo_path, notify::Error::generic(&e.to_string())))?
+            .map_err(|e| watch_error(repo_path, e))?;
+        }
+
+        match self.repos.entry(repo_path.to_string()) {
+            // Another caller started watching while this one was setting up
+            Entry::Occupied(mut entry) => entry.get_mut().watchers += 1,
+            Entry::Vacant(entry) => {
+                let task_handle = tokio::spawn(refresh_task(
+                    repo_path.to_string(),
+                    rx,
+                    self.debounce,
+                    status.clone(),
+                    self.on_change.clone(),
+                ));
+                let dir_task_handle = tokio::spawn(watch_new_dirs(watcher, dirs, dir_rx));
+                entry.insert(WatchedRepo {
+                    task_handle,
+                    dir_task_handle,
+                    watchers: 1,
+                });
+                log::info!("RepoWatcher: watching {repo_path}");
+            }
+        }
+        Ok(status)
+    }
+
+    /// Releases one [`watch`](Self::watch) of `repo_path`, and stops
+    /// watching when none remain. Returns whether the path is still watched.
+    pub fn unwatch(&self, repo_path: &str) -> bool {
+        let Some(mut repo) = self.repos.get_mut(repo_path) else {
+            return false;
+        };
+        repo.watchers = repo.watchers.saturating_sub(1);
+        if repo.watchers > 0 {
+            return true;
+        }
+        drop(repo);
+        if let Some((_, repo)) = self.repos.remove(repo_path) {
+            repo.task_handle.abort();
+            repo.dir_task_handle.abort();
+            log::info!("RepoWatcher: stopped watching {repo_path}");
+        }
+        false
+    }
+
+    /// Paths currently being watched.
+    pub fn watched_repos(&self) -> Vec<String> {
+        self.repos.iter().map(|entry| entry.key().clone()).collect()
+    }
+
+    /// Reads the status of `repo_path`. Parts that fail to load are left
+    /// empty rather than failing the whole status.
+    pub async fn status(repo_path: &str) -> RepoChanged {
+        let git = Git::new(repo_path);
+        let (branch, uncommitted_count, worktrees) = tokio::join!(
+            git.current_branch(),
+            git.uncommitted_count(),
+            git.worktree_list()
+        );
+        RepoChanged {
+            repo_path: repo_path.to_string(),
+            branch: branch.ok(),
+            uncommitted_count: uncommitted_count.ok(),
+            worktrees: worktrees.unwrap_or_default(),
+        }
+    }
+}
+
+impl Drop for RepoWatcher {
+    fn drop(&mut self) {
+        for entry in self.repos.iter() {
+            entry.value().task_handle.abort();
+            entry.value().dir_task_handle.abort();
+        }
+    }
+}
+
+fn watch_error(repo_path: &str, e: notify::Error) -> GitError {
+    GitError::SpawnError {
+        source: std::io::Error::other(e.to_string()),
+        command: format!("watch {repo_path}"),
+    }
+}
+
+/// Watches `dir` and whatever beneath it can affect the status: inside a git
+/// directory only `refs/` and `worktrees/` (recursively), in the working
+/// tree every subdirectory except [`IGNORED_DIRS`] and nested `.git`
+/// directories.
+fn watch_dir(watcher: &mut dyn Watcher, dirs: &RepoDirs, dir: &Path) -> notify::Result<()> {
+    for git_dir in [&dirs.git_dir, &dirs.common_dir] {
+        let Ok(rel) = dir.strip_prefix(git_dir) else {
+            continue;
+        };
+        if rel.as_os_str().is_empty() {
+            watcher.watch(dir, RecursiveMode::NonRecursive)?;
+            for sub in ["refs", "worktrees"] {
+                if dir.join(sub).is_dir() {
+                    watcher.watch(&dir.join(sub), RecursiveMode::Recursive)?;
+                }
+            }
+        } else if rel == Path::new("refs") || rel == Path::new("worktrees") {
+            watcher.watch(dir, RecursiveMode::Recursive)?;
+        }
+        return Ok(());
+    }
+    let Ok(rel) = dir.strip_prefix(&dirs.root) else {
+        return Ok(());
+    };
+    if rel.components().any(|c| is_skipped_dir(c.as_os_str())) {
+        return Ok(());
+    }
+
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        match watcher.watch(&dir, RecursiveMode::NonRecursive) {
+            Ok(()) => {}
+            // Removed again before it could be watched
+            Err(_) if !dir.is_dir() => continue,
+            Err(e) => return Err(e),
+        }
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            // `file_type` doesn't follow symlinks, so linked trees are skipped
+            if !entry.file_type().is_ok_and(|t| t.is_dir()) {
+                continue;
+            }
+            let path = entry.path();
+            if path == dirs.git_dir {
+                watch_dir(watcher, dirs, &path)?;
+            } else if !is_skipped_dir(&entry.file_name()) {
+                pending.push(path);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Whether a working-tree directory named `name` is left unwatched.
+fn is_skipped_dir(name: &OsStr) -> bool {
+    name == ".git" || IGNORED_DIRS.iter().any(|d| OsStr::new(d) == name)
+}
+
+/// Watches directories reported as created after the initial walk.
+async fn watch_new_dirs(
+    watcher: Arc<Mutex<RecommendedWatcher>>,
+    dirs: RepoDirs,
+    mut dir_rx: mpsc::UnboundedReceiver<PathBuf>,
+) {
+    while let Some(dir) = dir_rx.recv().await {
+        let mut batch = vec![dir];
+        while let Ok(dir) = dir_rx.try_recv() {
+            batch.push(dir);
+        }
+        let (watcher, dirs) = (watcher.clone(), dirs.clone());
+        let result = tokio::task::spawn_blocking(move || {
+            let mut watcher = watcher.lock().unwrap();
+            for dir in batch {
+                if let Err(e) = watch_dir(&mut *watcher, &dirs, &dir) {
+                    log::warn!("RepoWatcher: failed to watch {}: {e}", dir.display());
+                }
+            }
+        })
+        .await;
+        if result.is_err() {
+            break;
+        }
+    }
+}
+
+/// Finds the working tree root, git directory and common directory of
+/// `repo_path`, canonicalized to match the paths notify reports.
+async fn resolve_dirs(repo_path: &str) -> Result<RepoDirs, GitError> {
+    let output = Git::new(repo_path)
+        .run(&[
+            "rev-parse",
+            "--show-toplevel",
+            "--absolute-git-dir",
+            "--git-common-dir",
+        ])
+        .await?;
+    let lines = output.lines();
+    let [root, git_dir, common_dir] = lines.as_slice() else {
+        return Err(GitError::ParseError {
+            message: format!("unexpected rev-parse output: {:?}", output.stdout),
+        });
+    };
+    // `--git-common-dir` may be relative to `repo_path`.
+    let common_dir = Path::new(repo_path).join(common_dir);
+    let canonical =
+        |path: &Path| std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    Ok(RepoDirs {
+        root: canonical(Path::new(root)),
+        git_dir: canonical(Path::new(git_dir)),
+        common_dir: canonical(&common_dir),
+    })
+}
+
+/// Recomputes the status after each settled burst of changes and reports it
+/// if it differs from the last one.
+async fn refresh_task(
+    repo_path: String,
+    mut rx: mpsc::Receiver<()>,
+    debounce: Duration,
+    mut last: RepoChanged,
+    on_change: Option<RepoChangedFn>,
+) {
+    while rx.recv().await.is_some() {
+        tokio::time::sleep(debounce).await;
+        while rx.try_recv().is_ok() {}
+
+        let status = RepoWatcher::status(&repo_path).await;
+        if status != last {
+            if let Some(ref on_change) = on_change {
+                on_change(&status);
+            }
+            last = status;
+        }
+    }
+    log::debug!("RepoWatcher: refresh task for {repo_path} exiting");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    fn dirs(root: &str, git_dir: &str, common_dir: &str) -> RepoDirs {
+        RepoDirs {
+            root: PathBuf::from(root),
+            git_dir: PathBuf::from(git_dir),
+            common_dir: PathBuf::from(common_dir),
+        }
+    }
+
+    #[test]
+    fn test_relevant_paths_in_main_worktree() {
+        let d = dirs("/repo", "/repo/.git", "/repo/.git");
+        assert!(d.is_relevant(Path::new("/repo/src/main.rs")));
+        assert!(d.is_relevant(Path::new("/repo/.git/HEAD")));
+        assert!(d.is_relevant(Path::new("/repo/.git/index")));
+        assert!(d.is_relevant(Path::new("/repo/.git/refs/heads/main")));
+        assert!(d.is_relevant(Path::new("/repo/.git/packed-refs")));
+        assert!(d.is_relevant(Path::new("/repo/.git/worktrees/feature")));
+
+        assert!(!d.is_relevant(Path::new("/repo/.git/index.lock")));
+        assert!(!d.is_relevant(Path::new("/repo/.git/objects/ab/cdef")));
+        assert!(!d.is_relevant(Path::new("/repo/.git/logs/HEAD")));
+        assert!(!d.is_relevant(Path::new("/repo/.git/worktrees/feature/logs/HEAD")));
+        assert!(!d.is_relevant(Path::new("/repo/node_modules/pkg/index.js")));
+        assert!(!d.is_relevant(Path::new("/repo/target/debug/app")));
+        assert!(!d.is_relevant(Path::new("/elsewhere/file")));
+    }
+
+    #[test]
+    fn test_relevant_paths_in_linked_worktree() {
+        let d = dirs("/wt", "/repo/.git/worktrees/wt", "/repo/.git");
+        assert!(d.is_relevant(Path::new("/wt/README.md")));
+        assert!(d.is_relevant(Path::new("/repo/.git/worktrees/wt/HEAD")));
+        assert!(d.is_relevant(Path::new("/repo/.git/worktrees/wt/index")));
+        assert!(d.is_relevant(Path::new("/repo/.git/refs/heads/wt")));
+        assert!(!d.is_relevant(Path::new("/repo/.git/worktrees/wt/ORIG_HEAD")));
+        // The main worktree's files don't affect this one.
+        assert!(!d.is_relevant(Path::new("/repo/src/main.rs")));
+    }
+
+    #[test]
+    fn test_reads_are_not_changes() {
+        assert!(!is_change(&EventKind::Access(AccessKind::Open(
+            notify::event::AccessMode::Any
+        ))));
+        assert!(!is_change(&EventKind::Access(AccessKind::Close(
+            AccessMode::Read
+        ))));
+        assert!(is_change(&EventKind::Access(AccessKind::Close(
+            AccessMode::Write
+        ))));
+        assert!(is_change(&EventKind::Any));
+    }
+
+    #[tokio::test]
+    async fn test_watch_reports_edits_and_branch_switches() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = std::fs::canonicalize(dir.path()).unwrap();
+        let git = Git::new(&path);
+        git.run(&["init", "-b", "main"]).await.unwrap();
+        git.run(&["config", "user.email", "test@test.com"])
+            .await
+            .unwrap();
+        git.run(&["config", "user.name", "Test"]).await.unwrap();
+        tokio::fs::write(path.join("a.txt"), "a").await.unwrap();
+        git.run(&["add", "."]).await.unwrap();
+        git.run(&["commit", "-m", "initial"]).await.unwrap();
+        git.run(&["branch", "feature"]).await.unwrap();
+
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let sink = seen.clone();
+        let watcher = RepoWatcher::new(Some(Arc::new(move |change: &RepoChanged| {
+            sink.lock().unwrap().push(change.clone());
+        })))
+        .with_debounce(Duration::from_millis(50));
+
+        let repo_path = path.to_string_lossy().to_string();
+        let initial = watcher.watch(&repo_path).await.unwrap();
+        assert_eq!(initial.branch.as_deref(), Some("main"));
+        assert_eq!(initial.uncommitted_count, Some(0));
+        assert_eq!(initial.worktrees.len(), 1);
+
+        async fn wait_for(seen: &Mutex<Vec<RepoChanged>>, pred: impl Fn(&RepoChanged) -> bool) {
+            for _ in 0..100 {
+                if seen.lock().unwrap().iter().any(&pred) {
+                    return;
+                }
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            }
+            panic!("no matching change in {:?}", seen.lock().unwrap());
+        }
+
+        tokio::fs::write(path.join("a.txt"), "edited")
+            .await
+            .unwrap();
+        wait_for(&seen, |c| c.uncommitted_count == Some(1)).await;
+
+        git.run(&["checkout", "-q", "feature"]).await.unwrap();
+        wait_for(&seen, |c| c.branch.as_deref() == Some("feature")).await;
+
+        // Directories created after `watch` are watched too, but only once
+        // their creation has been seen, so give that a moment.
+        tokio::fs::create_dir_all(path.join("src/deep")).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        tokio::fs::write(path.join("src/deep/b.txt"), "b")
+            .await
+            .unwrap();
+        wait_for(&seen, |c| c.uncommitted_count == Some(2)).await;
+
+        // A second watch keeps the repo watched through one unwatch.
+        watcher.watch(&repo_path).await.unwrap();
+        assert!(watcher.unwatch(&repo_path));
+        assert!(!watcher.unwatch(&repo_path));
+        assert!(watcher.watched_repos().is_empty());
+    }
+}
@@ -0,0 +1,859 @@
+//! Feeds pull request review feedback back into the session that wrote the PR.
+//!
+//! [`ReviewFeedback`] polls the pull request linked to each agent session
+//! (discovering it by the session's branch when no link exists yet) for
+//! review comments, review summaries and failing checks; checks and reviews
+//! come from the status [`PrMonitor`] already fetched. Anything not seen
+//! before is batched into a single prompt per session. Depending on the
+//! session's [`FeedbackPolicy`] the batch waits for the user to confirm it,
+//! is delivered automatically, or is dropped. Delivery types the prompt into
+//! the session's PTY when the session is idle, or holds it until the session
+//! next reports `Idle` or `Done`.
+//!
+//! The first poll of a pull request only records what is already there, so
+//! opening Maestro on an old PR does not replay its whole history.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use super::pr_monitor::{PrMonitor, PrStatus, WatchedPr};
+use super::session_manager::{AiMode, PullRequestLink, SessionConfig, SessionStatus};
+use super::task_queue::{prompt_input, DispatchFn};
+use crate::github::{GitHub, GitHubError, ReviewThread};
+
+/// How often linked pull requests are checked for new feedback.
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(90);
+
+/// Callback invoked with a session's batch whenever it changes.
+pub type FeedbackFn = Arc<dyn Fn(&FeedbackBatch) + Send + Sync>;
+
+/// What to do with new feedback for a session.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FeedbackPolicy {
+    /// Ignore feedback for this session.
+    Off,
+    /// Collect feedback and wait for the user to send it.
+    #[default]
+    Confirm,
+    /// Send feedback as soon as the session is idle.
+    Auto,
+}
+
+/// One piece of feedback on a pull request.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum FeedbackItem {
+    /// A comment on a line of the diff.
+    #[serde(rename_all = "camelCase")]
+    LineComment {
+        id: String,
+        author: String,
+        path: String,
+        line: Option<u32>,
+        body: String,
+        url: String,
+    },
+    /// A comment on the pull request's conversation.
+    Comment {
+        id: String,
+        author: String,
+        body: String,
+    },
+    /// A submitted review with a summary body.
+    Review {
+        author: String,
+        /// `CHANGES_REQUESTED`, `COMMENTED`, ...
+        state: String,
+        body: String,
+    },
+    /// A check that failed.
+    FailingCheck {
+        name: String,
+        description: String,
+        link: String,
+    },
+}
+
+/// Feedback waiting to be delivered to a session.
+#[derive(Debug, Clone, Serialize)]
+pub struct FeedbackBatch {
+    pub session_id: u32,
+    pub number: u64,
+    pub url: String,
+    pub policy: FeedbackPolicy,
+    pub items: Vec<FeedbackItem>,
+    /// The text that will be typed into the session.
+    pub prompt: String,
+    /// Delivery was requested and is waiting for the session to go idle.
+    pub queued: bool,
+    /// RFC 3339 time the batch last changed.
+    pub updated_at: String,
+}
+
+/// Everything currently on a pull request that can produce feedback, keyed
+/// by a stable identity so repeated polls only surface new items.
+#[derive(Debug, Clone, Default)]
+pub struct FeedbackSnapshot {
+    pub items: Vec<(String, FeedbackItem)>,
+}
+
+impl FeedbackSnapshot {
+    /// Collects feedback from fetched checks, reviews, review threads and
+    /// conversation comments. Resolved threads and pending (unsubmitted)
+    /// review comments are skipped.
+    pub fn new(
+        status: &PrStatus,
+        threads: &[ReviewThread],
+        comments: &[crate::github::Comment],
+    ) -> Self {
+        let mut items = Vec::new();
+
+        for thread in threads.iter().filter(|t| !t.is_resolved) {
+            for comment in &thread.comments {
+                if comment.review_state.as_deref() == Some("PENDING") {
+                    continue;
+                }
+                items.push((
+                    format!("comment:{}", comment.id),
+                    FeedbackItem::LineComment {
+                        id: comment.id.clone(),
+                        author: comment.author.login.clone(),
+                        path: thread.path.clone(),
+                        line: thread.line.or(thread.original_line),
+                        body: comment.body.clone(),
+                        url: comment.url.clone(),
+                    },
+                ));
+            }
+        }
+
+        for comment in comments {
+            items.push((
+                format!("comment:{}", comment.id),
+                FeedbackItem::Comment {
+                    id: comment.id.clone(),
+                    author: comment.author.login.clone(),
+                    body: comment.body.clone(),
+                },
+            ));
+        }
+
+        for review in &status.latest_reviews {
+            if review.body.trim().is_empty() || review.state == "PENDING" {
+                continue;
+            }
+            items.push((
+                format!(
+                    "review:{}:{}",
+                    review.author.login,
+                    review.submitted_at.as_deref().unwrap_or_default()
+                ),
+                FeedbackItem::Review {
+                    author: review.author.login.clone(),
+                    state: review.state.clone(),
+                    body: review.body.clone(),
+                },
+            ));
+        }
+
+        for check in status.checks.iter().filter(|c| c.bucket == "fail") {
+            // A re-run that fails again has a new start time
+            let run = check
+                .started_at
+                .as_deref()
+                .or(check.completed_at.as_deref())
+                .unwrap_or_default();
+            items.push((
+                format!("check:{}:{}", check.name, run),
+                FeedbackItem::FailingCheck {
+                    name: check.name.clone(),
+                    description: check.description.clone(),
+                    link: check.link.clone(),
+                },
+            ));
+        }
+
+        Self { items }
+    }
+}
+
+/// Builds the prompt typed into the session for a batch of feedback.
+///
+/// Comment bodies, check descriptions and the like are untrusted; escape
+/// sequences and control characters are stripped from them by
+/// [`prompt_input`] when the prompt is typed.
+pub fn build_prompt(number: u64, url: &str, items: &[FeedbackItem]) -> String {
+    let mut line_comments = Vec::new();
+    let mut comments = Vec::new();
+    let mut checks = Vec::new();
+
+    for item in items {
+        match item {
+            FeedbackItem::LineComment {
+                author,
+                path,
+                line,
+                body,
+                ..
+            } => {
+                let location = match line {
+                    Some(line) => format!("{path}:{line}"),
+                    None => path.clone(),
+                };
+                line_comments.push(format!("- {location} (@{author}): {}", indent(body)));
+            }
+            FeedbackItem::Comment { author, body, .. } => {
+                comments.push(format!("- @{author}: {}", indent(body)));
+            }
+            FeedbackItem::Review {
+                author,
+                state,
+                body,
+            } => {
+                let verb = match state.as_str() {
+                    "CHANGES_REQUESTED" => "requested changes",
+                    "APPROVED" => "approved",
+                    _ => "reviewed",
+                };
+                comments.push(format!("- @{author} {verb}: {}", indent(body)));
+            }
+            FeedbackItem::FailingCheck {
+                name,
+                description,
+                link,
+            } => {
+                let mut line = format!("- {name}");
+                if !description.is_empty() {
+                    line.push_str(&format!(": {description}"));
+                }
+                if !link.is_empty() {
+                    line.push_str(&format!(" ({link})"));
+                }
+                checks.push(line);
+            }
+        }
+    }
+
+    let mut prompt = format!("New feedback on pull request #{number} ({url}):\n");
+    for (title, lines) in [
+        ("Review comments", line_comments),
+        ("Comments", comments),
+        ("Failing checks", checks),
+    ] {
+        if !lines.is_empty() {
+            prompt.push_str(&format!("\n{title}:\n{}\n", lines.join("\n")));
+        }
+    }
+    prompt.push_str("\nPlease address this feedback and push the fixes.");
+    prompt
+}
+
+/// Indents continuation lines so multi-line bodies stay inside their bullet.
+fn indent(body: &str) -> String {
+    body.trim().replace('\n', "\n  ")
+}
+
+#[derive(Debug, Default)]
+struct SessionFeedback {
+    policy: FeedbackPolicy,
+    /// Identities of items already surfaced (or present at the first poll).
+    seen: HashSet<String>,
+    /// Whether the first poll has recorded the existing feedback.
+    baselined: bool,
+    /// Last reported idle state; `None` until the session reports a status.
+    idle: Option<bool>,
+    number: u64,
+    url: String,
+    pending: Vec<FeedbackItem>,
+    queued: bool,
+    updated_at: String,
+}
+
+impl SessionFeedback {
+    fn batch(&self, session_id: u32) -> FeedbackBatch {
+        FeedbackBatch {
+            session_id,
+            number: self.number,
+            url: self.url.clone(),
+            policy: self.policy,
+            items: self.pending.clone(),
+            prompt: build_prompt(self.number, &self.url, &self.pending),
+            queued: self.queued,
+            updated_at: self.updated_at.clone(),
+        }
+    }
+}
+
+/// Deferred side effect, executed after the state lock is released.
+enum Effect {
+    Deliver { session_id: u32, text: String },
+    Changed(FeedbackBatch),
+}
+
+/// Tracks feedback per session and delivers it to the session's PTY.
+pub struct ReviewFeedback {
+    sessions: Mutex<HashMap<u32, SessionFeedback>>,
+    dispatch_fn: DispatchFn,
+    on_change: Option<FeedbackFn>,
+}
+
+impl ReviewFeedback {
+    /// Creates a watcher that types prompts with `dispatch_fn` and reports
+    /// batch changes to `on_change`.
+    pub fn new(dispatch_fn: DispatchFn, on_change: Option<FeedbackFn>) -> Self {
+        Self {
+            sessions: Mutex::new(HashMap::new()),
+            dispatch_fn,
+            on_change,
+        }
+    }
+
+    /// Finds open pull requests for agent sessions that have a branch but no
+    /// linked pull request yet. Lookup failures are logged and skipped.
+    pub async fn discover_pull_requests(sessions: &[SessionConfig]) -> Vec<(u32, PullRequestLink)> {
+        let mut found = Vec::new();
+        for session in sessions {
+            if session.pull_request.is_some() || session.mode == AiMode::Plain {
+                continue;
+            }
+            let Some(branch) = session.branch.as_deref() else {
+                continue;
+            };
+            let gh = GitHub::new(
+                session
+                    .worktree_path
+                    .as_deref()
+                    .unwrap_or(&session.project_path),
+            );
+            match gh.pull_request_for_branch(branch).await {
+                Ok(Some(pr)) => found.push((
+                    session.id,
+                    PullRequestLink {
+                        number: pr.number,
+                        url: pr.url,
+                    },
+                )),
+                Ok(None) => {}
+                Err(e) => log::debug!(
+                    "Review feedback: no pull request lookup for session {} ({}): {}",
+                    session.id,
+                    branch,
+                    e
+                ),
+            }
+        }
+        found
+    }
+
+    /// Fetches the review threads and conversation comments of a pull
+    /// request and combines them with its checks and reviews from `status`.
+    pub async fn fetch(
+        watched: &WatchedPr,
+        status: &PrStatus,
+    ) -> Result<FeedbackSnapshot, GitHubError> {
+        let gh = GitHub::new(&watched.repo_dir);
+        let threads = gh.list_review_threads(watched.number).await?;
+        let detail = gh.get_pull_request(watched.number).await?;
+        Ok(FeedbackSnapshot::new(status, &threads, &detail.comments))
+    }
+
+    /// Polls the linked pull requests of agent sessions and forgets sessions
+    /// that are gone. Checks and reviews come from the statuses `monitor`
+    /// already fetched. Fetch failures are logged and retried on the next poll.
+    pub async fn poll(&self, sessions: &[SessionConfig], monitor: &PrMonitor) {
+        let agents: Vec<SessionConfig> = sessions
+            .iter()
+            .filter(|s| s.mode != AiMode::Plain)
+            .cloned()
+            .collect();
+        self.sync_sessions(&agents);
+
+        let watched = PrMonitor::watched_from_sessions(&agents);
+        for (watched, status) in self.with_status(watched, monitor) {
+            match Self::fetch(&watched, &status).await {
+                Ok(snapshot) => self.apply(&watched, snapshot),
+                Err(e) => log::warn!(
+                    "Review feedback: failed to poll PR #{} for session {}: {}",
+                    watched.number,
+                    watched.session_id,
+                    e
+                ),
+            }
+        }
+    }
+
+    /// Pairs each watched pull request with the status `monitor` last
+    /// fetched for it. Sessions with feedback turned off are skipped, and so
+    /// are pull requests the monitor has not fetched yet (e.g. just linked);
+    /// they are picked up by a later poll.
+    fn with_status(
+        &self,
+        watched: Vec<WatchedPr>,
+        monitor: &PrMonitor,
+    ) -> Vec<(WatchedPr, PrStatus)> {
+        watched
+            .into_iter()
+            .filter(|w| self.policy(w.session_id) != FeedbackPolicy::Off)
+            .filter_map(|w| {
+                let status = monitor
+                    .get(w.session_id)
+                    .filter(|status| status.number == w.number)?;
+                Some((w, status))
+            })
+            .collect()
+    }
+
+    /// Drops state for sessions that no longer exist and seeds the idle
+    /// state of sessions that have not reported a status yet.
+    fn sync_sessions(&self, sessions: &[SessionConfig]) {
+        let ids: HashSet<u32> = sessions.iter().map(|s| s.id).collect();
+        let mut state = self.lock();
+        state.retain(|id, _| ids.contains(id));
+        for session in sessions {
+            let entry = state.entry(session.id).or_default();
+            if entry.idle.is_none() {
+                entry.idle = Some(matches!(
+                    session.status,
+                    SessionStatus::Idle | SessionStatus::Done
+                ));
+            }
+        }
+    }
+
+    /// Records a fetched snapshot, adding unseen items to the session's
+    /// batch and acting on them according to its policy.
+    pub fn apply(&self, watched: &WatchedPr, snapshot: FeedbackSnapshot) {
+        self.mutate(|state, effects| {
+            let entry = state.entry(watched.session_id).or_default();
+            if entry.number != watched.number {
+                // A different PR was linked; start over
+                *entry = SessionFeedback {
+                    policy: entry.policy,
+                    idle: entry.idle,
+                    ..Default::default()
+                };
+                entry.number = watched.number;
+                entry.url = watched.url.clone();
+            }
+
+            let mut new_items = Vec::new();
+            for (key, item) in snapshot.items {
+                if entry.seen.insert(key) && entry.baselined {
+                    new_items.push(item);
+                }
+            }
+            entry.baselined = true;
+            if new_items.is_empty() || entry.policy == FeedbackPolicy::Off {
+                return;
+            }
+
+            entry.pending.extend(new_items);
+            entry.updated_at = now();
+            if entry.policy == FeedbackPolicy::Auto {
+                entry.queued = true;
+            }
+            Self::flush(watched.session_id, entry, effects);
+        });
+    }
+
+    /// Sends a session's pending feedback now if it is idle, or as soon as
+    /// it next becomes idle. Returns the batch, or `None` if nothing is pending.
+    pub fn send(&self, session_id: u32) -> Option<FeedbackBatch> {
+        self.mutate(|state, effects| {
+            let entry = state.get_mut(&session_id)?;
+            if entry.pending.is_empty() {
+                return None;
+            }
+            entry.queued = true;
+            entry.updated_at = now();
+            let batch = entry.batch(session_id);
+            Self::flush(session_id, entry, effects);
+            Some(batch)
+        })
+    }
+
+    /// Discards a session's pending feedback without sending it.
+    pub fn dismiss(&self, session_id: u32) {
+        self.mutate(|state, effects| {
+            if let Some(entry) = state.get_mut(&session_id) {
+                if entry.pending.is_empty() {
+                    return;
+                }
+                entry.pending.clear();
+                entry.queued = false;
+                entry.updated_at = now();
+                effects.push(Effect::Changed(entry.batch(session_id)));
+            }
+        })
+    }
+
+    /// Sets what happens with new feedback for a session. Switching to
+    /// `Auto` sends anything already pending.
+    pub fn set_policy(&self, session_id: u32, policy: FeedbackPolicy) {
+        self.mutate(|state, effects| {
+            let entry = state.entry(session_id).or_default();
+            entry.policy = policy;
+            match policy {
+                FeedbackPolicy::Off => {
+                    entry.pending.clear();
+                    entry.queued = false;
+                }
+                FeedbackPolicy::Auto if !entry.pending.is_empty() => entry.queued = true,
+                _ => {}
+            }
+            entry.updated_at = now();
+            Self::flush(session_id, entry, effects);
+        })
+    }
+
+    /// Returns a session's feedback policy.
+    pub fn policy(&self, session_id: u32) -> FeedbackPolicy {
+        self.lock()
+            .get(&session_id)
+            .map(|s| s.policy)
+            .unwrap_or_default()
+    }
+
+    /// Pending feedback for every session that has some.
+    pub fn batches(&self) -> Vec<FeedbackBatch> {
+        let state = self.lock();
+        let mut batches: Vec<_> = state
+            .iter()
+            .filter(|(_, s)| !s.pending.is_empty())
+            .map(|(id, s)| s.batch(*id))
+            .collect();
+        batches.sort_by_key(|b| b.session_id);
+        batches
+    }
+
+    /// Feeds a session status change (as emitted to the frontend) into the
+    /// watcher, delivering queued feedback once the session is idle.
+    pub fn on_session_status(&self, session_id: u32, status: &str) {
+        self.mutate(|state, effects| {
+            let Some(entry) = state.get_mut(&session_id) else {
+                return;
+            };
+            match status {
+                "Idle" | "Done" => {
+                    entry.idle = Some(true);
+                    Self::flush(session_id, entry, effects);
+                }
+                "Working" | "NeedsInput" | "Starting" => entry.idle = Some(false),
+                _ => {}
+            }
+        })
+    }
+
+    /// Delivers the batch if it is queued and the session is idle; otherwise
+    /// just announces its current state.
+    fn flush(session_id: u32, entry: &mut SessionFeedback, effects: &mut Vec<Effect>) {
+        if entry.queued && entry.idle == Some(true) && !entry.pending.is_empty() {
+            let text = prompt_input(&build_prompt(entry.number, &entry.url, &entry.pending));
+            entry.pending.clear();
+            entry.queued = false;
+            // The agent starts working on the prompt
+            entry.idle = Some(false);
+            effects.push(Effect::Deliver { session_id, text });
+        }
+        effects.push(Effect::Changed(entry.batch(session_id)));
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<u32, SessionFeedback>> {
+        self.sessions.lock().expect("review feedback lock poisoned")
+    }
+
+    /// Runs `f` under the state lock, then executes the collected effects.
+    fn mutate<R>(
+        &self,
+        f: impl FnOnce(&mut HashMap<u32, SessionFeedback>, &mut Vec<Effect>) -> R,
+    ) -> R {
+        let mut effects = Vec::new();
+        let result = f(&mut self.lock(), &mut effects);
+
+        for effect in effects {
+            match effect {
+                Effect::Deliver { session_id, text } => {
+                    if let Err(e) = (self.dispatch_fn)(session_id, &text) {
+                        log::warn!(
+                            "Review feedback: failed to write to session {}: {}",
+                            session_id,
+                            e
+                        );
+                    }
+                }
+                Effect::Changed(batch) => {
+                    if let Some(ref on_change) = self.on_change {
+                        on_change(&batch);
+                    }
+                }
+            }
+        }
+        result
+    }
+}
+
+fn now() -> String {
+    chrono::Utc::now().to_rfc3339()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::github::{PrAuthor, PrCheck, PrReview};
+
+    type Writes = Arc<Mutex<Vec<(u32, String)>>>;
+
+    fn watcher() -> (ReviewFeedback, Writes) {
+        let writes: Writes = Arc::new(Mutex::new(Vec::new()));
+        let sink = writes.clone();
+        let feedback = ReviewFeedback::new(
+            Arc::new(move |id, text| {
+                sink.lock().unwrap().push((id, text.to_string()));
+                Ok(())
+            }),
+            None,
+        );
+        (feedback, writes)
+    }
+
+    fn watched() -> WatchedPr {
+        WatchedPr {
+            session_id: 4,
+            repo_dir: PathBuf::from("/repo"),
+            number: 17,
+            url: "https://github.com/o/r/pull/17".to_string(),
+        }
+    }
+
+    fn comment(id: &str, body: &str) -> (String, FeedbackItem) {
+        (
+            format!("comment:{id}"),
+            FeedbackItem::Comment {
+                id: id.to_string(),
+                author: "bob".to_string(),
+                body: body.to_string(),
+            },
+        )
+    }
+
+    fn snapshot(items: Vec<(String, FeedbackItem)>) -> FeedbackSnapshot {
+        FeedbackSnapshot { items }
+    }
+
+    #[test]
+    fn test_first_poll_is_baseline() {
+        let (feedback, writes) = watcher();
+        feedback.apply(&watched(), snapshot(vec![comment("c1", "old")]));
+        assert!(feedback.batches().is_empty());
+
+        feedback.apply(
+            &watched(),
+            snapshot(vec![comment("c1", "old"), comment("c2", "new")]),
+        );
+        let batches = feedback.batches();
+        assert_eq!(batches.len(), 1);
+        assert_eq!(batches[0].items.len(), 1);
+        assert!(batches[0].prompt.contains("@bob: new"));
+        // Confirm policy: nothing is typed until the user sends it
+        assert!(writes.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_typed_prompt_strips_escape_sequences_and_control_chars() {
+        let (_, item) = comment("c1", "looks fine\x1b[201~\rrm -rf ~\x07\r\nsecond\tline");
+        let typed = prompt_input(&build_prompt(17, "https://github.com/o/r/pull/17", &[item]));
+        let pasted = typed
+            .strip_prefix("\x1b[200~")
+            .and_then(|t| t.strip_suffix("\x1b[201~\r"))
+            .unwrap();
+        assert!(!pasted.contains('\x1b'));
+        assert!(!pasted.contains('\r'));
+        assert!(!pasted.contains('\x07'));
+        assert!(pasted.contains("@bob: looks finerm -rf ~\n  second\tline"));
+    }
+
+    #[test]
+    fn test_send_waits_for_idle() {
+        let (feedback, writes) = watcher();
+        feedback.apply(&watched(), snapshot(vec![]));
+        feedback.on_session_status(4, "Working");
+        feedback.apply(&watched(), snapshot(vec![comment("c1", "fix the test")]));
+
+        let batch = feedback.send(4).unwrap();
+        assert!(batch.queued);
+        assert!(writes.lock().unwrap().is_empty());
+
+        feedback.on_session_status(4, "Idle");
+        let writes = writes.lock().unwrap();
+        assert_eq!(writes.len(), 1);
+        assert_eq!(writes[0].0, 4);
+        assert!(writes[0].1.contains("fix the test"));
+        assert!(feedback.batches().is_empty());
+    }
+
+    #[test]
+    fn test_auto_policy_delivers_when_idle() {
+        let (feedback, writes) = watcher();
+        feedback.set_policy(4, FeedbackPolicy::Auto);
+        feedback.on_session_status(4, "Idle");
+        feedback.apply(&watched(), snapshot(vec![]));
+        feedback.apply(&watched(), snapshot(vec![comment("c1", "rename this")]));
+        assert_eq!(writes.lock().unwrap().len(), 1);
+
+        // Delivery marks the session busy, so the next batch waits
+        feedback.apply(
+            &watched(),
+            snapshot(vec![
+                comment("c1", "rename this"),
+                comment("c2", "and this"),
+            ]),
+        );
+        assert_eq!(writes.lock().unwrap().len(), 1);
+        feedback.on_session_status(4, "Done");
+        assert_eq!(writes.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_off_policy_and_dismiss() {
+        let (feedback, _writes) = watcher();
+        feedback.apply(&watched(), snapshot(vec![]));
+        feedback.apply(&watched(), snapshot(vec![comment("c1", "a")]));
+        feedback.dismiss(4);
+        assert!(feedback.batches().is_empty());
+
+        feedback.set_policy(4, FeedbackPolicy::Off);
+        feedback.apply(&watched(), snapshot(vec![comment("c2", "b")]));
+        assert!(feedback.batches().is_empty());
+        assert!(feedback.send(4).is_none());
+    }
+
+    #[test]
+    fn test_poll_uses_statuses_fetched_by_the_monitor() {
+        let (feedback, _writes) = watcher();
+        let monitor = PrMonitor::new(None, None);
+        let status = |session_id, number| {
+            PrStatus::new(
+                session_id,
+                number,
+                String::new(),
+                vec![],
+                None,
+                vec![],
+                vec![],
+            )
+        };
+        monitor.apply(status(4, 17));
+        monitor.apply(status(5, 20));
+        monitor.apply(status(7, 40));
+        feedback.set_policy(7, FeedbackPolicy::Off);
+
+        let pr = |session_id, number| WatchedPr {
+            session_id,
+            number,
+            ..watched()
+        };
+        let due = feedback.with_status(vec![pr(4, 17), pr(5, 21), pr(6, 30), pr(7, 40)], &monitor);
+        let due: Vec<(u32, u64)> = due
+            .iter()
+            .map(|(w, status)| (w.session_id, status.number))
+            .collect();
+        // 5 was relinked and 6 not fetched yet; 7 has feedback turned off
+        assert_eq!(due, vec![(4, 17)]);
+    }
+
+    #[test]
+    fn test_snapshot_skips_resolved_and_pending() {
+        let status = PrStatus::new(
+            4,
+            17,
+            "u".to_string(),
+            vec![PrCheck {
+                name: "build".to_string(),
+                state: "FAILURE".to_string(),
+                bucket: "fail".to_string(),
+                link: "https://ci/1".to_string(),
+                workflow: "CI".to_string(),
+                description: "exit 101".to_string(),
+                started_at: Some("2026-01-01T00:00:00Z".to_string()),
+                completed_at: None,
+            }],
+            Some("CHANGES_REQUESTED".to_string()),
+            vec![],
+            vec![
+                PrReview {
+                    author: PrAuthor {
+                        login: "alice".to_string(),
+                    },
+                    state: "CHANGES_REQUESTED".to_string(),
+                    body: "Needs tests".to_string(),
+                    submitted_at: Some("2026-01-01T00:00:00Z".to_string()),
+                },
+                PrReview {
+                    author: PrAuthor {
+                        login: "carol".to_string(),
+                    },
+                    state: "APPROVED".to_string(),
+                    body: String::new(),
+                    submitted_at: None,
+                },
+            ],
+        );
+        let thread = |id: &str, resolved: bool, review_state: &str| ReviewThread {
+            id: format!("T{id}"),
+            path: "src/lib.rs".to_string(),
+            line: Some(42),
+            start_line: None,
+            original_line: Some(40),
+            original_start_line: None,
+            diff_side: crate::github::DiffSide::Right,
+            is_resolved: resolved,
+            is_outdated: false,
+            resolved_by: None,
+            comments: vec![crate::github::ReviewComment {
+                id: id.to_string(),
+                database_id: 1,
+                author: PrAuthor {
+                    login: "bob".to_string(),
+                },
+                body: "Off by one".to_string(),
+                created_at: "t".to_string(),
+                updated_at: None,
+                diff_hunk: String::new(),
+                url: String::new(),
+                reply_to_id: None,
+                review_state: Some(review_state.to_string()),
+            }],
+        };
+
+        let snapshot = FeedbackSnapshot::new(
+            &status,
+            &[
+                thread("c1", false, "COMMENTED"),
+                thread("c2", true, "COMMENTED"),
+                thread("c3", false, "PENDING"),
+            ],
+            &[],
+        );
+        let keys: Vec<&str> = snapshot.items.iter().map(|(k, _)| k.as_str()).collect();
+        assert_eq!(
+            keys,
+            vec![
+                "comment:c1",
+                "review:alice:2026-01-01T00:00:00Z",
+                "check:build:2026-01-01T00:00:00Z"
+            ]
+        );
+
+        let items: Vec<FeedbackItem> = snapshot.items.into_iter().map(|(_, i)| i).collect();
+        let prompt = build_prompt(17, "u", &items);
+        assert!(prompt.contains("- src/lib.rs:42 (@bob): Off by one"));
+        assert!(prompt.contains("- @alice requested changes: Needs tests"));
+        assert!(prompt.contains("- build: exit 101 (https://ci/1)"));
+    }
+}
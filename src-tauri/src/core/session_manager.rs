@@ -2,11 +2,13 @@ use dashmap::DashMap;
 use dashmap::mapref::entry::Entry;
 use serde::{Deserialize, Serialize};
 
+use super::port_allocator::PortBlock;
+
 /// Which AI backend a session is configured to use.
 ///
 /// `Plain` is a raw terminal with no AI agent attached, useful for
 /// manual shell work within a worktree.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum AiMode {
     Claude,
     Gemini,
@@ -30,6 +32,27 @@ pub enum SessionStatus {
     Error,
 }
 
+/// GitHub issue a session was started from.
+///
+/// Kept on the session so status displays and PR creation can refer back
+/// to the issue.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IssueLink {
+    pub number: u64,
+    pub title: String,
+    pub url: String,
+}
+
+/// Pull request opened from a session's branch.
+///
+/// Sessions with a linked PR have their checks and reviews polled by the
+/// `PrMonitor`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PullRequestLink {
+    pub number: u64,
+    pub url: String,
+}
+
 /// Frontend-visible configuration and state for a single session.
 ///
 /// `branch` and `worktree_path` are `None` until `assign_branch` is called,
@@ -44,6 +67,16 @@ pub struct SessionConfig {
     /// The project directory this session belongs to.
     /// Canonicalized absolute path for reliable comparison.
     pub project_path: String,
+    /// Issue this session is working on, if it was started from one.
+    #[serde(default)]
+    pub issue: Option<IssueLink>,
+    /// Pull request opened for this session's branch, if any.
+    #[serde(default)]
+    pub pull_request: Option<PullRequestLink>,
+    /// Ports reserved for this session's shell (exported as `PORT` and
+    /// `MAESTRO_PORT_*`).
+    #[serde(default)]
+    pub ports: Option<PortBlock>,
 }
 
 /// Thread-safe session registry backed by `DashMap` for lock-free concurrent reads.
@@ -71,6 +104,7 @@ impl SessionManager {
 
     /// Inserts a new session with `Idle` status and no branch assigned.
     /// Returns `Err` with the existing config if a session with this ID already exists.
+    #[allow(clippy::result_large_err)]
     pub fn create_session(&self, id: u32, mode: AiMode, project_path: String) -> Result<SessionConfig, SessionConfig> {
         let config = SessionConfig {
             id,
@@ -79,6 +113,9 @@ impl SessionManager {
             status: SessionStatus::Idle,
             worktree_path: None,
             project_path,
+            issue: None,
+            pull_request: None,
+            ports: None,
         };
         match self.sessions.entry(id) {
             Entry::Occupied(e) => Err(e.get().clone()),
@@ -117,6 +154,39 @@ impl SessionManager {
         }
     }
 
+    /// Links a session to the GitHub issue it is working on.
+    /// Returns the updated config, or `None` if the session does not exist.
+    pub fn link_issue(&self, id: u32, issue: IssueLink) -> Option<SessionConfig> {
+        if let Some(mut session) = self.sessions.get_mut(&id) {
+            session.issue = Some(issue);
+            Some(session.clone())
+        } else {
+            None
+        }
+    }
+
+    /// Links a session to the pull request opened from its branch.
+    /// Returns the updated config, or `None` if the session does not exist.
+    pub fn link_pull_request(&self, id: u32, pull_request: PullRequestLink) -> Option<SessionConfig> {
+        if let Some(mut session) = self.sessions.get_mut(&id) {
+            session.pull_request = Some(pull_request);
+            Some(session.clone())
+        } else {
+            None
+        }
+    }
+
+    /// Records the port block reserved for the session's shell.
+    /// Returns the updated config, or `None` if the session does not exist.
+    pub fn assign_ports(&self, id: u32, ports: PortBlock) -> Option<SessionConfig> {
+        if let Some(mut session) = self.sessions.get_mut(&id) {
+            session.ports = Some(ports);
+            Some(session.clone())
+        } else {
+            None
+        }
+    }
+
     /// Returns a snapshot of all active sessions. Order is not guaranteed.
     pub fn all_sessions(&self) -> Vec<SessionConfig> {
         self.sessions.iter().map(|e| e.value().clone()).collect()
@@ -0,0 +1,304 @@
+//! Automatic work-in-progress snapshots of session worktrees.
+//!
+//! Every session running in its own worktree is snapshotted onto a hidden
+//! ref (see [`crate::git::snapshot`]) on a fixed interval, and shortly after
+//! the agent edits or creates files. The index, HEAD and branches are never
+//! touched, so snapshots are invisible to the agent and to `git status`.
+//!
+//! Edit notifications arrive through [`SnapshotManager::note_event`], which
+//! is fed from the event bus callback. A burst of edits is coalesced into one
+//! snapshot per session after [`EDIT_DEBOUNCE`]. Snapshots are skipped when
+//! nothing changed since the previous one.
+//!
+//! Each chain is capped at [`SNAPSHOT_RETENTION`] snapshots. To avoid
+//! rewriting the chain on every snapshot, it may grow to twice that while
+//! the session runs, and is trimmed back when it does and when the session
+//! ends.
+
+use std::collections::BTreeSet;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use tokio::sync::Notify;
+use tokio::time::Interval;
+
+use super::claude_event::ClaudeEvent;
+use super::session_manager::SessionConfig;
+use crate::git::{worktree_snapshot_name, Git, Snapshot};
+
+/// How often every session worktree is snapshotted.
+pub const DEFAULT_SNAPSHOT_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// Quiet period after a file edit before snapshotting, so a burst of edits
+/// produces one snapshot.
+pub const EDIT_DEBOUNCE: Duration = Duration::from_secs(15);
+
+/// Snapshots kept per worktree chain.
+pub const SNAPSHOT_RETENTION: usize = 100;
+
+/// A snapshot taken of a session's worktree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SessionSnapshot {
+    pub session_id: u32,
+    pub worktree_path: String,
+    pub snapshot: Snapshot,
+}
+
+/// What caused a round of snapshots.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SnapshotTrigger {
+    /// The periodic timer fired: snapshot every session.
+    Interval,
+    /// These sessions edited files.
+    Edited(BTreeSet<u32>),
+}
+
+impl SnapshotTrigger {
+    /// Reason recorded in the snapshot commit.
+    pub fn reason(&self) -> &'static str {
+        match self {
+            SnapshotTrigger::Interval => "interval",
+            SnapshotTrigger::Edited(_) => "file-edited",
+        }
+    }
+
+    /// Whether the round covers `session_id`.
+    pub fn includes(&self, session_id: u32) -> bool {
+        match self {
+            SnapshotTrigger::Interval => true,
+            SnapshotTrigger::Edited(ids) => ids.contains(&session_id),
+        }
+    }
+}
+
+/// Tracks which sessions have unsnapshotted edits and takes snapshots.
+pub struct SnapshotManager {
+    pending: Mutex<BTreeSet<u32>>,
+    edited: Notify,
+}
+
+impl Default for SnapshotManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SnapshotManager {
+    pub fn new() -> Self {
+        Self {
+            pending: Mutex::new(BTreeSet::new()),
+            edited: Notify::new(),
+        }
+    }
+
+    /// Marks the session as edited if `event` reports a file change.
+    pub fn note_event(&self, event: &ClaudeEvent) {
+        let session_id = match event {
+            ClaudeEvent::FileEdited { session_id, .. }
+            | ClaudeEvent::FileCreated { session_id, .. } => *session_id,
+            _ => return,
+        };
+        self.pending.lock().unwrap().insert(session_id);
+        self.edited.notify_one();
+    }
+
+    /// Waits for the next round: either the interval ticks, or files were
+    /// edited and [`EDIT_DEBOUNCE`] (`debounce` in tests) has passed since.
+    /// Edits made during the debounce join the same round.
+    pub async fn next_trigger(
+        &self,
+        interval: &mut Interval,
+        debounce: Duration,
+    ) -> SnapshotTrigger {
+        tokio::select! {
+            _ = interval.tick() => {
+                // Everything is about to be snapshotted anyway.
+                self.pending.lock().unwrap().clear();
+                SnapshotTrigger::Interval
+            }
+            _ = self.edited.notified() => {
+                tokio::time::sleep(debounce).await;
+                SnapshotTrigger::Edited(std::mem::take(&mut *self.pending.lock().unwrap()))
+            }
+        }
+    }
+
+    /// Snapshots the worktrees of the sessions covered by `trigger`.
+    ///
+    /// Sessions without their own worktree are skipped, since snapshotting
+    /// the shared project directory would mix several sessions' work.
+    /// Failures are logged and skipped.
+    pub async fn run(
+        &self,
+        sessions: &[SessionConfig],
+        trigger: &SnapshotTrigger,
+    ) -> Vec<SessionSnapshot> {
+        let mut seen = BTreeSet::new();
+        let mut created = Vec::new();
+        for session in sessions.iter().filter(|s| trigger.includes(s.id)) {
+            let Some(ref worktree_path) = session.worktree_path else {
+                continue;
+            };
+            if !seen.insert(worktree_path.as_str()) {
+                continue;
+            }
+            match Self::snapshot_session(session.id, worktree_path, trigger.reason()).await {
+                Ok(Some(snapshot)) => {
+                    Self::prune(worktree_path, SNAPSHOT_RETENTION * 2).await;
+                    created.push(snapshot);
+                }
+                Ok(None) => {}
+                Err(e) => log::warn!(
+                    "Snapshot manager: failed to snapshot session {} ({}): {}",
+                    session.id,
+                    worktree_path,
+                    e
+                ),
+            }
+        }
+        created
+    }
+
+    /// Trims the chain of an ended session's worktree to
+    /// [`SNAPSHOT_RETENTION`].
+    pub async fn session_ended(session: &SessionConfig) {
+        if let Some(ref worktree_path) = session.worktree_path {
+            Self::prune(worktree_path, SNAPSHOT_RETENTION).await;
+        }
+    }
+
+    /// Trims a worktree's chain to [`SNAPSHOT_RETENTION`] once it holds more
+    /// than `max` snapshots. Failures are logged.
+    async fn prune(worktree_path: &str, max: usize) {
+        let name = worktree_snapshot_name(Path::new(worktree_path));
+        match Git::new(worktree_path)
+            .prune_snapshots(&name, max, SNAPSHOT_RETENTION)
+            .await
+        {
+            Ok(false) => {}
+            Ok(true) => log::debug!("Snapshot manager: pruned snapshots of {}", worktree_path),
+            Err(e) => log::warn!(
+                "Snapshot manager: failed to prune snapshots of {}: {}",
+                worktree_path,
+                e
+            ),
+        }
+    }
+
+    /// Snapshots one worktree. Returns `None` if nothing changed since its
+    /// previous snapshot.
+    pub async fn snapshot_session(
+        session_id: u32,
+        worktree_path: &str,
+        reason: &str,
+    ) -> Result<Option<SessionSnapshot>, crate::git::GitError> {
+        let name = worktree_snapshot_name(Path::new(worktree_path));
+        let snapshot = Git::new(worktree_path)
+            .create_snapshot(&name, reason, Some(session_id))
+            .await?;
+        Ok(snapshot.map(|snapshot| SessionSnapshot {
+            session_id,
+            worktree_path: worktree_path.to_string(),
+            snapshot,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::session_manager::{AiMode, SessionStatus};
+    use tempfile::tempdir;
+
+    fn session(id: u32, worktree_path: Option<&Path>) -> SessionConfig {
+        SessionConfig {
+            id,
+            mode: AiMode::Claude,
+            branch: None,
+            status: SessionStatus::Idle,
+            worktree_path: worktree_path.map(|p| p.to_string_lossy().to_string()),
+            project_path: "/project".into(),
+            issue: None,
+            pull_request: None,
+            ports: None,
+        }
+    }
+
+    fn file_edited(session_id: u32) -> ClaudeEvent {
+        ClaudeEvent::FileEdited {
+            session_id,
+            file_path: "/a".into(),
+            tool: "Edit".into(),
+            timestamp: "t".into(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_edits_are_debounced_into_one_round() {
+        let manager = SnapshotManager::new();
+        let mut interval = tokio::time::interval(Duration::from_secs(3600));
+        interval.tick().await;
+
+        manager.note_event(&file_edited(1));
+        manager.note_event(&ClaudeEvent::SessionEnded {
+            session_id: 2,
+            reason: "exit".into(),
+            timestamp: "t".into(),
+        });
+        manager.note_event(&file_edited(3));
+        let trigger = manager
+            .next_trigger(&mut interval, Duration::from_millis(10))
+            .await;
+        assert_eq!(trigger, SnapshotTrigger::Edited(BTreeSet::from([1, 3])));
+        assert!(trigger.includes(3) && !trigger.includes(2));
+
+        // No edits since: the next round comes from the interval.
+        let mut interval = tokio::time::interval(Duration::from_millis(20));
+        let trigger = manager
+            .next_trigger(&mut interval, Duration::from_millis(10))
+            .await;
+        assert_eq!(trigger, SnapshotTrigger::Interval);
+    }
+
+    #[tokio::test]
+    async fn test_run_snapshots_worktree_sessions() {
+        let dir = tempdir().unwrap();
+        let git = Git::new(dir.path());
+        git.run(&["init", "-b", "main"]).await.unwrap();
+        git.run(&["config", "user.email", "test@test.com"])
+            .await
+            .unwrap();
+        git.run(&["config", "user.name", "Test"]).await.unwrap();
+        tokio::fs::write(dir.path().join("a.txt"), "a")
+            .await
+            .unwrap();
+        git.run(&["add", "."]).await.unwrap();
+        git.run(&["commit", "-m", "initial"]).await.unwrap();
+
+        let manager = SnapshotManager::new();
+        let sessions = vec![session(1, Some(dir.path())), session(2, None)];
+
+        // Clean worktree: nothing to record.
+        assert!(manager
+            .run(&sessions, &SnapshotTrigger::Interval)
+            .await
+            .is_empty());
+
+        tokio::fs::write(dir.path().join("a.txt"), "changed")
+            .await
+            .unwrap();
+        let edited_other = SnapshotTrigger::Edited(BTreeSet::from([2]));
+        assert!(manager.run(&sessions, &edited_other).await.is_empty());
+
+        let edited = SnapshotTrigger::Edited(BTreeSet::from([1]));
+        let created = manager.run(&sessions, &edited).await;
+        assert_eq!(created.len(), 1);
+        assert_eq!(created[0].snapshot.reason, "file-edited");
+        assert_eq!(created[0].snapshot.session_id, Some(1));
+        assert_eq!(created[0].session_id, 1);
+
+        let name = worktree_snapshot_name(dir.path());
+        assert_eq!(git.list_snapshots(&name, 10).await.unwrap().len(), 1);
+    }
+}
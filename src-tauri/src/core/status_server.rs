@@ -10,6 +10,7 @@ use std::sync::Arc;
 use axum::{
     extract::State,
     http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
     routing::post,
     Json, Router,
 };
@@ -21,6 +22,9 @@ use tauri::{AppHandle, Emitter};
 use tokio::sync::RwLock;
 
 use super::claude_event::ClaudeEvent;
+use super::message_bus::MessageBus;
+use super::review_feedback::ReviewFeedback;
+use super::task_queue::{TaskQueue, TaskSpec};
 
 /// Maximum number of pending statuses to buffer (prevents memory leaks).
 const MAX_PENDING_STATUSES: usize = 100;
@@ -72,10 +76,62 @@ pub struct HookGenericRequest {
     pub extra: serde_json::Value,
 }
 
+/// Request payload for subscribing to / unsubscribing from a mailbox topic.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct MessageTopicRequest {
+    pub session_id: u32,
+    pub instance_id: String,
+    pub topic: String,
+}
+
+/// Request payload for publishing to a topic.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct MessagePublishRequest {
+    pub session_id: u32,
+    pub instance_id: String,
+    pub topic: String,
+    pub body: String,
+}
+
+/// Request payload for sending a direct message to another session.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct MessageSendRequest {
+    pub session_id: u32,
+    pub instance_id: String,
+    pub to_session: u32,
+    pub topic: String,
+    pub body: String,
+}
+
+/// Request payload for draining a session's mailbox.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct MessageReceiveRequest {
+    pub session_id: u32,
+    pub instance_id: String,
+    pub topic: Option<String>,
+    pub max: Option<usize>,
+}
+
+/// Request payload for enqueueing a task from an agent session.
+#[derive(Debug, Deserialize)]
+pub struct TaskEnqueueRequest {
+    /// Maestro session making the request; required.
+    #[serde(default)]
+    pub session_id: Option<u32>,
+    pub instance_id: String,
+    #[serde(flatten)]
+    pub spec: TaskSpec,
+}
+
+/// Default number of messages returned by `/messages/receive`.
+const DEFAULT_RECEIVE_MAX: usize = 20;
+
 /// State shared with the HTTP handler.
 struct ServerState {
     emit_fn: EmitFn,
     hook_emit_fn: Option<HookEmitFn>,
+    message_bus: Option<Arc<MessageBus>>,
+    task_queue: Option<Arc<TaskQueue>>,
     instance_id: String,
     /// Maps session_id -> project_path for routing status updates
     session_projects: Arc<RwLock<HashMap<u32, String>>>,
@@ -100,17 +156,36 @@ fn build_router(state: Arc<ServerState>) -> Router {
         .route("/hook/session-end", post(handle_hook_session_end))
         .route("/hook/pre-tool", post(handle_hook_pre_tool))
         .route("/hook/stop", post(handle_hook_stop))
+        .route("/messages/subscribe", post(handle_message_subscribe))
+        .route("/messages/unsubscribe", post(handle_message_unsubscribe))
+        .route("/messages/publish", post(handle_message_publish))
+        .route("/messages/send", post(handle_message_send))
+        .route("/messages/receive", post(handle_message_receive))
+        .route("/tasks/enqueue", post(handle_task_enqueue))
         .with_state(state)
 }
 
 /// Create an `EmitFn` from a Tauri `AppHandle`.
-fn emit_fn_from_app_handle(app_handle: AppHandle) -> EmitFn {
+///
+/// Status changes are also fed to the task queue and review feedback watcher
+/// (if any) so they can deliver prompts to sessions that became idle.
+fn emit_fn_from_app_handle(
+    app_handle: AppHandle,
+    task_queue: Option<Arc<TaskQueue>>,
+    review_feedback: Option<Arc<ReviewFeedback>>,
+) -> EmitFn {
     Arc::new(move |payload: SessionStatusPayload| {
         if let Err(e) = app_handle.emit("session-status-changed", &payload) {
             eprintln!("[STATUS] EMIT FAILED: {}", e);
         } else {
             eprintln!("[STATUS] EMIT SUCCESS");
         }
+        if let Some(ref queue) = task_queue {
+            queue.on_session_status(payload.session_id, &payload.status, &payload.message);
+        }
+        if let Some(ref feedback) = review_feedback {
+            feedback.on_session_status(payload.session_id, &payload.status);
+        }
     })
 }
 
@@ -143,17 +218,22 @@ impl StatusServer {
         app_handle: AppHandle,
         instance_id: String,
         hook_emit_fn: Option<Arc<dyn Fn(ClaudeEvent) + Send + Sync>>,
+        message_bus: Option<Arc<MessageBus>>,
+        task_queue: Option<Arc<TaskQueue>>,
+        review_feedback: Option<Arc<ReviewFeedback>>,
     ) -> Option<Self> {
         // Find and bind in one step to avoid race conditions where another
         // process grabs the port between checking and binding
         let (port, listener) = Self::find_and_bind_port(9900, 9999).await?;
         let session_projects = Arc::new(RwLock::new(HashMap::new()));
         let pending_statuses = Arc::new(RwLock::new(HashMap::new()));
-        let emit_fn = emit_fn_from_app_handle(app_handle);
+        let emit_fn = emit_fn_from_app_handle(app_handle, task_queue.clone(), review_feedback);
 
         let state = Arc::new(ServerState {
             emit_fn: emit_fn.clone(),
             hook_emit_fn,
+            message_bus,
+            task_queue,
             instance_id: instance_id.clone(),
             session_projects: session_projects.clone(),
             pending_statuses: pending_statuses.clone(),
@@ -527,6 +607,163 @@ async fn handle_hook_stop(
     StatusCode::OK
 }
 
+// ── Message bus handlers ─────────────────────────────────────────────
+
+/// Resolve the message bus for a mailbox request, applying the same trust
+/// rule as `/status`: a registered session is always accepted, otherwise the
+/// instance ID must match this server.
+async fn authorize_mailbox(
+    state: &ServerState,
+    session_id: u32,
+    instance_id: &str,
+) -> Result<Arc<MessageBus>, Response> {
+    let Some(ref bus) = state.message_bus else {
+        return Err((StatusCode::SERVICE_UNAVAILABLE, "message bus not available").into_response());
+    };
+
+    let registered = state.session_projects.read().await.contains_key(&session_id);
+    if !registered && instance_id != state.instance_id {
+        eprintln!(
+            "[MESSAGES] REJECTED - unknown session {} with wrong instance (expected {}, got {})",
+            session_id, state.instance_id, instance_id
+        );
+        return Err(StatusCode::FORBIDDEN.into_response());
+    }
+
+    Ok(bus.clone())
+}
+
+/// Handle a topic subscription request.
+async fn handle_message_subscribe(
+    State(state): State<Arc<ServerState>>,
+    Json(payload): Json<MessageTopicRequest>,
+) -> Response {
+    let bus = match authorize_mailbox(&state, payload.session_id, &payload.instance_id).await {
+        Ok(bus) => bus,
+        Err(resp) => return resp,
+    };
+    match bus.subscribe(payload.session_id, &payload.topic) {
+        Ok(added) => Json(serde_json::json!({ "subscribed": added })).into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, e.to_string()).into_response(),
+    }
+}
+
+/// Handle a topic unsubscription request.
+async fn handle_message_unsubscribe(
+    State(state): State<Arc<ServerState>>,
+    Json(payload): Json<MessageTopicRequest>,
+) -> Response {
+    let bus = match authorize_mailbox(&state, payload.session_id, &payload.instance_id).await {
+        Ok(bus) => bus,
+        Err(resp) => return resp,
+    };
+    let removed = bus.unsubscribe(payload.session_id, &payload.topic);
+    Json(serde_json::json!({ "unsubscribed": removed })).into_response()
+}
+
+/// Handle a topic publish request.
+async fn handle_message_publish(
+    State(state): State<Arc<ServerState>>,
+    Json(payload): Json<MessagePublishRequest>,
+) -> Response {
+    let bus = match authorize_mailbox(&state, payload.session_id, &payload.instance_id).await {
+        Ok(bus) => bus,
+        Err(resp) => return resp,
+    };
+    match bus.publish(Some(payload.session_id), &payload.topic, &payload.body) {
+        Ok(receipt) => Json(receipt).into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, e.to_string()).into_response(),
+    }
+}
+
+/// Handle a direct message request.
+async fn handle_message_send(
+    State(state): State<Arc<ServerState>>,
+    Json(payload): Json<MessageSendRequest>,
+) -> Response {
+    let bus = match authorize_mailbox(&state, payload.session_id, &payload.instance_id).await {
+        Ok(bus) => bus,
+        Err(resp) => return resp,
+    };
+    match bus.send(
+        Some(payload.session_id),
+        payload.to_session,
+        &payload.topic,
+        &payload.body,
+    ) {
+        Ok(message) => Json(message).into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, e.to_string()).into_response(),
+    }
+}
+
+/// Handle a mailbox drain request. Returned messages are removed from the queue.
+async fn handle_message_receive(
+    State(state): State<Arc<ServerState>>,
+    Json(payload): Json<MessageReceiveRequest>,
+) -> Response {
+    let bus = match authorize_mailbox(&state, payload.session_id, &payload.instance_id).await {
+        Ok(bus) => bus,
+        Err(resp) => return resp,
+    };
+    let max = payload.max.unwrap_or(DEFAULT_RECEIVE_MAX);
+    let messages = bus.receive(payload.session_id, payload.topic.as_deref(), max);
+    Json(messages).into_response()
+}
+
+// ── Task queue handlers ──────────────────────────────────────────────
+
+/// Handle a task enqueue request from an agent session.
+///
+/// Queued tasks open new agent sessions, so the caller must be a registered
+/// session of this instance and may only enqueue tasks for its own project.
+/// The project path is canonicalized like the IPC path does, so it matches
+/// the paths stored on sessions.
+async fn handle_task_enqueue(
+    State(state): State<Arc<ServerState>>,
+    Json(payload): Json<TaskEnqueueRequest>,
+) -> Response {
+    let Some(ref queue) = state.task_queue else {
+        return (StatusCode::SERVICE_UNAVAILABLE, "task queue not available").into_response();
+    };
+
+    if payload.instance_id != state.instance_id {
+        eprintln!(
+            "[TASKS] REJECTED - wrong instance (expected {}, got {})",
+            state.instance_id, payload.instance_id
+        );
+        return StatusCode::FORBIDDEN.into_response();
+    }
+    let session_project = match payload.session_id {
+        Some(id) => state.session_projects.read().await.get(&id).cloned(),
+        None => None,
+    };
+    let Some(session_project) = session_project else {
+        return (StatusCode::FORBIDDEN, "tasks must be enqueued by a registered session").into_response();
+    };
+
+    let mut spec = payload.spec;
+    spec.project_path = match std::fs::canonicalize(&spec.project_path) {
+        Ok(path) => path.to_string_lossy().into_owned(),
+        Err(e) => {
+            let message = format!("Invalid project path '{}': {}", spec.project_path, e);
+            return (StatusCode::BAD_REQUEST, message).into_response();
+        }
+    };
+    if spec.project_path != session_project {
+        eprintln!(
+            "[TASKS] REJECTED - session {:?} of '{}' enqueued for '{}'",
+            payload.session_id, session_project, spec.project_path
+        );
+        return (StatusCode::FORBIDDEN, "sessions can only enqueue tasks for their own project")
+            .into_response();
+    }
+
+    match queue.enqueue(spec) {
+        Ok(task) => Json(task).into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, e.to_string()).into_response(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -570,6 +807,8 @@ mod tests {
         let state = Arc::new(ServerState {
             emit_fn,
             hook_emit_fn: None,
+            message_bus: None,
+            task_queue: None,
             instance_id: instance_id.to_string(),
             session_projects: session_projects.clone(),
             pending_statuses: pending_statuses.clone(),
@@ -881,6 +1120,8 @@ mod tests {
         let state = Arc::new(ServerState {
             emit_fn,
             hook_emit_fn: Some(hook_emit_fn),
+            message_bus: None,
+            task_queue: None,
             instance_id: "test-instance".to_string(),
             session_projects: Arc::new(RwLock::new(HashMap::new())),
             pending_statuses: Arc::new(RwLock::new(HashMap::new())),
@@ -956,4 +1197,180 @@ mod tests {
 
         assert_eq!(resp.status().as_u16(), 400);
     }
+
+    // ── Message bus endpoint tests ───────────────────────────────────
+
+    /// Spin up a test HTTP server backed by an in-memory MessageBus.
+    async fn start_test_http_server_with_bus(
+        instance_id: &str,
+    ) -> (u16, Arc<MessageBus>, Arc<RwLock<HashMap<u32, String>>>) {
+        let (emit_fn, _) = test_emit_fn();
+        let bus = Arc::new(MessageBus::new(None, None));
+        let session_projects = Arc::new(RwLock::new(HashMap::new()));
+
+        let state = Arc::new(ServerState {
+            emit_fn,
+            hook_emit_fn: None,
+            message_bus: Some(bus.clone()),
+            task_queue: None,
+            instance_id: instance_id.to_string(),
+            session_projects: session_projects.clone(),
+            pending_statuses: Arc::new(RwLock::new(HashMap::new())),
+        });
+
+        let app = build_router(state);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+
+        (port, bus, session_projects)
+    }
+
+    /// Helper: POST a JSON body to a message endpoint.
+    async fn post_message(port: u16, route: &str, body: serde_json::Value) -> reqwest::Response {
+        reqwest::Client::new()
+            .post(format!("http://127.0.0.1:{}/messages/{}", port, route))
+            .json(&body)
+            .send()
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_message_publish_and_receive_roundtrip() {
+        let (port, _bus, _) = start_test_http_server_with_bus("inst-1").await;
+
+        let resp = post_message(port, "subscribe", serde_json::json!({
+            "session_id": 2, "instance_id": "inst-1", "topic": "api-contract"
+        })).await;
+        assert_eq!(resp.status().as_u16(), 200);
+
+        let resp = post_message(port, "publish", serde_json::json!({
+            "session_id": 1, "instance_id": "inst-1", "topic": "api-contract", "body": "GET /users"
+        })).await;
+        assert_eq!(resp.status().as_u16(), 200);
+        let receipt: serde_json::Value = resp.json().await.unwrap();
+        assert_eq!(receipt["delivered_to"], serde_json::json!([2]));
+
+        let resp = post_message(port, "receive", serde_json::json!({
+            "session_id": 2, "instance_id": "inst-1"
+        })).await;
+        let messages: Vec<serde_json::Value> = resp.json().await.unwrap();
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0]["body"], "GET /users");
+        assert_eq!(messages[0]["from_session"], 1);
+    }
+
+    #[tokio::test]
+    async fn test_message_send_registered_session_with_stale_instance() {
+        let (port, bus, projects) = start_test_http_server_with_bus("inst-current").await;
+        projects.write().await.insert(1, "/path/project".to_string());
+
+        let resp = post_message(port, "send", serde_json::json!({
+            "session_id": 1, "instance_id": "inst-old", "to_session": 3, "topic": "notes", "body": "hi"
+        })).await;
+        assert_eq!(resp.status().as_u16(), 200);
+        assert_eq!(bus.peek(3, None).len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_message_wrong_instance_unregistered_returns_403() {
+        let (port, bus, _) = start_test_http_server_with_bus("inst-current").await;
+
+        let resp = post_message(port, "send", serde_json::json!({
+            "session_id": 9, "instance_id": "inst-foreign", "to_session": 3, "topic": "notes", "body": "hi"
+        })).await;
+        assert_eq!(resp.status().as_u16(), 403);
+        assert!(bus.peek(3, None).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_message_invalid_topic_returns_400() {
+        let (port, _bus, _) = start_test_http_server_with_bus("inst-1").await;
+
+        let resp = post_message(port, "publish", serde_json::json!({
+            "session_id": 1, "instance_id": "inst-1", "topic": "", "body": "x"
+        })).await;
+        assert_eq!(resp.status().as_u16(), 400);
+    }
+
+    #[tokio::test]
+    async fn test_message_routes_without_bus_return_503() {
+        let (emit_fn, _) = test_emit_fn();
+        let (addr, _, _) = start_test_http_server("inst-1", emit_fn).await;
+
+        let resp = post_message(addr.port(), "receive", serde_json::json!({
+            "session_id": 1, "instance_id": "inst-1"
+        })).await;
+        assert_eq!(resp.status().as_u16(), 503);
+    }
+
+    // ── Task queue endpoint tests ────────────────────────────────────
+
+    #[tokio::test]
+    async fn test_task_enqueue_requires_session_of_the_project() {
+        let dir = tempfile::tempdir().unwrap();
+        let project = dir.path().join("repo");
+        let other = dir.path().join("other");
+        std::fs::create_dir(&project).unwrap();
+        std::fs::create_dir(&other).unwrap();
+        let canonical = std::fs::canonicalize(&project).unwrap().to_string_lossy().into_owned();
+        // A non-canonical spelling of the same directory
+        let spelled = format!("{}/../repo", other.display());
+
+        let (emit_fn, _) = test_emit_fn();
+        let queue = Arc::new(TaskQueue::new(Arc::new(|_, _| Ok(())), Arc::new(|_| {})));
+        let state = Arc::new(ServerState {
+            emit_fn,
+            hook_emit_fn: None,
+            message_bus: None,
+            task_queue: Some(queue.clone()),
+            instance_id: "inst-1".to_string(),
+            session_projects: Arc::new(RwLock::new(HashMap::from([(3, canonical.clone())]))),
+            pending_statuses: Arc::new(RwLock::new(HashMap::new())),
+        });
+
+        let app = build_router(state);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+
+        let url = format!("http://127.0.0.1:{}/tasks/enqueue", port);
+        let client = reqwest::Client::new();
+        for body in [
+            // Wrong instance
+            serde_json::json!({
+                "session_id": 3, "instance_id": "inst-other", "project_path": spelled, "prompt": "do it"
+            }),
+            // No calling session
+            serde_json::json!({
+                "instance_id": "inst-1", "project_path": spelled, "prompt": "do it"
+            }),
+            // Another directory than the session's project
+            serde_json::json!({
+                "session_id": 3, "instance_id": "inst-1",
+                "project_path": other.to_string_lossy(), "prompt": "do it"
+            }),
+        ] {
+            let resp = client.post(&url).json(&body).send().await.unwrap();
+            assert_eq!(resp.status().as_u16(), 403, "{body}");
+        }
+        assert!(queue.list().is_empty());
+
+        let body = serde_json::json!({
+            "session_id": 3, "instance_id": "inst-1", "project_path": spelled,
+            "prompt": "do it", "branch": "feat"
+        });
+        let resp = client.post(&url).json(&body).send().await.unwrap();
+        assert_eq!(resp.status().as_u16(), 200);
+        let task: serde_json::Value = resp.json().await.unwrap();
+        assert_eq!(task["prompt"], "do it");
+        assert_eq!(task["branch"], "feat");
+        assert_eq!(task["project_path"], canonical.as_str());
+        assert_eq!(queue.list().len(), 1);
+    }
 }
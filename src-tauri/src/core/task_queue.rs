@@ -0,0 +1,1129 @@
+//! Work queue that feeds prompts to idle agent sessions.
+//!
+//! Tasks are enqueued with a prompt and optional branch / AI mode
+//! constraints. Sessions opt in as *workers* via [`TaskQueue::enroll_session`].
+//! Whenever a worker reports `Idle` or `Done` through the status server, the
+//! next matching task is typed into its PTY. If no idle worker matches and the
+//! number of active tasks is below the concurrency cap, a
+//! [`TaskQueueEvent::SpawnRequested`] is emitted so the app can open a new
+//! worktree session for the task; that session then enrolls with the task ID
+//! and receives the prompt once its agent reports ready.
+//!
+//! A task's lifecycle is `Queued -> (Spawning) -> Dispatched -> Running ->
+//! Completed`. An `Error` status or a failed PTY write counts as a failed
+//! attempt: the task is requeued until `max_retries` is exhausted, then marked
+//! `Failed`. A task whose session does not report ready within
+//! [`SPAWN_TIMEOUT`] fails outright and frees its concurrency slot. An agent
+//! that reports `Idle` again more than [`DISPATCH_GRACE`] after the dispatch
+//! without ever reporting `Working` has finished already; one that reports
+//! nothing at all within [`DISPATCH_TIMEOUT`] counts as a failed attempt. The
+//! final status message reported by the agent is captured as the task result.
+//!
+//! Side effects (PTY writes, frontend events) are collected while the state
+//! lock is held and executed after it is released.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use super::session_manager::AiMode;
+use crate::github::actions::strip_ansi;
+
+/// Default number of tasks allowed to run at once.
+const DEFAULT_MAX_CONCURRENT: usize = 4;
+
+/// Default number of retries after the first failed attempt.
+const DEFAULT_MAX_RETRIES: u32 = 1;
+
+/// How long a task may stay in `Spawning` before it is failed.
+pub const SPAWN_TIMEOUT: Duration = Duration::from_secs(180);
+
+/// How long after a dispatch an `Idle` is still taken to be the status from
+/// before the prompt arrived, rather than the agent finishing the task.
+pub const DISPATCH_GRACE: Duration = Duration::from_secs(10);
+
+/// How long a task may stay `Dispatched` without any status from the agent
+/// before the attempt is failed.
+pub const DISPATCH_TIMEOUT: Duration = Duration::from_secs(180);
+
+/// Writes text into a session's PTY. In production this wraps
+/// `ProcessManager::write_stdin`; in tests it records the writes.
+pub type DispatchFn = Arc<dyn Fn(u32, &str) -> Result<(), String> + Send + Sync>;
+
+/// Receives queue events for forwarding to the frontend.
+pub type TaskEventFn = Arc<dyn Fn(TaskQueueEvent) + Send + Sync>;
+
+/// Errors returned by task queue operations, serialized as a plain string.
+#[derive(Debug, thiserror::Error)]
+pub enum TaskQueueError {
+    #[error("task prompt must not be empty")]
+    EmptyPrompt,
+
+    #[error("task {0} not found")]
+    NotFound(u64),
+
+    #[error("task {id} is {state:?} and cannot be {action}")]
+    InvalidState {
+        id: u64,
+        state: TaskState,
+        action: &'static str,
+    },
+}
+
+impl serde::Serialize for TaskQueueError {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+/// What to run and where it may run.
+#[derive(Debug, Clone, Deserialize)]
+pub struct TaskSpec {
+    /// Canonical project path; only workers of the same project pick the task up.
+    pub project_path: String,
+    pub prompt: String,
+    /// Only dispatch to a worker on this branch (and spawn on it if needed).
+    #[serde(default)]
+    pub branch: Option<String>,
+    /// Only dispatch to a worker running this AI mode.
+    #[serde(default)]
+    pub mode: Option<AiMode>,
+    /// Retries after the first failed attempt. Defaults to 1.
+    #[serde(default)]
+    pub max_retries: Option<u32>,
+}
+
+/// Lifecycle state of a queued task.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TaskState {
+    /// Waiting for an idle worker or a spawn slot.
+    Queued,
+    /// A new session was requested for this task and has not reported ready yet.
+    Spawning,
+    /// The prompt was written to the session; waiting for it to start working.
+    Dispatched,
+    /// The session reported `Working` or `NeedsInput` after dispatch.
+    Running,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+impl TaskState {
+    /// Whether the task occupies a concurrency slot.
+    fn is_active(self) -> bool {
+        matches!(self, Self::Spawning | Self::Dispatched | Self::Running)
+    }
+
+    /// Whether the task has reached a final state.
+    pub fn is_finished(self) -> bool {
+        matches!(self, Self::Completed | Self::Failed | Self::Cancelled)
+    }
+}
+
+/// Outcome captured from the session's final status report.
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskResult {
+    pub session_id: u32,
+    /// Final session status label (`Idle`, `Done` or `Error`).
+    pub status: String,
+    /// Status message reported by the agent alongside the final status.
+    pub message: String,
+}
+
+/// A unit of work and its progress.
+#[derive(Debug, Clone, Serialize)]
+pub struct Task {
+    pub id: u64,
+    pub project_path: String,
+    pub prompt: String,
+    pub branch: Option<String>,
+    pub mode: Option<AiMode>,
+    pub max_retries: u32,
+    pub state: TaskState,
+    /// Number of times the prompt has been dispatched.
+    pub attempts: u32,
+    /// Session currently (or last) handling the task.
+    pub session_id: Option<u32>,
+    pub created_at: String,
+    pub started_at: Option<String>,
+    pub finished_at: Option<String>,
+    pub result: Option<TaskResult>,
+    pub last_error: Option<String>,
+}
+
+/// Asks for a new session to run a task.
+///
+/// The handler should create the session (in a worktree for `branch` if
+/// given, running `mode` or Claude by default) and then enroll it with
+/// `task_id`, or report failure through [`TaskQueue::fail_spawn`].
+#[derive(Debug, Clone, Serialize)]
+pub struct SpawnRequest {
+    pub task_id: u64,
+    pub project_path: String,
+    pub branch: Option<String>,
+    pub mode: Option<AiMode>,
+}
+
+/// Notifications emitted by the queue.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind")]
+pub enum TaskQueueEvent {
+    /// A task was created or changed state.
+    TaskUpdated { task: Task },
+    /// A new session is needed to run a task.
+    SpawnRequested { request: SpawnRequest },
+}
+
+/// Scheduler settings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskQueueConfig {
+    /// Maximum number of tasks in `Spawning`, `Dispatched` or `Running`.
+    pub max_concurrent: usize,
+    /// Request new sessions when no idle worker matches a queued task.
+    pub auto_spawn: bool,
+}
+
+/// An enrolled session that can receive tasks.
+#[derive(Debug)]
+struct Worker {
+    project_path: String,
+    branch: Option<String>,
+    mode: AiMode,
+    idle: bool,
+    task_id: Option<u64>,
+}
+
+impl Worker {
+    fn accepts(&self, task: &Task) -> bool {
+        self.project_path == task.project_path
+            && task.branch.as_ref().is_none_or(|b| self.branch.as_ref() == Some(b))
+            && task.mode.as_ref().is_none_or(|m| *m == self.mode)
+    }
+}
+
+/// A dispatch the agent has not confirmed with `Working` yet.
+struct Dispatch {
+    since: Instant,
+    /// Message of an `Idle` received within [`DISPATCH_GRACE`], kept as the
+    /// result in case the agent finished without reporting `Working`.
+    early_idle: Option<String>,
+}
+
+/// Deferred side effect, executed after the state lock is released.
+enum Effect {
+    Dispatch { session_id: u32, task_id: u64, text: String },
+    Event(Box<TaskQueueEvent>),
+}
+
+struct QueueState {
+    /// Ordered by ID, which is also FIFO order.
+    tasks: BTreeMap<u64, Task>,
+    workers: HashMap<u32, Worker>,
+    /// When each task last entered `Spawning`, for [`TaskQueue::expire_spawning`].
+    spawning_since: HashMap<u64, Instant>,
+    /// When each task was last dispatched, for [`TaskQueue::expire_dispatched`].
+    dispatched: HashMap<u64, Dispatch>,
+    next_id: u64,
+    config: TaskQueueConfig,
+}
+
+impl QueueState {
+    fn insert_task(&mut self, spec: TaskSpec, initial: TaskState) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.tasks.insert(
+            id,
+            Task {
+                id,
+                project_path: spec.project_path,
+                prompt: spec.prompt,
+                branch: spec.branch,
+                mode: spec.mode,
+                max_retries: spec.max_retries.unwrap_or(DEFAULT_MAX_RETRIES),
+                state: initial,
+                attempts: 0,
+                session_id: None,
+                created_at: now(),
+                started_at: None,
+                finished_at: None,
+                result: None,
+                last_error: None,
+            },
+        );
+        if initial == TaskState::Spawning {
+            self.spawning_since.insert(id, Instant::now());
+        }
+        id
+    }
+
+    fn updated(&self, task_id: u64, effects: &mut Vec<Effect>) {
+        if let Some(task) = self.tasks.get(&task_id) {
+            effects.push(Effect::Event(Box::new(TaskQueueEvent::TaskUpdated { task: task.clone() })));
+        }
+    }
+
+    /// Marks the task dispatched to `session_id` and queues the PTY write.
+    fn assign(&mut self, task_id: u64, session_id: u32, effects: &mut Vec<Effect>) {
+        let Some(task) = self.tasks.get_mut(&task_id) else { return };
+        task.state = TaskState::Dispatched;
+        task.attempts += 1;
+        task.session_id = Some(session_id);
+        task.started_at = Some(now());
+        let text = prompt_input(&task.prompt);
+        self.dispatched.insert(
+            task_id,
+            Dispatch {
+                since: Instant::now(),
+                early_idle: None,
+            },
+        );
+
+        if let Some(worker) = self.workers.get_mut(&session_id) {
+            worker.idle = false;
+            worker.task_id = Some(task_id);
+        }
+        effects.push(Effect::Dispatch { session_id, task_id, text });
+        self.updated(task_id, effects);
+    }
+
+    /// Marks the task completed with the agent's final status.
+    fn complete(&mut self, task_id: u64, session_id: u32, status: &str, message: String, effects: &mut Vec<Effect>) {
+        if let Some(worker) = self.workers.get_mut(&session_id) {
+            worker.idle = true;
+            worker.task_id = None;
+        }
+        if let Some(task) = self.tasks.get_mut(&task_id) {
+            task.state = TaskState::Completed;
+            task.finished_at = Some(now());
+            task.result = Some(TaskResult {
+                session_id,
+                status: status.to_string(),
+                message,
+            });
+        }
+        self.updated(task_id, effects);
+    }
+
+    /// Records a failed attempt, requeueing the task if retries remain.
+    fn fail_attempt(&mut self, task_id: u64, session_id: u32, error: String, effects: &mut Vec<Effect>) {
+        let Some(task) = self.tasks.get_mut(&task_id) else { return };
+        task.last_error = Some(error.clone());
+        if task.attempts <= task.max_retries {
+            log::info!(
+                "TaskQueue: task {task_id} failed attempt {} on session {session_id}, requeueing",
+                task.attempts
+            );
+            task.state = TaskState::Queued;
+            task.session_id = None;
+        } else {
+            log::warn!("TaskQueue: task {task_id} failed after {} attempts", task.attempts);
+            task.state = TaskState::Failed;
+            task.finished_at = Some(now());
+            task.result = Some(TaskResult {
+                session_id,
+                status: "Error".to_string(),
+                message: error,
+            });
+        }
+        self.updated(task_id, effects);
+    }
+
+    /// Fails a task whose session never became ready. The session, if one
+    /// was enrolled, is dropped from the worker pool.
+    fn fail_spawning(&mut self, task_id: u64, error: String, effects: &mut Vec<Effect>) {
+        let Some(task) = self.tasks.get_mut(&task_id) else { return };
+        if task.state != TaskState::Spawning {
+            return;
+        }
+        log::warn!("TaskQueue: task {task_id} failed to start: {error}");
+        task.state = TaskState::Failed;
+        task.finished_at = Some(now());
+        task.last_error = Some(error);
+        self.workers.retain(|_, w| w.task_id != Some(task_id));
+        self.updated(task_id, effects);
+    }
+
+    /// Returns a task to the queue without counting a failed attempt.
+    fn requeue(&mut self, task_id: u64, effects: &mut Vec<Effect>) {
+        if let Some(task) = self.tasks.get_mut(&task_id) {
+            if task.state.is_active() {
+                task.state = TaskState::Queued;
+                task.session_id = None;
+                self.updated(task_id, effects);
+            }
+        }
+    }
+
+    /// Hands queued tasks to idle workers, or requests new sessions, up to the
+    /// concurrency cap.
+    fn schedule(&mut self, effects: &mut Vec<Effect>) {
+        let mut active = self.tasks.values().filter(|t| t.state.is_active()).count();
+        let queued: Vec<u64> = self
+            .tasks
+            .values()
+            .filter(|t| t.state == TaskState::Queued)
+            .map(|t| t.id)
+            .collect();
+
+        for task_id in queued {
+            if active >= self.config.max_concurrent {
+                break;
+            }
+            let task = &self.tasks[&task_id];
+            let mut candidates: Vec<u32> = self
+                .workers
+                .iter()
+                .filter(|(_, w)| w.idle && w.task_id.is_none() && w.accepts(task))
+                .map(|(id, _)| *id)
+                .collect();
+            candidates.sort_unstable();
+
+            if let Some(&session_id) = candidates.first() {
+                self.assign(task_id, session_id, effects);
+                active += 1;
+            } else if self.config.auto_spawn {
+                let task = self.tasks.get_mut(&task_id).expect("task exists");
+                task.state = TaskState::Spawning;
+                self.spawning_since.insert(task_id, Instant::now());
+                let request = SpawnRequest {
+                    task_id,
+                    project_path: task.project_path.clone(),
+                    branch: task.branch.clone(),
+                    mode: task.mode.clone(),
+                };
+                effects.push(Effect::Event(Box::new(TaskQueueEvent::SpawnRequested { request })));
+                self.updated(task_id, effects);
+                active += 1;
+            }
+        }
+    }
+}
+
+/// Scheduler shared between IPC commands and the status server.
+pub struct TaskQueue {
+    state: Mutex<QueueState>,
+    dispatch_fn: DispatchFn,
+    event_fn: TaskEventFn,
+}
+
+impl TaskQueue {
+    /// Creates an empty queue with the default configuration.
+    pub fn new(dispatch_fn: DispatchFn, event_fn: TaskEventFn) -> Self {
+        Self {
+            state: Mutex::new(QueueState {
+                tasks: BTreeMap::new(),
+                workers: HashMap::new(),
+                spawning_since: HashMap::new(),
+                dispatched: HashMap::new(),
+                next_id: 1,
+                config: TaskQueueConfig {
+                    max_concurrent: DEFAULT_MAX_CONCURRENT,
+                    auto_spawn: true,
+                },
+            }),
+            dispatch_fn,
+            event_fn,
+        }
+    }
+
+    /// Adds a task to the end of the queue and schedules it immediately if a
+    /// worker or spawn slot is available.
+    pub fn enqueue(&self, spec: TaskSpec) -> Result<Task, TaskQueueError> {
+        if spec.prompt.trim().is_empty() {
+            return Err(TaskQueueError::EmptyPrompt);
+        }
+        let id = self.mutate(|state, effects| {
+            let id = state.insert_task(spec, TaskState::Queued);
+            state.updated(id, effects);
+            state.schedule(effects);
+            id
+        });
+        Ok(self.get(id).expect("task was just inserted"))
+    }
+
+    /// Creates a task reserved for a session that was just opened for it,
+    /// enrolling the session as a worker. The prompt is typed in once the
+    /// session reports `Idle`, like a task spawned through a [`SpawnRequest`].
+    pub fn enqueue_for_session(
+        &self,
+        spec: TaskSpec,
+        session_id: u32,
+        branch: Option<String>,
+        mode: AiMode,
+    ) -> Result<Task, TaskQueueError> {
+        if spec.prompt.trim().is_empty() {
+            return Err(TaskQueueError::EmptyPrompt);
+        }
+        let id = self.mutate(|state, effects| {
+            let project_path = spec.project_path.clone();
+            let id = state.insert_task(spec, TaskState::Spawning);
+            if let Some(task) = state.tasks.get_mut(&id) {
+                task.session_id = Some(session_id);
+            }
+            state.workers.insert(
+                session_id,
+                Worker {
+                    project_path,
+                    branch,
+                    mode,
+                    idle: false,
+                    task_id: Some(id),
+                },
+            );
+            state.updated(id, effects);
+            id
+        });
+        Ok(self.get(id).expect("task was just inserted"))
+    }
+
+    /// Returns all tasks in queue order.
+    pub fn list(&self) -> Vec<Task> {
+        self.lock().tasks.values().cloned().collect()
+    }
+
+    /// Returns a single task.
+    pub fn get(&self, task_id: u64) -> Option<Task> {
+        self.lock().tasks.get(&task_id).cloned()
+    }
+
+    /// Cancels a task that has not finished. A session already working on the
+    /// task is left running but is no longer tracked for it.
+    pub fn cancel(&self, task_id: u64) -> Result<Task, TaskQueueError> {
+        self.mutate(|state, effects| {
+            let task = state.tasks.get_mut(&task_id).ok_or(TaskQueueError::NotFound(task_id))?;
+            if task.state.is_finished() {
+                return Err(TaskQueueError::InvalidState {
+                    id: task_id,
+                    state: task.state,
+                    action: "cancelled",
+                });
+            }
+            task.state = TaskState::Cancelled;
+            task.finished_at = Some(now());
+            for worker in state.workers.values_mut() {
+                if worker.task_id == Some(task_id) {
+                    worker.task_id = None;
+                }
+            }
+            state.updated(task_id, effects);
+            state.schedule(effects);
+            Ok(())
+        })?;
+        Ok(self.get(task_id).expect("task exists"))
+    }
+
+    /// Puts a failed or cancelled task back in the queue with a fresh retry budget.
+    pub fn retry(&self, task_id: u64) -> Result<Task, TaskQueueError> {
+        self.mutate(|state, effects| {
+            let task = state.tasks.get_mut(&task_id).ok_or(TaskQueueError::NotFound(task_id))?;
+            if !matches!(task.state, TaskState::Failed | TaskState::Cancelled) {
+                return Err(TaskQueueError::InvalidState {
+                    id: task_id,
+                    state: task.state,
+                    action: "retried",
+                });
+            }
+            task.state = TaskState::Queued;
+            task.attempts = 0;
+            task.session_id = None;
+            task.finished_at = None;
+            task.result = None;
+            state.updated(task_id, effects);
+            state.schedule(effects);
+            Ok(())
+        })?;
+        Ok(self.get(task_id).expect("task exists"))
+    }
+
+    /// Drops all completed, failed and cancelled tasks. Returns how many were removed.
+    pub fn clear_finished(&self) -> usize {
+        let mut state = self.lock();
+        let before = state.tasks.len();
+        state.tasks.retain(|_, t| !t.state.is_finished());
+        before - state.tasks.len()
+    }
+
+    /// Enrolls a session as a worker.
+    ///
+    /// With `for_task`, the session was opened in response to a
+    /// [`SpawnRequest`]: the task is reserved for it and dispatched once the
+    /// session reports `Idle`. Without it, the session is assumed idle and
+    /// may receive work immediately.
+    pub fn enroll_session(
+        &self,
+        session_id: u32,
+        project_path: String,
+        branch: Option<String>,
+        mode: AiMode,
+        for_task: Option<u64>,
+    ) -> Result<(), TaskQueueError> {
+        self.mutate(|state, effects| {
+            if let Some(task_id) = for_task {
+                let task = state.tasks.get_mut(&task_id).ok_or(TaskQueueError::NotFound(task_id))?;
+                if task.state != TaskState::Spawning {
+                    return Err(TaskQueueError::InvalidState {
+                        id: task_id,
+                        state: task.state,
+                        action: "assigned to a new session",
+                    });
+                }
+                task.session_id = Some(session_id);
+                state.updated(task_id, effects);
+            }
+            state.workers.insert(
+                session_id,
+                Worker {
+                    project_path,
+                    branch,
+                    mode,
+                    idle: for_task.is_none(),
+                    task_id: for_task,
+                },
+            );
+            state.schedule(effects);
+            Ok(())
+        })
+    }
+
+    /// Fails a `Spawning` task whose session could not be opened.
+    pub fn fail_spawn(&self, task_id: u64, error: String) {
+        self.mutate(|state, effects| {
+            state.fail_spawning(task_id, error, effects);
+            state.schedule(effects);
+        })
+    }
+
+    /// Fails tasks that have been `Spawning` for longer than `timeout`,
+    /// releasing their concurrency slots. Returns the IDs of the failed tasks.
+    pub fn expire_spawning(&self, timeout: Duration) -> Vec<u64> {
+        self.mutate(|state, effects| {
+            let QueueState { tasks, spawning_since, .. } = &mut *state;
+            spawning_since.retain(|id, _| tasks.get(id).is_some_and(|t| t.state == TaskState::Spawning));
+            let expired: Vec<u64> = state
+                .tasks
+                .values()
+                .filter(|t| {
+                    t.state == TaskState::Spawning
+                        && state.spawning_since.get(&t.id).is_some_and(|since| since.elapsed() >= timeout)
+                })
+                .map(|t| t.id)
+                .collect();
+            for &task_id in &expired {
+                let error = format!("session did not become ready within {}s", timeout.as_secs());
+                state.fail_spawning(task_id, error, effects);
+            }
+            if !expired.is_empty() {
+                state.schedule(effects);
+            }
+            expired
+        })
+    }
+
+    /// Settles tasks that stayed `Dispatched`: a task whose agent reported
+    /// `Idle` early and nothing since is completed once [`DISPATCH_GRACE`]
+    /// has passed, and one without any status for `timeout` counts as a
+    /// failed attempt whose session is dropped from the worker pool. Returns
+    /// the IDs of the failed tasks.
+    pub fn expire_dispatched(&self, timeout: Duration) -> Vec<u64> {
+        self.mutate(|state, effects| {
+            let QueueState { tasks, dispatched, .. } = &mut *state;
+            dispatched.retain(|id, _| tasks.get(id).is_some_and(|t| t.state == TaskState::Dispatched));
+
+            let mut finished = Vec::new();
+            let mut expired = Vec::new();
+            for (&task_id, dispatch) in &state.dispatched {
+                let Some(session_id) = state.tasks[&task_id].session_id else { continue };
+                match dispatch.early_idle {
+                    Some(ref message) if dispatch.since.elapsed() >= DISPATCH_GRACE => {
+                        finished.push((task_id, session_id, message.clone()))
+                    }
+                    None if dispatch.since.elapsed() >= timeout => expired.push((task_id, session_id)),
+                    _ => {}
+                }
+            }
+            for (task_id, session_id, message) in finished {
+                state.dispatched.remove(&task_id);
+                state.complete(task_id, session_id, "Idle", message, effects);
+            }
+            for &(task_id, session_id) in &expired {
+                state.dispatched.remove(&task_id);
+                state.workers.remove(&session_id);
+                let error = format!("agent did not start the task within {}s", timeout.as_secs());
+                state.fail_attempt(task_id, session_id, error, effects);
+            }
+            state.schedule(effects);
+            expired.into_iter().map(|(task_id, _)| task_id).collect()
+        })
+    }
+
+    /// Removes a session from the worker pool. Its in-flight task, if any, is
+    /// returned to the queue.
+    pub fn release_session(&self, session_id: u32) {
+        self.mutate(|state, effects| {
+            if let Some(worker) = state.workers.remove(&session_id) {
+                if let Some(task_id) = worker.task_id {
+                    state.requeue(task_id, effects);
+                }
+                state.schedule(effects);
+            }
+        })
+    }
+
+    /// Returns the current scheduler settings.
+    pub fn config(&self) -> TaskQueueConfig {
+        self.lock().config.clone()
+    }
+
+    /// Updates the scheduler settings and reschedules. A cap of zero pauses
+    /// dispatching without touching running tasks.
+    pub fn set_config(&self, config: TaskQueueConfig) -> TaskQueueConfig {
+        self.mutate(|state, effects| {
+            state.config = config;
+            state.schedule(effects);
+            state.config.clone()
+        })
+    }
+
+    /// Feeds a session status change (as emitted to the frontend) into the
+    /// scheduler. Statuses for sessions that are not enrolled are ignored.
+    pub fn on_session_status(&self, session_id: u32, status: &str, message: &str) {
+        self.mutate(|state, effects| {
+            let Some(worker) = state.workers.get(&session_id) else { return };
+            let task_id = worker.task_id;
+            let task_state = task_id.and_then(|id| state.tasks.get(&id)).map(|t| t.state);
+            let set_worker = |state: &mut QueueState, idle: bool, task_id: Option<u64>| {
+                if let Some(worker) = state.workers.get_mut(&session_id) {
+                    worker.idle = idle;
+                    worker.task_id = task_id;
+                }
+            };
+
+            match (status, task_id, task_state) {
+                ("Working" | "NeedsInput", _, _) => {
+                    set_worker(state, false, task_id);
+                    if let (Some(id), Some(TaskState::Dispatched)) = (task_id, task_state) {
+                        state.dispatched.remove(&id);
+                        if let Some(task) = state.tasks.get_mut(&id) {
+                            task.state = TaskState::Running;
+                        }
+                        state.updated(id, effects);
+                    }
+                }
+                ("Idle" | "Done", Some(id), Some(TaskState::Spawning)) => {
+                    state.assign(id, session_id, effects);
+                }
+                // Right after the dispatch an `Idle` is usually the status from
+                // before the prompt arrived. Keep it: if nothing follows,
+                // `expire_dispatched` takes it as the result.
+                ("Idle", Some(id), Some(TaskState::Dispatched))
+                    if state
+                        .dispatched
+                        .get(&id)
+                        .is_some_and(|d| d.since.elapsed() < DISPATCH_GRACE) =>
+                {
+                    if let Some(dispatch) = state.dispatched.get_mut(&id) {
+                        dispatch.early_idle = Some(message.to_string());
+                    }
+                }
+                ("Idle" | "Done", Some(id), Some(TaskState::Dispatched | TaskState::Running)) => {
+                    state.dispatched.remove(&id);
+                    state.complete(id, session_id, status, message.to_string(), effects);
+                }
+                ("Idle" | "Done", _, _) => set_worker(state, true, None),
+                ("Error", Some(id), Some(TaskState::Dispatched | TaskState::Running)) => {
+                    state.dispatched.remove(&id);
+                    set_worker(state, true, None);
+                    state.fail_attempt(id, session_id, message.to_string(), effects);
+                }
+                ("Error", _, _) => set_worker(state, true, task_id),
+                _ => {}
+            }
+            state.schedule(effects);
+        })
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, QueueState> {
+        self.state.lock().expect("task queue lock poisoned")
+    }
+
+    /// Runs `f` under the state lock, then executes the collected effects.
+    /// PTY write failures are fed back as failed attempts, which may produce
+    /// further effects.
+    fn mutate<R>(&self, f: impl FnOnce(&mut QueueState, &mut Vec<Effect>) -> R) -> R {
+        let mut effects = Vec::new();
+        let result = f(&mut self.lock(), &mut effects);
+
+        while !effects.is_empty() {
+            let mut follow_up = Vec::new();
+            for effect in effects {
+                match effect {
+                    Effect::Event(event) => (self.event_fn)(*event),
+                    Effect::Dispatch { session_id, task_id, text } => {
+                        if let Err(e) = (self.dispatch_fn)(session_id, &text) {
+                            log::warn!("TaskQueue: failed to write task {task_id} to session {session_id}: {e}");
+                            let mut state = self.lock();
+                            state.workers.remove(&session_id);
+                            state.fail_attempt(task_id, session_id, e, &mut follow_up);
+                            state.schedule(&mut follow_up);
+                        }
+                    }
+                }
+            }
+            effects = follow_up;
+        }
+        result
+    }
+}
+
+/// Text typed into the PTY for a prompt, submitted with `\r` (Enter in a
+/// raw-mode TUI). Multi-line prompts are wrapped in a bracketed paste so
+/// embedded newlines don't submit early.
+///
+/// Prompts carry untrusted text (issue bodies, review comments, CI logs), so
+/// ANSI escape sequences and every control character except newline and tab
+/// are removed first: they could end the bracketed paste (`ESC [201~`) or
+/// submit early (`\r`).
+pub(crate) fn prompt_input(prompt: &str) -> String {
+    let prompt: String = strip_ansi(prompt)
+        .chars()
+        .filter(|&c| c == '\n' || c == '\t' || !c.is_control())
+        .collect();
+    if prompt.contains('\n') {
+        format!("\x1b[200~{prompt}\x1b[201~\r")
+    } else {
+        format!("{prompt}\r")
+    }
+}
+
+fn now() -> String {
+    chrono::Utc::now().to_rfc3339()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Writes = Arc<Mutex<Vec<(u32, String)>>>;
+    type Events = Arc<Mutex<Vec<TaskQueueEvent>>>;
+
+    /// Helper: build a queue whose dispatches and events are recorded.
+    /// Writes to sessions listed in `dead_sessions` fail.
+    fn test_queue(dead_sessions: &[u32]) -> (TaskQueue, Writes, Events) {
+        let writes: Writes = Arc::new(Mutex::new(Vec::new()));
+        let events: Events = Arc::new(Mutex::new(Vec::new()));
+        let writes_clone = writes.clone();
+        let events_clone = events.clone();
+        let dead = dead_sessions.to_vec();
+        let queue = TaskQueue::new(
+            Arc::new(move |id, text| {
+                if dead.contains(&id) {
+                    return Err("session not found".to_string());
+                }
+                writes_clone.lock().unwrap().push((id, text.to_string()));
+                Ok(())
+            }),
+            Arc::new(move |e| events_clone.lock().unwrap().push(e)),
+        );
+        (queue, writes, events)
+    }
+
+    fn spec(prompt: &str) -> TaskSpec {
+        TaskSpec {
+            project_path: "/repo".to_string(),
+            prompt: prompt.to_string(),
+            branch: None,
+            mode: None,
+            max_retries: None,
+        }
+    }
+
+    fn no_spawn(queue: &TaskQueue) {
+        queue.set_config(TaskQueueConfig { max_concurrent: 4, auto_spawn: false });
+    }
+
+    fn spawn_requests(events: &Events) -> Vec<u64> {
+        events
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|e| match e {
+                TaskQueueEvent::SpawnRequested { request } => Some(request.task_id),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_dispatches_to_idle_worker_and_completes() {
+        let (queue, writes, _) = test_queue(&[]);
+        no_spawn(&queue);
+        queue.enroll_session(1, "/repo".into(), None, AiMode::Claude, None).unwrap();
+
+        let task = queue.enqueue(spec("fix the tests")).unwrap();
+        assert_eq!(writes.lock().unwrap()[0], (1, "fix the tests\r".to_string()));
+        assert_eq!(queue.get(task.id).unwrap().state, TaskState::Dispatched);
+
+        // Stale Idle before the agent picks the prompt up is ignored.
+        queue.on_session_status(1, "Idle", "Ready");
+        assert_eq!(queue.get(task.id).unwrap().state, TaskState::Dispatched);
+
+        queue.on_session_status(1, "Working", "Running tests");
+        assert_eq!(queue.get(task.id).unwrap().state, TaskState::Running);
+
+        queue.on_session_status(1, "Idle", "All tests pass");
+        let done = queue.get(task.id).unwrap();
+        assert_eq!(done.state, TaskState::Completed);
+        let result = done.result.unwrap();
+        assert_eq!(result.session_id, 1);
+        assert_eq!(result.message, "All tests pass");
+    }
+
+    #[test]
+    fn test_next_task_dispatched_when_worker_frees_up() {
+        let (queue, writes, _) = test_queue(&[]);
+        no_spawn(&queue);
+        queue.enroll_session(1, "/repo".into(), None, AiMode::Claude, None).unwrap();
+
+        let first = queue.enqueue(spec("one")).unwrap();
+        let second = queue.enqueue(spec("two")).unwrap();
+        assert_eq!(queue.get(second.id).unwrap().state, TaskState::Queued);
+
+        queue.on_session_status(1, "Working", "");
+        queue.on_session_status(1, "Done", "finished one");
+
+        assert_eq!(queue.get(first.id).unwrap().state, TaskState::Completed);
+        assert_eq!(queue.get(second.id).unwrap().state, TaskState::Dispatched);
+        assert_eq!(writes.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_branch_and_mode_constraints() {
+        let (queue, writes, _) = test_queue(&[]);
+        no_spawn(&queue);
+        queue.enroll_session(1, "/repo".into(), Some("main".into()), AiMode::Claude, None).unwrap();
+        queue.enroll_session(2, "/repo".into(), Some("feat".into()), AiMode::Codex, None).unwrap();
+        queue.enroll_session(3, "/other".into(), Some("feat".into()), AiMode::Claude, None).unwrap();
+
+        let mut s = spec("on feat");
+        s.branch = Some("feat".into());
+        queue.enqueue(s).unwrap();
+
+        let mut s = spec("needs claude on feat");
+        s.branch = Some("feat".into());
+        s.mode = Some(AiMode::Claude);
+        let unmatched = queue.enqueue(s).unwrap();
+
+        assert_eq!(writes.lock().unwrap().as_slice(), &[(2, "on feat\r".to_string())]);
+        assert_eq!(queue.get(unmatched.id).unwrap().state, TaskState::Queued);
+    }
+
+    #[test]
+    fn test_spawn_requested_up_to_concurrency_cap() {
+        let (queue, writes, events) = test_queue(&[]);
+        queue.set_config(TaskQueueConfig { max_concurrent: 2, auto_spawn: true });
+
+        let a = queue.enqueue(spec("a")).unwrap();
+        let b = queue.enqueue(spec("b")).unwrap();
+        let c = queue.enqueue(spec("c")).unwrap();
+        assert_eq!(spawn_requests(&events), vec![a.id, b.id]);
+        assert_eq!(queue.get(c.id).unwrap().state, TaskState::Queued);
+
+        // The spawned session enrolls, then reports ready once the agent starts.
+        queue.enroll_session(7, "/repo".into(), None, AiMode::Claude, Some(a.id)).unwrap();
+        assert!(writes.lock().unwrap().is_empty());
+        queue.on_session_status(7, "Idle", "Ready");
+        assert_eq!(writes.lock().unwrap()[0], (7, "a\r".to_string()));
+
+        // Finishing `a` frees a slot for `c`.
+        queue.on_session_status(7, "Working", "");
+        queue.on_session_status(7, "Done", "");
+        assert_eq!(queue.get(c.id).unwrap().state, TaskState::Dispatched);
+        assert_eq!(queue.get(c.id).unwrap().session_id, Some(7));
+    }
+
+    #[test]
+    fn test_error_retries_then_fails() {
+        let (queue, _, _) = test_queue(&[]);
+        no_spawn(&queue);
+        queue.enroll_session(1, "/repo".into(), None, AiMode::Claude, None).unwrap();
+
+        let mut s = spec("flaky");
+        s.max_retries = Some(1);
+        let task = queue.enqueue(s).unwrap();
+
+        queue.on_session_status(1, "Working", "");
+        queue.on_session_status(1, "Error", "build broke");
+        let retried = queue.get(task.id).unwrap();
+        assert_eq!(retried.state, TaskState::Dispatched, "requeued and redispatched");
+        assert_eq!(retried.attempts, 2);
+        assert_eq!(retried.last_error.as_deref(), Some("build broke"));
+
+        queue.on_session_status(1, "Working", "");
+        queue.on_session_status(1, "Error", "still broken");
+        let failed = queue.get(task.id).unwrap();
+        assert_eq!(failed.state, TaskState::Failed);
+        assert_eq!(failed.result.unwrap().message, "still broken");
+
+        let again = queue.retry(task.id).unwrap();
+        assert_eq!(again.state, TaskState::Dispatched);
+        assert_eq!(again.attempts, 1);
+    }
+
+    #[test]
+    fn test_dispatch_failure_drops_worker_and_requeues() {
+        let (queue, writes, _) = test_queue(&[1]);
+        no_spawn(&queue);
+        queue.enroll_session(1, "/repo".into(), None, AiMode::Claude, None).unwrap();
+        queue.enroll_session(2, "/repo".into(), None, AiMode::Claude, None).unwrap();
+
+        let task = queue.enqueue(spec("go")).unwrap();
+        let task = queue.get(task.id).unwrap();
+        assert_eq!(task.session_id, Some(2));
+        assert_eq!(task.attempts, 2);
+        assert_eq!(writes.lock().unwrap().as_slice(), &[(2, "go\r".to_string())]);
+    }
+
+    #[test]
+    fn test_release_and_cancel() {
+        let (queue, _, _) = test_queue(&[]);
+        no_spawn(&queue);
+        queue.enroll_session(1, "/repo".into(), None, AiMode::Claude, None).unwrap();
+        let task = queue.enqueue(spec("work")).unwrap();
+
+        queue.release_session(1);
+        let requeued = queue.get(task.id).unwrap();
+        assert_eq!(requeued.state, TaskState::Queued);
+        assert_eq!(requeued.session_id, None);
+
+        let cancelled = queue.cancel(task.id).unwrap();
+        assert_eq!(cancelled.state, TaskState::Cancelled);
+        assert!(matches!(
+            queue.cancel(task.id),
+            Err(TaskQueueError::InvalidState { .. })
+        ));
+        assert_eq!(queue.clear_finished(), 1);
+        assert!(queue.list().is_empty());
+    }
+
+    #[test]
+    fn test_enqueue_for_session_waits_for_ready() {
+        let (queue, writes, events) = test_queue(&[]);
+        let task = queue
+            .enqueue_for_session(spec("seed"), 3, Some("issue-1".into()), AiMode::Claude)
+            .unwrap();
+        assert_eq!(task.state, TaskState::Spawning);
+        assert!(spawn_requests(&events).is_empty());
+        assert!(writes.lock().unwrap().is_empty());
+
+        queue.on_session_status(3, "Idle", "Ready");
+        assert_eq!(writes.lock().unwrap()[0], (3, "seed\r".to_string()));
+    }
+
+    #[test]
+    fn test_spawn_timeout_fails_task_and_frees_slot() {
+        let (queue, writes, events) = test_queue(&[]);
+        queue.set_config(TaskQueueConfig { max_concurrent: 1, auto_spawn: true });
+
+        let stuck = queue.enqueue(spec("stuck")).unwrap();
+        let next = queue.enqueue(spec("next")).unwrap();
+        queue.enroll_session(7, "/repo".into(), None, AiMode::Claude, Some(stuck.id)).unwrap();
+        assert!(queue.expire_spawning(SPAWN_TIMEOUT).is_empty());
+
+        assert_eq!(queue.expire_spawning(Duration::ZERO), vec![stuck.id]);
+        let failed = queue.get(stuck.id).unwrap();
+        assert_eq!(failed.state, TaskState::Failed);
+        assert!(failed.last_error.unwrap().contains("did not become ready"));
+        assert_eq!(spawn_requests(&events), vec![stuck.id, next.id]);
+
+        // The unresponsive session is no longer a worker.
+        queue.on_session_status(7, "Idle", "Ready");
+        assert!(writes.lock().unwrap().is_empty());
+    }
+
+    /// Moves the task's dispatch time back past [`DISPATCH_GRACE`].
+    fn age_dispatch(queue: &TaskQueue, task_id: u64) {
+        let mut state = queue.lock();
+        let dispatch = state.dispatched.get_mut(&task_id).unwrap();
+        dispatch.since -= DISPATCH_GRACE;
+    }
+
+    #[test]
+    fn test_dispatch_followed_directly_by_idle_completes() {
+        let (queue, writes, _) = test_queue(&[]);
+        no_spawn(&queue);
+        queue.enroll_session(1, "/repo".into(), None, AiMode::Claude, None).unwrap();
+        let quick = queue.enqueue(spec("bump the version")).unwrap();
+
+        // The agent finished before its first Working report was seen
+        queue.on_session_status(1, "Idle", "Bumped to 1.2.0");
+        assert_eq!(queue.get(quick.id).unwrap().state, TaskState::Dispatched);
+        assert!(queue.expire_dispatched(DISPATCH_TIMEOUT).is_empty());
+        assert_eq!(queue.get(quick.id).unwrap().state, TaskState::Dispatched);
+        age_dispatch(&queue, quick.id);
+        assert!(queue.expire_dispatched(DISPATCH_TIMEOUT).is_empty());
+        let done = queue.get(quick.id).unwrap();
+        assert_eq!(done.state, TaskState::Completed);
+        assert_eq!(done.result.unwrap().message, "Bumped to 1.2.0");
+
+        // An Idle arriving after the grace period completes right away
+        let next = queue.enqueue(spec("tag it")).unwrap();
+        assert_eq!(writes.lock().unwrap().len(), 2, "worker freed for the next task");
+        age_dispatch(&queue, next.id);
+        queue.on_session_status(1, "Idle", "Tagged");
+        assert_eq!(queue.get(next.id).unwrap().state, TaskState::Completed);
+    }
+
+    #[test]
+    fn test_silent_dispatch_times_out_and_frees_slot() {
+        let (queue, _, _) = test_queue(&[]);
+        no_spawn(&queue);
+        queue.enroll_session(1, "/repo".into(), None, AiMode::Claude, None).unwrap();
+        let task = queue.enqueue(spec("hang")).unwrap();
+
+        assert_eq!(queue.expire_dispatched(Duration::ZERO), vec![task.id]);
+        let requeued = queue.get(task.id).unwrap();
+        assert_eq!(requeued.state, TaskState::Queued, "one retry left");
+        assert!(requeued.last_error.unwrap().contains("did not start"));
+        // The silent session is no longer a worker.
+        queue.on_session_status(1, "Idle", "Ready");
+        assert_eq!(queue.get(task.id).unwrap().state, TaskState::Queued);
+    }
+
+    #[test]
+    fn test_fail_spawn_frees_slot() {
+        let (queue, _, events) = test_queue(&[]);
+        queue.set_config(TaskQueueConfig { max_concurrent: 1, auto_spawn: true });
+
+        let a = queue.enqueue(spec("a")).unwrap();
+        let b = queue.enqueue(spec("b")).unwrap();
+        queue.fail_spawn(a.id, "no worktree".to_string());
+
+        assert_eq!(queue.get(a.id).unwrap().state, TaskState::Failed);
+        assert_eq!(queue.get(b.id).unwrap().state, TaskState::Spawning);
+        assert_eq!(spawn_requests(&events), vec![a.id, b.id]);
+    }
+
+    #[test]
+    fn test_prompt_input_submits_with_carriage_return() {
+        assert_eq!(prompt_input("a\nb"), "\x1b[200~a\nb\x1b[201~\r");
+        assert_eq!(prompt_input("single"), "single\r");
+    }
+
+    #[test]
+    fn test_prompt_input_strips_escape_sequences_and_control_chars() {
+        assert_eq!(
+            prompt_input("done\x1b[201~\rrm -rf ~\x03\x04\r\nnext\tline"),
+            "\x1b[200~donerm -rf ~\nnext\tline\x1b[201~\r"
+        );
+        assert_eq!(prompt_input("one\rline\x1b[31m"), "oneline\r");
+    }
+
+    #[test]
+    fn test_empty_prompt_rejected() {
+        let (queue, _, _) = test_queue(&[]);
+        assert!(matches!(queue.enqueue(spec("  ")), Err(TaskQueueError::EmptyPrompt)));
+    }
+}
@@ -0,0 +1,795 @@
+//! Bootstraps freshly created worktrees so agents can start working at once.
+//!
+//! `git worktree add` only checks out tracked files, so a new worktree lacks
+//! `.env` files, installed dependencies and build caches. A per-project
+//! [`BootstrapSpec`] lists heavy directories to clone copy-on-write (see
+//! [`super::fast_clone`]), untracked paths to copy or symlink from the main
+//! checkout and setup commands (e.g. `npm ci`) to run afterwards. Command
+//! output is streamed line by line and every step is recorded in a
+//! [`BootstrapReport`].
+
+use std::collections::VecDeque;
+use std::path::{Component, Path, PathBuf};
+use std::process::Stdio;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+use tokio::process::Command;
+
+use super::fast_clone::{clone_tree, CloneDir, CloneStats};
+use super::windows_process::TokioCommandExt;
+use crate::git::Git;
+
+/// Default time limit for each setup command.
+pub const DEFAULT_COMMAND_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// Output lines kept for the failure message of a command.
+const FAILURE_TAIL_LINES: usize = 20;
+
+/// What to set up in a new worktree. Paths are relative to the repository
+/// root and must stay inside it.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct BootstrapSpec {
+    /// Large ignored directories (e.g. `target`, `node_modules`) cloned
+    /// from the main checkout before anything else, so builds start warm.
+    pub clone: Vec<CloneDir>,
+    /// Files or directories copied from the main checkout (e.g. `.env`).
+    pub copy: Vec<String>,
+    /// Files or directories symlinked to the main checkout (e.g. large,
+    /// read-mostly caches).
+    pub symlink: Vec<String>,
+    /// Shell commands run in the worktree, in order, after copying.
+    pub commands: Vec<String>,
+    /// Per-command time limit; defaults to [`DEFAULT_COMMAND_TIMEOUT`].
+    pub command_timeout_secs: Option<u64>,
+}
+
+impl BootstrapSpec {
+    /// Whether the spec has nothing to do.
+    pub fn is_empty(&self) -> bool {
+        self.clone.is_empty()
+            && self.copy.is_empty()
+            && self.symlink.is_empty()
+            && self.commands.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BootstrapStepKind {
+    Clone,
+    Copy,
+    Symlink,
+    Command,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BootstrapStepStatus {
+    Succeeded,
+    /// Nothing to do (source missing, destination already present, or an
+    /// earlier command failed).
+    Skipped,
+    Failed,
+}
+
+/// Outcome of one copy, symlink or command.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BootstrapStep {
+    pub kind: BootstrapStepKind,
+    /// The path or command.
+    pub target: String,
+    pub status: BootstrapStepStatus,
+    /// Why the step was skipped or failed. For failed commands this
+    /// includes the last lines of output.
+    pub message: Option<String>,
+    pub exit_code: Option<i32>,
+    pub duration_ms: u64,
+    /// Files, bytes and methods used by a successful clone step.
+    pub clone: Option<CloneStats>,
+}
+
+/// Outcome of bootstrapping one worktree.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BootstrapReport {
+    pub worktree_path: String,
+    pub steps: Vec<BootstrapStep>,
+    /// `false` if any step failed.
+    pub success: bool,
+}
+
+impl BootstrapReport {
+    /// One-line description of the failed steps, if any.
+    pub fn failure_summary(&self) -> Option<String> {
+        let failed: Vec<String> = self
+            .steps
+            .iter()
+            .filter(|s| s.status == BootstrapStepStatus::Failed)
+            .map(|s| s.target.clone())
+            .collect();
+        (!failed.is_empty()).then(|| format!("Worktree bootstrap failed: {}", failed.join(", ")))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputStream {
+    Stdout,
+    Stderr,
+}
+
+/// A line of output from a setup command.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BootstrapOutput {
+    pub worktree_path: String,
+    pub command: String,
+    pub stream: OutputStream,
+    pub line: String,
+}
+
+/// Copies and symlinks the spec's paths from `main_checkout` into
+/// `worktree`, then runs its commands there, reporting command output
+/// through `on_output`.
+///
+/// Copied and symlinked paths are added to the repository's exclude file so
+/// `git add -A` never stages them. A symlinked `node_modules` is a file to
+/// git and would slip past a `node_modules/` ignore pattern otherwise.
+///
+/// Existing destinations are never overwritten. Commands stop at the first
+/// failure since later ones usually depend on it; the rest are reported as
+/// skipped.
+pub async fn run_bootstrap(
+    spec: &BootstrapSpec,
+    main_checkout: &Path,
+    worktree: &Path,
+    mut on_output: impl FnMut(BootstrapOutput),
+) -> BootstrapReport {
+    let mut steps = Vec::new();
+
+    for dir in &spec.clone {
+        let started = Instant::now();
+        let (result, stats) = clone_dir(dir, main_checkout, worktree).await;
+        let mut clone_step = step(BootstrapStepKind::Clone, &dir.path, started, result, None);
+        clone_step.clone = stats;
+        steps.push(clone_step);
+    }
+
+    for (kind, paths) in [
+        (BootstrapStepKind::Copy, &spec.copy),
+        (BootstrapStepKind::Symlink, &spec.symlink),
+    ] {
+        for path in paths {
+            let started = Instant::now();
+            let result = link_or_copy(kind, path, main_checkout, worktree).await;
+            steps.push(step(kind, path, started, result, None));
+        }
+    }
+
+    let placed: Vec<&str> = steps
+        .iter()
+        .filter(|s| {
+            matches!(s.kind, BootstrapStepKind::Copy | BootstrapStepKind::Symlink)
+                && s.status == BootstrapStepStatus::Succeeded
+        })
+        .map(|s| s.target.as_str())
+        .collect();
+    if !placed.is_empty() {
+        if let Err(e) = exclude_paths(worktree, &placed).await {
+            log::warn!(
+                "Failed to exclude bootstrapped paths in {}: {}",
+                worktree.display(),
+                e
+            );
+        }
+    }
+
+    let timeout = spec
+        .command_timeout_secs
+        .map(Duration::from_secs)
+        .unwrap_or(DEFAULT_COMMAND_TIMEOUT);
+    let mut failed = false;
+    for command in &spec.commands {
+        if failed {
+            steps.push(BootstrapStep {
+                kind: BootstrapStepKind::Command,
+                target: command.clone(),
+                status: BootstrapStepStatus::Skipped,
+                message: Some("an earlier command failed".to_string()),
+                exit_code: None,
+                duration_ms: 0,
+                clone: None,
+            });
+            continue;
+        }
+        let started = Instant::now();
+        let (result, exit_code) =
+            run_command(command, main_checkout, worktree, timeout, &mut on_output).await;
+        failed = result.is_err();
+        steps.push(step(
+            BootstrapStepKind::Command,
+            command,
+            started,
+            result,
+            exit_code,
+        ));
+    }
+
+    let success = steps
+        .iter()
+        .all(|s| s.status != BootstrapStepStatus::Failed);
+    BootstrapReport {
+        worktree_path: worktree.to_string_lossy().to_string(),
+        steps,
+        success,
+    }
+}
+
+/// Result of a step before timing is attached: `Ok(None)` succeeded,
+/// `Ok(Some(reason))` skipped, `Err(reason)` failed.
+type StepResult = Result<Option<String>, String>;
+
+fn step(
+    kind: BootstrapStepKind,
+    target: &str,
+    started: Instant,
+    result: StepResult,
+    exit_code: Option<i32>,
+) -> BootstrapStep {
+    let (status, message) = match result {
+        Ok(None) => (BootstrapStepStatus::Succeeded, None),
+        Ok(Some(reason)) => (BootstrapStepStatus::Skipped, Some(reason)),
+        Err(reason) => (BootstrapStepStatus::Failed, Some(reason)),
+    };
+    BootstrapStep {
+        kind,
+        target: target.to_string(),
+        status,
+        message,
+        exit_code,
+        duration_ms: started.elapsed().as_millis() as u64,
+        clone: None,
+    }
+}
+
+/// Rejects absolute paths and paths that climb out of the repository.
+fn relative_path(path: &str) -> Result<PathBuf, String> {
+    let path = Path::new(path);
+    let valid = !path.as_os_str().is_empty()
+        && path
+            .components()
+            .all(|c| matches!(c, Component::Normal(_) | Component::CurDir));
+    if valid {
+        Ok(path.to_path_buf())
+    } else {
+        Err("path must be relative to the repository root".to_string())
+    }
+}
+
+async fn link_or_copy(
+    kind: BootstrapStepKind,
+    path: &str,
+    main_checkout: &Path,
+    worktree: &Path,
+) -> StepResult {
+    let relative = relative_path(path)?;
+    let source = main_checkout.join(&relative);
+    let dest = worktree.join(&relative);
+
+    if tokio::fs::symlink_metadata(&source).await.is_err() {
+        return Ok(Some("not found in the main checkout".to_string()));
+    }
+    if tokio::fs::symlink_metadata(&dest).await.is_ok() {
+        return Ok(Some("already exists in the worktree".to_string()));
+    }
+
+    tokio::task::spawn_blocking(move || {
+        if let Some(parent) = dest.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        match kind {
+            BootstrapStepKind::Symlink => symlink(&source, &dest),
+            _ => copy_recursive(&source, &dest),
+        }
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map(|_| None)
+    .map_err(|e| e.to_string())
+}
+
+async fn clone_dir(
+    dir: &CloneDir,
+    main_checkout: &Path,
+    worktree: &Path,
+) -> (StepResult, Option<CloneStats>) {
+    let relative = match relative_path(&dir.path) {
+        Ok(relative) => relative,
+        Err(e) => return (Err(e), None),
+    };
+    let source = main_checkout.join(&relative);
+    let dest = worktree.join(&relative);
+
+    if tokio::fs::symlink_metadata(&source).await.is_err() {
+        return (Ok(Some("not found in the main checkout".to_string())), None);
+    }
+    if tokio::fs::symlink_metadata(&dest).await.is_ok() {
+        return (Ok(Some("already exists in the worktree".to_string())), None);
+    }
+
+    let allow_hardlink = dir.allow_hardlink;
+    let cloned = tokio::task::spawn_blocking(move || {
+        if let Some(parent) = dest.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let result = clone_tree(&source, &dest, allow_hardlink);
+        if result.is_err() {
+            // Don't leave a half-cloned directory behind for tools to trip over.
+            let _ = std::fs::remove_dir_all(&dest);
+        }
+        result
+    })
+    .await;
+
+    match cloned {
+        Ok(Ok(stats)) => (Ok(None), Some(stats)),
+        Ok(Err(e)) => (Err(e.to_string()), None),
+        Err(e) => (Err(e.to_string()), None),
+    }
+}
+
+/// Appends anchored patterns for `paths` to the exclude file of the
+/// repository at `worktree` (`info/exclude`, shared by all its worktrees),
+/// skipping patterns already listed.
+async fn exclude_paths(worktree: &Path, paths: &[&str]) -> Result<(), String> {
+    let git = Git::new(worktree);
+    let exclude = git
+        .run(&["rev-parse", "--git-path", "info/exclude"])
+        .await
+        .map_err(|e| e.to_string())?;
+    let exclude = worktree.join(exclude.trimmed());
+
+    let mut content = match tokio::fs::read_to_string(&exclude).await {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e.to_string()),
+    };
+    let original_len = content.len();
+    for path in paths {
+        let pattern = exclude_pattern(path);
+        if content.lines().any(|line| line == pattern) {
+            continue;
+        }
+        if !content.is_empty() && !content.ends_with('\n') {
+            content.push('\n');
+        }
+        content.push_str(&pattern);
+        content.push('\n');
+    }
+    if content.len() == original_len {
+        return Ok(());
+    }
+
+    if let Some(parent) = exclude.parent() {
+        tokio::fs::create_dir_all(parent)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+    tokio::fs::write(&exclude, content)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Gitignore pattern matching exactly `path` from the repository root: a
+/// leading `/` anchors it, and no trailing slash lets it match a symlink as
+/// well as a directory. Glob characters are escaped.
+fn exclude_pattern(path: &str) -> String {
+    let mut pattern = String::new();
+    for component in Path::new(path).components() {
+        if let Component::Normal(part) = component {
+            pattern.push('/');
+            for c in part.to_string_lossy().chars() {
+                if matches!(c, '*' | '?' | '[' | '\\') {
+                    pattern.push('\\');
+                }
+                pattern.push(c);
+            }
+        }
+    }
+    pattern
+}
+
+/// Copies a file or directory tree. Symlinks inside the tree are recreated
+/// rather than followed.
+fn copy_recursive(source: &Path, dest: &Path) -> std::io::Result<()> {
+    let meta = std::fs::symlink_metadata(source)?;
+    if meta.file_type().is_symlink() {
+        let target = std::fs::read_link(source)?;
+        let target = if target.is_absolute() {
+            target
+        } else {
+            source.parent().unwrap_or(source).join(target)
+        };
+        return symlink(&target, dest);
+    }
+    if meta.is_dir() {
+        std::fs::create_dir_all(dest)?;
+        for entry in std::fs::read_dir(source)? {
+            let entry = entry?;
+            copy_recursive(&entry.path(), &dest.join(entry.file_name()))?;
+        }
+        return Ok(());
+    }
+    std::fs::copy(source, dest).map(|_| ())
+}
+
+#[cfg(unix)]
+fn symlink(target: &Path, link: &Path) -> std::io::Result<()> {
+    std::os::unix::fs::symlink(target, link)
+}
+
+#[cfg(windows)]
+fn symlink(target: &Path, link: &Path) -> std::io::Result<()> {
+    if target.is_dir() {
+        std::os::windows::fs::symlink_dir(target, link)
+    } else {
+        std::os::windows::fs::symlink_file(target, link)
+    }
+}
+
+/// Builds the shell invocation for a setup command. On Unix the user's
+/// login shell is used so tools installed via the shell profile (nvm,
+/// asdf, Homebrew) are on `PATH`, which GUI apps do not inherit.
+fn shell_command(command: &str) -> Command {
+    #[cfg(windows)]
+    {
+        let mut cmd = Command::new("cmd");
+        cmd.args(["/C", command]);
+        cmd
+    }
+    #[cfg(not(windows))]
+    {
+        let shell = std::env::var("SHELL").unwrap_or_else(|_| "/bin/sh".to_string());
+        let mut cmd = Command::new(shell);
+        cmd.args(["-lc", command]);
+        cmd
+    }
+}
+
+async fn run_command(
+    command: &str,
+    main_checkout: &Path,
+    worktree: &Path,
+    timeout: Duration,
+    on_output: &mut impl FnMut(BootstrapOutput),
+) -> (StepResult, Option<i32>) {
+    let mut cmd = shell_command(command);
+    cmd.current_dir(worktree)
+        .env("MAESTRO_MAIN_CHECKOUT", main_checkout)
+        .env("MAESTRO_WORKTREE", worktree)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .hide_console_window();
+
+    let mut child = match cmd.spawn() {
+        Ok(child) => child,
+        Err(e) => return (Err(format!("failed to start: {}", e)), None),
+    };
+    let (Some(stdout), Some(stderr)) = (child.stdout.take(), child.stderr.take()) else {
+        return (Err("failed to capture output".to_string()), None);
+    };
+
+    let worktree_path = worktree.to_string_lossy().to_string();
+    let mut tail: VecDeque<String> = VecDeque::with_capacity(FAILURE_TAIL_LINES);
+    let mut emit = |stream: OutputStream, line: String| {
+        if tail.len() == FAILURE_TAIL_LINES {
+            tail.pop_front();
+        }
+        tail.push_back(line.clone());
+        on_output(BootstrapOutput {
+            worktree_path: worktree_path.clone(),
+            command: command.to_string(),
+            stream,
+            line,
+        });
+    };
+
+    let run = async {
+        let mut stdout = lines(stdout);
+        let mut stderr = lines(stderr);
+        let (mut stdout_done, mut stderr_done) = (false, false);
+        while !(stdout_done && stderr_done) {
+            tokio::select! {
+                line = next_line(&mut stdout), if !stdout_done => match line {
+                    Some(line) => emit(OutputStream::Stdout, line),
+                    None => stdout_done = true,
+                },
+                line = next_line(&mut stderr), if !stderr_done => match line {
+                    Some(line) => emit(OutputStream::Stderr, line),
+                    None => stderr_done = true,
+                },
+            }
+        }
+        child.wait().await
+    };
+
+    let result = tokio::time::timeout(timeout, run).await;
+    let tail_text = tail.into_iter().collect::<Vec<_>>().join("\n");
+    match result {
+        Err(_) => (Err(format!("timed out after {}s", timeout.as_secs())), None),
+        Ok(Err(e)) => (Err(e.to_string()), None),
+        Ok(Ok(status)) if status.success() => (Ok(None), status.code()),
+        Ok(Ok(status)) => {
+            let code = status.code();
+            let mut message = match code {
+                Some(code) => format!("exited with code {}", code),
+                None => "terminated by a signal".to_string(),
+            };
+            if !tail_text.is_empty() {
+                message.push('\n');
+                message.push_str(&tail_text);
+            }
+            (Err(message), code)
+        }
+    }
+}
+
+fn lines<R: AsyncRead + Unpin>(reader: R) -> tokio::io::Split<BufReader<R>> {
+    BufReader::new(reader).split(b'\n')
+}
+
+/// Reads the next line, decoding invalid UTF-8 lossily. `None` at EOF.
+async fn next_line<R: AsyncRead + Unpin>(
+    lines: &mut tokio::io::Split<BufReader<R>>,
+) -> Option<String> {
+    let bytes = lines.next_segment().await.ok()??;
+    let line = String::from_utf8_lossy(&bytes);
+    Some(line.trim_end_matches('\r').to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn dirs() -> (tempfile::TempDir, PathBuf, PathBuf) {
+        let dir = tempdir().unwrap();
+        let main = dir.path().join("main");
+        let wt = dir.path().join("wt");
+        std::fs::create_dir_all(main.join("cache/nested")).unwrap();
+        std::fs::create_dir_all(&wt).unwrap();
+        std::fs::write(main.join(".env"), "KEY=1\n").unwrap();
+        std::fs::write(main.join("cache/nested/data"), "x").unwrap();
+        (dir, main, wt)
+    }
+
+    #[test]
+    fn test_exclude_pattern() {
+        assert_eq!(exclude_pattern(".env"), "/.env");
+        assert_eq!(exclude_pattern("./config/local.json"), "/config/local.json");
+        assert_eq!(exclude_pattern("node_modules/"), "/node_modules");
+        assert_eq!(exclude_pattern("data[1]*.db"), "/data\\[1]\\*.db");
+    }
+
+    #[tokio::test]
+    async fn test_bootstrapped_paths_are_not_staged() {
+        let (dir, main, _) = dirs();
+        let git = Git::new(&main);
+        git.run(&["init", "-b", "main"]).await.unwrap();
+        git.run(&["config", "user.email", "test@test.com"]).await.unwrap();
+        git.run(&["config", "user.name", "Test"]).await.unwrap();
+        std::fs::write(main.join(".gitignore"), ".env\ncache/\nnode_modules/\n").unwrap();
+        std::fs::create_dir_all(main.join("node_modules/pkg")).unwrap();
+        std::fs::write(main.join("node_modules/pkg/index.js"), "").unwrap();
+        git.run(&["add", ".gitignore"]).await.unwrap();
+        git.run(&["commit", "-m", "initial"]).await.unwrap();
+
+        let wt = dir.path().join("linked");
+        git.run(&["worktree", "add", "-b", "feature", &wt.to_string_lossy()])
+            .await
+            .unwrap();
+        let spec = BootstrapSpec {
+            copy: vec![".env".into()],
+            symlink: vec!["node_modules".into(), "./cache".into()],
+            ..Default::default()
+        };
+        let report = run_bootstrap(&spec, &main, &wt, |_| {}).await;
+        assert!(report.success);
+        // Listed patterns are not added twice
+        exclude_paths(&wt, &[".env"]).await.unwrap();
+
+        let wt_git = Git::new(&wt);
+        wt_git.stage_all().await.unwrap();
+        assert!(wt_git.staged_files().await.unwrap().is_empty());
+
+        let exclude = std::fs::read_to_string(main.join(".git/info/exclude")).unwrap();
+        let patterns: Vec<_> = exclude.lines().filter(|l| l.starts_with('/')).collect();
+        assert_eq!(patterns, vec!["/.env", "/node_modules", "/cache"]);
+    }
+
+    #[test]
+    fn test_relative_path_validation() {
+        assert!(relative_path(".env").is_ok());
+        assert!(relative_path("./config/local.json").is_ok());
+        assert!(relative_path("").is_err());
+        assert!(relative_path("../secrets").is_err());
+        assert!(relative_path("a/../../b").is_err());
+        assert!(relative_path("/etc/passwd").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_copy_and_symlink() {
+        let (_dir, main, wt) = dirs();
+        std::fs::write(wt.join("existing"), "keep").unwrap();
+        std::fs::write(main.join("existing"), "replace").unwrap();
+
+        let spec = BootstrapSpec {
+            copy: vec![
+                ".env".into(),
+                "missing.txt".into(),
+                "existing".into(),
+                "../x".into(),
+            ],
+            symlink: vec!["cache".into()],
+            ..Default::default()
+        };
+        let report = run_bootstrap(&spec, &main, &wt, |_| {}).await;
+
+        let statuses: Vec<_> = report.steps.iter().map(|s| s.status).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                BootstrapStepStatus::Succeeded,
+                BootstrapStepStatus::Skipped,
+                BootstrapStepStatus::Skipped,
+                BootstrapStepStatus::Failed,
+                BootstrapStepStatus::Succeeded,
+            ]
+        );
+        assert!(!report.success);
+        assert_eq!(std::fs::read_to_string(wt.join(".env")).unwrap(), "KEY=1\n");
+        assert_eq!(
+            std::fs::read_to_string(wt.join("existing")).unwrap(),
+            "keep"
+        );
+        assert!(std::fs::symlink_metadata(wt.join("cache"))
+            .unwrap()
+            .file_type()
+            .is_symlink());
+        assert_eq!(
+            std::fs::read_to_string(wt.join("cache/nested/data")).unwrap(),
+            "x"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_copy_directory_tree() {
+        let (_dir, main, wt) = dirs();
+        let spec = BootstrapSpec {
+            copy: vec!["cache".into()],
+            ..Default::default()
+        };
+        let report = run_bootstrap(&spec, &main, &wt, |_| {}).await;
+        assert!(report.success);
+        assert!(!std::fs::symlink_metadata(wt.join("cache"))
+            .unwrap()
+            .file_type()
+            .is_symlink());
+        assert_eq!(
+            std::fs::read_to_string(wt.join("cache/nested/data")).unwrap(),
+            "x"
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_commands_stream_output_and_stop_on_failure() {
+        let (_dir, main, wt) = dirs();
+        let spec = BootstrapSpec {
+            commands: vec![
+                "echo out; echo err >&2; pwd > where.txt; echo \"$MAESTRO_MAIN_CHECKOUT\" > main.txt".into(),
+                "echo failing; exit 3".into(),
+                "touch never".into(),
+            ],
+            ..Default::default()
+        };
+        let mut output = Vec::new();
+        let report = run_bootstrap(&spec, &main, &wt, |o| output.push((o.stream, o.line))).await;
+
+        assert!(!report.success);
+        assert_eq!(report.steps[0].status, BootstrapStepStatus::Succeeded);
+        assert_eq!(report.steps[1].status, BootstrapStepStatus::Failed);
+        assert_eq!(report.steps[1].exit_code, Some(3));
+        assert!(report.steps[1]
+            .message
+            .as_deref()
+            .unwrap()
+            .contains("failing"));
+        assert_eq!(report.steps[2].status, BootstrapStepStatus::Skipped);
+        assert!(!wt.join("never").exists());
+        assert_eq!(
+            report.failure_summary().unwrap(),
+            "Worktree bootstrap failed: echo failing; exit 3"
+        );
+
+        assert!(output.contains(&(OutputStream::Stdout, "out".to_string())));
+        assert!(output.contains(&(OutputStream::Stderr, "err".to_string())));
+        let cwd = std::fs::read_to_string(wt.join("where.txt")).unwrap();
+        assert_eq!(
+            Path::new(cwd.trim()).canonicalize().unwrap(),
+            wt.canonicalize().unwrap()
+        );
+        let main_env = std::fs::read_to_string(wt.join("main.txt")).unwrap();
+        assert_eq!(main_env.trim(), main.to_string_lossy());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_command_timeout() {
+        let (_dir, main, wt) = dirs();
+        let spec = BootstrapSpec {
+            commands: vec!["sleep 5".into()],
+            command_timeout_secs: Some(1),
+            ..Default::default()
+        };
+        let report = run_bootstrap(&spec, &main, &wt, |_| {}).await;
+        assert_eq!(report.steps[0].status, BootstrapStepStatus::Failed);
+        assert_eq!(
+            report.steps[0].message.as_deref(),
+            Some("timed out after 1s")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_clone_step_reports_stats() {
+        let (_dir, main, wt) = dirs();
+        std::fs::create_dir_all(main.join("target/debug")).unwrap();
+        std::fs::write(main.join("target/debug/app"), "0123456789").unwrap();
+        std::fs::create_dir_all(wt.join("node_modules")).unwrap();
+        std::fs::create_dir_all(main.join("node_modules")).unwrap();
+
+        let spec = BootstrapSpec {
+            clone: vec![
+                CloneDir {
+                    path: "target".into(),
+                    allow_hardlink: false,
+                },
+                CloneDir {
+                    path: "node_modules".into(),
+                    allow_hardlink: true,
+                },
+            ],
+            ..Default::default()
+        };
+        let report = run_bootstrap(&spec, &main, &wt, |_| {}).await;
+
+        assert!(report.success);
+        assert_eq!(report.steps[0].kind, BootstrapStepKind::Clone);
+        assert_eq!(report.steps[0].status, BootstrapStepStatus::Succeeded);
+        let stats = report.steps[0].clone.unwrap();
+        assert_eq!(stats.size.files, 1);
+        assert_eq!(stats.size.bytes, 10);
+        assert_eq!(
+            std::fs::read_to_string(wt.join("target/debug/app")).unwrap(),
+            "0123456789"
+        );
+        assert_eq!(report.steps[1].status, BootstrapStepStatus::Skipped);
+        assert!(report.steps[1].clone.is_none());
+    }
+
+    #[test]
+    fn test_spec_deserializes_with_defaults() {
+        let spec: BootstrapSpec = serde_json::from_str(r#"{"copy":[".env"]}"#).unwrap();
+        assert_eq!(spec.copy, vec![".env"]);
+        assert!(spec.commands.is_empty());
+        assert!(spec.clone.is_empty());
+        assert!(!spec.is_empty());
+        assert!(BootstrapSpec::default().is_empty());
+    }
+}
@@ -0,0 +1,620 @@
+//! Garbage collection for Maestro-managed worktrees.
+//!
+//! [`WorktreeManager::prune`] only deletes directories git no longer knows
+//! about. Live worktrees, especially the UUID-suffixed ones created for
+//! `force_new` sessions, otherwise pile up under the managed base directory.
+//! [`WorktreeManager::worktree_usage`] reports each managed worktree's disk
+//! usage, last activity, dirty state and whether its HEAD is merged into the
+//! default branch; [`WorktreeManager::collect_garbage`] applies a
+//! [`GcPolicy`] to that report, optionally as a dry run.
+//!
+//! Worktrees in use by a session, worktrees whose status can't be read and,
+//! by default, worktrees with uncommitted changes are never removed. Unless
+//! the policy opts out of dirty protection, removal isn't forced, so git
+//! itself refuses a worktree that gained changes since it was inspected.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use super::fast_clone::{tree_size, TreeSize};
+use super::worktree_manager::WorktreeManager;
+use crate::git::{Git, GitError, WorktreeInfo};
+
+const DAY_MS: u64 = 24 * 60 * 60 * 1000;
+
+/// Which worktrees [`WorktreeManager::collect_garbage`] removes.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct GcPolicy {
+    /// Remove worktrees with no activity for more than this many days.
+    pub max_idle_days: Option<u64>,
+    /// Remove worktrees whose HEAD is contained in the default branch.
+    pub remove_merged: bool,
+    /// Remove the least recently active worktrees until the project's
+    /// managed worktrees use at most this many bytes.
+    pub max_total_bytes: Option<u64>,
+    /// Keep worktrees with uncommitted or untracked changes regardless of
+    /// the rules above.
+    pub protect_dirty: bool,
+}
+
+impl Default for GcPolicy {
+    fn default() -> Self {
+        Self {
+            max_idle_days: Some(14),
+            remove_merged: true,
+            max_total_bytes: None,
+            protect_dirty: true,
+        }
+    }
+}
+
+/// Disk usage and state of one managed worktree.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorktreeUsage {
+    pub path: String,
+    pub branch: Option<String>,
+    pub head: String,
+    /// `None` if the directory no longer exists.
+    pub size: Option<TreeSize>,
+    /// Most recent checkout, commit, staging or edit of a changed file, in
+    /// milliseconds since the Unix epoch.
+    pub last_activity_ms: Option<u64>,
+    /// Number of changed, staged or untracked files.
+    pub dirty_files: usize,
+    /// Set if `git status` failed, in which case `dirty_files` is unknown.
+    pub status_error: Option<String>,
+    /// Whether HEAD is contained in `base_branch`.
+    pub merged: bool,
+    pub base_branch: Option<String>,
+}
+
+impl WorktreeUsage {
+    /// Whether the worktree has changes, or might (its status is unknown).
+    pub fn is_dirty(&self) -> bool {
+        self.dirty_files > 0 || self.status_error.is_some()
+    }
+
+    fn bytes(&self) -> u64 {
+        self.size.map(|s| s.bytes).unwrap_or(0)
+    }
+}
+
+/// Why a worktree qualifies for removal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum GcReason {
+    /// The directory is gone; only git's record of it remains.
+    Missing,
+    Merged,
+    Idle,
+    OverBudget,
+}
+
+/// Why a qualifying worktree is kept anyway.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum GcProtection {
+    ActiveSession,
+    Dirty,
+    /// `git status` failed; kept regardless of [`GcPolicy::protect_dirty`].
+    StatusUnknown,
+}
+
+/// Decision for one worktree.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GcEntry {
+    pub worktree: WorktreeUsage,
+    pub reasons: Vec<GcReason>,
+    pub protection: Option<GcProtection>,
+    /// Whether the worktree is (or, in a dry run, would be) removed.
+    pub remove: bool,
+    /// Set if removal was attempted and failed.
+    pub error: Option<String>,
+}
+
+/// Outcome of a garbage collection run.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GcReport {
+    pub dry_run: bool,
+    pub entries: Vec<GcEntry>,
+    /// Size of all managed worktrees before collection.
+    pub total_bytes: u64,
+    /// Bytes freed (or that would be freed in a dry run).
+    pub reclaimed_bytes: u64,
+}
+
+impl WorktreeManager {
+    /// Reports usage for every managed worktree of the repository.
+    pub async fn worktree_usage(
+        &self,
+        repo_path: &Path,
+        base_override: Option<&Path>,
+    ) -> Result<Vec<WorktreeUsage>, GitError> {
+        let worktrees = self
+            .list_managed_with_base(repo_path, base_override)
+            .await?;
+        let base_branch = Git::new(repo_path).default_base_branch().await?;
+
+        let mut usages = Vec::with_capacity(worktrees.len());
+        for wt in worktrees {
+            usages.push(inspect_worktree(repo_path, wt, base_branch.as_deref()).await);
+        }
+        Ok(usages)
+    }
+
+    /// Removes managed worktrees selected by `policy`, skipping any whose
+    /// path is in `protected_paths` (e.g. worktrees of live sessions). With
+    /// `dry_run` nothing is removed and the report shows what would be.
+    pub async fn collect_garbage(
+        &self,
+        repo_path: &Path,
+        base_override: Option<&Path>,
+        policy: &GcPolicy,
+        protected_paths: &[String],
+        dry_run: bool,
+    ) -> Result<GcReport, GitError> {
+        let usages = self.worktree_usage(repo_path, base_override).await?;
+        let total_bytes = usages.iter().map(WorktreeUsage::bytes).sum();
+        let protected: HashSet<PathBuf> = protected_paths.iter().map(|p| path_key(p)).collect();
+        let mut entries = plan_gc(usages, policy, &protected, now_ms());
+
+        let mut reclaimed_bytes = 0;
+        let mut removed_any = false;
+        for entry in entries.iter_mut().filter(|e| e.remove) {
+            if dry_run {
+                reclaimed_bytes += entry.worktree.bytes();
+                continue;
+            }
+            if entry.worktree.size.is_none() {
+                // Nothing on disk; `git worktree prune` below drops the record.
+                removed_any = true;
+                continue;
+            }
+            let path = PathBuf::from(&entry.worktree.path);
+            let removed = if policy.protect_dirty {
+                self.remove_if_clean(repo_path, &path).await
+            } else {
+                self.remove(repo_path, &path).await
+            };
+            match removed {
+                Ok(()) => {
+                    log::info!("GC removed worktree {}", entry.worktree.path);
+                    reclaimed_bytes += entry.worktree.bytes();
+                    removed_any = true;
+                }
+                Err(e) => {
+                    log::warn!(
+                        "GC failed to remove worktree {}: {}",
+                        entry.worktree.path,
+                        e
+                    );
+                    entry.remove = false;
+                    entry.error = Some(e.to_string());
+                }
+            }
+        }
+        if removed_any {
+            Git::new(repo_path).worktree_prune().await?;
+        }
+
+        Ok(GcReport {
+            dry_run,
+            entries,
+            total_bytes,
+            reclaimed_bytes,
+        })
+    }
+}
+
+/// Gathers usage for one worktree. Failures degrade individual fields
+/// rather than failing the whole report.
+async fn inspect_worktree(
+    repo_path: &Path,
+    wt: WorktreeInfo,
+    base_branch: Option<&str>,
+) -> WorktreeUsage {
+    let path = PathBuf::from(&wt.path);
+    let exists = tokio::fs::metadata(&path)
+        .await
+        .map(|m| m.is_dir())
+        .unwrap_or(false);
+
+    let mut usage = WorktreeUsage {
+        path: wt.path.clone(),
+        branch: wt.branch.clone(),
+        head: wt.head.clone(),
+        size: None,
+        last_activity_ms: None,
+        dirty_files: 0,
+        status_error: None,
+        merged: false,
+        base_branch: base_branch.map(str::to_string),
+    };
+
+    if let Some(base) = base_branch {
+        let is_base = wt.branch.as_deref() == Some(base.trim_start_matches("origin/"));
+        if !is_base && !wt.head.is_empty() {
+            usage.merged = Git::new(repo_path)
+                .is_ancestor(&wt.head, base)
+                .await
+                .unwrap_or(false);
+        }
+    }
+
+    if !exists {
+        return usage;
+    }
+
+    // Activity is read before `git status`, which may rewrite the index.
+    let git = Git::new(&path);
+    let mut activity = Vec::new();
+    if let Ok(out) = git.run(&["rev-parse", "--absolute-git-dir"]).await {
+        let git_dir = PathBuf::from(out.trimmed());
+        for name in ["HEAD", "index", "logs/HEAD"] {
+            activity.push(git_dir.join(name));
+        }
+    }
+    match git.status_entries().await {
+        Ok(entries) => {
+            usage.dirty_files = entries.len();
+            activity.extend(entries.iter().map(|e| path.join(&e.path)));
+        }
+        Err(e) => {
+            log::warn!("GC could not read status of worktree {}: {}", wt.path, e);
+            usage.status_error = Some(e.to_string());
+        }
+    }
+    usage.last_activity_ms = latest_mtime_ms(&activity).await;
+
+    let dir = path.clone();
+    usage.size = tokio::task::spawn_blocking(move || tree_size(&dir).ok())
+        .await
+        .ok()
+        .flatten();
+    usage
+}
+
+async fn latest_mtime_ms(paths: &[PathBuf]) -> Option<u64> {
+    let mut latest = None;
+    for path in paths {
+        if let Ok(modified) = tokio::fs::metadata(path).await.and_then(|m| m.modified()) {
+            latest = latest.max(system_time_ms(modified));
+        }
+    }
+    latest
+}
+
+fn system_time_ms(time: SystemTime) -> Option<u64> {
+    time.duration_since(UNIX_EPOCH)
+        .ok()
+        .map(|d| d.as_millis() as u64)
+}
+
+fn now_ms() -> u64 {
+    system_time_ms(SystemTime::now()).unwrap_or(0)
+}
+
+/// Canonical form of a path for comparison, or the path itself if it does
+/// not exist.
+fn path_key(path: &str) -> PathBuf {
+    std::fs::canonicalize(path).unwrap_or_else(|_| PathBuf::from(path))
+}
+
+/// Decides which worktrees to remove. Missing, merged and idle worktrees
+/// are selected first; if the remainder still exceeds the size budget, the
+/// least recently active unprotected ones are added until it fits.
+fn plan_gc(
+    usages: Vec<WorktreeUsage>,
+    policy: &GcPolicy,
+    protected: &HashSet<PathBuf>,
+    now_ms: u64,
+) -> Vec<GcEntry> {
+    let mut entries: Vec<GcEntry> = usages
+        .into_iter()
+        .map(|usage| {
+            let mut reasons = Vec::new();
+            if usage.size.is_none() {
+                reasons.push(GcReason::Missing);
+            }
+            if policy.remove_merged && usage.merged {
+                reasons.push(GcReason::Merged);
+            }
+            let idle = policy
+                .max_idle_days
+                .zip(usage.last_activity_ms)
+                .is_some_and(|(days, last)| {
+                    now_ms.saturating_sub(last) > days.saturating_mul(DAY_MS)
+                });
+            if idle {
+                reasons.push(GcReason::Idle);
+            }
+
+            let protection = if protected.contains(&path_key(&usage.path)) {
+                Some(GcProtection::ActiveSession)
+            } else if usage.status_error.is_some() {
+                Some(GcProtection::StatusUnknown)
+            } else if policy.protect_dirty && usage.is_dirty() {
+                Some(GcProtection::Dirty)
+            } else {
+                None
+            };
+
+            GcEntry {
+                remove: !reasons.is_empty() && protection.is_none(),
+                worktree: usage,
+                reasons,
+                protection,
+                error: None,
+            }
+        })
+        .collect();
+
+    if let Some(budget) = policy.max_total_bytes {
+        let mut remaining: u64 = entries
+            .iter()
+            .filter(|e| !e.remove)
+            .map(|e| e.worktree.bytes())
+            .sum();
+        let mut candidates: Vec<usize> =
+            (0..entries.len()).filter(|&i| !entries[i].remove).collect();
+        candidates.sort_by_key(|&i| entries[i].worktree.last_activity_ms);
+
+        for i in candidates {
+            if remaining <= budget {
+                break;
+            }
+            let entry = &mut entries[i];
+            entry.reasons.push(GcReason::OverBudget);
+            if entry.protection.is_none() {
+                entry.remove = true;
+                remaining -= entry.worktree.bytes();
+            }
+        }
+    }
+
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn usage(path: &str, bytes: u64, last_activity_days_ago: u64) -> WorktreeUsage {
+        WorktreeUsage {
+            path: path.to_string(),
+            branch: Some(path.trim_start_matches('/').to_string()),
+            head: "abc".to_string(),
+            size: Some(TreeSize { files: 1, bytes }),
+            last_activity_ms: Some(NOW - last_activity_days_ago * DAY_MS),
+            dirty_files: 0,
+            status_error: None,
+            merged: false,
+            base_branch: Some("main".to_string()),
+        }
+    }
+
+    const NOW: u64 = 1_000 * DAY_MS;
+
+    fn removed(entries: &[GcEntry]) -> Vec<&str> {
+        entries
+            .iter()
+            .filter(|e| e.remove)
+            .map(|e| e.worktree.path.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_plan_selects_merged_idle_and_missing() {
+        let merged = WorktreeUsage {
+            merged: true,
+            ..usage("/merged", 10, 0)
+        };
+        let missing = WorktreeUsage {
+            size: None,
+            ..usage("/missing", 0, 0)
+        };
+        let entries = plan_gc(
+            vec![
+                merged,
+                usage("/idle", 10, 30),
+                usage("/fresh", 10, 1),
+                missing,
+            ],
+            &GcPolicy::default(),
+            &HashSet::new(),
+            NOW,
+        );
+        assert_eq!(removed(&entries), vec!["/merged", "/idle", "/missing"]);
+        assert_eq!(entries[0].reasons, vec![GcReason::Merged]);
+        assert_eq!(entries[1].reasons, vec![GcReason::Idle]);
+        assert!(entries[2].reasons.is_empty());
+        assert_eq!(entries[3].reasons, vec![GcReason::Missing]);
+    }
+
+    #[test]
+    fn test_plan_protects_dirty_and_active_worktrees() {
+        let dirty = WorktreeUsage {
+            dirty_files: 2,
+            ..usage("/dirty", 10, 30)
+        };
+        let protected = HashSet::from([PathBuf::from("/active")]);
+        let entries = plan_gc(
+            vec![dirty.clone(), usage("/active", 10, 30)],
+            &GcPolicy::default(),
+            &protected,
+            NOW,
+        );
+        assert!(removed(&entries).is_empty());
+        assert_eq!(entries[0].protection, Some(GcProtection::Dirty));
+        assert_eq!(entries[1].protection, Some(GcProtection::ActiveSession));
+        assert_eq!(entries[1].reasons, vec![GcReason::Idle]);
+
+        let unprotected = GcPolicy {
+            protect_dirty: false,
+            ..GcPolicy::default()
+        };
+        let entries = plan_gc(vec![dirty], &unprotected, &HashSet::new(), NOW);
+        assert_eq!(removed(&entries), vec!["/dirty"]);
+    }
+
+    #[test]
+    fn test_plan_keeps_worktrees_with_unknown_status() {
+        let unreadable = WorktreeUsage {
+            status_error: Some("fatal: index file corrupt".to_string()),
+            merged: true,
+            ..usage("/unreadable", 10, 30)
+        };
+        let unprotected = GcPolicy {
+            protect_dirty: false,
+            ..GcPolicy::default()
+        };
+        let entries = plan_gc(vec![unreadable], &unprotected, &HashSet::new(), NOW);
+        assert!(removed(&entries).is_empty());
+        assert_eq!(entries[0].protection, Some(GcProtection::StatusUnknown));
+    }
+
+    #[test]
+    fn test_plan_enforces_size_budget_oldest_first() {
+        let policy = GcPolicy {
+            max_idle_days: None,
+            remove_merged: false,
+            max_total_bytes: Some(250),
+            protect_dirty: true,
+        };
+        let dirty_oldest = WorktreeUsage {
+            dirty_files: 1,
+            ..usage("/dirty", 100, 9)
+        };
+        let entries = plan_gc(
+            vec![
+                usage("/new", 100, 1),
+                dirty_oldest,
+                usage("/old", 100, 5),
+                usage("/mid", 100, 3),
+            ],
+            &policy,
+            &HashSet::new(),
+            NOW,
+        );
+        // 400 bytes: the dirty tree can't go, so /old and /mid are removed.
+        assert_eq!(removed(&entries), vec!["/old", "/mid"]);
+        assert_eq!(entries[1].reasons, vec![GcReason::OverBudget]);
+        assert_eq!(entries[1].protection, Some(GcProtection::Dirty));
+        assert!(entries[0].reasons.is_empty());
+    }
+
+    async fn create_test_repo() -> (tempfile::TempDir, PathBuf) {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("repo");
+        std::fs::create_dir(&path).unwrap();
+        let git = Git::new(&path);
+        git.run(&["init", "-b", "main"]).await.unwrap();
+        git.run(&["config", "user.email", "test@test.com"])
+            .await
+            .unwrap();
+        git.run(&["config", "user.name", "Test"]).await.unwrap();
+        tokio::fs::write(path.join("README.md"), "# Test")
+            .await
+            .unwrap();
+        git.run(&["add", "."]).await.unwrap();
+        git.run(&["commit", "-m", "initial"]).await.unwrap();
+        (dir, path)
+    }
+
+    #[tokio::test]
+    async fn test_collect_garbage_removes_merged_and_keeps_dirty() {
+        let (dir, repo) = create_test_repo().await;
+        let base = dir.path().join("worktrees");
+        let git = Git::new(&repo);
+        let wm = WorktreeManager::new();
+
+        // Merged: branch at main's commit. Unmerged: one commit ahead.
+        git.run(&["branch", "merged"]).await.unwrap();
+        git.run(&["branch", "ahead"]).await.unwrap();
+        git.run(&["branch", "dirty"]).await.unwrap();
+        let merged = wm
+            .create_with_base("merged", &repo, Some(&base), false)
+            .await
+            .unwrap();
+        let ahead = wm
+            .create_with_base("ahead", &repo, Some(&base), false)
+            .await
+            .unwrap();
+        let dirty = wm
+            .create_with_base("dirty", &repo, Some(&base), false)
+            .await
+            .unwrap();
+        let ahead_git = Git::new(&ahead);
+        tokio::fs::write(ahead.join("new.txt"), "x").await.unwrap();
+        ahead_git.run(&["add", "."]).await.unwrap();
+        ahead_git.run(&["commit", "-m", "ahead"]).await.unwrap();
+        tokio::fs::write(dirty.join("scratch.txt"), "wip")
+            .await
+            .unwrap();
+
+        let usages = wm.worktree_usage(&repo, Some(&base)).await.unwrap();
+        assert_eq!(usages.len(), 3);
+        let find = |p: &Path| {
+            usages
+                .iter()
+                .find(|u| path_key(&u.path) == path_key(&p.to_string_lossy()))
+                .unwrap()
+                .clone()
+        };
+        assert!(find(&merged).merged);
+        assert!(!find(&ahead).merged);
+        assert_eq!(find(&dirty).dirty_files, 1);
+        assert_eq!(find(&merged).base_branch.as_deref(), Some("main"));
+        assert!(find(&merged).size.unwrap().bytes > 0);
+        assert!(find(&merged).last_activity_ms.is_some());
+
+        let policy = GcPolicy::default();
+        let report = wm
+            .collect_garbage(&repo, Some(&base), &policy, &[], true)
+            .await
+            .unwrap();
+        assert!(report.dry_run);
+        let planned: Vec<_> = report.entries.iter().filter(|e| e.remove).collect();
+        assert_eq!(planned.len(), 1);
+        assert!(report.reclaimed_bytes > 0);
+        assert!(merged.exists());
+
+        // A live session's worktree is protected even when merged.
+        let report = wm
+            .collect_garbage(
+                &repo,
+                Some(&base),
+                &policy,
+                &[merged.to_string_lossy().to_string()],
+                false,
+            )
+            .await
+            .unwrap();
+        assert!(report.entries.iter().all(|e| !e.remove));
+        assert!(merged.exists());
+
+        let report = wm
+            .collect_garbage(&repo, Some(&base), &policy, &[], false)
+            .await
+            .unwrap();
+        assert_eq!(report.entries.iter().filter(|e| e.remove).count(), 1);
+        assert!(!merged.exists());
+        assert!(ahead.exists());
+        assert!(dirty.exists());
+        assert_eq!(
+            wm.list_managed_with_base(&repo, Some(&base))
+                .await
+                .unwrap()
+                .len(),
+            2
+        );
+    }
+}
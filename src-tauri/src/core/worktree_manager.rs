@@ -3,7 +3,7 @@ use std::path::{Path, PathBuf};
 
 use sha2::{Digest, Sha256};
 
-use crate::git::{Git, GitError, WorktreeInfo};
+use crate::git::{Git, GitError, GitOperation, WorktreeInfo};
 
 pub(crate) fn worktree_base_dir() -> PathBuf {
     directories::ProjectDirs::from("com", "maestro", "maestro")
@@ -153,7 +153,7 @@ impl WorktreeManager {
         base_override: Option<&Path>,
         force: bool,
     ) -> Result<PathBuf, GitError> {
-        self.create_with_base_inner(branch, repo_path, base_override, force, false).await
+        self.create_with_base_inner(branch, repo_path, base_override, force, false, None).await
     }
 
     pub async fn create_with_base_new(
@@ -162,7 +162,31 @@ impl WorktreeManager {
         repo_path: &Path,
         base_override: Option<&Path>,
     ) -> Result<PathBuf, GitError> {
-        self.create_with_base_inner(branch, repo_path, base_override, true, true).await
+        self.create_with_base_inner(branch, repo_path, base_override, true, true, None).await
+    }
+
+    /// Like [`create_with_base`](Self::create_with_base) (or
+    /// [`create_with_base_new`](Self::create_with_base_new) when `force_new`),
+    /// but runs git under `operation` so the checkout can be cancelled. A
+    /// cancelled worktree is removed again.
+    pub async fn create_with_operation(
+        &self,
+        branch: &str,
+        repo_path: &Path,
+        base_override: Option<&Path>,
+        force: bool,
+        force_new: bool,
+        operation: GitOperation,
+    ) -> Result<PathBuf, GitError> {
+        self.create_with_base_inner(
+            branch,
+            repo_path,
+            base_override,
+            force || force_new,
+            force_new,
+            Some(operation),
+        )
+        .await
     }
 
     async fn create_with_base_inner(
@@ -172,8 +196,12 @@ impl WorktreeManager {
         base_override: Option<&Path>,
         force: bool,
         force_new: bool,
+        operation: Option<GitOperation>,
     ) -> Result<PathBuf, GitError> {
-        let git = Git::new(repo_path);
+        let mut git = Git::new(repo_path);
+        if let Some(operation) = operation {
+            git = git.with_operation(operation);
+        }
 
         // force_new: use a UUID-based unique path so each session gets its own directory.
         // Normal mode: use the deterministic branch-based path.
@@ -209,11 +237,19 @@ impl WorktreeManager {
             })?;
         }
 
-        if force || force_new {
-            git.worktree_add_force(&wt_path, Some(branch)).await?;
+        let added = if force || force_new {
+            git.worktree_add_force(&wt_path, Some(branch)).await
         } else {
-            git.worktree_add(&wt_path, None, Some(branch)).await?;
+            git.worktree_add(&wt_path, None, Some(branch)).await
+        };
+        if let Err(e @ GitError::Cancelled { .. }) = added {
+            // The killed checkout leaves a half-populated worktree behind.
+            if let Err(cleanup) = self.remove(repo_path, &wt_path).await {
+                log::warn!("Failed to clean up cancelled worktree {:?}: {}", wt_path, cleanup);
+            }
+            return Err(e);
         }
+        added?;
 
         Ok(wt_path)
     }
@@ -221,8 +257,18 @@ impl WorktreeManager {
     /// Force-removes a worktree and prunes its git ref, then attempts to
     /// clean up the empty parent directory (silently ignored if non-empty).
     pub async fn remove(&self, repo_path: &Path, wt_path: &Path) -> Result<(), GitError> {
+        self.remove_with(repo_path, wt_path, true).await
+    }
+
+    /// Like [`Self::remove`], but git refuses worktrees with modified or
+    /// untracked files.
+    pub async fn remove_if_clean(&self, repo_path: &Path, wt_path: &Path) -> Result<(), GitError> {
+        self.remove_with(repo_path, wt_path, false).await
+    }
+
+    async fn remove_with(&self, repo_path: &Path, wt_path: &Path, force: bool) -> Result<(), GitError> {
         let git = Git::new(repo_path);
-        git.worktree_remove(wt_path, true).await?;
+        git.worktree_remove(wt_path, force).await?;
         git.worktree_prune().await?;
 
         // Clean up empty parent directories
@@ -456,6 +502,26 @@ mod tests {
         assert!(!wt_path.exists());
     }
 
+    #[tokio::test]
+    async fn test_cancelled_create_leaves_no_worktree() {
+        let (_dir, path) = create_test_repo().await;
+        let git = Git::new(&path);
+        git.run(&["branch", "cancel-test"]).await.unwrap();
+        let base = tempfile::tempdir().unwrap();
+
+        let operation = GitOperation::new("wt-1");
+        operation.cancel_token().cancel();
+        let wm = WorktreeManager::new();
+        let err = wm
+            .create_with_operation("cancel-test", &path, Some(base.path()), false, true, operation)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, GitError::Cancelled { .. }));
+
+        let managed = wm.list_managed_with_base(&path, Some(base.path())).await.unwrap();
+        assert!(managed.is_empty());
+    }
+
     #[tokio::test]
     async fn test_list_managed_excludes_main() {
         let (_dir, path) = create_test_repo().await;
@@ -0,0 +1,479 @@
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+use std::time::{Duration, SystemTime};
+
+use dashmap::DashMap;
+use serde::Serialize;
+
+use super::error::ForgeError;
+use super::{token, AnyForge, ForgeKind, GitLab, Gitea};
+use crate::git::{Git, RemoteInfo};
+use crate::github::GitHub;
+
+/// A repository remote resolved to the forge hosting it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ForgeRemote {
+    pub kind: ForgeKind,
+    /// Name of the git remote this was resolved from (e.g. `origin`).
+    pub remote: String,
+    /// Host name without port, used as the token key.
+    pub host: String,
+    /// Web/API root, e.g. `https://gitlab.example.com`.
+    pub base_url: String,
+    /// Project path without `.git`, e.g. `group/subgroup/repo`.
+    pub path: String,
+}
+
+/// Detection results per repository path, reused until the repository's git
+/// config (where its remotes live) changes.
+static CACHE: LazyLock<DashMap<PathBuf, CachedForge>> = LazyLock::new(DashMap::new);
+
+#[derive(Debug, Clone)]
+struct CachedForge {
+    /// Modification time of the git config the remote was resolved from.
+    config_modified: SystemTime,
+    remote: ForgeRemote,
+    /// Token for the remote's host, loaded on first use by [`open_forge`].
+    token: Option<Option<String>>,
+}
+
+/// A remote URL split into the parts needed to reach the forge API.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemoteLocation {
+    pub host: String,
+    pub base_url: String,
+    pub path: String,
+}
+
+/// Parses HTTPS, `ssh://` and scp-style (`git@host:owner/repo.git`) remote URLs.
+///
+/// SSH remotes map to `https://<host>` since the API is never served over
+/// the SSH port. Returns `None` for local paths and unrecognized schemes.
+pub fn parse_remote_url(url: &str) -> Option<RemoteLocation> {
+    let url = url.trim();
+
+    if url.contains("://") {
+        let parsed = url::Url::parse(url).ok()?;
+        let host = parsed.host_str()?.to_string();
+        let base_url = match parsed.scheme() {
+            "http" | "https" => match parsed.port() {
+                Some(port) => format!("{}://{}:{}", parsed.scheme(), host, port),
+                None => format!("{}://{}", parsed.scheme(), host),
+            },
+            "ssh" | "git" | "git+ssh" | "ssh+git" => format!("https://{}", host),
+            _ => return None,
+        };
+        let path = clean_path(parsed.path())?;
+        return Some(RemoteLocation {
+            host,
+            base_url,
+            path,
+        });
+    }
+
+    // scp-like syntax: [user@]host:path (a single letter is a Windows drive)
+    let (authority, path) = url.split_once(':')?;
+    if authority.contains('/') || authority.len() == 1 {
+        return None;
+    }
+    let host = authority.rsplit('@').next()?.to_string();
+    if host.is_empty() {
+        return None;
+    }
+    Some(RemoteLocation {
+        base_url: format!("https://{}", host),
+        path: clean_path(path)?,
+        host,
+    })
+}
+
+fn clean_path(path: &str) -> Option<String> {
+    let path = path.trim_matches('/');
+    let path = path.strip_suffix(".git").unwrap_or(path);
+    path.contains('/').then(|| path.to_string())
+}
+
+/// Guesses the forge from well-known host names.
+fn kind_from_host(host: &str) -> Option<ForgeKind> {
+    let host = host.to_lowercase();
+    if host == "github.com" || host.ends_with(".github.com") || host.starts_with("github.") {
+        Some(ForgeKind::GitHub)
+    } else if host.contains("gitlab") {
+        Some(ForgeKind::GitLab)
+    } else if host.contains("gitea") || host.contains("forgejo") || host == "codeberg.org" {
+        Some(ForgeKind::Gitea)
+    } else {
+        None
+    }
+}
+
+/// Identifies a self-hosted instance by its unauthenticated version endpoints.
+///
+/// Gitea answers `/api/v1/version` publicly; GitLab answers `/api/v4/version`
+/// with 200 or, on instances that hide it, 401. GitHub Enterprise Server
+/// answers `/api/v3/meta` with its installed version.
+pub async fn probe_kind(base_url: &str) -> Option<ForgeKind> {
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(5))
+        .build()
+        .ok()?;
+
+    if let Ok(response) = client
+        .get(format!("{}/api/v1/version", base_url))
+        .send()
+        .await
+    {
+        if response.status().is_success() {
+            let body: Option<serde_json::Value> = response.json().await.ok();
+            if body.is_some_and(|b| b.get("version").is_some()) {
+                return Some(ForgeKind::Gitea);
+            }
+        }
+    }
+
+    if let Ok(response) = client
+        .get(format!("{}/api/v4/version", base_url))
+        .send()
+        .await
+    {
+        let status = response.status();
+        if status.is_success() || status == reqwest::StatusCode::UNAUTHORIZED {
+            return Some(ForgeKind::GitLab);
+        }
+    }
+
+    if let Ok(response) = client
+        .get(format!("{}/api/v3/meta", base_url))
+        .send()
+        .await
+    {
+        if response.status().is_success() {
+            let body: Option<serde_json::Value> = response.json().await.ok();
+            if body.is_some_and(|b| b.get("installed_version").is_some()) {
+                return Some(ForgeKind::GitHub);
+            }
+        }
+    }
+
+    None
+}
+
+/// Orders remotes so `origin` wins, then `upstream`, then the rest by name.
+fn prioritized(mut remotes: Vec<RemoteInfo>) -> Vec<RemoteInfo> {
+    remotes.sort_by_key(|r| match r.name.as_str() {
+        "origin" => (0, String::new()),
+        "upstream" => (1, String::new()),
+        other => (2, other.to_string()),
+    });
+    remotes
+}
+
+/// Modification time of the git config holding the remotes of `repo_path`,
+/// following a linked worktree's `.git` file to the shared config.
+fn config_modified(repo_path: &Path) -> Option<SystemTime> {
+    let dot_git = repo_path.join(".git");
+    let git_dir = if dot_git.is_dir() {
+        dot_git
+    } else {
+        let contents = std::fs::read_to_string(&dot_git).ok()?;
+        let git_dir = repo_path.join(contents.strip_prefix("gitdir:")?.trim());
+        match std::fs::read_to_string(git_dir.join("commondir")) {
+            Ok(common) => git_dir.join(common.trim()),
+            Err(_) => git_dir,
+        }
+    };
+    std::fs::metadata(git_dir.join("config")).ok()?.modified().ok()
+}
+
+/// Drops cached tokens for `host` so the next [`open_forge`] reloads them.
+pub(crate) fn forget_token(host: &str) {
+    for mut entry in CACHE.iter_mut() {
+        if entry.remote.host.eq_ignore_ascii_case(host) {
+            entry.token = None;
+        }
+    }
+}
+
+/// Resolves which forge hosts the repository at `repo_path` from its remotes.
+///
+/// The result is cached per path until the repository's git config changes.
+pub async fn detect_forge(repo_path: &Path) -> Result<ForgeRemote, ForgeError> {
+    let modified = config_modified(repo_path);
+    if let (Some(modified), Some(cached)) = (modified, CACHE.get(repo_path)) {
+        if cached.config_modified == modified {
+            return Ok(cached.remote.clone());
+        }
+    }
+
+    let remote = resolve_forge(repo_path).await?;
+    if let Some(modified) = modified {
+        CACHE.insert(
+            repo_path.to_path_buf(),
+            CachedForge {
+                config_modified: modified,
+                remote: remote.clone(),
+                token: None,
+            },
+        );
+    }
+    Ok(remote)
+}
+
+/// Resolves the forge from the repository's remotes, without the cache.
+///
+/// Well-known hosts are matched by name; other hosts are probed over HTTP.
+/// When no probe answers (e.g. a GitHub Enterprise instance in private mode),
+/// the highest-priority remote is treated as GitHub so the `gh` CLI, which
+/// knows the hosts it is logged into, gets to handle it.
+async fn resolve_forge(repo_path: &Path) -> Result<ForgeRemote, ForgeError> {
+    let remotes = Git::new(repo_path).list_remotes().await.unwrap_or_default();
+
+    let mut unknown = Vec::new();
+    for remote in prioritized(remotes) {
+        let Some(location) = parse_remote_url(&remote.url) else {
+            continue;
+        };
+        match kind_from_host(&location.host) {
+            Some(kind) => return Ok(into_remote(kind, remote.name, location)),
+            None => unknown.push((remote.name, location)),
+        }
+    }
+
+    for (name, location) in &unknown {
+        if let Some(kind) = probe_kind(&location.base_url).await {
+            return Ok(into_remote(kind, name.clone(), location.clone()));
+        }
+    }
+
+    if let Some((name, location)) = unknown.into_iter().next() {
+        log::info!(
+            "Could not identify forge at {}, falling back to GitHub",
+            location.base_url
+        );
+        return Ok(into_remote(ForgeKind::GitHub, name, location));
+    }
+
+    Err(ForgeError::NoForgeRemote {
+        path: repo_path.display().to_string(),
+    })
+}
+
+fn into_remote(kind: ForgeKind, remote: String, location: RemoteLocation) -> ForgeRemote {
+    ForgeRemote {
+        kind,
+        remote,
+        host: location.host,
+        base_url: location.base_url,
+        path: location.path,
+    }
+}
+
+/// Returns the stored token for `remote`'s host, caching it alongside the
+/// detection result for `repo_path` until [`forget_token`] drops it.
+///
+/// A broken credential store should not take the whole panel down: public
+/// projects stay readable without a token, so read errors yield `None`.
+pub(crate) async fn repo_token(repo_path: &Path, remote: &ForgeRemote) -> Option<String> {
+    if let Some(token) = CACHE.get(repo_path).and_then(|c| c.token.clone()) {
+        return token;
+    }
+
+    let token = token::get_token(&remote.host).await.unwrap_or_else(|e| {
+        log::warn!("Could not read forge token for {}: {}", remote.host, e);
+        None
+    });
+    if let Some(mut cached) = CACHE.get_mut(repo_path) {
+        if cached.remote == *remote {
+            cached.token = Some(token.clone());
+        }
+    }
+    token
+}
+
+/// Detects the forge for `repo_path` and builds a client for it, loading the
+/// host's token from the credential store for GitLab and Gitea. Both are
+/// cached alongside the detection result.
+pub async fn open_forge(repo_path: &Path) -> Result<AnyForge, ForgeError> {
+    let remote = detect_forge(repo_path).await?;
+
+    // gh manages its own credentials
+    let token = match remote.kind {
+        ForgeKind::GitHub => None,
+        _ => repo_token(repo_path, &remote).await,
+    };
+
+    Ok(match remote.kind {
+        ForgeKind::GitHub => AnyForge::GitHub(GitHub::new(repo_path)),
+        ForgeKind::GitLab => AnyForge::GitLab(GitLab::new(
+            &remote.base_url,
+            &remote.host,
+            &remote.path,
+            token,
+        )),
+        ForgeKind::Gitea => {
+            let (owner, repo) = remote.path.rsplit_once('/').unwrap_or(("", &remote.path));
+            AnyForge::Gitea(Gitea::new(
+                &remote.base_url,
+                &remote.host,
+                owner,
+                repo,
+                token,
+            ))
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::forge::mock::MockServer;
+
+    fn location(host: &str, base_url: &str, path: &str) -> Option<RemoteLocation> {
+        Some(RemoteLocation {
+            host: host.into(),
+            base_url: base_url.into(),
+            path: path.into(),
+        })
+    }
+
+    #[test]
+    fn test_parse_remote_url_variants() {
+        assert_eq!(
+            parse_remote_url("https://github.com/owner/repo.git"),
+            location("github.com", "https://github.com", "owner/repo")
+        );
+        assert_eq!(
+            parse_remote_url("git@gitlab.example.com:group/sub/repo.git"),
+            location(
+                "gitlab.example.com",
+                "https://gitlab.example.com",
+                "group/sub/repo"
+            )
+        );
+        assert_eq!(
+            parse_remote_url("ssh://git@git.corp.net:2222/team/app.git"),
+            location("git.corp.net", "https://git.corp.net", "team/app")
+        );
+        assert_eq!(
+            parse_remote_url("http://gitea.local:3000/team/app/"),
+            location("gitea.local", "http://gitea.local:3000", "team/app")
+        );
+        assert_eq!(parse_remote_url("/srv/git/repo.git"), None);
+        assert_eq!(parse_remote_url("file:///srv/git/repo.git"), None);
+    }
+
+    #[test]
+    fn test_kind_from_host() {
+        assert_eq!(kind_from_host("github.com"), Some(ForgeKind::GitHub));
+        assert_eq!(kind_from_host("gitlab.com"), Some(ForgeKind::GitLab));
+        assert_eq!(kind_from_host("gitlab.corp.net"), Some(ForgeKind::GitLab));
+        assert_eq!(kind_from_host("codeberg.org"), Some(ForgeKind::Gitea));
+        assert_eq!(kind_from_host("git.corp.net"), None);
+    }
+
+    #[test]
+    fn test_prioritized_prefers_origin() {
+        let remote = |name: &str| RemoteInfo {
+            name: name.into(),
+            url: String::new(),
+        };
+        let names: Vec<String> =
+            prioritized(vec![remote("fork"), remote("upstream"), remote("origin")])
+                .into_iter()
+                .map(|r| r.name)
+                .collect();
+        assert_eq!(names, vec!["origin", "upstream", "fork"]);
+    }
+
+    #[tokio::test]
+    async fn test_probe_kind() {
+        let gitea = MockServer::start(vec![(
+            "GET /api/v1/version",
+            r#"{"version":"1.22.0"}"#.to_string(),
+        )])
+        .await;
+        assert_eq!(probe_kind(&gitea.url).await, Some(ForgeKind::Gitea));
+
+        let gitlab = MockServer::start(vec![("GET /api/v4/version", "!401".to_string())]).await;
+        assert_eq!(probe_kind(&gitlab.url).await, Some(ForgeKind::GitLab));
+
+        let enterprise = MockServer::start(vec![(
+            "GET /api/v3/meta",
+            r#"{"installed_version":"3.12.0","verifiable_password_authentication":true}"#
+                .to_string(),
+        )])
+        .await;
+        assert_eq!(probe_kind(&enterprise.url).await, Some(ForgeKind::GitHub));
+
+        let neither = MockServer::start::<String>(vec![]).await;
+        assert_eq!(probe_kind(&neither.url).await, None);
+    }
+
+    #[tokio::test]
+    async fn test_detect_forge_from_remotes() {
+        let dir = tempfile::tempdir().unwrap();
+        let git = |dir: &Path, args: &[&str]| {
+            let status = std::process::Command::new("git")
+                .args(args)
+                .current_dir(dir)
+                .output()
+                .unwrap()
+                .status;
+            assert!(status.success(), "git {:?} failed", args);
+        };
+        git(dir.path(), &["init", "-q"]);
+        git(
+            dir.path(),
+            &["remote", "add", "upstream", "https://github.com/owner/repo.git"],
+        );
+        git(dir.path(), &["remote", "add", "origin", "git@gitlab.com:team/app.git"]);
+
+        let remote = detect_forge(dir.path()).await.unwrap();
+        assert_eq!(remote.kind, ForgeKind::GitLab);
+        assert_eq!(remote.remote, "origin");
+        assert_eq!(remote.path, "team/app");
+
+        // Unidentifiable hosts fall back to GitHub (handled by gh)
+        let enterprise = tempfile::tempdir().unwrap();
+        git(enterprise.path(), &["init", "-q"]);
+        git(
+            enterprise.path(),
+            &["remote", "add", "origin", "http://127.0.0.1:9/corp/app.git"],
+        );
+        let remote = detect_forge(enterprise.path()).await.unwrap();
+        assert_eq!(remote.kind, ForgeKind::GitHub);
+        assert_eq!(remote.host, "127.0.0.1");
+        assert_eq!(remote.path, "corp/app");
+
+        let empty = tempfile::tempdir().unwrap();
+        assert!(matches!(
+            detect_forge(empty.path()).await,
+            Err(ForgeError::NoForgeRemote { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_detect_forge_cache_follows_remote_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let git = |args: &[&str]| {
+            let status = std::process::Command::new("git")
+                .args(args)
+                .current_dir(dir.path())
+                .status()
+                .unwrap();
+            assert!(status.success(), "git {:?} failed", args);
+        };
+        git(&["init", "-q"]);
+        git(&["remote", "add", "origin", "git@gitlab.com:team/app.git"]);
+        assert_eq!(detect_forge(dir.path()).await.unwrap().kind, ForgeKind::GitLab);
+        assert!(CACHE.contains_key(dir.path()));
+
+        // Make sure the rewritten config gets a different mtime
+        std::thread::sleep(Duration::from_millis(20));
+        git(&["remote", "set-url", "origin", "https://codeberg.org/team/app.git"]);
+        let remote = detect_forge(dir.path()).await.unwrap();
+        assert_eq!(remote.kind, ForgeKind::Gitea);
+        assert_eq!(remote.host, "codeberg.org");
+    }
+}
@@ -0,0 +1,77 @@
+use crate::github::GitHubError;
+
+/// All possible errors from forge operations, serialized as a string to the
+/// Tauri frontend via the custom `Serialize` impl below.
+#[derive(Debug, thiserror::Error)]
+pub enum ForgeError {
+    /// An error from the `gh`-backed GitHub forge.
+    #[error(transparent)]
+    GitHub(#[from] GitHubError),
+
+    /// No remote of the repository points at a known forge.
+    #[error("no GitHub, GitLab or Gitea remote found for {path}")]
+    NoForgeRemote { path: String },
+
+    /// No API token is stored for the forge's host.
+    #[error("Not authenticated with {host}. Add an access token for it in Maestro.")]
+    NotAuthenticated { host: String },
+
+    /// The forge rejected the request as rate limited.
+    #[error("{host} API rate limit exceeded. Try again later.")]
+    RateLimitExceeded {
+        host: String,
+        /// Unix time the limit resets, if the forge said.
+        reset_at: Option<i64>,
+    },
+
+    /// The forge API returned an unexpected status.
+    #[error("{host} API request failed ({status}): {message}")]
+    Api {
+        host: String,
+        status: u16,
+        message: String,
+    },
+
+    /// The HTTP request could not be sent or its response not read.
+    #[error("request to forge API failed: {0}")]
+    Request(#[from] reqwest::Error),
+
+    /// JSON deserialization failed.
+    #[error("failed to deserialize JSON: {0}")]
+    JsonError(#[from] serde_json::Error),
+
+    /// The platform credential store could not be accessed.
+    #[error("credential store error: {0}")]
+    Keyring(String),
+
+    /// The forge has no equivalent of the requested operation.
+    #[error("{operation} is not supported on {forge}")]
+    Unsupported {
+        operation: &'static str,
+        forge: &'static str,
+    },
+
+    /// A label, user or milestone named in a request does not exist.
+    #[error("{kind} '{name}' not found on {forge}")]
+    NameNotFound {
+        kind: &'static str,
+        name: String,
+        forge: &'static str,
+    },
+
+    /// Pull request (merge request) not found.
+    #[error("Pull request #{number} not found")]
+    PullRequestNotFound { number: u64 },
+
+    /// Issue not found.
+    #[error("Issue #{number} not found")]
+    IssueNotFound { number: u64 },
+}
+
+/// Serializes the error as its `Display` string so the frontend receives a
+/// single human-readable message rather than a tagged enum structure.
+impl serde::Serialize for ForgeError {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
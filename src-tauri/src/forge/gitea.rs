@@ -0,0 +1,759 @@
+use reqwest::Method;
+use serde::Deserialize;
+use serde_json::json;
+
+use super::error::ForgeError;
+use super::http::{encode_segment, is_not_found, ApiClient};
+use super::{Forge, ForgeKind};
+use crate::github::{
+    AuthStatus, Comment, CommentReactions, CreatePullRequestOptions, IssueDetail, IssueFilter,
+    IssueInfo, MergeMethod, PrAuthor, PrLabel, PullRequestDetail, PullRequestFilter,
+    PullRequestInfo,
+};
+
+/// Gitea (and Forgejo) forge backed by the REST API v1.
+///
+/// Pull requests share their number space with issues, so PR comments go
+/// through the issue comment endpoints. Authenticated with an access token
+/// sent as `Authorization: token <value>`.
+#[derive(Debug, Clone)]
+pub struct Gitea {
+    api: ApiClient,
+    /// `/repos/{owner}/{repo}` with both segments URL-encoded.
+    repo: String,
+}
+
+impl Gitea {
+    /// Creates a client for `owner/repo` on the instance at `base_url`
+    /// (e.g. `https://gitea.example.com`).
+    pub fn new(base_url: &str, host: &str, owner: &str, repo: &str, token: Option<String>) -> Self {
+        let api_base = format!("{}/api/v1", base_url.trim_end_matches('/'));
+        Self {
+            api: ApiClient::new(&api_base, host, "authorization", token, |t| {
+                format!("token {}", t)
+            }),
+            repo: format!("/repos/{}/{}", encode_segment(owner), encode_segment(repo)),
+        }
+    }
+
+    fn repo_path(&self, rest: &str) -> String {
+        format!("{}{}", self.repo, rest)
+    }
+
+    async fn comments(&self, number: u64) -> Result<Vec<Comment>, ForgeError> {
+        let comments: Vec<GtComment> = self
+            .api
+            .get(
+                &self.repo_path(&format!("/issues/{}/comments", number)),
+                &[],
+            )
+            .await?;
+        Ok(comments.into_iter().map(GtComment::into_comment).collect())
+    }
+
+    async fn comment(&self, number: u64, body: &str) -> Result<(), ForgeError> {
+        self.api
+            .send_json_ignore(
+                Method::POST,
+                &self.repo_path(&format!("/issues/{}/comments", number)),
+                &json!({ "body": body }),
+            )
+            .await
+    }
+
+    async fn set_state(&self, kind: &str, number: u64, state: &str) -> Result<(), ForgeError> {
+        self.api
+            .send_json_ignore(
+                Method::PATCH,
+                &self.repo_path(&format!("/{}/{}", kind, number)),
+                &json!({ "state": state }),
+            )
+            .await
+    }
+
+    /// Resolves label names to the numeric IDs the pull request API expects.
+    async fn label_ids(&self, names: &[String]) -> Result<Vec<u64>, ForgeError> {
+        let mut labels: Vec<GtNamed> = Vec::new();
+        for page in 1.. {
+            let batch: Vec<GtNamed> = self
+                .api
+                .get(
+                    &self.repo_path("/labels"),
+                    &[("page", page.to_string()), ("limit", "50".to_string())],
+                )
+                .await?;
+            let done = batch.len() < 50;
+            labels.extend(batch);
+            if done {
+                break;
+            }
+        }
+        names
+            .iter()
+            .map(|name| {
+                labels
+                    .iter()
+                    .find(|label| label.name.as_deref() == Some(name.as_str()))
+                    .map(|label| label.id)
+                    .ok_or_else(|| ForgeError::NameNotFound {
+                        kind: "Label",
+                        name: name.clone(),
+                        forge: ForgeKind::Gitea.display_name(),
+                    })
+            })
+            .collect()
+    }
+
+    /// Resolves a milestone title to its ID, including closed milestones.
+    async fn milestone_id(&self, title: &str) -> Result<u64, ForgeError> {
+        let milestones: Vec<GtNamed> = self
+            .api
+            .get(
+                &self.repo_path("/milestones"),
+                &[("name", title.to_string()), ("state", "all".to_string())],
+            )
+            .await?;
+        milestones
+            .into_iter()
+            .find(|m| m.title.as_deref() == Some(title))
+            .map(|m| m.id)
+            .ok_or_else(|| ForgeError::NameNotFound {
+                kind: "Milestone",
+                name: title.to_string(),
+                forge: ForgeKind::Gitea.display_name(),
+            })
+    }
+}
+
+/// A label (`name`) or milestone (`title`) looked up by name.
+#[derive(Deserialize)]
+struct GtNamed {
+    id: u64,
+    name: Option<String>,
+    title: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct GtUser {
+    login: String,
+}
+
+impl GtUser {
+    fn into_author(self) -> PrAuthor {
+        PrAuthor { login: self.login }
+    }
+}
+
+#[derive(Deserialize)]
+struct GtLabel {
+    name: String,
+    #[serde(default)]
+    color: String,
+}
+
+#[derive(Deserialize)]
+struct GtBranch {
+    #[serde(rename = "ref")]
+    ref_name: String,
+}
+
+#[derive(Deserialize)]
+struct GtPullRequest {
+    number: u64,
+    title: String,
+    #[serde(default)]
+    body: Option<String>,
+    state: String,
+    #[serde(default)]
+    merged: bool,
+    user: GtUser,
+    created_at: String,
+    updated_at: String,
+    head: GtBranch,
+    base: GtBranch,
+    /// Only reported by Gitea 1.22+; older versions mark drafts with a
+    /// `WIP:` title prefix instead.
+    #[serde(default)]
+    draft: Option<bool>,
+    #[serde(default)]
+    additions: Option<u64>,
+    #[serde(default)]
+    deletions: Option<u64>,
+    #[serde(default)]
+    changed_files: Option<u64>,
+    html_url: String,
+    #[serde(default)]
+    labels: Vec<GtLabel>,
+    #[serde(default)]
+    merged_at: Option<String>,
+    #[serde(default)]
+    closed_at: Option<String>,
+    #[serde(default)]
+    mergeable: bool,
+}
+
+impl GtPullRequest {
+    fn is_draft(&self) -> bool {
+        self.draft.unwrap_or_else(|| {
+            let title = self.title.to_ascii_lowercase();
+            title.starts_with("wip:") || title.starts_with("[wip]")
+        })
+    }
+
+    fn normalized_state(&self) -> String {
+        if self.merged {
+            "MERGED".to_string()
+        } else {
+            self.state.to_uppercase()
+        }
+    }
+
+    fn into_info(self) -> PullRequestInfo {
+        PullRequestInfo {
+            number: self.number,
+            is_draft: self.is_draft(),
+            state: self.normalized_state(),
+            title: self.title,
+            author: self.user.into_author(),
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+            head_ref_name: self.head.ref_name,
+            base_ref_name: self.base.ref_name,
+            additions: self.additions.unwrap_or(0),
+            deletions: self.deletions.unwrap_or(0),
+            url: self.html_url,
+            labels: labels(self.labels),
+            merged_at: self.merged_at,
+            closed_at: self.closed_at,
+        }
+    }
+
+    fn into_detail(self, comments: Vec<Comment>) -> PullRequestDetail {
+        let body = self.body.clone().unwrap_or_default();
+        let changed_files = self.changed_files.unwrap_or(0);
+        let mergeable = if self.mergeable {
+            "MERGEABLE"
+        } else if self.state == "open" {
+            "CONFLICTING"
+        } else {
+            "UNKNOWN"
+        }
+        .to_string();
+        let info = self.into_info();
+
+        PullRequestDetail {
+            number: info.number,
+            title: info.title,
+            body,
+            state: info.state,
+            author: info.author,
+            created_at: info.created_at,
+            updated_at: info.updated_at,
+            head_ref_name: info.head_ref_name,
+            base_ref_name: info.base_ref_name,
+            is_draft: info.is_draft,
+            additions: info.additions,
+            deletions: info.deletions,
+            changed_files,
+            url: info.url,
+            labels: info.labels,
+            merged_at: info.merged_at,
+            closed_at: info.closed_at,
+            mergeable,
+            review_decision: None,
+            comments,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct GtIssue {
+    number: u64,
+    title: String,
+    #[serde(default)]
+    body: Option<String>,
+    state: String,
+    user: GtUser,
+    created_at: String,
+    updated_at: String,
+    html_url: String,
+    #[serde(default)]
+    labels: Vec<GtLabel>,
+    #[serde(default)]
+    closed_at: Option<String>,
+}
+
+impl GtIssue {
+    fn into_info(self) -> IssueInfo {
+        IssueInfo {
+            number: self.number,
+            title: self.title,
+            state: self.state.to_uppercase(),
+            author: self.user.into_author(),
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+            url: self.html_url,
+            labels: labels(self.labels),
+            closed_at: self.closed_at,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct GtComment {
+    id: u64,
+    body: String,
+    user: GtUser,
+    created_at: String,
+    #[serde(default)]
+    updated_at: Option<String>,
+}
+
+impl GtComment {
+    fn into_comment(self) -> Comment {
+        Comment {
+            id: self.id.to_string(),
+            author: self.user.into_author(),
+            body: self.body,
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+            reactions: CommentReactions::default(),
+            is_answer: false,
+        }
+    }
+}
+
+fn labels(labels: Vec<GtLabel>) -> Vec<PrLabel> {
+    labels
+        .into_iter()
+        .map(|l| PrLabel {
+            name: l.name,
+            color: l.color.trim_start_matches('#').to_string(),
+        })
+        .collect()
+}
+
+/// Gitea has no `merged` list state; merged PRs are closed PRs with
+/// `merged: true`, so that filter is applied client-side.
+fn state_query(state: Option<&str>) -> &'static str {
+    match state {
+        Some("closed") | Some("merged") => "closed",
+        Some("all") => "all",
+        _ => "open",
+    }
+}
+
+fn limit_query(limit: Option<u32>) -> String {
+    limit.unwrap_or(50).min(50).to_string()
+}
+
+impl Forge for Gitea {
+    async fn auth_status(&self) -> Result<AuthStatus, ForgeError> {
+        if !self.api.has_token() {
+            return Ok(AuthStatus {
+                logged_in: false,
+                username: None,
+                scopes: vec![],
+            });
+        }
+
+        match self.api.get::<GtUser>("/user", &[]).await {
+            Ok(user) => Ok(AuthStatus {
+                logged_in: true,
+                username: Some(user.login),
+                scopes: vec![],
+            }),
+            Err(ForgeError::NotAuthenticated { .. }) => Ok(AuthStatus {
+                logged_in: false,
+                username: None,
+                scopes: vec![],
+            }),
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn list_pull_requests(
+        &self,
+        filter: PullRequestFilter,
+    ) -> Result<Vec<PullRequestInfo>, ForgeError> {
+        let query = vec![
+            ("state", state_query(filter.state.as_deref()).to_string()),
+            ("limit", limit_query(filter.limit)),
+            ("sort", "recentupdate".to_string()),
+        ];
+        let prs: Vec<GtPullRequest> = self.api.get(&self.repo_path("/pulls"), &query).await?;
+
+        // The pulls endpoint has no search parameter
+        let search = filter.search.as_deref().map(str::to_lowercase);
+        let only_merged = filter.state.as_deref() == Some("merged");
+        Ok(prs
+            .into_iter()
+            .filter(|pr| !only_merged || pr.merged)
+            .filter(|pr| {
+                search
+                    .as_deref()
+                    .is_none_or(|s| pr.title.to_lowercase().contains(s))
+            })
+            .map(GtPullRequest::into_info)
+            .collect())
+    }
+
+    async fn get_pull_request(&self, number: u64) -> Result<PullRequestDetail, ForgeError> {
+        let pr: GtPullRequest = self
+            .api
+            .get(&self.repo_path(&format!("/pulls/{}", number)), &[])
+            .await
+            .map_err(|e| {
+                if is_not_found(&e) {
+                    ForgeError::PullRequestNotFound { number }
+                } else {
+                    e
+                }
+            })?;
+        let comments = self.comments(number).await?;
+        Ok(pr.into_detail(comments))
+    }
+
+    async fn create_pull_request(
+        &self,
+        options: CreatePullRequestOptions,
+    ) -> Result<PullRequestInfo, ForgeError> {
+        if !options.projects.is_empty() {
+            return Err(ForgeError::Unsupported {
+                operation: "Adding pull requests to projects",
+                forge: ForgeKind::Gitea.display_name(),
+            });
+        }
+
+        let title = if options.draft {
+            format!("WIP: {}", options.title)
+        } else {
+            options.title
+        };
+        let mut body = json!({
+            "title": title,
+            "body": options.body,
+            "head": options.head,
+            "base": options.base,
+        });
+        if !options.labels.is_empty() {
+            body["labels"] = json!(self.label_ids(&options.labels).await?);
+        }
+        if !options.assignees.is_empty() {
+            body["assignees"] = json!(options.assignees);
+        }
+        if let Some(milestone) = options.milestone.as_deref().filter(|m| !m.is_empty()) {
+            body["milestone"] = json!(self.milestone_id(milestone).await?);
+        }
+
+        let pr: GtPullRequest = self
+            .api
+            .send_json(Method::POST, &self.repo_path("/pulls"), &body)
+            .await?;
+
+        // Reviewers can only be requested once the pull request exists
+        if !options.reviewers.is_empty() {
+            let (teams, users): (Vec<&String>, Vec<&String>) =
+                options.reviewers.iter().partition(|r| r.contains('/'));
+            let teams: Vec<&str> = teams
+                .iter()
+                .filter_map(|slug| slug.split_once('/').map(|(_, team)| team))
+                .collect();
+            self.api
+                .send_json_ignore(
+                    Method::POST,
+                    &self.repo_path(&format!("/pulls/{}/requested_reviewers", pr.number)),
+                    &json!({ "reviewers": users, "team_reviewers": teams }),
+                )
+                .await?;
+        }
+        Ok(pr.into_info())
+    }
+
+    async fn merge_pull_request(
+        &self,
+        number: u64,
+        method: MergeMethod,
+        delete_branch: bool,
+    ) -> Result<(), ForgeError> {
+        let style = match method {
+            MergeMethod::Merge => "merge",
+            MergeMethod::Squash => "squash",
+            MergeMethod::Rebase => "rebase",
+        };
+        self.api
+            .send_json_ignore(
+                Method::POST,
+                &self.repo_path(&format!("/pulls/{}/merge", number)),
+                &json!({
+                    "Do": style,
+                    "delete_branch_after_merge": delete_branch,
+                }),
+            )
+            .await
+    }
+
+    async fn close_pull_request(&self, number: u64) -> Result<(), ForgeError> {
+        self.set_state("pulls", number, "closed").await
+    }
+
+    async fn comment_pull_request(&self, number: u64, body: &str) -> Result<(), ForgeError> {
+        self.comment(number, body).await
+    }
+
+    async fn list_issues(&self, filter: IssueFilter) -> Result<Vec<IssueInfo>, ForgeError> {
+        let mut query = vec![
+            ("state", state_query(filter.state.as_deref()).to_string()),
+            ("limit", limit_query(filter.limit)),
+            ("type", "issues".to_string()),
+        ];
+        if let Some(search) = filter.search {
+            query.push(("q", search));
+        }
+        let issues: Vec<GtIssue> = self.api.get(&self.repo_path("/issues"), &query).await?;
+        Ok(issues.into_iter().map(GtIssue::into_info).collect())
+    }
+
+    async fn get_issue(&self, number: u64) -> Result<IssueDetail, ForgeError> {
+        let issue: GtIssue = self
+            .api
+            .get(&self.repo_path(&format!("/issues/{}", number)), &[])
+            .await
+            .map_err(|e| {
+                if is_not_found(&e) {
+                    ForgeError::IssueNotFound { number }
+                } else {
+                    e
+                }
+            })?;
+        let comments = self.comments(number).await?;
+        let body = issue.body.clone().unwrap_or_default();
+        let info = issue.into_info();
+
+        Ok(IssueDetail {
+            number: info.number,
+            title: info.title,
+            body,
+            state: info.state,
+            author: info.author,
+            created_at: info.created_at,
+            updated_at: info.updated_at,
+            url: info.url,
+            labels: info.labels,
+            closed_at: info.closed_at,
+            comments,
+        })
+    }
+
+    async fn comment_issue(&self, number: u64, body: &str) -> Result<(), ForgeError> {
+        self.comment(number, body).await
+    }
+
+    async fn close_issue(&self, number: u64) -> Result<(), ForgeError> {
+        self.set_state("issues", number, "closed").await
+    }
+
+    async fn reopen_issue(&self, number: u64) -> Result<(), ForgeError> {
+        self.set_state("issues", number, "open").await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::forge::mock::MockServer;
+
+    fn pr_json(number: u64, title: &str, state: &str, merged: bool) -> String {
+        format!(
+            r##"{{
+                "number": {number}, "title": "{title}", "body": "desc", "state": "{state}",
+                "merged": {merged}, "user": {{"login": "carol"}},
+                "created_at": "2026-01-01T00:00:00Z", "updated_at": "2026-01-02T00:00:00Z",
+                "head": {{"ref": "feature"}}, "base": {{"ref": "main"}},
+                "html_url": "https://gitea.test/o/r/pulls/{number}",
+                "labels": [{{"name": "bug", "color": "#ee0701"}}],
+                "mergeable": true
+            }}"##
+        )
+    }
+
+    #[test]
+    fn test_draft_falls_back_to_wip_prefix() {
+        let pr: GtPullRequest =
+            serde_json::from_str(&pr_json(1, "WIP: refactor", "open", false)).unwrap();
+        assert!(pr.is_draft());
+        let info = pr.into_info();
+        assert_eq!(info.labels[0].color, "ee0701");
+    }
+
+    #[tokio::test]
+    async fn test_list_pull_requests_filters_merged_and_search() {
+        let prs = format!(
+            "[{},{},{}]",
+            pr_json(1, "Fix login", "closed", true),
+            pr_json(2, "Fix logout", "closed", false),
+            pr_json(3, "Docs", "closed", true),
+        );
+        let server = MockServer::start(vec![("GET /api/v1/repos/o/r/pulls", prs)]).await;
+        let gitea = Gitea::new(&server.url, "gitea.test", "o", "r", Some("secret".into()));
+
+        let merged = gitea
+            .list_pull_requests(PullRequestFilter {
+                state: Some("merged".into()),
+                limit: None,
+                search: Some("fix".into()),
+            })
+            .await
+            .unwrap();
+        assert_eq!(merged.len(), 1);
+        assert_eq!(merged[0].number, 1);
+        assert_eq!(merged[0].state, "MERGED");
+
+        let request = &server.requests()[0];
+        assert!(request.query.contains("state=closed"));
+        assert_eq!(request.header("authorization"), Some("token secret"));
+    }
+
+    #[tokio::test]
+    async fn test_get_pull_request_uses_issue_comments() {
+        let comments = r#"[{"id": 5, "body": "nice", "user": {"login": "dave"},
+                            "created_at": "2026-01-03T00:00:00Z"}]"#;
+        let server = MockServer::start(vec![
+            (
+                "GET /api/v1/repos/o/r/pulls/4",
+                pr_json(4, "Feature", "open", false),
+            ),
+            (
+                "GET /api/v1/repos/o/r/issues/4/comments",
+                comments.to_string(),
+            ),
+        ])
+        .await;
+        let gitea = Gitea::new(&server.url, "gitea.test", "o", "r", None);
+
+        let detail = gitea.get_pull_request(4).await.unwrap();
+        assert_eq!(detail.state, "OPEN");
+        assert_eq!(detail.mergeable, "MERGEABLE");
+        assert_eq!(detail.comments[0].id, "5");
+        assert_eq!(detail.comments[0].author.login, "dave");
+
+        assert!(matches!(
+            gitea.get_issue(404).await,
+            Err(ForgeError::IssueNotFound { number: 404 })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_merge_and_create_request_bodies() {
+        let server = MockServer::start(vec![
+            ("POST /api/v1/repos/o/r/pulls/4/merge", String::new()),
+            (
+                "POST /api/v1/repos/o/r/pulls",
+                pr_json(9, "WIP: New", "open", false),
+            ),
+        ])
+        .await;
+        let gitea = Gitea::new(&server.url, "gitea.test", "o", "r", Some("secret".into()));
+
+        gitea
+            .merge_pull_request(4, MergeMethod::Rebase, false)
+            .await
+            .unwrap();
+        let created = gitea
+            .create_pull_request(CreatePullRequestOptions {
+                title: "New".into(),
+                body: "body".into(),
+                base: "main".into(),
+                head: "feature".into(),
+                draft: true,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert!(created.is_draft);
+
+        let requests = server.requests();
+        let merge: serde_json::Value = serde_json::from_str(&requests[0].body).unwrap();
+        assert_eq!(merge["Do"], "rebase");
+        assert_eq!(merge["delete_branch_after_merge"], false);
+        let create: serde_json::Value = serde_json::from_str(&requests[1].body).unwrap();
+        assert_eq!(create["title"], "WIP: New");
+    }
+
+    #[tokio::test]
+    async fn test_create_pull_request_maps_labels_users_and_milestone() {
+        let server = MockServer::start(vec![
+            (
+                "GET /api/v1/repos/o/r/labels",
+                r#"[{"id": 3, "name": "bug"}, {"id": 5, "name": "ui"}]"#.to_string(),
+            ),
+            (
+                "GET /api/v1/repos/o/r/milestones",
+                r#"[{"id": 2, "title": "v1.0"}]"#.to_string(),
+            ),
+            (
+                "POST /api/v1/repos/o/r/pulls",
+                pr_json(9, "New", "open", false),
+            ),
+            (
+                "POST /api/v1/repos/o/r/pulls/9/requested_reviewers",
+                "[]".to_string(),
+            ),
+        ])
+        .await;
+        let gitea = Gitea::new(&server.url, "gitea.test", "o", "r", Some("secret".into()));
+
+        gitea
+            .create_pull_request(CreatePullRequestOptions {
+                title: "New".into(),
+                base: "main".into(),
+                head: "feature".into(),
+                labels: vec!["ui".into()],
+                assignees: vec!["alice".into()],
+                reviewers: vec!["bob".into(), "o/core".into()],
+                milestone: Some("v1.0".into()),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        let requests = server.requests();
+        assert!(requests[1].query.contains("name=v1.0"));
+        let create: serde_json::Value = serde_json::from_str(&requests[2].body).unwrap();
+        assert_eq!(create["labels"], json!([5]));
+        assert_eq!(create["assignees"], json!(["alice"]));
+        assert_eq!(create["milestone"], 2);
+        let review: serde_json::Value = serde_json::from_str(&requests[3].body).unwrap();
+        assert_eq!(review["reviewers"], json!(["bob"]));
+        assert_eq!(review["team_reviewers"], json!(["core"]));
+    }
+
+    #[tokio::test]
+    async fn test_create_pull_request_rejects_unmappable_options() {
+        let server = MockServer::start(vec![(
+            "GET /api/v1/repos/o/r/labels",
+            r#"[{"id": 3, "name": "bug"}]"#.to_string(),
+        )])
+        .await;
+        let gitea = Gitea::new(&server.url, "gitea.test", "o", "r", Some("secret".into()));
+        let options = |labels: Vec<String>, projects: Vec<String>| CreatePullRequestOptions {
+            title: "t".into(),
+            head: "f".into(),
+            base: "main".into(),
+            labels,
+            projects,
+            ..Default::default()
+        };
+
+        assert!(matches!(
+            gitea.create_pull_request(options(vec![], vec!["Roadmap".into()])).await,
+            Err(ForgeError::Unsupported { .. })
+        ));
+        assert!(matches!(
+            gitea.create_pull_request(options(vec!["missing".into()], vec![])).await,
+            Err(ForgeError::NameNotFound { kind: "Label", .. })
+        ));
+        // Nothing was created
+        assert!(server.requests().iter().all(|r| r.method == "GET"));
+    }
+}
@@ -0,0 +1,776 @@
+use reqwest::Method;
+use serde::Deserialize;
+use serde_json::json;
+
+use super::error::ForgeError;
+use super::http::{encode_segment, is_not_found, ApiClient};
+use super::{Forge, ForgeKind};
+use crate::github::{
+    AuthStatus, Comment, CommentReactions, CreatePullRequestOptions, IssueDetail, IssueFilter,
+    IssueInfo, MergeMethod, PrAuthor, PrLabel, PullRequestDetail, PullRequestFilter,
+    PullRequestInfo,
+};
+
+/// GitLab forge backed by the REST API v4.
+///
+/// Merge requests are addressed by their project-scoped `iid`, which is the
+/// `!123` number users see, and authenticated with a personal access token
+/// sent as an `Authorization: Bearer` header, which unlike `PRIVATE-TOKEN`
+/// is not forwarded on redirects to another host.
+#[derive(Debug, Clone)]
+pub struct GitLab {
+    api: ApiClient,
+    /// URL-encoded `namespace/project` path used as the project id.
+    project: String,
+}
+
+impl GitLab {
+    /// Creates a client for the project at `project_path` (e.g. `group/sub/repo`)
+    /// on the instance at `base_url` (e.g. `https://gitlab.example.com`).
+    pub fn new(base_url: &str, host: &str, project_path: &str, token: Option<String>) -> Self {
+        let api_base = format!("{}/api/v4", base_url.trim_end_matches('/'));
+        Self {
+            api: ApiClient::new(&api_base, host, "authorization", token, |t| {
+                format!("Bearer {}", t)
+            }),
+            project: encode_segment(project_path),
+        }
+    }
+
+    fn project_path(&self, rest: &str) -> String {
+        format!("/projects/{}{}", self.project, rest)
+    }
+
+    async fn notes(&self, kind: &str, iid: u64) -> Result<Vec<Comment>, ForgeError> {
+        let notes: Vec<GlNote> = self
+            .api
+            .get(
+                &self.project_path(&format!("/{}/{}/notes", kind, iid)),
+                &[
+                    ("sort", "asc".to_string()),
+                    ("order_by", "created_at".to_string()),
+                    ("per_page", "100".to_string()),
+                ],
+            )
+            .await?;
+
+        Ok(notes
+            .into_iter()
+            .filter(|n| !n.system)
+            .map(GlNote::into_comment)
+            .collect())
+    }
+
+    async fn set_state(&self, kind: &str, iid: u64, event: &str) -> Result<(), ForgeError> {
+        self.api
+            .send_json_ignore(
+                Method::PUT,
+                &self.project_path(&format!("/{}/{}", kind, iid)),
+                &json!({ "state_event": event }),
+            )
+            .await
+    }
+
+    /// Resolves usernames to the numeric IDs the merge request API expects.
+    async fn user_ids(&self, logins: &[String]) -> Result<Vec<u64>, ForgeError> {
+        let mut ids = Vec::with_capacity(logins.len());
+        for login in logins {
+            let users: Vec<GlId> = self
+                .api
+                .get("/users", &[("username", login.clone())])
+                .await?;
+            let user = users.into_iter().next().ok_or_else(|| ForgeError::NameNotFound {
+                kind: "User",
+                name: login.clone(),
+                forge: ForgeKind::GitLab.display_name(),
+            })?;
+            ids.push(user.id);
+        }
+        Ok(ids)
+    }
+
+    /// Resolves a milestone title to its project-level ID.
+    async fn milestone_id(&self, title: &str) -> Result<u64, ForgeError> {
+        let milestones: Vec<GlId> = self
+            .api
+            .get(
+                &self.project_path("/milestones"),
+                &[("title", title.to_string())],
+            )
+            .await?;
+        milestones
+            .into_iter()
+            .next()
+            .map(|m| m.id)
+            .ok_or_else(|| ForgeError::NameNotFound {
+                kind: "Milestone",
+                name: title.to_string(),
+                forge: ForgeKind::GitLab.display_name(),
+            })
+    }
+}
+
+#[derive(Deserialize)]
+struct GlUser {
+    username: String,
+}
+
+/// Any GitLab object referenced by its global `id`.
+#[derive(Deserialize)]
+struct GlId {
+    id: u64,
+}
+
+impl GlUser {
+    fn into_author(self) -> PrAuthor {
+        PrAuthor {
+            login: self.username,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct GlMergeRequest {
+    iid: u64,
+    title: String,
+    #[serde(default)]
+    description: Option<String>,
+    state: String,
+    author: GlUser,
+    created_at: String,
+    updated_at: String,
+    source_branch: String,
+    target_branch: String,
+    #[serde(default)]
+    draft: bool,
+    #[serde(default)]
+    work_in_progress: bool,
+    web_url: String,
+    #[serde(default)]
+    labels: Vec<String>,
+    #[serde(default)]
+    merged_at: Option<String>,
+    #[serde(default)]
+    closed_at: Option<String>,
+    /// Number of changed files as a string, capped at `"1000+"`.
+    #[serde(default)]
+    changes_count: Option<String>,
+    #[serde(default)]
+    detailed_merge_status: Option<String>,
+    #[serde(default)]
+    merge_status: Option<String>,
+}
+
+impl GlMergeRequest {
+    fn into_info(self) -> PullRequestInfo {
+        PullRequestInfo {
+            number: self.iid,
+            title: self.title,
+            state: normalize_state(&self.state),
+            author: self.author.into_author(),
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+            head_ref_name: self.source_branch,
+            base_ref_name: self.target_branch,
+            is_draft: self.draft || self.work_in_progress,
+            // GitLab only reports line counts per diff, not per MR
+            additions: 0,
+            deletions: 0,
+            url: self.web_url,
+            labels: labels(self.labels),
+            merged_at: self.merged_at,
+            closed_at: self.closed_at,
+        }
+    }
+
+    fn into_detail(self, comments: Vec<Comment>) -> PullRequestDetail {
+        let mergeable = mergeable(
+            self.detailed_merge_status
+                .as_deref()
+                .or(self.merge_status.as_deref()),
+        );
+        let changed_files = self
+            .changes_count
+            .as_deref()
+            .map(|c| c.trim_end_matches('+'))
+            .and_then(|c| c.parse().ok())
+            .unwrap_or(0);
+        let body = self.description.clone().unwrap_or_default();
+        let info = self.into_info();
+
+        PullRequestDetail {
+            number: info.number,
+            title: info.title,
+            body,
+            state: info.state,
+            author: info.author,
+            created_at: info.created_at,
+            updated_at: info.updated_at,
+            head_ref_name: info.head_ref_name,
+            base_ref_name: info.base_ref_name,
+            is_draft: info.is_draft,
+            additions: 0,
+            deletions: 0,
+            changed_files,
+            url: info.url,
+            labels: info.labels,
+            merged_at: info.merged_at,
+            closed_at: info.closed_at,
+            mergeable,
+            review_decision: None,
+            comments,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct GlIssue {
+    iid: u64,
+    title: String,
+    #[serde(default)]
+    description: Option<String>,
+    state: String,
+    author: GlUser,
+    created_at: String,
+    updated_at: String,
+    web_url: String,
+    #[serde(default)]
+    labels: Vec<String>,
+    #[serde(default)]
+    closed_at: Option<String>,
+}
+
+impl GlIssue {
+    fn into_info(self) -> IssueInfo {
+        IssueInfo {
+            number: self.iid,
+            title: self.title,
+            state: normalize_state(&self.state),
+            author: self.author.into_author(),
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+            url: self.web_url,
+            labels: labels(self.labels),
+            closed_at: self.closed_at,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct GlNote {
+    id: u64,
+    body: String,
+    author: GlUser,
+    created_at: String,
+    #[serde(default)]
+    updated_at: Option<String>,
+    /// Notes generated by GitLab itself ("changed the description", ...).
+    #[serde(default)]
+    system: bool,
+}
+
+impl GlNote {
+    fn into_comment(self) -> Comment {
+        Comment {
+            id: self.id.to_string(),
+            author: self.author.into_author(),
+            body: self.body,
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+            reactions: CommentReactions::default(),
+            is_answer: false,
+        }
+    }
+}
+
+/// Maps GitLab's `opened`/`closed`/`merged`/`locked` to gh's uppercase states.
+fn normalize_state(state: &str) -> String {
+    match state {
+        "opened" | "locked" => "OPEN",
+        "merged" => "MERGED",
+        "closed" => "CLOSED",
+        other => return other.to_uppercase(),
+    }
+    .to_string()
+}
+
+/// Maps a PR filter state onto GitLab's `state` query value.
+fn state_query(state: Option<&str>) -> &'static str {
+    match state {
+        Some("closed") => "closed",
+        Some("merged") => "merged",
+        Some("all") => "all",
+        _ => "opened",
+    }
+}
+
+/// Maps GitLab's merge status onto gh's `MERGEABLE`/`CONFLICTING`/`UNKNOWN`.
+fn mergeable(status: Option<&str>) -> String {
+    match status {
+        Some("mergeable") | Some("can_be_merged") => "MERGEABLE",
+        Some("conflict") | Some("cannot_be_merged") | Some("broken_status") => "CONFLICTING",
+        _ => "UNKNOWN",
+    }
+    .to_string()
+}
+
+/// GitLab list endpoints only return label names.
+fn labels(names: Vec<String>) -> Vec<PrLabel> {
+    names
+        .into_iter()
+        .map(|name| PrLabel {
+            name,
+            color: String::new(),
+        })
+        .collect()
+}
+
+fn list_query(
+    state: Option<&str>,
+    limit: Option<u32>,
+    search: Option<&str>,
+) -> Vec<(&'static str, String)> {
+    let mut query = vec![
+        ("state", state_query(state).to_string()),
+        ("per_page", limit.unwrap_or(50).min(100).to_string()),
+        ("order_by", "updated_at".to_string()),
+    ];
+    if let Some(search) = search {
+        query.push(("search", search.to_string()));
+    }
+    query
+}
+
+impl Forge for GitLab {
+    async fn auth_status(&self) -> Result<AuthStatus, ForgeError> {
+        if !self.api.has_token() {
+            return Ok(AuthStatus {
+                logged_in: false,
+                username: None,
+                scopes: vec![],
+            });
+        }
+
+        match self.api.get::<GlUser>("/user", &[]).await {
+            Ok(user) => Ok(AuthStatus {
+                logged_in: true,
+                username: Some(user.username),
+                scopes: vec![],
+            }),
+            Err(ForgeError::NotAuthenticated { .. }) => Ok(AuthStatus {
+                logged_in: false,
+                username: None,
+                scopes: vec![],
+            }),
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn list_pull_requests(
+        &self,
+        filter: PullRequestFilter,
+    ) -> Result<Vec<PullRequestInfo>, ForgeError> {
+        let query = list_query(
+            filter.state.as_deref(),
+            filter.limit,
+            filter.search.as_deref(),
+        );
+        let mrs: Vec<GlMergeRequest> = self
+            .api
+            .get(&self.project_path("/merge_requests"), &query)
+            .await?;
+        Ok(mrs.into_iter().map(GlMergeRequest::into_info).collect())
+    }
+
+    async fn get_pull_request(&self, number: u64) -> Result<PullRequestDetail, ForgeError> {
+        let mr: GlMergeRequest = self
+            .api
+            .get(
+                &self.project_path(&format!("/merge_requests/{}", number)),
+                &[],
+            )
+            .await
+            .map_err(|e| {
+                if is_not_found(&e) {
+                    ForgeError::PullRequestNotFound { number }
+                } else {
+                    e
+                }
+            })?;
+        let comments = self.notes("merge_requests", number).await?;
+        Ok(mr.into_detail(comments))
+    }
+
+    async fn create_pull_request(
+        &self,
+        options: CreatePullRequestOptions,
+    ) -> Result<PullRequestInfo, ForgeError> {
+        if !options.projects.is_empty() {
+            return Err(ForgeError::Unsupported {
+                operation: "Adding merge requests to projects",
+                forge: ForgeKind::GitLab.display_name(),
+            });
+        }
+        if options.reviewers.iter().any(|r| r.contains('/')) {
+            return Err(ForgeError::Unsupported {
+                operation: "Requesting reviews from teams",
+                forge: ForgeKind::GitLab.display_name(),
+            });
+        }
+
+        // GitLab marks drafts by title prefix rather than a flag
+        let title = if options.draft {
+            format!("Draft: {}", options.title)
+        } else {
+            options.title
+        };
+        let mut body = json!({
+            "source_branch": options.head,
+            "target_branch": options.base,
+            "title": title,
+            "description": options.body,
+        });
+        if !options.labels.is_empty() {
+            body["labels"] = json!(options.labels.join(","));
+        }
+        if !options.assignees.is_empty() {
+            body["assignee_ids"] = json!(self.user_ids(&options.assignees).await?);
+        }
+        if !options.reviewers.is_empty() {
+            body["reviewer_ids"] = json!(self.user_ids(&options.reviewers).await?);
+        }
+        if let Some(milestone) = options.milestone.as_deref().filter(|m| !m.is_empty()) {
+            body["milestone_id"] = json!(self.milestone_id(milestone).await?);
+        }
+
+        let mr: GlMergeRequest = self
+            .api
+            .send_json(Method::POST, &self.project_path("/merge_requests"), &body)
+            .await?;
+        Ok(mr.into_info())
+    }
+
+    async fn merge_pull_request(
+        &self,
+        number: u64,
+        method: MergeMethod,
+        delete_branch: bool,
+    ) -> Result<(), ForgeError> {
+        let squash = match method {
+            MergeMethod::Merge => false,
+            MergeMethod::Squash => true,
+            // Rebasing is a separate asynchronous endpoint on GitLab and the
+            // project's merge method decides whether a merge fast-forwards.
+            MergeMethod::Rebase => {
+                return Err(ForgeError::Unsupported {
+                    operation: "Rebase merging",
+                    forge: ForgeKind::GitLab.display_name(),
+                })
+            }
+        };
+
+        self.api
+            .send_json_ignore(
+                Method::PUT,
+                &self.project_path(&format!("/merge_requests/{}/merge", number)),
+                &json!({
+                    "squash": squash,
+                    "should_remove_source_branch": delete_branch,
+                }),
+            )
+            .await
+    }
+
+    async fn close_pull_request(&self, number: u64) -> Result<(), ForgeError> {
+        self.set_state("merge_requests", number, "close").await
+    }
+
+    async fn comment_pull_request(&self, number: u64, body: &str) -> Result<(), ForgeError> {
+        self.api
+            .send_json_ignore(
+                Method::POST,
+                &self.project_path(&format!("/merge_requests/{}/notes", number)),
+                &json!({ "body": body }),
+            )
+            .await
+    }
+
+    async fn list_issues(&self, filter: IssueFilter) -> Result<Vec<IssueInfo>, ForgeError> {
+        let query = list_query(
+            filter.state.as_deref(),
+            filter.limit,
+            filter.search.as_deref(),
+        );
+        let issues: Vec<GlIssue> = self.api.get(&self.project_path("/issues"), &query).await?;
+        Ok(issues.into_iter().map(GlIssue::into_info).collect())
+    }
+
+    async fn get_issue(&self, number: u64) -> Result<IssueDetail, ForgeError> {
+        let issue: GlIssue = self
+            .api
+            .get(&self.project_path(&format!("/issues/{}", number)), &[])
+            .await
+            .map_err(|e| {
+                if is_not_found(&e) {
+                    ForgeError::IssueNotFound { number }
+                } else {
+                    e
+                }
+            })?;
+        let comments = self.notes("issues", number).await?;
+        let body = issue.description.clone().unwrap_or_default();
+        let info = issue.into_info();
+
+        Ok(IssueDetail {
+            number: info.number,
+            title: info.title,
+            body,
+            state: info.state,
+            author: info.author,
+            created_at: info.created_at,
+            updated_at: info.updated_at,
+            url: info.url,
+            labels: info.labels,
+            closed_at: info.closed_at,
+            comments,
+        })
+    }
+
+    async fn comment_issue(&self, number: u64, body: &str) -> Result<(), ForgeError> {
+        self.api
+            .send_json_ignore(
+                Method::POST,
+                &self.project_path(&format!("/issues/{}/notes", number)),
+                &json!({ "body": body }),
+            )
+            .await
+    }
+
+    async fn close_issue(&self, number: u64) -> Result<(), ForgeError> {
+        self.set_state("issues", number, "close").await
+    }
+
+    async fn reopen_issue(&self, number: u64) -> Result<(), ForgeError> {
+        self.set_state("issues", number, "reopen").await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::forge::mock::{MockResponse, MockServer};
+
+    const MR_JSON: &str = r#"{
+        "iid": 7, "title": "Add login", "description": "Closes #3", "state": "opened",
+        "author": {"username": "alice"},
+        "created_at": "2026-01-01T00:00:00Z", "updated_at": "2026-01-02T00:00:00Z",
+        "source_branch": "feature/login", "target_branch": "main",
+        "draft": true, "web_url": "https://gitlab.test/g/r/-/merge_requests/7",
+        "labels": ["backend"], "merged_at": null, "closed_at": null,
+        "changes_count": "1000+", "detailed_merge_status": "mergeable"
+    }"#;
+
+    #[test]
+    fn test_normalize_state() {
+        assert_eq!(normalize_state("opened"), "OPEN");
+        assert_eq!(normalize_state("merged"), "MERGED");
+        assert_eq!(normalize_state("closed"), "CLOSED");
+        assert_eq!(normalize_state("locked"), "OPEN");
+    }
+
+    #[test]
+    fn test_merge_request_into_detail() {
+        let mr: GlMergeRequest = serde_json::from_str(MR_JSON).unwrap();
+        let detail = mr.into_detail(vec![]);
+        assert_eq!(detail.number, 7);
+        assert_eq!(detail.state, "OPEN");
+        assert_eq!(detail.body, "Closes #3");
+        assert_eq!(detail.head_ref_name, "feature/login");
+        assert!(detail.is_draft);
+        assert_eq!(detail.changed_files, 1000);
+        assert_eq!(detail.mergeable, "MERGEABLE");
+        assert_eq!(detail.labels[0].name, "backend");
+    }
+
+    #[tokio::test]
+    async fn test_list_and_get_merge_requests() {
+        let notes = r#"[
+            {"id": 1, "body": "assigned to @bob", "author": {"username": "alice"},
+             "created_at": "2026-01-01T00:00:00Z", "system": true},
+            {"id": 2, "body": "LGTM", "author": {"username": "bob"},
+             "created_at": "2026-01-01T01:00:00Z", "system": false}
+        ]"#;
+        let server = MockServer::start(vec![
+            (
+                "GET /api/v4/projects/g%2Fr/merge_requests",
+                format!("[{}]", MR_JSON),
+            ),
+            (
+                "GET /api/v4/projects/g%2Fr/merge_requests/7",
+                MR_JSON.to_string(),
+            ),
+            (
+                "GET /api/v4/projects/g%2Fr/merge_requests/7/notes",
+                notes.to_string(),
+            ),
+        ])
+        .await;
+        let gitlab = GitLab::new(&server.url, "gitlab.test", "g/r", Some("secret".into()));
+
+        let mrs = gitlab
+            .list_pull_requests(PullRequestFilter {
+                state: Some("open".into()),
+                limit: Some(10),
+                search: None,
+            })
+            .await
+            .unwrap();
+        assert_eq!(mrs.len(), 1);
+        assert_eq!(mrs[0].author.login, "alice");
+
+        let detail = gitlab.get_pull_request(7).await.unwrap();
+        assert_eq!(detail.comments.len(), 1, "system notes are dropped");
+        assert_eq!(detail.comments[0].body, "LGTM");
+
+        let requests = server.requests();
+        assert!(requests[0].query.contains("state=opened"));
+        assert!(requests[0].query.contains("per_page=10"));
+        assert_eq!(requests[0].header("authorization"), Some("Bearer secret"));
+    }
+
+    #[tokio::test]
+    async fn test_merge_sends_squash_and_branch_removal() {
+        let server = MockServer::start(vec![(
+            "PUT /api/v4/projects/g%2Fr/merge_requests/7/merge",
+            MR_JSON.to_string(),
+        )])
+        .await;
+        let gitlab = GitLab::new(&server.url, "gitlab.test", "g/r", Some("secret".into()));
+
+        gitlab
+            .merge_pull_request(7, MergeMethod::Squash, true)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_str(&server.requests()[0].body).unwrap();
+        assert_eq!(body["squash"], true);
+        assert_eq!(body["should_remove_source_branch"], true);
+
+        assert!(matches!(
+            gitlab
+                .merge_pull_request(7, MergeMethod::Rebase, false)
+                .await,
+            Err(ForgeError::Unsupported { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_missing_merge_request_and_bad_token() {
+        let server = MockServer::start::<String>(vec![]).await;
+        let gitlab = GitLab::new(&server.url, "gitlab.test", "g/r", Some("secret".into()));
+        assert!(matches!(
+            gitlab.get_pull_request(99).await,
+            Err(ForgeError::PullRequestNotFound { number: 99 })
+        ));
+
+        let server = MockServer::start(vec![("GET /api/v4/user", "!401".to_string())]).await;
+        let gitlab = GitLab::new(&server.url, "gitlab.test", "g/r", Some("expired".into()));
+        let status = gitlab.auth_status().await.unwrap();
+        assert!(!status.logged_in);
+    }
+
+    #[tokio::test]
+    async fn test_create_merge_request_maps_labels_users_and_milestone() {
+        let server = MockServer::start(vec![
+            ("GET /api/v4/users", r#"[{"id": 41}]"#.to_string()),
+            (
+                "GET /api/v4/projects/g%2Fr/milestones",
+                r#"[{"id": 9}]"#.to_string(),
+            ),
+            ("POST /api/v4/projects/g%2Fr/merge_requests", MR_JSON.to_string()),
+        ])
+        .await;
+        let gitlab = GitLab::new(&server.url, "gitlab.test", "g/r", Some("secret".into()));
+
+        gitlab
+            .create_pull_request(CreatePullRequestOptions {
+                title: "Add login".into(),
+                body: "Closes #3".into(),
+                base: "main".into(),
+                head: "feature/login".into(),
+                draft: true,
+                labels: vec!["backend".into(), "auth".into()],
+                assignees: vec!["alice".into()],
+                reviewers: vec!["bob".into()],
+                milestone: Some("v1.0".into()),
+                projects: vec![],
+            })
+            .await
+            .unwrap();
+
+        let requests = server.requests();
+        assert!(requests[0].query.contains("username=alice"));
+        assert!(requests[1].query.contains("username=bob"));
+        assert!(requests[2].query.contains("title=v1.0"));
+        let body: serde_json::Value = serde_json::from_str(&requests[3].body).unwrap();
+        assert_eq!(body["title"], "Draft: Add login");
+        assert_eq!(body["labels"], "backend,auth");
+        assert_eq!(body["assignee_ids"], json!([41]));
+        assert_eq!(body["reviewer_ids"], json!([41]));
+        assert_eq!(body["milestone_id"], 9);
+    }
+
+    #[tokio::test]
+    async fn test_create_merge_request_rejects_unmappable_options() {
+        let server = MockServer::start(vec![("GET /api/v4/users", "[]".to_string())]).await;
+        let gitlab = GitLab::new(&server.url, "gitlab.test", "g/r", Some("secret".into()));
+        let options = |projects: Vec<String>, reviewers: Vec<String>| CreatePullRequestOptions {
+            title: "t".into(),
+            head: "f".into(),
+            base: "main".into(),
+            projects,
+            reviewers,
+            ..Default::default()
+        };
+
+        assert!(matches!(
+            gitlab.create_pull_request(options(vec!["Roadmap".into()], vec![])).await,
+            Err(ForgeError::Unsupported { .. })
+        ));
+        assert!(matches!(
+            gitlab.create_pull_request(options(vec![], vec!["org/team".into()])).await,
+            Err(ForgeError::Unsupported { .. })
+        ));
+        assert!(matches!(
+            gitlab.create_pull_request(options(vec![], vec!["ghost".into()])).await,
+            Err(ForgeError::NameNotFound { kind: "User", .. })
+        ));
+        // Nothing was created
+        assert!(server.requests().iter().all(|r| r.method == "GET"));
+    }
+
+    #[tokio::test]
+    async fn test_token_not_forwarded_on_cross_host_redirect() {
+        let path = "/api/v4/projects/g%2Fr/merge_requests";
+        let other = MockServer::start(vec![(
+            "GET /api/v4/projects/g%2Fr/merge_requests",
+            format!("[{}]", MR_JSON),
+        )])
+        .await;
+        let server = MockServer::start(vec![(
+            "GET /api/v4/projects/g%2Fr/merge_requests",
+            MockResponse::status(302).with_header("location", format!("{}{}", other.url, path)),
+        )])
+        .await;
+        let gitlab = GitLab::new(&server.url, "gitlab.test", "g/r", Some("secret".into()));
+
+        let mrs = gitlab
+            .list_pull_requests(PullRequestFilter::default())
+            .await
+            .unwrap();
+        assert_eq!(mrs.len(), 1);
+        assert_eq!(server.requests()[0].header("authorization"), Some("Bearer secret"));
+        let redirected = &other.requests()[0];
+        assert_eq!(redirected.header("authorization"), None);
+        assert_eq!(redirected.header("private-token"), None);
+    }
+}
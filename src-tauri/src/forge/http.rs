@@ -0,0 +1,383 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, ETAG, IF_NONE_MATCH};
+use reqwest::{Method, RequestBuilder, Response, StatusCode};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use super::error::ForgeError;
+
+/// Minimal JSON REST client shared by the GitHub, GitLab and Gitea APIs.
+///
+/// Paths are appended to `api_base` verbatim, so callers percent-encode any
+/// path segments themselves. The auth header is only sent when a token is
+/// configured; requests without one still work against public projects.
+/// Tokens are only sent over HTTPS (or plain HTTP to the local machine), and
+/// should go in `Authorization`, which is dropped on cross-origin redirects.
+#[derive(Debug, Clone)]
+pub(crate) struct ApiClient {
+    client: reqwest::Client,
+    api_base: String,
+    host: String,
+    has_token: bool,
+    /// Shared by clones, so a cached client keeps its conditional GETs.
+    etags: Option<Arc<Mutex<EtagCache>>>,
+}
+
+/// Bodies of previous GET responses keyed by URL, replayed when the server
+/// answers a conditional request with 304. Holds at most `capacity` entries
+/// and evicts the least recently stored.
+#[derive(Debug)]
+struct EtagCache {
+    capacity: usize,
+    entries: HashMap<String, (String, String)>,
+    order: VecDeque<String>,
+}
+
+impl EtagCache {
+    fn insert(&mut self, url: String, etag: String, body: String) {
+        if self.entries.insert(url.clone(), (etag, body)).is_some() {
+            self.order.retain(|u| *u != url);
+        }
+        self.order.push_back(url);
+        while self.order.len() > self.capacity {
+            if let Some(oldest) = self.order.pop_front() {
+                self.entries.remove(&oldest);
+            }
+        }
+    }
+}
+
+/// Whether a token may be sent to `api_base`: HTTPS anywhere, plain HTTP
+/// only to the local machine.
+fn token_allowed(api_base: &str) -> bool {
+    let Ok(url) = url::Url::parse(api_base) else {
+        return false;
+    };
+    match (url.scheme(), url.host()) {
+        ("https", Some(_)) => true,
+        ("http", Some(url::Host::Domain(domain))) => domain.eq_ignore_ascii_case("localhost"),
+        ("http", Some(url::Host::Ipv4(ip))) => ip.is_loopback(),
+        ("http", Some(url::Host::Ipv6(ip))) => ip.is_loopback(),
+        _ => false,
+    }
+}
+
+impl ApiClient {
+    /// Creates a client for `api_base` (e.g. `https://gitlab.example.com/api/v4`)
+    /// that sends `token` in `auth_header`, formatted by `auth_value`.
+    pub(crate) fn new(
+        api_base: &str,
+        host: &str,
+        auth_header: &'static str,
+        token: Option<String>,
+        auth_value: impl Fn(&str) -> String,
+    ) -> Self {
+        Self::with_headers(api_base, host, HeaderMap::new(), auth_header, token, auth_value)
+    }
+
+    /// Like [`new`](Self::new), but also sends `headers` with every request.
+    pub(crate) fn with_headers(
+        api_base: &str,
+        host: &str,
+        mut headers: HeaderMap,
+        auth_header: &'static str,
+        token: Option<String>,
+        auth_value: impl Fn(&str) -> String,
+    ) -> Self {
+        let token = token.filter(|_| {
+            let allowed = token_allowed(api_base);
+            if !allowed {
+                log::warn!("Not sending the {} token over insecure {}", host, api_base);
+            }
+            allowed
+        });
+        if let Some(mut value) = token
+            .as_deref()
+            .and_then(|t| HeaderValue::from_str(&auth_value(t)).ok())
+        {
+            value.set_sensitive(true);
+            headers.insert(HeaderName::from_static(auth_header), value);
+        }
+
+        let client = reqwest::Client::builder()
+            .default_headers(headers)
+            .user_agent(concat!("maestro/", env!("CARGO_PKG_VERSION")))
+            .timeout(Duration::from_secs(30))
+            .build()
+            .unwrap_or_default();
+
+        Self {
+            client,
+            api_base: api_base.trim_end_matches('/').to_string(),
+            host: host.to_string(),
+            has_token: token.is_some(),
+            etags: None,
+        }
+    }
+
+    /// Makes [`get`](Self::get) send conditional requests, remembering up
+    /// to `capacity` response bodies by their `ETag`. 304 responses don't
+    /// count against GitHub's rate limit, which matters for polled panels.
+    pub(crate) fn with_etag_cache(mut self, capacity: usize) -> Self {
+        self.etags = Some(Arc::new(Mutex::new(EtagCache {
+            capacity,
+            entries: HashMap::new(),
+            order: VecDeque::new(),
+        })));
+        self
+    }
+
+    /// A client for another API root on the same host (e.g. GitHub's
+    /// GraphQL endpoint), sharing the connection pool and credentials.
+    pub(crate) fn with_base(&self, api_base: &str) -> Self {
+        Self {
+            api_base: api_base.trim_end_matches('/').to_string(),
+            etags: None,
+            ..self.clone()
+        }
+    }
+
+    /// Whether a token was configured for this client.
+    pub(crate) fn has_token(&self) -> bool {
+        self.has_token
+    }
+
+    fn request(&self, method: Method, path: &str) -> RequestBuilder {
+        self.client
+            .request(method, format!("{}{}", self.api_base, path))
+    }
+
+    /// Sends a GET with query parameters and deserializes the JSON response.
+    /// With an ETag cache, a 304 replays the previously cached body.
+    pub(crate) async fn get<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        query: &[(&str, String)],
+    ) -> Result<T, ForgeError> {
+        let Some(ref etags) = self.etags else {
+            let response = self
+                .send(self.request(Method::GET, path).query(query))
+                .await?;
+            return Ok(response.json().await?);
+        };
+
+        let mut request = self.request(Method::GET, path).query(query).build()?;
+        let key = request.url().to_string();
+        let cached = etags.lock().unwrap().entries.get(&key).cloned();
+        if let Some(value) = cached
+            .as_ref()
+            .and_then(|(etag, _)| HeaderValue::from_str(etag).ok())
+        {
+            request.headers_mut().insert(IF_NONE_MATCH, value);
+        }
+
+        let response = self.client.execute(request).await?;
+        if response.status() == StatusCode::NOT_MODIFIED {
+            if let Some((_, body)) = cached {
+                return Ok(serde_json::from_str(&body)?);
+            }
+        }
+        let response = self.check(response).await?;
+        let etag = response
+            .headers()
+            .get(ETAG)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+        let body = response.text().await?;
+        let parsed = serde_json::from_str(&body)?;
+        if let Some(etag) = etag {
+            etags.lock().unwrap().insert(key, etag, body);
+        }
+        Ok(parsed)
+    }
+
+    /// Sends a GET and returns the raw response, for callers that need its
+    /// headers or a non-JSON body.
+    pub(crate) async fn get_response(&self, path: &str) -> Result<Response, ForgeError> {
+        self.send(self.request(Method::GET, path)).await
+    }
+
+    /// Sends `body` as JSON and deserializes the JSON response.
+    pub(crate) async fn send_json<B: Serialize + ?Sized, T: DeserializeOwned>(
+        &self,
+        method: Method,
+        path: &str,
+        body: &B,
+    ) -> Result<T, ForgeError> {
+        let response = self.send(self.request(method, path).json(body)).await?;
+        Ok(response.json().await?)
+    }
+
+    /// Sends `body` as JSON and discards the response body.
+    pub(crate) async fn send_json_ignore<B: Serialize + ?Sized>(
+        &self,
+        method: Method,
+        path: &str,
+        body: &B,
+    ) -> Result<(), ForgeError> {
+        self.send(self.request(method, path).json(body)).await?;
+        Ok(())
+    }
+
+    /// Sends a request and maps non-success statuses to [`ForgeError`].
+    async fn send(&self, request: RequestBuilder) -> Result<Response, ForgeError> {
+        self.check(request.send().await?).await
+    }
+
+    /// Passes successful responses through and maps the others.
+    ///
+    /// 404 is returned as `Api { status: 404, .. }` so callers can turn it
+    /// into `PullRequestNotFound` / `IssueNotFound` with the right number.
+    async fn check(&self, response: Response) -> Result<Response, ForgeError> {
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response);
+        }
+
+        let reset_at = rate_limit_reset(response.headers());
+        let exhausted = response
+            .headers()
+            .get("x-ratelimit-remaining")
+            .is_some_and(|v| v.as_bytes() == b"0");
+        let message = api_message(&response.text().await.unwrap_or_default());
+        let host = self.host.clone();
+        Err(match status {
+            StatusCode::UNAUTHORIZED => ForgeError::NotAuthenticated { host },
+            // GitHub reports exhausted rate limits as 403
+            StatusCode::TOO_MANY_REQUESTS => ForgeError::RateLimitExceeded { host, reset_at },
+            StatusCode::FORBIDDEN
+                if exhausted || message.to_lowercase().contains("rate limit") =>
+            {
+                ForgeError::RateLimitExceeded { host, reset_at }
+            }
+            StatusCode::FORBIDDEN if !self.has_token => ForgeError::NotAuthenticated { host },
+            _ => ForgeError::Api {
+                host,
+                status: status.as_u16(),
+                message,
+            },
+        })
+    }
+}
+
+/// Returns when the rate limit resets, from `x-ratelimit-reset` /
+/// `ratelimit-reset` (Unix time) or `retry-after` (seconds from now).
+fn rate_limit_reset(headers: &HeaderMap) -> Option<i64> {
+    let header = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.trim().parse::<i64>().ok())
+    };
+    header("x-ratelimit-reset")
+        .or_else(|| header("ratelimit-reset"))
+        .or_else(|| header("retry-after").map(|secs| chrono::Utc::now().timestamp() + secs))
+}
+
+/// Extracts the error message from a GitHub or Gitea (`message`) or GitLab
+/// (`message` / `error`) error body, falling back to the raw text.
+fn api_message(body: &str) -> String {
+    let parsed: Option<serde_json::Value> = serde_json::from_str(body).ok();
+    let message = parsed.as_ref().and_then(|v| {
+        v.get("message")
+            .or_else(|| v.get("error"))
+            .map(|m| match m {
+                serde_json::Value::String(s) => s.clone(),
+                other => other.to_string(),
+            })
+    });
+    message.unwrap_or_else(|| body.trim().to_string())
+}
+
+/// Percent-encodes a single URL path segment (including `/`).
+pub(crate) fn encode_segment(segment: &str) -> String {
+    url::form_urlencoded::byte_serialize(segment.as_bytes())
+        .collect::<String>()
+        // form encoding turns spaces into `+`, which is literal in a path
+        .replace('+', "%20")
+}
+
+/// Returns true if the error is a 404 from the forge API.
+pub(super) fn is_not_found(err: &ForgeError) -> bool {
+    matches!(err, ForgeError::Api { status: 404, .. })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_api_message_prefers_json_message() {
+        assert_eq!(
+            api_message(r#"{"message":"404 Not found"}"#),
+            "404 Not found"
+        );
+        assert_eq!(
+            api_message(r#"{"message":{"base":["is invalid"]}}"#),
+            r#"{"base":["is invalid"]}"#
+        );
+        assert_eq!(api_message(r#"{"error":"invalid_token"}"#), "invalid_token");
+        assert_eq!(api_message("  bad gateway \n"), "bad gateway");
+    }
+
+    #[test]
+    fn test_rate_limit_reset_headers() {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-ratelimit-reset", HeaderValue::from_static("1767225600"));
+        assert_eq!(rate_limit_reset(&headers), Some(1767225600));
+
+        let mut headers = HeaderMap::new();
+        headers.insert("retry-after", HeaderValue::from_static("60"));
+        let reset = rate_limit_reset(&headers).unwrap();
+        assert!(reset >= chrono::Utc::now().timestamp() + 59);
+
+        assert_eq!(rate_limit_reset(&HeaderMap::new()), None);
+    }
+
+    #[test]
+    fn test_tokens_only_sent_over_https_or_to_localhost() {
+        assert!(token_allowed("https://gitlab.example.com/api/v4"));
+        assert!(token_allowed("https://gitlab.example.com:8443/api/v4"));
+        assert!(token_allowed("http://localhost:3000/api/v1"));
+        assert!(token_allowed("http://127.0.0.1:8080/api/v4"));
+        assert!(token_allowed("http://[::1]/api/v4"));
+        assert!(!token_allowed("http://gitlab.example.com/api/v4"));
+        assert!(!token_allowed("http://localhost.example.com/api/v4"));
+        assert!(!token_allowed("not a url"));
+
+        let token = Some("secret".to_string());
+        let client =
+            |base| ApiClient::new(base, "host", "authorization", token.clone(), str::to_string);
+        assert!(client("https://gitlab.example.com/api/v4").has_token());
+        assert!(!client("http://gitlab.example.com/api/v4").has_token());
+    }
+
+    #[test]
+    fn test_etag_cache_evicts_oldest() {
+        let client = ApiClient::new("http://localhost", "localhost", "authorization", None, |t| {
+            t.to_string()
+        })
+        .with_etag_cache(2);
+        let etags = client.etags.as_ref().unwrap();
+        let mut cache = etags.lock().unwrap();
+        cache.insert("a".into(), "1".into(), "A".into());
+        cache.insert("b".into(), "1".into(), "B".into());
+        cache.insert("a".into(), "2".into(), "A2".into());
+        cache.insert("c".into(), "1".into(), "C".into());
+        assert_eq!(cache.entries.len(), 2);
+        assert!(!cache.entries.contains_key("b"));
+        assert_eq!(cache.entries["a"].1, "A2");
+    }
+
+    #[test]
+    fn test_encode_segment_escapes_slashes() {
+        assert_eq!(
+            encode_segment("group/sub group/repo"),
+            "group%2Fsub%20group%2Frepo"
+        );
+        assert_eq!(encode_segment("feature/x"), "feature%2Fx");
+    }
+}
@@ -0,0 +1,163 @@
+//! Local HTTP server standing in for a forge API in tests.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use axum::body::{to_bytes, Body};
+use axum::http::{Request, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::Router;
+
+/// A request received by [`MockServer`].
+#[derive(Debug, Clone)]
+pub(crate) struct RecordedRequest {
+    pub method: String,
+    pub path: String,
+    pub query: String,
+    pub headers: HashMap<String, String>,
+    pub body: String,
+}
+
+impl RecordedRequest {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name).map(String::as_str)
+    }
+}
+
+/// A canned response with optional headers.
+///
+/// When `etag` is set the response carries it, and a request whose
+/// `If-None-Match` matches is answered with an empty 304 instead.
+#[derive(Debug, Clone)]
+pub(crate) struct MockResponse {
+    pub status: u16,
+    pub headers: Vec<(&'static str, String)>,
+    pub etag: Option<String>,
+    pub body: String,
+}
+
+impl MockResponse {
+    pub fn json(body: impl Into<String>) -> Self {
+        Self {
+            status: 200,
+            headers: Vec::new(),
+            etag: None,
+            body: body.into(),
+        }
+    }
+
+    pub fn status(status: u16) -> Self {
+        Self {
+            status,
+            ..Self::json("")
+        }
+    }
+
+    pub fn with_header(mut self, name: &'static str, value: impl Into<String>) -> Self {
+        self.headers.push((name, value.into()));
+        self
+    }
+
+    pub fn with_etag(mut self, etag: impl Into<String>) -> Self {
+        self.etag = Some(etag.into());
+        self
+    }
+
+    fn into_response(self, if_none_match: Option<&str>) -> Response {
+        if let (Some(etag), Some(seen)) = (&self.etag, if_none_match) {
+            if etag == seen {
+                return StatusCode::NOT_MODIFIED.into_response();
+            }
+        }
+
+        let mut builder = Response::builder()
+            .status(self.status)
+            .header("content-type", "application/json");
+        for (name, value) in &self.headers {
+            builder = builder.header(*name, value);
+        }
+        if let Some(etag) = &self.etag {
+            builder = builder.header("etag", etag);
+        }
+        builder.body(Body::from(self.body)).unwrap()
+    }
+}
+
+/// Shorthand used by most tests: a JSON body returned with 200, or `"!NNN"`
+/// to answer with that status code.
+impl From<String> for MockResponse {
+    fn from(body: String) -> Self {
+        match body.strip_prefix('!').and_then(|code| code.parse().ok()) {
+            Some(code) => Self::status(code),
+            None => Self::json(body),
+        }
+    }
+}
+
+/// Serves canned responses keyed by `"METHOD /raw/path"` on an ephemeral port.
+///
+/// Unknown routes return a GitLab/Gitea-style 404. Every request is recorded
+/// in order for assertions.
+pub(crate) struct MockServer {
+    pub url: String,
+    requests: Arc<Mutex<Vec<RecordedRequest>>>,
+}
+
+impl MockServer {
+    pub async fn start<R: Into<MockResponse>>(routes: Vec<(&str, R)>) -> Self {
+        let routes: Arc<HashMap<String, MockResponse>> = Arc::new(
+            routes
+                .into_iter()
+                .map(|(key, response)| (key.to_string(), response.into()))
+                .collect(),
+        );
+        let requests = Arc::new(Mutex::new(Vec::new()));
+
+        let recorded = Arc::clone(&requests);
+        let app = Router::new().fallback(move |req: Request<Body>| {
+            let routes = Arc::clone(&routes);
+            let recorded = Arc::clone(&recorded);
+            async move {
+                let (parts, body) = req.into_parts();
+                let body = to_bytes(body, usize::MAX).await.unwrap_or_default();
+                let request = RecordedRequest {
+                    method: parts.method.to_string(),
+                    path: parts.uri.path().to_string(),
+                    query: parts.uri.query().unwrap_or_default().to_string(),
+                    headers: parts
+                        .headers
+                        .iter()
+                        .map(|(k, v)| (k.to_string(), v.to_str().unwrap_or_default().to_string()))
+                        .collect(),
+                    body: String::from_utf8_lossy(&body).into_owned(),
+                };
+                let key = format!("{} {}", request.method, request.path);
+                let if_none_match = request.header("if-none-match").map(str::to_string);
+                recorded.lock().unwrap().push(request);
+
+                match routes.get(&key) {
+                    Some(response) => response.clone().into_response(if_none_match.as_deref()),
+                    None => (
+                        StatusCode::NOT_FOUND,
+                        [("content-type", "application/json")],
+                        r#"{"message":"404 Not Found"}"#,
+                    )
+                        .into_response(),
+                }
+            }
+        });
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let _ = axum::serve(listener, app).await;
+        });
+
+        Self { url, requests }
+    }
+
+    /// Requests received so far, oldest first.
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+}
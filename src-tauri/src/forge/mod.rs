@@ -0,0 +1,271 @@
+//! Forge abstraction over GitHub, GitLab and Gitea.
+//!
+//! [`Forge`] covers the operations the GitHub panel needs — pull/merge
+//! requests, issues, comments, merging and auth status — and maps every forge
+//! onto the GitHub types in [`crate::github`] so the frontend renders them
+//! unchanged. GitHub keeps going through the `gh` CLI; GitLab and Gitea talk
+//! to their REST APIs with a per-host token from the platform credential
+//! store. [`detect`] picks the implementation from the repository's remotes.
+
+pub mod detect;
+pub mod error;
+pub mod gitea;
+pub mod gitlab;
+pub(crate) mod http;
+#[cfg(test)]
+pub(crate) mod mock;
+pub mod token;
+
+use std::future::Future;
+
+use serde::{Deserialize, Serialize};
+
+use crate::github::{
+    AuthStatus, CreatePullRequestOptions, GitHub, IssueDetail, IssueFilter, IssueInfo, MergeMethod,
+    PullRequestDetail, PullRequestFilter, PullRequestInfo,
+};
+
+pub use detect::{detect_forge, open_forge, ForgeRemote};
+pub use error::ForgeError;
+pub use gitea::Gitea;
+pub use gitlab::GitLab;
+
+/// The hosting service behind a repository remote.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ForgeKind {
+    GitHub,
+    GitLab,
+    Gitea,
+}
+
+impl ForgeKind {
+    /// Human-readable name used in error messages.
+    pub fn display_name(self) -> &'static str {
+        match self {
+            ForgeKind::GitHub => "GitHub",
+            ForgeKind::GitLab => "GitLab",
+            ForgeKind::Gitea => "Gitea",
+        }
+    }
+}
+
+/// Operations shared by every supported forge.
+///
+/// GitLab merge requests are exposed as pull requests numbered by their
+/// project-scoped `iid`, and states are normalized to gh's `OPEN`, `CLOSED`
+/// and `MERGED` so callers never branch on the forge.
+pub trait Forge: Send + Sync {
+    /// Checks whether the user is authenticated with the forge.
+    fn auth_status(&self) -> impl Future<Output = Result<AuthStatus, ForgeError>> + Send;
+
+    /// Lists pull requests with optional filtering.
+    fn list_pull_requests(
+        &self,
+        filter: PullRequestFilter,
+    ) -> impl Future<Output = Result<Vec<PullRequestInfo>, ForgeError>> + Send;
+
+    /// Gets a pull request with its body and comments.
+    fn get_pull_request(
+        &self,
+        number: u64,
+    ) -> impl Future<Output = Result<PullRequestDetail, ForgeError>> + Send;
+
+    /// Opens a new pull request.
+    fn create_pull_request(
+        &self,
+        options: CreatePullRequestOptions,
+    ) -> impl Future<Output = Result<PullRequestInfo, ForgeError>> + Send;
+
+    /// Merges a pull request, optionally deleting its source branch.
+    fn merge_pull_request(
+        &self,
+        number: u64,
+        method: MergeMethod,
+        delete_branch: bool,
+    ) -> impl Future<Output = Result<(), ForgeError>> + Send;
+
+    /// Closes a pull request without merging.
+    fn close_pull_request(
+        &self,
+        number: u64,
+    ) -> impl Future<Output = Result<(), ForgeError>> + Send;
+
+    /// Adds a comment to a pull request.
+    fn comment_pull_request(
+        &self,
+        number: u64,
+        body: &str,
+    ) -> impl Future<Output = Result<(), ForgeError>> + Send;
+
+    /// Lists issues with optional filtering.
+    fn list_issues(
+        &self,
+        filter: IssueFilter,
+    ) -> impl Future<Output = Result<Vec<IssueInfo>, ForgeError>> + Send;
+
+    /// Gets an issue with its body and comments.
+    fn get_issue(
+        &self,
+        number: u64,
+    ) -> impl Future<Output = Result<IssueDetail, ForgeError>> + Send;
+
+    /// Adds a comment to an issue.
+    fn comment_issue(
+        &self,
+        number: u64,
+        body: &str,
+    ) -> impl Future<Output = Result<(), ForgeError>> + Send;
+
+    /// Closes an issue.
+    fn close_issue(&self, number: u64) -> impl Future<Output = Result<(), ForgeError>> + Send;
+
+    /// Reopens a closed issue.
+    fn reopen_issue(&self, number: u64) -> impl Future<Output = Result<(), ForgeError>> + Send;
+}
+
+/// The `gh`-backed GitHub implementation; every method delegates to the
+/// inherent [`GitHub`] operation of the same name.
+impl Forge for GitHub {
+    async fn auth_status(&self) -> Result<AuthStatus, ForgeError> {
+        Ok(GitHub::auth_status(self).await?)
+    }
+
+    async fn list_pull_requests(
+        &self,
+        filter: PullRequestFilter,
+    ) -> Result<Vec<PullRequestInfo>, ForgeError> {
+        Ok(GitHub::list_pull_requests(self, filter).await?)
+    }
+
+    async fn get_pull_request(&self, number: u64) -> Result<PullRequestDetail, ForgeError> {
+        Ok(GitHub::get_pull_request(self, number).await?)
+    }
+
+    async fn create_pull_request(
+        &self,
+        options: CreatePullRequestOptions,
+    ) -> Result<PullRequestInfo, ForgeError> {
+        Ok(GitHub::create_pull_request(self, options).await?)
+    }
+
+    async fn merge_pull_request(
+        &self,
+        number: u64,
+        method: MergeMethod,
+        delete_branch: bool,
+    ) -> Result<(), ForgeError> {
+        Ok(GitHub::merge_pull_request(self, number, method, delete_branch).await?)
+    }
+
+    async fn close_pull_request(&self, number: u64) -> Result<(), ForgeError> {
+        Ok(GitHub::close_pull_request(self, number).await?)
+    }
+
+    async fn comment_pull_request(&self, number: u64, body: &str) -> Result<(), ForgeError> {
+        Ok(GitHub::comment_pull_request(self, number, body).await?)
+    }
+
+    async fn list_issues(&self, filter: IssueFilter) -> Result<Vec<IssueInfo>, ForgeError> {
+        Ok(GitHub::list_issues(self, filter).await?)
+    }
+
+    async fn get_issue(&self, number: u64) -> Result<IssueDetail, ForgeError> {
+        Ok(GitHub::get_issue(self, number).await?)
+    }
+
+    async fn comment_issue(&self, number: u64, body: &str) -> Result<(), ForgeError> {
+        Ok(GitHub::comment_issue(self, number, body).await?)
+    }
+
+    async fn close_issue(&self, number: u64) -> Result<(), ForgeError> {
+        Ok(GitHub::close_issue(self, number).await?)
+    }
+
+    async fn reopen_issue(&self, number: u64) -> Result<(), ForgeError> {
+        Ok(GitHub::reopen_issue(self, number).await?)
+    }
+}
+
+/// A forge chosen at runtime by [`open_forge`].
+///
+/// `Forge` returns `impl Future` and is therefore not object safe; this enum
+/// gives commands a single concrete type to hold instead of `Box<dyn Forge>`.
+#[derive(Debug, Clone)]
+pub enum AnyForge {
+    GitHub(GitHub),
+    GitLab(GitLab),
+    Gitea(Gitea),
+}
+
+/// Forwards a method call to whichever forge the enum holds.
+macro_rules! dispatch {
+    ($self:ident, $forge:ident => $call:expr) => {
+        match $self {
+            AnyForge::GitHub($forge) => $call,
+            AnyForge::GitLab($forge) => $call,
+            AnyForge::Gitea($forge) => $call,
+        }
+    };
+}
+
+impl Forge for AnyForge {
+    async fn auth_status(&self) -> Result<AuthStatus, ForgeError> {
+        dispatch!(self, f => Forge::auth_status(f).await)
+    }
+
+    async fn list_pull_requests(
+        &self,
+        filter: PullRequestFilter,
+    ) -> Result<Vec<PullRequestInfo>, ForgeError> {
+        dispatch!(self, f => Forge::list_pull_requests(f, filter).await)
+    }
+
+    async fn get_pull_request(&self, number: u64) -> Result<PullRequestDetail, ForgeError> {
+        dispatch!(self, f => Forge::get_pull_request(f, number).await)
+    }
+
+    async fn create_pull_request(
+        &self,
+        options: CreatePullRequestOptions,
+    ) -> Result<PullRequestInfo, ForgeError> {
+        dispatch!(self, f => Forge::create_pull_request(f, options).await)
+    }
+
+    async fn merge_pull_request(
+        &self,
+        number: u64,
+        method: MergeMethod,
+        delete_branch: bool,
+    ) -> Result<(), ForgeError> {
+        dispatch!(self, f => Forge::merge_pull_request(f, number, method, delete_branch).await)
+    }
+
+    async fn close_pull_request(&self, number: u64) -> Result<(), ForgeError> {
+        dispatch!(self, f => Forge::close_pull_request(f, number).await)
+    }
+
+    async fn comment_pull_request(&self, number: u64, body: &str) -> Result<(), ForgeError> {
+        dispatch!(self, f => Forge::comment_pull_request(f, number, body).await)
+    }
+
+    async fn list_issues(&self, filter: IssueFilter) -> Result<Vec<IssueInfo>, ForgeError> {
+        dispatch!(self, f => Forge::list_issues(f, filter).await)
+    }
+
+    async fn get_issue(&self, number: u64) -> Result<IssueDetail, ForgeError> {
+        dispatch!(self, f => Forge::get_issue(f, number).await)
+    }
+
+    async fn comment_issue(&self, number: u64, body: &str) -> Result<(), ForgeError> {
+        dispatch!(self, f => Forge::comment_issue(f, number, body).await)
+    }
+
+    async fn close_issue(&self, number: u64) -> Result<(), ForgeError> {
+        dispatch!(self, f => Forge::close_issue(f, number).await)
+    }
+
+    async fn reopen_issue(&self, number: u64) -> Result<(), ForgeError> {
+        dispatch!(self, f => Forge::reopen_issue(f, number).await)
+    }
+}
@@ -0,0 +1,58 @@
+//! Per-host forge API tokens kept in the platform credential store
+//! (Keychain, Credential Manager or Secret Service).
+
+use super::error::ForgeError;
+
+/// Keyring service name under which tokens are stored, keyed by host.
+const KEYRING_SERVICE: &str = "maestro-forge";
+
+fn entry(host: &str) -> Result<keyring::Entry, ForgeError> {
+    keyring::Entry::new(KEYRING_SERVICE, &host.to_lowercase())
+        .map_err(|e| ForgeError::Keyring(e.to_string()))
+}
+
+/// Runs a blocking credential-store call off the async runtime.
+async fn blocking<T: Send + 'static>(
+    f: impl FnOnce() -> Result<T, ForgeError> + Send + 'static,
+) -> Result<T, ForgeError> {
+    tokio::task::spawn_blocking(f)
+        .await
+        .map_err(|e| ForgeError::Keyring(format!("task join error: {}", e)))?
+}
+
+/// Returns the stored token for `host`, or `None` if there is none.
+pub async fn get_token(host: &str) -> Result<Option<String>, ForgeError> {
+    let host = host.to_string();
+    blocking(move || match entry(&host)?.get_password() {
+        Ok(token) => Ok(Some(token)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(ForgeError::Keyring(e.to_string())),
+    })
+    .await
+}
+
+/// Stores (or replaces) the token for `host`.
+pub async fn set_token(host: &str, token: &str) -> Result<(), ForgeError> {
+    let key = host.to_string();
+    let token = token.trim().to_string();
+    blocking(move || {
+        entry(&key)?
+            .set_password(&token)
+            .map_err(|e| ForgeError::Keyring(e.to_string()))
+    })
+    .await?;
+    super::detect::forget_token(host);
+    Ok(())
+}
+
+/// Removes the token for `host`. Succeeds if none was stored.
+pub async fn delete_token(host: &str) -> Result<(), ForgeError> {
+    let key = host.to_string();
+    blocking(move || match entry(&key)?.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(ForgeError::Keyring(e.to_string())),
+    })
+    .await?;
+    super::detect::forget_token(host);
+    Ok(())
+}
@@ -0,0 +1,979 @@
+//! Structured diffs of the working tree and index.
+//!
+//! [`Git::status_entries`] reports per-file index and working-tree state, and
+//! [`Git::diff`] parses `git diff` output into files, hunks and numbered
+//! lines, with optional word-level highlights for modified line pairs.
+//! Untracked files are included as additions so a session's new files show
+//! up next to its edits; very large ones are listed without their contents.
+//! Output is decoded lossily, so files in other encodings don't fail the
+//! whole diff.
+
+use serde::{Deserialize, Serialize};
+
+use super::error::GitError;
+use super::ops::FileChangeStatus;
+use super::runner::Git;
+
+/// Bytes inspected when deciding whether an untracked file is binary
+/// (same heuristic as git: a NUL byte in the first 8000 bytes).
+const BINARY_SNIFF_LEN: usize = 8000;
+
+/// Untracked files larger than this are listed without their contents.
+const MAX_UNTRACKED_DIFF_BYTES: u64 = 1024 * 1024;
+
+/// Word highlighting is skipped for line pairs whose token grid exceeds this
+/// size, to keep the LCS cheap on very long (e.g. minified) lines.
+const MAX_WORD_DIFF_CELLS: usize = 250_000;
+
+/// Per-file state from `git status`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StatusEntry {
+    pub path: String,
+    /// Source path for renames and copies staged in the index.
+    pub old_path: Option<String>,
+    /// Change staged in the index relative to HEAD.
+    pub staged: Option<FileChangeStatus>,
+    /// Change in the working tree relative to the index.
+    pub unstaged: Option<FileChangeStatus>,
+    pub untracked: bool,
+    /// Unmerged path (merge or rebase conflict).
+    pub conflicted: bool,
+}
+
+/// What to compare the working tree or index against.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum DiffTarget {
+    /// Working tree against the index (changes not yet staged).
+    Unstaged,
+    /// Index against HEAD (changes staged for the next commit).
+    Staged,
+    /// Working tree against HEAD (staged and unstaged changes).
+    Head,
+    /// Working tree against the merge base of HEAD and `branch` — everything
+    /// the current branch changed since it forked.
+    Base { branch: String },
+    /// One commit or tree against another (e.g. two snapshots).
+    Commits { from: String, to: String },
+}
+
+/// Options for [`Git::diff`].
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct DiffOptions {
+    /// Lines of context around each change.
+    pub context_lines: u32,
+    /// Compute word-level highlights for modified line pairs.
+    pub word_diff: bool,
+    /// Include untracked files as additions (ignored for `Staged` and
+    /// `Commits`).
+    pub include_untracked: bool,
+    /// Restrict the diff to these paths (pathspecs relative to the repo root).
+    pub paths: Vec<String>,
+}
+
+impl Default for DiffOptions {
+    fn default() -> Self {
+        Self {
+            context_lines: 3,
+            word_diff: true,
+            include_untracked: true,
+            paths: Vec::new(),
+        }
+    }
+}
+
+/// Kind of a line within a hunk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiffLineKind {
+    Context,
+    Added,
+    Removed,
+}
+
+/// A changed span within a line, as character (not byte) offsets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct TextRange {
+    pub start: usize,
+    pub end: usize,
+}
+
+/// A single line of a hunk with its line numbers on each side.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DiffLine {
+    pub kind: DiffLineKind,
+    pub old_lineno: Option<u32>,
+    pub new_lineno: Option<u32>,
+    pub content: String,
+    /// Spans that differ from the paired removed/added line. Empty when the
+    /// line has no counterpart or word diffing is disabled.
+    pub highlights: Vec<TextRange>,
+}
+
+/// A contiguous block of changes (`@@ -a,b +c,d @@`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DiffHunk {
+    pub old_start: u32,
+    pub old_lines: u32,
+    pub new_start: u32,
+    pub new_lines: u32,
+    /// Section heading git prints after the range (usually the enclosing function).
+    pub header: String,
+    pub lines: Vec<DiffLine>,
+}
+
+/// Changes to one file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FileDiff {
+    pub path: String,
+    /// Previous path for renames and copies.
+    pub old_path: Option<String>,
+    pub status: FileChangeStatus,
+    pub binary: bool,
+    pub untracked: bool,
+    /// Untracked file over [`MAX_UNTRACKED_DIFF_BYTES`], listed without hunks.
+    pub too_large: bool,
+    pub additions: u32,
+    pub deletions: u32,
+    pub hunks: Vec<DiffHunk>,
+}
+
+impl Git {
+    /// Lists changed, untracked and conflicted files with separate index and
+    /// working-tree states (`git status --porcelain -z`).
+    pub async fn status_entries(&self) -> Result<Vec<StatusEntry>, GitError> {
+        let output = self
+            .run_lossy(&["status", "--porcelain=v1", "-z", "--untracked-files=all"])
+            .await?;
+        Ok(parse_status(&output.stdout))
+    }
+
+    /// Returns the merge base of two commits.
+    pub async fn merge_base(&self, a: &str, b: &str) -> Result<String, GitError> {
+        let output = self.run(&["merge-base", a, b]).await?;
+        Ok(output.trimmed().to_string())
+    }
+
+    /// Picks the branch a session's work should be compared against: the
+    /// `origin` default branch, then `init.defaultBranch`, then `main` or
+    /// `master`. Prefers the local branch and falls back to its
+    /// remote-tracking ref. Returns `None` if none of them exist.
+    pub async fn default_base_branch(&self) -> Result<Option<String>, GitError> {
+        let mut candidates = Vec::new();
+        if let Some(name) = self.remote_default_branch("origin").await? {
+            candidates.push(name);
+        }
+        if let Ok(Some(name)) = self.get_default_branch().await {
+            candidates.push(name);
+        }
+        candidates.extend(["main".to_string(), "master".to_string()]);
+
+        for name in candidates {
+            for candidate in [
+                format!("refs/heads/{name}"),
+                format!("refs/remotes/origin/{name}"),
+            ] {
+                match self
+                    .run(&["rev-parse", "--verify", "--quiet", &candidate])
+                    .await
+                {
+                    Ok(_) => {
+                        let short = candidate
+                            .strip_prefix("refs/heads/")
+                            .or_else(|| candidate.strip_prefix("refs/remotes/"))
+                            .unwrap_or(&candidate);
+                        return Ok(Some(short.to_string()));
+                    }
+                    Err(GitError::CommandFailed { .. }) => {}
+                    Err(e) => return Err(e),
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    /// Diffs the working tree or index against `target`, or two commits.
+    pub async fn diff(
+        &self,
+        target: &DiffTarget,
+        options: &DiffOptions,
+    ) -> Result<Vec<FileDiff>, GitError> {
+        let context = format!("-U{}", options.context_lines);
+        let mut args = vec![
+            "-c",
+            "core.quotePath=false",
+            "diff",
+            "--no-color",
+            "--no-ext-diff",
+            "-M",
+            &context,
+            "--src-prefix=a/",
+            "--dst-prefix=b/",
+        ];
+
+        let base;
+        match target {
+            DiffTarget::Unstaged => {}
+            DiffTarget::Staged => args.push("--cached"),
+            DiffTarget::Head => args.push("HEAD"),
+            DiffTarget::Base { branch } => {
+                base = self.merge_base("HEAD", branch).await?;
+                args.push(&base);
+            }
+            DiffTarget::Commits { from, to } => {
+                args.push(from);
+                args.push(to);
+            }
+        }
+        if !options.paths.is_empty() {
+            args.push("--");
+            args.extend(options.paths.iter().map(String::as_str));
+        }
+
+        let output = self.run_lossy(&args).await?;
+        let mut files = parse_diff(&output.stdout);
+
+        let compares_working_tree =
+            !matches!(target, DiffTarget::Staged | DiffTarget::Commits { .. });
+        if options.include_untracked && compares_working_tree {
+            for entry in self.status_entries().await? {
+                if !entry.untracked || !matches_paths(&entry.path, &options.paths) {
+                    continue;
+                }
+                let full_path = self.repo_path().join(&entry.path);
+                let size = tokio::fs::metadata(&full_path).await.map(|m| m.len());
+                if size.as_ref().is_ok_and(|&len| len > MAX_UNTRACKED_DIFF_BYTES) {
+                    let mut file = untracked_file_diff(entry.path, &[]);
+                    file.too_large = true;
+                    files.push(file);
+                    continue;
+                }
+                match tokio::fs::read(&full_path).await {
+                    Ok(bytes) => files.push(untracked_file_diff(entry.path, &bytes)),
+                    Err(e) => log::warn!(
+                        "diff: could not read untracked {}: {}",
+                        full_path.display(),
+                        e
+                    ),
+                }
+            }
+        }
+
+        if options.word_diff {
+            for file in &mut files {
+                for hunk in &mut file.hunks {
+                    highlight_hunk(hunk);
+                }
+            }
+        }
+        Ok(files)
+    }
+}
+
+/// Whether `path` falls under one of the pathspecs (all paths if empty).
+fn matches_paths(path: &str, paths: &[String]) -> bool {
+    paths.is_empty()
+        || paths.iter().any(|p| {
+            let p = p.trim_end_matches('/');
+            path == p || path.starts_with(&format!("{p}/"))
+        })
+}
+
+fn status_code(code: char) -> Option<FileChangeStatus> {
+    match code {
+        ' ' | '?' | '!' => None,
+        'M' | 'T' => Some(FileChangeStatus::Modified),
+        'A' => Some(FileChangeStatus::Added),
+        'D' => Some(FileChangeStatus::Deleted),
+        'R' => Some(FileChangeStatus::Renamed),
+        'C' => Some(FileChangeStatus::Copied),
+        _ => Some(FileChangeStatus::Unknown),
+    }
+}
+
+/// Parses `git status --porcelain=v1 -z` output. Renamed and copied entries
+/// are followed by their source path as a separate NUL-terminated field.
+fn parse_status(output: &str) -> Vec<StatusEntry> {
+    let mut entries = Vec::new();
+    let mut fields = output.split('\0').filter(|f| !f.is_empty());
+
+    while let Some(field) = fields.next() {
+        if field.len() < 4 {
+            continue;
+        }
+        let mut codes = field.chars();
+        let x = codes.next().unwrap_or(' ');
+        let y = codes.next().unwrap_or(' ');
+        let path = field[3..].to_string();
+
+        let old_path = if matches!(x, 'R' | 'C') || matches!(y, 'R' | 'C') {
+            fields.next().map(str::to_string)
+        } else {
+            None
+        };
+        let untracked = x == '?' && y == '?';
+        let conflicted = x == 'U' || y == 'U' || (x == 'A' && y == 'A') || (x == 'D' && y == 'D');
+
+        entries.push(StatusEntry {
+            path,
+            old_path,
+            staged: if untracked || conflicted {
+                None
+            } else {
+                status_code(x)
+            },
+            unstaged: if untracked || conflicted {
+                None
+            } else {
+                status_code(y)
+            },
+            untracked,
+            conflicted,
+        });
+    }
+    entries
+}
+
+/// Reverses git's C-style quoting of paths containing special characters.
+fn unquote_path(raw: &str) -> String {
+    let Some(inner) = raw.strip_prefix('"').and_then(|s| s.strip_suffix('"')) else {
+        return raw.to_string();
+    };
+
+    let mut bytes = Vec::with_capacity(inner.len());
+    let mut chars = inner.bytes().peekable();
+    while let Some(b) = chars.next() {
+        if b != b'\\' {
+            bytes.push(b);
+            continue;
+        }
+        match chars.next() {
+            Some(b'n') => bytes.push(b'\n'),
+            Some(b't') => bytes.push(b'\t'),
+            Some(b'r') => bytes.push(b'\r'),
+            Some(b'a') => bytes.push(0x07),
+            Some(b'b') => bytes.push(0x08),
+            Some(b'f') => bytes.push(0x0c),
+            Some(b'v') => bytes.push(0x0b),
+            Some(d @ b'0'..=b'7') => {
+                let mut value = u32::from(d - b'0');
+                for _ in 0..2 {
+                    match chars.peek() {
+                        Some(&n @ b'0'..=b'7') => {
+                            value = value * 8 + u32::from(n - b'0');
+                            chars.next();
+                        }
+                        _ => break,
+                    }
+                }
+                bytes.push(value as u8);
+            }
+            Some(other) => bytes.push(other),
+            None => bytes.push(b'\\'),
+        }
+    }
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+/// Strips the `a/` / `b/` prefix from a `---` / `+++` path. Returns `None`
+/// for `/dev/null`.
+fn header_path(raw: &str, prefix: &str) -> Option<String> {
+    // Timestamps may follow a tab on some diff drivers
+    let raw = raw.split('\t').next().unwrap_or(raw);
+    if raw == "/dev/null" {
+        return None;
+    }
+    let path = unquote_path(raw);
+    Some(
+        path.strip_prefix(prefix)
+            .map(str::to_string)
+            .unwrap_or(path),
+    )
+}
+
+/// Extracts the path from `diff --git a/X b/X` when both sides are equal,
+/// which is the case for everything except renames and copies.
+fn git_header_path(rest: &str) -> Option<String> {
+    let n = rest.len().checked_sub(5)? / 2;
+    if rest.len() != 2 * n + 5 || !rest.starts_with("a/") || !rest.is_char_boundary(n + 2) {
+        return None;
+    }
+    let (old, new) = (&rest[2..n + 2], &rest[n + 3..]);
+    (new.strip_prefix("b/") == Some(old)).then(|| old.to_string())
+}
+
+/// Parses `@@ -a,b +c,d @@ header`.
+fn parse_hunk_header(line: &str) -> Option<DiffHunk> {
+    let rest = line.strip_prefix("@@ -")?;
+    let (ranges, header) = rest.split_once(" @@")?;
+    let (old, new) = ranges.split_once(" +")?;
+    let range = |r: &str| -> Option<(u32, u32)> {
+        match r.split_once(',') {
+            Some((start, len)) => Some((start.parse().ok()?, len.parse().ok()?)),
+            None => Some((r.parse().ok()?, 1)),
+        }
+    };
+    let (old_start, old_lines) = range(old)?;
+    let (new_start, new_lines) = range(new)?;
+    Some(DiffHunk {
+        old_start,
+        old_lines,
+        new_start,
+        new_lines,
+        header: header.trim().to_string(),
+        lines: Vec::new(),
+    })
+}
+
+/// Parses unified `git diff` output into per-file diffs.
+pub(crate) fn parse_diff(output: &str) -> Vec<FileDiff> {
+    let mut files = Vec::new();
+    let mut current: Option<FileDiff> = None;
+    // Next line numbers on each side while inside a hunk
+    let mut cursor: Option<(u32, u32)> = None;
+
+    for line in output.lines() {
+        if let Some(rest) = line.strip_prefix("diff --git ") {
+            files.extend(current.take());
+            cursor = None;
+            current = Some(FileDiff {
+                path: git_header_path(rest).unwrap_or_default(),
+                old_path: None,
+                status: FileChangeStatus::Modified,
+                binary: false,
+                untracked: false,
+                too_large: false,
+                additions: 0,
+                deletions: 0,
+                hunks: Vec::new(),
+            });
+            continue;
+        }
+        let Some(file) = current.as_mut() else {
+            continue;
+        };
+
+        if line.starts_with("@@ ") {
+            if let Some(hunk) = parse_hunk_header(line) {
+                cursor = Some((hunk.old_start, hunk.new_start));
+                file.hunks.push(hunk);
+            }
+            continue;
+        }
+
+        if let (Some((old_no, new_no)), Some(hunk)) = (cursor.as_mut(), file.hunks.last_mut()) {
+            let (kind, old_lineno, new_lineno) = match line.chars().next() {
+                Some('+') => {
+                    *new_no += 1;
+                    file.additions += 1;
+                    (DiffLineKind::Added, None, Some(*new_no - 1))
+                }
+                Some('-') => {
+                    *old_no += 1;
+                    file.deletions += 1;
+                    (DiffLineKind::Removed, Some(*old_no - 1), None)
+                }
+                Some(' ') | None => {
+                    *old_no += 1;
+                    *new_no += 1;
+                    (DiffLineKind::Context, Some(*old_no - 1), Some(*new_no - 1))
+                }
+                // "\ No newline at end of file"
+                _ => continue,
+            };
+            hunk.lines.push(DiffLine {
+                kind,
+                old_lineno,
+                new_lineno,
+                content: line.get(1..).unwrap_or_default().to_string(),
+                highlights: Vec::new(),
+            });
+            continue;
+        }
+
+        // Extended header lines (before the first hunk)
+        if line.starts_with("new file mode") {
+            file.status = FileChangeStatus::Added;
+        } else if line.starts_with("deleted file mode") {
+            file.status = FileChangeStatus::Deleted;
+        } else if let Some(path) = line.strip_prefix("rename from ") {
+            file.status = FileChangeStatus::Renamed;
+            file.old_path = Some(unquote_path(path));
+        } else if let Some(path) = line.strip_prefix("rename to ") {
+            file.path = unquote_path(path);
+        } else if let Some(path) = line.strip_prefix("copy from ") {
+            file.status = FileChangeStatus::Copied;
+            file.old_path = Some(unquote_path(path));
+        } else if let Some(path) = line.strip_prefix("copy to ") {
+            file.path = unquote_path(path);
+        } else if let Some(path) = line.strip_prefix("--- ") {
+            if file.path.is_empty() {
+                if let Some(path) = header_path(path, "a/") {
+                    file.path = path;
+                }
+            }
+        } else if let Some(path) = line.strip_prefix("+++ ") {
+            if let Some(path) = header_path(path, "b/") {
+                file.path = path;
+            }
+        } else if line.starts_with("Binary files ") || line == "GIT binary patch" {
+            file.binary = true;
+        }
+    }
+    files.extend(current);
+    files
+}
+
+/// Builds an all-additions diff for an untracked file.
+fn untracked_file_diff(path: String, bytes: &[u8]) -> FileDiff {
+    let sniff = &bytes[..bytes.len().min(BINARY_SNIFF_LEN)];
+    // Like git, treat anything without a NUL byte as text, whatever its encoding
+    let text = if sniff.contains(&0) {
+        None
+    } else {
+        Some(String::from_utf8_lossy(bytes))
+    };
+
+    let mut file = FileDiff {
+        path,
+        old_path: None,
+        status: FileChangeStatus::Added,
+        binary: text.is_none(),
+        untracked: true,
+        too_large: false,
+        additions: 0,
+        deletions: 0,
+        hunks: Vec::new(),
+    };
+
+    if let Some(text) = text.filter(|t| !t.is_empty()) {
+        let lines: Vec<DiffLine> = text
+            .lines()
+            .enumerate()
+            .map(|(i, line)| DiffLine {
+                kind: DiffLineKind::Added,
+                old_lineno: None,
+                new_lineno: Some(i as u32 + 1),
+                content: line.to_string(),
+                highlights: Vec::new(),
+            })
+            .collect();
+        file.additions = lines.len() as u32;
+        file.hunks.push(DiffHunk {
+            old_start: 0,
+            old_lines: 0,
+            new_start: 1,
+            new_lines: file.additions,
+            header: String::new(),
+            lines,
+        });
+    }
+    file
+}
+
+/// Pairs each run of removed lines with the added lines that directly follow
+/// it (first with first, second with second, ...) and highlights the words
+/// that differ within each pair.
+fn highlight_hunk(hunk: &mut DiffHunk) {
+    let mut i = 0;
+    while i < hunk.lines.len() {
+        if hunk.lines[i].kind != DiffLineKind::Removed {
+            i += 1;
+            continue;
+        }
+        let removed_start = i;
+        while i < hunk.lines.len() && hunk.lines[i].kind == DiffLineKind::Removed {
+            i += 1;
+        }
+        let added_start = i;
+        while i < hunk.lines.len() && hunk.lines[i].kind == DiffLineKind::Added {
+            i += 1;
+        }
+
+        let pairs = (added_start - removed_start).min(i - added_start);
+        for k in 0..pairs {
+            let (old, new) = word_highlights(
+                &hunk.lines[removed_start + k].content,
+                &hunk.lines[added_start + k].content,
+            );
+            hunk.lines[removed_start + k].highlights = old;
+            hunk.lines[added_start + k].highlights = new;
+        }
+    }
+}
+
+/// Splits a line into words, whitespace runs and single punctuation
+/// characters. Returns `(char_start, char_end, text)` tokens.
+fn tokenize(line: &str) -> Vec<(usize, usize, &str)> {
+    #[derive(PartialEq)]
+    enum Class {
+        Word,
+        Space,
+        Other,
+    }
+    let class = |c: char| {
+        if c.is_alphanumeric() || c == '_' {
+            Class::Word
+        } else if c.is_whitespace() {
+            Class::Space
+        } else {
+            Class::Other
+        }
+    };
+
+    let mut tokens = Vec::new();
+    let mut chars = line.char_indices().enumerate().peekable();
+    while let Some((char_start, (byte_start, c))) = chars.next() {
+        let cls = class(c);
+        let mut char_end = char_start + 1;
+        let mut byte_end = byte_start + c.len_utf8();
+        if cls != Class::Other {
+            while let Some(&(_, (b, next))) = chars.peek() {
+                if class(next) != cls {
+                    break;
+                }
+                char_end += 1;
+                byte_end = b + next.len_utf8();
+                chars.next();
+            }
+        }
+        tokens.push((char_start, char_end, &line[byte_start..byte_end]));
+    }
+    tokens
+}
+
+/// Computes changed spans for a modified line pair using an LCS over tokens.
+fn word_highlights(old: &str, new: &str) -> (Vec<TextRange>, Vec<TextRange>) {
+    let a = tokenize(old);
+    let b = tokenize(new);
+    if a.len() * b.len() > MAX_WORD_DIFF_CELLS {
+        return (Vec::new(), Vec::new());
+    }
+
+    // lcs[i][j] = LCS length of a[i..] and b[j..]
+    let mut lcs = vec![vec![0u32; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i][j] = if a[i].2 == b[j].2 {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut keep_a = vec![false; a.len()];
+    let mut keep_b = vec![false; b.len()];
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        if a[i].2 == b[j].2 {
+            keep_a[i] = true;
+            keep_b[j] = true;
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+
+    (ranges(&a, &keep_a), ranges(&b, &keep_b))
+}
+
+/// Merges adjacent unmatched tokens into ranges.
+fn ranges(tokens: &[(usize, usize, &str)], keep: &[bool]) -> Vec<TextRange> {
+    let mut out: Vec<TextRange> = Vec::new();
+    for (token, kept) in tokens.iter().zip(keep) {
+        if *kept {
+            continue;
+        }
+        match out.last_mut() {
+            Some(last) if last.end == token.0 => last.end = token.1,
+            _ => out.push(TextRange {
+                start: token.0,
+                end: token.1,
+            }),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    const SAMPLE: &str = "\
+diff --git a/src/lib.rs b/src/lib.rs
+index 1111111..2222222 100644
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -1,4 +1,4 @@ mod tests
+ fn main() {
+-    let x = 1;
++    let x = 2;
+     println!(\"{x}\");
+ }
+@@ -10,2 +10,3 @@
+ a
++b
+ c
+\\ No newline at end of file
+diff --git a/old name.txt b/new name.txt
+similarity index 90%
+rename from old name.txt
+rename to new name.txt
+diff --git a/logo.png b/logo.png
+new file mode 100644
+index 0000000..3333333
+Binary files /dev/null and b/logo.png differ
+diff --git a/gone.rs b/gone.rs
+deleted file mode 100644
+--- a/gone.rs
++++ /dev/null
+@@ -1 +0,0 @@
+-bye
+";
+
+    #[test]
+    fn test_parse_diff_files_and_statuses() {
+        let files = parse_diff(SAMPLE);
+        assert_eq!(files.len(), 4);
+
+        assert_eq!(files[0].path, "src/lib.rs");
+        assert!(matches!(files[0].status, FileChangeStatus::Modified));
+        assert_eq!((files[0].additions, files[0].deletions), (2, 1));
+
+        assert_eq!(files[1].path, "new name.txt");
+        assert_eq!(files[1].old_path.as_deref(), Some("old name.txt"));
+        assert!(matches!(files[1].status, FileChangeStatus::Renamed));
+
+        assert_eq!(files[2].path, "logo.png");
+        assert!(files[2].binary);
+        assert!(matches!(files[2].status, FileChangeStatus::Added));
+
+        assert_eq!(files[3].path, "gone.rs");
+        assert!(matches!(files[3].status, FileChangeStatus::Deleted));
+    }
+
+    #[test]
+    fn test_parse_diff_line_numbers() {
+        let files = parse_diff(SAMPLE);
+        let hunk = &files[0].hunks[0];
+        assert_eq!(
+            (
+                hunk.old_start,
+                hunk.old_lines,
+                hunk.new_start,
+                hunk.new_lines
+            ),
+            (1, 4, 1, 4)
+        );
+        assert_eq!(hunk.header, "mod tests");
+
+        let removed = &hunk.lines[1];
+        assert_eq!(removed.kind, DiffLineKind::Removed);
+        assert_eq!((removed.old_lineno, removed.new_lineno), (Some(2), None));
+        let added = &hunk.lines[2];
+        assert_eq!(added.kind, DiffLineKind::Added);
+        assert_eq!((added.old_lineno, added.new_lineno), (None, Some(2)));
+        let context = &hunk.lines[3];
+        assert_eq!((context.old_lineno, context.new_lineno), (Some(3), Some(3)));
+
+        // "\ No newline" marker is not a line
+        let second = &files[0].hunks[1];
+        assert_eq!(second.lines.len(), 3);
+        assert_eq!(second.lines[1].new_lineno, Some(11));
+    }
+
+    #[test]
+    fn test_word_highlights() {
+        let (old, new) = word_highlights("    let x = 1;", "    let x = 2;");
+        assert_eq!(old, vec![TextRange { start: 12, end: 13 }]);
+        assert_eq!(new, vec![TextRange { start: 12, end: 13 }]);
+
+        let (old, new) = word_highlights("foo(bar)", "foo(bar, baz)");
+        assert!(old.is_empty());
+        assert_eq!(new, vec![TextRange { start: 7, end: 12 }]);
+    }
+
+    #[test]
+    fn test_word_highlights_use_char_offsets() {
+        let (_, new) = word_highlights("naïve café", "naïve caffè");
+        assert_eq!(new, vec![TextRange { start: 6, end: 11 }]);
+    }
+
+    #[test]
+    fn test_parse_status_entries() {
+        let output = "M  staged.rs\0 M unstaged.rs\0R  new.rs\0old.rs\0?? new dir/file.txt\0UU conflict.rs\0";
+        let entries = parse_status(output);
+        assert_eq!(entries.len(), 5);
+
+        assert!(matches!(
+            entries[0].staged,
+            Some(FileChangeStatus::Modified)
+        ));
+        assert!(entries[0].unstaged.is_none());
+        assert!(entries[1].staged.is_none());
+        assert!(matches!(
+            entries[1].unstaged,
+            Some(FileChangeStatus::Modified)
+        ));
+        assert_eq!(entries[2].path, "new.rs");
+        assert_eq!(entries[2].old_path.as_deref(), Some("old.rs"));
+        assert!(entries[3].untracked);
+        assert_eq!(entries[3].path, "new dir/file.txt");
+        assert!(entries[4].conflicted);
+    }
+
+    #[test]
+    fn test_unquote_path() {
+        assert_eq!(unquote_path("plain.txt"), "plain.txt");
+        assert_eq!(unquote_path("\"a\\tb.txt\""), "a\tb.txt");
+        assert_eq!(unquote_path("\"caf\\303\\251.txt\""), "café.txt");
+        assert_eq!(unquote_path("\"quote\\\"d\""), "quote\"d");
+    }
+
+    async fn create_test_repo() -> (tempfile::TempDir, Git) {
+        let dir = tempdir().unwrap();
+        let git = Git::new(dir.path());
+        git.run(&["init", "-b", "main"]).await.unwrap();
+        git.run(&["config", "user.email", "test@test.com"])
+            .await
+            .unwrap();
+        git.run(&["config", "user.name", "Test"]).await.unwrap();
+        tokio::fs::write(dir.path().join("a.txt"), "one\ntwo\nthree\n")
+            .await
+            .unwrap();
+        git.run(&["add", "."]).await.unwrap();
+        git.run(&["commit", "-m", "initial"]).await.unwrap();
+        (dir, git)
+    }
+
+    #[tokio::test]
+    async fn test_diff_head_includes_untracked_and_staged() {
+        let (dir, git) = create_test_repo().await;
+        tokio::fs::write(dir.path().join("a.txt"), "one\nTWO\nthree\n")
+            .await
+            .unwrap();
+        tokio::fs::write(dir.path().join("new.txt"), "hello\nworld\n")
+            .await
+            .unwrap();
+        tokio::fs::write(dir.path().join("blob.bin"), [0u8, 1, 2])
+            .await
+            .unwrap();
+
+        let files = git
+            .diff(&DiffTarget::Head, &DiffOptions::default())
+            .await
+            .unwrap();
+        let a = files.iter().find(|f| f.path == "a.txt").unwrap();
+        assert_eq!((a.additions, a.deletions), (1, 1));
+        let added = a.hunks[0]
+            .lines
+            .iter()
+            .find(|l| l.kind == DiffLineKind::Added)
+            .unwrap();
+        assert_eq!(added.new_lineno, Some(2));
+        assert_eq!(added.highlights, vec![TextRange { start: 0, end: 3 }]);
+
+        let new = files.iter().find(|f| f.path == "new.txt").unwrap();
+        assert!(new.untracked);
+        assert_eq!(new.additions, 2);
+        let bin = files.iter().find(|f| f.path == "blob.bin").unwrap();
+        assert!(bin.binary);
+
+        // Staged view only sees what was added to the index
+        git.run(&["add", "a.txt"]).await.unwrap();
+        let staged = git
+            .diff(&DiffTarget::Staged, &DiffOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(staged.len(), 1);
+        assert_eq!(staged[0].path, "a.txt");
+        let unstaged = git
+            .diff(&DiffTarget::Unstaged, &DiffOptions::default())
+            .await
+            .unwrap();
+        assert!(unstaged.iter().all(|f| f.untracked));
+    }
+
+    #[tokio::test]
+    async fn test_diff_survives_large_and_non_utf8_files() {
+        let (dir, git) = create_test_repo().await;
+        // Latin-1 "café" in a tracked file and an untracked one
+        tokio::fs::write(dir.path().join("a.txt"), b"one\ncaf\xe9\nthree\n")
+            .await
+            .unwrap();
+        tokio::fs::write(dir.path().join("latin1.txt"), b"caf\xe9\n")
+            .await
+            .unwrap();
+        let big = "x".repeat(MAX_UNTRACKED_DIFF_BYTES as usize + 1);
+        tokio::fs::write(dir.path().join("big.txt"), big)
+            .await
+            .unwrap();
+
+        let files = git
+            .diff(&DiffTarget::Head, &DiffOptions::default())
+            .await
+            .unwrap();
+        let a = files.iter().find(|f| f.path == "a.txt").unwrap();
+        assert_eq!((a.additions, a.deletions), (1, 1));
+        let latin1 = files.iter().find(|f| f.path == "latin1.txt").unwrap();
+        assert!(!latin1.binary);
+        assert_eq!(latin1.hunks[0].lines[0].content, "caf\u{fffd}");
+        let big = files.iter().find(|f| f.path == "big.txt").unwrap();
+        assert!(big.too_large);
+        assert!(big.hunks.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_diff_against_base_branch_tracks_renames() {
+        let (dir, git) = create_test_repo().await;
+        git.run(&["checkout", "-b", "feature"]).await.unwrap();
+        git.run(&["mv", "a.txt", "b.txt"]).await.unwrap();
+        git.run(&["commit", "-m", "rename"]).await.unwrap();
+        tokio::fs::write(dir.path().join("c.txt"), "c\n")
+            .await
+            .unwrap();
+        assert_eq!(
+            git.default_base_branch().await.unwrap().as_deref(),
+            Some("main")
+        );
+
+        let target = DiffTarget::Base {
+            branch: "main".to_string(),
+        };
+        let files = git.diff(&target, &DiffOptions::default()).await.unwrap();
+        let renamed = files.iter().find(|f| f.path == "b.txt").unwrap();
+        assert!(matches!(renamed.status, FileChangeStatus::Renamed));
+        assert_eq!(renamed.old_path.as_deref(), Some("a.txt"));
+
+        let options = DiffOptions {
+            paths: vec!["c.txt".to_string()],
+            ..Default::default()
+        };
+        let only_c = git.diff(&target, &options).await.unwrap();
+        assert_eq!(only_c.len(), 1);
+        assert_eq!(only_c[0].path, "c.txt");
+    }
+
+    #[tokio::test]
+    async fn test_status_entries_in_repo() {
+        let (dir, git) = create_test_repo().await;
+        tokio::fs::write(dir.path().join("a.txt"), "changed\n")
+            .await
+            .unwrap();
+        tokio::fs::write(dir.path().join("u.txt"), "u\n")
+            .await
+            .unwrap();
+
+        let entries = git.status_entries().await.unwrap();
+        assert_eq!(entries.len(), 2);
+        let a = entries.iter().find(|e| e.path == "a.txt").unwrap();
+        assert!(matches!(a.unstaged, Some(FileChangeStatus::Modified)));
+        assert!(entries.iter().any(|e| e.path == "u.txt" && e.untracked));
+    }
+}
@@ -24,6 +24,10 @@ pub enum GitError {
     #[error("git command was killed by signal")]
     Killed { command: String },
 
+    /// The command's operation was cancelled and the process killed.
+    #[error("git operation was cancelled")]
+    Cancelled { command: String },
+
     /// The git process could not be spawned (e.g., permission denied).
     #[error("failed to spawn git process: {source}")]
     SpawnError {
@@ -50,6 +54,18 @@ pub enum GitError {
     /// The specified worktree path does not exist in git's worktree list.
     #[error("worktree not found: {0}")]
     WorktreeNotFound(String),
+
+    /// A commit was requested but nothing is staged.
+    #[error("nothing to commit")]
+    NothingToCommit,
+
+    /// The remote rejected our credentials (HTTPS token or SSH key).
+    #[error("authentication failed for remote '{remote}': {message}")]
+    AuthenticationFailed { remote: String, message: String },
+
+    /// The remote refused the push (non-fast-forward, protected branch, hook).
+    #[error("push to '{remote}' rejected: {message}")]
+    PushRejected { remote: String, message: String },
 }
 
 /// Serializes the error as its `Display` string so the frontend receives a
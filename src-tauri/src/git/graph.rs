@@ -0,0 +1,469 @@
+//! Commit graph with lane layout, for the history view.
+//!
+//! [`Git::commit_graph`] returns one page of commits in topological order,
+//! each already assigned a column, with edges to its parents and the refs
+//! pointing at it. The lane state at the end of a page is returned as a
+//! [`GraphCursor`] so the next page continues the same layout without
+//! re-reading earlier history.
+//!
+//! Layout rules: a commit takes the lowest lane waiting for it (or the
+//! lowest free lane); its first parent continues in that lane and further
+//! parents of a merge join a lane already waiting for them or open a new
+//! one. Other lanes waiting for the same commit end at it.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::error::GitError;
+use super::ops::CommitInfo;
+use super::runner::Git;
+
+/// Commits per page when the query does not say.
+pub const DEFAULT_GRAPH_PAGE_SIZE: usize = 200;
+
+/// What to include in the graph.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct GraphQuery {
+    /// Revisions to start from (branches, tags, commits). Empty means HEAD
+    /// and every branch, remote-tracking branch and tag.
+    pub branches: Vec<String>,
+    /// Only commits touching these paths; parents are rewritten so the graph
+    /// stays connected.
+    pub paths: Vec<String>,
+    /// Commits per page.
+    pub limit: usize,
+    /// Where to continue from; `None` for the first page.
+    pub cursor: Option<GraphCursor>,
+}
+
+impl Default for GraphQuery {
+    fn default() -> Self {
+        Self {
+            branches: Vec::new(),
+            paths: Vec::new(),
+            limit: DEFAULT_GRAPH_PAGE_SIZE,
+            cursor: None,
+        }
+    }
+}
+
+/// Position and lane state after a page, to be passed back for the next one.
+///
+/// Only valid for the same query while the refs involved do not move.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GraphCursor {
+    /// Commits already returned.
+    pub skip: usize,
+    /// Commit each lane is waiting for; `None` for a free lane.
+    pub lanes: Vec<Option<String>>,
+}
+
+/// Whether an edge leads to a commit's first parent or to a merged parent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EdgeKind {
+    Parent,
+    Merge,
+}
+
+/// An edge from a commit to one of its parents.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct GraphEdge {
+    pub parent: String,
+    /// Lane the edge runs down until it reaches the parent. The parent is
+    /// drawn in this lane unless a lower lane also leads to it.
+    pub column: usize,
+    pub kind: EdgeKind,
+}
+
+/// A commit placed in the graph.
+#[derive(Debug, Clone, Serialize)]
+pub struct GraphRow {
+    pub commit: CommitInfo,
+    /// Row in the whole graph, counting earlier pages.
+    pub row: usize,
+    pub column: usize,
+    pub edges: Vec<GraphEdge>,
+    /// Refs pointing at the commit, formatted like
+    /// [`Git::refs_for_commit`]: `main`, `origin/main`, `tag:v1.0`.
+    pub refs: Vec<String>,
+}
+
+/// One page of the commit graph.
+#[derive(Debug, Clone, Serialize)]
+pub struct GraphPage {
+    pub rows: Vec<GraphRow>,
+    /// Number of lanes needed to draw this page.
+    pub width: usize,
+    /// Cursor for the next page, or `None` at the end of history.
+    pub next: Option<GraphCursor>,
+}
+
+impl Git {
+    /// Returns a page of the commit graph with lanes assigned and ref
+    /// decorations attached.
+    pub async fn commit_graph(&self, query: &GraphQuery) -> Result<GraphPage, GitError> {
+        let limit = query.limit.max(1);
+        let mut cursor = query.cursor.clone().unwrap_or_default();
+
+        let skip = format!("--skip={}", cursor.skip);
+        // One extra commit tells us whether another page exists.
+        let count = format!("-n{}", limit + 1);
+        let mut args = vec![
+            "log",
+            "--topo-order",
+            "--format=%H%x1f%h%x1f%P%x1f%an%x1f%ae%x1f%at%x1f%s",
+            &skip,
+            &count,
+        ];
+        if query.branches.is_empty() {
+            // A detached HEAD is shown too; an unborn one would be an error.
+            let head = self
+                .run_accepting(&["rev-parse", "--verify", "--quiet", "HEAD"], &[1])
+                .await?;
+            if !head.trimmed().is_empty() {
+                args.push("HEAD");
+            }
+            // Not `--all`: that would pull in stashes and snapshot refs.
+            args.extend(["--branches", "--remotes", "--tags"]);
+        } else {
+            args.extend(query.branches.iter().map(String::as_str));
+        }
+        if !query.paths.is_empty() {
+            // Rewrite parents to the nearest ancestor touching the paths.
+            args.push("--parents");
+        }
+        args.push("--");
+        args.extend(query.paths.iter().map(String::as_str));
+        let output = self.run(&args).await?;
+
+        let mut commits: Vec<CommitInfo> = output
+            .lines()
+            .into_iter()
+            .filter_map(parse_commit)
+            .collect();
+        let has_more = commits.len() > limit;
+        commits.truncate(limit);
+
+        let mut refs = if commits.is_empty() {
+            HashMap::new()
+        } else {
+            self.ref_decorations().await?
+        };
+
+        let mut width = cursor.lanes.len();
+        let mut rows = Vec::with_capacity(commits.len());
+        for (i, commit) in commits.into_iter().enumerate() {
+            let placed = place_commit(&mut cursor.lanes, &commit.hash, &commit.parent_hashes);
+            width = width.max(placed.width);
+            rows.push(GraphRow {
+                row: cursor.skip + i,
+                column: placed.column,
+                edges: placed.edges,
+                refs: refs.remove(&commit.hash).unwrap_or_default(),
+                commit,
+            });
+        }
+
+        cursor.skip += rows.len();
+        Ok(GraphPage {
+            rows,
+            width,
+            next: has_more.then_some(cursor),
+        })
+    }
+
+    /// Maps commit hashes to the branches, remote-tracking branches and tags
+    /// pointing at them, in one `for-each-ref` call. Annotated tags are
+    /// resolved to the commit they tag.
+    pub async fn ref_decorations(&self) -> Result<HashMap<String, Vec<String>>, GitError> {
+        let output = self
+            .run(&[
+                "for-each-ref",
+                "--format=%(objectname)%1f%(*objectname)%1f%(refname)",
+                "refs/heads",
+                "refs/remotes",
+                "refs/tags",
+            ])
+            .await?;
+
+        let mut labelled: Vec<(u8, String, String)> = Vec::new();
+        for line in output.lines() {
+            let mut fields = line.splitn(3, '\x1f');
+            let (Some(object), Some(peeled), Some(refname)) =
+                (fields.next(), fields.next(), fields.next())
+            else {
+                continue;
+            };
+            let commit = if peeled.is_empty() { object } else { peeled };
+            let (order, label) = if let Some(name) = refname.strip_prefix("refs/heads/") {
+                (0, name.to_string())
+            } else if let Some(name) = refname.strip_prefix("refs/remotes/") {
+                if name.ends_with("/HEAD") {
+                    continue;
+                }
+                (1, name.to_string())
+            } else if let Some(name) = refname.strip_prefix("refs/tags/") {
+                (2, format!("tag:{name}"))
+            } else {
+                continue;
+            };
+            labelled.push((order, label, commit.to_string()));
+        }
+        labelled.sort();
+
+        let mut refs: HashMap<String, Vec<String>> = HashMap::new();
+        for (_, label, commit) in labelled {
+            refs.entry(commit).or_default().push(label);
+        }
+        Ok(refs)
+    }
+}
+
+/// Parses one `%H%x1f%h%x1f%P%x1f%an%x1f%ae%x1f%at%x1f%s` line. With
+/// `--parents`, `%P` holds the rewritten parents.
+fn parse_commit(line: &str) -> Option<CommitInfo> {
+    let parts: Vec<&str> = line.splitn(7, '\x1f').collect();
+    let [hash, short_hash, parents, author_name, author_email, timestamp, summary] = parts[..]
+    else {
+        return None;
+    };
+    Some(CommitInfo {
+        hash: hash.to_string(),
+        short_hash: short_hash.to_string(),
+        parent_hashes: parents.split_whitespace().map(str::to_string).collect(),
+        author_name: author_name.to_string(),
+        author_email: author_email.to_string(),
+        timestamp: timestamp.parse().unwrap_or(0),
+        summary: summary.to_string(),
+    })
+}
+
+/// Where a commit landed and what it connects to.
+#[derive(Debug, PartialEq, Eq)]
+struct Placement {
+    column: usize,
+    edges: Vec<GraphEdge>,
+    /// Lanes in use at this row, including ones opened for its parents.
+    width: usize,
+}
+
+/// Assigns a column to the next commit and updates the lanes for its
+/// parents.
+fn place_commit(lanes: &mut Vec<Option<String>>, hash: &str, parents: &[String]) -> Placement {
+    let waiting = |lane: &Option<String>| lane.as_deref() == Some(hash);
+    let column = match lanes.iter().position(waiting) {
+        Some(column) => column,
+        None => free_lane(lanes),
+    };
+    // Lanes converging on this commit end here.
+    for lane in lanes.iter_mut().filter(|lane| waiting(lane)) {
+        *lane = None;
+    }
+
+    let mut edges = Vec::with_capacity(parents.len());
+    if let Some(first) = parents.first() {
+        lanes[column] = Some(first.clone());
+        edges.push(GraphEdge {
+            parent: first.clone(),
+            column,
+            kind: EdgeKind::Parent,
+        });
+    }
+    for parent in parents.iter().skip(1) {
+        let lane = match lanes.iter().position(|l| l.as_deref() == Some(parent)) {
+            Some(lane) => lane,
+            None => {
+                let lane = free_lane(lanes);
+                lanes[lane] = Some(parent.clone());
+                lane
+            }
+        };
+        edges.push(GraphEdge {
+            parent: parent.clone(),
+            column: lane,
+            kind: EdgeKind::Merge,
+        });
+    }
+
+    let width = lanes.len();
+    while lanes.last().is_some_and(Option::is_none) {
+        lanes.pop();
+    }
+    Placement {
+        column,
+        edges,
+        width,
+    }
+}
+
+/// Index of the lowest free lane, opening a new one if none is free.
+fn free_lane(lanes: &mut Vec<Option<String>>) -> usize {
+    match lanes.iter().position(Option::is_none) {
+        Some(lane) => lane,
+        None => {
+            lanes.push(None);
+            lanes.len() - 1
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn place(lanes: &mut Vec<Option<String>>, hash: &str, parents: &[&str]) -> Placement {
+        let parents: Vec<String> = parents.iter().map(|p| p.to_string()).collect();
+        place_commit(lanes, hash, &parents)
+    }
+
+    fn columns(edges: &[GraphEdge]) -> Vec<(&str, usize, EdgeKind)> {
+        edges
+            .iter()
+            .map(|e| (e.parent.as_str(), e.column, e.kind))
+            .collect()
+    }
+
+    #[test]
+    fn test_layout_branch_and_merge() {
+        // m merges b2 into a1; b1 and a1 both come from r.
+        //   m
+        //   |\
+        //   a1 b2
+        //   |  b1
+        //   | /
+        //   r
+        let mut lanes = Vec::new();
+        let m = place(&mut lanes, "m", &["a1", "b2"]);
+        assert_eq!(m.column, 0);
+        assert_eq!(
+            columns(&m.edges),
+            vec![("a1", 0, EdgeKind::Parent), ("b2", 1, EdgeKind::Merge)]
+        );
+        assert_eq!(m.width, 2);
+
+        let a1 = place(&mut lanes, "a1", &["r"]);
+        assert_eq!(a1.column, 0);
+        assert_eq!(lanes, vec![Some("r".to_string()), Some("b2".to_string())]);
+
+        let b2 = place(&mut lanes, "b2", &["b1"]);
+        assert_eq!(b2.column, 1);
+        assert_eq!(lanes, vec![Some("r".to_string()), Some("b1".to_string())]);
+        let b1 = place(&mut lanes, "b1", &["r"]);
+        assert_eq!(b1.column, 1);
+
+        // Both lanes wait for r: it takes the lower one and the other ends.
+        let r = place(&mut lanes, "r", &[]);
+        assert_eq!(r.column, 0);
+        assert!(lanes.is_empty());
+    }
+
+    #[test]
+    fn test_layout_reuses_freed_lanes_and_joins_waiting_lane() {
+        let mut lanes = vec![None, Some("x".to_string())];
+        // A new tip takes the free lane 0.
+        let tip = place(&mut lanes, "t", &["p", "x"]);
+        assert_eq!(tip.column, 0);
+        // The merged parent is already awaited in lane 1.
+        assert_eq!(
+            columns(&tip.edges),
+            vec![("p", 0, EdgeKind::Parent), ("x", 1, EdgeKind::Merge)]
+        );
+        assert_eq!(lanes, vec![Some("p".to_string()), Some("x".to_string())]);
+    }
+
+    async fn commit(git: &Git, dir: &std::path::Path, file: &str, message: &str) -> String {
+        tokio::fs::write(dir.join(file), message).await.unwrap();
+        git.run(&["add", "."]).await.unwrap();
+        git.run(&["commit", "-m", message]).await.unwrap();
+        git.run(&["rev-parse", "HEAD"])
+            .await
+            .unwrap()
+            .trimmed()
+            .to_string()
+    }
+
+    #[tokio::test]
+    async fn test_commit_graph_pages_refs_and_paths() {
+        let dir = tempdir().unwrap();
+        let git = Git::new(dir.path());
+        git.run(&["init", "-b", "main"]).await.unwrap();
+        git.run(&["config", "user.email", "test@test.com"])
+            .await
+            .unwrap();
+        git.run(&["config", "user.name", "Test"]).await.unwrap();
+
+        let empty = git.commit_graph(&GraphQuery::default()).await.unwrap();
+        assert!(empty.rows.is_empty() && empty.next.is_none());
+
+        let root = commit(&git, dir.path(), "a.txt", "root").await;
+        git.run(&["checkout", "-b", "feature"]).await.unwrap();
+        let feature = commit(&git, dir.path(), "b.txt", "feature").await;
+        git.run(&["checkout", "main"]).await.unwrap();
+        let main = commit(&git, dir.path(), "a.txt", "main").await;
+        git.run(&["merge", "--no-ff", "-m", "merge", "feature"])
+            .await
+            .unwrap();
+        let merge = git
+            .run(&["rev-parse", "HEAD"])
+            .await
+            .unwrap()
+            .trimmed()
+            .to_string();
+        git.run(&["tag", "-a", "v1", "-m", "v1"]).await.unwrap();
+
+        let first = git
+            .commit_graph(&GraphQuery {
+                limit: 2,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(first.rows.len(), 2);
+        assert_eq!(first.rows[0].commit.hash, merge);
+        assert_eq!(first.rows[0].refs, vec!["main", "tag:v1"]);
+        assert_eq!(first.rows[0].edges.len(), 2);
+        assert_eq!(first.width, 2);
+
+        let cursor = first.next.clone().unwrap();
+        assert_eq!(cursor.skip, 2);
+        let second = git
+            .commit_graph(&GraphQuery {
+                limit: 2,
+                cursor: Some(cursor),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert!(second.next.is_none());
+        assert_eq!(second.rows.last().unwrap().commit.hash, root);
+        assert_eq!(second.rows.last().unwrap().row, 3);
+        assert_eq!(second.rows.last().unwrap().column, 0);
+
+        let all: Vec<_> = first.rows.iter().chain(&second.rows).collect();
+        let feature_row = all.iter().find(|r| r.commit.hash == feature).unwrap();
+        assert_eq!(feature_row.refs, vec!["feature"]);
+        assert_eq!(feature_row.column, 1);
+        let main_row = all.iter().find(|r| r.commit.hash == main).unwrap();
+        assert_eq!(main_row.column, 0);
+
+        // Only commits touching b.txt, with parents rewritten past the rest.
+        let by_path = git
+            .commit_graph(&GraphQuery {
+                paths: vec!["b.txt".to_string()],
+                branches: vec!["main".to_string()],
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        let hashes: Vec<_> = by_path
+            .rows
+            .iter()
+            .map(|r| r.commit.hash.as_str())
+            .collect();
+        assert_eq!(hashes, vec![feature.as_str()]);
+    }
+}
@@ -0,0 +1,540 @@
+//! File-centric history: line blame and per-file commit logs.
+//!
+//! Blame ranges can be attributed to Maestro session branches with
+//! [`Git::branch_commits`], which answers "which agent last touched this
+//! line" even after the session branch has been merged.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::error::GitError;
+use super::ops::{parse_name_status, CommitInfo, FileChangeStatus};
+use super::runner::Git;
+
+/// Options for [`Git::blame`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct BlameOptions {
+    /// Blame the file as of this revision instead of the working tree.
+    pub rev: Option<String>,
+    /// Only blame lines `start..=end` (1-based, inclusive).
+    pub lines: Option<(u32, u32)>,
+    /// Commits to look through, e.g. bulk reformatting.
+    pub ignore_revs: Vec<String>,
+    /// A file of commits to look through (`.git-blame-ignore-revs`), relative
+    /// to the repository root.
+    pub ignore_revs_file: Option<String>,
+}
+
+/// The commit a blamed range was last changed in.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BlameCommit {
+    pub hash: String,
+    pub author_name: String,
+    pub author_email: String,
+    /// Unix timestamp in seconds.
+    pub author_time: i64,
+    pub summary: String,
+    /// The commit's parent and the file's path there, for blaming further
+    /// back. `None` for the commit that added the file.
+    pub previous: Option<(String, String)>,
+    /// The range goes back to the history's root (or a shallow boundary).
+    pub boundary: bool,
+    /// The lines are uncommitted changes in the working tree.
+    pub uncommitted: bool,
+}
+
+/// Consecutive lines last changed by the same commit.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BlameRange {
+    /// First line in the blamed file (1-based).
+    pub start_line: u32,
+    pub line_count: u32,
+    /// First line in the file as of `commit`.
+    pub original_start_line: u32,
+    /// The file's path as of `commit`, which differs after renames.
+    pub original_path: String,
+    pub commit: BlameCommit,
+    /// The Maestro session branch `commit` was made on, if any; filled in
+    /// by [`mark_session_branches`].
+    pub session_branch: Option<String>,
+}
+
+/// A commit in a file's history.
+#[derive(Debug, Clone, Serialize)]
+pub struct FileLogEntry {
+    pub commit: CommitInfo,
+    pub status: FileChangeStatus,
+    /// The file's path after this commit.
+    pub path: String,
+    /// The file's path before this commit, for renames and copies.
+    pub old_path: Option<String>,
+}
+
+impl Git {
+    /// Blames `path` (relative to the repository root), returning ranges of
+    /// lines in file order.
+    pub async fn blame(
+        &self,
+        path: &str,
+        options: &BlameOptions,
+    ) -> Result<Vec<BlameRange>, GitError> {
+        let mut args = vec!["blame".to_string(), "--porcelain".to_string()];
+        if let Some((start, end)) = options.lines {
+            args.push(format!("-L{start},{end}"));
+        }
+        for rev in &options.ignore_revs {
+            args.push(format!("--ignore-rev={rev}"));
+        }
+        if let Some(ref file) = options.ignore_revs_file {
+            args.push(format!("--ignore-revs-file={file}"));
+        }
+        if let Some(ref rev) = options.rev {
+            args.push(rev.clone());
+        }
+        args.push("--".to_string());
+        args.push(path.to_string());
+
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        let output = self.run(&args).await?;
+        parse_blame_porcelain(&output.stdout)
+    }
+
+    /// Returns up to `max_count` commits that changed `path`, newest first,
+    /// skipping the first `skip`. Follows the file across renames.
+    pub async fn file_log(
+        &self,
+        path: &str,
+        rev: Option<&str>,
+        max_count: usize,
+        skip: usize,
+    ) -> Result<Vec<FileLogEntry>, GitError> {
+        // `--skip` counts commits before `--follow` filters them, so page
+        // here instead.
+        let count_arg = format!("--max-count={}", skip + max_count);
+        let mut args = vec![
+            "log",
+            "--follow",
+            "--name-status",
+            "--format=%x1e%H%x1f%h%x1f%P%x1f%an%x1f%ae%x1f%at%x1f%s",
+            &count_arg,
+        ];
+        if let Some(rev) = rev {
+            args.push(rev);
+        }
+        args.extend(["--", path]);
+
+        let output = self.run(&args).await?;
+        let mut entries = parse_file_log(&output.stdout, path);
+        entries.drain(..skip.min(entries.len()));
+        Ok(entries)
+    }
+
+    /// Maps each commit made on one of `branches` since the branch was
+    /// created to that branch's name.
+    ///
+    /// The creation point comes from the branch's reflog, so commits stay
+    /// attributed after the branch is merged. Branches without a reflog
+    /// fall back to their commits not on any other local branch. Branches
+    /// that don't exist are skipped.
+    pub async fn branch_commits(
+        &self,
+        branches: &[String],
+    ) -> Result<HashMap<String, String>, GitError> {
+        let mut commits = HashMap::new();
+        for branch in branches {
+            let full_ref = format!("refs/heads/{branch}");
+            let Ok(reflog) = self.run(&["log", "-g", "--format=%H", &full_ref]).await else {
+                continue;
+            };
+            let mut args = vec!["rev-list".to_string(), full_ref.clone()];
+            match reflog.lines().last() {
+                Some(created_at) => args.push(format!("^{created_at}")),
+                None => {
+                    args.push("--not".to_string());
+                    args.extend(branches.iter().map(|b| format!("--exclude={b}")));
+                    args.push("--branches".to_string());
+                }
+            }
+            let args: Vec<&str> = args.iter().map(String::as_str).collect();
+            for hash in self.run(&args).await?.lines() {
+                commits
+                    .entry(hash.to_string())
+                    .or_insert_with(|| branch.clone());
+            }
+        }
+        Ok(commits)
+    }
+}
+
+/// Sets [`BlameRange::session_branch`] from a commit-to-branch map built by
+/// [`Git::branch_commits`].
+pub fn mark_session_branches(ranges: &mut [BlameRange], branch_commits: &HashMap<String, String>) {
+    for range in ranges {
+        range.session_branch = branch_commits.get(&range.commit.hash).cloned();
+    }
+}
+
+/// Parses `git blame --porcelain` output.
+///
+/// Each group of lines starts with `<hash> <orig-line> <final-line> <count>`;
+/// the commit's details follow only the first time the commit appears, and
+/// `filename` follows every group header.
+fn parse_blame_porcelain(output: &str) -> Result<Vec<BlameRange>, GitError> {
+    struct PendingRange {
+        hash: String,
+        start_line: u32,
+        line_count: u32,
+        original_start_line: u32,
+        original_path: String,
+    }
+
+    let mut commits: HashMap<String, BlameCommit> = HashMap::new();
+    let mut pending: Vec<PendingRange> = Vec::new();
+    let mut current: Option<String> = None;
+
+    for line in output.lines() {
+        if line.starts_with('\t') {
+            continue;
+        }
+        let fields: Vec<&str> = line.split(' ').collect();
+        if is_group_header(&fields) {
+            let hash = fields[0].to_string();
+            commits.entry(hash.clone()).or_insert_with(|| BlameCommit {
+                hash: hash.clone(),
+                author_name: String::new(),
+                author_email: String::new(),
+                author_time: 0,
+                summary: String::new(),
+                previous: None,
+                boundary: false,
+                uncommitted: hash.bytes().all(|b| b == b'0'),
+            });
+            if let [_, original, start, count] = fields[..] {
+                pending.push(PendingRange {
+                    hash: hash.clone(),
+                    start_line: parse_number(start)?,
+                    line_count: parse_number(count)?,
+                    original_start_line: parse_number(original)?,
+                    original_path: String::new(),
+                });
+            }
+            current = Some(hash);
+            continue;
+        }
+
+        let Some(commit) = current.as_ref().and_then(|hash| commits.get_mut(hash)) else {
+            continue;
+        };
+        let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+        match key {
+            "author" => commit.author_name = value.to_string(),
+            "author-mail" => {
+                commit.author_email = value
+                    .trim_start_matches('<')
+                    .trim_end_matches('>')
+                    .to_string()
+            }
+            "author-time" => commit.author_time = value.parse().unwrap_or(0),
+            "summary" => commit.summary = value.to_string(),
+            "previous" => {
+                commit.previous = value
+                    .split_once(' ')
+                    .map(|(hash, path)| (hash.to_string(), path.to_string()))
+            }
+            "boundary" => commit.boundary = true,
+            "filename" => {
+                if let Some(range) = pending.last_mut() {
+                    range.original_path = value.to_string();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Ok(pending
+        .into_iter()
+        .map(|range| BlameRange {
+            commit: commits[&range.hash].clone(),
+            start_line: range.start_line,
+            line_count: range.line_count,
+            original_start_line: range.original_start_line,
+            original_path: range.original_path,
+            session_branch: None,
+        })
+        .collect())
+}
+
+/// Whether `fields` is a `<hash> <orig-line> <final-line> [<count>]` line.
+fn is_group_header(fields: &[&str]) -> bool {
+    matches!(fields.len(), 3 | 4)
+        && matches!(fields[0].len(), 40 | 64)
+        && fields[0].bytes().all(|b| b.is_ascii_hexdigit())
+        && fields[1..].iter().all(|f| f.parse::<u32>().is_ok())
+}
+
+fn parse_number(field: &str) -> Result<u32, GitError> {
+    field.parse().map_err(|_| GitError::ParseError {
+        message: format!("invalid line number in blame output: {field}"),
+    })
+}
+
+/// Parses `git log --follow --name-status` output whose commits start with
+/// a `0x1e` record separator and have `0x1f`-separated fields.
+///
+/// Commits without a status line (merges) keep the path the file had in the
+/// next newer commit.
+fn parse_file_log(output: &str, path: &str) -> Vec<FileLogEntry> {
+    let mut entries = Vec::new();
+    let mut current_path = path.to_string();
+
+    for record in output.split('\x1e').filter(|r| !r.trim().is_empty()) {
+        let mut lines = record.lines();
+        let Some(header) = lines.next() else {
+            continue;
+        };
+        let parts: Vec<&str> = header.splitn(7, '\x1f').collect();
+        if parts.len() < 7 {
+            continue;
+        }
+        let commit = CommitInfo {
+            hash: parts[0].to_string(),
+            short_hash: parts[1].to_string(),
+            parent_hashes: parts[2].split_whitespace().map(str::to_string).collect(),
+            author_name: parts[3].to_string(),
+            author_email: parts[4].to_string(),
+            timestamp: parts[5].parse().unwrap_or(0),
+            summary: parts[6].to_string(),
+        };
+
+        let changes: Vec<&str> = lines.filter(|l| !l.is_empty()).collect();
+        let entry = match parse_name_status(&changes).into_iter().next() {
+            Some(change) => FileLogEntry {
+                commit,
+                status: change.status,
+                path: change.path,
+                old_path: change.old_path,
+            },
+            None => FileLogEntry {
+                commit,
+                status: FileChangeStatus::Modified,
+                path: current_path.clone(),
+                old_path: None,
+            },
+        };
+        current_path = entry.old_path.clone().unwrap_or_else(|| entry.path.clone());
+        entries.push(entry);
+    }
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    async fn create_test_repo() -> (tempfile::TempDir, Git) {
+        let dir = tempdir().unwrap();
+        let git = Git::new(dir.path());
+        git.run(&["init", "-b", "main"]).await.unwrap();
+        git.run(&["config", "user.email", "test@test.com"])
+            .await
+            .unwrap();
+        git.run(&["config", "user.name", "Test"]).await.unwrap();
+        tokio::fs::write(dir.path().join("lib.rs"), "one\ntwo\nthree\n")
+            .await
+            .unwrap();
+        git.run(&["add", "."]).await.unwrap();
+        git.run(&["commit", "-m", "initial"]).await.unwrap();
+        (dir, git)
+    }
+
+    async fn commit_file(dir: &std::path::Path, git: &Git, path: &str, content: &str, msg: &str) {
+        tokio::fs::write(dir.join(path), content).await.unwrap();
+        git.run(&["add", "-A"]).await.unwrap();
+        git.run(&["commit", "-m", msg]).await.unwrap();
+    }
+
+    #[test]
+    fn test_parse_blame_porcelain() {
+        let a = "a".repeat(40);
+        let b = "b".repeat(40);
+        let output = format!(
+            "{a} 1 1 2\nauthor Alice\nauthor-mail <alice@example.com>\nauthor-time 1700000000\n\
+             author-tz +0000\nsummary Add lines\nboundary\nfilename old.rs\n\tone\n{a} 2 2\n\ttwo\n\
+             {b} 3 3 1\nauthor Bob\nauthor-mail <bob@example.com>\nauthor-time 1700000100\n\
+             summary Change three\nprevious {a} old.rs\nfilename new.rs\n\tthree\n\
+             {a} 5 4 1\nfilename old.rs\n\tfour\n"
+        );
+        let ranges = parse_blame_porcelain(&output).unwrap();
+        assert_eq!(ranges.len(), 3);
+
+        assert_eq!(
+            (
+                ranges[0].start_line,
+                ranges[0].line_count,
+                ranges[0].original_start_line
+            ),
+            (1, 2, 1)
+        );
+        assert_eq!(ranges[0].original_path, "old.rs");
+        assert_eq!(ranges[0].commit.author_name, "Alice");
+        assert_eq!(ranges[0].commit.author_email, "alice@example.com");
+        assert_eq!(ranges[0].commit.author_time, 1_700_000_000);
+        assert!(ranges[0].commit.boundary);
+        assert!(!ranges[0].commit.uncommitted);
+
+        assert_eq!(ranges[1].commit.summary, "Change three");
+        assert_eq!(
+            ranges[1].commit.previous,
+            Some((a.clone(), "old.rs".into()))
+        );
+        assert_eq!(ranges[1].original_path, "new.rs");
+
+        // Details are only printed once per commit.
+        assert_eq!(ranges[2].commit, ranges[0].commit);
+        assert_eq!(
+            (ranges[2].start_line, ranges[2].original_start_line),
+            (4, 5)
+        );
+    }
+
+    #[test]
+    fn test_parse_file_log_keeps_path_across_merges() {
+        let output = "\x1eccc\x1fccc\x1fbbb\x1fA\x1fa@x\x1f3\x1fEdit\n\nM\tsrc/new.rs\n\
+                      \x1ebbb\x1fbbb\x1faaa\x1fA\x1fa@x\x1f2\x1fMove\n\nR100\told.rs\tsrc/new.rs\n\
+                      \x1emmm\x1fmmm\x1fx y\x1fA\x1fa@x\x1f1\x1fMerge\n\
+                      \x1eaaa\x1faaa\x1f\x1fA\x1fa@x\x1f0\x1fAdd\n\nA\told.rs\n";
+        let entries = parse_file_log(output, "src/new.rs");
+        let summary: Vec<_> = entries
+            .iter()
+            .map(|e| (e.commit.summary.as_str(), e.path.as_str(), e.status.clone()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("Edit", "src/new.rs", FileChangeStatus::Modified),
+                ("Move", "src/new.rs", FileChangeStatus::Renamed),
+                ("Merge", "old.rs", FileChangeStatus::Modified),
+                ("Add", "old.rs", FileChangeStatus::Added),
+            ]
+        );
+        assert_eq!(entries[1].old_path.as_deref(), Some("old.rs"));
+        assert_eq!(entries[2].commit.parent_hashes, vec!["x", "y"]);
+    }
+
+    #[tokio::test]
+    async fn test_blame_uncommitted_lines_and_ignore_revs() {
+        let (dir, git) = create_test_repo().await;
+        commit_file(dir.path(), &git, "lib.rs", "ONE\ntwo\nthree\n", "shout").await;
+        let shout = git
+            .run(&["rev-parse", "HEAD"])
+            .await
+            .unwrap()
+            .trimmed()
+            .to_string();
+        tokio::fs::write(dir.path().join("lib.rs"), "ONE\ntwo\nthree\nfour\n")
+            .await
+            .unwrap();
+
+        let ranges = git.blame("lib.rs", &BlameOptions::default()).await.unwrap();
+        let summaries: Vec<_> = ranges
+            .iter()
+            .map(|r| (r.start_line, r.line_count, r.commit.summary.as_str()))
+            .collect();
+        assert_eq!(
+            summaries,
+            vec![
+                (1, 1, "shout"),
+                (2, 2, "initial"),
+                (4, 1, "Version of lib.rs from lib.rs")
+            ]
+        );
+        assert!(ranges[2].commit.uncommitted);
+
+        let options = BlameOptions {
+            rev: Some("HEAD".into()),
+            lines: Some((1, 1)),
+            ignore_revs: vec![shout],
+            ..Default::default()
+        };
+        let ranges = git.blame("lib.rs", &options).await.unwrap();
+        assert_eq!(ranges.len(), 1);
+        assert_eq!(ranges[0].commit.summary, "initial");
+    }
+
+    #[tokio::test]
+    async fn test_file_log_follows_renames() {
+        let (dir, git) = create_test_repo().await;
+        tokio::fs::create_dir(dir.path().join("src")).await.unwrap();
+        git.run(&["mv", "lib.rs", "src/lib.rs"]).await.unwrap();
+        git.run(&["commit", "-m", "move"]).await.unwrap();
+        commit_file(
+            dir.path(),
+            &git,
+            "src/lib.rs",
+            "one\ntwo\nthree\nfour\n",
+            "grow",
+        )
+        .await;
+        commit_file(dir.path(), &git, "other.rs", "x", "unrelated").await;
+
+        let log = git.file_log("src/lib.rs", None, 10, 0).await.unwrap();
+        let summary: Vec<_> = log
+            .iter()
+            .map(|e| (e.commit.summary.as_str(), e.path.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("grow", "src/lib.rs"),
+                ("move", "src/lib.rs"),
+                ("initial", "lib.rs")
+            ]
+        );
+        assert_eq!(log[1].status, FileChangeStatus::Renamed);
+        assert_eq!(log[1].old_path.as_deref(), Some("lib.rs"));
+
+        let page = git.file_log("src/lib.rs", None, 1, 1).await.unwrap();
+        assert_eq!(page.len(), 1);
+        assert_eq!(page[0].commit.summary, "move");
+    }
+
+    #[tokio::test]
+    async fn test_session_branch_attribution_survives_merge() {
+        let (dir, git) = create_test_repo().await;
+        git.run(&["checkout", "-q", "-b", "session-1"])
+            .await
+            .unwrap();
+        commit_file(
+            dir.path(),
+            &git,
+            "lib.rs",
+            "one\nTWO\nthree\n",
+            "agent edit",
+        )
+        .await;
+        git.run(&["checkout", "-q", "main"]).await.unwrap();
+        git.run(&["merge", "-q", "--ff-only", "session-1"])
+            .await
+            .unwrap();
+
+        let commits = git
+            .branch_commits(&["session-1".to_string(), "missing".to_string()])
+            .await
+            .unwrap();
+        assert_eq!(commits.len(), 1);
+
+        let mut ranges = git.blame("lib.rs", &BlameOptions::default()).await.unwrap();
+        mark_session_branches(&mut ranges, &commits);
+        let attributed: Vec<_> = ranges
+            .iter()
+            .map(|r| (r.start_line, r.session_branch.as_deref()))
+            .collect();
+        assert_eq!(
+            attributed,
+            vec![(1, None), (2, Some("session-1")), (3, None)]
+        );
+    }
+}
@@ -0,0 +1,528 @@
+//! Merge and rebase operations with structured conflict reporting.
+//!
+//! Conflicting operations stop with the repository mid-merge or mid-rebase
+//! and return a [`MergeOutcome::Conflicted`] listing every unmerged file and
+//! its conflict hunks. Callers resolve the files, then call
+//! [`Git::merge_continue`] / [`Git::rebase_continue`], or abort.
+//!
+//! Conflicts are written in `diff3` style so each hunk carries the merge-base
+//! version alongside both sides.
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use serde::Serialize;
+
+use super::error::GitError;
+use super::runner::Git;
+
+/// Merges and rebases can touch many files and run hooks; allow more than
+/// the default 30 seconds.
+const MERGE_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// One `<<<<<<<` ... `>>>>>>>` block in a conflicted file.
+///
+/// During a merge "ours" is the branch being merged into and "theirs" the
+/// branch being merged. During a rebase they are swapped: "ours" is the
+/// upstream being rebased onto and "theirs" the commit being replayed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ConflictHunk {
+    /// 1-based line of the `<<<<<<<` marker.
+    pub start_line: u32,
+    /// 1-based line of the `>>>>>>>` marker.
+    pub end_line: u32,
+    pub ours_label: String,
+    pub theirs_label: String,
+    pub ours: Vec<String>,
+    /// Merge-base lines, present for `diff3` / `zdiff3` conflicts.
+    pub base: Option<Vec<String>>,
+    pub theirs: Vec<String>,
+}
+
+/// An unmerged file and its conflict hunks.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ConflictedFile {
+    pub path: String,
+    /// The file is binary (or not UTF-8) and has no textual hunks.
+    pub binary: bool,
+    /// The file was deleted on one side (or added on only one side); it has
+    /// no hunks.
+    pub deleted: bool,
+    pub hunks: Vec<ConflictHunk>,
+}
+
+/// Result of a merge, rebase or continue step.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "status", content = "conflicts", rename_all = "snake_case")]
+pub enum MergeOutcome {
+    /// The operation finished; `HEAD` points at the result.
+    Completed,
+    /// The operation stopped on conflicts and is still in progress.
+    Conflicted(Vec<ConflictedFile>),
+}
+
+impl Git {
+    /// Merges `branch` into `HEAD`, always creating a merge commit
+    /// (`--no-ff`). With `squash`, the changes are combined into a single
+    /// regular commit instead.
+    pub async fn merge_branch(
+        &self,
+        branch: &str,
+        message: &str,
+        squash: bool,
+    ) -> Result<MergeOutcome, GitError> {
+        let mode = if squash { "--squash" } else { "--no-ff" };
+        let args = [
+            "-c",
+            "merge.conflictStyle=diff3",
+            "merge",
+            mode,
+            "--no-edit",
+            "-m",
+            message,
+            branch,
+        ];
+        if let Some(outcome) = self.run_merge_step(&args).await? {
+            return Ok(outcome);
+        }
+        if squash {
+            // --squash stops before committing
+            self.run(&["commit", "--no-edit", "-m", message]).await?;
+        }
+        Ok(MergeOutcome::Completed)
+    }
+
+    /// Rebases `HEAD` onto `upstream`.
+    pub async fn rebase_onto(&self, upstream: &str) -> Result<MergeOutcome, GitError> {
+        let args = ["-c", "merge.conflictStyle=diff3", "rebase", upstream];
+        Ok(self
+            .run_merge_step(&args)
+            .await?
+            .unwrap_or(MergeOutcome::Completed))
+    }
+
+    /// Commits a merge (or squash merge) whose conflicts have been resolved.
+    /// Stages the conflicted files that no longer contain conflict markers
+    /// first. Returns the remaining conflicts if any file still contains
+    /// markers or is a binary or modify/delete conflict the caller has not
+    /// staged (`git add` / `git rm`) yet.
+    pub async fn merge_continue(&self) -> Result<MergeOutcome, GitError> {
+        if let Some(outcome) = self.stage_resolutions().await? {
+            return Ok(outcome);
+        }
+        self.run(&["commit", "--no-edit"]).await?;
+        Ok(MergeOutcome::Completed)
+    }
+
+    /// Continues a rebase after its conflicts have been resolved. Stages the
+    /// resolved files first, like [`Git::merge_continue`], and may stop again
+    /// on a later commit.
+    ///
+    /// The resolved commit is committed directly (reusing its message) so
+    /// `rebase --continue` never needs an editor. A commit that became empty
+    /// through the resolution is skipped.
+    pub async fn rebase_continue(&self) -> Result<MergeOutcome, GitError> {
+        if let Some(outcome) = self.stage_resolutions().await? {
+            return Ok(outcome);
+        }
+        let nothing_staged = self.run(&["diff", "--cached", "--quiet"]).await.is_ok();
+        let step = if nothing_staged {
+            vec!["-c", "merge.conflictStyle=diff3", "rebase", "--skip"]
+        } else {
+            self.run(&["commit", "--no-edit"]).await?;
+            vec!["-c", "merge.conflictStyle=diff3", "rebase", "--continue"]
+        };
+        Ok(self
+            .run_merge_step(&step)
+            .await?
+            .unwrap_or(MergeOutcome::Completed))
+    }
+
+    /// Aborts an in-progress merge, restoring the pre-merge state.
+    pub async fn merge_abort(&self) -> Result<(), GitError> {
+        self.run(&["merge", "--abort"]).await?;
+        Ok(())
+    }
+
+    /// Aborts an in-progress rebase, restoring the original `HEAD`.
+    pub async fn rebase_abort(&self) -> Result<(), GitError> {
+        self.run(&["rebase", "--abort"]).await?;
+        Ok(())
+    }
+
+    /// Lists unmerged files with their conflict hunks.
+    ///
+    /// Unmerged index entries are read with `git ls-files --unmerged`; a file
+    /// missing the "ours" (stage 2) or "theirs" (stage 3) entry was deleted on
+    /// that side.
+    pub async fn conflicted_files(&self) -> Result<Vec<ConflictedFile>, GitError> {
+        let output = self.run(&["ls-files", "--unmerged", "-z"]).await?;
+        let mut stages: BTreeMap<String, Vec<&str>> = BTreeMap::new();
+        for record in output.stdout.split('\0') {
+            // <mode> SP <object> SP <stage> TAB <path>
+            let Some((info, path)) = record.split_once('\t') else {
+                continue;
+            };
+            let stage = info.rsplit(' ').next().unwrap_or_default();
+            stages.entry(path.to_string()).or_default().push(stage);
+        }
+
+        let mut files = Vec::new();
+        for (path, stages) in stages {
+            let both_sides = stages.contains(&"2") && stages.contains(&"3");
+            files.push(self.read_conflicted_file(path, !both_sides).await);
+        }
+        Ok(files)
+    }
+
+    async fn read_conflicted_file(&self, path: String, deleted: bool) -> ConflictedFile {
+        if deleted {
+            return ConflictedFile {
+                path,
+                binary: false,
+                deleted: true,
+                hunks: Vec::new(),
+            };
+        }
+        match tokio::fs::read(self.repo_path().join(&path)).await {
+            Ok(bytes) => match String::from_utf8(bytes) {
+                Ok(text) if !text.contains('\0') => ConflictedFile {
+                    hunks: parse_conflict_markers(&text),
+                    path,
+                    binary: false,
+                    deleted: false,
+                },
+                _ => ConflictedFile {
+                    path,
+                    binary: true,
+                    deleted: false,
+                    hunks: Vec::new(),
+                },
+            },
+            Err(_) => ConflictedFile {
+                path,
+                binary: false,
+                deleted: true,
+                hunks: Vec::new(),
+            },
+        }
+    }
+
+    /// Runs a merge/rebase step. Returns `Some(Conflicted)` if it stopped on
+    /// conflicts, `None` if it succeeded, and the original error if it
+    /// failed for another reason.
+    async fn run_merge_step(&self, args: &[&str]) -> Result<Option<MergeOutcome>, GitError> {
+        match self.run_with_timeout(args, MERGE_TIMEOUT).await {
+            Ok(_) => Ok(None),
+            Err(e @ GitError::CommandFailed { .. }) => {
+                let conflicts = self.conflicted_files().await?;
+                if conflicts.is_empty() {
+                    Err(e)
+                } else {
+                    Ok(Some(MergeOutcome::Conflicted(conflicts)))
+                }
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Stages the unmerged text files once none of them still contains
+    /// conflict markers. Binary and modify/delete conflicts have no markers
+    /// to check, so they stay unresolved until the caller stages them.
+    /// Returns the remaining conflicts otherwise (nothing is staged).
+    async fn stage_resolutions(&self) -> Result<Option<MergeOutcome>, GitError> {
+        let (resolved, remaining): (Vec<ConflictedFile>, Vec<ConflictedFile>) = self
+            .conflicted_files()
+            .await?
+            .into_iter()
+            .partition(|f| !f.binary && !f.deleted && f.hunks.is_empty());
+        if !remaining.is_empty() {
+            return Ok(Some(MergeOutcome::Conflicted(remaining)));
+        }
+        if !resolved.is_empty() {
+            let mut args = vec!["add", "--"];
+            args.extend(resolved.iter().map(|f| f.path.as_str()));
+            self.run(&args).await?;
+        }
+        Ok(None)
+    }
+}
+
+/// Parses conflict marker blocks (merge, `diff3` and `zdiff3` styles).
+/// Unterminated blocks are ignored.
+pub(crate) fn parse_conflict_markers(content: &str) -> Vec<ConflictHunk> {
+    enum Section {
+        Ours,
+        Base,
+        Theirs,
+    }
+
+    let mut hunks = Vec::new();
+    let mut current: Option<(ConflictHunk, Section)> = None;
+
+    for (i, line) in content.lines().enumerate() {
+        let lineno = i as u32 + 1;
+        let marker = |m: &str| -> Option<String> {
+            let rest = line.strip_prefix(m)?;
+            if rest.is_empty() {
+                Some(String::new())
+            } else {
+                rest.strip_prefix(' ').map(str::to_string)
+            }
+        };
+
+        match current.as_mut() {
+            None => {
+                if let Some(label) = marker("<<<<<<<") {
+                    let hunk = ConflictHunk {
+                        start_line: lineno,
+                        end_line: lineno,
+                        ours_label: label,
+                        theirs_label: String::new(),
+                        ours: Vec::new(),
+                        base: None,
+                        theirs: Vec::new(),
+                    };
+                    current = Some((hunk, Section::Ours));
+                }
+            }
+            Some((hunk, section)) => {
+                if matches!(section, Section::Ours) && marker("|||||||").is_some() {
+                    hunk.base = Some(Vec::new());
+                    *section = Section::Base;
+                } else if !matches!(section, Section::Theirs) && line == "=======" {
+                    *section = Section::Theirs;
+                } else if let (Section::Theirs, Some(label)) = (&section, marker(">>>>>>>")) {
+                    hunk.end_line = lineno;
+                    hunk.theirs_label = label;
+                    hunks.extend(current.take().map(|(hunk, _)| hunk));
+                } else {
+                    let target = match section {
+                        Section::Ours => &mut hunk.ours,
+                        Section::Base => hunk.base.get_or_insert_with(Vec::new),
+                        Section::Theirs => &mut hunk.theirs,
+                    };
+                    target.push(line.to_string());
+                }
+            }
+        }
+    }
+    hunks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_parse_conflict_markers_diff3() {
+        let content = "\
+fn route() {
+<<<<<<< HEAD
+    a();
+||||||| merged common ancestors
+    base();
+=======
+    b();
+    c();
+>>>>>>> feat/b
+}
+";
+        let hunks = parse_conflict_markers(content);
+        assert_eq!(hunks.len(), 1);
+        let hunk = &hunks[0];
+        assert_eq!((hunk.start_line, hunk.end_line), (2, 9));
+        assert_eq!(hunk.ours_label, "HEAD");
+        assert_eq!(hunk.theirs_label, "feat/b");
+        assert_eq!(hunk.ours, vec!["    a();"]);
+        assert_eq!(hunk.base, Some(vec!["    base();".to_string()]));
+        assert_eq!(hunk.theirs, vec!["    b();", "    c();"]);
+    }
+
+    #[test]
+    fn test_parse_conflict_markers_merge_style_and_unterminated() {
+        let content = "<<<<<<< ours\nx\n=======\ny\n>>>>>>> theirs\nok\n<<<<<<< ours\nz\n";
+        let hunks = parse_conflict_markers(content);
+        assert_eq!(hunks.len(), 1);
+        assert_eq!(hunks[0].base, None);
+        assert_eq!(hunks[0].ours, vec!["x"]);
+        assert_eq!(hunks[0].theirs, vec!["y"]);
+    }
+
+    #[test]
+    fn test_parse_conflict_markers_ignores_lookalikes() {
+        assert!(parse_conflict_markers("<<<<<<<<< not a marker\n=======\n").is_empty());
+    }
+
+    async fn create_diverged_repo() -> (tempfile::TempDir, Git) {
+        let dir = tempdir().unwrap();
+        let git = Git::new(dir.path());
+        git.run(&["init", "-b", "main"]).await.unwrap();
+        git.run(&["config", "user.email", "test@test.com"])
+            .await
+            .unwrap();
+        git.run(&["config", "user.name", "Test"]).await.unwrap();
+        tokio::fs::write(dir.path().join("f.txt"), "a\nb\nc\n")
+            .await
+            .unwrap();
+        git.run(&["add", "."]).await.unwrap();
+        git.run(&["commit", "-m", "initial"]).await.unwrap();
+
+        git.run(&["checkout", "-b", "feature"]).await.unwrap();
+        tokio::fs::write(dir.path().join("f.txt"), "a\nFEATURE\nc\n")
+            .await
+            .unwrap();
+        git.run(&["commit", "-am", "feature"]).await.unwrap();
+
+        git.run(&["checkout", "main"]).await.unwrap();
+        tokio::fs::write(dir.path().join("f.txt"), "a\nMAIN\nc\n")
+            .await
+            .unwrap();
+        git.run(&["commit", "-am", "main"]).await.unwrap();
+        (dir, git)
+    }
+
+    #[tokio::test]
+    async fn test_merge_conflict_resolve_and_continue() {
+        let (dir, git) = create_diverged_repo().await;
+
+        let outcome = git
+            .merge_branch("feature", "Merge feature", false)
+            .await
+            .unwrap();
+        let MergeOutcome::Conflicted(files) = outcome else {
+            panic!("expected conflicts, got {:?}", outcome);
+        };
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].path, "f.txt");
+        assert_eq!(files[0].hunks[0].ours, vec!["MAIN"]);
+        assert_eq!(files[0].hunks[0].base, Some(vec!["b".to_string()]));
+        assert_eq!(files[0].hunks[0].theirs, vec!["FEATURE"]);
+
+        // Continuing with markers still present reports them again
+        assert!(matches!(
+            git.merge_continue().await.unwrap(),
+            MergeOutcome::Conflicted(_)
+        ));
+
+        tokio::fs::write(dir.path().join("f.txt"), "a\nBOTH\nc\n")
+            .await
+            .unwrap();
+        assert_eq!(git.merge_continue().await.unwrap(), MergeOutcome::Completed);
+        let parents = git
+            .run(&["rev-list", "--parents", "-n1", "HEAD"])
+            .await
+            .unwrap();
+        assert_eq!(parents.trimmed().split(' ').count(), 3);
+        let subject = git.run(&["log", "-1", "--format=%s"]).await.unwrap();
+        assert_eq!(subject.trimmed(), "Merge feature");
+    }
+
+    #[tokio::test]
+    async fn test_merge_abort_restores_head() {
+        let (dir, git) = create_diverged_repo().await;
+        let head = git
+            .run(&["rev-parse", "HEAD"])
+            .await
+            .unwrap()
+            .trimmed()
+            .to_string();
+
+        git.merge_branch("feature", "Merge feature", false)
+            .await
+            .unwrap();
+        git.merge_abort().await.unwrap();
+
+        let after = git.run(&["rev-parse", "HEAD"]).await.unwrap();
+        assert_eq!(after.trimmed(), head);
+        let content = tokio::fs::read_to_string(dir.path().join("f.txt"))
+            .await
+            .unwrap();
+        assert_eq!(content, "a\nMAIN\nc\n");
+    }
+
+    #[tokio::test]
+    async fn test_squash_merge_clean() {
+        let (dir, git) = create_diverged_repo().await;
+        git.run(&["checkout", "-b", "other", "HEAD~1"])
+            .await
+            .unwrap();
+        tokio::fs::write(dir.path().join("g.txt"), "g\n")
+            .await
+            .unwrap();
+        git.run(&["add", "."]).await.unwrap();
+        git.run(&["commit", "-m", "g"]).await.unwrap();
+        git.run(&["checkout", "main"]).await.unwrap();
+
+        let outcome = git
+            .merge_branch("other", "Squash other", true)
+            .await
+            .unwrap();
+        assert_eq!(outcome, MergeOutcome::Completed);
+        let parents = git
+            .run(&["rev-list", "--parents", "-n1", "HEAD"])
+            .await
+            .unwrap();
+        assert_eq!(parents.trimmed().split(' ').count(), 2);
+        assert!(dir.path().join("g.txt").exists());
+    }
+
+    #[tokio::test]
+    async fn test_rebase_conflict_continue() {
+        let (dir, git) = create_diverged_repo().await;
+        git.run(&["checkout", "feature"]).await.unwrap();
+
+        let outcome = git.rebase_onto("main").await.unwrap();
+        let MergeOutcome::Conflicted(files) = outcome else {
+            panic!("expected conflicts, got {:?}", outcome);
+        };
+        // During a rebase "ours" is the upstream
+        assert_eq!(files[0].hunks[0].ours, vec!["MAIN"]);
+
+        tokio::fs::write(dir.path().join("f.txt"), "a\nMAIN+FEATURE\nc\n")
+            .await
+            .unwrap();
+        assert_eq!(
+            git.rebase_continue().await.unwrap(),
+            MergeOutcome::Completed
+        );
+
+        let log = git
+            .run(&["log", "--format=%s", "main..HEAD"])
+            .await
+            .unwrap();
+        assert_eq!(log.trimmed(), "feature");
+        let base = git.run(&["merge-base", "HEAD", "main"]).await.unwrap();
+        let main = git.run(&["rev-parse", "main"]).await.unwrap();
+        assert_eq!(base.trimmed(), main.trimmed());
+    }
+
+    #[tokio::test]
+    async fn test_modify_delete_conflict_needs_explicit_resolution() {
+        let (dir, git) = create_diverged_repo().await;
+        git.run(&["checkout", "-b", "gone", "HEAD~1"]).await.unwrap();
+        git.run(&["rm", "-q", "f.txt"]).await.unwrap();
+        git.run(&["commit", "-m", "delete f"]).await.unwrap();
+        git.run(&["checkout", "main"]).await.unwrap();
+
+        let outcome = git.merge_branch("gone", "Merge gone", false).await.unwrap();
+        let MergeOutcome::Conflicted(files) = outcome else {
+            panic!("expected conflicts, got {:?}", outcome);
+        };
+        assert_eq!(files.len(), 1);
+        assert!(files[0].deleted);
+        // The modified side is left in the worktree without markers
+        assert!(dir.path().join("f.txt").exists());
+
+        // Not staged automatically
+        assert!(matches!(
+            git.merge_continue().await.unwrap(),
+            MergeOutcome::Conflicted(_)
+        ));
+
+        git.run(&["add", "f.txt"]).await.unwrap();
+        assert_eq!(git.merge_continue().await.unwrap(), MergeOutcome::Completed);
+        assert!(dir.path().join("f.txt").exists());
+    }
+}
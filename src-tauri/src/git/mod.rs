@@ -1,7 +1,28 @@
+pub mod diff;
 pub mod error;
+pub mod graph;
+pub mod history;
+pub mod merge;
+#[cfg(feature = "gix-backend")]
+mod native;
 pub mod ops;
+pub mod progress;
 pub mod runner;
+pub mod snapshot;
+pub mod stash;
 
+pub use diff::{
+    DiffHunk, DiffLine, DiffLineKind, DiffOptions, DiffTarget, FileDiff, StatusEntry, TextRange,
+};
 pub use error::GitError;
-pub use ops::{BranchInfo, CommitInfo, FileChange, FileChangeStatus, GitUserConfig, RemoteInfo, WorktreeInfo};
+pub use graph::{GraphCursor, GraphPage, GraphQuery};
+pub use history::{BlameCommit, BlameOptions, BlameRange, FileLogEntry};
+pub use merge::{ConflictHunk, ConflictedFile, MergeOutcome};
+pub use ops::{
+    BranchInfo, CommitInfo, FileChange, FileChangeStatus, GitUserConfig, MergeTreeResult,
+    PushResult, RemoteInfo, WorktreeInfo,
+};
+pub use progress::{CancelToken, GitOperation, GitProgress, ProgressFn};
 pub use runner::Git;
+pub use snapshot::{worktree_snapshot_name, Snapshot, SNAPSHOT_REF_PREFIX};
+pub use stash::{AutostashOutcome, StashEntry};
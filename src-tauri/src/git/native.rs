@@ -0,0 +1,345 @@
+//! In-process implementations of the hot read-only operations, built on
+//! gitoxide (`gix`) behind the `gix-backend` feature.
+//!
+//! The UI polls `current_branch`, `uncommitted_count`, `list_branches`,
+//! `worktree_list` and `list_remotes` for every session and repository, and
+//! each CLI call spawns a `git` process. These functions answer the same
+//! questions without spawning anything and return the same values as the CLI
+//! parsers in `ops.rs`.
+//!
+//! Each function returns `Ok(None)` for states it does not model (e.g. a
+//! detached HEAD in `list_branches`, which the CLI labels from the reflog).
+//! [`Git::native`] treats that, and any error, as a signal to fall back to
+//! the CLI, so callers always get CLI-compatible results and errors.
+
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use super::ops::{BranchInfo, RemoteInfo, WorktreeInfo};
+use super::runner::Git;
+
+type NativeError = Box<dyn std::error::Error + Send + Sync>;
+
+/// `Ok(None)` means "not supported here, ask the CLI".
+pub(super) type NativeResult<T> = Result<Option<T>, NativeError>;
+
+impl Git {
+    /// Runs `op` against this runner's repository in-process, on the blocking
+    /// thread pool. Returns `None` if `op` declines or fails, in which case
+    /// the caller runs the CLI implementation.
+    pub(super) async fn native<T: Send + 'static>(
+        &self,
+        name: &'static str,
+        op: fn(&gix::Repository) -> NativeResult<T>,
+    ) -> Option<T> {
+        let path = self.repo_path().to_path_buf();
+        let result = tokio::task::spawn_blocking(move || -> NativeResult<T> {
+            let repo = gix::discover(&path)?;
+            op(&repo)
+        })
+        .await;
+        match result {
+            Ok(Ok(value)) => value,
+            Ok(Err(e)) => {
+                log::debug!(
+                    "In-process {} failed in {}, falling back to git: {}",
+                    name,
+                    self.repo_path().display(),
+                    e
+                );
+                None
+            }
+            Err(e) => {
+                log::warn!("In-process {} panicked: {}", name, e);
+                None
+            }
+        }
+    }
+}
+
+/// `git symbolic-ref --short HEAD`, or `git rev-parse --short HEAD` when
+/// detached.
+pub(super) fn current_branch(repo: &gix::Repository) -> NativeResult<String> {
+    if let Some(name) = repo.head_name()? {
+        return Ok(Some(name.shorten().to_string()));
+    }
+    Ok(Some(repo.head_id()?.shorten()?.to_string()))
+}
+
+/// Number of lines `git status --porcelain` would print: one per changed
+/// path, with fully untracked directories collapsed into one entry.
+pub(super) fn uncommitted_count(repo: &gix::Repository) -> NativeResult<usize> {
+    use gix::status::index_worktree::Item as WorktreeItem;
+    use gix::status::plumbing::index_as_worktree::EntryStatus;
+
+    if repo.workdir().is_none() {
+        return Ok(None);
+    }
+    let mut paths = BTreeSet::new();
+    for item in repo
+        .status(gix::progress::Discard)?
+        .index_worktree_rewrites(None)
+        .into_iter(Vec::new())?
+    {
+        let item = item?;
+        if let gix::status::Item::IndexWorktree(WorktreeItem::Modification {
+            status: EntryStatus::NeedsUpdate(_),
+            ..
+        }) = item
+        {
+            // Only the stat info changed; git doesn't report these either.
+            continue;
+        }
+        paths.insert(item.location().to_owned());
+    }
+    Ok(Some(paths.len()))
+}
+
+/// `git branch -a`: local branches, then remote-tracking branches, each
+/// sorted by name and without `HEAD` pointers.
+pub(super) fn list_branches(repo: &gix::Repository) -> NativeResult<Vec<BranchInfo>> {
+    let head = repo.head()?;
+    if head.is_detached() {
+        // The CLI lists a "(HEAD detached at ...)" pseudo-branch.
+        return Ok(None);
+    }
+    let current = head.referent_name().map(|name| name.to_owned());
+
+    let refs = repo.references()?;
+    let mut branches = Vec::new();
+    for (iter, is_remote) in [
+        (refs.local_branches()?, false),
+        (refs.remote_branches()?, true),
+    ] {
+        let mut names = Vec::new();
+        for reference in iter {
+            names.push(reference?.name().to_owned());
+        }
+        names.sort();
+        for full_name in names {
+            let short = full_name.shorten().to_string();
+            if short == "HEAD" || short.ends_with("/HEAD") {
+                continue;
+            }
+            branches.push(BranchInfo {
+                is_current: current.as_ref() == Some(&full_name),
+                name: short,
+                is_remote,
+            });
+        }
+    }
+    Ok(Some(branches))
+}
+
+/// `git worktree list --porcelain`: the main worktree first, then linked
+/// worktrees sorted by path.
+pub(super) fn worktree_list(repo: &gix::Repository) -> NativeResult<Vec<WorktreeInfo>> {
+    let main = repo.main_repo()?;
+    let mut worktrees = Vec::new();
+    let main_path = match main.workdir() {
+        Some(workdir) => workdir.to_path_buf(),
+        None => main.git_dir().to_path_buf(),
+    };
+    let mut info = worktree_info(&main, &std::fs::canonicalize(&main_path)?)?;
+    info.is_main_worktree = true;
+    if main.is_bare() {
+        info.head = String::new();
+        info.branch = None;
+        info.is_bare = true;
+    }
+    worktrees.push(info);
+
+    let mut linked = Vec::new();
+    for proxy in main.worktrees()? {
+        let base = proxy.base()?;
+        let worktree = proxy.into_repo_with_possibly_inaccessible_worktree()?;
+        linked.push(worktree_info(&worktree, &base)?);
+    }
+    linked.sort_by(|a, b| a.path.cmp(&b.path));
+    worktrees.extend(linked);
+    Ok(Some(worktrees))
+}
+
+fn worktree_info(repo: &gix::Repository, path: &Path) -> Result<WorktreeInfo, NativeError> {
+    let head = repo.head()?;
+    let branch = head
+        .referent_name()
+        .and_then(|name| name.as_bstr().strip_prefix(b"refs/heads/"))
+        .map(|branch| String::from_utf8_lossy(branch).into_owned());
+    let head_id = match head.id() {
+        Some(id) => id.to_string(),
+        // Unborn branch: git prints the null id.
+        None => repo.object_hash().null().to_string(),
+    };
+    Ok(WorktreeInfo {
+        path: path.to_string_lossy().into_owned(),
+        head: head_id,
+        branch,
+        is_bare: false,
+        is_main_worktree: false,
+    })
+}
+
+/// `git remote -v`: each remote once, sorted by name, with its fetch URL.
+pub(super) fn list_remotes(repo: &gix::Repository) -> NativeResult<Vec<RemoteInfo>> {
+    let mut remotes = Vec::new();
+    for name in repo.remote_names() {
+        let remote = repo.find_remote(name.as_ref())?;
+        let Some(url) = remote.url(gix::remote::Direction::Fetch) else {
+            continue;
+        };
+        remotes.push(RemoteInfo {
+            name: name.to_string(),
+            url: url.to_bstring().to_string(),
+        });
+    }
+    Ok(Some(remotes))
+}
+
+#[cfg(test)]
+mod tests {
+    //! Parity tests: every in-process answer must match the CLI's.
+
+    use super::*;
+    use std::fmt::Debug;
+    use tempfile::TempDir;
+
+    async fn git_ok(git: &Git, args: &[&str]) {
+        git.run(args).await.unwrap();
+    }
+
+    /// A clone with two remotes, several branches and two linked worktrees.
+    async fn fixture() -> (TempDir, Git) {
+        let dir = tempfile::tempdir().unwrap();
+        let upstream = Git::new(dir.path());
+        git_ok(&upstream, &["init", "-b", "main", "upstream"]).await;
+        let upstream = Git::new(dir.path().join("upstream"));
+        git_ok(&upstream, &["config", "user.email", "test@test.com"]).await;
+        git_ok(&upstream, &["config", "user.name", "Test"]).await;
+        tokio::fs::write(dir.path().join("upstream/a.txt"), "a")
+            .await
+            .unwrap();
+        git_ok(&upstream, &["add", "."]).await;
+        git_ok(&upstream, &["commit", "-m", "initial"]).await;
+        git_ok(&upstream, &["branch", "feature/remote-only"]).await;
+
+        let root = Git::new(dir.path());
+        git_ok(&root, &["clone", "-q", "upstream", "repo"]).await;
+        let git = Git::new(dir.path().join("repo"));
+        git_ok(&git, &["config", "user.email", "test@test.com"]).await;
+        git_ok(&git, &["config", "user.name", "Test"]).await;
+        git_ok(&git, &["branch", "zeta"]).await;
+        git_ok(&git, &["branch", "alpha"]).await;
+        git_ok(
+            &git,
+            &["remote", "add", "backup", "git@github.com:org/repo.git"],
+        )
+        .await;
+        let wt = dir.path().join("wt-zeta");
+        git_ok(
+            &git,
+            &["worktree", "add", "-q", wt.to_str().unwrap(), "zeta"],
+        )
+        .await;
+        let detached = dir.path().join("wt-detached");
+        git_ok(
+            &git,
+            &[
+                "worktree",
+                "add",
+                "-q",
+                "--detach",
+                detached.to_str().unwrap(),
+            ],
+        )
+        .await;
+        (dir, git)
+    }
+
+    fn native<T>(git: &Git, op: fn(&gix::Repository) -> NativeResult<T>) -> Option<T> {
+        op(&gix::discover(git.repo_path()).unwrap()).unwrap()
+    }
+
+    fn assert_same<T: serde::Serialize + Debug>(native: Option<T>, cli: T) {
+        let native = native.expect("in-process backend declined");
+        assert_eq!(
+            serde_json::to_value(&native).unwrap(),
+            serde_json::to_value(&cli).unwrap(),
+            "in-process {native:?} != CLI {cli:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_refs_and_worktrees_match_cli() {
+        let (dir, git) = fixture().await;
+        for git in [&git, &Git::new(dir.path().join("wt-zeta"))] {
+            assert_same(
+                native(git, current_branch),
+                git.current_branch_cli().await.unwrap(),
+            );
+            assert_same(
+                native(git, list_branches),
+                git.list_branches_cli().await.unwrap(),
+            );
+            assert_same(
+                native(git, worktree_list),
+                git.worktree_list_cli().await.unwrap(),
+            );
+            assert_same(
+                native(git, list_remotes),
+                git.list_remotes_cli().await.unwrap(),
+            );
+        }
+
+        // Detached HEAD: the short hash matches, branch listing defers to git.
+        let detached = Git::new(dir.path().join("wt-detached"));
+        assert_same(
+            native(&detached, current_branch),
+            detached.current_branch_cli().await.unwrap(),
+        );
+        assert!(native(&detached, list_branches).is_none());
+        assert_same(
+            native(&detached, worktree_list),
+            detached.worktree_list_cli().await.unwrap(),
+        );
+    }
+
+    #[tokio::test]
+    async fn test_uncommitted_count_matches_cli() {
+        let (dir, git) = fixture().await;
+        let repo = dir.path().join("repo");
+        assert_same(native(&git, uncommitted_count), 0);
+
+        // Modified, staged new file, untracked file, untracked directory.
+        tokio::fs::write(repo.join("a.txt"), "changed")
+            .await
+            .unwrap();
+        tokio::fs::write(repo.join("new.txt"), "new").await.unwrap();
+        git_ok(&git, &["add", "new.txt"]).await;
+        tokio::fs::write(repo.join("loose.txt"), "loose")
+            .await
+            .unwrap();
+        tokio::fs::create_dir_all(repo.join("untracked/deeper"))
+            .await
+            .unwrap();
+        tokio::fs::write(repo.join("untracked/one.txt"), "1")
+            .await
+            .unwrap();
+        tokio::fs::write(repo.join("untracked/deeper/two.txt"), "2")
+            .await
+            .unwrap();
+        assert_same(
+            native(&git, uncommitted_count),
+            git.uncommitted_count_cli().await.unwrap(),
+        );
+
+        // Staged rename and a deletion; touching an unchanged file is not a change.
+        git_ok(&git, &["commit", "-qam", "second"]).await;
+        git_ok(&git, &["mv", "a.txt", "renamed.txt"]).await;
+        git_ok(&git, &["rm", "-q", "new.txt"]).await;
+        assert_same(
+            native(&git, uncommitted_count),
+            git.uncommitted_count_cli().await.unwrap(),
+        );
+    }
+}
@@ -4,6 +4,11 @@ use std::path::Path;
 use super::error::GitError;
 use super::runner::Git;
 
+/// Upper bound for `fetch` and `fetch_all`. Generous because large
+/// repositories can take minutes; the caller can cancel sooner through the
+/// runner's operation.
+pub const FETCH_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(600);
+
 /// A local or remote branch returned by `list_branches`.
 ///
 /// Remote branches have `is_remote = true` and names like `origin/main`.
@@ -20,7 +25,7 @@ pub struct BranchInfo {
 ///
 /// `branch` is `None` for detached HEAD states or bare repositories.
 /// `head` contains the full commit SHA the worktree currently points to.
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct WorktreeInfo {
     pub path: String,
     pub head: String,
@@ -57,7 +62,7 @@ pub struct FileChange {
 }
 
 /// The type of change made to a file.
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 #[serde(rename_all = "lowercase")]
 pub enum FileChangeStatus {
     Added,
@@ -82,6 +87,33 @@ pub struct RemoteInfo {
     pub url: String,
 }
 
+/// Outcome of a successful `push`.
+#[derive(Debug, Clone, Serialize)]
+pub struct PushResult {
+    pub remote: String,
+    pub branch: String,
+    /// Whether `--set-upstream` was passed.
+    pub set_upstream: bool,
+    /// `true` when the remote already had every commit ("Everything up-to-date").
+    pub up_to_date: bool,
+}
+
+/// Outcome of a dry-run merge (`git merge-tree --write-tree`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MergeTreeResult {
+    /// Tree the merge would produce (with conflict markers in conflicted files).
+    pub tree: String,
+    /// Paths that would conflict. Empty for a clean merge.
+    pub conflicted_files: Vec<String>,
+}
+
+impl MergeTreeResult {
+    /// Whether the merge would complete without conflicts.
+    pub fn is_clean(&self) -> bool {
+        self.conflicted_files.is_empty()
+    }
+}
+
 impl Git {
     /// Lists all local and remote branches, excluding `HEAD` pointer entries.
     ///
@@ -89,6 +121,18 @@ impl Git {
     /// Any branch name containing "HEAD" (e.g. `origin/HEAD`) is skipped to
     /// avoid exposing symbolic refs that confuse branch selectors in the UI.
     pub async fn list_branches(&self) -> Result<Vec<BranchInfo>, GitError> {
+        #[cfg(feature = "gix-backend")]
+        if let Some(branches) = self
+            .native("list_branches", super::native::list_branches)
+            .await
+        {
+            return Ok(branches);
+        }
+        self.list_branches_cli().await
+    }
+
+    /// The `git` CLI path of [`Self::list_branches`].
+    pub(super) async fn list_branches_cli(&self) -> Result<Vec<BranchInfo>, GitError> {
         let output = self
             .run(&[
                 "branch",
@@ -112,9 +156,11 @@ impl Git {
                 continue;
             }
 
+            // `%(refname:rstrip=-2)` keeps the first two components, i.e.
+            // `refs/remotes` (not `remotes`) for remote-tracking branches.
             let is_remote = parts
                 .get(2)
-                .map(|r| r.trim() == "remotes")
+                .map(|r| r.trim() == "refs/remotes")
                 .unwrap_or(false);
 
             branches.push(BranchInfo {
@@ -131,6 +177,18 @@ impl Git {
     /// Uses `symbolic-ref` first; if that fails (detached HEAD), falls back to
     /// `rev-parse --short HEAD` so the caller always gets a usable label.
     pub async fn current_branch(&self) -> Result<String, GitError> {
+        #[cfg(feature = "gix-backend")]
+        if let Some(branch) = self
+            .native("current_branch", super::native::current_branch)
+            .await
+        {
+            return Ok(branch);
+        }
+        self.current_branch_cli().await
+    }
+
+    /// The `git` CLI path of [`Self::current_branch`].
+    pub(super) async fn current_branch_cli(&self) -> Result<String, GitError> {
         match self.run(&["symbolic-ref", "--short", "HEAD"]).await {
             Ok(output) => Ok(output.trimmed().to_string()),
             Err(GitError::CommandFailed { code, stderr, .. }) => {
@@ -157,6 +215,18 @@ impl Git {
     /// Counts non-empty lines from `git status --porcelain`. Each line represents
     /// one changed file, so the count reflects individual file changes.
     pub async fn uncommitted_count(&self) -> Result<usize, GitError> {
+        #[cfg(feature = "gix-backend")]
+        if let Some(count) = self
+            .native("uncommitted_count", super::native::uncommitted_count)
+            .await
+        {
+            return Ok(count);
+        }
+        self.uncommitted_count_cli().await
+    }
+
+    /// The `git` CLI path of [`Self::uncommitted_count`].
+    pub(super) async fn uncommitted_count_cli(&self) -> Result<usize, GitError> {
         let output = self.run(&["status", "--porcelain"]).await?;
         Ok(output.lines().len())
     }
@@ -166,6 +236,18 @@ impl Git {
     /// Porcelain format uses blank-line-separated stanzas with `worktree`, `HEAD`,
     /// `branch`, and `bare` fields. Detached worktrees will have `branch: None`.
     pub async fn worktree_list(&self) -> Result<Vec<WorktreeInfo>, GitError> {
+        #[cfg(feature = "gix-backend")]
+        if let Some(worktrees) = self
+            .native("worktree_list", super::native::worktree_list)
+            .await
+        {
+            return Ok(worktrees);
+        }
+        self.worktree_list_cli().await
+    }
+
+    /// The `git` CLI path of [`Self::worktree_list`].
+    pub(super) async fn worktree_list_cli(&self) -> Result<Vec<WorktreeInfo>, GitError> {
         let output = self.run(&["worktree", "list", "--porcelain"]).await?;
 
         let mut worktrees = Vec::new();
@@ -296,6 +378,17 @@ impl Git {
         })
     }
 
+    /// Creates a worktree at `path` with a detached `HEAD` at `commitish`.
+    ///
+    /// Unlike checking out a branch, this works even when the branch is
+    /// checked out elsewhere, which makes it suitable for scratch worktrees.
+    pub async fn worktree_add_detached(&self, path: &Path, commitish: &str) -> Result<(), GitError> {
+        let path_str = path.to_string_lossy();
+        self.run(&["worktree", "add", "--detach", &path_str, commitish])
+            .await?;
+        Ok(())
+    }
+
     /// Removes a worktree at the given path. Pass `force: true` to remove
     /// even if the worktree has uncommitted changes.
     pub async fn worktree_remove(&self, path: &Path, force: bool) -> Result<(), GitError> {
@@ -418,43 +511,7 @@ impl Git {
             .run(&["show", "--name-status", "--format=", hash])
             .await?;
 
-        let mut files = Vec::new();
-        for line in output.lines() {
-            let parts: Vec<&str> = line.split('\t').collect();
-            if parts.is_empty() {
-                continue;
-            }
-
-            let status_char = parts[0].chars().next().unwrap_or('?');
-            let (status, path, old_path) = match status_char {
-                'A' => (FileChangeStatus::Added, parts.get(1).unwrap_or(&"").to_string(), None),
-                'M' => (FileChangeStatus::Modified, parts.get(1).unwrap_or(&"").to_string(), None),
-                'D' => (FileChangeStatus::Deleted, parts.get(1).unwrap_or(&"").to_string(), None),
-                'R' => {
-                    // Renamed: R100\told_path\tnew_path
-                    let old = parts.get(1).map(|s| s.to_string());
-                    let new = parts.get(2).unwrap_or(&"").to_string();
-                    (FileChangeStatus::Renamed, new, old)
-                }
-                'C' => {
-                    // Copied: C100\told_path\tnew_path
-                    let old = parts.get(1).map(|s| s.to_string());
-                    let new = parts.get(2).unwrap_or(&"").to_string();
-                    (FileChangeStatus::Copied, new, old)
-                }
-                _ => (FileChangeStatus::Unknown, parts.get(1).unwrap_or(&"").to_string(), None),
-            };
-
-            if !path.is_empty() {
-                files.push(FileChange {
-                    path,
-                    status,
-                    old_path,
-                });
-            }
-        }
-
-        Ok(files)
+        Ok(parse_name_status(&output.lines()))
     }
 
     /// Gets the git user config (name and email) for this repository.
@@ -500,6 +557,18 @@ impl Git {
 
     /// Lists all configured remotes with their URLs.
     pub async fn list_remotes(&self) -> Result<Vec<RemoteInfo>, GitError> {
+        #[cfg(feature = "gix-backend")]
+        if let Some(remotes) = self
+            .native("list_remotes", super::native::list_remotes)
+            .await
+        {
+            return Ok(remotes);
+        }
+        self.list_remotes_cli().await
+    }
+
+    /// The `git` CLI path of [`Self::list_remotes`].
+    pub(super) async fn list_remotes_cli(&self) -> Result<Vec<RemoteInfo>, GitError> {
         let output = self.run(&["remote", "-v"]).await?;
 
         let mut remotes: Vec<RemoteInfo> = Vec::new();
@@ -609,11 +678,14 @@ impl Git {
     /// Fetches refs and objects from a specific remote.
     ///
     /// Uses `--prune` to remove stale remote-tracking branches that no longer
-    /// exist on the remote. Allows up to 120 seconds for large repositories.
+    /// exist on the remote. Progress is reported to the runner's operation,
+    /// which can also cancel the fetch; otherwise it may take up to
+    /// [`FETCH_TIMEOUT`] on large repositories.
     pub async fn fetch(&self, remote_name: &str) -> Result<(), GitError> {
-        self.run_with_timeout(
-            &["fetch", "--prune", remote_name],
-            std::time::Duration::from_secs(120),
+        self.run_streaming(
+            &["fetch", "--progress", "--prune", remote_name],
+            FETCH_TIMEOUT,
+            |_| {},
         )
         .await?;
         Ok(())
@@ -622,11 +694,12 @@ impl Git {
     /// Fetches refs and objects from all configured remotes.
     ///
     /// Uses `--all --prune` to update every remote and clean up stale
-    /// remote-tracking branches. Allows up to 120 seconds.
+    /// remote-tracking branches. Reports progress like [`Git::fetch`].
     pub async fn fetch_all(&self) -> Result<(), GitError> {
-        self.run_with_timeout(
-            &["fetch", "--all", "--prune"],
-            std::time::Duration::from_secs(120),
+        self.run_streaming(
+            &["fetch", "--progress", "--all", "--prune"],
+            FETCH_TIMEOUT,
+            |_| {},
         )
         .await?;
         Ok(())
@@ -675,6 +748,144 @@ impl Git {
         Ok(())
     }
 
+    /// Stages every change in the working tree, including deletions and
+    /// untracked files (`git add -A`).
+    pub async fn stage_all(&self) -> Result<(), GitError> {
+        self.run(&["add", "-A"]).await?;
+        Ok(())
+    }
+
+    /// Returns the files currently staged for commit.
+    pub async fn staged_files(&self) -> Result<Vec<FileChange>, GitError> {
+        let output = self
+            .run(&["diff", "--cached", "--name-status", "-M"])
+            .await?;
+        Ok(parse_name_status(&output.lines()))
+    }
+
+    /// Commits the staged changes and returns the new HEAD commit.
+    ///
+    /// Returns `NothingToCommit` when the index matches HEAD. Hook failures
+    /// surface as `CommandFailed` with the hook's stderr.
+    pub async fn commit(&self, message: &str) -> Result<CommitInfo, GitError> {
+        if self.staged_files().await?.is_empty() {
+            return Err(GitError::NothingToCommit);
+        }
+        self.run_with_timeout(&["commit", "-m", message], std::time::Duration::from_secs(120))
+            .await?;
+
+        self.commit_log(1, false)
+            .await?
+            .into_iter()
+            .next()
+            .ok_or_else(|| GitError::ParseError {
+                message: "commit succeeded but HEAD could not be read".to_string(),
+            })
+    }
+
+    /// Pushes `branch` to `remote`, forwarding git's progress output.
+    ///
+    /// With `set_upstream`, the remote branch becomes the local branch's
+    /// upstream. Credential and rejection failures are mapped to
+    /// `AuthenticationFailed` and `PushRejected`. Allows up to 5 minutes.
+    pub async fn push(
+        &self,
+        remote: &str,
+        branch: &str,
+        set_upstream: bool,
+        on_progress: impl FnMut(&str),
+    ) -> Result<PushResult, GitError> {
+        let mut args = vec!["push", "--progress"];
+        if set_upstream {
+            args.push("--set-upstream");
+        }
+        args.push(remote);
+        args.push(branch);
+
+        let output = self
+            .run_streaming(&args, std::time::Duration::from_secs(300), on_progress)
+            .await
+            .map_err(|e| classify_push_error(e, remote))?;
+
+        Ok(PushResult {
+            remote: remote.to_string(),
+            branch: branch.to_string(),
+            set_upstream,
+            up_to_date: output.stderr.contains("Everything up-to-date"),
+        })
+    }
+
+    /// Returns the branch the remote's `HEAD` points to (e.g. `main`), as
+    /// recorded by the last clone or `git remote set-head`.
+    ///
+    /// Returns `None` when the remote-tracking `HEAD` ref is not set.
+    pub async fn remote_default_branch(&self, remote: &str) -> Result<Option<String>, GitError> {
+        let head_ref = format!("refs/remotes/{remote}/HEAD");
+        match self.run(&["symbolic-ref", "--short", &head_ref]).await {
+            Ok(output) => {
+                let prefix = format!("{remote}/");
+                let name = output.trimmed();
+                Ok(Some(name.strip_prefix(&prefix).unwrap_or(name).to_string()))
+            }
+            Err(GitError::CommandFailed { .. }) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Merges two commits in memory without touching any working tree, index
+    /// or ref, and reports which files would conflict.
+    ///
+    /// Requires git 2.38+ (`merge-tree --write-tree`). Exit code 1 means the
+    /// merge has conflicts and is not treated as an error.
+    pub async fn merge_tree(&self, ours: &str, theirs: &str) -> Result<MergeTreeResult, GitError> {
+        let output = self
+            .run_accepting(
+                &["merge-tree", "--write-tree", "--name-only", "--no-messages", "-z", ours, theirs],
+                &[1],
+            )
+            .await?;
+
+        let mut fields = output.stdout.split('\0').filter(|f| !f.is_empty());
+        let tree = fields.next().unwrap_or_default().trim().to_string();
+        let mut conflicted_files: Vec<String> = Vec::new();
+        for path in fields {
+            // A path is listed once per conflicting stage; keep it once
+            if !conflicted_files.iter().any(|p| p == path) {
+                conflicted_files.push(path.to_string());
+            }
+        }
+        Ok(MergeTreeResult {
+            tree,
+            conflicted_files,
+        })
+    }
+
+    /// Lists the paths that differ between two commits.
+    pub async fn changed_paths(&self, from: &str, to: &str) -> Result<Vec<String>, GitError> {
+        let output = self
+            .run(&["diff", "--name-only", "--no-renames", "-z", from, to])
+            .await?;
+        Ok(output
+            .stdout
+            .split('\0')
+            .filter(|p| !p.is_empty())
+            .map(str::to_string)
+            .collect())
+    }
+
+    /// Whether `ancestor` is reachable from `descendant` (e.g. whether a
+    /// branch has been merged into another).
+    pub async fn is_ancestor(&self, ancestor: &str, descendant: &str) -> Result<bool, GitError> {
+        match self
+            .run(&["merge-base", "--is-ancestor", ancestor, descendant])
+            .await
+        {
+            Ok(_) => Ok(true),
+            Err(GitError::CommandFailed { code: 1, .. }) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
     /// Checks whether the repository path is a git worktree (not the main working tree).
     ///
     /// Compares `git rev-parse --git-dir` with `git rev-parse --git-common-dir`.
@@ -696,6 +907,96 @@ impl Git {
     }
 }
 
+/// Parses `--name-status` lines (`M\tpath`, `R100\told\tnew`, ...) into
+/// file changes. Lines without a path are skipped.
+pub(super) fn parse_name_status(lines: &[&str]) -> Vec<FileChange> {
+    let mut files = Vec::new();
+    for line in lines {
+        let parts: Vec<&str> = line.split('\t').collect();
+        if parts.is_empty() {
+            continue;
+        }
+
+        let status_char = parts[0].chars().next().unwrap_or('?');
+        let (status, path, old_path) = match status_char {
+            'A' => (FileChangeStatus::Added, parts.get(1).unwrap_or(&"").to_string(), None),
+            'M' => (FileChangeStatus::Modified, parts.get(1).unwrap_or(&"").to_string(), None),
+            'D' => (FileChangeStatus::Deleted, parts.get(1).unwrap_or(&"").to_string(), None),
+            'R' => {
+                // Renamed: R100\told_path\tnew_path
+                let old = parts.get(1).map(|s| s.to_string());
+                let new = parts.get(2).unwrap_or(&"").to_string();
+                (FileChangeStatus::Renamed, new, old)
+            }
+            'C' => {
+                // Copied: C100\told_path\tnew_path
+                let old = parts.get(1).map(|s| s.to_string());
+                let new = parts.get(2).unwrap_or(&"").to_string();
+                (FileChangeStatus::Copied, new, old)
+            }
+            _ => (FileChangeStatus::Unknown, parts.get(1).unwrap_or(&"").to_string(), None),
+        };
+
+        if !path.is_empty() {
+            files.push(FileChange {
+                path,
+                status,
+                old_path,
+            });
+        }
+    }
+
+    files
+}
+
+/// Maps a failed `git push` onto `AuthenticationFailed` / `PushRejected`
+/// based on the messages git and common hosts print. Other errors pass
+/// through unchanged.
+fn classify_push_error(err: GitError, remote: &str) -> GitError {
+    let GitError::CommandFailed { ref stderr, .. } = err else {
+        return err;
+    };
+    let lower = stderr.to_lowercase();
+    let message = stderr
+        .lines()
+        .rev()
+        .find(|l| l.contains("error:") || l.contains("fatal:") || l.contains("remote:"))
+        .unwrap_or(stderr)
+        .trim()
+        .to_string();
+
+    const AUTH_PATTERNS: &[&str] = &[
+        "authentication failed",
+        "permission denied (publickey",
+        "could not read username",
+        "could not read password",
+        "terminal prompts disabled",
+        "invalid username or password",
+        "the requested url returned error: 403",
+        "the requested url returned error: 401",
+    ];
+    const REJECT_PATTERNS: &[&str] = &[
+        "[rejected]",
+        "[remote rejected]",
+        "non-fast-forward",
+        "failed to push some refs",
+    ];
+
+    if AUTH_PATTERNS.iter().any(|p| lower.contains(p)) {
+        GitError::AuthenticationFailed {
+            remote: remote.to_string(),
+            message,
+        }
+    } else if REJECT_PATTERNS.iter().any(|p| lower.contains(p)) {
+        GitError::PushRejected {
+            remote: remote.to_string(),
+            message,
+        }
+    } else {
+        err
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -851,6 +1152,28 @@ mod tests {
         assert!(local_names.contains(&"local-test"));
     }
 
+    #[tokio::test]
+    async fn test_list_branches_marks_remote_tracking_branches() {
+        let (dir, git) = create_test_repo().await;
+        let clone_path = dir.path().join("clone");
+        git.run(&["clone", "-q", ".", clone_path.to_str().unwrap()])
+            .await
+            .unwrap();
+
+        // The CLI path directly, so the gix backend can't mask it
+        let main = git.current_branch().await.unwrap();
+        let branches = Git::new(&clone_path).list_branches_cli().await.unwrap();
+        let remote: Vec<&str> = branches
+            .iter()
+            .filter(|b| b.is_remote)
+            .map(|b| b.name.as_str())
+            .collect();
+        assert_eq!(remote, vec![format!("origin/{main}")]);
+        assert!(branches
+            .iter()
+            .any(|b| b.name == main && !b.is_remote && b.is_current));
+    }
+
     #[tokio::test]
     async fn test_create_branch_from_head() {
         let (_dir, git) = create_test_repo().await;
@@ -884,4 +1207,180 @@ mod tests {
             current
         );
     }
+
+    #[tokio::test]
+    async fn test_stage_all_and_commit() {
+        let (dir, git) = create_test_repo().await;
+        tokio::fs::write(dir.path().join("new.txt"), "hello").await.unwrap();
+        tokio::fs::write(dir.path().join("README.md"), "# Changed").await.unwrap();
+
+        git.stage_all().await.unwrap();
+        let staged = git.staged_files().await.unwrap();
+        assert_eq!(staged.len(), 2);
+        assert!(staged
+            .iter()
+            .any(|f| f.path == "new.txt" && matches!(f.status, FileChangeStatus::Added)));
+
+        let commit = git.commit("Add new.txt\n\nWith a body").await.unwrap();
+        assert_eq!(commit.summary, "Add new.txt");
+        assert_eq!(commit.parent_hashes.len(), 1);
+        assert!(git.staged_files().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_commit_with_nothing_staged() {
+        let (_dir, git) = create_test_repo().await;
+        let result = git.commit("empty").await;
+        assert!(matches!(result, Err(GitError::NothingToCommit)));
+    }
+
+    #[tokio::test]
+    async fn test_push_sets_upstream_and_reports_progress() {
+        let (dir, git) = create_test_repo().await;
+        let remote_dir = tempdir().unwrap();
+        Git::new(remote_dir.path()).run(&["init", "--bare"]).await.unwrap();
+        git.add_remote("origin", &remote_dir.path().to_string_lossy())
+            .await
+            .unwrap();
+        git.run(&["checkout", "-b", "feature"]).await.unwrap();
+        tokio::fs::write(dir.path().join("f.txt"), "x").await.unwrap();
+        git.stage_all().await.unwrap();
+        git.commit("feature work").await.unwrap();
+
+        let mut progress = Vec::new();
+        let result = git
+            .push("origin", "feature", true, |line| progress.push(line.to_string()))
+            .await
+            .unwrap();
+        assert!(result.set_upstream);
+        assert!(!result.up_to_date);
+        assert!(!progress.is_empty(), "push should report progress lines");
+
+        let upstream = git
+            .run(&["rev-parse", "--abbrev-ref", "feature@{upstream}"])
+            .await
+            .unwrap();
+        assert_eq!(upstream.trimmed(), "origin/feature");
+
+        let again = git.push("origin", "feature", false, |_| {}).await.unwrap();
+        assert!(again.up_to_date);
+    }
+
+    #[tokio::test]
+    async fn test_push_rejected_non_fast_forward() {
+        let (dir, git) = create_test_repo().await;
+        let remote_dir = tempdir().unwrap();
+        Git::new(remote_dir.path()).run(&["init", "--bare"]).await.unwrap();
+        git.add_remote("origin", &remote_dir.path().to_string_lossy())
+            .await
+            .unwrap();
+        git.run(&["checkout", "-b", "feature"]).await.unwrap();
+        tokio::fs::write(dir.path().join("f.txt"), "1").await.unwrap();
+        git.stage_all().await.unwrap();
+        git.commit("one").await.unwrap();
+        git.push("origin", "feature", true, |_| {}).await.unwrap();
+
+        // Rewrite history locally so the next push is non-fast-forward
+        git.run(&["reset", "--hard", "HEAD~1"]).await.unwrap();
+        tokio::fs::write(dir.path().join("g.txt"), "2").await.unwrap();
+        git.stage_all().await.unwrap();
+        git.commit("two").await.unwrap();
+
+        let result = git.push("origin", "feature", false, |_| {}).await;
+        assert!(
+            matches!(result, Err(GitError::PushRejected { ref remote, .. }) if remote == "origin"),
+            "got {:?}",
+            result
+        );
+    }
+
+    #[tokio::test]
+    async fn test_merge_tree_reports_conflicts() {
+        let (dir, git) = create_test_repo().await;
+        let base = git.run(&["rev-parse", "HEAD"]).await.unwrap().trimmed().to_string();
+
+        git.run(&["checkout", "-b", "one"]).await.unwrap();
+        tokio::fs::write(dir.path().join("README.md"), "# One").await.unwrap();
+        git.run(&["commit", "-am", "one"]).await.unwrap();
+
+        git.run(&["checkout", "-b", "two", &base]).await.unwrap();
+        tokio::fs::write(dir.path().join("README.md"), "# Two").await.unwrap();
+        tokio::fs::write(dir.path().join("other.txt"), "x").await.unwrap();
+        git.run(&["add", "."]).await.unwrap();
+        git.run(&["commit", "-m", "two"]).await.unwrap();
+
+        let result = git.merge_tree("one", "two").await.unwrap();
+        assert!(!result.is_clean());
+        assert_eq!(result.conflicted_files, vec!["README.md"]);
+        assert!(!result.tree.is_empty());
+
+        let clean = git.merge_tree(&base, "two").await.unwrap();
+        assert!(clean.is_clean());
+
+        let mut changed = git.changed_paths(&base, "two").await.unwrap();
+        changed.sort();
+        assert_eq!(changed, vec!["README.md", "other.txt"]);
+    }
+
+    #[tokio::test]
+    async fn test_is_ancestor() {
+        let (dir, git) = create_test_repo().await;
+        let base = git.run(&["rev-parse", "HEAD"]).await.unwrap().trimmed().to_string();
+        git.run(&["checkout", "-b", "feature"]).await.unwrap();
+        tokio::fs::write(dir.path().join("README.md"), "# Feature").await.unwrap();
+        git.run(&["commit", "-am", "feature"]).await.unwrap();
+
+        assert!(git.is_ancestor(&base, "feature").await.unwrap());
+        assert!(!git.is_ancestor("feature", &base).await.unwrap());
+        assert!(git.is_ancestor("no-such-ref", "feature").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_remote_default_branch_unset() {
+        let (_dir, git) = create_test_repo().await;
+        assert_eq!(git.remote_default_branch("origin").await.unwrap(), None);
+    }
+
+    #[test]
+    fn test_classify_push_error_auth() {
+        let err = GitError::CommandFailed {
+            code: 128,
+            stderr: "remote: Invalid username or password.\nfatal: Authentication failed for 'https://github.com/a/b.git/'".to_string(),
+            command: "git push".to_string(),
+        };
+        match classify_push_error(err, "origin") {
+            GitError::AuthenticationFailed { remote, message } => {
+                assert_eq!(remote, "origin");
+                assert!(message.starts_with("fatal: Authentication failed"));
+            }
+            other => panic!("expected AuthenticationFailed, got {:?}", other),
+        }
+
+        let ssh = GitError::CommandFailed {
+            code: 128,
+            stderr: "git@github.com: Permission denied (publickey).\nfatal: Could not read from remote repository.".to_string(),
+            command: "git push".to_string(),
+        };
+        assert!(matches!(
+            classify_push_error(ssh, "origin"),
+            GitError::AuthenticationFailed { .. }
+        ));
+    }
+
+    #[test]
+    fn test_classify_push_error_passthrough() {
+        let err = GitError::CommandFailed {
+            code: 128,
+            stderr: "fatal: 'nope' does not appear to be a git repository".to_string(),
+            command: "git push".to_string(),
+        };
+        assert!(matches!(
+            classify_push_error(err, "nope"),
+            GitError::CommandFailed { .. }
+        ));
+        assert!(matches!(
+            classify_push_error(GitError::GitNotFound, "origin"),
+            GitError::GitNotFound
+        ));
+    }
 }
@@ -0,0 +1,252 @@
+//! Progress reporting and cancellation for long-running git commands.
+//!
+//! A [`GitOperation`] is attached to a runner with
+//! [`Git::with_operation`](super::runner::Git::with_operation). Every command
+//! run through that runner can then be cancelled with the operation's
+//! [`CancelToken`], which kills the child process, and commands run through
+//! `run_streaming` report their `--progress` output as [`GitProgress`]
+//! updates tagged with the operation ID.
+
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use serde::Serialize;
+use tokio::sync::Notify;
+
+/// Callback invoked with each parsed progress update.
+pub type ProgressFn = Arc<dyn Fn(&GitProgress) + Send + Sync>;
+
+/// A progress update parsed from one of git's progress meters, e.g.
+/// `Receiving objects:  45% (450/1000), 1.20 MiB | 2.00 MiB/s`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct GitProgress {
+    pub operation_id: String,
+    /// The meter's title, e.g. `Receiving objects` or `Resolving deltas`.
+    pub phase: String,
+    /// Whether the meter came from the remote (`remote: ` prefix).
+    pub remote: bool,
+    pub percent: Option<u8>,
+    /// Items processed so far (objects, deltas, files).
+    pub current: Option<u64>,
+    pub total: Option<u64>,
+    /// Transfer size and rate, e.g. `1.20 MiB | 2.00 MiB/s`.
+    pub throughput: Option<String>,
+    /// The phase has finished.
+    pub done: bool,
+}
+
+/// Parses a progress meter line. Returns `None` for other output such as
+/// `From github.com:org/repo` or ref update lines.
+pub fn parse_progress(operation_id: &str, line: &str) -> Option<GitProgress> {
+    let line = line.trim();
+    let (remote, line) = match line.strip_prefix("remote:") {
+        Some(rest) => (true, rest.trim_start()),
+        None => (false, line),
+    };
+    let (phase, rest) = line.split_once(": ")?;
+    if phase.is_empty() || !phase.chars().all(|c| c.is_ascii_alphabetic() || c == ' ') {
+        return None;
+    }
+    let rest = rest.trim();
+
+    let done = rest.ends_with("done.");
+    let rest = rest
+        .trim_end_matches("done.")
+        .trim_end()
+        .trim_end_matches(',');
+    let (counts, throughput) = match rest.split_once(", ") {
+        Some((counts, throughput)) => (counts, Some(throughput.trim().to_string())),
+        None => (rest, None),
+    };
+
+    let mut progress = GitProgress {
+        operation_id: operation_id.to_string(),
+        phase: phase.to_string(),
+        remote,
+        percent: None,
+        current: None,
+        total: None,
+        throughput: throughput.filter(|t| !t.is_empty()),
+        done,
+    };
+
+    if let Some((percent, ratio)) = counts.split_once('%') {
+        // `45% (450/1000)`
+        progress.percent = Some(percent.trim().parse().ok()?);
+        let ratio = ratio.trim().trim_start_matches('(').trim_end_matches(')');
+        if let Some((current, total)) = ratio.split_once('/') {
+            progress.current = current.trim().parse().ok();
+            progress.total = total.trim().parse().ok();
+        }
+    } else {
+        // A meter without a known total: `Enumerating objects: 1234`
+        progress.current = Some(counts.trim().parse().ok()?);
+    }
+    Some(progress)
+}
+
+/// Signals cancellation to a running operation. Clones share the same
+/// state.
+#[derive(Clone, Default)]
+pub struct CancelToken {
+    inner: Arc<CancelState>,
+}
+
+#[derive(Default)]
+struct CancelState {
+    cancelled: AtomicBool,
+    notify: Notify,
+}
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancels the operation. Running and future commands fail with
+    /// `GitError::Cancelled`.
+    pub fn cancel(&self) {
+        self.inner.cancelled.store(true, Ordering::SeqCst);
+        self.inner.notify.notify_waiters();
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Completes once [`cancel`](Self::cancel) has been called.
+    pub async fn cancelled(&self) {
+        loop {
+            // Register before checking the flag so a concurrent `cancel`
+            // cannot slip in between.
+            let notified = self.inner.notify.notified();
+            if self.is_cancelled() {
+                return;
+            }
+            notified.await;
+        }
+    }
+}
+
+impl fmt::Debug for CancelToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CancelToken")
+            .field("cancelled", &self.is_cancelled())
+            .finish()
+    }
+}
+
+/// A cancellable git operation that reports progress under an ID.
+#[derive(Clone)]
+pub struct GitOperation {
+    id: String,
+    cancel: CancelToken,
+    on_progress: Option<ProgressFn>,
+}
+
+impl GitOperation {
+    pub fn new(id: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            cancel: CancelToken::new(),
+            on_progress: None,
+        }
+    }
+
+    /// Sends parsed progress updates to `on_progress`.
+    pub fn with_progress(mut self, on_progress: ProgressFn) -> Self {
+        self.on_progress = Some(on_progress);
+        self
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn cancel_token(&self) -> &CancelToken {
+        &self.cancel
+    }
+
+    /// Parses a line of stderr and forwards it if it is a progress meter.
+    pub(crate) fn report(&self, line: &str) {
+        if let Some(ref on_progress) = self.on_progress {
+            if let Some(progress) = parse_progress(&self.id, line) {
+                on_progress(&progress);
+            }
+        }
+    }
+}
+
+impl fmt::Debug for GitOperation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GitOperation")
+            .field("id", &self.id)
+            .field("cancel", &self.cancel)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_progress_with_totals_and_throughput() {
+        let p = parse_progress(
+            "op",
+            "Receiving objects:  45% (450/1000), 1.20 MiB | 2.00 MiB/s",
+        )
+        .unwrap();
+        assert_eq!(p.operation_id, "op");
+        assert_eq!(p.phase, "Receiving objects");
+        assert!(!p.remote);
+        assert_eq!(p.percent, Some(45));
+        assert_eq!((p.current, p.total), (Some(450), Some(1000)));
+        assert_eq!(p.throughput.as_deref(), Some("1.20 MiB | 2.00 MiB/s"));
+        assert!(!p.done);
+    }
+
+    #[test]
+    fn test_parse_progress_done_and_remote() {
+        let p = parse_progress("op", "Resolving deltas: 100% (30/30), done.").unwrap();
+        assert_eq!((p.percent, p.total, p.done), (Some(100), Some(30), true));
+        assert_eq!(p.throughput, None);
+
+        let p = parse_progress("op", "remote: Counting objects: 100% (10/10), done.").unwrap();
+        assert!(p.remote);
+        assert_eq!(p.phase, "Counting objects");
+
+        let p = parse_progress("op", "remote: Enumerating objects: 5, done.").unwrap();
+        assert_eq!((p.current, p.percent, p.done), (Some(5), None, true));
+
+        let p = parse_progress("op", "Enumerating objects: 1234").unwrap();
+        assert_eq!((p.current, p.done), (Some(1234), false));
+    }
+
+    #[test]
+    fn test_parse_progress_ignores_other_output() {
+        assert_eq!(parse_progress("op", "From github.com:org/repo"), None);
+        assert_eq!(
+            parse_progress("op", " * [new branch]      main -> origin/main"),
+            None
+        );
+        assert_eq!(parse_progress("op", "fatal: repository not found"), None);
+        assert_eq!(parse_progress("op", "Cloning into 'repo'..."), None);
+    }
+
+    #[tokio::test]
+    async fn test_cancel_token_wakes_waiters() {
+        let token = CancelToken::new();
+        let waiter = {
+            let token = token.clone();
+            tokio::spawn(async move { token.cancelled().await })
+        };
+        tokio::task::yield_now().await;
+        assert!(!token.is_cancelled());
+        token.cancel();
+        waiter.await.unwrap();
+        // Already cancelled: completes immediately.
+        token.cancelled().await;
+    }
+}
@@ -1,9 +1,12 @@
 use std::path::{Path, PathBuf};
+use std::process::Stdio;
 use std::sync::OnceLock;
+use tokio::io::AsyncReadExt;
 use tokio::process::Command;
 use tokio::time::{timeout, Duration};
 
 use super::error::GitError;
+use super::progress::GitOperation;
 use crate::core::windows_process::TokioCommandExt;
 
 /// Resolves the `SSH_AUTH_SOCK` path for SSH-based git operations.
@@ -137,6 +140,7 @@ impl GitOutput {
 #[derive(Debug, Clone)]
 pub struct Git {
     repo_path: PathBuf,
+    operation: Option<GitOperation>,
 }
 
 impl Git {
@@ -144,9 +148,23 @@ impl Git {
     pub fn new(repo_path: impl Into<PathBuf>) -> Self {
         Self {
             repo_path: repo_path.into(),
+            operation: None,
         }
     }
 
+    /// Runs every command of this runner as part of `operation`: cancelling
+    /// the operation kills the running command, and `run_streaming` reports
+    /// progress to it.
+    pub fn with_operation(mut self, operation: GitOperation) -> Self {
+        self.operation = Some(operation);
+        self
+    }
+
+    /// Returns the repository directory this runner targets.
+    pub fn repo_path(&self) -> &Path {
+        &self.repo_path
+    }
+
     /// Executes a git subcommand with the default 30-second timeout.
     ///
     /// Returns `GitNotFound` if the git binary is missing, `SpawnError` for
@@ -163,12 +181,237 @@ impl Git {
         args: &[&str],
         timeout_duration: Duration,
     ) -> Result<GitOutput, GitError> {
+        self.execute(args, timeout_duration, &[], &[], false).await
+    }
+
+    /// Like `run`, but decodes output lossily instead of failing on invalid
+    /// UTF-8, for commands that print file contents or paths in arbitrary
+    /// encodings (e.g. `git diff`).
+    pub async fn run_lossy(&self, args: &[&str]) -> Result<GitOutput, GitError> {
+        self.execute(args, Duration::from_secs(30), &[], &[], true)
+            .await
+    }
+
+    /// Like `run`, but treats the listed non-zero exit codes as success.
+    ///
+    /// Some subcommands report their result through the exit code while
+    /// still printing useful output (e.g. `git merge-tree --write-tree`
+    /// exits 1 when the merge has conflicts).
+    pub async fn run_accepting(
+        &self,
+        args: &[&str],
+        accepted_exit_codes: &[i32],
+    ) -> Result<GitOutput, GitError> {
+        self.execute(args, Duration::from_secs(30), accepted_exit_codes, &[], false)
+            .await
+    }
+
+    /// Like `run`, but with extra environment variables (e.g.
+    /// `GIT_INDEX_FILE` to work on a scratch index).
+    pub async fn run_with_env(
+        &self,
+        args: &[&str],
+        envs: &[(&str, &str)],
+    ) -> Result<GitOutput, GitError> {
+        self.execute(args, Duration::from_secs(30), &[], envs, false)
+            .await
+    }
+
+    async fn execute(
+        &self,
+        args: &[&str],
+        timeout_duration: Duration,
+        accepted_exit_codes: &[i32],
+        envs: &[(&str, &str)],
+        lossy: bool,
+    ) -> Result<GitOutput, GitError> {
+        let mut cmd = self.command(args);
+        cmd.envs(envs.iter().copied());
+        let command_str = format!("git -C {} {}", self.repo_path.display(), args.join(" "));
+        let timeout_secs = timeout_duration.as_secs();
+
+        // Dropping the output future kills the child (`kill_on_drop`).
+        let output = self
+            .cancellable(&command_str, timeout(timeout_duration, cmd.output()))
+            .await?
+            .map_err(|_| GitError::CommandFailed {
+                code: -1,
+                stderr: format!("Command timed out after {timeout_secs}s: {command_str}"),
+                command: command_str.clone(),
+            })?
+            .map_err(|source| {
+                if source.kind() == std::io::ErrorKind::NotFound {
+                    GitError::GitNotFound
+                } else {
+                    GitError::SpawnError {
+                        source,
+                        command: command_str.clone(),
+                    }
+                }
+            })?;
+
+        let (stdout, stderr) = if lossy {
+            (
+                String::from_utf8_lossy(&output.stdout).into_owned(),
+                String::from_utf8_lossy(&output.stderr).into_owned(),
+            )
+        } else {
+            (
+                String::from_utf8(output.stdout)?,
+                String::from_utf8(output.stderr)?,
+            )
+        };
+
+        let accepted = output
+            .status
+            .code()
+            .is_some_and(|code| accepted_exit_codes.contains(&code));
+        if output.status.success() || accepted {
+            Ok(GitOutput { stdout, stderr })
+        } else {
+            Err(GitError::CommandFailed {
+                code: output.status.code().unwrap_or(-1),
+                stderr: stderr.trim().to_string(),
+                command: command_str,
+            })
+        }
+    }
+
+    /// Like `run_with_timeout`, but forwards stderr to `on_progress` line by
+    /// line while the command runs.
+    ///
+    /// Git writes progress meters (`--progress`) to stderr and redraws them
+    /// with carriage returns, so both `\r` and `\n` end a line. The full
+    /// stderr is still returned in `GitOutput` / `CommandFailed`.
+    pub async fn run_streaming(
+        &self,
+        args: &[&str],
+        timeout_duration: Duration,
+        mut on_progress: impl FnMut(&str),
+    ) -> Result<GitOutput, GitError> {
+        let mut cmd = self.command(args);
+        cmd.stdout(Stdio::piped()).stderr(Stdio::piped());
+
+        let command_str = format!("git -C {} {}", self.repo_path.display(), args.join(" "));
+        let timeout_secs = timeout_duration.as_secs();
+
+        let mut child = cmd.spawn().map_err(|source| {
+            if source.kind() == std::io::ErrorKind::NotFound {
+                GitError::GitNotFound
+            } else {
+                GitError::SpawnError {
+                    source,
+                    command: command_str.clone(),
+                }
+            }
+        })?;
+        let mut stdout_pipe = child.stdout.take().expect("stdout is piped");
+        let mut stderr_pipe = child.stderr.take().expect("stderr is piped");
+        let mut report = |line: &str| {
+            if let Some(ref operation) = self.operation {
+                operation.report(line);
+            }
+            on_progress(line);
+        };
+
+        let work = async {
+            // Drain stdout concurrently so a full pipe can't stall git
+            let stdout_task = async {
+                let mut buf = Vec::new();
+                stdout_pipe.read_to_end(&mut buf).await.map(|_| buf)
+            };
+            let stderr_task = async {
+                let mut all = Vec::new();
+                let mut line = Vec::new();
+                let mut chunk = [0u8; 1024];
+                loop {
+                    let n = stderr_pipe.read(&mut chunk).await?;
+                    if n == 0 {
+                        break;
+                    }
+                    for &byte in &chunk[..n] {
+                        all.push(byte);
+                        if byte == b'\r' || byte == b'\n' {
+                            if !line.is_empty() {
+                                report(String::from_utf8_lossy(&line).trim_end());
+                                line.clear();
+                            }
+                        } else {
+                            line.push(byte);
+                        }
+                    }
+                }
+                if !line.is_empty() {
+                    report(String::from_utf8_lossy(&line).trim_end());
+                }
+                Ok::<_, std::io::Error>(all)
+            };
+            let (stdout, stderr) = tokio::try_join!(stdout_task, stderr_task)?;
+            let status = child.wait().await?;
+            Ok::<_, std::io::Error>((status, stdout, stderr))
+        };
+
+        let (status, stdout, stderr) = self
+            .cancellable(&command_str, timeout(timeout_duration, work))
+            .await?
+            .map_err(|_| GitError::CommandFailed {
+                code: -1,
+                stderr: format!("Command timed out after {timeout_secs}s: {command_str}"),
+                command: command_str.clone(),
+            })?
+            .map_err(|source| GitError::SpawnError {
+                source,
+                command: command_str.clone(),
+            })?;
+
+        let stdout = String::from_utf8(stdout)?;
+        let stderr = String::from_utf8(stderr)?;
+
+        if status.success() {
+            Ok(GitOutput { stdout, stderr })
+        } else {
+            Err(GitError::CommandFailed {
+                code: status.code().unwrap_or(-1),
+                stderr: stderr.trim().to_string(),
+                command: command_str,
+            })
+        }
+    }
+
+    /// Awaits `work` unless the runner's operation is cancelled first, in
+    /// which case `work` (and with it the child process) is dropped.
+    async fn cancellable<T>(
+        &self,
+        command_str: &str,
+        work: impl std::future::Future<Output = T>,
+    ) -> Result<T, GitError> {
+        let Some(ref operation) = self.operation else {
+            return Ok(work.await);
+        };
+        let cancelled = || GitError::Cancelled {
+            command: command_str.to_string(),
+        };
+        if operation.cancel_token().is_cancelled() {
+            return Err(cancelled());
+        }
+        tokio::select! {
+            result = work => Ok(result),
+            _ = operation.cancel_token().cancelled() => Err(cancelled()),
+        }
+    }
+
+    /// Builds a `git -C <repo>` command with the non-interactive environment
+    /// shared by `run_with_timeout` and `run_streaming`.
+    fn command(&self, args: &[&str]) -> Command {
         let mut cmd = Command::new("git");
         cmd.arg("-C")
             .arg(&self.repo_path)
             .args(args)
             .env("GIT_TERMINAL_PROMPT", "0")
             .env("LC_ALL", "C")
+            // Read-only commands like `status` otherwise refresh and rewrite
+            // the index, which the RepoWatcher would see as a new change.
+            .env("GIT_OPTIONAL_LOCKS", "0")
             .kill_on_drop(true)
             .hide_console_window();
 
@@ -200,39 +443,7 @@ impl Git {
             cmd.env("GIT_SSH_COMMAND", &ssh_opts);
         }
 
-        let command_str = format!("git -C {} {}", self.repo_path.display(), args.join(" "));
-        let timeout_secs = timeout_duration.as_secs();
-
-        let output = timeout(timeout_duration, cmd.output())
-            .await
-            .map_err(|_| GitError::CommandFailed {
-                code: -1,
-                stderr: format!("Command timed out after {timeout_secs}s: {command_str}"),
-                command: command_str.clone(),
-            })?
-            .map_err(|source| {
-                if source.kind() == std::io::ErrorKind::NotFound {
-                    GitError::GitNotFound
-                } else {
-                    GitError::SpawnError {
-                        source,
-                        command: command_str.clone(),
-                    }
-                }
-            })?;
-
-        let stdout = String::from_utf8(output.stdout)?;
-        let stderr = String::from_utf8(output.stderr)?;
-
-        if output.status.success() {
-            Ok(GitOutput { stdout, stderr })
-        } else {
-            Err(GitError::CommandFailed {
-                code: output.status.code().unwrap_or(-1),
-                stderr: stderr.trim().to_string(),
-                command: command_str,
-            })
-        }
+        cmd
     }
 
     /// Convenience wrapper that runs a git command in a different directory
@@ -314,6 +525,105 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_run_streaming_forwards_stderr_lines() {
+        let git = Git::new(".");
+        let mut lines = Vec::new();
+        let result = git
+            .run_streaming(&["rev-parse", "--verify", "no-such-ref-xyz"], Duration::from_secs(10), |line| {
+                lines.push(line.to_string())
+            })
+            .await;
+
+        assert!(matches!(result, Err(GitError::CommandFailed { .. })));
+        assert!(
+            lines.iter().any(|l| l.contains("fatal")),
+            "stderr should be streamed, got {:?}",
+            lines
+        );
+    }
+
+    #[tokio::test]
+    async fn test_run_streaming_returns_stdout() {
+        let git = Git::new(".");
+        let output = git
+            .run_streaming(&["--version"], Duration::from_secs(10), |_| {})
+            .await
+            .unwrap();
+        assert!(output.stdout.contains("git version"));
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_operation_kills_command() {
+        let operation = GitOperation::new("op");
+        let git = Git::new(".").with_operation(operation.clone());
+        let cancel = operation.cancel_token().clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            cancel.cancel();
+        });
+
+        let started = std::time::Instant::now();
+        let err = git
+            .run(&["-c", "alias.nap=!sleep 30", "nap"])
+            .await
+            .unwrap_err();
+        assert!(matches!(err, GitError::Cancelled { .. }), "{err:?}");
+        assert!(started.elapsed() < Duration::from_secs(10));
+
+        // Further commands fail straight away.
+        let err = git
+            .run_streaming(&["--version"], Duration::from_secs(10), |_| {})
+            .await
+            .unwrap_err();
+        assert!(matches!(err, GitError::Cancelled { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_run_streaming_reports_progress_to_operation() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("source");
+        let git = Git::new(dir.path());
+        git.run(&["init", "-b", "main", source.to_str().unwrap()])
+            .await
+            .unwrap();
+        let src = Git::new(&source);
+        src.run(&["config", "user.email", "test@test.com"])
+            .await
+            .unwrap();
+        src.run(&["config", "user.name", "Test"]).await.unwrap();
+        tokio::fs::write(source.join("file.txt"), "content")
+            .await
+            .unwrap();
+        src.run(&["add", "."]).await.unwrap();
+        src.run(&["commit", "-m", "initial"]).await.unwrap();
+
+        let updates = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = updates.clone();
+        let operation = GitOperation::new("clone-1").with_progress(std::sync::Arc::new(
+            move |p: &crate::git::GitProgress| {
+                sink.lock().unwrap().push(p.clone());
+            },
+        ));
+        let url = format!("file://{}", source.display());
+        Git::new(dir.path())
+            .with_operation(operation)
+            .run_streaming(
+                &["clone", "--progress", &url, "dest"],
+                Duration::from_secs(30),
+                |_| {},
+            )
+            .await
+            .unwrap();
+
+        let updates = updates.lock().unwrap();
+        assert!(!updates.is_empty());
+        assert!(updates.iter().all(|p| p.operation_id == "clone-1"));
+        assert!(updates
+            .iter()
+            .any(|p| p.phase == "Receiving objects" && p.done));
+    }
+
     // Error handling tests
 
     #[test]
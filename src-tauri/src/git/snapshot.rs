@@ -0,0 +1,708 @@
+//! Work-in-progress snapshots of a working tree.
+//!
+//! A snapshot records every tracked and untracked (but not ignored) file as
+//! a commit on a hidden ref, `refs/maestro/snapshots/<name>`, without
+//! touching the index, HEAD or any branch. The tree is built in a scratch
+//! index (`GIT_INDEX_FILE`) and committed with `git commit-tree`.
+//!
+//! Snapshots of one name form a chain: each commit's first parent is the
+//! previous snapshot and its second parent the HEAD it was taken on (the
+//! very first snapshot only has HEAD). HEAD is also recorded in a
+//! `Maestro-Head` trailer so listing does not depend on the parent layout.
+//! Because refs are shared by all worktrees of a repository, snapshots
+//! outlive the worktree they were taken in.
+//!
+//! [`Git::prune_snapshots`] caps a chain by rewriting its newest snapshots
+//! onto a fresh root; the dropped commits are left for `git gc`.
+
+use std::path::Path;
+
+use serde::Serialize;
+
+use super::diff::{DiffOptions, DiffTarget, FileDiff};
+use super::error::GitError;
+use super::runner::Git;
+
+/// Namespace holding snapshot refs.
+pub const SNAPSHOT_REF_PREFIX: &str = "refs/maestro/snapshots/";
+
+/// Subject prefix identifying snapshot commits.
+const SNAPSHOT_SUBJECT: &str = "maestro snapshot";
+
+const HEAD_TRAILER: &str = "Maestro-Head: ";
+const SESSION_TRAILER: &str = "Maestro-Session: ";
+
+/// Identity used for snapshot commits, so they work without `user.name`.
+const SNAPSHOT_IDENTITY: [(&str, &str); 4] = [
+    ("GIT_AUTHOR_NAME", "Maestro"),
+    ("GIT_AUTHOR_EMAIL", "maestro@localhost"),
+    ("GIT_COMMITTER_NAME", "Maestro"),
+    ("GIT_COMMITTER_EMAIL", "maestro@localhost"),
+];
+
+/// A snapshot commit.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Snapshot {
+    pub commit: String,
+    pub tree: String,
+    /// HEAD when the snapshot was taken; `None` on an unborn branch.
+    pub head: Option<String>,
+    /// Why the snapshot was taken (e.g. `interval`, `file-edited`).
+    pub reason: String,
+    pub session_id: Option<u32>,
+    /// Unix timestamp in seconds.
+    pub timestamp: i64,
+}
+
+/// Snapshot chain name for a worktree: its directory name, which Maestro
+/// derives from the session branch and which stays stable across restarts
+/// (unlike session IDs).
+pub fn worktree_snapshot_name(worktree_path: &Path) -> String {
+    worktree_path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+/// Full ref name for a snapshot chain. `name` is sanitized into a single
+/// valid ref component.
+pub fn snapshot_ref(name: &str) -> String {
+    let mut component: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    component = component.trim_start_matches('.').to_string();
+    if component.ends_with(".lock") || component.ends_with('.') {
+        component.push('_');
+    }
+    while component.contains("..") {
+        component = component.replace("..", ".");
+    }
+    if component.is_empty() {
+        component.push_str("unnamed");
+    }
+    format!("{SNAPSHOT_REF_PREFIX}{component}")
+}
+
+impl Git {
+    /// Writes the working tree, including untracked files that are not
+    /// ignored, as a tree object and returns its id. The real index is left
+    /// untouched.
+    pub async fn working_tree_tree(&self) -> Result<String, GitError> {
+        let index = self.run(&["rev-parse", "--git-path", "index"]).await?;
+        let index = self.repo_path().join(index.trimmed());
+        let scratch = index.with_file_name(format!(
+            "maestro-snapshot-{}.index",
+            uuid::Uuid::new_v4().simple()
+        ));
+
+        // Starting from a copy of the real index reuses its stat cache, so
+        // `add -A` only rehashes files that actually changed.
+        if tokio::fs::try_exists(&index).await.unwrap_or(false) {
+            tokio::fs::copy(&index, &scratch)
+                .await
+                .map_err(|source| GitError::SpawnError {
+                    source,
+                    command: format!("copy {} {}", index.display(), scratch.display()),
+                })?;
+        }
+
+        let scratch_str = scratch.to_string_lossy().to_string();
+        let env = [("GIT_INDEX_FILE", scratch_str.as_str())];
+        let result = async {
+            self.run_with_env(&["add", "-A", "--", "."], &env).await?;
+            let tree = self.run_with_env(&["write-tree"], &env).await?;
+            Ok(tree.trimmed().to_string())
+        }
+        .await;
+        let _ = tokio::fs::remove_file(&scratch).await;
+        result
+    }
+
+    /// Returns a commit capturing the working tree, including untracked files
+    /// that are not ignored, on top of `HEAD`. No ref, the index or the stash
+    /// list is touched. Returns `HEAD` itself when nothing changed.
+    pub async fn snapshot_commit(&self) -> Result<String, GitError> {
+        let tree = self.working_tree_tree().await?;
+        let head = self.resolve_commit("HEAD").await?;
+        if let Some(ref head) = head {
+            let head_tree = self.run(&["rev-parse", &format!("{head}^{{tree}}")]).await?;
+            if head_tree.trimmed() == tree {
+                return Ok(head.clone());
+            }
+        }
+
+        let message = snapshot_message("working tree", head.as_deref(), None);
+        let mut args = vec!["commit-tree", tree.as_str(), "-m", message.as_str()];
+        if let Some(ref head) = head {
+            args.push("-p");
+            args.push(head);
+        }
+        Ok(self
+            .run_with_env(&args, &SNAPSHOT_IDENTITY)
+            .await?
+            .trimmed()
+            .to_string())
+    }
+
+    /// Records the working tree on the `name` snapshot chain. Returns `None`
+    /// without creating a commit if nothing changed since the previous
+    /// snapshot (or since HEAD, for the first one).
+    pub async fn create_snapshot(
+        &self,
+        name: &str,
+        reason: &str,
+        session_id: Option<u32>,
+    ) -> Result<Option<Snapshot>, GitError> {
+        let ref_name = snapshot_ref(name);
+        let tree = self.working_tree_tree().await?;
+        let previous = self.resolve_commit(&ref_name).await?;
+        let head = self.resolve_commit("HEAD").await?;
+
+        let unchanged_against = previous.as_ref().or(head.as_ref());
+        if let Some(commit) = unchanged_against {
+            let existing = self
+                .run(&["rev-parse", &format!("{commit}^{{tree}}")])
+                .await?;
+            if existing.trimmed() == tree {
+                return Ok(None);
+            }
+        }
+
+        let message = snapshot_message(reason, head.as_deref(), session_id);
+        let mut args = vec!["commit-tree", tree.as_str(), "-m", message.as_str()];
+        for parent in previous.iter().chain(head.iter()) {
+            args.push("-p");
+            args.push(parent);
+        }
+        let commit = self
+            .run_with_env(&args, &SNAPSHOT_IDENTITY)
+            .await?
+            .trimmed()
+            .to_string();
+
+        // Compare-and-swap so concurrent snapshots can't drop each other.
+        let old = previous.as_deref().unwrap_or("");
+        self.run(&["update-ref", "-m", &message, &ref_name, &commit, old])
+            .await?;
+
+        Ok(self
+            .list_snapshots(name, 1)
+            .await?
+            .into_iter()
+            .next()
+            .filter(|s| s.commit == commit))
+    }
+
+    /// Lists up to `limit` snapshots of the `name` chain, newest first.
+    pub async fn list_snapshots(
+        &self,
+        name: &str,
+        limit: usize,
+    ) -> Result<Vec<Snapshot>, GitError> {
+        let ref_name = snapshot_ref(name);
+        if self.resolve_commit(&ref_name).await?.is_none() {
+            return Ok(Vec::new());
+        }
+        let count = format!("-n{limit}");
+        let output = self
+            .run(&[
+                "log",
+                "--first-parent",
+                &count,
+                "--format=%H%x1f%T%x1f%ct%x1f%B%x1e",
+                &ref_name,
+            ])
+            .await?;
+        Ok(parse_snapshot_log(&output.stdout))
+    }
+
+    /// Diffs a snapshot against the current working tree, or against
+    /// another snapshot or commit when `against` is given.
+    pub async fn diff_snapshot(
+        &self,
+        snapshot: &str,
+        against: Option<&str>,
+        options: &DiffOptions,
+    ) -> Result<Vec<FileDiff>, GitError> {
+        let to = match against {
+            Some(commit) => commit.to_string(),
+            None => self.working_tree_tree().await?,
+        };
+        let target = DiffTarget::Commits {
+            from: snapshot.to_string(),
+            to,
+        };
+        self.diff(&target, options).await
+    }
+
+    /// Restores files from a snapshot into the working tree, leaving the
+    /// index and HEAD alone. With no `paths` the whole tree is restored,
+    /// including deleting files created after the snapshot.
+    ///
+    /// The current state is snapshotted first (reason `before-restore`) so
+    /// the restore itself can be undone; that snapshot is returned if one
+    /// was needed.
+    pub async fn restore_snapshot(
+        &self,
+        name: &str,
+        snapshot: &str,
+        paths: &[String],
+        session_id: Option<u32>,
+    ) -> Result<Option<Snapshot>, GitError> {
+        let commit = self
+            .resolve_commit(snapshot)
+            .await?
+            .ok_or_else(|| GitError::ParseError {
+                message: format!("snapshot {snapshot} not found"),
+            })?;
+        let safety = self
+            .create_snapshot(name, "before-restore", session_id)
+            .await?;
+
+        let mut pathspecs: Vec<&str> = vec!["--"];
+        if paths.is_empty() {
+            pathspecs.push(":/");
+        } else {
+            pathspecs.extend(paths.iter().map(String::as_str));
+        }
+
+        // Files that exist now but not in the snapshot. `git restore`
+        // deletes tracked ones itself; untracked ones must go by hand.
+        let current = self.working_tree_tree().await?;
+        let mut args = vec![
+            "diff",
+            "--name-only",
+            "--no-renames",
+            "--diff-filter=A",
+            "-z",
+            &commit,
+            &current,
+        ];
+        args.extend(&pathspecs);
+        let added = self.run(&args).await?;
+        for path in added.stdout.split('\0').filter(|p| !p.is_empty()) {
+            let full = self.repo_path().join(path);
+            if let Err(e) = tokio::fs::remove_file(&full).await {
+                log::warn!(
+                    "restore_snapshot: could not remove {}: {}",
+                    full.display(),
+                    e
+                );
+            }
+        }
+
+        let source = format!("--source={commit}");
+        let mut args = vec!["restore", source.as_str(), "--worktree"];
+        args.extend(&pathspecs);
+        // `restore` fails if a pathspec matches nothing in the snapshot,
+        // which is expected when every matched file was just deleted.
+        match self.run(&args).await {
+            Ok(_) => {}
+            Err(GitError::CommandFailed { ref stderr, .. })
+                if stderr.contains("did not match any file") => {}
+            Err(e) => return Err(e),
+        }
+        Ok(safety)
+    }
+
+    /// Caps the `name` chain: if it holds more than `max` snapshots, all but
+    /// the newest `keep` (at least one) are dropped. Returns whether any
+    /// were.
+    ///
+    /// The kept snapshots are recommitted (same trees, messages and dates)
+    /// so the oldest no longer links to the dropped ones, which changes
+    /// their commit ids.
+    pub async fn prune_snapshots(
+        &self,
+        name: &str,
+        max: usize,
+        keep: usize,
+    ) -> Result<bool, GitError> {
+        let keep = keep.max(1);
+        let mut snapshots = self.list_snapshots(name, max.max(keep) + 1).await?;
+        if snapshots.len() <= max {
+            return Ok(false);
+        }
+        let Some(tip) = snapshots.first().map(|s| s.commit.clone()) else {
+            return Ok(false);
+        };
+        snapshots.truncate(keep);
+
+        let mut previous: Option<String> = None;
+        for snapshot in snapshots.iter().rev() {
+            let message = snapshot_message(
+                &snapshot.reason,
+                snapshot.head.as_deref(),
+                snapshot.session_id,
+            );
+            let mut args = vec!["commit-tree", snapshot.tree.as_str(), "-m", message.as_str()];
+            for parent in previous.iter().chain(snapshot.head.iter()) {
+                args.push("-p");
+                args.push(parent);
+            }
+            let date = format!("@{} +0000", snapshot.timestamp);
+            let mut env = SNAPSHOT_IDENTITY.to_vec();
+            env.push(("GIT_AUTHOR_DATE", &date));
+            env.push(("GIT_COMMITTER_DATE", &date));
+            let commit = self.run_with_env(&args, &env).await?;
+            previous = Some(commit.trimmed().to_string());
+        }
+
+        // Compare-and-swap: a snapshot taken meanwhile makes this fail
+        // rather than get lost.
+        if let Some(new_tip) = previous {
+            self.run(&[
+                "update-ref",
+                "-m",
+                "maestro snapshot: prune",
+                &snapshot_ref(name),
+                &new_tip,
+                &tip,
+            ])
+            .await?;
+        }
+        Ok(true)
+    }
+
+    /// Deletes the `name` snapshot chain. Its commits become unreachable and
+    /// are eventually garbage collected by git.
+    pub async fn delete_snapshots(&self, name: &str) -> Result<(), GitError> {
+        let ref_name = snapshot_ref(name);
+        if self.resolve_commit(&ref_name).await?.is_some() {
+            self.run(&["update-ref", "-d", &ref_name]).await?;
+        }
+        Ok(())
+    }
+
+    /// Resolves a ref or revision to a commit id, or `None` if it does not
+    /// exist.
+    async fn resolve_commit(&self, rev: &str) -> Result<Option<String>, GitError> {
+        let spec = format!("{rev}^{{commit}}");
+        match self.run(&["rev-parse", "--verify", "--quiet", &spec]).await {
+            Ok(output) => Ok(Some(output.trimmed().to_string())),
+            Err(GitError::CommandFailed { code: 1, .. }) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+/// Commit message of a snapshot: subject plus `Maestro-Head` and
+/// `Maestro-Session` trailers.
+fn snapshot_message(reason: &str, head: Option<&str>, session_id: Option<u32>) -> String {
+    let mut message = format!("{SNAPSHOT_SUBJECT}: {reason}\n\n");
+    if let Some(head) = head {
+        message.push_str(&format!("{HEAD_TRAILER}{head}\n"));
+    }
+    if let Some(id) = session_id {
+        message.push_str(&format!("{SESSION_TRAILER}{id}\n"));
+    }
+    message
+}
+
+/// Parses `git log --format=%H%x1f%T%x1f%ct%x1f%B%x1e` output, stopping at
+/// the first commit that is not a snapshot (the branch history below the
+/// first snapshot of a chain).
+fn parse_snapshot_log(output: &str) -> Vec<Snapshot> {
+    let mut snapshots = Vec::new();
+    for record in output.split('\x1e') {
+        let record = record.trim_start_matches('\n');
+        if record.is_empty() {
+            continue;
+        }
+        let mut fields = record.splitn(4, '\x1f');
+        let (Some(commit), Some(tree), Some(timestamp), Some(body)) =
+            (fields.next(), fields.next(), fields.next(), fields.next())
+        else {
+            continue;
+        };
+        let mut lines = body.lines();
+        let Some(reason) = lines
+            .next()
+            .and_then(|subject| subject.strip_prefix(SNAPSHOT_SUBJECT))
+            .map(|rest| rest.trim_start_matches(':').trim().to_string())
+        else {
+            break;
+        };
+
+        let mut head = None;
+        let mut session_id = None;
+        for line in lines {
+            if let Some(value) = line.strip_prefix(HEAD_TRAILER) {
+                head = Some(value.trim().to_string());
+            } else if let Some(value) = line.strip_prefix(SESSION_TRAILER) {
+                session_id = value.trim().parse().ok();
+            }
+        }
+
+        snapshots.push(Snapshot {
+            commit: commit.to_string(),
+            tree: tree.to_string(),
+            head,
+            reason,
+            session_id,
+            timestamp: timestamp.parse().unwrap_or(0),
+        });
+    }
+    snapshots
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    async fn create_test_repo() -> (tempfile::TempDir, Git) {
+        let dir = tempdir().unwrap();
+        let git = Git::new(dir.path());
+        git.run(&["init", "-b", "main"]).await.unwrap();
+        git.run(&["config", "user.email", "test@test.com"])
+            .await
+            .unwrap();
+        git.run(&["config", "user.name", "Test"]).await.unwrap();
+        tokio::fs::write(dir.path().join("README.md"), "# Test")
+            .await
+            .unwrap();
+        tokio::fs::write(dir.path().join(".gitignore"), "target/\n")
+            .await
+            .unwrap();
+        git.run(&["add", "."]).await.unwrap();
+        git.run(&["commit", "-m", "initial"]).await.unwrap();
+        (dir, git)
+    }
+
+    #[test]
+    fn test_snapshot_ref_sanitizes_names() {
+        assert_eq!(
+            snapshot_ref("feature-x"),
+            "refs/maestro/snapshots/feature-x"
+        );
+        assert_eq!(snapshot_ref("a/b c"), "refs/maestro/snapshots/a-b-c");
+        assert_eq!(
+            snapshot_ref("..x..y.lock"),
+            "refs/maestro/snapshots/x.y.lock_"
+        );
+        assert_eq!(snapshot_ref(""), "refs/maestro/snapshots/unnamed");
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_commit_captures_working_tree() {
+        let (dir, git) = create_test_repo().await;
+        let head = git.run(&["rev-parse", "HEAD"]).await.unwrap().trimmed().to_string();
+        assert_eq!(git.snapshot_commit().await.unwrap(), head);
+
+        tokio::fs::write(dir.path().join("README.md"), "# Edited").await.unwrap();
+        tokio::fs::write(dir.path().join("new.txt"), "new").await.unwrap();
+        tokio::fs::create_dir(dir.path().join("target")).await.unwrap();
+        tokio::fs::write(dir.path().join("target/out"), "ignored").await.unwrap();
+        let snapshot = git.snapshot_commit().await.unwrap();
+        assert_ne!(snapshot, head);
+        assert_eq!(
+            git.changed_paths(&head, &snapshot).await.unwrap(),
+            vec!["README.md", "new.txt"]
+        );
+
+        // The index, working tree and stash list are untouched
+        let status = git.run(&["status", "--porcelain"]).await.unwrap();
+        assert_eq!(status.trimmed(), "M README.md\n?? new.txt");
+        let stashes = git.run(&["stash", "list"]).await.unwrap();
+        assert!(stashes.trimmed().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_commits_expose_add_add_conflicts() {
+        let (dir, git) = create_test_repo().await;
+        let other = tempdir().unwrap();
+        let other_path = other.path().join("wt");
+        git.run(&["worktree", "add", "-b", "other", &other_path.to_string_lossy()])
+            .await
+            .unwrap();
+
+        tokio::fs::write(dir.path().join("api.ts"), "export const a = 1;\n").await.unwrap();
+        tokio::fs::write(other_path.join("api.ts"), "export const b = 2;\n").await.unwrap();
+
+        let ours = git.snapshot_commit().await.unwrap();
+        let theirs = Git::new(&other_path).snapshot_commit().await.unwrap();
+        let merge = git.merge_tree(&ours, &theirs).await.unwrap();
+        assert_eq!(merge.conflicted_files, vec!["api.ts"]);
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_leaves_index_and_head_alone() {
+        let (dir, git) = create_test_repo().await;
+        let head = git
+            .run(&["rev-parse", "HEAD"])
+            .await
+            .unwrap()
+            .trimmed()
+            .to_string();
+
+        // Nothing changed yet: no snapshot.
+        assert!(git
+            .create_snapshot("wt", "interval", Some(1))
+            .await
+            .unwrap()
+            .is_none());
+
+        tokio::fs::write(dir.path().join("README.md"), "# Edited")
+            .await
+            .unwrap();
+        tokio::fs::write(dir.path().join("new.txt"), "new")
+            .await
+            .unwrap();
+        tokio::fs::create_dir(dir.path().join("target"))
+            .await
+            .unwrap();
+        tokio::fs::write(dir.path().join("target/out"), "ignored")
+            .await
+            .unwrap();
+        let status_before = git.run(&["status", "--porcelain"]).await.unwrap().stdout;
+
+        let snap = git
+            .create_snapshot("wt", "file-edited", Some(1))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(snap.head.as_deref(), Some(head.as_str()));
+        assert_eq!(snap.reason, "file-edited");
+        assert_eq!(snap.session_id, Some(1));
+
+        let files = git
+            .run(&["ls-tree", "-r", "--name-only", &snap.commit])
+            .await
+            .unwrap();
+        assert_eq!(files.lines(), vec![".gitignore", "README.md", "new.txt"]);
+        assert_eq!(
+            git.run(&["status", "--porcelain"]).await.unwrap().stdout,
+            status_before
+        );
+        assert_eq!(
+            git.run(&["rev-parse", "HEAD"]).await.unwrap().trimmed(),
+            head
+        );
+
+        // Unchanged since the last snapshot: skipped.
+        assert!(git
+            .create_snapshot("wt", "interval", Some(1))
+            .await
+            .unwrap()
+            .is_none());
+
+        tokio::fs::write(dir.path().join("new.txt"), "newer")
+            .await
+            .unwrap();
+        let second = git
+            .create_snapshot("wt", "interval", None)
+            .await
+            .unwrap()
+            .unwrap();
+        let list = git.list_snapshots("wt", 10).await.unwrap();
+        assert_eq!(list.len(), 2);
+        assert_eq!(list[0], second);
+        assert_eq!(list[1], snap);
+        assert!(git.list_snapshots("other", 10).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_diff_and_restore_snapshot() {
+        let (dir, git) = create_test_repo().await;
+        tokio::fs::write(dir.path().join("README.md"), "# Good work")
+            .await
+            .unwrap();
+        tokio::fs::write(dir.path().join("notes.txt"), "keep me")
+            .await
+            .unwrap();
+        let good = git
+            .create_snapshot("wt", "manual", None)
+            .await
+            .unwrap()
+            .unwrap();
+
+        // A bad edit: clobber README, delete notes, add a stray file.
+        tokio::fs::write(dir.path().join("README.md"), "# Broken")
+            .await
+            .unwrap();
+        tokio::fs::remove_file(dir.path().join("notes.txt"))
+            .await
+            .unwrap();
+        tokio::fs::write(dir.path().join("stray.txt"), "oops")
+            .await
+            .unwrap();
+
+        let diff = git
+            .diff_snapshot(&good.commit, None, &DiffOptions::default())
+            .await
+            .unwrap();
+        let mut paths: Vec<_> = diff.iter().map(|f| f.path.as_str()).collect();
+        paths.sort();
+        assert_eq!(paths, vec!["README.md", "notes.txt", "stray.txt"]);
+
+        let safety = git
+            .restore_snapshot("wt", &good.commit, &[], None)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(safety.reason, "before-restore");
+        let read = |p: &str| std::fs::read_to_string(dir.path().join(p)).unwrap();
+        assert_eq!(read("README.md"), "# Good work");
+        assert_eq!(read("notes.txt"), "keep me");
+        assert!(!dir.path().join("stray.txt").exists());
+        // Index still matches HEAD: README shows as unstaged only.
+        let status = git.run(&["status", "--porcelain"]).await.unwrap().stdout;
+        assert!(status.contains(" M README.md"));
+
+        // Undo the restore for one path only.
+        git.restore_snapshot("wt", &safety.commit, &["README.md".to_string()], None)
+            .await
+            .unwrap();
+        assert_eq!(read("README.md"), "# Broken");
+        assert_eq!(read("notes.txt"), "keep me");
+
+        git.delete_snapshots("wt").await.unwrap();
+        assert!(git.list_snapshots("wt", 10).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_prune_keeps_newest_snapshots() {
+        let (dir, git) = create_test_repo().await;
+        for i in 0..5 {
+            tokio::fs::write(dir.path().join("n.txt"), i.to_string())
+                .await
+                .unwrap();
+            git.create_snapshot("wt", "interval", Some(i))
+                .await
+                .unwrap()
+                .unwrap();
+        }
+        let before = git.list_snapshots("wt", 10).await.unwrap();
+
+        // Under the cap: untouched.
+        assert!(!git.prune_snapshots("wt", 5, 2).await.unwrap());
+        assert_eq!(git.list_snapshots("wt", 10).await.unwrap(), before);
+
+        assert!(git.prune_snapshots("wt", 4, 2).await.unwrap());
+        let after = git.list_snapshots("wt", 10).await.unwrap();
+        assert_eq!(after.len(), 2);
+        for (kept, original) in after.iter().zip(&before) {
+            assert_eq!(kept.tree, original.tree);
+            assert_eq!(kept.head, original.head);
+            assert_eq!(kept.session_id, original.session_id);
+            assert_eq!(kept.timestamp, original.timestamp);
+        }
+        // The chain continues from the rewritten tip.
+        tokio::fs::write(dir.path().join("n.txt"), "last")
+            .await
+            .unwrap();
+        git.create_snapshot("wt", "interval", None)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(git.list_snapshots("wt", 10).await.unwrap().len(), 3);
+    }
+}
@@ -0,0 +1,403 @@
+//! Stash management and the auto-stash used when Maestro switches branches.
+//!
+//! Stashes are addressed by their position in the stash list (`stash@{n}`),
+//! which shifts as entries are pushed and dropped, so callers should re-list
+//! after every change.
+
+use serde::Serialize;
+
+use super::diff::{DiffOptions, DiffTarget, FileDiff};
+use super::error::GitError;
+use super::ops::FileChangeStatus;
+use super::runner::Git;
+
+/// Message prefix of stashes created by [`Git::checkout_with_autostash`].
+pub const AUTOSTASH_PREFIX: &str = "maestro autostash";
+
+/// An entry in the stash list.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StashEntry {
+    /// Position in the stash list; `0` is the most recent.
+    pub index: usize,
+    /// Reflog selector, e.g. `stash@{0}`.
+    pub reference: String,
+    pub commit: String,
+    /// The stash message, without git's `On <branch>:` prefix.
+    pub message: String,
+    /// Branch the stash was created on; `None` for a detached HEAD.
+    pub branch: Option<String>,
+    /// Whether untracked files were stashed too.
+    pub includes_untracked: bool,
+    /// Unix timestamp in seconds.
+    pub timestamp: i64,
+}
+
+/// What [`Git::checkout_with_autostash`] did besides switching branches.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct AutostashOutcome {
+    /// Local changes stashed before leaving the previous branch.
+    pub stashed: Option<StashEntry>,
+    /// An earlier auto-stash of the target branch that was re-applied.
+    pub restored: Option<StashEntry>,
+    /// Set when re-applying the earlier auto-stash failed (e.g. it
+    /// conflicts); the stash is kept so nothing is lost.
+    pub restore_error: Option<String>,
+}
+
+fn stash_ref(index: usize) -> String {
+    format!("stash@{{{index}}}")
+}
+
+impl Git {
+    /// Lists stashes, most recent first.
+    pub async fn stash_list(&self) -> Result<Vec<StashEntry>, GitError> {
+        let output = self
+            .run(&["stash", "list", "--format=%H%x1f%P%x1f%ct%x1f%gs"])
+            .await?;
+        Ok(parse_stash_list(&output.stdout))
+    }
+
+    /// Stashes local changes (`git stash push`), optionally including
+    /// untracked files. Returns the new entry, or `None` if there was
+    /// nothing to stash.
+    pub async fn stash_push(
+        &self,
+        message: Option<&str>,
+        include_untracked: bool,
+    ) -> Result<Option<StashEntry>, GitError> {
+        let before = self.stash_top().await?;
+        let mut args = vec!["stash", "push"];
+        if include_untracked {
+            args.push("--include-untracked");
+        }
+        if let Some(message) = message {
+            args.extend(["-m", message]);
+        }
+        self.run(&args).await?;
+
+        if self.stash_top().await? == before {
+            return Ok(None);
+        }
+        Ok(self.stash_list().await?.into_iter().next())
+    }
+
+    /// Applies a stash to the working tree, keeping it in the list.
+    pub async fn stash_apply(&self, index: usize) -> Result<(), GitError> {
+        self.run(&["stash", "apply", &stash_ref(index)]).await?;
+        Ok(())
+    }
+
+    /// Applies a stash and removes it from the list. On conflicts git keeps
+    /// the stash and this returns the error.
+    pub async fn stash_pop(&self, index: usize) -> Result<(), GitError> {
+        self.run(&["stash", "pop", &stash_ref(index)]).await?;
+        Ok(())
+    }
+
+    /// Removes a stash from the list without applying it.
+    pub async fn stash_drop(&self, index: usize) -> Result<(), GitError> {
+        self.run(&["stash", "drop", &stash_ref(index)]).await?;
+        Ok(())
+    }
+
+    /// Diffs a stash against the commit it was created on. Stashed
+    /// untracked files are included as additions with `untracked` set.
+    pub async fn stash_diff(
+        &self,
+        index: usize,
+        options: &DiffOptions,
+    ) -> Result<Vec<FileDiff>, GitError> {
+        let stash = stash_ref(index);
+        let base = format!("{stash}^1");
+        let mut files = self
+            .diff(
+                &DiffTarget::Commits {
+                    from: base.clone(),
+                    to: stash.clone(),
+                },
+                options,
+            )
+            .await?;
+
+        let untracked = format!("{stash}^3");
+        let has_untracked = !self
+            .run_accepting(&["rev-parse", "--verify", "--quiet", &untracked], &[1])
+            .await?
+            .trimmed()
+            .is_empty();
+        if has_untracked {
+            // The untracked commit holds only the untracked files, so every
+            // other path shows up as deleted relative to the base.
+            let target = DiffTarget::Commits {
+                from: base,
+                to: untracked,
+            };
+            for mut file in self.diff(&target, options).await? {
+                if file.status == FileChangeStatus::Added {
+                    file.untracked = true;
+                    files.push(file);
+                }
+            }
+        }
+        Ok(files)
+    }
+
+    /// Switches the checkout to `branch` without risking local changes.
+    ///
+    /// Uncommitted changes, untracked files included, are stashed first under
+    /// an [`AUTOSTASH_PREFIX`] message naming the branch being left. If the
+    /// newest stash left on `branch` by an earlier switch exists, it is
+    /// popped after the checkout, so switching away and back round-trips
+    /// the work in progress. If the checkout fails, the changes are restored
+    /// before returning the error.
+    pub async fn checkout_with_autostash(
+        &self,
+        branch: &str,
+    ) -> Result<AutostashOutcome, GitError> {
+        let from = self.current_branch().await.ok();
+        let mut outcome = AutostashOutcome::default();
+
+        if !self.status_entries().await?.is_empty() {
+            let message = format!(
+                "{AUTOSTASH_PREFIX} on {}",
+                from.as_deref().unwrap_or("detached HEAD")
+            );
+            outcome.stashed = self.stash_push(Some(&message), true).await?;
+            if let Some(ref stashed) = outcome.stashed {
+                log::info!(
+                    "Auto-stashed local changes as {} before switching to {}",
+                    stashed.commit,
+                    branch
+                );
+            }
+        }
+
+        if let Err(e) = self.checkout_branch(branch).await {
+            if outcome.stashed.is_some() {
+                if let Err(pop_err) = self.stash_pop(0).await {
+                    log::error!(
+                        "Failed to restore auto-stash after failed checkout: {}",
+                        pop_err
+                    );
+                }
+            }
+            return Err(e);
+        }
+
+        let to = self.current_branch().await.ok();
+        let Some(to) = to.filter(|to| Some(to) != from.as_ref()) else {
+            return Ok(outcome);
+        };
+        let wanted = format!("{AUTOSTASH_PREFIX} on {to}");
+        let earlier =
+            self.stash_list().await?.into_iter().find(|entry| {
+                entry.branch.as_deref() == Some(to.as_str()) && entry.message == wanted
+            });
+        if let Some(entry) = earlier {
+            match self.stash_pop(entry.index).await {
+                Ok(()) => outcome.restored = Some(entry),
+                Err(e) => {
+                    log::warn!("Failed to re-apply auto-stash {}: {}", entry.reference, e);
+                    // A conflicted pop leaves conflict markers behind; put the
+                    // tree back as the checkout left it. The stash is kept.
+                    let _ = self.run(&["reset", "--merge"]).await;
+                    outcome.restore_error = Some(e.to_string());
+                }
+            }
+        }
+        Ok(outcome)
+    }
+
+    /// Commit of the newest stash, or `None` when the stash list is empty.
+    async fn stash_top(&self) -> Result<Option<String>, GitError> {
+        let output = self
+            .run_accepting(&["rev-parse", "--verify", "--quiet", "refs/stash"], &[1])
+            .await?;
+        Ok(Some(output.trimmed().to_string()).filter(|s| !s.is_empty()))
+    }
+}
+
+/// Parses `git stash list --format=%H%x1f%P%x1f%ct%x1f%gs` output.
+fn parse_stash_list(output: &str) -> Vec<StashEntry> {
+    output
+        .lines()
+        .filter(|line| !line.is_empty())
+        .enumerate()
+        .filter_map(|(index, line)| {
+            let mut fields = line.splitn(4, '\x1f');
+            let commit = fields.next()?;
+            let parents = fields.next()?;
+            let timestamp = fields.next()?.parse().unwrap_or(0);
+            let subject = fields.next()?;
+            let (branch, message) = parse_stash_subject(subject);
+            Some(StashEntry {
+                index,
+                reference: stash_ref(index),
+                commit: commit.to_string(),
+                message,
+                branch,
+                includes_untracked: parents.split_whitespace().count() >= 3,
+                timestamp,
+            })
+        })
+        .collect()
+}
+
+/// Splits a stash reflog subject (`On main: msg` or
+/// `WIP on main: abc1234 commit subject`) into branch and message.
+fn parse_stash_subject(subject: &str) -> (Option<String>, String) {
+    let rest = subject
+        .strip_prefix("WIP on ")
+        .or_else(|| subject.strip_prefix("On "));
+    match rest.and_then(|rest| rest.split_once(": ")) {
+        Some((branch, message)) => {
+            let branch = (branch != "(no branch)").then(|| branch.to_string());
+            (branch, message.to_string())
+        }
+        None => (None, subject.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    async fn create_test_repo() -> (tempfile::TempDir, Git) {
+        let dir = tempdir().unwrap();
+        let git = Git::new(dir.path());
+        git.run(&["init", "-b", "main"]).await.unwrap();
+        git.run(&["config", "user.email", "test@test.com"])
+            .await
+            .unwrap();
+        git.run(&["config", "user.name", "Test"]).await.unwrap();
+        tokio::fs::write(dir.path().join("README.md"), "# Test\n")
+            .await
+            .unwrap();
+        git.run(&["add", "."]).await.unwrap();
+        git.run(&["commit", "-m", "initial"]).await.unwrap();
+        (dir, git)
+    }
+
+    #[test]
+    fn test_parse_stash_subject() {
+        assert_eq!(
+            parse_stash_subject("On main: my work"),
+            (Some("main".into()), "my work".into())
+        );
+        assert_eq!(
+            parse_stash_subject("WIP on feature/x: abc1234 initial"),
+            (Some("feature/x".into()), "abc1234 initial".into())
+        );
+        assert_eq!(
+            parse_stash_subject("On (no branch): detached"),
+            (None, "detached".into())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_stash_push_list_show_and_pop() {
+        let (dir, git) = create_test_repo().await;
+        assert!(git
+            .stash_push(Some("nothing"), true)
+            .await
+            .unwrap()
+            .is_none());
+
+        tokio::fs::write(dir.path().join("README.md"), "# Changed\n")
+            .await
+            .unwrap();
+        tokio::fs::write(dir.path().join("new.txt"), "new\n")
+            .await
+            .unwrap();
+        let entry = git
+            .stash_push(Some("my work"), true)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(entry.index, 0);
+        assert_eq!(entry.reference, "stash@{0}");
+        assert_eq!(entry.message, "my work");
+        assert_eq!(entry.branch.as_deref(), Some("main"));
+        assert!(entry.includes_untracked);
+        assert!(git.status_entries().await.unwrap().is_empty());
+
+        let diff = git.stash_diff(0, &DiffOptions::default()).await.unwrap();
+        assert_eq!(diff.len(), 2);
+        assert_eq!(diff[0].path, "README.md");
+        assert!(!diff[0].untracked);
+        assert_eq!(diff[1].path, "new.txt");
+        assert!(diff[1].untracked);
+
+        tokio::fs::write(dir.path().join("README.md"), "# Second\n")
+            .await
+            .unwrap();
+        git.stash_push(None, false).await.unwrap().unwrap();
+        let list = git.stash_list().await.unwrap();
+        assert_eq!(list.len(), 2);
+        assert_eq!(list[1].message, "my work");
+        assert!(!list[0].includes_untracked);
+
+        git.stash_drop(0).await.unwrap();
+        git.stash_apply(0).await.unwrap();
+        assert_eq!(git.stash_list().await.unwrap().len(), 1);
+        git.run(&["checkout", "--", "."]).await.unwrap();
+        tokio::fs::remove_file(dir.path().join("new.txt"))
+            .await
+            .unwrap();
+
+        git.stash_pop(0).await.unwrap();
+        assert!(git.stash_list().await.unwrap().is_empty());
+        let readme = tokio::fs::read_to_string(dir.path().join("README.md"))
+            .await
+            .unwrap();
+        assert_eq!(readme, "# Changed\n");
+        assert!(dir.path().join("new.txt").exists());
+    }
+
+    #[tokio::test]
+    async fn test_checkout_with_autostash_round_trip() {
+        let (dir, git) = create_test_repo().await;
+        git.run(&["branch", "other"]).await.unwrap();
+        tokio::fs::write(dir.path().join("README.md"), "# WIP\n")
+            .await
+            .unwrap();
+        tokio::fs::write(dir.path().join("scratch.txt"), "x\n")
+            .await
+            .unwrap();
+
+        let away = git.checkout_with_autostash("other").await.unwrap();
+        let stashed = away.stashed.unwrap();
+        assert_eq!(stashed.message, "maestro autostash on main");
+        assert!(away.restored.is_none());
+        assert_eq!(git.current_branch().await.unwrap(), "other");
+        assert!(git.status_entries().await.unwrap().is_empty());
+
+        // Clean tree: nothing to stash, and nothing to restore on "other".
+        let back = git.checkout_with_autostash("main").await.unwrap();
+        assert!(back.stashed.is_none());
+        assert_eq!(back.restored.unwrap().commit, stashed.commit);
+        assert!(git.stash_list().await.unwrap().is_empty());
+        let readme = tokio::fs::read_to_string(dir.path().join("README.md"))
+            .await
+            .unwrap();
+        assert_eq!(readme, "# WIP\n");
+        assert!(dir.path().join("scratch.txt").exists());
+    }
+
+    #[tokio::test]
+    async fn test_checkout_with_autostash_restores_on_failure() {
+        let (dir, git) = create_test_repo().await;
+        tokio::fs::write(dir.path().join("README.md"), "# WIP\n")
+            .await
+            .unwrap();
+
+        assert!(git.checkout_with_autostash("missing").await.is_err());
+        assert_eq!(git.current_branch().await.unwrap(), "main");
+        assert!(git.stash_list().await.unwrap().is_empty());
+        let readme = tokio::fs::read_to_string(dir.path().join("README.md"))
+            .await
+            .unwrap();
+        assert_eq!(readme, "# WIP\n");
+    }
+}
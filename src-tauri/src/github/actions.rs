@@ -0,0 +1,476 @@
+//! GitHub Actions job logs: fetching a job's log split into its steps,
+//! extracting the steps that failed, re-running failed jobs and cancelling
+//! runs.
+//!
+//! Logs come from `gh run view --job <id> --log`, which labels each line
+//! with its step, or from the REST job log endpoint, whose lines are
+//! assigned to steps by their timestamps. Either way ANSI escapes and
+//! runner timestamps are stripped so the text can be shown in the UI or
+//! typed into an agent session as-is.
+
+use serde::{Deserialize, Serialize};
+
+use super::error::GitHubError;
+use super::ops::{WorkflowJob, WorkflowStep};
+use super::runner::GitHub;
+
+/// How many trailing log lines of a failing step are kept for the prompt.
+pub const FAILING_STEP_LOG_LINES: usize = 200;
+
+/// The output of one step of a job.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StepLog {
+    /// Step number within the job; `None` when the log could not be matched
+    /// to one of the job's steps.
+    pub number: Option<u64>,
+    pub name: String,
+    /// `success`, `failure`, `skipped`, ...; empty when unknown.
+    pub conclusion: String,
+    pub lines: Vec<String>,
+}
+
+/// A job's log split into its steps.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JobLog {
+    pub job_id: u64,
+    pub job_name: String,
+    pub conclusion: String,
+    pub url: String,
+    pub steps: Vec<StepLog>,
+}
+
+/// A failed step with the tail of its log, ready to hand to an agent.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FailingStep {
+    pub run_id: u64,
+    pub job_id: u64,
+    pub job_name: String,
+    /// `None` when the job failed outside of any step (e.g. it timed out
+    /// or its log could not be split), in which case `log` is the job's.
+    pub step_number: Option<u64>,
+    pub step_name: Option<String>,
+    pub url: String,
+    /// The last [`FAILING_STEP_LOG_LINES`] lines of the step's log.
+    pub log: String,
+    /// Number of earlier lines left out of `log`.
+    pub omitted_lines: usize,
+    /// Text to type into an agent session to have it fix the failure.
+    pub prompt: String,
+}
+
+impl FailingStep {
+    fn new(run_id: u64, job: &JobLog, step: Option<&StepLog>, lines: &[String]) -> Self {
+        let omitted_lines = lines.len().saturating_sub(FAILING_STEP_LOG_LINES);
+        let log = lines[omitted_lines..].join("\n");
+        let step_name = step.map(|s| s.name.clone());
+
+        let mut prompt = format!("The CI job \"{}\"", job.job_name);
+        if let Some(name) = &step_name {
+            prompt.push_str(&format!(" failed at step \"{name}\""));
+        } else {
+            prompt.push_str(" failed");
+        }
+        if !job.url.is_empty() {
+            prompt.push_str(&format!(" ({})", job.url));
+        }
+        if omitted_lines > 0 {
+            prompt.push_str(&format!(
+                ". The last {} lines of its log:\n",
+                lines.len() - omitted_lines
+            ));
+        } else {
+            prompt.push_str(". Its log:\n");
+        }
+        prompt.push_str(&format!(
+            "\n```\n{log}\n```\n\nPlease find the cause of this failure and fix it."
+        ));
+
+        Self {
+            run_id,
+            job_id: job.job_id,
+            job_name: job.job_name.clone(),
+            step_number: step.and_then(|s| s.number),
+            step_name,
+            url: job.url.clone(),
+            log,
+            omitted_lines,
+            prompt,
+        }
+    }
+}
+
+impl JobLog {
+    /// Returns the failed steps of this job. A failed job with no failed
+    /// step in its log yields one entry covering the whole job log.
+    pub fn failing_steps(&self, run_id: u64) -> Vec<FailingStep> {
+        let failed: Vec<FailingStep> = self
+            .steps
+            .iter()
+            .filter(|s| s.conclusion == "failure")
+            .map(|s| FailingStep::new(run_id, self, Some(s), &s.lines))
+            .collect();
+        if !failed.is_empty() || self.conclusion != "failure" {
+            return failed;
+        }
+
+        let lines: Vec<String> = self
+            .steps
+            .iter()
+            .flat_map(|s| s.lines.iter().cloned())
+            .collect();
+        vec![FailingStep::new(run_id, self, None, &lines)]
+    }
+
+    /// Builds a job log from `gh run view --job <id> --log` output, whose
+    /// lines are `job<TAB>step<TAB>timestamp text`.
+    pub(crate) fn from_gh_output(job: &WorkflowJob, output: &str) -> Self {
+        let mut steps: Vec<StepLog> = Vec::new();
+        let mut unmatched: Vec<&WorkflowStep> = job.steps.iter().collect();
+
+        for line in output.lines() {
+            let mut fields = line.splitn(3, '\t');
+            let (Some(_job), Some(step), Some(text)) =
+                (fields.next(), fields.next(), fields.next())
+            else {
+                continue;
+            };
+            let text = clean_line(text);
+
+            if steps.last().map(|s| s.name.as_str()) != Some(step) {
+                let known = unmatched
+                    .iter()
+                    .position(|s| s.name == step)
+                    .map(|i| unmatched.remove(i));
+                steps.push(StepLog {
+                    number: known.map(|s| s.number),
+                    name: step.to_string(),
+                    conclusion: known.map(|s| s.conclusion.clone()).unwrap_or_default(),
+                    lines: Vec::new(),
+                });
+            }
+            if let Some(current) = steps.last_mut() {
+                current.lines.push(text);
+            }
+        }
+
+        Self::with_steps(job, steps)
+    }
+
+    /// Builds a job log from the REST job log endpoint, assigning each line
+    /// to the last step that had started by the line's timestamp.
+    pub(crate) fn from_raw_log(job: &WorkflowJob, raw: &str) -> Self {
+        let timed: Vec<(&WorkflowStep, &str)> = job
+            .steps
+            .iter()
+            .filter_map(|s| s.started_at.as_deref().map(|t| (s, second(t))))
+            .collect();
+
+        let mut steps: Vec<StepLog> = if timed.is_empty() {
+            vec![StepLog {
+                number: None,
+                name: job.name.clone(),
+                conclusion: String::new(),
+                lines: Vec::new(),
+            }]
+        } else {
+            timed
+                .iter()
+                .map(|(s, _)| StepLog {
+                    number: Some(s.number),
+                    name: s.name.clone(),
+                    conclusion: s.conclusion.clone(),
+                    lines: Vec::new(),
+                })
+                .collect()
+        };
+
+        // Lines without a timestamp continue the previous line's step
+        let mut current = 0;
+        for line in raw.lines() {
+            let line = line.trim_start_matches('\u{feff}');
+            if let (Some(at), _) = split_timestamp(line) {
+                current = timed
+                    .iter()
+                    .rposition(|(_, started)| *started <= second(at))
+                    .unwrap_or(0);
+            }
+            steps[current].lines.push(clean_line(line));
+        }
+
+        steps.retain(|s| !s.lines.is_empty() || s.conclusion == "failure");
+        Self::with_steps(job, steps)
+    }
+
+    fn with_steps(job: &WorkflowJob, steps: Vec<StepLog>) -> Self {
+        Self {
+            job_id: job.database_id,
+            job_name: job.name.clone(),
+            conclusion: job.conclusion.clone(),
+            url: job.url.clone(),
+            steps,
+        }
+    }
+}
+
+/// Removes ANSI escape sequences (colors, cursor movement, OSC titles and
+/// hyperlinks) from `text`.
+pub fn strip_ansi(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            // CSI: parameters and intermediates up to a final byte in @..~
+            Some('[') => {
+                for c in chars.by_ref() {
+                    if ('@'..='~').contains(&c) {
+                        break;
+                    }
+                }
+            }
+            // OSC: terminated by BEL or ESC \
+            Some(']') => {
+                while let Some(c) = chars.next() {
+                    if c == '\x07' {
+                        break;
+                    }
+                    if c == '\x1b' && chars.peek() == Some(&'\\') {
+                        chars.next();
+                        break;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    out
+}
+
+/// Strips the runner timestamp, ANSI escapes and control characters other
+/// than tab from a line. Log lines end up in prompts typed into a terminal,
+/// where a stray `\r` or escape would act on the agent's TUI.
+fn clean_line(line: &str) -> String {
+    let (_, text) = split_timestamp(line.trim_start_matches('\u{feff}'));
+    strip_ansi(text)
+        .chars()
+        .filter(|&c| c == '\t' || !c.is_control())
+        .collect()
+}
+
+/// Splits a `2024-01-01T00:00:00.1234567Z text` log line into its
+/// timestamp and text.
+fn split_timestamp(line: &str) -> (Option<&str>, &str) {
+    match line.split_once(' ') {
+        Some((at, text))
+            if at.len() >= 20
+                && at.ends_with('Z')
+                && at.as_bytes()[4] == b'-'
+                && at.as_bytes()[10] == b'T' =>
+        {
+            (Some(at), text)
+        }
+        _ => (None, line),
+    }
+}
+
+/// Truncates an RFC 3339 UTC timestamp to whole seconds so step times
+/// (seconds) and log line times (sub-second) compare correctly as strings.
+fn second(timestamp: &str) -> &str {
+    timestamp.get(..19).unwrap_or(timestamp)
+}
+
+impl GitHub {
+    /// Gets a job's log split into its steps.
+    pub async fn workflow_job_log(&self, run_id: u64, job_id: u64) -> Result<JobLog, GitHubError> {
+        let run = self.get_workflow_run(run_id).await?;
+        let job = run
+            .jobs
+            .iter()
+            .find(|j| j.database_id == job_id)
+            .ok_or_else(|| GitHubError::ParseError {
+                message: format!("job {} not found in workflow run {}", job_id, run_id),
+            })?;
+
+        if let Some(api) = self.native().await? {
+            let raw = api.job_log(job_id).await?;
+            return Ok(JobLog::from_raw_log(job, &raw));
+        }
+
+        let id_str = job_id.to_string();
+        let output = self
+            .run(&["run", "view", "--job", &id_str, "--log"])
+            .await?;
+        Ok(JobLog::from_gh_output(job, &output.stdout))
+    }
+
+    /// Returns every failed step of a workflow run with the tail of its log.
+    pub async fn workflow_run_failing_steps(
+        &self,
+        run_id: u64,
+    ) -> Result<Vec<FailingStep>, GitHubError> {
+        let run = self.get_workflow_run(run_id).await?;
+        let mut failing = Vec::new();
+        for job in run.jobs.iter().filter(|j| j.conclusion == "failure") {
+            let log = self.workflow_job_log(run_id, job.database_id).await?;
+            failing.extend(log.failing_steps(run_id));
+        }
+        Ok(failing)
+    }
+
+    /// Re-runs the failed jobs of a workflow run.
+    pub async fn rerun_failed_jobs(&self, run_id: u64) -> Result<(), GitHubError> {
+        if let Some(api) = self.native().await? {
+            return api.rerun_failed_jobs(run_id).await;
+        }
+
+        let id_str = run_id.to_string();
+        self.run(&["run", "rerun", &id_str, "--failed"]).await?;
+        Ok(())
+    }
+
+    /// Re-runs a single job of a workflow run.
+    pub async fn rerun_workflow_job(&self, job_id: u64) -> Result<(), GitHubError> {
+        if let Some(api) = self.native().await? {
+            return api.rerun_job(job_id).await;
+        }
+
+        let id_str = job_id.to_string();
+        self.run(&["run", "rerun", "--job", &id_str]).await?;
+        Ok(())
+    }
+
+    /// Cancels a queued or in-progress workflow run.
+    pub async fn cancel_workflow_run(&self, run_id: u64) -> Result<(), GitHubError> {
+        if let Some(api) = self.native().await? {
+            return api.cancel_workflow_run(run_id).await;
+        }
+
+        let id_str = run_id.to_string();
+        self.run(&["run", "cancel", &id_str]).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn step(number: u64, name: &str, conclusion: &str, started_at: Option<&str>) -> WorkflowStep {
+        WorkflowStep {
+            number,
+            name: name.to_string(),
+            status: "completed".to_string(),
+            conclusion: conclusion.to_string(),
+            started_at: started_at.map(String::from),
+            completed_at: None,
+        }
+    }
+
+    fn job(steps: Vec<WorkflowStep>) -> WorkflowJob {
+        WorkflowJob {
+            database_id: 7,
+            name: "test".to_string(),
+            status: "completed".to_string(),
+            conclusion: "failure".to_string(),
+            url: "https://github.com/o/r/actions/runs/1/job/7".to_string(),
+            started_at: None,
+            completed_at: None,
+            steps,
+        }
+    }
+
+    #[test]
+    fn test_strip_ansi() {
+        assert_eq!(strip_ansi("\x1b[31;1merror\x1b[0m: boom"), "error: boom");
+        assert_eq!(
+            strip_ansi("\x1b]8;;https://x.dev\x1b\\link\x1b]8;;\x1b\\ done\x07"),
+            "link done\x07"
+        );
+        assert_eq!(strip_ansi("plain"), "plain");
+    }
+
+    #[test]
+    fn test_clean_line_strips_timestamp_and_control_chars() {
+        assert_eq!(
+            clean_line("2026-01-01T00:00:00.1234567Z \x1b[31merror\x1b[0m:\tboom\r"),
+            "error:\tboom"
+        );
+        assert_eq!(
+            clean_line("\u{feff}50%\r100%\x08\x07 \x1b[201~\x03done"),
+            "50%100% done"
+        );
+    }
+
+    #[test]
+    fn test_job_log_from_gh_output() {
+        let job = job(vec![
+            step(1, "Set up job", "success", None),
+            step(2, "Run tests", "failure", None),
+        ]);
+        let output = "test\tSet up job\t\u{feff}2024-01-01T00:00:00.1000000Z Runner ready\n\
+                      test\tRun tests\t2024-01-01T00:00:05.0000000Z \x1b[32mrunning\x1b[0m\r\n\
+                      test\tRun tests\t2024-01-01T00:00:06.0000000Z ##[error]assertion failed\n";
+
+        let log = JobLog::from_gh_output(&job, output);
+        assert_eq!(log.steps.len(), 2);
+        assert_eq!(log.steps[0].lines, vec!["Runner ready"]);
+        assert_eq!(log.steps[1].number, Some(2));
+        assert_eq!(log.steps[1].conclusion, "failure");
+        assert_eq!(
+            log.steps[1].lines,
+            vec!["running", "##[error]assertion failed"]
+        );
+
+        let failing = log.failing_steps(1);
+        assert_eq!(failing.len(), 1);
+        assert_eq!(failing[0].step_name.as_deref(), Some("Run tests"));
+        assert_eq!(failing[0].log, "running\n##[error]assertion failed");
+        assert!(failing[0].prompt.contains("failed at step \"Run tests\""));
+        assert!(failing[0].prompt.contains("##[error]assertion failed"));
+    }
+
+    #[test]
+    fn test_job_log_from_raw_log_splits_by_step_time() {
+        let job = job(vec![
+            step(1, "Set up job", "success", Some("2024-01-01T00:00:00Z")),
+            step(2, "Build", "success", Some("2024-01-01T00:00:03Z")),
+            step(3, "Run tests", "failure", Some("2024-01-01T00:00:09Z")),
+            step(4, "Post job cleanup", "skipped", None),
+        ]);
+        let raw = "\u{feff}2024-01-01T00:00:00.5000000Z Runner ready\n\
+                   2024-01-01T00:00:03.2000000Z Compiling\n\
+                   2024-01-01T00:00:09.0000000Z test a ... FAILED\n\
+                   panicked at src/lib.rs:3\n";
+
+        let log = JobLog::from_raw_log(&job, raw);
+        let names: Vec<&str> = log.steps.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["Set up job", "Build", "Run tests"]);
+        assert_eq!(
+            log.steps[2].lines,
+            vec!["test a ... FAILED", "panicked at src/lib.rs:3"]
+        );
+    }
+
+    #[test]
+    fn test_failing_steps_falls_back_to_job_log_and_truncates() {
+        let mut job = job(vec![]);
+        job.conclusion = "failure".to_string();
+        let raw: String = (0..FAILING_STEP_LOG_LINES + 5)
+            .map(|i| format!("2024-01-01T00:00:00.0000000Z line {i}\n"))
+            .collect();
+
+        let failing = JobLog::from_raw_log(&job, &raw).failing_steps(1);
+        assert_eq!(failing.len(), 1);
+        assert_eq!(failing[0].step_number, None);
+        assert_eq!(failing[0].omitted_lines, 5);
+        assert!(failing[0].log.starts_with("line 5\n"));
+        assert!(failing[0].prompt.contains("The last 200 lines"));
+    }
+}
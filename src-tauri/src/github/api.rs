@@ -0,0 +1,1692 @@
+//! Native GitHub REST/GraphQL client used when the `gh` CLI is not installed.
+//!
+//! Covers the same operations as the `gh`-backed methods in `ops.rs` and maps
+//! the REST responses onto the same types, so callers cannot tell which path
+//! served them. Authenticates with `GH_TOKEN` / `GITHUB_TOKEN` or a personal
+//! access token kept in the platform credential store.
+
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+use dashmap::DashMap;
+use reqwest::header::{HeaderMap, HeaderValue};
+use reqwest::Method;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use super::error::GitHubError;
+use super::ops::{
+    AuthStatus, Comment, CommentReactions, CreateIssueOptions, CreatePullRequestOptions,
+    EditOptions, IssueDetail, IssueFilter, IssueInfo, MergeMethod, PrAuthor, PrCheck, PrLabel, PrReview, PrReviewStatus,
+    PullRequestDetail, PullRequestFilter, PullRequestInfo, WorkflowJob, WorkflowRun,
+    WorkflowRunDetail, WorkflowStep,
+};
+use super::review::{graphql_data, graphql_string};
+use crate::forge::http::{encode_segment, ApiClient};
+use crate::forge::{self, ForgeError, ForgeKind, ForgeRemote};
+
+/// Response bodies each client keeps for conditional GETs.
+const ETAG_CACHE_CAPACITY: usize = 256;
+
+/// Clients built by [`GitHubApi::for_repo`], keyed by repository path, so
+/// repeated operations skip remote detection and keep their ETag cache.
+static CLIENTS: LazyLock<DashMap<PathBuf, CachedApi>> = LazyLock::new(DashMap::new);
+
+struct CachedApi {
+    remote: ForgeRemote,
+    token: Option<String>,
+    api: GitHubApi,
+}
+
+/// REST/GraphQL client bound to one GitHub repository.
+#[derive(Debug, Clone)]
+pub struct GitHubApi {
+    api: ApiClient,
+    graphql: ApiClient,
+    owner: String,
+    repo: String,
+}
+
+impl GitHubApi {
+    /// Creates a client for `owner/repo` against `api_base`
+    /// (`https://api.github.com`, or `https://<host>/api/v3` on Enterprise).
+    pub fn new(
+        api_base: &str,
+        graphql_url: &str,
+        owner: &str,
+        repo: &str,
+        token: Option<String>,
+    ) -> Self {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "accept",
+            HeaderValue::from_static("application/vnd.github+json"),
+        );
+        headers.insert(
+            "x-github-api-version",
+            HeaderValue::from_static("2022-11-28"),
+        );
+        let host = url::Url::parse(api_base)
+            .ok()
+            .and_then(|u| u.host_str().map(str::to_string))
+            .unwrap_or_else(|| "github.com".to_string());
+        let api = ApiClient::with_headers(api_base, &host, headers, "authorization", token, |t| {
+            format!("Bearer {}", t)
+        })
+        .with_etag_cache(ETAG_CACHE_CAPACITY);
+
+        Self {
+            graphql: api.with_base(graphql_url),
+            api,
+            owner: owner.to_string(),
+            repo: repo.to_string(),
+        }
+    }
+
+    /// Returns the client for the GitHub repository at `repo_path`, resolving
+    /// owner/repo from its remotes and the token from the environment or
+    /// credential store. Clients are reused until either changes.
+    pub async fn for_repo(repo_path: &Path) -> Result<Self, GitHubError> {
+        let remote = forge::detect_forge(repo_path)
+            .await
+            .ok()
+            .filter(|r| r.kind == ForgeKind::GitHub)
+            .ok_or(GitHubError::NotGitHubRepo)?;
+        let token = match env_token() {
+            Some(token) => Some(token),
+            None => forge::detect::repo_token(repo_path, &remote).await,
+        };
+        if let Some(cached) = CLIENTS.get(repo_path) {
+            if cached.remote == remote && cached.token == token {
+                return Ok(cached.api.clone());
+            }
+        }
+
+        let (owner, repo) = remote
+            .path
+            .rsplit_once('/')
+            .ok_or(GitHubError::NotGitHubRepo)?;
+        let (api_base, graphql_url) = if remote.host.eq_ignore_ascii_case("github.com") {
+            (
+                "https://api.github.com".to_string(),
+                "https://api.github.com/graphql".to_string(),
+            )
+        } else {
+            (
+                format!("{}/api/v3", remote.base_url),
+                format!("{}/api/graphql", remote.base_url),
+            )
+        };
+
+        let api = Self::new(&api_base, &graphql_url, owner, repo, token.clone());
+        CLIENTS.insert(
+            repo_path.to_path_buf(),
+            CachedApi {
+                remote,
+                token,
+                api: api.clone(),
+            },
+        );
+        Ok(api)
+    }
+
+    /// The repository owner (user or organization login).
+    pub fn owner(&self) -> &str {
+        &self.owner
+    }
+
+    /// The repository name.
+    pub fn repo(&self) -> &str {
+        &self.repo
+    }
+
+    fn repo_url(&self, rest: &str) -> String {
+        format!(
+            "/repos/{}/{}{}",
+            encode_segment(&self.owner),
+            encode_segment(&self.repo),
+            rest
+        )
+    }
+
+    /// Sends a conditional GET, replaying the cached body on 304.
+    async fn get<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        query: &[(&str, String)],
+    ) -> Result<T, GitHubError> {
+        self.api.get(path, query).await.map_err(|e| self.error(e))
+    }
+
+    /// Sends `body` as JSON and discards the response.
+    async fn send<B: Serialize + ?Sized>(
+        &self,
+        method: Method,
+        path: &str,
+        body: &B,
+    ) -> Result<(), GitHubError> {
+        self.api
+            .send_json_ignore(method, path, body)
+            .await
+            .map_err(|e| self.error(e))
+    }
+
+    /// Sends `body` as JSON and deserializes the JSON response.
+    async fn send_json<B: Serialize + ?Sized, T: DeserializeOwned>(
+        &self,
+        method: Method,
+        path: &str,
+        body: &B,
+    ) -> Result<T, GitHubError> {
+        self.api
+            .send_json(method, path, body)
+            .await
+            .map_err(|e| self.error(e))
+    }
+
+    /// Maps a shared client error onto the errors the `gh` path reports.
+    fn error(&self, err: ForgeError) -> GitHubError {
+        match err {
+            ForgeError::NotAuthenticated { .. } if self.api.has_token() => {
+                GitHubError::NotAuthenticated
+            }
+            // Private repositories look missing to anonymous requests
+            ForgeError::NotAuthenticated { .. } | ForgeError::Api { status: 404, .. }
+                if !self.api.has_token() =>
+            {
+                GitHubError::NoToken
+            }
+            ForgeError::RateLimitExceeded { reset_at, .. } => {
+                GitHubError::RateLimitExceeded { reset_at }
+            }
+            ForgeError::Api {
+                status, message, ..
+            } => GitHubError::ApiError { status, message },
+            ForgeError::Request(e) => GitHubError::Request(e),
+            ForgeError::JsonError(e) => GitHubError::JsonError(e),
+            ForgeError::PullRequestNotFound { number } => GitHubError::PullRequestNotFound { number },
+            ForgeError::IssueNotFound { number } => GitHubError::IssueNotFound { number },
+            ForgeError::GitHub(e) => e,
+            other => GitHubError::ApiError {
+                status: 0,
+                message: other.to_string(),
+            },
+        }
+    }
+
+    /// Runs a GraphQL query, returning the full `{ "data": ... }` response
+    /// like `gh api graphql` does.
+    pub async fn graphql(&self, query: &str) -> Result<serde_json::Value, GitHubError> {
+        if !self.api.has_token() {
+            return Err(GitHubError::NoToken);
+        }
+        self.graphql
+            .send_json(Method::POST, "", &json!({ "query": query }))
+            .await
+            .map_err(|e| self.error(e))
+    }
+
+    /// Checks whether the token is valid and who it belongs to.
+    pub async fn auth_status(&self) -> Result<AuthStatus, GitHubError> {
+        let logged_out = AuthStatus {
+            logged_in: false,
+            username: None,
+            scopes: vec![],
+        };
+        if !self.api.has_token() {
+            return Ok(logged_out);
+        }
+
+        let response = match self.api.get_response("/user").await {
+            Ok(response) => response,
+            Err(ForgeError::NotAuthenticated { .. }) => return Ok(logged_out),
+            Err(e) => return Err(self.error(e)),
+        };
+        let scopes = response
+            .headers()
+            .get("x-oauth-scopes")
+            .and_then(|v| v.to_str().ok())
+            .map(|s| {
+                s.split(',')
+                    .map(str::trim)
+                    .filter(|s| !s.is_empty())
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default();
+        let user: RestUser = response.json().await?;
+
+        Ok(AuthStatus {
+            logged_in: true,
+            username: Some(user.login),
+            scopes,
+        })
+    }
+
+    /// Lists pull requests. `merged` and `search` are applied client-side
+    /// since the pulls endpoint supports neither.
+    pub async fn list_pull_requests(
+        &self,
+        filter: &PullRequestFilter,
+    ) -> Result<Vec<PullRequestInfo>, GitHubError> {
+        let state = match filter.state.as_deref() {
+            Some("closed") | Some("merged") => "closed",
+            Some("all") => "all",
+            _ => "open",
+        };
+        let pulls: Vec<RestPull> = self
+            .get(
+                &self.repo_url("/pulls"),
+                &[
+                    ("state", state.to_string()),
+                    ("per_page", per_page(filter.limit)),
+                    ("sort", "updated".to_string()),
+                    ("direction", "desc".to_string()),
+                ],
+            )
+            .await?;
+
+        let only_merged = filter.state.as_deref() == Some("merged");
+        let search = filter.search.as_deref().map(str::to_lowercase);
+        Ok(pulls
+            .into_iter()
+            .filter(|pr| !only_merged || pr.merged_at.is_some())
+            .filter(|pr| matches_search(&pr.title, search.as_deref()))
+            .map(RestPull::into_info)
+            .collect())
+    }
+
+    /// Returns the open pull request whose head is `branch` in this
+    /// repository, if any.
+    pub async fn pull_request_for_branch(
+        &self,
+        branch: &str,
+    ) -> Result<Option<PullRequestInfo>, GitHubError> {
+        let pulls: Vec<RestPull> = self
+            .get(
+                &self.repo_url("/pulls"),
+                &[
+                    ("state", "open".to_string()),
+                    ("head", format!("{}:{}", self.owner, branch)),
+                    ("per_page", "1".to_string()),
+                ],
+            )
+            .await?;
+        Ok(pulls.into_iter().next().map(RestPull::into_info))
+    }
+
+    async fn pull(&self, number: u64) -> Result<RestPull, GitHubError> {
+        self.get(&self.repo_url(&format!("/pulls/{}", number)), &[])
+            .await
+            .map_err(|e| match e {
+                GitHubError::ApiError { status: 404, .. } => {
+                    GitHubError::PullRequestNotFound { number }
+                }
+                e => e,
+            })
+    }
+
+    async fn comments(&self, number: u64) -> Result<Vec<Comment>, GitHubError> {
+        let comments: Vec<RestComment> = self
+            .get(
+                &self.repo_url(&format!("/issues/{}/comments", number)),
+                &[("per_page", "100".to_string())],
+            )
+            .await?;
+        Ok(comments
+            .into_iter()
+            .map(RestComment::into_comment)
+            .collect())
+    }
+
+    /// Gets a pull request with its conversation comments.
+    pub async fn get_pull_request(&self, number: u64) -> Result<PullRequestDetail, GitHubError> {
+        let pull = self.pull(number).await?;
+        let comments = self.comments(number).await?;
+        Ok(pull.into_detail(comments))
+    }
+
+    /// Opens a pull request, then applies its labels, assignees, milestone,
+    /// reviewers and projects.
+    pub async fn create_pull_request(
+        &self,
+        options: &CreatePullRequestOptions,
+    ) -> Result<PullRequestInfo, GitHubError> {
+        // Resolve names first so a typo does not leave a half-configured PR
+        let milestone = match options.milestone.as_deref() {
+            Some(title) => Some(self.milestone_number(title).await?),
+            None => None,
+        };
+        let projects = self.project_ids(&options.projects).await?;
+
+        let pull: RestPull = self
+            .send_json(
+                Method::POST,
+                &self.repo_url("/pulls"),
+                &json!({
+                    "title": options.title,
+                    "body": options.body,
+                    "head": options.head,
+                    "base": options.base,
+                    "draft": options.draft,
+                }),
+            )
+            .await?;
+
+        let mut fields = serde_json::Map::new();
+        if !options.labels.is_empty() {
+            fields.insert("labels".into(), json!(options.labels));
+        }
+        if !options.assignees.is_empty() {
+            fields.insert("assignees".into(), json!(options.assignees));
+        }
+        if let Some(milestone) = milestone {
+            fields.insert("milestone".into(), json!(milestone));
+        }
+        let unchanged = fields.is_empty() && options.reviewers.is_empty() && projects.is_empty();
+        if unchanged {
+            return Ok(pull.into_info());
+        }
+
+        if !fields.is_empty() {
+            self.send(
+                Method::PATCH,
+                &self.repo_url(&format!("/issues/{}", pull.number)),
+                &fields,
+            )
+            .await?;
+        }
+        self.request_reviewers(Method::POST, pull.number, &options.reviewers)
+            .await?;
+        self.add_to_projects(&pull.node_id, &projects).await?;
+        Ok(self.pull(pull.number).await?.into_info())
+    }
+
+    /// Edits an issue or, with `pull_request`, a pull request.
+    pub async fn edit(
+        &self,
+        number: u64,
+        options: &EditOptions,
+        pull_request: bool,
+    ) -> Result<(), GitHubError> {
+        let issue_url = self.repo_url(&format!("/issues/{}", number));
+
+        let mut fields = serde_json::Map::new();
+        if let Some(title) = &options.title {
+            fields.insert("title".into(), json!(title));
+        }
+        if let Some(body) = &options.body {
+            fields.insert("body".into(), json!(body));
+        }
+        match options.milestone.as_deref() {
+            Some("") => {
+                fields.insert("milestone".into(), serde_json::Value::Null);
+            }
+            Some(title) => {
+                let milestone = self.milestone_number(title).await?;
+                fields.insert("milestone".into(), json!(milestone));
+            }
+            None => {}
+        }
+        if !fields.is_empty() {
+            self.send(Method::PATCH, &issue_url, &fields).await?;
+        }
+
+        if !options.add_labels.is_empty() {
+            let url = format!("{}/labels", issue_url);
+            self.send(Method::POST, &url, &json!({ "labels": options.add_labels }))
+                .await?;
+        }
+        for label in &options.remove_labels {
+            let url = format!("{}/labels/{}", issue_url, encode_segment(label));
+            self.send(Method::DELETE, &url, &json!({})).await?;
+        }
+        for (method, assignees) in [
+            (Method::POST, &options.add_assignees),
+            (Method::DELETE, &options.remove_assignees),
+        ] {
+            if !assignees.is_empty() {
+                let url = format!("{}/assignees", issue_url);
+                self.send(method, &url, &json!({ "assignees": assignees }))
+                    .await?;
+            }
+        }
+        if pull_request {
+            self.request_reviewers(Method::POST, number, &options.add_reviewers)
+                .await?;
+            self.request_reviewers(Method::DELETE, number, &options.remove_reviewers)
+                .await?;
+        }
+
+        if !options.add_projects.is_empty() {
+            let projects = self.project_ids(&options.add_projects).await?;
+            let issue: RestIssue = self.get(&issue_url, &[]).await?;
+            self.add_to_projects(&issue.node_id, &projects).await?;
+        }
+        if !options.remove_projects.is_empty() {
+            let projects = self.project_ids(&options.remove_projects).await?;
+            self.remove_from_projects(number, &projects).await?;
+        }
+        Ok(())
+    }
+
+    /// Requests (`POST`) or withdraws (`DELETE`) reviews from users and
+    /// `org/team` slugs.
+    async fn request_reviewers(
+        &self,
+        method: Method,
+        number: u64,
+        reviewers: &[String],
+    ) -> Result<(), GitHubError> {
+        if reviewers.is_empty() {
+            return Ok(());
+        }
+        let (teams, users): (Vec<&String>, Vec<&String>) =
+            reviewers.iter().partition(|r| r.contains('/'));
+        let teams: Vec<&str> = teams
+            .iter()
+            .filter_map(|t| t.split_once('/').map(|(_, slug)| slug))
+            .collect();
+        self.send(
+            method,
+            &self.repo_url(&format!("/pulls/{}/requested_reviewers", number)),
+            &json!({ "reviewers": users, "team_reviewers": teams }),
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Looks up a milestone's number by its title.
+    async fn milestone_number(&self, title: &str) -> Result<u64, GitHubError> {
+        #[derive(Deserialize)]
+        struct RestMilestone {
+            number: u64,
+            title: String,
+        }
+
+        let milestones: Vec<RestMilestone> = self
+            .get(
+                &self.repo_url("/milestones"),
+                &[
+                    ("state", "all".to_string()),
+                    ("per_page", "100".to_string()),
+                ],
+            )
+            .await?;
+        milestones
+            .into_iter()
+            .find(|m| m.title == title)
+            .map(|m| m.number)
+            .ok_or_else(|| GitHubError::MilestoneNotFound {
+                title: title.to_string(),
+            })
+    }
+
+    /// Resolves project titles to node ids, looking at the projects linked
+    /// to the repository and those owned by its user or organization.
+    async fn project_ids(&self, titles: &[String]) -> Result<Vec<String>, GitHubError> {
+        if titles.is_empty() {
+            return Ok(Vec::new());
+        }
+        #[derive(Deserialize)]
+        struct Project {
+            id: String,
+            title: String,
+        }
+
+        let query = format!(
+            r#"query {{
+                repository(owner: {}, name: {}) {{
+                    projectsV2(first: 100) {{ nodes {{ id title }} }}
+                    owner {{ ... on ProjectV2Owner {{ projectsV2(first: 100) {{ nodes {{ id title }} }} }} }}
+                }}
+            }}"#,
+            graphql_string(&self.owner),
+            graphql_string(&self.repo),
+        );
+        let json = self.graphql(&query).await?;
+        let repository = graphql_data(&json, "/data/repository")?;
+        let mut projects: Vec<Project> = Vec::new();
+        for pointer in ["/projectsV2/nodes", "/owner/projectsV2/nodes"] {
+            if let Some(nodes) = repository.pointer(pointer) {
+                projects.extend(serde_json::from_value::<Vec<Project>>(nodes.clone())?);
+            }
+        }
+
+        titles
+            .iter()
+            .map(|title| {
+                projects
+                    .iter()
+                    .find(|p| &p.title == title)
+                    .map(|p| p.id.clone())
+                    .ok_or_else(|| GitHubError::ProjectNotFound {
+                        title: title.clone(),
+                    })
+            })
+            .collect()
+    }
+
+    /// Adds an issue or pull request (by node id) to projects.
+    async fn add_to_projects(
+        &self,
+        content_id: &str,
+        project_ids: &[String],
+    ) -> Result<(), GitHubError> {
+        let mutations: Vec<String> = project_ids
+            .iter()
+            .enumerate()
+            .map(|(i, project)| {
+                format!(
+                    "p{}: addProjectV2ItemById(input: {{ projectId: {}, contentId: {} }}) {{ item {{ id }} }}",
+                    i,
+                    graphql_string(project),
+                    graphql_string(content_id),
+                )
+            })
+            .collect();
+        self.mutate_projects(&mutations).await
+    }
+
+    /// Removes an issue or pull request from the given projects.
+    async fn remove_from_projects(
+        &self,
+        number: u64,
+        project_ids: &[String],
+    ) -> Result<(), GitHubError> {
+        #[derive(Deserialize)]
+        struct Item {
+            id: String,
+            project: ProjectRef,
+        }
+        #[derive(Deserialize)]
+        struct ProjectRef {
+            id: String,
+        }
+
+        let query = format!(
+            r#"query {{
+                repository(owner: {}, name: {}) {{
+                    issueOrPullRequest(number: {}) {{
+                        ... on Issue {{ projectItems(first: 100) {{ nodes {{ id project {{ id }} }} }} }}
+                        ... on PullRequest {{ projectItems(first: 100) {{ nodes {{ id project {{ id }} }} }} }}
+                    }}
+                }}
+            }}"#,
+            graphql_string(&self.owner),
+            graphql_string(&self.repo),
+            number,
+        );
+        let json = self.graphql(&query).await?;
+        let nodes = graphql_data(
+            &json,
+            "/data/repository/issueOrPullRequest/projectItems/nodes",
+        )?;
+        let items: Vec<Item> = serde_json::from_value(nodes.clone())?;
+
+        let mutations: Vec<String> = items
+            .iter()
+            .filter(|item| project_ids.contains(&item.project.id))
+            .enumerate()
+            .map(|(i, item)| {
+                format!(
+                    "p{}: deleteProjectV2Item(input: {{ projectId: {}, itemId: {} }}) {{ deletedItemId }}",
+                    i,
+                    graphql_string(&item.project.id),
+                    graphql_string(&item.id),
+                )
+            })
+            .collect();
+        self.mutate_projects(&mutations).await
+    }
+
+    /// Runs aliased project mutations (`p0`, `p1`, ...) in one request.
+    async fn mutate_projects(&self, mutations: &[String]) -> Result<(), GitHubError> {
+        if mutations.is_empty() {
+            return Ok(());
+        }
+        let json = self
+            .graphql(&format!("mutation {{ {} }}", mutations.join(" ")))
+            .await?;
+        for i in 0..mutations.len() {
+            graphql_data(&json, &format!("/data/p{}", i))?;
+        }
+        Ok(())
+    }
+
+    /// Returns the repository's default branch.
+    pub async fn default_branch(&self) -> Result<String, GitHubError> {
+        #[derive(Deserialize)]
+        struct RestRepo {
+            default_branch: String,
+        }
+        let repo: RestRepo = self.get(&self.repo_url(""), &[]).await?;
+        Ok(repo.default_branch)
+    }
+
+    /// Lists check runs and commit statuses on the pull request's head commit,
+    /// normalized to `gh pr checks` buckets.
+    pub async fn pr_checks(&self, number: u64) -> Result<Vec<PrCheck>, GitHubError> {
+        #[derive(Deserialize)]
+        struct CheckRuns {
+            check_runs: Vec<RestCheckRun>,
+        }
+        #[derive(Deserialize)]
+        struct CombinedStatus {
+            statuses: Vec<RestStatus>,
+        }
+
+        let sha = self.pull(number).await?.head.sha;
+        let runs: CheckRuns = self
+            .get(
+                &self.repo_url(&format!("/commits/{}/check-runs", sha)),
+                &[("per_page", "100".to_string())],
+            )
+            .await?;
+        let statuses: CombinedStatus = self
+            .get(&self.repo_url(&format!("/commits/{}/status", sha)), &[])
+            .await?;
+
+        Ok(runs
+            .check_runs
+            .into_iter()
+            .map(RestCheckRun::into_check)
+            .chain(statuses.statuses.into_iter().map(RestStatus::into_check))
+            .collect())
+    }
+
+    /// Returns the review decision, pending review requests and latest reviews.
+    pub async fn pr_review_status(&self, number: u64) -> Result<PrReviewStatus, GitHubError> {
+        let query = format!(
+            r#"{{
+                repository(owner: "{}", name: "{}") {{
+                    pullRequest(number: {}) {{
+                        reviewDecision
+                        reviewRequests(first: 50) {{
+                            nodes {{
+                                requestedReviewer {{
+                                    ... on User {{ login }}
+                                    ... on Team {{ name }}
+                                }}
+                            }}
+                        }}
+                        latestReviews(first: 50) {{
+                            nodes {{
+                                author {{ login }}
+                                state
+                                body
+                                submittedAt
+                            }}
+                        }}
+                    }}
+                }}
+            }}"#,
+            self.owner, self.repo, number
+        );
+        let json = self.graphql(&query).await?;
+        let pull = json
+            .pointer("/data/repository/pullRequest")
+            .filter(|v| !v.is_null())
+            .ok_or(GitHubError::PullRequestNotFound { number })?;
+
+        #[derive(Deserialize)]
+        struct Reviewer {
+            #[serde(default)]
+            login: Option<String>,
+            #[serde(default)]
+            name: Option<String>,
+        }
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct ReviewRequest {
+            requested_reviewer: Option<Reviewer>,
+        }
+
+        let requests: Vec<ReviewRequest> = serde_json::from_value(
+            pull.pointer("/reviewRequests/nodes")
+                .cloned()
+                .unwrap_or_default(),
+        )
+        .unwrap_or_default();
+        let latest_reviews: Vec<PrReview> = serde_json::from_value(
+            pull.pointer("/latestReviews/nodes")
+                .cloned()
+                .unwrap_or_default(),
+        )
+        .unwrap_or_default();
+
+        Ok(PrReviewStatus {
+            review_decision: pull
+                .get("reviewDecision")
+                .and_then(|d| d.as_str())
+                .filter(|d| !d.is_empty())
+                .map(str::to_string),
+            requested_reviewers: requests
+                .into_iter()
+                .filter_map(|r| r.requested_reviewer)
+                .filter_map(|r| r.login.or(r.name))
+                .collect(),
+            latest_reviews,
+        })
+    }
+
+    /// Lists recent workflow runs, optionally only for one branch.
+    pub async fn list_workflow_runs(
+        &self,
+        branch: Option<&str>,
+        limit: u32,
+    ) -> Result<Vec<WorkflowRun>, GitHubError> {
+        #[derive(Deserialize)]
+        struct Runs {
+            workflow_runs: Vec<RestRun>,
+        }
+
+        let mut query = vec![("per_page", per_page(Some(limit)))];
+        if let Some(branch) = branch {
+            query.push(("branch", branch.to_string()));
+        }
+        let runs: Runs = self.get(&self.repo_url("/actions/runs"), &query).await?;
+        Ok(runs
+            .workflow_runs
+            .into_iter()
+            .map(RestRun::into_run)
+            .collect())
+    }
+
+    async fn jobs(&self, run_id: u64) -> Result<Vec<RestJob>, GitHubError> {
+        #[derive(Deserialize)]
+        struct Jobs {
+            jobs: Vec<RestJob>,
+        }
+        let jobs: Jobs = self
+            .get(
+                &self.repo_url(&format!("/actions/runs/{}/jobs", run_id)),
+                &[("per_page", "100".to_string())],
+            )
+            .await?;
+        Ok(jobs.jobs)
+    }
+
+    /// Gets a workflow run with its jobs and steps.
+    pub async fn get_workflow_run(&self, run_id: u64) -> Result<WorkflowRunDetail, GitHubError> {
+        let run: RestRun = self
+            .get(&self.repo_url(&format!("/actions/runs/{}", run_id)), &[])
+            .await?;
+        let jobs = self.jobs(run_id).await?;
+        Ok(WorkflowRunDetail {
+            run: run.into_run(),
+            jobs: jobs.into_iter().map(RestJob::into_job).collect(),
+        })
+    }
+
+    /// Returns the raw log of a job, one timestamped line per output line.
+    pub async fn job_log(&self, job_id: u64) -> Result<String, GitHubError> {
+        let response = self
+            .api
+            .get_response(&self.repo_url(&format!("/actions/jobs/{}/logs", job_id)))
+            .await
+            .map_err(|e| self.error(e))?;
+        Ok(response.text().await?)
+    }
+
+    /// Returns the logs of the run's failed jobs, each line prefixed with
+    /// the job name like `gh run view --log-failed`.
+    pub async fn workflow_run_failed_log(&self, run_id: u64) -> Result<String, GitHubError> {
+        let mut log = String::new();
+        for job in self.jobs(run_id).await? {
+            if job.conclusion.as_deref() != Some("failure") {
+                continue;
+            }
+            let text = self.job_log(job.id).await?;
+            for line in text.lines() {
+                log.push_str(&job.name);
+                log.push('\t');
+                log.push_str(line);
+                log.push('\n');
+            }
+        }
+        Ok(log)
+    }
+
+    /// Re-runs the failed jobs of a workflow run (and the jobs depending on them).
+    pub async fn rerun_failed_jobs(&self, run_id: u64) -> Result<(), GitHubError> {
+        self.send(
+            Method::POST,
+            &self.repo_url(&format!("/actions/runs/{}/rerun-failed-jobs", run_id)),
+            &json!({}),
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Re-runs a single job of a workflow run.
+    pub async fn rerun_job(&self, job_id: u64) -> Result<(), GitHubError> {
+        self.send(
+            Method::POST,
+            &self.repo_url(&format!("/actions/jobs/{}/rerun", job_id)),
+            &json!({}),
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Cancels a queued or in-progress workflow run.
+    pub async fn cancel_workflow_run(&self, run_id: u64) -> Result<(), GitHubError> {
+        self.send(
+            Method::POST,
+            &self.repo_url(&format!("/actions/runs/{}/cancel", run_id)),
+            &json!({}),
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Merges a pull request, deleting its head branch afterwards if asked.
+    pub async fn merge_pull_request(
+        &self,
+        number: u64,
+        method: MergeMethod,
+        delete_branch: bool,
+    ) -> Result<(), GitHubError> {
+        let pull = self.pull(number).await?;
+        let merge_method = match method {
+            MergeMethod::Merge => "merge",
+            MergeMethod::Squash => "squash",
+            MergeMethod::Rebase => "rebase",
+        };
+        self.send(
+            Method::PUT,
+            &self.repo_url(&format!("/pulls/{}/merge", number)),
+            &json!({ "merge_method": merge_method }),
+        )
+        .await?;
+
+        if delete_branch {
+            self.send(
+                Method::DELETE,
+                &self.repo_url(&format!("/git/refs/heads/{}", pull.head.ref_name)),
+                &json!({}),
+            )
+            .await?;
+        }
+        Ok(())
+    }
+
+    /// Closes a pull request without merging.
+    pub async fn close_pull_request(&self, number: u64) -> Result<(), GitHubError> {
+        self.send(
+            Method::PATCH,
+            &self.repo_url(&format!("/pulls/{}", number)),
+            &json!({ "state": "closed" }),
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Adds a comment to an issue or pull request (they share numbering).
+    pub async fn comment(&self, number: u64, body: &str) -> Result<(), GitHubError> {
+        self.send(
+            Method::POST,
+            &self.repo_url(&format!("/issues/{}/comments", number)),
+            &json!({ "body": body }),
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Lists issues, skipping the pull requests the issues endpoint includes.
+    pub async fn list_issues(&self, filter: &IssueFilter) -> Result<Vec<IssueInfo>, GitHubError> {
+        let state = match filter.state.as_deref() {
+            Some("closed") => "closed",
+            Some("all") => "all",
+            _ => "open",
+        };
+        let issues: Vec<RestIssue> = self
+            .get(
+                &self.repo_url("/issues"),
+                &[
+                    ("state", state.to_string()),
+                    ("per_page", per_page(filter.limit)),
+                ],
+            )
+            .await?;
+
+        let search = filter.search.as_deref().map(str::to_lowercase);
+        Ok(issues
+            .into_iter()
+            .filter(|i| i.pull_request.is_none())
+            .filter(|i| matches_search(&i.title, search.as_deref()))
+            .map(RestIssue::into_info)
+            .collect())
+    }
+
+    /// Gets an issue with its comments.
+    pub async fn get_issue(&self, number: u64) -> Result<IssueDetail, GitHubError> {
+        let issue: RestIssue = self
+            .get(&self.repo_url(&format!("/issues/{}", number)), &[])
+            .await
+            .map_err(|e| match e {
+                GitHubError::ApiError { status: 404, .. } => GitHubError::IssueNotFound { number },
+                e => e,
+            })?;
+        let comments = self.comments(number).await?;
+        let body = issue.body.clone().unwrap_or_default();
+        let info = issue.into_info();
+
+        Ok(IssueDetail {
+            number: info.number,
+            title: info.title,
+            body,
+            state: info.state,
+            author: info.author,
+            created_at: info.created_at,
+            updated_at: info.updated_at,
+            url: info.url,
+            labels: info.labels,
+            closed_at: info.closed_at,
+            comments,
+        })
+    }
+
+    /// Opens an issue with its labels, assignees, milestone and projects.
+    pub async fn create_issue(
+        &self,
+        options: &CreateIssueOptions,
+    ) -> Result<IssueInfo, GitHubError> {
+        let milestone = match options.milestone.as_deref() {
+            Some(title) => Some(self.milestone_number(title).await?),
+            None => None,
+        };
+        let projects = self.project_ids(&options.projects).await?;
+
+        let issue: RestIssue = self
+            .send_json(
+                Method::POST,
+                &self.repo_url("/issues"),
+                &json!({
+                    "title": options.title,
+                    "body": options.body,
+                    "labels": options.labels,
+                    "assignees": options.assignees,
+                    "milestone": milestone,
+                }),
+            )
+            .await?;
+        self.add_to_projects(&issue.node_id, &projects).await?;
+        Ok(issue.into_info())
+    }
+
+    /// Sets an issue's state to `open` or `closed`.
+    pub async fn set_issue_state(&self, number: u64, state: &str) -> Result<(), GitHubError> {
+        self.send(
+            Method::PATCH,
+            &self.repo_url(&format!("/issues/{}", number)),
+            &json!({ "state": state }),
+        )
+        .await?;
+        Ok(())
+    }
+}
+
+/// Reads a token from the variables `gh` itself honours.
+fn env_token() -> Option<String> {
+    ["GH_TOKEN", "GITHUB_TOKEN"]
+        .iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|t| !t.trim().is_empty())
+}
+
+fn per_page(limit: Option<u32>) -> String {
+    limit.unwrap_or(50).clamp(1, 100).to_string()
+}
+
+fn matches_search(title: &str, search: Option<&str>) -> bool {
+    search.is_none_or(|s| title.to_lowercase().contains(s))
+}
+
+#[derive(Deserialize)]
+struct RestUser {
+    login: String,
+}
+
+impl RestUser {
+    fn into_author(self) -> PrAuthor {
+        PrAuthor { login: self.login }
+    }
+}
+
+/// `user` is null for deleted accounts; gh shows those as `ghost`.
+fn author(user: Option<RestUser>) -> PrAuthor {
+    user.map(RestUser::into_author).unwrap_or_else(|| PrAuthor {
+        login: "ghost".to_string(),
+    })
+}
+
+#[derive(Deserialize)]
+struct RestRef {
+    #[serde(rename = "ref")]
+    ref_name: String,
+    sha: String,
+}
+
+#[derive(Deserialize)]
+struct RestPull {
+    number: u64,
+    #[serde(default)]
+    node_id: String,
+    title: String,
+    #[serde(default)]
+    body: Option<String>,
+    state: String,
+    user: Option<RestUser>,
+    created_at: String,
+    updated_at: String,
+    head: RestRef,
+    base: RestRef,
+    #[serde(default)]
+    draft: bool,
+    html_url: String,
+    #[serde(default)]
+    labels: Vec<PrLabel>,
+    #[serde(default)]
+    merged_at: Option<String>,
+    #[serde(default)]
+    closed_at: Option<String>,
+    /// Only present on the single-PR endpoint.
+    #[serde(default)]
+    additions: Option<u64>,
+    #[serde(default)]
+    deletions: Option<u64>,
+    #[serde(default)]
+    changed_files: Option<u64>,
+    /// `null` while GitHub is still computing mergeability.
+    #[serde(default)]
+    mergeable: Option<bool>,
+}
+
+impl RestPull {
+    fn into_info(self) -> PullRequestInfo {
+        PullRequestInfo {
+            number: self.number,
+            state: if self.merged_at.is_some() {
+                "MERGED".to_string()
+            } else {
+                self.state.to_uppercase()
+            },
+            title: self.title,
+            author: author(self.user),
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+            head_ref_name: self.head.ref_name,
+            base_ref_name: self.base.ref_name,
+            is_draft: self.draft,
+            additions: self.additions.unwrap_or(0),
+            deletions: self.deletions.unwrap_or(0),
+            url: self.html_url,
+            labels: self.labels,
+            merged_at: self.merged_at,
+            closed_at: self.closed_at,
+        }
+    }
+
+    fn into_detail(self, comments: Vec<Comment>) -> PullRequestDetail {
+        let body = self.body.clone().unwrap_or_default();
+        let changed_files = self.changed_files.unwrap_or(0);
+        let mergeable = match self.mergeable {
+            Some(true) => "MERGEABLE",
+            Some(false) => "CONFLICTING",
+            None => "UNKNOWN",
+        }
+        .to_string();
+        let info = self.into_info();
+
+        PullRequestDetail {
+            number: info.number,
+            title: info.title,
+            body,
+            state: info.state,
+            author: info.author,
+            created_at: info.created_at,
+            updated_at: info.updated_at,
+            head_ref_name: info.head_ref_name,
+            base_ref_name: info.base_ref_name,
+            is_draft: info.is_draft,
+            additions: info.additions,
+            deletions: info.deletions,
+            changed_files,
+            url: info.url,
+            labels: info.labels,
+            merged_at: info.merged_at,
+            closed_at: info.closed_at,
+            mergeable,
+            review_decision: None,
+            comments,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct RestIssue {
+    number: u64,
+    #[serde(default)]
+    node_id: String,
+    title: String,
+    #[serde(default)]
+    body: Option<String>,
+    state: String,
+    user: Option<RestUser>,
+    created_at: String,
+    updated_at: String,
+    html_url: String,
+    #[serde(default)]
+    labels: Vec<PrLabel>,
+    #[serde(default)]
+    closed_at: Option<String>,
+    /// Set when the "issue" is actually a pull request.
+    #[serde(default)]
+    pull_request: Option<serde_json::Value>,
+}
+
+impl RestIssue {
+    fn into_info(self) -> IssueInfo {
+        IssueInfo {
+            number: self.number,
+            title: self.title,
+            state: self.state.to_uppercase(),
+            author: author(self.user),
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+            url: self.html_url,
+            labels: self.labels,
+            closed_at: self.closed_at,
+        }
+    }
+}
+
+#[derive(Deserialize, Default)]
+struct RestReactions {
+    #[serde(default)]
+    total_count: u64,
+    #[serde(rename = "+1", default)]
+    thumbs_up: u64,
+    #[serde(rename = "-1", default)]
+    thumbs_down: u64,
+    #[serde(default)]
+    laugh: u64,
+    #[serde(default)]
+    hooray: u64,
+    #[serde(default)]
+    confused: u64,
+    #[serde(default)]
+    heart: u64,
+    #[serde(default)]
+    rocket: u64,
+    #[serde(default)]
+    eyes: u64,
+}
+
+#[derive(Deserialize)]
+struct RestComment {
+    /// GraphQL node id, matching the ids gh returns.
+    node_id: String,
+    user: Option<RestUser>,
+    body: String,
+    created_at: String,
+    #[serde(default)]
+    updated_at: Option<String>,
+    #[serde(default)]
+    reactions: RestReactions,
+}
+
+impl RestComment {
+    fn into_comment(self) -> Comment {
+        let r = self.reactions;
+        Comment {
+            id: self.node_id,
+            author: author(self.user),
+            body: self.body,
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+            reactions: CommentReactions {
+                total_count: r.total_count,
+                thumbs_up: r.thumbs_up,
+                thumbs_down: r.thumbs_down,
+                laugh: r.laugh,
+                hooray: r.hooray,
+                confused: r.confused,
+                heart: r.heart,
+                rocket: r.rocket,
+                eyes: r.eyes,
+            },
+            is_answer: false,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct RestCheckOutput {
+    #[serde(default)]
+    title: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct RestCheckRun {
+    name: String,
+    status: String,
+    #[serde(default)]
+    conclusion: Option<String>,
+    #[serde(default)]
+    html_url: Option<String>,
+    #[serde(default)]
+    started_at: Option<String>,
+    #[serde(default)]
+    completed_at: Option<String>,
+    #[serde(default)]
+    output: Option<RestCheckOutput>,
+}
+
+impl RestCheckRun {
+    fn into_check(self) -> PrCheck {
+        let (state, bucket) = match (self.status.as_str(), self.conclusion.as_deref()) {
+            ("completed", Some(conclusion)) => {
+                let bucket = match conclusion {
+                    "success" | "neutral" => "pass",
+                    "skipped" => "skipping",
+                    "cancelled" => "cancel",
+                    _ => "fail",
+                };
+                (conclusion.to_uppercase(), bucket)
+            }
+            (status, _) => (status.to_uppercase(), "pending"),
+        };
+        PrCheck {
+            name: self.name,
+            state,
+            bucket: bucket.to_string(),
+            link: self.html_url.unwrap_or_default(),
+            workflow: String::new(),
+            description: self.output.and_then(|o| o.title).unwrap_or_default(),
+            started_at: self.started_at,
+            completed_at: self.completed_at,
+        }
+    }
+}
+
+/// A legacy commit status (from the statuses API rather than Checks).
+#[derive(Deserialize)]
+struct RestStatus {
+    context: String,
+    state: String,
+    #[serde(default)]
+    target_url: Option<String>,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    created_at: Option<String>,
+    #[serde(default)]
+    updated_at: Option<String>,
+}
+
+impl RestStatus {
+    fn into_check(self) -> PrCheck {
+        let bucket = match self.state.as_str() {
+            "success" => "pass",
+            "pending" => "pending",
+            _ => "fail",
+        };
+        let completed_at = if bucket == "pending" {
+            None
+        } else {
+            self.updated_at
+        };
+        PrCheck {
+            name: self.context,
+            state: self.state.to_uppercase(),
+            bucket: bucket.to_string(),
+            link: self.target_url.unwrap_or_default(),
+            workflow: String::new(),
+            description: self.description.unwrap_or_default(),
+            started_at: self.created_at,
+            completed_at,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct RestRun {
+    id: u64,
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    display_title: String,
+    status: String,
+    #[serde(default)]
+    conclusion: Option<String>,
+    #[serde(default)]
+    event: String,
+    #[serde(default)]
+    head_branch: Option<String>,
+    #[serde(default)]
+    head_sha: String,
+    html_url: String,
+    created_at: String,
+    #[serde(default)]
+    updated_at: String,
+}
+
+impl RestRun {
+    fn into_run(self) -> WorkflowRun {
+        let name = self.name.unwrap_or_default();
+        WorkflowRun {
+            database_id: self.id,
+            workflow_name: name.clone(),
+            name,
+            display_title: self.display_title,
+            status: self.status,
+            conclusion: self.conclusion.unwrap_or_default(),
+            event: self.event,
+            head_branch: self.head_branch.unwrap_or_default(),
+            head_sha: self.head_sha,
+            url: self.html_url,
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct RestStep {
+    number: u64,
+    name: String,
+    status: String,
+    #[serde(default)]
+    conclusion: Option<String>,
+    #[serde(default)]
+    started_at: Option<String>,
+    #[serde(default)]
+    completed_at: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct RestJob {
+    id: u64,
+    name: String,
+    status: String,
+    #[serde(default)]
+    conclusion: Option<String>,
+    #[serde(default)]
+    html_url: Option<String>,
+    #[serde(default)]
+    started_at: Option<String>,
+    #[serde(default)]
+    completed_at: Option<String>,
+    #[serde(default)]
+    steps: Vec<RestStep>,
+}
+
+impl RestJob {
+    fn into_job(self) -> WorkflowJob {
+        WorkflowJob {
+            database_id: self.id,
+            name: self.name,
+            status: self.status,
+            conclusion: self.conclusion.unwrap_or_default(),
+            url: self.html_url.unwrap_or_default(),
+            started_at: self.started_at,
+            completed_at: self.completed_at,
+            steps: self
+                .steps
+                .into_iter()
+                .map(|s| WorkflowStep {
+                    number: s.number,
+                    name: s.name,
+                    status: s.status,
+                    conclusion: s.conclusion.unwrap_or_default(),
+                    started_at: s.started_at,
+                    completed_at: s.completed_at,
+                })
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::forge::mock::{MockResponse, MockServer};
+
+    const PULL_JSON: &str = r#"{
+        "number": 12, "title": "Add cache", "body": null, "state": "closed",
+        "user": {"login": "octocat"},
+        "created_at": "2026-01-01T00:00:00Z", "updated_at": "2026-01-02T00:00:00Z",
+        "head": {"ref": "feature/cache", "sha": "abc123"}, "base": {"ref": "main", "sha": "def456"},
+        "draft": false, "html_url": "https://github.com/o/r/pull/12",
+        "labels": [{"name": "perf", "color": "0e8a16"}],
+        "merged_at": "2026-01-02T00:00:00Z", "closed_at": "2026-01-02T00:00:00Z",
+        "additions": 40, "deletions": 2, "changed_files": 3, "mergeable": null
+    }"#;
+
+    fn api(server: &MockServer, token: Option<&str>) -> GitHubApi {
+        GitHubApi::new(
+            &server.url,
+            &format!("{}/graphql", server.url),
+            "o",
+            "r",
+            token.map(str::to_string),
+        )
+    }
+
+    #[test]
+    fn test_rate_limit_error_message_includes_reset_time() {
+        let err = GitHubError::RateLimitExceeded {
+            reset_at: Some(1767225600),
+        };
+        assert!(err.to_string().contains("00:00 UTC"));
+        let err = GitHubError::RateLimitExceeded { reset_at: None };
+        assert!(err.to_string().contains("Try again later"));
+    }
+
+    #[test]
+    fn test_check_run_buckets() {
+        let run = |status: &str, conclusion: Option<&str>| RestCheckRun {
+            name: "build".into(),
+            status: status.into(),
+            conclusion: conclusion.map(str::to_string),
+            html_url: None,
+            started_at: None,
+            completed_at: None,
+            output: None,
+        };
+        assert_eq!(
+            run("completed", Some("success")).into_check().bucket,
+            "pass"
+        );
+        assert_eq!(
+            run("completed", Some("timed_out")).into_check().bucket,
+            "fail"
+        );
+        assert_eq!(
+            run("completed", Some("skipped")).into_check().bucket,
+            "skipping"
+        );
+        let pending = run("in_progress", None).into_check();
+        assert_eq!(pending.bucket, "pending");
+        assert_eq!(pending.state, "IN_PROGRESS");
+    }
+
+    #[tokio::test]
+    async fn test_get_pull_request_maps_rest_fields() {
+        let comments = r#"[{"node_id": "IC_1", "user": {"login": "hubot"}, "body": "ship it",
+                            "created_at": "2026-01-01T00:00:00Z",
+                            "reactions": {"total_count": 2, "+1": 1, "heart": 1}}]"#;
+        let server = MockServer::start(vec![
+            ("GET /repos/o/r/pulls/12", PULL_JSON.to_string()),
+            ("GET /repos/o/r/issues/12/comments", comments.to_string()),
+        ])
+        .await;
+
+        let detail = api(&server, Some("t0k"))
+            .get_pull_request(12)
+            .await
+            .unwrap();
+        assert_eq!(detail.state, "MERGED");
+        assert_eq!(detail.body, "");
+        assert_eq!(detail.head_ref_name, "feature/cache");
+        assert_eq!(detail.changed_files, 3);
+        assert_eq!(detail.mergeable, "UNKNOWN");
+        assert_eq!(detail.comments[0].id, "IC_1");
+        assert_eq!(detail.comments[0].reactions.thumbs_up, 1);
+        assert_eq!(
+            server.requests()[0].header("authorization"),
+            Some("Bearer t0k")
+        );
+
+        assert!(matches!(
+            api(&server, Some("t0k")).get_pull_request(99).await,
+            Err(GitHubError::PullRequestNotFound { number: 99 })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_conditional_get_replays_cached_body() {
+        let server = MockServer::start(vec![(
+            "GET /repos/o/r/pulls",
+            MockResponse::json(format!("[{}]", PULL_JSON)).with_etag("\"v1\""),
+        )])
+        .await;
+        let api = api(&server, None);
+        let filter = PullRequestFilter::default();
+
+        let first = api.list_pull_requests(&filter).await.unwrap();
+        let second = api.list_pull_requests(&filter).await.unwrap();
+        assert_eq!(first.len(), 1);
+        assert_eq!(second[0].number, 12);
+
+        let requests = server.requests();
+        assert_eq!(requests[0].header("if-none-match"), None);
+        assert_eq!(requests[1].header("if-none-match"), Some("\"v1\""));
+    }
+
+    #[tokio::test]
+    async fn test_rate_limited_response_maps_to_error() {
+        let server = MockServer::start(vec![(
+            "GET /repos/o/r/issues",
+            MockResponse::status(403)
+                .with_header("x-ratelimit-remaining", "0")
+                .with_header("x-ratelimit-reset", "1767225600"),
+        )])
+        .await;
+
+        let result = api(&server, Some("t0k"))
+            .list_issues(&IssueFilter::default())
+            .await;
+        assert!(matches!(
+            result,
+            Err(GitHubError::RateLimitExceeded {
+                reset_at: Some(1767225600)
+            })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_list_issues_skips_pull_requests() {
+        let issues = r#"[
+            {"number": 1, "title": "Crash on start", "state": "open", "user": {"login": "a"},
+             "created_at": "2026-01-01T00:00:00Z", "updated_at": "2026-01-01T00:00:00Z",
+             "html_url": "https://github.com/o/r/issues/1"},
+            {"number": 2, "title": "Fix crash", "state": "open", "user": null,
+             "created_at": "2026-01-01T00:00:00Z", "updated_at": "2026-01-01T00:00:00Z",
+             "html_url": "https://github.com/o/r/pull/2", "pull_request": {}}
+        ]"#;
+        let server = MockServer::start(vec![("GET /repos/o/r/issues", issues.to_string())]).await;
+
+        let listed = api(&server, None)
+            .list_issues(&IssueFilter::default())
+            .await
+            .unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].state, "OPEN");
+    }
+
+    #[tokio::test]
+    async fn test_create_issue_resolves_milestone() {
+        let issue = r#"{"number": 5, "node_id": "I_5", "title": "Bug", "state": "open",
+            "user": {"login": "a"}, "created_at": "2026-01-01T00:00:00Z",
+            "updated_at": "2026-01-01T00:00:00Z", "html_url": "https://github.com/o/r/issues/5",
+            "labels": [{"name": "bug", "color": "d73a4a"}]}"#;
+        let server = MockServer::start(vec![
+            (
+                "GET /repos/o/r/milestones",
+                r#"[{"number": 3, "title": "v1.0"}]"#.to_string(),
+            ),
+            ("POST /repos/o/r/issues", issue.to_string()),
+        ])
+        .await;
+        let api = api(&server, Some("t0k"));
+
+        let created = api
+            .create_issue(&CreateIssueOptions {
+                title: "Bug".into(),
+                body: "Steps".into(),
+                labels: vec!["bug".into()],
+                milestone: Some("v1.0".into()),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(created.number, 5);
+        let sent: serde_json::Value = serde_json::from_str(&server.requests()[1].body).unwrap();
+        assert_eq!(sent["milestone"], 3);
+        assert_eq!(sent["labels"], json!(["bug"]));
+
+        assert!(matches!(
+            api.create_issue(&CreateIssueOptions {
+                milestone: Some("v9".into()),
+                ..Default::default()
+            })
+            .await,
+            Err(GitHubError::MilestoneNotFound { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_edit_removes_labels_by_encoded_name() {
+        let server = MockServer::start(vec![(
+            "DELETE /repos/o/r/issues/5/labels/help%20wanted",
+            "[]".to_string(),
+        )])
+        .await;
+
+        api(&server, Some("t0k"))
+            .edit(
+                5,
+                &EditOptions {
+                    remove_labels: vec!["help wanted".into()],
+                    ..Default::default()
+                },
+                false,
+            )
+            .await
+            .unwrap();
+        assert_eq!(server.requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_auth_status_reads_scopes() {
+        let server = MockServer::start(vec![(
+            "GET /user",
+            MockResponse::json(r#"{"login": "octocat"}"#)
+                .with_header("x-oauth-scopes", "repo, read:org"),
+        )])
+        .await;
+
+        let status = api(&server, Some("t0k")).auth_status().await.unwrap();
+        assert!(status.logged_in);
+        assert_eq!(status.username.as_deref(), Some("octocat"));
+        assert_eq!(status.scopes, vec!["repo", "read:org"]);
+
+        let anonymous = api(&server, None).auth_status().await.unwrap();
+        assert!(!anonymous.logged_in);
+    }
+}
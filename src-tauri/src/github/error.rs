@@ -49,9 +49,27 @@ pub enum GitHubError {
     #[error("Discussions are not enabled for this repository")]
     DiscussionsNotEnabled,
 
-    /// Rate limit exceeded.
-    #[error("GitHub API rate limit exceeded. Try again later.")]
-    RateLimitExceeded,
+    /// Rate limit exceeded. `reset_at` is the Unix time the limit resets,
+    /// when the API reported it.
+    #[error("{}", rate_limit_message(*reset_at))]
+    RateLimitExceeded { reset_at: Option<i64> },
+
+    /// gh is missing and no personal access token is available for the
+    /// native API client.
+    #[error("GitHub CLI (gh) not found and no GitHub token is stored. Install gh from https://cli.github.com or add a personal access token in Maestro.")]
+    NoToken,
+
+    /// The GitHub REST or GraphQL API returned an unexpected status.
+    #[error("GitHub API request failed ({status}): {message}")]
+    ApiError { status: u16, message: String },
+
+    /// A GraphQL query or mutation returned errors instead of data.
+    #[error("GitHub GraphQL request failed: {message}")]
+    GraphQl { message: String },
+
+    /// The HTTP request to the GitHub API could not be sent or read.
+    #[error("request to GitHub API failed: {0}")]
+    Request(#[from] reqwest::Error),
 
     /// Pull request not found.
     #[error("Pull request #{number} not found")]
@@ -60,6 +78,24 @@ pub enum GitHubError {
     /// Issue not found.
     #[error("Issue #{number} not found")]
     IssueNotFound { number: u64 },
+
+    /// No milestone with this title exists in the repository.
+    #[error("Milestone \"{title}\" not found")]
+    MilestoneNotFound { title: String },
+
+    /// No project with this title is linked to the repository or its owner.
+    #[error("Project \"{title}\" not found")]
+    ProjectNotFound { title: String },
+}
+
+fn rate_limit_message(reset_at: Option<i64>) -> String {
+    match reset_at.and_then(|t| chrono::DateTime::from_timestamp(t, 0)) {
+        Some(reset) => format!(
+            "GitHub API rate limit exceeded. Try again after {}.",
+            reset.format("%H:%M UTC")
+        ),
+        None => "GitHub API rate limit exceeded. Try again later.".to_string(),
+    }
 }
 
 /// Serializes the error as its `Display` string so the frontend receives a
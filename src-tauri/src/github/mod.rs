@@ -1,11 +1,22 @@
+pub mod actions;
+pub mod api;
 pub mod error;
 pub mod ops;
+pub mod review;
 pub mod runner;
+pub mod templates;
 
+pub use actions::{FailingStep, JobLog, StepLog};
 pub use error::GitHubError;
 pub use ops::{
-    AuthStatus, Comment, CommentReactions, CreatePullRequestOptions, DiscussionCategory,
-    DiscussionDetail, DiscussionInfo, IssueDetail, IssueFilter, IssueInfo, MergeMethod, PrAuthor,
-    PrLabel, PullRequestDetail, PullRequestFilter, PullRequestInfo,
+    AuthStatus, Comment, CommentReactions, CreateIssueOptions, CreatePullRequestOptions,
+    DiscussionCategory, DiscussionDetail, DiscussionInfo, EditOptions, IssueDetail, IssueFilter,
+    IssueInfo, MergeMethod, PrAuthor, PrCheck, PrLabel, PrReview, PrReviewStatus,
+    PullRequestDetail, PullRequestFilter, PullRequestInfo, WorkflowJob, WorkflowRun,
+    WorkflowRunDetail, WorkflowStep,
 };
+pub use review::{DiffSide, DraftReviewComment, ReviewComment, ReviewEvent, ReviewThread};
 pub use runner::GitHub;
+pub use templates::{
+    load_templates, IssueFormField, IssueTemplate, PullRequestTemplate, RepoTemplates,
+};
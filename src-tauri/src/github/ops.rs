@@ -36,7 +36,7 @@ pub struct PullRequestInfo {
 }
 
 /// Pull request author.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct PrAuthor {
     pub login: String,
 }
@@ -195,6 +195,127 @@ pub struct DiscussionDetail {
     pub comments: Vec<Comment>,
 }
 
+/// A status check on a pull request, from `gh pr checks --json`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrCheck {
+    pub name: String,
+    /// Raw state reported by GitHub (e.g. `SUCCESS`, `FAILURE`, `IN_PROGRESS`).
+    pub state: String,
+    /// gh's normalized state: `pass`, `fail`, `pending`, `skipping` or `cancel`.
+    pub bucket: String,
+    #[serde(default)]
+    pub link: String,
+    #[serde(default)]
+    pub workflow: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub started_at: Option<String>,
+    #[serde(default)]
+    pub completed_at: Option<String>,
+}
+
+/// A submitted pull request review.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrReview {
+    pub author: PrAuthor,
+    /// `APPROVED`, `CHANGES_REQUESTED`, `COMMENTED`, `DISMISSED` or `PENDING`.
+    pub state: String,
+    #[serde(default)]
+    pub body: String,
+    #[serde(default)]
+    pub submitted_at: Option<String>,
+}
+
+/// Review state of a pull request.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrReviewStatus {
+    /// `APPROVED`, `CHANGES_REQUESTED` or `REVIEW_REQUIRED`; `None` when the
+    /// repository does not require reviews.
+    pub review_decision: Option<String>,
+    /// Users (by login) and teams (by name) whose review is still requested.
+    pub requested_reviewers: Vec<String>,
+    /// The latest review from each reviewer.
+    pub latest_reviews: Vec<PrReview>,
+}
+
+/// A GitHub Actions workflow run, from `gh run list`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkflowRun {
+    pub database_id: u64,
+    pub name: String,
+    #[serde(default)]
+    pub display_title: String,
+    /// `queued`, `in_progress`, `completed`, ...
+    pub status: String,
+    /// `success`, `failure`, `cancelled`, ...; empty while the run is in progress.
+    #[serde(default)]
+    pub conclusion: String,
+    #[serde(default)]
+    pub event: String,
+    #[serde(default)]
+    pub head_branch: String,
+    #[serde(default)]
+    pub head_sha: String,
+    pub url: String,
+    pub created_at: String,
+    #[serde(default)]
+    pub updated_at: String,
+    #[serde(default)]
+    pub workflow_name: String,
+}
+
+/// A step within a workflow job.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkflowStep {
+    pub number: u64,
+    pub name: String,
+    pub status: String,
+    #[serde(default)]
+    pub conclusion: String,
+    #[serde(default)]
+    pub started_at: Option<String>,
+    #[serde(default)]
+    pub completed_at: Option<String>,
+}
+
+/// A job within a workflow run.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkflowJob {
+    pub database_id: u64,
+    pub name: String,
+    pub status: String,
+    #[serde(default)]
+    pub conclusion: String,
+    #[serde(default)]
+    pub url: String,
+    #[serde(default)]
+    pub started_at: Option<String>,
+    #[serde(default)]
+    pub completed_at: Option<String>,
+    #[serde(default)]
+    pub steps: Vec<WorkflowStep>,
+}
+
+/// A workflow run with its jobs, from `gh run view`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkflowRunDetail {
+    #[serde(flatten)]
+    pub run: WorkflowRun,
+    #[serde(default)]
+    pub jobs: Vec<WorkflowJob>,
+}
+
+/// Fields requested from `gh run list` / `gh run view`.
+const WORKFLOW_RUN_FIELDS: &str = "databaseId,name,displayTitle,status,conclusion,event,headBranch,headSha,url,createdAt,updatedAt,workflowName";
+
 /// Filter options for listing pull requests.
 #[derive(Debug, Clone, Default)]
 pub struct PullRequestFilter {
@@ -231,19 +352,164 @@ impl MergeMethod {
 }
 
 /// Options for creating a pull request.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+///
+/// Labels, assignees, reviewers and milestones are resolved by name on every
+/// forge; projects are GitHub only and rejected elsewhere.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct CreatePullRequestOptions {
     pub title: String,
     pub body: String,
     pub base: String,
     pub head: String,
     pub draft: bool,
+    /// Label names.
+    #[serde(default)]
+    pub labels: Vec<String>,
+    /// User logins.
+    #[serde(default)]
+    pub assignees: Vec<String>,
+    /// User logins or `org/team` slugs.
+    #[serde(default)]
+    pub reviewers: Vec<String>,
+    /// Milestone title.
+    #[serde(default)]
+    pub milestone: Option<String>,
+    /// Project titles.
+    #[serde(default)]
+    pub projects: Vec<String>,
+}
+
+/// Options for creating an issue.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CreateIssueOptions {
+    pub title: String,
+    pub body: String,
+    /// Label names.
+    #[serde(default)]
+    pub labels: Vec<String>,
+    /// User logins.
+    #[serde(default)]
+    pub assignees: Vec<String>,
+    /// Milestone title.
+    #[serde(default)]
+    pub milestone: Option<String>,
+    /// Project titles.
+    #[serde(default)]
+    pub projects: Vec<String>,
+}
+
+/// Changes to an issue or pull request. Fields left empty are unchanged.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EditOptions {
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub body: Option<String>,
+    #[serde(default)]
+    pub add_labels: Vec<String>,
+    #[serde(default)]
+    pub remove_labels: Vec<String>,
+    #[serde(default)]
+    pub add_assignees: Vec<String>,
+    #[serde(default)]
+    pub remove_assignees: Vec<String>,
+    /// Pull requests only.
+    #[serde(default)]
+    pub add_reviewers: Vec<String>,
+    /// Pull requests only.
+    #[serde(default)]
+    pub remove_reviewers: Vec<String>,
+    /// Milestone title to set; an empty title removes the milestone.
+    #[serde(default)]
+    pub milestone: Option<String>,
+    #[serde(default)]
+    pub add_projects: Vec<String>,
+    #[serde(default)]
+    pub remove_projects: Vec<String>,
+}
+
+impl EditOptions {
+    /// Whether the edit changes anything.
+    pub fn is_empty(&self) -> bool {
+        self.title.is_none()
+            && self.body.is_none()
+            && self.milestone.is_none()
+            && [
+                &self.add_labels,
+                &self.remove_labels,
+                &self.add_assignees,
+                &self.remove_assignees,
+                &self.add_reviewers,
+                &self.remove_reviewers,
+                &self.add_projects,
+                &self.remove_projects,
+            ]
+            .iter()
+            .all(|v| v.is_empty())
+    }
+
+    /// `gh issue edit` / `gh pr edit` flags for this edit.
+    fn gh_args(&self, pull_request: bool) -> Vec<String> {
+        let mut args = Vec::new();
+        if let Some(title) = &self.title {
+            args.extend(["--title".to_string(), title.clone()]);
+        }
+        if let Some(body) = &self.body {
+            args.extend(["--body".to_string(), body.clone()]);
+        }
+        let mut lists = vec![
+            ("--add-label", &self.add_labels),
+            ("--remove-label", &self.remove_labels),
+            ("--add-assignee", &self.add_assignees),
+            ("--remove-assignee", &self.remove_assignees),
+            ("--add-project", &self.add_projects),
+            ("--remove-project", &self.remove_projects),
+        ];
+        if pull_request {
+            lists.push(("--add-reviewer", &self.add_reviewers));
+            lists.push(("--remove-reviewer", &self.remove_reviewers));
+        }
+        for (flag, values) in lists {
+            if !values.is_empty() {
+                args.extend([flag.to_string(), values.join(",")]);
+            }
+        }
+        match self.milestone.as_deref() {
+            Some("") => args.push("--remove-milestone".to_string()),
+            Some(milestone) => args.extend(["--milestone".to_string(), milestone.to_string()]),
+            None => {}
+        }
+        args
+    }
+}
+
+/// `--flag value` pairs for each value, as `gh issue create` / `gh pr create` expect.
+fn repeated_flag(args: &mut Vec<String>, flag: &str, values: &[String]) {
+    for value in values {
+        args.extend([flag.to_string(), value.clone()]);
+    }
+}
+
+/// Parses the issue or pull request number from the URL `gh ... create` prints
+/// (e.g. https://github.com/owner/repo/pull/123).
+fn number_from_url(url: &str) -> Result<u64, GitHubError> {
+    url.rsplit('/')
+        .next()
+        .and_then(|s| s.parse().ok())
+        .ok_or_else(|| GitHubError::ParseError {
+            message: format!("Could not parse number from URL: {}", url),
+        })
 }
 
 /// GitHub operations using the `gh` CLI.
 impl GitHub {
     /// Checks if the user is authenticated with GitHub.
     pub async fn auth_status(&self) -> Result<AuthStatus, GitHubError> {
+        if let Some(api) = self.native().await? {
+            return api.auth_status().await;
+        }
+
         let result = self.run(&["auth", "status"]).await;
 
         match result {
@@ -281,6 +547,10 @@ impl GitHub {
         &self,
         filter: PullRequestFilter,
     ) -> Result<Vec<PullRequestInfo>, GitHubError> {
+        if let Some(api) = self.native().await? {
+            return api.list_pull_requests(&filter).await;
+        }
+
         let mut args = vec![
             "pr", "list",
             "--json", "number,title,state,author,createdAt,updatedAt,headRefName,baseRefName,isDraft,additions,deletions,url,labels,mergedAt,closedAt",
@@ -309,8 +579,33 @@ impl GitHub {
         self.run_json(&args).await
     }
 
+    /// Returns the open pull request whose head is `branch`, if any.
+    pub async fn pull_request_for_branch(
+        &self,
+        branch: &str,
+    ) -> Result<Option<PullRequestInfo>, GitHubError> {
+        if let Some(api) = self.native().await? {
+            return api.pull_request_for_branch(branch).await;
+        }
+
+        let prs: Vec<PullRequestInfo> = self
+            .run_json(&[
+                "pr", "list",
+                "--head", branch,
+                "--state", "open",
+                "--limit", "1",
+                "--json", "number,title,state,author,createdAt,updatedAt,headRefName,baseRefName,isDraft,additions,deletions,url,labels,mergedAt,closedAt",
+            ])
+            .await?;
+        Ok(prs.into_iter().next())
+    }
+
     /// Gets detailed information about a specific pull request.
     pub async fn get_pull_request(&self, number: u64) -> Result<PullRequestDetail, GitHubError> {
+        if let Some(api) = self.native().await? {
+            return api.get_pull_request(number).await;
+        }
+
         let number_str = number.to_string();
         let args = vec![
             "pr", "view", &number_str,
@@ -441,30 +736,36 @@ impl GitHub {
         &self,
         options: CreatePullRequestOptions,
     ) -> Result<PullRequestInfo, GitHubError> {
-        let mut args = vec![
+        if let Some(api) = self.native().await? {
+            return api.create_pull_request(&options).await;
+        }
+
+        let mut args: Vec<String> = [
             "pr", "create",
             "--title", &options.title,
             "--body", &options.body,
             "--base", &options.base,
             "--head", &options.head,
-        ];
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
 
         if options.draft {
-            args.push("--draft");
+            args.push("--draft".to_string());
+        }
+        repeated_flag(&mut args, "--label", &options.labels);
+        repeated_flag(&mut args, "--assignee", &options.assignees);
+        repeated_flag(&mut args, "--reviewer", &options.reviewers);
+        repeated_flag(&mut args, "--project", &options.projects);
+        if let Some(milestone) = &options.milestone {
+            args.extend(["--milestone".to_string(), milestone.clone()]);
         }
 
         // Create the PR and get its number from the output URL
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
         let output = self.run(&args).await?;
-        let url = output.trimmed();
-
-        // Extract PR number from URL (e.g., https://github.com/owner/repo/pull/123)
-        let number: u64 = url
-            .split('/')
-            .last()
-            .and_then(|s| s.parse().ok())
-            .ok_or_else(|| GitHubError::ParseError {
-                message: format!("Could not parse PR number from URL: {}", url),
-            })?;
+        let number = number_from_url(output.trimmed())?;
 
         // Fetch the full PR info
         let detail = self.get_pull_request(number).await?;
@@ -488,6 +789,171 @@ impl GitHub {
         })
     }
 
+    /// Edits a pull request's title, body, labels, assignees, reviewers,
+    /// milestone or projects.
+    pub async fn edit_pull_request(
+        &self,
+        number: u64,
+        options: &EditOptions,
+    ) -> Result<(), GitHubError> {
+        if options.is_empty() {
+            return Ok(());
+        }
+        if let Some(api) = self.native().await? {
+            return api.edit(number, options, true).await;
+        }
+
+        let number_str = number.to_string();
+        let flags = options.gh_args(true);
+        let mut args = vec!["pr", "edit", &number_str];
+        args.extend(flags.iter().map(String::as_str));
+        self.run(&args).await?;
+        Ok(())
+    }
+
+    /// Returns the repository's default branch on GitHub (e.g. `main`).
+    pub async fn default_branch(&self) -> Result<String, GitHubError> {
+        if let Some(api) = self.native().await? {
+            return api.default_branch().await;
+        }
+
+        let output = self
+            .run(&["repo", "view", "--json", "defaultBranchRef", "-q", ".defaultBranchRef.name"])
+            .await?;
+        let name = output.trimmed();
+        if name.is_empty() {
+            return Err(GitHubError::ParseError {
+                message: "repository has no default branch".to_string(),
+            });
+        }
+        Ok(name.to_string())
+    }
+
+    /// Lists the status checks on a pull request.
+    ///
+    /// `gh pr checks` exits 1 when a check failed and 8 when checks are
+    /// pending; both still print the JSON list, so they are not errors here.
+    /// A PR without any checks returns an empty list.
+    pub async fn pr_checks(&self, number: u64) -> Result<Vec<PrCheck>, GitHubError> {
+        if let Some(api) = self.native().await? {
+            return api.pr_checks(number).await;
+        }
+
+        let number_str = number.to_string();
+        let output = self
+            .run_accepting(
+                &[
+                    "pr", "checks", &number_str,
+                    "--json", "name,state,bucket,link,workflow,description,startedAt,completedAt",
+                ],
+                &[1, 8],
+            )
+            .await?;
+
+        if output.trimmed().is_empty() {
+            if output.stderr.contains("no checks reported") {
+                return Ok(Vec::new());
+            }
+            return Err(GitHubError::CommandFailed {
+                code: 1,
+                stderr: output.stderr.trim().to_string(),
+                command: format!("gh pr checks {}", number),
+            });
+        }
+        Ok(serde_json::from_str(&output.stdout)?)
+    }
+
+    /// Returns the review decision, pending review requests and latest
+    /// reviews of a pull request.
+    pub async fn pr_review_status(&self, number: u64) -> Result<PrReviewStatus, GitHubError> {
+        if let Some(api) = self.native().await? {
+            return api.pr_review_status(number).await;
+        }
+
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct ReviewRequestRaw {
+            #[serde(default)]
+            login: Option<String>,
+            #[serde(default)]
+            name: Option<String>,
+        }
+
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct ReviewViewResponse {
+            #[serde(default)]
+            review_decision: Option<String>,
+            #[serde(default)]
+            review_requests: Vec<ReviewRequestRaw>,
+            #[serde(default)]
+            latest_reviews: Vec<PrReview>,
+        }
+
+        let number_str = number.to_string();
+        let response: ReviewViewResponse = self
+            .run_json(&[
+                "pr", "view", &number_str,
+                "--json", "reviewDecision,reviewRequests,latestReviews",
+            ])
+            .await?;
+
+        Ok(PrReviewStatus {
+            // gh reports "" when no review policy applies
+            review_decision: response.review_decision.filter(|d| !d.is_empty()),
+            requested_reviewers: response
+                .review_requests
+                .into_iter()
+                .filter_map(|r| r.login.or(r.name))
+                .collect(),
+            latest_reviews: response.latest_reviews,
+        })
+    }
+
+    /// Lists recent workflow runs, optionally only for one branch.
+    pub async fn list_workflow_runs(
+        &self,
+        branch: Option<&str>,
+        limit: u32,
+    ) -> Result<Vec<WorkflowRun>, GitHubError> {
+        if let Some(api) = self.native().await? {
+            return api.list_workflow_runs(branch, limit).await;
+        }
+
+        let limit_arg = format!("--limit={}", limit);
+        let mut args = vec!["run", "list", "--json", WORKFLOW_RUN_FIELDS, &limit_arg];
+
+        let branch_arg;
+        if let Some(branch) = branch {
+            branch_arg = format!("--branch={}", branch);
+            args.push(&branch_arg);
+        }
+
+        self.run_json(&args).await
+    }
+
+    /// Gets a workflow run with its jobs and steps.
+    pub async fn get_workflow_run(&self, run_id: u64) -> Result<WorkflowRunDetail, GitHubError> {
+        if let Some(api) = self.native().await? {
+            return api.get_workflow_run(run_id).await;
+        }
+
+        let id_str = run_id.to_string();
+        let fields = format!("{},jobs", WORKFLOW_RUN_FIELDS);
+        self.run_json(&["run", "view", &id_str, "--json", &fields]).await
+    }
+
+    /// Returns the log output of the failed steps of a workflow run.
+    pub async fn workflow_run_failed_log(&self, run_id: u64) -> Result<String, GitHubError> {
+        if let Some(api) = self.native().await? {
+            return api.workflow_run_failed_log(run_id).await;
+        }
+
+        let id_str = run_id.to_string();
+        let output = self.run(&["run", "view", &id_str, "--log-failed"]).await?;
+        Ok(output.stdout)
+    }
+
     /// Merges a pull request.
     pub async fn merge_pull_request(
         &self,
@@ -495,6 +961,10 @@ impl GitHub {
         method: MergeMethod,
         delete_branch: bool,
     ) -> Result<(), GitHubError> {
+        if let Some(api) = self.native().await? {
+            return api.merge_pull_request(number, method, delete_branch).await;
+        }
+
         let number_str = number.to_string();
         let mut args = vec!["pr", "merge", &number_str, method.as_flag()];
 
@@ -508,6 +978,10 @@ impl GitHub {
 
     /// Closes a pull request without merging.
     pub async fn close_pull_request(&self, number: u64) -> Result<(), GitHubError> {
+        if let Some(api) = self.native().await? {
+            return api.close_pull_request(number).await;
+        }
+
         let number_str = number.to_string();
         self.run(&["pr", "close", &number_str]).await?;
         Ok(())
@@ -515,6 +989,10 @@ impl GitHub {
 
     /// Adds a comment to a pull request.
     pub async fn comment_pull_request(&self, number: u64, body: &str) -> Result<(), GitHubError> {
+        if let Some(api) = self.native().await? {
+            return api.comment(number, body).await;
+        }
+
         let number_str = number.to_string();
         self.run(&["pr", "comment", &number_str, "--body", body]).await?;
         Ok(())
@@ -522,6 +1000,10 @@ impl GitHub {
 
     /// Lists issues with optional filtering.
     pub async fn list_issues(&self, filter: IssueFilter) -> Result<Vec<IssueInfo>, GitHubError> {
+        if let Some(api) = self.native().await? {
+            return api.list_issues(&filter).await;
+        }
+
         let mut args = vec![
             "issue", "list",
             "--json", "number,title,state,author,createdAt,updatedAt,url,labels,closedAt",
@@ -552,6 +1034,10 @@ impl GitHub {
 
     /// Gets detailed information about a specific issue.
     pub async fn get_issue(&self, number: u64) -> Result<IssueDetail, GitHubError> {
+        if let Some(api) = self.native().await? {
+            return api.get_issue(number).await;
+        }
+
         let number_str = number.to_string();
 
         // First get the basic issue info with JSON
@@ -659,8 +1145,64 @@ impl GitHub {
         })
     }
 
+    /// Opens an issue.
+    pub async fn create_issue(&self, options: &CreateIssueOptions) -> Result<IssueInfo, GitHubError> {
+        if let Some(api) = self.native().await? {
+            return api.create_issue(options).await;
+        }
+
+        let mut args: Vec<String> = ["issue", "create", "--title", &options.title, "--body", &options.body]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        repeated_flag(&mut args, "--label", &options.labels);
+        repeated_flag(&mut args, "--assignee", &options.assignees);
+        repeated_flag(&mut args, "--project", &options.projects);
+        if let Some(milestone) = &options.milestone {
+            args.extend(["--milestone".to_string(), milestone.clone()]);
+        }
+
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        let output = self.run(&args).await?;
+        let number = number_from_url(output.trimmed())?;
+
+        let detail = self.get_issue(number).await?;
+        Ok(IssueInfo {
+            number: detail.number,
+            title: detail.title,
+            state: detail.state,
+            author: detail.author,
+            created_at: detail.created_at,
+            updated_at: detail.updated_at,
+            url: detail.url,
+            labels: detail.labels,
+            closed_at: detail.closed_at,
+        })
+    }
+
+    /// Edits an issue's title, body, labels, assignees, milestone or projects.
+    pub async fn edit_issue(&self, number: u64, options: &EditOptions) -> Result<(), GitHubError> {
+        if options.is_empty() {
+            return Ok(());
+        }
+        if let Some(api) = self.native().await? {
+            return api.edit(number, options, false).await;
+        }
+
+        let number_str = number.to_string();
+        let flags = options.gh_args(false);
+        let mut args = vec!["issue", "edit", &number_str];
+        args.extend(flags.iter().map(String::as_str));
+        self.run(&args).await?;
+        Ok(())
+    }
+
     /// Adds a comment to an issue.
     pub async fn comment_issue(&self, number: u64, body: &str) -> Result<(), GitHubError> {
+        if let Some(api) = self.native().await? {
+            return api.comment(number, body).await;
+        }
+
         let number_str = number.to_string();
         self.run(&["issue", "comment", &number_str, "--body", body]).await?;
         Ok(())
@@ -668,6 +1210,10 @@ impl GitHub {
 
     /// Closes an issue.
     pub async fn close_issue(&self, number: u64) -> Result<(), GitHubError> {
+        if let Some(api) = self.native().await? {
+            return api.set_issue_state(number, "closed").await;
+        }
+
         let number_str = number.to_string();
         self.run(&["issue", "close", &number_str]).await?;
         Ok(())
@@ -675,11 +1221,36 @@ impl GitHub {
 
     /// Reopens a closed issue.
     pub async fn reopen_issue(&self, number: u64) -> Result<(), GitHubError> {
+        if let Some(api) = self.native().await? {
+            return api.set_issue_state(number, "open").await;
+        }
+
         let number_str = number.to_string();
         self.run(&["issue", "reopen", &number_str]).await?;
         Ok(())
     }
 
+    /// Returns the repository's owner login and name.
+    pub(super) async fn repo_owner_and_name(&self) -> Result<(String, String), GitHubError> {
+        if let Some(api) = self.native().await? {
+            return Ok((api.owner().to_string(), api.repo().to_string()));
+        }
+
+        #[derive(Deserialize)]
+        struct RepoInfo {
+            owner: RepoOwner,
+            name: String,
+        }
+
+        #[derive(Deserialize)]
+        struct RepoOwner {
+            login: String,
+        }
+
+        let repo_info: RepoInfo = self.run_json(&["repo", "view", "--json", "owner,name"]).await?;
+        Ok((repo_info.owner.login, repo_info.name))
+    }
+
     /// Lists discussions using the GraphQL API.
     pub async fn list_discussions(&self, limit: u32) -> Result<Vec<DiscussionInfo>, GitHubError> {
         let query = format!(
@@ -706,25 +1277,11 @@ impl GitHub {
             limit
         );
 
-        // We need to get repo info first to fill in OWNER/REPO
-        let repo_output = self.run(&["repo", "view", "--json", "owner,name"]).await?;
-
-        #[derive(Deserialize)]
-        struct RepoInfo {
-            owner: RepoOwner,
-            name: String,
-        }
-
-        #[derive(Deserialize)]
-        struct RepoOwner {
-            login: String,
-        }
-
-        let repo_info: RepoInfo = serde_json::from_str(&repo_output.stdout)?;
+        let (owner, name) = self.repo_owner_and_name().await?;
 
         let query = query
-            .replace("OWNER", &repo_info.owner.login)
-            .replace("REPO", &repo_info.name);
+            .replace("OWNER", &owner)
+            .replace("REPO", &name);
 
         let result = self.graphql(&query).await;
 
@@ -765,21 +1322,7 @@ impl GitHub {
 
     /// Gets detailed information about a specific discussion using GraphQL.
     pub async fn get_discussion(&self, number: u64) -> Result<DiscussionDetail, GitHubError> {
-        // Get repo info first
-        let repo_output = self.run(&["repo", "view", "--json", "owner,name"]).await?;
-
-        #[derive(Deserialize)]
-        struct RepoInfo {
-            owner: RepoOwner,
-            name: String,
-        }
-
-        #[derive(Deserialize)]
-        struct RepoOwner {
-            login: String,
-        }
-
-        let repo_info: RepoInfo = serde_json::from_str(&repo_output.stdout)?;
+        let (owner, name) = self.repo_owner_and_name().await?;
 
         let query = format!(
             r#"{{
@@ -825,7 +1368,7 @@ impl GitHub {
                     }}
                 }}
             }}"#,
-            repo_info.owner.login, repo_info.name, number
+            owner, name, number
         );
 
         let json = self.graphql(&query).await?;
@@ -933,21 +1476,7 @@ impl GitHub {
 
     /// Adds a comment to a discussion using GraphQL mutation.
     pub async fn comment_discussion(&self, number: u64, body: &str) -> Result<(), GitHubError> {
-        // Get repo info first
-        let repo_output = self.run(&["repo", "view", "--json", "owner,name"]).await?;
-
-        #[derive(Deserialize)]
-        struct RepoInfo {
-            owner: RepoOwner,
-            name: String,
-        }
-
-        #[derive(Deserialize)]
-        struct RepoOwner {
-            login: String,
-        }
-
-        let repo_info: RepoInfo = serde_json::from_str(&repo_output.stdout)?;
+        let (owner, name) = self.repo_owner_and_name().await?;
 
         // First, get the discussion ID (GraphQL node ID)
         let id_query = format!(
@@ -958,7 +1487,7 @@ impl GitHub {
                     }}
                 }}
             }}"#,
-            repo_info.owner.login, repo_info.name, number
+            owner, name, number
         );
 
         let id_json = self.graphql(&id_query).await?;
@@ -1000,6 +1529,35 @@ impl GitHub {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_edit_options_gh_args() {
+        let options = EditOptions {
+            title: Some("New title".into()),
+            add_labels: vec!["bug".into(), "p1".into()],
+            add_reviewers: vec!["octocat".into()],
+            milestone: Some(String::new()),
+            ..Default::default()
+        };
+        assert!(!options.is_empty());
+        assert_eq!(
+            options.gh_args(true),
+            vec!["--title", "New title", "--add-label", "bug,p1", "--add-reviewer", "octocat", "--remove-milestone"]
+        );
+        // Issues have no reviewers
+        assert_eq!(
+            options.gh_args(false),
+            vec!["--title", "New title", "--add-label", "bug,p1", "--remove-milestone"]
+        );
+        assert!(EditOptions::default().is_empty());
+    }
+
+    #[test]
+    fn test_number_from_url() {
+        assert_eq!(number_from_url("https://github.com/o/r/pull/123").unwrap(), 123);
+        assert_eq!(number_from_url("https://github.com/o/r/issues/7").unwrap(), 7);
+        assert!(number_from_url("https://github.com/o/r").is_err());
+    }
+
     #[test]
     fn test_merge_method_flag() {
         assert_eq!(MergeMethod::Merge.as_flag(), "--merge");
@@ -1076,4 +1634,36 @@ mod tests {
         assert_eq!(issue.number, 456);
         assert_eq!(issue.title, "Test Issue");
     }
+
+    #[test]
+    fn test_pr_check_deserialization() {
+        let json = r#"[
+            {"name": "build", "state": "FAILURE", "bucket": "fail", "link": "https://github.com/o/r/actions/runs/1/job/2",
+             "workflow": "CI", "description": "", "startedAt": "2024-01-01T00:00:00Z", "completedAt": "2024-01-01T00:05:00Z"},
+            {"name": "lint", "state": "IN_PROGRESS", "bucket": "pending"}
+        ]"#;
+
+        let checks: Vec<PrCheck> = serde_json::from_str(json).unwrap();
+        assert_eq!(checks.len(), 2);
+        assert_eq!(checks[0].bucket, "fail");
+        assert_eq!(checks[0].workflow, "CI");
+        assert_eq!(checks[1].completed_at, None);
+    }
+
+    #[test]
+    fn test_workflow_run_detail_deserialization() {
+        let json = r#"{
+            "databaseId": 99, "name": "CI", "displayTitle": "Fix login", "status": "completed",
+            "conclusion": "failure", "event": "pull_request", "headBranch": "feature", "headSha": "abc",
+            "url": "https://github.com/o/r/actions/runs/99", "createdAt": "2024-01-01T00:00:00Z",
+            "updatedAt": "2024-01-01T00:10:00Z", "workflowName": "CI",
+            "jobs": [{"databaseId": 1, "name": "test", "status": "completed", "conclusion": "failure",
+                      "steps": [{"number": 1, "name": "cargo test", "status": "completed", "conclusion": "failure"}]}]
+        }"#;
+
+        let detail: WorkflowRunDetail = serde_json::from_str(json).unwrap();
+        assert_eq!(detail.run.database_id, 99);
+        assert_eq!(detail.run.conclusion, "failure");
+        assert_eq!(detail.jobs[0].steps[0].name, "cargo test");
+    }
 }
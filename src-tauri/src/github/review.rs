@@ -0,0 +1,601 @@
+//! Diff-anchored pull request reviews: review threads, replies, resolution
+//! and pending reviews submitted as approve, request-changes or comment.
+//!
+//! Everything goes through GraphQL, which is the only API that exposes
+//! thread resolution, so the same code serves both `gh` and the native
+//! client.
+
+use serde::{Deserialize, Serialize};
+
+use super::error::GitHubError;
+use super::ops::PrAuthor;
+use super::runner::GitHub;
+
+/// Which side of the diff a review comment is anchored to.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum DiffSide {
+    /// The base version (deleted or unchanged lines).
+    Left,
+    /// The head version (added or unchanged lines).
+    #[default]
+    Right,
+}
+
+impl DiffSide {
+    fn as_graphql(self) -> &'static str {
+        match self {
+            DiffSide::Left => "LEFT",
+            DiffSide::Right => "RIGHT",
+        }
+    }
+}
+
+/// A comment within a review thread.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReviewComment {
+    /// GraphQL node id.
+    pub id: String,
+    pub database_id: u64,
+    pub author: PrAuthor,
+    pub body: String,
+    pub created_at: String,
+    pub updated_at: Option<String>,
+    /// The diff hunk the comment was made on, ending at the commented line.
+    pub diff_hunk: String,
+    pub url: String,
+    /// Id of the comment this one replies to; `None` for the thread's first comment.
+    pub reply_to_id: Option<String>,
+    /// State of the review the comment belongs to (`PENDING` until submitted).
+    pub review_state: Option<String>,
+}
+
+/// A conversation anchored to lines of a pull request's diff.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReviewThread {
+    /// GraphQL node id, used to reply to and resolve the thread.
+    pub id: String,
+    pub path: String,
+    /// Last commented line in the current diff; `None` once outdated.
+    pub line: Option<u32>,
+    /// First line of a multi-line comment.
+    pub start_line: Option<u32>,
+    /// Line in the diff the thread was originally made on.
+    pub original_line: Option<u32>,
+    pub original_start_line: Option<u32>,
+    pub diff_side: DiffSide,
+    pub is_resolved: bool,
+    /// The lines changed after the thread was started.
+    pub is_outdated: bool,
+    pub resolved_by: Option<String>,
+    pub comments: Vec<ReviewComment>,
+}
+
+/// A line comment to add to a pending review.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DraftReviewComment {
+    pub path: String,
+    /// Line to comment on (last line for a multi-line comment), 1-based in
+    /// the file version on `side`.
+    pub line: u32,
+    /// First line of a multi-line comment.
+    #[serde(default)]
+    pub start_line: Option<u32>,
+    #[serde(default)]
+    pub side: DiffSide,
+    pub body: String,
+    /// Replacement for the commented lines, rendered as a suggested change.
+    #[serde(default)]
+    pub suggestion: Option<String>,
+}
+
+impl DraftReviewComment {
+    /// The comment body with the suggested change appended, if any.
+    fn full_body(&self) -> String {
+        let Some(suggestion) = &self.suggestion else {
+            return self.body.clone();
+        };
+        // The fence must be longer than any backtick run in the suggestion
+        let longest_run = suggestion
+            .split(|c| c != '`')
+            .map(str::len)
+            .max()
+            .unwrap_or(0);
+        let fence = "`".repeat(longest_run.max(2) + 1);
+        let suggestion = suggestion.strip_suffix('\n').unwrap_or(suggestion);
+        if self.body.is_empty() {
+            format!("{fence}suggestion\n{suggestion}\n{fence}")
+        } else {
+            format!("{}\n\n{fence}suggestion\n{suggestion}\n{fence}", self.body)
+        }
+    }
+
+    /// Fields of a `DraftPullRequestReviewThread` / `AddPullRequestReviewThreadInput`.
+    fn graphql_fields(&self) -> String {
+        let side = self.side.as_graphql();
+        let mut fields = format!(
+            "path: {}, line: {}, side: {}, body: {}",
+            graphql_string(&self.path),
+            self.line,
+            side,
+            graphql_string(&self.full_body())
+        );
+        if let Some(start) = self.start_line.filter(|start| *start < self.line) {
+            fields.push_str(&format!(", startLine: {}, startSide: {}", start, side));
+        }
+        fields
+    }
+}
+
+/// How a pending review is submitted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReviewEvent {
+    Approve,
+    RequestChanges,
+    Comment,
+}
+
+impl ReviewEvent {
+    fn as_graphql(self) -> &'static str {
+        match self {
+            ReviewEvent::Approve => "APPROVE",
+            ReviewEvent::RequestChanges => "REQUEST_CHANGES",
+            ReviewEvent::Comment => "COMMENT",
+        }
+    }
+}
+
+/// Quotes `s` as a GraphQL string literal. JSON string escaping is valid
+/// GraphQL, which avoids hand-rolling escapes for review bodies.
+pub(super) fn graphql_string(s: &str) -> String {
+    serde_json::Value::from(s).to_string()
+}
+
+/// Returns the value at `pointer` in a GraphQL response, or the response's
+/// `errors` when it is missing or null.
+pub(super) fn graphql_data<'a>(
+    json: &'a serde_json::Value,
+    pointer: &str,
+) -> Result<&'a serde_json::Value, GitHubError> {
+    if let Some(value) = json.pointer(pointer).filter(|v| !v.is_null()) {
+        return Ok(value);
+    }
+    let messages: Vec<&str> = json
+        .get("errors")
+        .and_then(|e| e.as_array())
+        .map(|errors| {
+            errors
+                .iter()
+                .filter_map(|e| e.get("message").and_then(|m| m.as_str()))
+                .collect()
+        })
+        .unwrap_or_default();
+    if messages.is_empty() {
+        return Err(GitHubError::ParseError {
+            message: format!("GraphQL response has no {}", pointer),
+        });
+    }
+    Err(GitHubError::GraphQl {
+        message: messages.join("; "),
+    })
+}
+
+/// Fields selected for every review comment.
+const COMMENT_FIELDS: &str = "id databaseId author { login } body createdAt updatedAt diffHunk url replyTo { id } pullRequestReview { state }";
+
+#[derive(Deserialize)]
+struct NodeId {
+    id: String,
+}
+
+#[derive(Deserialize)]
+struct ReviewState {
+    state: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ReviewCommentRaw {
+    id: String,
+    database_id: u64,
+    author: PrAuthor,
+    body: String,
+    created_at: String,
+    #[serde(default)]
+    updated_at: Option<String>,
+    #[serde(default)]
+    diff_hunk: String,
+    url: String,
+    #[serde(default)]
+    reply_to: Option<NodeId>,
+    #[serde(default)]
+    pull_request_review: Option<ReviewState>,
+}
+
+impl From<ReviewCommentRaw> for ReviewComment {
+    fn from(raw: ReviewCommentRaw) -> Self {
+        Self {
+            id: raw.id,
+            database_id: raw.database_id,
+            author: raw.author,
+            body: raw.body,
+            created_at: raw.created_at,
+            updated_at: raw.updated_at,
+            diff_hunk: raw.diff_hunk,
+            url: raw.url,
+            reply_to_id: raw.reply_to.map(|r| r.id),
+            review_state: raw.pull_request_review.map(|r| r.state),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct Nodes<T> {
+    nodes: Vec<T>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ReviewThreadRaw {
+    id: String,
+    path: String,
+    #[serde(default)]
+    line: Option<u32>,
+    #[serde(default)]
+    start_line: Option<u32>,
+    #[serde(default)]
+    original_line: Option<u32>,
+    #[serde(default)]
+    original_start_line: Option<u32>,
+    diff_side: DiffSide,
+    is_resolved: bool,
+    is_outdated: bool,
+    #[serde(default)]
+    resolved_by: Option<PrAuthor>,
+    comments: Nodes<ReviewCommentRaw>,
+}
+
+impl From<ReviewThreadRaw> for ReviewThread {
+    fn from(raw: ReviewThreadRaw) -> Self {
+        Self {
+            id: raw.id,
+            path: raw.path,
+            line: raw.line,
+            start_line: raw.start_line,
+            original_line: raw.original_line,
+            original_start_line: raw.original_start_line,
+            diff_side: raw.diff_side,
+            is_resolved: raw.is_resolved,
+            is_outdated: raw.is_outdated,
+            resolved_by: raw.resolved_by.map(|a| a.login),
+            comments: raw.comments.nodes.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+/// Pull request review operations.
+impl GitHub {
+    /// Lists the review threads on a pull request with their diff positions
+    /// and comments, including the viewer's pending review comments.
+    pub async fn list_review_threads(&self, number: u64) -> Result<Vec<ReviewThread>, GitHubError> {
+        let (owner, name) = self.repo_owner_and_name().await?;
+        let query = format!(
+            r#"{{
+                repository(owner: {}, name: {}) {{
+                    pullRequest(number: {}) {{
+                        reviewThreads(first: 100) {{
+                            nodes {{
+                                id
+                                path
+                                line
+                                startLine
+                                originalLine
+                                originalStartLine
+                                diffSide
+                                isResolved
+                                isOutdated
+                                resolvedBy {{ login }}
+                                comments(first: 100) {{
+                                    nodes {{ {} }}
+                                }}
+                            }}
+                        }}
+                    }}
+                }}
+            }}"#,
+            graphql_string(&owner),
+            graphql_string(&name),
+            number,
+            COMMENT_FIELDS
+        );
+
+        let json = self.graphql(&query).await?;
+        let pull = graphql_data(&json, "/data/repository/pullRequest")
+            .map_err(|_| GitHubError::PullRequestNotFound { number })?;
+        let threads: Nodes<ReviewThreadRaw> =
+            serde_json::from_value(pull.get("reviewThreads").cloned().unwrap_or_default())?;
+        Ok(threads.nodes.into_iter().map(Into::into).collect())
+    }
+
+    /// Replies to a review thread.
+    pub async fn reply_to_review_thread(
+        &self,
+        thread_id: &str,
+        body: &str,
+    ) -> Result<ReviewComment, GitHubError> {
+        let mutation = format!(
+            r#"mutation {{
+                addPullRequestReviewThreadReply(input: {{pullRequestReviewThreadId: {}, body: {}}}) {{
+                    comment {{ {} }}
+                }}
+            }}"#,
+            graphql_string(thread_id),
+            graphql_string(body),
+            COMMENT_FIELDS
+        );
+
+        let json = self.graphql(&mutation).await?;
+        let comment = graphql_data(&json, "/data/addPullRequestReviewThreadReply/comment")?;
+        let raw: ReviewCommentRaw = serde_json::from_value(comment.clone())?;
+        Ok(raw.into())
+    }
+
+    /// Marks a review thread as resolved, or unresolved again.
+    pub async fn set_review_thread_resolved(
+        &self,
+        thread_id: &str,
+        resolved: bool,
+    ) -> Result<(), GitHubError> {
+        let mutation_name = if resolved {
+            "resolveReviewThread"
+        } else {
+            "unresolveReviewThread"
+        };
+        let mutation = format!(
+            r#"mutation {{
+                {}(input: {{threadId: {}}}) {{
+                    thread {{ id }}
+                }}
+            }}"#,
+            mutation_name,
+            graphql_string(thread_id)
+        );
+
+        let json = self.graphql(&mutation).await?;
+        graphql_data(&json, &format!("/data/{}/thread", mutation_name))?;
+        Ok(())
+    }
+
+    /// Starts a pending review on a pull request with the given line
+    /// comments, returning the review's node id. The review stays visible
+    /// only to the viewer until [`GitHub::submit_review`] is called.
+    pub async fn start_review(
+        &self,
+        number: u64,
+        body: Option<&str>,
+        comments: &[DraftReviewComment],
+    ) -> Result<String, GitHubError> {
+        let (owner, name) = self.repo_owner_and_name().await?;
+        let id_query = format!(
+            r#"{{
+                repository(owner: {}, name: {}) {{
+                    pullRequest(number: {}) {{ id }}
+                }}
+            }}"#,
+            graphql_string(&owner),
+            graphql_string(&name),
+            number
+        );
+        let id_json = self.graphql(&id_query).await?;
+        let pull_id = graphql_data(&id_json, "/data/repository/pullRequest/id")
+            .ok()
+            .and_then(|id| id.as_str())
+            .ok_or(GitHubError::PullRequestNotFound { number })?;
+
+        let threads: Vec<String> = comments
+            .iter()
+            .map(|c| format!("{{{}}}", c.graphql_fields()))
+            .collect();
+        let mut input = format!(
+            "pullRequestId: {}, threads: [{}]",
+            graphql_string(pull_id),
+            threads.join(", ")
+        );
+        if let Some(body) = body.filter(|b| !b.is_empty()) {
+            input.push_str(&format!(", body: {}", graphql_string(body)));
+        }
+        // Omitting `event` leaves the review pending
+        let mutation = format!(
+            r#"mutation {{
+                addPullRequestReview(input: {{{}}}) {{
+                    pullRequestReview {{ id }}
+                }}
+            }}"#,
+            input
+        );
+
+        let json = self.graphql(&mutation).await?;
+        let review = graphql_data(&json, "/data/addPullRequestReview/pullRequestReview")?;
+        let review: NodeId = serde_json::from_value(review.clone())?;
+        Ok(review.id)
+    }
+
+    /// Adds a line comment to a pending review, returning the new thread's id.
+    pub async fn add_review_comment(
+        &self,
+        review_id: &str,
+        comment: &DraftReviewComment,
+    ) -> Result<String, GitHubError> {
+        let mutation = format!(
+            r#"mutation {{
+                addPullRequestReviewThread(input: {{pullRequestReviewId: {}, {}}}) {{
+                    thread {{ id }}
+                }}
+            }}"#,
+            graphql_string(review_id),
+            comment.graphql_fields()
+        );
+
+        let json = self.graphql(&mutation).await?;
+        let thread = graphql_data(&json, "/data/addPullRequestReviewThread/thread")?;
+        let thread: NodeId = serde_json::from_value(thread.clone())?;
+        Ok(thread.id)
+    }
+
+    /// Submits a pending review as approve, request-changes or comment.
+    pub async fn submit_review(
+        &self,
+        review_id: &str,
+        event: ReviewEvent,
+        body: Option<&str>,
+    ) -> Result<(), GitHubError> {
+        let mut input = format!(
+            "pullRequestReviewId: {}, event: {}",
+            graphql_string(review_id),
+            event.as_graphql()
+        );
+        if let Some(body) = body.filter(|b| !b.is_empty()) {
+            input.push_str(&format!(", body: {}", graphql_string(body)));
+        }
+        let mutation = format!(
+            r#"mutation {{
+                submitPullRequestReview(input: {{{}}}) {{
+                    pullRequestReview {{ id }}
+                }}
+            }}"#,
+            input
+        );
+
+        let json = self.graphql(&mutation).await?;
+        graphql_data(&json, "/data/submitPullRequestReview/pullRequestReview")?;
+        Ok(())
+    }
+
+    /// Deletes a pending review and its comments.
+    pub async fn discard_review(&self, review_id: &str) -> Result<(), GitHubError> {
+        let mutation = format!(
+            r#"mutation {{
+                deletePullRequestReview(input: {{pullRequestReviewId: {}}}) {{
+                    pullRequestReview {{ id }}
+                }}
+            }}"#,
+            graphql_string(review_id)
+        );
+
+        let json = self.graphql(&mutation).await?;
+        graphql_data(&json, "/data/deletePullRequestReview/pullRequestReview")?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn draft(body: &str, suggestion: Option<&str>) -> DraftReviewComment {
+        DraftReviewComment {
+            path: "src/main.rs".to_string(),
+            line: 12,
+            start_line: None,
+            side: DiffSide::Right,
+            body: body.to_string(),
+            suggestion: suggestion.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_graphql_string_escapes() {
+        assert_eq!(graphql_string("plain"), r#""plain""#);
+        assert_eq!(
+            graphql_string("say \"hi\"\n\\path"),
+            r#""say \"hi\"\n\\path""#
+        );
+    }
+
+    #[test]
+    fn test_suggestion_body() {
+        assert_eq!(draft("Typo", None).full_body(), "Typo");
+        assert_eq!(
+            draft("Use a constant", Some("let x = MAX;\n")).full_body(),
+            "Use a constant\n\n```suggestion\nlet x = MAX;\n```"
+        );
+        // Backticks in the suggestion need a longer fence
+        assert_eq!(
+            draft("", Some("/// ```\n/// run()\n/// ```")).full_body(),
+            "````suggestion\n/// ```\n/// run()\n/// ```\n````"
+        );
+    }
+
+    #[test]
+    fn test_multi_line_comment_fields() {
+        let mut comment = draft("Extract this", None);
+        assert_eq!(
+            comment.graphql_fields(),
+            r#"path: "src/main.rs", line: 12, side: RIGHT, body: "Extract this""#
+        );
+
+        comment.start_line = Some(8);
+        comment.side = DiffSide::Left;
+        assert!(comment
+            .graphql_fields()
+            .ends_with("startLine: 8, startSide: LEFT"));
+
+        // A start line at or after `line` is a single-line comment
+        comment.start_line = Some(12);
+        assert!(!comment.graphql_fields().contains("startLine"));
+    }
+
+    #[test]
+    fn test_graphql_data_reports_errors() {
+        let json = serde_json::json!({
+            "data": {"resolveReviewThread": null},
+            "errors": [{"message": "Could not resolve to a node"}, {"message": "Forbidden"}]
+        });
+        match graphql_data(&json, "/data/resolveReviewThread/thread") {
+            Err(GitHubError::GraphQl { message }) => {
+                assert_eq!(message, "Could not resolve to a node; Forbidden")
+            }
+            other => panic!("expected GraphQl error, got {:?}", other),
+        }
+
+        let json = serde_json::json!({"data": {"viewer": {"login": "octocat"}}});
+        assert_eq!(
+            graphql_data(&json, "/data/viewer/login").unwrap(),
+            "octocat"
+        );
+    }
+
+    #[test]
+    fn test_review_thread_deserialization() {
+        let json = r#"{
+            "id": "PRRT_1", "path": "src/lib.rs", "line": null, "startLine": null,
+            "originalLine": 40, "originalStartLine": null, "diffSide": "RIGHT",
+            "isResolved": true, "isOutdated": true, "resolvedBy": {"login": "octocat"},
+            "comments": {"nodes": [
+                {"id": "PRRC_1", "databaseId": 7, "author": {"login": "hubot"}, "body": "Off by one?",
+                 "createdAt": "2026-01-01T00:00:00Z", "updatedAt": "2026-01-01T00:00:00Z",
+                 "diffHunk": "@@ -1,3 +1,4 @@", "url": "https://github.com/o/r/pull/1#discussion_r7",
+                 "replyTo": null, "pullRequestReview": {"state": "COMMENTED"}},
+                {"id": "PRRC_2", "databaseId": 8, "author": {"login": "octocat"}, "body": "Fixed",
+                 "createdAt": "2026-01-02T00:00:00Z", "diffHunk": "@@ -1,3 +1,4 @@",
+                 "url": "https://github.com/o/r/pull/1#discussion_r8", "replyTo": {"id": "PRRC_1"}}
+            ]}
+        }"#;
+
+        let raw: ReviewThreadRaw = serde_json::from_str(json).unwrap();
+        let thread = ReviewThread::from(raw);
+        assert_eq!(thread.line, None);
+        assert_eq!(thread.original_line, Some(40));
+        assert_eq!(thread.resolved_by.as_deref(), Some("octocat"));
+        assert_eq!(thread.comments.len(), 2);
+        assert_eq!(
+            thread.comments[0].review_state.as_deref(),
+            Some("COMMENTED")
+        );
+        assert_eq!(thread.comments[1].reply_to_id.as_deref(), Some("PRRC_1"));
+    }
+}
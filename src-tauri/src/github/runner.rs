@@ -2,6 +2,7 @@ use std::path::{Path, PathBuf};
 use tokio::process::Command;
 use tokio::time::{timeout, Duration};
 
+use super::api::GitHubApi;
 use super::error::GitHubError;
 use crate::core::windows_process::TokioCommandExt;
 
@@ -49,12 +50,35 @@ impl GitHub {
         &self.repo_path
     }
 
+    /// Returns the native API client when `gh` is not installed, or `None`
+    /// when operations should go through `gh` as usual.
+    pub(crate) async fn native(&self) -> Result<Option<GitHubApi>, GitHubError> {
+        if gh_installed() {
+            return Ok(None);
+        }
+        GitHubApi::for_repo(&self.repo_path).await.map(Some)
+    }
+
     /// Executes a gh subcommand and returns its captured output.
     ///
     /// Returns `GhNotFound` if the gh binary is missing, `SpawnError` for
     /// other I/O failures, and `CommandFailed` for non-zero exit codes.
     /// Both stdout and stderr are decoded as UTF-8 (returns `InvalidUtf8` on failure).
     pub async fn run(&self, args: &[&str]) -> Result<GitHubOutput, GitHubError> {
+        self.run_accepting(args, &[]).await
+    }
+
+    /// Like `run`, but treats the listed non-zero exit codes as success.
+    ///
+    /// Some gh subcommands encode results in the exit code (e.g. `gh pr checks`
+    /// exits 1 when a check failed and 8 when checks are pending) while still
+    /// printing valid output. Auth, rate-limit and repository errors are
+    /// still mapped to their variants regardless of the exit code.
+    pub async fn run_accepting(
+        &self,
+        args: &[&str],
+        accepted_exit_codes: &[i32],
+    ) -> Result<GitHubOutput, GitHubError> {
         let mut cmd = Command::new("gh");
         cmd.current_dir(&self.repo_path)
             .args(args)
@@ -98,7 +122,7 @@ impl GitHub {
                 return Err(GitHubError::NotAuthenticated);
             }
             if stderr_lower.contains("rate limit") {
-                return Err(GitHubError::RateLimitExceeded);
+                return Err(GitHubError::RateLimitExceeded { reset_at: None });
             }
             if stderr_lower.contains("not a git repository")
                 || stderr_lower.contains("could not determine")
@@ -106,8 +130,13 @@ impl GitHub {
                 return Err(GitHubError::NotGitHubRepo);
             }
 
+            let code = output.status.code().unwrap_or(-1);
+            if accepted_exit_codes.contains(&code) {
+                return Ok(GitHubOutput { stdout, stderr });
+            }
+
             Err(GitHubError::CommandFailed {
-                code: output.status.code().unwrap_or(-1),
+                code,
                 stderr: stderr.trim().to_string(),
                 command: command_str,
             })
@@ -124,14 +153,28 @@ impl GitHub {
         Ok(parsed)
     }
 
-    /// Executes a GraphQL query via `gh api graphql`.
+    /// Executes a GraphQL query via `gh api graphql`, or the native client
+    /// when gh is not installed.
     pub async fn graphql(&self, query: &str) -> Result<serde_json::Value, GitHubError> {
+        if let Some(api) = self.native().await? {
+            return api.graphql(query).await;
+        }
         let output = self.run(&["api", "graphql", "-f", &format!("query={}", query)]).await?;
         let parsed: serde_json::Value = serde_json::from_str(&output.stdout)?;
         Ok(parsed)
     }
 }
 
+/// Whether a `gh` executable is on `$PATH`.
+///
+/// Checked on every call rather than cached so installing gh while the app
+/// is running takes effect without a restart.
+fn gh_installed() -> bool {
+    let exe = if cfg!(windows) { "gh.exe" } else { "gh" };
+    std::env::var_os("PATH")
+        .is_some_and(|path| std::env::split_paths(&path).any(|dir| dir.join(exe).is_file()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
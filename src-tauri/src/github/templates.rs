@@ -0,0 +1,510 @@
+//! Issue and pull request templates read from the repository checkout.
+//!
+//! Issue templates come from `.github/ISSUE_TEMPLATE/`: Markdown files with
+//! YAML front matter, and issue forms (`.yml`) whose fields are returned as
+//! parsed and also rendered into a Markdown body the way GitHub formats a
+//! submitted form. Pull request templates are looked up where GitHub looks
+//! for them: `.github/`, the repository root and `docs/`, either as a single
+//! `pull_request_template.md` or a `PULL_REQUEST_TEMPLATE/` directory.
+//! File and directory names match case-insensitively, like on GitHub.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Deserializer, Serialize};
+
+/// A field of an issue form.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IssueFormField {
+    pub id: Option<String>,
+    /// `textarea`, `input`, `dropdown` or `checkboxes`.
+    pub kind: String,
+    pub label: String,
+    pub description: String,
+    pub placeholder: String,
+    /// Initial value, or the default option of a dropdown.
+    pub value: String,
+    /// Dropdown choices or checkbox labels.
+    pub options: Vec<String>,
+    pub required: bool,
+}
+
+/// An issue template from `.github/ISSUE_TEMPLATE`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IssueTemplate {
+    /// File name within the template directory, e.g. `bug_report.md`.
+    pub file_name: String,
+    pub name: String,
+    /// The template's `about` (Markdown) or `description` (form).
+    pub about: String,
+    /// Title prefilled for new issues.
+    pub title: String,
+    pub labels: Vec<String>,
+    pub assignees: Vec<String>,
+    pub projects: Vec<String>,
+    /// Markdown body; rendered from the fields for issue forms.
+    pub body: String,
+    /// Fields of an issue form; empty for Markdown templates.
+    pub fields: Vec<IssueFormField>,
+}
+
+/// A pull request template.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PullRequestTemplate {
+    /// Path relative to the repository root.
+    pub path: String,
+    /// File name without its extension.
+    pub name: String,
+    pub body: String,
+}
+
+/// All templates found in a repository.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RepoTemplates {
+    pub issue: Vec<IssueTemplate>,
+    pub pull_request: Vec<PullRequestTemplate>,
+}
+
+/// Directories GitHub searches for templates, in priority order.
+const TEMPLATE_DIRS: [&str; 3] = [".github", "", "docs"];
+
+/// Loads the issue and pull request templates of the repository at `repo`.
+///
+/// Unreadable or malformed templates are skipped with a warning rather than
+/// failing the whole lookup.
+pub fn load_templates(repo: &Path) -> RepoTemplates {
+    RepoTemplates {
+        issue: issue_templates(repo),
+        pull_request: pull_request_templates(repo),
+    }
+}
+
+fn issue_templates(repo: &Path) -> Vec<IssueTemplate> {
+    let Some(dir) = find_entry(&repo.join(".github"), "ISSUE_TEMPLATE") else {
+        return Vec::new();
+    };
+
+    let mut templates: Vec<IssueTemplate> = sorted_files(&dir)
+        .into_iter()
+        .filter_map(|path| {
+            let file_name = path.file_name()?.to_string_lossy().into_owned();
+            let extension = path.extension()?.to_string_lossy().to_lowercase();
+            let stem = path.file_stem()?.to_string_lossy().into_owned();
+            // config.yml configures the template chooser and is not a template
+            if stem.eq_ignore_ascii_case("config") {
+                return None;
+            }
+            let content = fs::read_to_string(&path).ok()?;
+            let parsed = match extension.as_str() {
+                "md" => parse_markdown_template(&file_name, &content),
+                "yml" | "yaml" => parse_issue_form(&file_name, &content),
+                _ => return None,
+            };
+            match parsed {
+                Ok(template) => Some(template),
+                Err(e) => {
+                    log::warn!("Skipping issue template {}: {}", path.display(), e);
+                    None
+                }
+            }
+        })
+        .collect();
+    templates.sort_by_key(|t| t.name.to_lowercase());
+    templates
+}
+
+fn pull_request_templates(repo: &Path) -> Vec<PullRequestTemplate> {
+    let mut templates = Vec::new();
+    for dir in TEMPLATE_DIRS {
+        let dir = repo.join(dir);
+        if let Some(path) = find_entry(&dir, "pull_request_template.md") {
+            templates.extend(read_pull_request_template(repo, &path));
+        }
+        if let Some(multi) = find_entry(&dir, "PULL_REQUEST_TEMPLATE") {
+            for path in sorted_files(&multi) {
+                let is_markdown = path
+                    .extension()
+                    .is_some_and(|e| e.eq_ignore_ascii_case("md"));
+                if is_markdown {
+                    templates.extend(read_pull_request_template(repo, &path));
+                }
+            }
+        }
+    }
+    templates
+}
+
+fn read_pull_request_template(repo: &Path, path: &Path) -> Option<PullRequestTemplate> {
+    let body = fs::read_to_string(path).ok()?;
+    Some(PullRequestTemplate {
+        path: path
+            .strip_prefix(repo)
+            .unwrap_or(path)
+            .to_string_lossy()
+            .replace('\\', "/"),
+        name: path.file_stem()?.to_string_lossy().into_owned(),
+        body,
+    })
+}
+
+/// Returns the entry of `dir` named `name`, ignoring ASCII case.
+fn find_entry(dir: &Path, name: &str) -> Option<PathBuf> {
+    fs::read_dir(dir)
+        .ok()?
+        .flatten()
+        .find(|entry| {
+            entry
+                .file_name()
+                .to_string_lossy()
+                .eq_ignore_ascii_case(name)
+        })
+        .map(|entry| entry.path())
+}
+
+/// Regular files directly inside `dir`, sorted by name.
+fn sorted_files(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file())
+        .collect();
+    files.sort();
+    files
+}
+
+/// Accepts `labels: bug, help wanted` as well as `labels: [bug]`.
+fn string_or_list<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<String>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum StringOrList {
+        One(String),
+        Many(Vec<String>),
+    }
+
+    Ok(match Option::<StringOrList>::deserialize(deserializer)? {
+        Some(StringOrList::One(s)) => s
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(String::from)
+            .collect(),
+        Some(StringOrList::Many(list)) => list,
+        None => Vec::new(),
+    })
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct TemplateHeader {
+    name: Option<String>,
+    about: Option<String>,
+    description: Option<String>,
+    title: Option<String>,
+    #[serde(deserialize_with = "string_or_list")]
+    labels: Vec<String>,
+    #[serde(deserialize_with = "string_or_list")]
+    assignees: Vec<String>,
+    #[serde(deserialize_with = "string_or_list")]
+    projects: Vec<String>,
+}
+
+impl TemplateHeader {
+    fn into_template(
+        self,
+        file_name: &str,
+        body: String,
+        fields: Vec<IssueFormField>,
+    ) -> IssueTemplate {
+        let name = self.name.unwrap_or_else(|| {
+            Path::new(file_name)
+                .file_stem()
+                .map(|s| s.to_string_lossy().into_owned())
+                .unwrap_or_default()
+        });
+        IssueTemplate {
+            file_name: file_name.to_string(),
+            name,
+            about: self.about.or(self.description).unwrap_or_default(),
+            title: self.title.unwrap_or_default(),
+            labels: self.labels,
+            assignees: self.assignees,
+            projects: self.projects,
+            body,
+            fields,
+        }
+    }
+}
+
+/// Parses a Markdown issue template with optional `---` front matter.
+fn parse_markdown_template(file_name: &str, content: &str) -> Result<IssueTemplate, String> {
+    let content = content.trim_start_matches('\u{feff}');
+    let Some(rest) = content.strip_prefix("---") else {
+        return Ok(TemplateHeader::default().into_template(file_name, content.to_string(), vec![]));
+    };
+    let Some(end) = rest.find("\n---") else {
+        return Err("front matter is not closed".to_string());
+    };
+
+    let header: TemplateHeader = if rest[..end].trim().is_empty() {
+        TemplateHeader::default()
+    } else {
+        serde_norway::from_str(&rest[..end]).map_err(|e| e.to_string())?
+    };
+    let body = rest[end + 4..]
+        .split_once('\n')
+        .map_or("", |(_, body)| body)
+        .trim_start_matches(['\r', '\n'])
+        .to_string();
+    Ok(header.into_template(file_name, body, vec![]))
+}
+
+#[derive(Debug, Deserialize)]
+struct IssueForm {
+    #[serde(flatten)]
+    header: TemplateHeader,
+    #[serde(default)]
+    body: Vec<FormElement>,
+}
+
+#[derive(Debug, Deserialize)]
+struct FormElement {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    id: Option<String>,
+    #[serde(default)]
+    attributes: FormAttributes,
+    #[serde(default)]
+    validations: FormValidations,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct FormAttributes {
+    label: String,
+    description: String,
+    placeholder: String,
+    value: String,
+    options: Vec<FormOption>,
+    default: Option<usize>,
+}
+
+/// Dropdown options are strings; checkbox options are `{ label: ... }`.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum FormOption {
+    Plain(String),
+    Checkbox { label: String },
+}
+
+impl FormOption {
+    fn label(&self) -> &str {
+        match self {
+            FormOption::Plain(label) | FormOption::Checkbox { label } => label,
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct FormValidations {
+    required: bool,
+}
+
+/// Parses an issue form, rendering its fields into a Markdown body.
+fn parse_issue_form(file_name: &str, content: &str) -> Result<IssueTemplate, String> {
+    let form: IssueForm = serde_norway::from_str(content).map_err(|e| e.to_string())?;
+
+    let fields: Vec<IssueFormField> = form
+        .body
+        .into_iter()
+        // Markdown elements are instructions shown on the form, not input
+        .filter(|element| element.kind != "markdown")
+        .map(|element| {
+            let options: Vec<String> = element
+                .attributes
+                .options
+                .iter()
+                .map(|o| o.label().to_string())
+                .collect();
+            let value = match element.attributes.default {
+                Some(index) if element.kind == "dropdown" => {
+                    options.get(index).cloned().unwrap_or_default()
+                }
+                _ => element.attributes.value,
+            };
+            IssueFormField {
+                id: element.id,
+                kind: element.kind,
+                label: element.attributes.label,
+                description: element.attributes.description,
+                placeholder: element.attributes.placeholder,
+                value,
+                options,
+                required: element.validations.required,
+            }
+        })
+        .collect();
+
+    let body = render_form_body(&fields);
+    Ok(form.header.into_template(file_name, body, fields))
+}
+
+/// Renders form fields as `### Label` sections, the way GitHub writes a
+/// submitted issue form.
+fn render_form_body(fields: &[IssueFormField]) -> String {
+    fields
+        .iter()
+        .map(|field| {
+            let content = if field.kind == "checkboxes" {
+                field
+                    .options
+                    .iter()
+                    .map(|option| format!("- [ ] {option}"))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            } else {
+                field.value.trim_end().to_string()
+            };
+            format!("### {}\n\n{}", field.label, content)
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(root: &Path, path: &str, content: &str) {
+        let path = root.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn test_parse_markdown_template() {
+        let content = "---\nname: Bug report\nabout: Something broke\ntitle: '[BUG] '\nlabels: bug, triage\nassignees: ''\n---\n\n**Describe the bug**\n";
+        let template = parse_markdown_template("bug.md", content).unwrap();
+        assert_eq!(template.name, "Bug report");
+        assert_eq!(template.about, "Something broke");
+        assert_eq!(template.title, "[BUG] ");
+        assert_eq!(template.labels, vec!["bug", "triage"]);
+        assert!(template.assignees.is_empty());
+        assert_eq!(template.body, "**Describe the bug**\n");
+
+        let plain = parse_markdown_template("plain.md", "Just text").unwrap();
+        assert_eq!(plain.name, "plain");
+        assert_eq!(plain.body, "Just text");
+    }
+
+    #[test]
+    fn test_parse_issue_form() {
+        let content = r#"
+name: Bug
+description: File a bug
+title: "[Bug]: "
+labels: ["bug"]
+projects: ["octo-org/1"]
+body:
+  - type: markdown
+    attributes:
+      value: Thanks for reporting!
+  - type: textarea
+    id: what
+    attributes:
+      label: What happened?
+      placeholder: Tell us
+    validations:
+      required: true
+  - type: dropdown
+    id: os
+    attributes:
+      label: OS
+      options: [macOS, Linux, Windows]
+      default: 1
+  - type: checkboxes
+    attributes:
+      label: Checks
+      options:
+        - label: I searched existing issues
+"#;
+        let template = parse_issue_form("bug.yml", content).unwrap();
+        assert_eq!(template.about, "File a bug");
+        assert_eq!(template.labels, vec!["bug"]);
+        assert_eq!(template.projects, vec!["octo-org/1"]);
+        assert_eq!(template.fields.len(), 3);
+        assert!(template.fields[0].required);
+        assert_eq!(template.fields[1].value, "Linux");
+        assert_eq!(
+            template.fields[2].options,
+            vec!["I searched existing issues"]
+        );
+        assert_eq!(
+            template.body,
+            "### What happened?\n\n\n\n### OS\n\nLinux\n\n### Checks\n\n- [ ] I searched existing issues"
+        );
+    }
+
+    #[test]
+    fn test_load_templates() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        write(
+            root,
+            ".github/ISSUE_TEMPLATE/feature.md",
+            "---\nname: Feature\n---\nIdea",
+        );
+        write(
+            root,
+            ".github/ISSUE_TEMPLATE/bug.yml",
+            "name: Bug\nbody: []\n",
+        );
+        write(
+            root,
+            ".github/ISSUE_TEMPLATE/config.yml",
+            "blank_issues_enabled: false\n",
+        );
+        write(
+            root,
+            ".github/ISSUE_TEMPLATE/broken.yml",
+            "name: [unclosed\n",
+        );
+        write(root, ".github/PULL_REQUEST_TEMPLATE.md", "## Summary\n");
+        write(
+            root,
+            "docs/pull_request_template/release.md",
+            "## Release\n",
+        );
+
+        let templates = load_templates(root);
+        let names: Vec<&str> = templates.issue.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, vec!["Bug", "Feature"]);
+
+        let paths: Vec<&str> = templates
+            .pull_request
+            .iter()
+            .map(|t| t.path.as_str())
+            .collect();
+        assert_eq!(
+            paths,
+            vec![
+                ".github/PULL_REQUEST_TEMPLATE.md",
+                "docs/pull_request_template/release.md"
+            ]
+        );
+        assert_eq!(templates.pull_request[0].body, "## Summary\n");
+
+        assert_eq!(
+            load_templates(&root.join("missing")),
+            RepoTemplates::default()
+        );
+    }
+}
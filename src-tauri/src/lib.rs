@@ -1,5 +1,6 @@
 mod commands;
 mod core;
+mod forge;
 mod git;
 mod github;
 
@@ -8,11 +9,17 @@ use std::sync::Arc;
 use tauri::menu::{MenuBuilder, MenuItem, SubmenuBuilder};
 use tauri::{Emitter, Manager};
 
+use core::git_operations::GitOperations;
+use core::integration_manager::IntegrationManager;
 use core::marketplace_manager::MarketplaceManager;
 use core::mcp_manager::McpManager;
 use core::plugin_manager::PluginManager;
 use core::status_server::StatusServer;
-use core::{ClaudeEvent, EventBus, TranscriptWatcher};
+use core::task_queue::TaskQueueEvent;
+use core::{
+    ClaudeEvent, ConflictDetector, EventBus, MessageBus, PrMonitor, RepoWatcher, ReviewFeedback,
+    SnapshotManager, TaskQueue, TranscriptWatcher,
+};
 use core::ProcessManager;
 use core::session_manager::SessionManager;
 use core::worktree_manager::WorktreeManager;
@@ -105,25 +112,186 @@ pub fn run() {
                 _ => {}
             }
         })
+        .manage(GitOperations::new())
         .manage(MarketplaceManager::new())
         .manage(McpManager::new())
         .manage(PluginManager::new())
         .manage(ProcessManager::new())
         .manage(SessionManager::new())
         .manage(WorktreeManager::new())
+        .manage(IntegrationManager::new())
         .setup(|app| {
             // Generate a unique instance ID for this Maestro run
             // This prevents status pollution between different app instances
             let instance_id = uuid::Uuid::new_v4().to_string();
             log::info!("Maestro instance ID: {}", instance_id);
 
+            // Create SnapshotManager - records WIP snapshots of session
+            // worktrees on hidden refs, on a timer and after file edits
+            let snapshot_manager = Arc::new(SnapshotManager::new());
+
             // Create EventBus - emits events to frontend via Tauri
             let app_handle_for_bus = app.handle().clone();
+            let snapshot_manager_for_bus = snapshot_manager.clone();
             let emit_fn: Arc<dyn Fn(ClaudeEvent) + Send + Sync> = Arc::new(move |event: ClaudeEvent| {
+                snapshot_manager_for_bus.note_event(&event);
                 let _ = app_handle_for_bus.emit("claude-event", &event);
             });
             let event_bus = Arc::new(EventBus::new(emit_fn));
 
+            // Create MessageBus - durable cross-session mailbox, announces
+            // deliveries on the EventBus
+            let message_bus = Arc::new(MessageBus::new(
+                Some(MessageBus::default_store_path()),
+                Some(event_bus.clone()),
+            ));
+
+            // Create TaskQueue - types queued prompts into idle worker sessions
+            // and opens new sessions when needed
+            let process_manager = app.state::<ProcessManager>().inner().clone();
+            let app_handle_for_tasks = app.handle().clone();
+            let task_queue = Arc::new(TaskQueue::new(
+                Arc::new(move |session_id: u32, text: &str| {
+                    process_manager
+                        .write_stdin(session_id, text)
+                        .map_err(|e| e.to_string())
+                }),
+                Arc::new(move |event: TaskQueueEvent| {
+                    let result = match event {
+                        TaskQueueEvent::TaskUpdated { task } => {
+                            app_handle_for_tasks.emit("task-queue-updated", &task)
+                        }
+                        TaskQueueEvent::SpawnRequested { request } => {
+                            let app = app_handle_for_tasks.clone();
+                            tauri::async_runtime::spawn(
+                                commands::session_launch::spawn_task_session(app, request),
+                            );
+                            Ok(())
+                        }
+                    };
+                    if let Err(e) = result {
+                        log::error!("Failed to emit task queue event: {}", e);
+                    }
+                }),
+            ));
+
+            let task_queue_for_expiry = task_queue.clone();
+            tauri::async_runtime::spawn(async move {
+                let mut interval = tokio::time::interval(std::time::Duration::from_secs(15));
+                loop {
+                    interval.tick().await;
+                    task_queue_for_expiry.expire_spawning(core::task_queue::SPAWN_TIMEOUT);
+                    task_queue_for_expiry.expire_dispatched(core::task_queue::DISPATCH_TIMEOUT);
+                }
+            });
+
+            // Create PrMonitor - polls checks and reviews of session-linked PRs
+            // and announces changes on the EventBus
+            let app_handle_for_prs = app.handle().clone();
+            let pr_monitor = Arc::new(PrMonitor::new(
+                Some(event_bus.clone()),
+                Some(Arc::new(move |status: &core::pr_monitor::PrStatus| {
+                    if let Err(e) = app_handle_for_prs.emit("pr-status-changed", status) {
+                        log::error!("Failed to emit pr-status-changed: {}", e);
+                    }
+                })),
+            ));
+            let pr_monitor_for_poll = pr_monitor.clone();
+            let app_handle_for_poll = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                let mut interval = tokio::time::interval(core::pr_monitor::DEFAULT_POLL_INTERVAL);
+                loop {
+                    interval.tick().await;
+                    let sessions = app_handle_for_poll.state::<SessionManager>().all_sessions();
+                    let watched = PrMonitor::watched_from_sessions(&sessions);
+                    pr_monitor_for_poll.poll(&watched).await;
+                }
+            });
+
+            // Create ReviewFeedback - collects new review comments and failing
+            // checks on session PRs and types them into the session once idle
+            let process_manager_for_feedback = app.state::<ProcessManager>().inner().clone();
+            let app_handle_for_feedback = app.handle().clone();
+            let review_feedback = Arc::new(ReviewFeedback::new(
+                Arc::new(move |session_id: u32, text: &str| {
+                    process_manager_for_feedback
+                        .write_stdin(session_id, text)
+                        .map_err(|e| e.to_string())
+                }),
+                Some(Arc::new(move |batch: &core::review_feedback::FeedbackBatch| {
+                    if let Err(e) = app_handle_for_feedback.emit("review-feedback-changed", batch) {
+                        log::error!("Failed to emit review-feedback-changed: {}", e);
+                    }
+                })),
+            ));
+            let review_feedback_for_poll = review_feedback.clone();
+            let pr_monitor_for_feedback = pr_monitor.clone();
+            let app_handle_for_feedback_poll = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                let mut interval =
+                    tokio::time::interval(core::review_feedback::DEFAULT_POLL_INTERVAL);
+                loop {
+                    interval.tick().await;
+                    let session_manager = app_handle_for_feedback_poll.state::<SessionManager>();
+                    let sessions = session_manager.all_sessions();
+                    for (session_id, link) in ReviewFeedback::discover_pull_requests(&sessions).await {
+                        session_manager.link_pull_request(session_id, link);
+                    }
+                    review_feedback_for_poll
+                        .poll(&session_manager.all_sessions(), &pr_monitor_for_feedback)
+                        .await;
+                }
+            });
+
+            // Create ConflictDetector - dry-run merges session worktrees against
+            // each other and the base branch, announcing new conflict risks
+            let app_handle_for_conflicts = app.handle().clone();
+            let conflict_detector = Arc::new(ConflictDetector::new(
+                Some(event_bus.clone()),
+                Some(Arc::new(move |risks: &[core::conflict_detector::ConflictRisk]| {
+                    if let Err(e) = app_handle_for_conflicts.emit("conflict-risks-changed", risks) {
+                        log::error!("Failed to emit conflict-risks-changed: {}", e);
+                    }
+                })),
+            ));
+            let conflict_detector_for_poll = conflict_detector.clone();
+            let app_handle_for_analyze = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                let mut interval =
+                    tokio::time::interval(core::conflict_detector::DEFAULT_ANALYZE_INTERVAL);
+                loop {
+                    interval.tick().await;
+                    let sessions = app_handle_for_analyze.state::<SessionManager>().all_sessions();
+                    let worktree_manager = app_handle_for_analyze.state::<WorktreeManager>();
+                    conflict_detector_for_poll.run(&sessions, &worktree_manager).await;
+                }
+            });
+
+            let snapshot_manager_for_poll = snapshot_manager.clone();
+            let app_handle_for_snapshot_poll = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                let mut interval =
+                    tokio::time::interval(core::snapshot_manager::DEFAULT_SNAPSHOT_INTERVAL);
+                loop {
+                    let trigger = snapshot_manager_for_poll
+                        .next_trigger(&mut interval, core::snapshot_manager::EDIT_DEBOUNCE)
+                        .await;
+                    let sessions = app_handle_for_snapshot_poll.state::<SessionManager>().all_sessions();
+                    snapshot_manager_for_poll.run(&sessions, &trigger).await;
+                }
+            });
+
+            // Create RepoWatcher - pushes branch and dirty-state changes of
+            // watched repositories and worktrees so the UI doesn't poll
+            let app_handle_for_repos = app.handle().clone();
+            let repo_watcher = Arc::new(RepoWatcher::new(Some(Arc::new(
+                move |change: &core::RepoChanged| {
+                    if let Err(e) = app_handle_for_repos.emit("repo-changed", change) {
+                        log::error!("Failed to emit repo-changed: {}", e);
+                    }
+                },
+            ))));
+
             // Create TranscriptWatcher
             let transcript_watcher = Arc::new(TranscriptWatcher::new(event_bus.clone()));
 
@@ -145,8 +313,19 @@ pub fn run() {
             // IMPORTANT: This must be done synchronously so the server is ready
             // before any commands try to use it
             let app_handle = app.handle().clone();
+            let message_bus_for_server = message_bus.clone();
+            let task_queue_for_server = task_queue.clone();
+            let review_feedback_for_server = review_feedback.clone();
             let server = tauri::async_runtime::block_on(async {
-                StatusServer::start(app_handle, instance_id, Some(hook_emit_fn)).await
+                StatusServer::start(
+                    app_handle,
+                    instance_id,
+                    Some(hook_emit_fn),
+                    Some(message_bus_for_server),
+                    Some(task_queue_for_server),
+                    Some(review_feedback_for_server),
+                )
+                .await
             });
 
             match server {
@@ -166,6 +345,13 @@ pub fn run() {
             }
 
             app.manage(event_bus);
+            app.manage(message_bus);
+            app.manage(task_queue);
+            app.manage(pr_monitor);
+            app.manage(review_feedback);
+            app.manage(conflict_detector);
+            app.manage(snapshot_manager);
+            app.manage(repo_watcher);
             app.manage(transcript_watcher);
 
             Ok(())
@@ -176,6 +362,8 @@ pub fn run() {
             commands::terminal::write_stdin,
             commands::terminal::resize_pty,
             commands::terminal::kill_session,
+            commands::terminal::get_session_ports,
+            commands::terminal::list_port_allocations,
             commands::terminal::kill_all_sessions,
             commands::terminal::check_cli_available,
             commands::terminal::get_backend_info,
@@ -191,6 +379,9 @@ pub fn run() {
             commands::git::git_worktree_add,
             commands::git::git_worktree_remove,
             commands::git::git_commit_log,
+            commands::git::git_blame,
+            commands::git::git_file_log,
+            commands::git::git_commit_graph,
             commands::git::git_checkout_branch,
             commands::git::git_create_branch,
             commands::git::git_commit_files,
@@ -202,6 +393,9 @@ pub fn run() {
             commands::git::git_refs_for_commit,
             commands::git::git_fetch,
             commands::git::git_fetch_all,
+            commands::git::cancel_git_operation,
+            commands::git::git_watch_repository,
+            commands::git::git_unwatch_repository,
             commands::git::git_test_remote,
             commands::git::git_set_remote_url,
             commands::git::git_get_default_branch,
@@ -209,11 +403,30 @@ pub fn run() {
             commands::git::is_git_repository,
             commands::git::is_git_worktree,
             commands::git::detect_repositories,
+            commands::git::git_stage_all,
+            commands::git::git_staged_files,
+            commands::git::git_commit,
+            commands::git::git_push,
+            commands::git::git_working_tree_status,
+            commands::git::git_diff,
+            commands::git::git_default_base_branch,
+            commands::git::session_diff,
+            commands::git::git_stash_list,
+            commands::git::git_stash_push,
+            commands::git::git_stash_apply,
+            commands::git::git_stash_pop,
+            commands::git::git_stash_drop,
+            commands::git::git_stash_diff,
+            commands::git::git_snapshot_create,
+            commands::git::git_snapshot_list,
+            commands::git::git_snapshot_diff,
+            commands::git::git_snapshot_restore,
             // Session commands (new)
             commands::session::get_sessions,
             commands::session::create_session,
             commands::session::update_session_status,
             commands::session::assign_session_branch,
+            commands::session::link_session_pull_request,
             commands::session::remove_session,
             commands::session::get_sessions_for_project,
             commands::session::remove_sessions_for_project,
@@ -222,6 +435,12 @@ pub fn run() {
             commands::worktree::cleanup_session_worktree,
             commands::worktree::get_default_worktree_base_dir,
             commands::worktree::has_managed_worktree,
+            commands::worktree::get_worktree_usage,
+            commands::worktree::collect_worktree_garbage,
+            commands::bootstrap::get_worktree_bootstrap,
+            commands::bootstrap::save_worktree_bootstrap,
+            commands::bootstrap::run_worktree_bootstrap,
+            commands::bootstrap::measure_worktree_clone_dirs,
             // MCP commands
             commands::mcp::get_project_mcp_servers,
             commands::mcp::refresh_project_mcp_servers,
@@ -291,17 +510,77 @@ pub fn run() {
             commands::github::github_list_prs,
             commands::github::github_get_pr,
             commands::github::github_create_pr,
+            commands::github::github_edit_pr,
             commands::github::github_merge_pr,
             commands::github::github_close_pr,
             commands::github::github_comment_pr,
             commands::github::github_list_issues,
             commands::github::github_list_discussions,
             commands::github::github_get_issue,
+            commands::github::github_create_issue,
+            commands::github::github_edit_issue,
+            commands::github::github_templates,
             commands::github::github_comment_issue,
             commands::github::github_close_issue,
             commands::github::github_reopen_issue,
             commands::github::github_get_discussion,
             commands::github::github_comment_discussion,
+            commands::github::github_pr_checks,
+            commands::github::github_pr_reviews,
+            commands::github::github_list_review_threads,
+            commands::github::github_reply_review_thread,
+            commands::github::github_resolve_review_thread,
+            commands::github::github_start_review,
+            commands::github::github_add_review_comment,
+            commands::github::github_submit_review,
+            commands::github::github_discard_review,
+            commands::github::github_list_runs,
+            commands::github::github_get_run,
+            commands::github::github_run_failed_log,
+            commands::github::github_job_log,
+            commands::github::github_run_failing_steps,
+            commands::github::github_send_failing_step,
+            commands::github::github_rerun_failed_jobs,
+            commands::github::github_rerun_job,
+            commands::github::github_cancel_run,
+            // Forge commands (GitHub via gh, GitLab and Gitea via REST)
+            commands::forge::forge_detect,
+            commands::forge::forge_set_token,
+            commands::forge::forge_delete_token,
+            commands::forge::forge_auth_status,
+            commands::forge::forge_list_prs,
+            commands::forge::forge_get_pr,
+            commands::forge::forge_create_pr,
+            commands::forge::forge_merge_pr,
+            commands::forge::forge_close_pr,
+            commands::forge::forge_comment_pr,
+            commands::forge::forge_list_issues,
+            commands::forge::forge_get_issue,
+            commands::forge::forge_comment_issue,
+            commands::forge::forge_close_issue,
+            commands::forge::forge_reopen_issue,
+            // PR status monitor commands
+            commands::pr_status::pr_status_list,
+            commands::pr_status::pr_status_get,
+            commands::pr_status::pr_status_refresh,
+            commands::review_feedback::review_feedback_list,
+            commands::review_feedback::review_feedback_send,
+            commands::review_feedback::review_feedback_dismiss,
+            commands::review_feedback::review_feedback_get_policy,
+            commands::review_feedback::review_feedback_set_policy,
+            // Conflict detection commands
+            commands::conflicts::conflict_risks_list,
+            commands::conflicts::conflict_risks_refresh,
+            // Branch integration commands
+            commands::integration::integration_start,
+            commands::integration::session_integrate,
+            commands::integration::integration_continue,
+            commands::integration::integration_abort,
+            commands::integration::integration_list,
+            // Issue session commands
+            commands::issue_session::start_issue_session,
+            // Ship commands
+            commands::ship::ship_session,
             // Update commands
             commands::update::check_for_updates,
             commands::update::download_and_install_update,
@@ -309,6 +588,26 @@ pub fn run() {
             // Hooks commands
             commands::hooks::write_session_hooks_config,
             commands::hooks::remove_session_hooks_config,
+            // Message bus commands
+            commands::messages::message_bus_publish,
+            commands::messages::message_bus_send,
+            commands::messages::message_bus_receive,
+            commands::messages::message_bus_peek,
+            commands::messages::message_bus_pending,
+            commands::messages::message_bus_subscribe,
+            commands::messages::message_bus_unsubscribe,
+            commands::messages::message_bus_topics,
+            // Task queue commands
+            commands::tasks::task_queue_enqueue,
+            commands::tasks::task_queue_list,
+            commands::tasks::task_queue_get,
+            commands::tasks::task_queue_cancel,
+            commands::tasks::task_queue_retry,
+            commands::tasks::task_queue_clear_finished,
+            commands::tasks::task_queue_enroll_session,
+            commands::tasks::task_queue_release_session,
+            commands::tasks::task_queue_get_config,
+            commands::tasks::task_queue_set_config,
         ])
         .run(tauri::generate_context!())
         .expect("error while running Maestro");
@@ -1,7 +1,6 @@
 import { invoke } from "@tauri-apps/api/core";
 import { GitFork, RefreshCw, X } from "lucide-react";
 import { useCallback, useEffect, useRef, useState } from "react";
-import { getDeduplicatedCurrentBranch } from "@/lib/git";
 import { killSession } from "@/lib/terminal";
 import { useOpenProject } from "@/lib/useOpenProject";
 import { useFDAStore } from "@/stores/useFDAStore";
@@ -10,6 +9,7 @@ import { useWorkspaceStore, type RepositoryInfo } from "@/stores/useWorkspaceSto
 import { useGitStore } from "./stores/useGitStore";
 import { useTerminalSettingsStore } from "./stores/useTerminalSettingsStore";
 import { useAppKeyboard } from "./hooks/useAppKeyboard";
+import { useRepoStatus } from "./hooks/useRepoStatus";
 import { useSwipeNavigation } from "./hooks/useSwipeNavigation";
 import { useUpdateStore } from "./stores/useUpdateStore";
 import { initActivityListener, stopActivityListener } from "./stores/useActivityStore";
@@ -186,24 +186,11 @@ function App() {
     }
   }, [activeRepoPath, fetchCommits]);
 
+  // Follow the active repo's branch via repo-changed events
+  const activeRepoStatus = useRepoStatus(activeRepoPath ?? null);
   useEffect(() => {
-    let cancelled = false;
-    if (!activeRepoPath) {
-      setCurrentBranch(undefined);
-      return () => {};
-    }
-    getDeduplicatedCurrentBranch(activeRepoPath)
-      .then((branch) => {
-        if (!cancelled) setCurrentBranch(branch);
-      })
-      .catch((err) => {
-        console.error("Failed to load current branch:", err);
-        if (!cancelled) setCurrentBranch(undefined);
-      });
-    return () => {
-      cancelled = true;
-    };
-  }, [activeRepoPath]);
+    setCurrentBranch(activeRepoStatus?.branch ?? undefined);
+  }, [activeRepoStatus]);
 
   // Rehydrate multi-repo tabs after store rehydration
   useEffect(() => {
@@ -233,14 +220,6 @@ function App() {
           })
           .catch((err) => console.error("Failed to refresh repos on focus:", err));
       }
-
-      // Refresh branch for the active repo
-      const repoPath = tab.selectedRepoPath ?? tab.projectPath;
-      if (repoPath) {
-        getDeduplicatedCurrentBranch(repoPath)
-          .then(setCurrentBranch)
-          .catch(() => {});
-      }
     };
 
     document.addEventListener("visibilitychange", handleVisibility);
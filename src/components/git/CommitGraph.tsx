@@ -1,5 +1,5 @@
 import { useCallback, useEffect, useMemo, useRef, useState } from "react";
-import { layoutGraph, type GraphNode } from "../../lib/graphLayout";
+import { layoutGraphRows, type GraphNode } from "../../lib/graphLayout";
 import { useGitStore } from "../../stores/useGitStore";
 import { CommitRow } from "./CommitRow";
 import { GraphCanvas } from "./GraphCanvas";
@@ -29,20 +29,21 @@ export function CommitGraph({
   // Git store
   const {
     commits,
+    graphRows,
     isLoading,
     isLoadingMore,
     hasMoreCommits,
     error,
     fetchCommits,
     loadMoreCommits,
-    getRefsForCommit,
   } = useGitStore();
 
-  // Refs cache - lazily loaded per commit
-  const [refsCache, setRefsCache] = useState<Map<string, string[]>>(new Map());
-
-  // Compute graph layout
-  const { nodes, rails } = useMemo(() => layoutGraph(commits), [commits]);
+  // Lanes and refs are computed by the backend
+  const { nodes, rails } = useMemo(() => layoutGraphRows(graphRows), [graphRows]);
+  const refsByHash = useMemo(
+    () => new Map(graphRows.map((row) => [row.commit.hash, row.refs])),
+    [graphRows]
+  );
 
   // Head commit hash (first commit on current branch)
   const headCommitHash = useMemo(() => {
@@ -58,31 +59,6 @@ export function CommitGraph({
     }
   }, [repoPath, fetchCommits]);
 
-  // Fetch refs for visible commits
-  useEffect(() => {
-    if (!repoPath || nodes.length === 0) return;
-
-    const fetchVisibleRefs = async () => {
-      const visibleNodes = nodes.slice(visibleRange.start, visibleRange.end + 10);
-      const newRefs = new Map(refsCache);
-      let updated = false;
-
-      for (const node of visibleNodes) {
-        if (!newRefs.has(node.commit.hash)) {
-          const refs = await getRefsForCommit(repoPath, node.commit.hash);
-          newRefs.set(node.commit.hash, refs);
-          updated = true;
-        }
-      }
-
-      if (updated) {
-        setRefsCache(newRefs);
-      }
-    };
-
-    fetchVisibleRefs();
-  }, [repoPath, nodes, visibleRange, refsCache, getRefsForCommit]);
-
   // Handle scroll for infinite loading and visibility tracking
   const handleScroll = useCallback(() => {
     const container = containerRef.current;
@@ -187,7 +163,7 @@ export function CommitGraph({
               node={node}
               isSelected={node.commit.hash === selectedCommitHash}
               isHead={node.commit.hash === headCommitHash}
-              refs={refsCache.get(node.commit.hash) ?? []}
+              refs={refsByHash.get(node.commit.hash) ?? []}
               onClick={() => onSelectCommit(node)}
               graphAreaWidth={graphAreaWidth}
             />
@@ -36,14 +36,17 @@ export function GitGraphPanel({
   const [selectedIssueNumber, setSelectedIssueNumber] = useState<number | null>(null);
   const [selectedDiscussionNumber, setSelectedDiscussionNumber] = useState<number | null>(null);
   const [activeTab, setActiveTab] = useState<GitPanelTab>("commits");
+  const [forgeToken, setForgeTokenInput] = useState("");
 
   const { checkoutBranch, createBranch } = useGitStore();
   const {
+    forge,
     authStatus,
     pullRequests,
     issues,
     prsError,
     checkAuth,
+    setForgeToken,
     fetchPullRequests,
     fetchIssues,
     fetchDiscussions,
@@ -197,6 +200,36 @@ export function GitGraphPanel({
 
   // Check for gh CLI not installed or not authenticated
   const isGhError = prsError?.includes("gh") || prsError?.includes("GitHub CLI");
+
+  // Personal access token for the forge host; on GitHub it is used when gh
+  // is not installed
+  const tokenForm = (
+    <form
+      className="flex flex-col items-center gap-2"
+      onSubmit={(e) => {
+        e.preventDefault();
+        if (!repoPath || !forgeToken.trim()) return;
+        setForgeToken(repoPath, forgeToken.trim())
+          .then(() => setForgeTokenInput(""))
+          .catch((err) => console.error("Failed to save forge token:", err));
+      }}
+    >
+      <input
+        type="password"
+        value={forgeToken}
+        onChange={(e) => setForgeTokenInput(e.target.value)}
+        placeholder="Personal access token"
+        className="w-56 rounded bg-maestro-card px-2 py-1 text-xs text-maestro-text placeholder:text-maestro-muted/40 focus:outline-none"
+      />
+      <button
+        type="submit"
+        className="rounded bg-maestro-card px-3 py-1 text-xs text-maestro-muted/60 transition-colors hover:bg-maestro-border hover:text-maestro-text"
+      >
+        Save token
+      </button>
+    </form>
+  );
+
   const showAuthPrompt =
     activeTab !== "commits" && authStatus && !authStatus.logged_in;
 
@@ -287,6 +320,10 @@ export function GitGraphPanel({
                   >
                     Install GitHub CLI
                   </a>
+                  <p className="text-[10px] text-maestro-muted/40">
+                    or use a personal access token instead
+                  </p>
+                  {tokenForm}
                 </div>
               </div>
             ) : showAuthPrompt ? (
@@ -299,18 +336,23 @@ export function GitGraphPanel({
                     strokeWidth={1}
                   />
                   <p className="text-xs text-maestro-muted/60">
-                    Not authenticated with GitHub
-                  </p>
-                  <p className="text-[10px] text-maestro-muted/40">
-                    Run <code className="rounded bg-maestro-card px-1 py-0.5">gh auth login</code> in your terminal
+                    Not authenticated with {forge && forge.kind !== "github" ? forge.host : "GitHub"}
                   </p>
-                  <button
-                    type="button"
-                    onClick={() => repoPath && checkAuth(repoPath)}
-                    className="mt-1 rounded bg-maestro-card px-3 py-1 text-xs text-maestro-muted/60 transition-colors hover:bg-maestro-border hover:text-maestro-text"
-                  >
-                    Retry
-                  </button>
+                  {forge?.kind === "github" && (
+                    <>
+                      <p className="text-[10px] text-maestro-muted/40">
+                        Run <code className="rounded bg-maestro-card px-1 py-0.5">gh auth login</code> in your terminal, or add a token
+                      </p>
+                      <button
+                        type="button"
+                        onClick={() => repoPath && checkAuth(repoPath)}
+                        className="mt-1 rounded bg-maestro-card px-3 py-1 text-xs text-maestro-muted/60 transition-colors hover:bg-maestro-border hover:text-maestro-text"
+                      >
+                        Retry
+                      </button>
+                    </>
+                  )}
+                  {tokenForm}
                 </div>
               </div>
             ) : (
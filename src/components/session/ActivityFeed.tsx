@@ -102,6 +102,55 @@ function EventRow({ event }: { event: ClaudeEvent }) {
           <span className="text-neutral-400">{event.reason}</span>
         </div>
       );
+    case "MessageReceived":
+      return (
+        <div className="flex gap-2 text-cyan-400">
+          <span className="text-neutral-600 shrink-0">{time}</span>
+          <span className="shrink-0">MSG</span>
+          <span className="font-semibold shrink-0">
+            {event.topic}
+            {event.from_session !== null && ` ← #${event.from_session}`}
+          </span>
+          <span className="text-neutral-400 truncate">{event.body}</span>
+        </div>
+      );
+    case "PullRequestStatusChanged":
+      return (
+        <div
+          className={`flex gap-2 ${
+            event.checks_state === "failing"
+              ? "text-red-400"
+              : event.checks_state === "passing"
+                ? "text-green-400"
+                : "text-neutral-400"
+          }`}
+        >
+          <span className="text-neutral-600 shrink-0">{time}</span>
+          <span className="shrink-0">PR #{event.number}</span>
+          <span className="font-semibold shrink-0">{event.checks_state}</span>
+          <span className="text-neutral-400 truncate">
+            {event.failing_checks.join(", ")}
+            {event.review_decision && ` ${event.review_decision}`}
+          </span>
+        </div>
+      );
+    case "ConflictRiskDetected":
+      return (
+        <div
+          className={`flex gap-2 ${
+            event.level === "conflict" ? "text-red-400" : "text-yellow-400"
+          }`}
+        >
+          <span className="text-neutral-600 shrink-0">{time}</span>
+          <span className="font-semibold shrink-0">{event.level}</span>
+          <span className="shrink-0">
+            {event.other_session_id !== null
+              ? `with #${event.other_session_id} (${event.against})`
+              : `with ${event.against}`}
+          </span>
+          <span className="text-neutral-400 truncate">{event.files.join(", ")}</span>
+        </div>
+      );
     default:
       return null;
   }
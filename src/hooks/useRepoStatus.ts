@@ -0,0 +1,56 @@
+import { listen } from "@tauri-apps/api/event";
+import { unwatchRepository, watchRepository, type RepoChanged } from "@/lib/git";
+import { useEffect, useState } from "react";
+
+/**
+ * Hook that returns the live status (branch, uncommitted changes, worktrees)
+ * of a repository or worktree.
+ *
+ * The backend watches the repository's working tree and `.git` directory and
+ * pushes `repo-changed` events when the status changes, so nothing is polled.
+ * The watch is released on unmount or when `repoPath` changes.
+ *
+ * Returns `null` until the first status arrives, or when `repoPath` is empty
+ * or `enabled` is false.
+ */
+export function useRepoStatus(
+  repoPath: string | null,
+  enabled: boolean = true,
+): RepoChanged | null {
+  const [status, setStatus] = useState<RepoChanged | null>(null);
+
+  useEffect(() => {
+    setStatus(null);
+    if (!repoPath || !enabled) return;
+
+    let cancelled = false;
+    let watching = false;
+
+    const unlistenPromise = listen<RepoChanged>("repo-changed", (event) => {
+      if (!cancelled && event.payload.repoPath === repoPath) {
+        setStatus(event.payload);
+      }
+    });
+
+    watchRepository(repoPath)
+      .then((initial) => {
+        watching = true;
+        if (cancelled) {
+          void unwatchRepository(repoPath);
+          return;
+        }
+        setStatus(initial);
+      })
+      .catch((err) => {
+        console.warn("Failed to watch repository:", repoPath, err);
+      });
+
+    return () => {
+      cancelled = true;
+      if (watching) void unwatchRepository(repoPath);
+      void unlistenPromise.then((unlisten) => unlisten());
+    };
+  }, [repoPath, enabled]);
+
+  return status;
+}
@@ -1,16 +1,15 @@
-import { getDeduplicatedCurrentBranch } from "@/lib/git";
-import { useEffect, useRef, useState } from "react";
-
-const POLL_INTERVAL_MS = 15_000;
+import { useRepoStatus } from "@/hooks/useRepoStatus";
+import { useEffect, useState } from "react";
 
 /**
  * Hook that returns the live branch name for a terminal session.
  *
  * - Worktree sessions: returns `initialBranch` immediately (branch is locked).
- * - Non-worktree sessions: fetches the real branch on mount, then polls every
- *   15 s so the header stays in sync after `git checkout` / `git switch`.
+ * - Non-worktree sessions: watches the project repository and follows
+ *   `repo-changed` events, so the header stays in sync after
+ *   `git checkout` / `git switch` without polling.
  *
- * Returns `null` while the first fetch is in-flight (caller shows "...").
+ * Returns `null` while the first status is loading (caller shows "...").
  */
 export function useSessionBranch(
   projectPath: string,
@@ -21,7 +20,6 @@ export function useSessionBranch(
   const [branch, setBranch] = useState<string | null>(
     isWorktree ? initialBranch : null,
   );
-  const mountedRef = useRef(true);
 
   // Keep in sync if the store pushes a new initialBranch while mounted
   useEffect(() => {
@@ -30,42 +28,14 @@ export function useSessionBranch(
     }
   }, [isWorktree, initialBranch]);
 
-  // Non-worktree: fetch immediately + poll
+  // Non-worktree: follow the watched repository. Inactive sessions don't
+  // watch, to avoid a boot barrage of watchers and git processes.
+  const status = useRepoStatus(projectPath, !isWorktree && isActive);
   useEffect(() => {
-    mountedRef.current = true;
-
-    if (isWorktree || !projectPath) return;
-
-    // Only reset if we don't have a value yet (initial mount)
-    if (branch === null) {
-      setBranch(null);
+    if (!isWorktree && status) {
+      setBranch(status.branch);
     }
-
-    const fetchBranch = (force = false) => {
-      // Skip if window is blurred or tab is inactive to avoid annoying
-      // macOS permission pop-ups in the background.
-      // On mount (force=true), we STILL respect isActive to prevent boot barrage.
-      if (!isActive || (!force && !document.hasFocus())) return;
-
-      getDeduplicatedCurrentBranch(projectPath)
-        .then((name) => {
-          if (mountedRef.current) setBranch(name);
-        })
-        .catch(() => {
-          if (mountedRef.current) setBranch(null);
-        });
-    };
-
-    // Initial fetch - no longer "forced" if inactive
-    fetchBranch(true);
-
-    const id = setInterval(() => fetchBranch(false), POLL_INTERVAL_MS);
-
-    return () => {
-      clearInterval(id);
-      mountedRef.current = false;
-    };
-  }, [isWorktree, projectPath, isActive]);
+  }, [isWorktree, status]);
 
   return branch;
 }
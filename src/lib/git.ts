@@ -1,5 +1,6 @@
 import { invoke } from "@tauri-apps/api/core";
-import { listWorktrees } from "./worktreeManager";
+import type { CommitInfo, FileChangeStatus } from "@/stores/useGitStore";
+import { listWorktrees, type WorktreeInfo } from "./worktreeManager";
 
 /** Branch info from the backend. */
 export interface BranchInfo {
@@ -93,3 +94,217 @@ export async function getDeduplicatedCurrentBranch(repoPath: string): Promise<st
   activeFetches.set(repoPath, promise);
   return promise;
 }
+
+/** A work-in-progress snapshot of a worktree, stored on a hidden ref. */
+export interface Snapshot {
+  commit: string;
+  tree: string;
+  head: string | null;
+  /** Why it was taken, e.g. "interval", "file-edited", "before-restore". */
+  reason: string;
+  session_id: number | null;
+  /** Unix timestamp in seconds. */
+  timestamp: number;
+}
+
+/**
+ * Lists a worktree's snapshots, newest first.
+ * @param repoPath - Path to the worktree
+ * @param limit - Maximum number of snapshots (backend default 50)
+ */
+export async function listSnapshots(repoPath: string, limit?: number): Promise<Snapshot[]> {
+  return invoke<Snapshot[]>("git_snapshot_list", { repoPath, limit });
+}
+
+/**
+ * Restores files from a snapshot into the worktree without touching the
+ * index or branch.
+ * @param repoPath - Path to the worktree
+ * @param snapshot - Snapshot commit to restore from
+ * @param paths - Files to restore; all files when omitted
+ * @returns The snapshot taken just before restoring, if anything changed
+ */
+export async function restoreSnapshot(
+  repoPath: string,
+  snapshot: string,
+  paths?: string[]
+): Promise<Snapshot | null> {
+  return invoke<Snapshot | null>("git_snapshot_restore", { repoPath, snapshot, paths });
+}
+
+/** An entry in the stash list. */
+export interface StashEntry {
+  /** Position in the stash list; 0 is the most recent. */
+  index: number;
+  /** Reflog selector, e.g. "stash@{0}". */
+  reference: string;
+  commit: string;
+  message: string;
+  branch: string | null;
+  includes_untracked: boolean;
+  /** Unix timestamp in seconds. */
+  timestamp: number;
+}
+
+/** What an auto-stashing checkout did besides switching branches. */
+export interface AutostashOutcome {
+  /** Local changes stashed before leaving the previous branch. */
+  stashed: StashEntry | null;
+  /** An earlier auto-stash of the target branch that was re-applied. */
+  restored: StashEntry | null;
+  /** Why re-applying the earlier auto-stash failed; the stash is kept. */
+  restore_error: string | null;
+}
+
+/**
+ * Lists stashes, most recent first.
+ * @param repoPath - Path to the git repository
+ */
+export async function listStashes(repoPath: string): Promise<StashEntry[]> {
+  return invoke<StashEntry[]>("git_stash_list", { repoPath });
+}
+
+/**
+ * Stashes local changes.
+ * @param repoPath - Path to the git repository
+ * @param message - Optional stash message
+ * @param includeUntracked - Also stash untracked files
+ * @returns The new entry, or null if there was nothing to stash
+ */
+export async function pushStash(
+  repoPath: string,
+  message?: string,
+  includeUntracked = false
+): Promise<StashEntry | null> {
+  return invoke<StashEntry | null>("git_stash_push", { repoPath, message, includeUntracked });
+}
+
+/** A progress update from a long-running git operation (`git-progress` event). */
+export interface GitProgress {
+  operation_id: string;
+  /** Progress meter title, e.g. "Receiving objects" or "Resolving deltas". */
+  phase: string;
+  /** Whether the meter was reported by the remote. */
+  remote: boolean;
+  percent: number | null;
+  current: number | null;
+  total: number | null;
+  /** Transfer size and rate, e.g. "1.20 MiB | 2.00 MiB/s". */
+  throughput: string | null;
+  /** The phase has finished. */
+  done: boolean;
+}
+
+/**
+ * Cancels a running git operation (fetch, worktree creation, plugin clone),
+ * killing its git process.
+ * @param operationId - The ID passed to (or generated for) the operation
+ * @returns false if no operation with that ID is running
+ */
+export async function cancelGitOperation(operationId: string): Promise<boolean> {
+  return invoke<boolean>("cancel_git_operation", { operationId });
+}
+
+/** Fresh status of a watched repository (`repo-changed` event). */
+export interface RepoChanged {
+  /** The path passed to `watchRepository`. */
+  repoPath: string;
+  /** Current branch, or the short hash when HEAD is detached. */
+  branch: string | null;
+  uncommittedCount: number | null;
+  worktrees: WorktreeInfo[];
+}
+
+/**
+ * Starts watching a repository or worktree for branch, index and file
+ * changes. Updates arrive as `repo-changed` events; each call must be
+ * matched by an `unwatchRepository` call.
+ * @returns The repository's current status
+ */
+export async function watchRepository(repoPath: string): Promise<RepoChanged> {
+  return invoke<RepoChanged>("git_watch_repository", { repoPath });
+}
+
+/**
+ * Releases one `watchRepository` call.
+ * @returns true if other callers are still watching the repository
+ */
+export async function unwatchRepository(repoPath: string): Promise<boolean> {
+  return invoke<boolean>("git_unwatch_repository", { repoPath });
+}
+
+/** Options for `getBlame`. */
+export interface BlameOptions {
+  /** Blame as of this revision instead of the working tree. */
+  rev?: string;
+  /** Only blame lines [start, end] (1-based, inclusive). */
+  lines?: [number, number];
+  /** Commits to look through, e.g. bulk reformatting. */
+  ignore_revs?: string[];
+  /** A file of commits to look through, relative to the repo root. */
+  ignore_revs_file?: string;
+}
+
+/** The commit a blamed range was last changed in. */
+export interface BlameCommit {
+  hash: string;
+  author_name: string;
+  author_email: string;
+  /** Unix timestamp in seconds. */
+  author_time: number;
+  summary: string;
+  /** Parent commit and the file's path there; null where the file was added. */
+  previous: [string, string] | null;
+  boundary: boolean;
+  /** The lines are uncommitted working tree changes. */
+  uncommitted: boolean;
+}
+
+/** Consecutive lines last changed by the same commit. */
+export interface BlameRange {
+  /** First line in the blamed file (1-based). */
+  start_line: number;
+  line_count: number;
+  original_start_line: number;
+  /** The file's path as of `commit` (differs after renames). */
+  original_path: string;
+  commit: BlameCommit;
+  /** Maestro session branch the commit was made on, if any. */
+  session_branch: string | null;
+}
+
+/** A commit in a file's history. */
+export interface FileLogEntry {
+  commit: CommitInfo;
+  status: FileChangeStatus;
+  /** The file's path after this commit. */
+  path: string;
+  /** The file's path before this commit, for renames and copies. */
+  old_path: string | null;
+}
+
+/**
+ * Blames a file, attributing ranges to Maestro session branches where possible.
+ * @param path - File path relative to the repository root
+ */
+export async function getBlame(
+  repoPath: string,
+  path: string,
+  options?: BlameOptions
+): Promise<BlameRange[]> {
+  return invoke<BlameRange[]>("git_blame", { repoPath, path, options });
+}
+
+/**
+ * Lists commits that changed a file, newest first, following renames.
+ * @param skip - Number of commits to skip, for paging further back
+ */
+export async function getFileLog(
+  repoPath: string,
+  path: string,
+  maxCount: number,
+  skip = 0,
+  rev?: string
+): Promise<FileLogEntry[]> {
+  return invoke<FileLogEntry[]>("git_file_log", { repoPath, path, rev, maxCount, skip });
+}
@@ -1,4 +1,4 @@
-import type { CommitInfo } from "../stores/useGitStore";
+import type { CommitInfo, GraphRow } from "../stores/useGitStore";
 
 /**
  * GitKraken-style color palette for rails.
@@ -44,95 +44,51 @@ export interface Rail {
 }
 
 /**
- * Lays out commits into a visual graph structure.
+ * Converts rows laid out by the backend (`git_commit_graph`) into graph nodes.
  *
- * Uses a two-pass algorithm:
- * 1. First pass: Assign columns to all commits based on parent relationships
- * 2. Second pass: Create graph nodes with connection information
+ * Parents that are not loaded yet are drawn off-screen down the lane the
+ * backend reserved for them.
  *
- * @param commits - Array of commits in topological order (newest first)
+ * @param rows - Graph rows in display order, possibly spanning several pages
  * @returns Tuple of graph nodes and rails
  */
-export function layoutGraph(commits: CommitInfo[]): { nodes: GraphNode[]; rails: Rail[] } {
-  if (commits.length === 0) {
-    return { nodes: [], rails: [] };
-  }
-
-  // Pre-compute set of all commit hashes for quick lookup
-  const commitHashSet = new Set(commits.map((c) => c.hash));
-  const totalRows = commits.length;
-
-  // Track which column each commit hash should use
-  const commitToColumn = new Map<string, number>();
-
-  // Track which row each commit is at
-  const commitToRow = new Map<string, number>();
-
-  // Track active columns: column index -> which commit hash is expected to continue there
-  // When we see a commit, we check if any column is "waiting" for it
-  const activeColumns = new Map<number, string>();
-
-  // First pass: assign columns to all commits
-  for (let row = 0; row < commits.length; row++) {
-    const commit = commits[row];
-    commitToRow.set(commit.hash, row);
-
-    // Find column for this commit
-    const column = findColumn(commit, activeColumns, commitToColumn);
-    commitToColumn.set(commit.hash, column);
-
-    // Update active columns based on this commit's parents (only for parents in our set)
-    updateActiveColumns(commit, column, activeColumns, commitHashSet);
-  }
-
-  // Second pass: create graph nodes with connections
-  const nodes: GraphNode[] = [];
-
-  for (let row = 0; row < commits.length; row++) {
-    const commit = commits[row];
-    const column = commitToColumn.get(commit.hash) ?? 0;
-
-    const parentConnections: ParentConnection[] = commit.parent_hashes
-      .map((parentHash) => {
-        const parentColumn = commitToColumn.get(parentHash);
-        const parentRow = commitToRow.get(parentHash);
-
-        if (parentColumn !== undefined && parentRow !== undefined) {
-          // Parent is in our loaded commits - normal connection
-          const connectionType = determineConnectionType(column, parentColumn);
-
-          return {
-            parentHash,
-            parentColumn,
-            parentRow,
-            connectionType,
-            isOffScreen: false,
-          };
-        } else {
-          // Parent exists but is outside loaded range - create off-screen connection
-          // Draw line extending to bottom of visible area in the same column
-          return {
-            parentHash,
-            parentColumn: column, // Stay in same column
-            parentRow: totalRows, // Extend to bottom
-            connectionType: "straight" as ConnectionType,
-            isOffScreen: true,
-          };
-        }
-      });
+export function layoutGraphRows(rows: GraphRow[]): { nodes: GraphNode[]; rails: Rail[] } {
+  const rowByHash = new Map(rows.map((row) => [row.commit.hash, row]));
+  let width = 0;
+
+  const nodes: GraphNode[] = rows.map((row) => {
+    const parentConnections: ParentConnection[] = row.edges.map((edge) => {
+      const parent = rowByHash.get(edge.parent);
+      width = Math.max(width, edge.column + 1);
+      if (parent) {
+        return {
+          parentHash: edge.parent,
+          parentColumn: parent.column,
+          parentRow: parent.row,
+          connectionType: determineConnectionType(row.column, parent.column),
+          isOffScreen: false,
+        };
+      }
+      return {
+        parentHash: edge.parent,
+        parentColumn: edge.column,
+        parentRow: rows.length,
+        connectionType: determineConnectionType(row.column, edge.column),
+        isOffScreen: true,
+      };
+    });
+    width = Math.max(width, row.column + 1);
 
-    nodes.push({
-      commit,
-      column,
-      row,
+    return {
+      commit: row.commit,
+      column: row.column,
+      row: row.row,
       parentConnections,
-      railColor: RAIL_COLORS[column % RAIL_COLORS.length],
-    });
-  }
+      railColor: RAIL_COLORS[row.column % RAIL_COLORS.length],
+    };
+  });
 
-  // Create rails for the columns used
-  const maxColumn = Math.max(...Array.from(commitToColumn.values()), 0) + 1;
-  const rails: Rail[] = Array.from({ length: maxColumn }, (_, index) => ({
+  const rails: Rail[] = Array.from({ length: width }, (_, index) => ({
     id: index,
     color: RAIL_COLORS[index % RAIL_COLORS.length],
   }));
@@ -140,75 +96,6 @@ export function layoutGraph(commits: CommitInfo[]): { nodes: GraphNode[]; rails:
   return { nodes, rails };
 }
 
-/**
- * Find the appropriate column for a commit.
- */
-function findColumn(
-  commit: CommitInfo,
-  activeColumns: Map<number, string>,
-  _commitToColumn: Map<string, number>
-): number {
-  // Check if any active column is waiting for this commit
-  for (const [column, expectedHash] of activeColumns) {
-    if (expectedHash === commit.hash) {
-      return column;
-    }
-  }
-
-  // No column is waiting for us, find first available
-  let column = 0;
-  while (activeColumns.has(column)) {
-    column++;
-  }
-
-  return column;
-}
-
-/**
- * Update active columns after processing a commit.
- */
-function updateActiveColumns(
-  commit: CommitInfo,
-  column: number,
-  activeColumns: Map<number, string>,
-  commitHashSet: Set<string>
-): void {
-  // Remove this commit from active columns (it's been consumed)
-  for (const [col, hash] of activeColumns) {
-    if (hash === commit.hash) {
-      activeColumns.delete(col);
-    }
-  }
-
-  if (commit.parent_hashes.length === 0) {
-    // Root commit - nothing more to track
-    return;
-  }
-
-  // First parent continues in the same column (only if it's in our commit set)
-  const firstParent = commit.parent_hashes[0];
-  if (firstParent && commitHashSet.has(firstParent)) {
-    activeColumns.set(column, firstParent);
-  }
-
-  // Additional parents (merge commits) need their own columns
-  for (let i = 1; i < commit.parent_hashes.length; i++) {
-    const parentHash = commit.parent_hashes[i];
-    // Only reserve column for parents that exist in our loaded commits
-    if (!commitHashSet.has(parentHash)) {
-      continue;
-    }
-
-    // Find a free column for this merge parent
-    // Try to place it adjacent to the current column
-    let mergeColumn = column + 1;
-    while (activeColumns.has(mergeColumn)) {
-      mergeColumn++;
-    }
-    activeColumns.set(mergeColumn, parentHash);
-  }
-}
-
 /**
  * Determine the type of connection line to draw.
  */
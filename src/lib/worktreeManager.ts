@@ -23,6 +23,70 @@ export interface WorktreePreparationResult {
   created: boolean;
   /** Warning message if something unexpected happened but we recovered. */
   warning: string | null;
+  /** Outcome of the project's bootstrap spec, when a new worktree was created. */
+  bootstrap?: BootstrapReport | null;
+}
+
+/** Mirrors the Rust `CloneStats` struct. */
+export interface CloneStats {
+  size: { files: number; bytes: number };
+  reflinked: number;
+  hardlinked: number;
+  copied: number;
+}
+
+/** Mirrors the Rust `BootstrapStep` struct. */
+export interface BootstrapStep {
+  kind: "clone" | "copy" | "symlink" | "command";
+  target: string;
+  status: "succeeded" | "skipped" | "failed";
+  message: string | null;
+  exitCode: number | null;
+  durationMs: number;
+  clone: CloneStats | null;
+}
+
+/** Mirrors the Rust `BootstrapReport` struct. */
+export interface BootstrapReport {
+  worktreePath: string;
+  steps: BootstrapStep[];
+  success: boolean;
+}
+
+/** Mirrors the Rust `GcPolicy` struct; omitted fields use the backend defaults. */
+export interface GcPolicy {
+  maxIdleDays?: number | null;
+  removeMerged?: boolean;
+  maxTotalBytes?: number | null;
+  protectDirty?: boolean;
+}
+
+/** Mirrors the Rust `WorktreeUsage` struct. */
+export interface WorktreeUsage {
+  path: string;
+  branch: string | null;
+  head: string;
+  size: { files: number; bytes: number } | null;
+  lastActivityMs: number | null;
+  dirtyFiles: number;
+  /** Set if `git status` failed; such worktrees are never removed. */
+  statusError: string | null;
+  merged: boolean;
+  baseBranch: string | null;
+}
+
+/** Mirrors the Rust `GcReport` struct. */
+export interface GcReport {
+  dryRun: boolean;
+  entries: {
+    worktree: WorktreeUsage;
+    reasons: ("missing" | "merged" | "idle" | "overBudget")[];
+    protection: "activeSession" | "dirty" | "statusUnknown" | null;
+    remove: boolean;
+    error: string | null;
+  }[];
+  totalBytes: number;
+  reclaimedBytes: number;
 }
 
 /**
@@ -273,3 +337,26 @@ export async function cleanupSessionWorktree(
     return false;
   }
 }
+
+/**
+ * Removes the project's stale managed worktrees according to `policy`.
+ * Worktrees used by live sessions are always kept.
+ *
+ * @param projectPath - The path to the main repository
+ * @param dryRun - Report what would be removed without deleting anything
+ * @param policy - Removal rules; backend defaults apply when omitted
+ * @param worktreeBasePath - Custom worktree base directory, if configured
+ */
+export async function collectWorktreeGarbage(
+  projectPath: string,
+  dryRun: boolean,
+  policy?: GcPolicy,
+  worktreeBasePath?: string | null
+): Promise<GcReport> {
+  return invoke<GcReport>("collect_worktree_garbage", {
+    projectPath,
+    dryRun,
+    policy: policy ?? null,
+    worktreeBasePath: worktreeBasePath ?? null,
+  });
+}
@@ -1,7 +1,19 @@
 import { invoke } from "@tauri-apps/api/core";
 import { create } from "zustand";
 
-/** GitHub authentication status. */
+/** Hosting service behind a repository remote. */
+export type ForgeKind = "github" | "gitlab" | "gitea";
+
+/** Repository remote resolved to the forge hosting it. */
+export interface ForgeRemote {
+  kind: ForgeKind;
+  remote: string;
+  host: string;
+  baseUrl: string;
+  path: string;
+}
+
+/** Forge authentication status. */
 export interface AuthStatus {
   logged_in: boolean;
   username: string | null;
@@ -129,7 +141,8 @@ export type IssueFilterState = "open" | "closed" | "all";
  * Handles PRs, issues, and discussions with filtering and actions.
  */
 interface GitHubState {
-  // Authentication
+  // Forge and authentication
+  forge: ForgeRemote | null;
   authStatus: AuthStatus | null;
   isCheckingAuth: boolean;
 
@@ -165,6 +178,7 @@ interface GitHubState {
 
   // Actions
   checkAuth: (repoPath: string) => Promise<void>;
+  setForgeToken: (repoPath: string, token: string) => Promise<void>;
   fetchPullRequests: (repoPath: string, state?: PrFilterState) => Promise<void>;
   fetchPullRequestDetail: (repoPath: string, number: number) => Promise<void>;
   createPullRequest: (
@@ -205,6 +219,7 @@ interface GitHubState {
 
 export const useGitHubStore = create<GitHubState>()((set, get) => ({
   // Initial state
+  forge: null,
   authStatus: null,
   isCheckingAuth: false,
   pullRequests: [],
@@ -229,10 +244,11 @@ export const useGitHubStore = create<GitHubState>()((set, get) => ({
   checkAuth: async (repoPath: string) => {
     set({ isCheckingAuth: true });
     try {
-      const authStatus = await invoke<AuthStatus>("github_auth_status", {
+      const forge = await invoke<ForgeRemote>("forge_detect", { repoPath });
+      const authStatus = await invoke<AuthStatus>("forge_auth_status", {
         repoPath,
       });
-      set({ authStatus, isCheckingAuth: false });
+      set({ forge, authStatus, isCheckingAuth: false });
     } catch (err) {
       console.error("Failed to check GitHub auth:", err);
       set({
@@ -242,11 +258,18 @@ export const useGitHubStore = create<GitHubState>()((set, get) => ({
     }
   },
 
+  setForgeToken: async (repoPath: string, token: string) => {
+    const forge = get().forge;
+    if (!forge) return;
+    await invoke("forge_set_token", { host: forge.host, token });
+    await get().checkAuth(repoPath);
+  },
+
   fetchPullRequests: async (repoPath: string, state?: PrFilterState) => {
     const filter = state ?? get().prFilter;
     set({ isPRsLoading: true, prsError: null, prFilter: filter });
     try {
-      const pullRequests = await invoke<PullRequestInfo[]>("github_list_prs", {
+      const pullRequests = await invoke<PullRequestInfo[]>("forge_list_prs", {
         repoPath,
         state: filter === "all" ? null : filter,
         limit: 50,
@@ -261,7 +284,7 @@ export const useGitHubStore = create<GitHubState>()((set, get) => ({
   fetchPullRequestDetail: async (repoPath: string, number: number) => {
     set({ isLoadingPRDetail: true });
     try {
-      const selectedPR = await invoke<PullRequestDetail>("github_get_pr", {
+      const selectedPR = await invoke<PullRequestDetail>("forge_get_pr", {
         repoPath,
         number,
       });
@@ -280,7 +303,7 @@ export const useGitHubStore = create<GitHubState>()((set, get) => ({
     head: string,
     draft: boolean
   ) => {
-    const pr = await invoke<PullRequestInfo>("github_create_pr", {
+    const pr = await invoke<PullRequestInfo>("forge_create_pr", {
       repoPath,
       title,
       body,
@@ -299,7 +322,7 @@ export const useGitHubStore = create<GitHubState>()((set, get) => ({
     method: MergeMethod,
     deleteBranch: boolean
   ) => {
-    await invoke("github_merge_pr", {
+    await invoke("forge_merge_pr", {
       repoPath,
       number,
       method,
@@ -311,7 +334,7 @@ export const useGitHubStore = create<GitHubState>()((set, get) => ({
   },
 
   closePullRequest: async (repoPath: string, number: number) => {
-    await invoke("github_close_pr", { repoPath, number });
+    await invoke("forge_close_pr", { repoPath, number });
     // Refresh PR list after close
     await get().fetchPullRequests(repoPath);
     set({ selectedPR: null });
@@ -322,7 +345,7 @@ export const useGitHubStore = create<GitHubState>()((set, get) => ({
     number: number,
     body: string
   ) => {
-    await invoke("github_comment_pr", { repoPath, number, body });
+    await invoke("forge_comment_pr", { repoPath, number, body });
     // Refresh PR detail to show the new comment
     await get().fetchPullRequestDetail(repoPath, number);
   },
@@ -331,7 +354,7 @@ export const useGitHubStore = create<GitHubState>()((set, get) => ({
     const filter = state ?? get().issueFilter;
     set({ isIssuesLoading: true, issuesError: null, issueFilter: filter });
     try {
-      const issues = await invoke<IssueInfo[]>("github_list_issues", {
+      const issues = await invoke<IssueInfo[]>("forge_list_issues", {
         repoPath,
         state: filter === "all" ? null : filter,
         limit: 50,
@@ -344,6 +367,12 @@ export const useGitHubStore = create<GitHubState>()((set, get) => ({
   },
 
   fetchDiscussions: async (repoPath: string) => {
+    // Discussions only exist on GitHub
+    const forge = get().forge;
+    if (forge && forge.kind !== "github") {
+      set({ discussionsEnabled: false, discussions: [] });
+      return;
+    }
     set({ isDiscussionsLoading: true, discussionsError: null });
     try {
       const discussions = await invoke<DiscussionInfo[]>(
@@ -373,7 +402,7 @@ export const useGitHubStore = create<GitHubState>()((set, get) => ({
   fetchIssueDetail: async (repoPath: string, number: number) => {
     set({ isLoadingIssueDetail: true });
     try {
-      const selectedIssue = await invoke<IssueDetail>("github_get_issue", {
+      const selectedIssue = await invoke<IssueDetail>("forge_get_issue", {
         repoPath,
         number,
       });
@@ -385,21 +414,21 @@ export const useGitHubStore = create<GitHubState>()((set, get) => ({
   },
 
   closeIssue: async (repoPath: string, number: number) => {
-    await invoke("github_close_issue", { repoPath, number });
+    await invoke("forge_close_issue", { repoPath, number });
     // Refresh issue list and detail
     await get().fetchIssues(repoPath);
     await get().fetchIssueDetail(repoPath, number);
   },
 
   reopenIssue: async (repoPath: string, number: number) => {
-    await invoke("github_reopen_issue", { repoPath, number });
+    await invoke("forge_reopen_issue", { repoPath, number });
     // Refresh issue list and detail
     await get().fetchIssues(repoPath);
     await get().fetchIssueDetail(repoPath, number);
   },
 
   commentIssue: async (repoPath: string, number: number, body: string) => {
-    await invoke("github_comment_issue", { repoPath, number, body });
+    await invoke("forge_comment_issue", { repoPath, number, body });
     // Refresh issue detail to show the new comment
     await get().fetchIssueDetail(repoPath, number);
   },
@@ -446,6 +475,7 @@ export const useGitHubStore = create<GitHubState>()((set, get) => ({
 
   reset: () => {
     set({
+      forge: null,
       authStatus: null,
       isCheckingAuth: false,
       pullRequests: [],
@@ -19,6 +19,38 @@ export interface CommitInfo {
   summary: string;
 }
 
+/** Edge from a commit to one of its parents in the commit graph. */
+export interface GraphEdge {
+  parent: string;
+  /** Lane the edge runs down until it reaches the parent. */
+  column: number;
+  kind: "parent" | "merge";
+}
+
+/** A commit with its lane assigned by the backend. */
+export interface GraphRow {
+  commit: CommitInfo;
+  /** Row in the whole graph, counting earlier pages. */
+  row: number;
+  column: number;
+  edges: GraphEdge[];
+  /** Branches, remote branches and tags ("tag:v1") pointing at the commit. */
+  refs: string[];
+}
+
+/** Lane state after a page of the commit graph; pass back for the next page. */
+export interface GraphCursor {
+  skip: number;
+  lanes: (string | null)[];
+}
+
+/** One page of the commit graph. */
+export interface GraphPage {
+  rows: GraphRow[];
+  width: number;
+  next: GraphCursor | null;
+}
+
 /** File change status enum. */
 export type FileChangeStatus =
   | "added"
@@ -70,6 +102,8 @@ interface GitState {
 
   // Commit state
   commits: CommitInfo[];
+  graphRows: GraphRow[];
+  graphCursor: GraphCursor | null;
   hasMoreCommits: boolean;
 
   // Config state
@@ -112,7 +146,6 @@ interface GitState {
   fetchDefaultBranch: (repoPath: string) => Promise<void>;
   setDefaultBranch: (repoPath: string, branch: string, global?: boolean) => Promise<void>;
   getCommitFiles: (repoPath: string, commitHash: string) => Promise<FileChange[]>;
-  getRefsForCommit: (repoPath: string, commitHash: string) => Promise<string[]>;
   reset: () => void;
 }
 
@@ -124,6 +157,8 @@ export const useGitStore = create<GitState>()((set, get) => ({
   currentBranch: null,
   branches: [],
   commits: [],
+  graphRows: [],
+  graphCursor: null,
   hasMoreCommits: true,
   userConfig: null,
   remotes: [],
@@ -159,38 +194,45 @@ export const useGitStore = create<GitState>()((set, get) => ({
   fetchCommits: async (repoPath: string, maxCount = INITIAL_COMMIT_COUNT, allBranches = true) => {
     set({ isLoading: true, error: null });
     try {
-      const commits = await invoke<CommitInfo[]>("git_commit_log", {
+      // Lanes and ref decorations come precomputed from the backend
+      const page = await invoke<GraphPage>("git_commit_graph", {
         repoPath,
-        maxCount,
-        allBranches,
+        query: { limit: maxCount, branches: allBranches ? [] : ["HEAD"] },
       });
       set({
-        commits,
+        commits: page.rows.map((row) => row.commit),
+        graphRows: page.rows,
+        graphCursor: page.next,
         isLoading: false,
-        hasMoreCommits: commits.length >= maxCount,
+        hasMoreCommits: page.next !== null,
       });
     } catch (err) {
       console.error("Failed to fetch commits:", err);
-      set({ error: String(err), isLoading: false, commits: [] });
+      set({ error: String(err), isLoading: false, commits: [], graphRows: [], graphCursor: null });
     }
   },
 
   loadMoreCommits: async (repoPath: string, allBranches = true) => {
-    const { commits, hasMoreCommits, isLoadingMore } = get();
-    if (!hasMoreCommits || isLoadingMore) return;
+    const { graphRows, graphCursor, hasMoreCommits, isLoadingMore } = get();
+    if (!hasMoreCommits || isLoadingMore || !graphCursor) return;
 
     set({ isLoadingMore: true });
     try {
-      const newCount = commits.length + LOAD_MORE_COUNT;
-      const allCommits = await invoke<CommitInfo[]>("git_commit_log", {
+      const page = await invoke<GraphPage>("git_commit_graph", {
         repoPath,
-        maxCount: newCount,
-        allBranches,
+        query: {
+          limit: LOAD_MORE_COUNT,
+          branches: allBranches ? [] : ["HEAD"],
+          cursor: graphCursor,
+        },
       });
+      const rows = [...graphRows, ...page.rows];
       set({
-        commits: allCommits,
+        commits: rows.map((row) => row.commit),
+        graphRows: rows,
+        graphCursor: page.next,
         isLoadingMore: false,
-        hasMoreCommits: allCommits.length >= newCount,
+        hasMoreCommits: page.next !== null,
       });
     } catch (err) {
       console.error("Failed to load more commits:", err);
@@ -399,20 +441,13 @@ export const useGitStore = create<GitState>()((set, get) => ({
     }
   },
 
-  getRefsForCommit: async (repoPath: string, commitHash: string): Promise<string[]> => {
-    try {
-      return await invoke<string[]>("git_refs_for_commit", { repoPath, commitHash });
-    } catch (err) {
-      console.error("Failed to get refs for commit:", err);
-      return [];
-    }
-  },
-
   reset: () => {
     set({
       currentBranch: null,
       branches: [],
       commits: [],
+      graphRows: [],
+      graphCursor: null,
       hasMoreCommits: true,
       userConfig: null,
       remotes: [],
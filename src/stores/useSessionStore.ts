@@ -22,6 +22,25 @@ export type BackendSessionStatus =
 /** Timeout in milliseconds for sessions stuck in Starting state (Bug #74) */
 const SESSION_STARTUP_TIMEOUT_MS = 30000;
 
+/** Mirrors the Rust `IssueLink` struct. */
+export interface IssueLink {
+  number: number;
+  title: string;
+  url: string;
+}
+
+/** Mirrors the Rust `PullRequestLink` struct. */
+export interface PullRequestLink {
+  number: number;
+  url: string;
+}
+
+/** Mirrors the Rust `PortBlock` struct. */
+export interface PortBlock {
+  base: number;
+  count: number;
+}
+
 /**
  * Mirrors the Rust `SessionConfig` struct returned by `get_sessions`.
  *
@@ -29,6 +48,9 @@ const SESSION_STARTUP_TIMEOUT_MS = 30000;
  * @property branch - Git branch the session operates on, or null for the default branch.
  * @property worktree_path - Filesystem path to the git worktree, if one was created.
  * @property project_path - Canonicalized project directory this session belongs to.
+ * @property issue - GitHub issue the session was started from, if any.
+ * @property pull_request - Pull request opened from the session's branch, if any.
+ * @property ports - Ports reserved for the session's shell (exported as `PORT`, `MAESTRO_PORT_*`).
  * @property statusMessage - Brief description of what the agent is doing (from MCP status).
  * @property needsInputPrompt - When status is NeedsInput, the specific question for the user.
  */
@@ -39,6 +61,9 @@ export interface SessionConfig {
   status: BackendSessionStatus;
   worktree_path: string | null;
   project_path: string;
+  issue?: IssueLink | null;
+  pull_request?: PullRequestLink | null;
+  ports?: PortBlock | null;
   statusMessage?: string;
   needsInputPrompt?: string;
   /** Timestamp of the last MCP-driven status update (used by activity heuristic). */
@@ -54,12 +79,20 @@ interface SessionStatusPayload {
   needs_input_prompt?: string;
 }
 
+/** Shape of the Tauri `session-launched` event payload (Rust `LaunchedSession`). */
+interface SessionLaunchedPayload {
+  session: SessionConfig;
+  working_directory: string;
+  warning: string | null;
+}
+
 /**
  * Zustand store slice for session metadata (not PTY I/O -- that lives in terminal.ts).
  *
  * @property sessions - Authoritative list of sessions fetched from the backend.
  * @property fetchSessions - Performs a one-shot IPC fetch to replace the session list.
- * @property initListeners - Subscribes to the global `session-status-changed` Tauri event.
+ * @property initListeners - Subscribes to the global `session-status-changed` and
+ *   `session-launched` Tauri events.
  *   Returns an unlisten function; callers must invoke the cleanup to decrement
  *   a reference count and remove the listener when the last subscriber exits.
  */
@@ -266,7 +299,7 @@ export const useSessionStore = create<SessionState>()((set, get) => ({
     try {
       if (!activeUnlisten) {
         if (!pendingInit) {
-          pendingInit = listen<SessionStatusPayload>("session-status-changed", (event) => {
+          const statusListener = listen<SessionStatusPayload>("session-status-changed", (event) => {
             const { session_id, project_path, status, message, needs_input_prompt } = event.payload;
 
             // Check if session exists in store
@@ -300,9 +333,22 @@ export const useSessionStore = create<SessionState>()((set, get) => ({
                   : s
               ),
             }));
-          })
-            .then((unlisten) => {
-              activeUnlisten = unlisten;
+          });
+
+          // Sessions opened by the backend (issue sessions, task queue spawns)
+          const launchListener = listen<SessionLaunchedPayload>("session-launched", (event) => {
+            const { session } = event.payload;
+            const exists = get().sessions.some(
+              (s) => s.id === session.id && s.project_path === session.project_path
+            );
+            if (!exists) {
+              get().addSession(session);
+            }
+          });
+
+          pendingInit = Promise.all([statusListener, launchListener])
+            .then((unlisteners) => {
+              activeUnlisten = () => unlisteners.forEach((unlisten) => unlisten());
             })
             .finally(() => {
               pendingInit = null;
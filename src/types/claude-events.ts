@@ -17,4 +17,7 @@ export type ClaudeEvent =
   | { event_type: "SubagentSpawned"; session_id: number; agent_type: string; agent_id: string; description: string; timestamp: string }
   | { event_type: "SubagentCompleted"; session_id: number; agent_id: string; timestamp: string }
   | { event_type: "StatusUpdate"; session_id: number; state: string; message: string; needs_input_prompt: string | null; timestamp: string }
-  | { event_type: "TokenUsageUpdate"; session_id: number; input_tokens: number; output_tokens: number; cache_read_tokens: number; cache_creation_tokens: number; timestamp: string };
+  | { event_type: "TokenUsageUpdate"; session_id: number; input_tokens: number; output_tokens: number; cache_read_tokens: number; cache_creation_tokens: number; timestamp: string }
+  | { event_type: "MessageReceived"; session_id: number; message_id: string; topic: string; from_session: number | null; body: string; timestamp: string }
+  | { event_type: "PullRequestStatusChanged"; session_id: number; number: number; url: string; checks_state: "passing" | "failing" | "pending" | "none"; failing_checks: string[]; review_decision: string | null; timestamp: string }
+  | { event_type: "ConflictRiskDetected"; session_id: number; other_session_id: number | null; against: string; level: "conflict" | "overlap"; files: string[]; timestamp: string };